@@ -1,4 +1,4 @@
-extern crate the_ray_tracer_challenge as raytracer;
+extern crate rtc_core as raytracer;
 
 use raytracer::tuple::*;
 
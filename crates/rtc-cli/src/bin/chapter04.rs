@@ -1,8 +1,9 @@
-extern crate the_ray_tracer_challenge as raytracer;
+extern crate rtc_core as raytracer;
 
+use rtc_core::angle::Angle;
+use rtc_core::matrix::Matrix;
 use std::f64::consts::PI;
 use std::fs::write;
-use the_ray_tracer_challenge::matrix::Matrix;
 
 use raytracer::canvas::to_png::*;
 use raytracer::canvas::to_ppm::*;
@@ -61,7 +62,7 @@ fn main() {
 
   for hour in 0..12 {
     let r = 200.0;
-    let rotation_transform = Matrix::rotation_z(2.0 * PI / 12.0 * (hour as f64));
+    let rotation_transform = Matrix::rotation_z(Angle::radians(2.0 * PI / 12.0 * (hour as f64)));
     let point = Tuple::point(0.0, r, 0.0);
 
     let transformed_point = origin_transform * rotation_transform * point;
@@ -1,13 +1,13 @@
 use itertools::Itertools;
 use rayon::prelude::*;
+use rtc_core::body::*;
+use rtc_core::canvas::to_png::*;
+use rtc_core::canvas::*;
+use rtc_core::ray::*;
+use rtc_core::sphere::*;
+use rtc_core::tuple::*;
 use std::fs::write;
 use std::sync::Mutex;
-use the_ray_tracer_challenge::body::*;
-use the_ray_tracer_challenge::canvas::to_png::*;
-use the_ray_tracer_challenge::canvas::*;
-use the_ray_tracer_challenge::ray::*;
-use the_ray_tracer_challenge::sphere::*;
-use the_ray_tracer_challenge::tuple::*;
 
 use indicatif::ProgressBar;
 
@@ -47,7 +47,7 @@ fn main() {
 
       let xs = sphere.intersect(ray);
 
-      if xs.hit() != None {
+      if xs.hit().is_some() {
         let mut canvas = canvas_mutex.lock().unwrap();
         canvas.write_pixel(x, y, yellow);
       }
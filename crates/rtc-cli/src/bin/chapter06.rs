@@ -1,15 +1,15 @@
 use itertools::Itertools;
 use rayon::prelude::*;
+use rtc_core::body::*;
+use rtc_core::canvas::to_png::*;
+use rtc_core::canvas::*;
+use rtc_core::light::PointLight;
+use rtc_core::material::{Illuminated, Material, Phong};
+use rtc_core::ray::*;
+use rtc_core::sphere::*;
+use rtc_core::tuple::*;
 use std::fs::write;
 use std::sync::Mutex;
-use the_ray_tracer_challenge::body::*;
-use the_ray_tracer_challenge::canvas::to_png::*;
-use the_ray_tracer_challenge::canvas::*;
-use the_ray_tracer_challenge::light::PointLight;
-use the_ray_tracer_challenge::material::{Illuminated, Material, Phong};
-use the_ray_tracer_challenge::ray::*;
-use the_ray_tracer_challenge::sphere::*;
-use the_ray_tracer_challenge::tuple::*;
 
 use indicatif::ProgressBar;
 
@@ -56,7 +56,7 @@ fn main() {
         let computed = hit.get_computed();
         let color = hit.body.material().lighting(
           &hit.body,
-          light,
+          light.into(),
           computed.point,
           computed.eyev,
           computed.normalv,
@@ -1,31 +1,33 @@
 use itertools::Itertools;
 use rayon::prelude::*;
+use rtc_core::angle::Angle;
+use rtc_core::animator::*;
+use rtc_core::body::*;
+use rtc_core::camera::Camera;
+use rtc_core::canvas::to_png::*;
+use rtc_core::canvas::*;
+use rtc_core::light::PointLight;
+use rtc_core::material::{Material, Phong};
+use rtc_core::matrix::Matrix;
+use rtc_core::sphere::*;
+use rtc_core::tuple::*;
+use rtc_core::world::World;
 use std::f64::consts::PI;
 use std::fs::write;
 use std::sync::Mutex;
-use the_ray_tracer_challenge::animator::*;
-use the_ray_tracer_challenge::body::*;
-use the_ray_tracer_challenge::camera::Camera;
-use the_ray_tracer_challenge::canvas::to_png::*;
-use the_ray_tracer_challenge::canvas::*;
-use the_ray_tracer_challenge::light::PointLight;
-use the_ray_tracer_challenge::material::{Material, Phong};
-use the_ray_tracer_challenge::matrix::Matrix;
-use the_ray_tracer_challenge::sphere::*;
-use the_ray_tracer_challenge::tuple::*;
-use the_ray_tracer_challenge::world::World;
 
 use indicatif::ProgressBar;
 
 fn main() {
   let animator = Animator::new(25 * 5);
-  animator.animate(|frame| {
+  let failures = animator.animate(|frame| {
     let canvas_width = 1920;
     let canvas_height = 1080;
 
     let light_rotation_scale = frame.linear_scale().with_range(vec![0.0, PI * 2.0]);
-    let light_transformation_matrix =
-      Matrix::rotation_y(light_rotation_scale.scale(frame.current_as_float()));
+    let light_transformation_matrix = Matrix::rotation_y(Angle::radians(
+      light_rotation_scale.scale(frame.current_as_float()),
+    ));
 
     let light = PointLight::new(
       light_transformation_matrix * Tuple::point(-10.0, 10.0, -10.0),
@@ -38,23 +40,23 @@ fn main() {
       .with_specular(0.0);
 
     let floor_sphere = Sphere::new(
-      Material::from(floor_and_wall_material),
+      Material::from(floor_and_wall_material.clone()),
       Matrix::scaling(10.0, 0.01, 10.0),
     );
 
     let left_wall_sphere = Sphere::new(
-      Material::from(floor_and_wall_material),
+      Material::from(floor_and_wall_material.clone()),
       Matrix::translation(0.0, 0.0, 5.0)
-        * Matrix::rotation_y(-PI / 4.0)
-        * Matrix::rotation_x(PI / 2.0)
+        * Matrix::rotation_y(Angle::radians(-PI / 4.0))
+        * Matrix::rotation_x(Angle::radians(PI / 2.0))
         * Matrix::scaling(10.0, 0.01, 10.0),
     );
 
     let right_wall_sphere = Sphere::new(
       Material::from(floor_and_wall_material),
       Matrix::translation(0.0, 0.0, 5.0)
-        * Matrix::rotation_y(PI / 4.0)
-        * Matrix::rotation_x(PI / 2.0)
+        * Matrix::rotation_y(Angle::radians(PI / 4.0))
+        * Matrix::rotation_x(Angle::radians(PI / 2.0))
         * Matrix::scaling(10.0, 0.01, 10.0),
     );
 
@@ -102,14 +104,15 @@ fn main() {
         Body::from(middle_sphere),
         Body::from(right_sphere),
       ],
-      vec![light],
+      vec![light.into()],
     );
 
-    let camera = Camera::new(canvas_width, canvas_height, PI / 3.0).look_at_from_position(
-      Tuple::point(0.0, 3.5, -5.0),
-      Tuple::point(0.0, 1.0, 0.0),
-      Tuple::vector(0.0, 1.0, 0.0),
-    );
+    let camera = Camera::new(canvas_width, canvas_height, Angle::radians(PI / 3.0))
+      .look_at_from_position(
+        Tuple::point(0.0, 3.5, -5.0),
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::vector(0.0, 1.0, 0.0),
+      );
 
     let canvas_mutex = Mutex::new(Canvas::new(canvas_width, canvas_height));
 
@@ -140,6 +143,9 @@ fn main() {
     write(filename, png).expect("Could not write ouput.png to disk.");
   });
 
+  for failure in failures.iter() {
+    eprintln!("{}", failure);
+  }
   println!("Everything done.");
   println!("Use ffmpeg to convert the output images into an animation movie:");
   println!("ffmpeg -stream_loop 4 -r 25 -f image2 -s 1920x1080 -i output%06d.png -vcodec libx264 -crf 25 animation.mp4");
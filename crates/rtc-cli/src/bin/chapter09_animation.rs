@@ -1,26 +1,27 @@
 use itertools::Itertools;
 use rayon::prelude::*;
+use rtc_core::angle::Angle;
+use rtc_core::animator::Animator;
+use rtc_core::body::*;
+use rtc_core::camera::Camera;
+use rtc_core::canvas::to_png::*;
+use rtc_core::canvas::*;
+use rtc_core::light::PointLight;
+use rtc_core::material::{Material, Phong};
+use rtc_core::matrix::Matrix;
+use rtc_core::plane::Plane;
+use rtc_core::sphere::*;
+use rtc_core::tuple::*;
+use rtc_core::world::World;
 use std::f64::consts::PI;
 use std::fs::write;
 use std::sync::Mutex;
-use the_ray_tracer_challenge::animator::Animator;
-use the_ray_tracer_challenge::body::*;
-use the_ray_tracer_challenge::camera::Camera;
-use the_ray_tracer_challenge::canvas::to_png::*;
-use the_ray_tracer_challenge::canvas::*;
-use the_ray_tracer_challenge::light::PointLight;
-use the_ray_tracer_challenge::material::{Material, Phong};
-use the_ray_tracer_challenge::matrix::Matrix;
-use the_ray_tracer_challenge::plane::Plane;
-use the_ray_tracer_challenge::sphere::*;
-use the_ray_tracer_challenge::tuple::*;
-use the_ray_tracer_challenge::world::World;
 
 use indicatif::ProgressBar;
 
 fn main() {
   let animator = Animator::new(25 * 5);
-  animator.animate(|frame| {
+  let failures = animator.animate(|frame| {
     let canvas_width = 1080;
     let canvas_height = 1920;
 
@@ -66,14 +67,15 @@ fn main() {
         Body::from(middle_sphere),
         Body::from(right_sphere),
       ],
-      vec![light],
+      vec![light.into()],
     );
 
-    let camera = Camera::new(canvas_width, canvas_height, PI / 3.0).look_at_from_position(
-      Tuple::point(0.0, 2.3, -8.0),
-      Tuple::point(0.0, 1.0, 0.0),
-      Tuple::vector(0.0, 1.0, 0.0),
-    );
+    let camera = Camera::new(canvas_width, canvas_height, Angle::radians(PI / 3.0))
+      .look_at_from_position(
+        Tuple::point(0.0, 2.3, -8.0),
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::vector(0.0, 1.0, 0.0),
+      );
 
     let canvas_mutex = Mutex::new(Canvas::new(canvas_width, canvas_height));
 
@@ -104,6 +106,9 @@ fn main() {
     write(filename, png).expect("Could not write ouput.png to disk.");
   });
 
+  for failure in failures.iter() {
+    eprintln!("{}", failure);
+  }
   println!("Everything done.");
   println!("Use ffmpeg to convert the output images into an animation movie:");
   println!("ffmpeg -stream_loop 4 -r 25 -f image2 -s 1080x1920 -i output%06d.png -vcodec libx264 -crf 25 animation.mp4");
@@ -1,12 +1,13 @@
 use anyhow::{anyhow, Context, Result};
 use itertools::Itertools;
 use rayon::prelude::*;
+use rtc_core::canvas::to_png::*;
+use rtc_core::canvas::*;
+use rtc_core::post_processing::PostProcess;
+use rtc_scene::world_loader::yaml;
+use rtc_scene::world_loader::WorldLoader;
 use std::fs::{read_to_string, write};
 use std::sync::Mutex;
-use the_ray_tracer_challenge::canvas::to_png::*;
-use the_ray_tracer_challenge::canvas::*;
-use the_ray_tracer_challenge::world_loader::yaml;
-use the_ray_tracer_challenge::world_loader::WorldLoader;
 
 use indicatif::ProgressBar;
 
@@ -42,8 +43,8 @@ Usage: {} <world.yaml>
   println!(
     "Going to render {} camera perspectives for a world with {} lights and {} bodies.",
     cameras.len(),
-    world.lights.len(),
-    world.bodies.len()
+    world.lights().len(),
+    world.bodies().len()
   );
 
   for (name, camera) in cameras.iter() {
@@ -59,7 +60,13 @@ Usage: {} <world.yaml>
       .cartesian_product(0..camera.vsize) // y
       .par_bridge()
       .for_each(|(x, y)| {
-        let color = world.color_at(camera.ray_for_pixel(x, y));
+        let rays = camera.rays_for_pixel(x, y);
+        let color = camera.expose(
+          rays
+            .iter()
+            .fold(Color::black(), |sum, ray| sum + world.color_at(*ray))
+            * (1.0 / rays.len() as f64),
+        );
         let mut canvas = canvas_mutex.lock().unwrap();
         canvas.write_pixel(x, y, color);
         progress.inc(1);
@@ -69,9 +76,8 @@ Usage: {} <world.yaml>
 
     println!("Writing ./{}.png", name);
 
-    let canvas = canvas_mutex.lock().unwrap();
+    let canvas = camera.post_processing.apply(&canvas_mutex.lock().unwrap());
     let png = canvas.to_png();
-    drop(canvas);
     write(format!("./{}.png", name), png)
       .context(format!("Could not write {}.png to disk.", name))?;
   }
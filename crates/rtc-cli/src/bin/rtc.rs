@@ -0,0 +1,1260 @@
+use anyhow::{anyhow, Context, Result};
+use rtc_core::animator::contact_sheet::ContactSheet;
+use rtc_core::animator::{self, Frame};
+use rtc_core::bake::{Bake, BakedMesh};
+use rtc_core::camera::Camera;
+use rtc_core::canvas::from_png::png_text;
+use rtc_core::canvas::to_gif::AnimatedGif;
+use rtc_core::canvas::to_png::*;
+use rtc_core::canvas::Canvas;
+use rtc_core::explain::explain_pixel;
+use rtc_core::fingerprint::Fingerprint;
+use rtc_core::layer::DEFAULT_LAYER;
+use rtc_core::material::{LightingPass, Material, Phong};
+use rtc_core::material_study::{MaterialProperty, MaterialStudy, StudyAxis};
+use rtc_core::mesh::Mesh;
+use rtc_core::post_processing::watermark::{Corner, Watermark};
+use rtc_core::post_processing::PostProcess;
+use rtc_core::probe::Probe;
+use rtc_core::ray_diagram::{Projection, RayDiagram};
+use rtc_core::renderer;
+use rtc_core::renderer::cost;
+use rtc_core::renderer::progressive;
+use rtc_core::stats;
+use rtc_core::tessellation::Tessellation;
+use rtc_core::tuple::Tuple;
+use rtc_core::world::World;
+use rtc_core::F;
+use rtc_scene::batch::BatchManifest;
+use rtc_scene::lint::lint;
+use rtc_scene::thumbnail::preview_camera;
+use rtc_scene::to_rust::to_rust;
+use rtc_scene::world_loader::WorldLoader;
+use rtc_scene::world_loader::{schema, yaml};
+use std::collections::HashMap;
+use std::fs::{read_to_string, write};
+use std::path::Path;
+use std::time::Duration;
+
+use indicatif::ProgressBar;
+
+fn usage(program: &str) -> String {
+  format!(
+    r#"
+The Raytracer Challenge Rust Renderer
+(c) 2021 Jakob Westhoff
+
+Usage: {0} render [--strict] [--clay] [--pass <pass>] [--heatmap] [--stats]
+              [--layers <names>] [--time-budget <duration>] [--frames <n>]
+              [<watermark options>] <world.yaml>
+       {0} turntable [--seconds <s>] [--fps <n>] [--radius <r>] [--height <h>] [--gif]
+              [--contact-sheet <n>] [<watermark options>] <world.yaml>
+       {0} rays [--view front|top|side] <world.yaml> <x,y>...
+       {0} explain <world.yaml> <x,y>...
+       {0} probe [--at <x,y,z>] [--size <n>] <world.yaml>
+       {0} bake [--indirect <samples>] <world.yaml>
+       {0} study --body <name> --columns <axis> --rows <axis> [--cell-size <n>] <world.yaml>
+       {0} export [--segments <n>] <world.yaml>
+       {0} verify [--clay] [--pass <pass>] [--layers <names>] [--frames <n>]
+              <image.png> <world.yaml>
+       {0} code <world.yaml>
+       {0} lint <world.yaml>
+       {0} batch <manifest.yaml>
+       {0} schema
+
+Options:
+  --strict                   Treat warnings in the world file as errors
+  --clay                     Render every body in matte gray, to judge lighting
+                             and composition without the materials
+  --pass <pass>              Render only the ambient, diffuse or specular light,
+                             or each light on its own with 'lights'
+  --heatmap                  Also write the rays traced per pixel as heatmap,
+                             rendering without time budget or adaptive sampling
+  --stats                    Print the intersection tests of every kind of shape
+                             and how many of them hit
+  --layers <names>           Render only the comma separated layers, bodies
+                             without layers belong to 'default'
+  --time-budget <duration>   Refine the images until the time (e.g. 90s, 10m, 1h)
+                             is used up, split evenly between all cameras
+  --frames <n>               Frames rendered of the 'animate' sweeps of the world
+                             file (default 25)
+  --seconds <s>              Duration of one turn (default 4)
+  --fps <n>                  Frames per second of the turntable (default 25)
+  --radius <r>, --height <h> Orbit around the center of the world, defaults to
+                             the distance and height of each camera
+  --gif                      Also write the turntable frames as animated GIF
+  --contact-sheet <n>        Also write every nth frame into a single grid image
+  --view <view>              Plane the ray diagram is drawn in (default side)
+  --at <x,y,z>               Point the chrome and gray reference balls are
+                             placed at, seen from each camera (default 0,0,0)
+  --size <n>                 Height of the reference ball images (default 200)
+  --indirect <samples>       Also bake the light bouncing off other bodies,
+                             sampled with the given rays per vertex
+  --body <name>              Bodies whose material is studied, by their name
+  --columns <axis>           Material property changing along the columns, as
+                             <property>:<from>:<to>:<steps>, e.g. shininess:10:300:4
+  --rows <axis>              Material property changing along the rows
+  --cell-size <n>            Longer side of each cell of the study (default 160)
+  --segments <n>             Triangles around curved bodies exported as OBJ
+                             (default 32)
+
+Watermark options:
+  --watermark <text>         Stamp the text onto every image, replacing {{scene}},
+                             {{camera}} and {{frame}} with the name of the world
+                             file, the camera and the frame
+  --watermark-image <png>    Stamp the image onto every image, in the corner
+                             opposite to the text if both are given
+  --watermark-corner <c>     top-left, top-right, bottom-left or bottom-right
+                             (default bottom-right)
+    "#,
+    program
+  )
+}
+
+#[derive(Default)]
+struct RenderOptions {
+  strict: bool,
+  clay: bool,
+  pass: Option<Pass>,
+  heatmap: bool,
+  stats: bool,
+  layers: Option<Vec<String>>,
+  time_budget: Option<Duration>,
+  frames: Option<usize>,
+  watermark: WatermarkOptions,
+}
+
+/// Part of the light rendered on its own for diagnosis
+#[derive(Copy, Clone)]
+enum Pass {
+  Lighting(LightingPass),
+  /// One image per light source
+  Lights,
+}
+
+impl Pass {
+  fn parse(pass: &str) -> Option<Self> {
+    match pass {
+      "ambient" => Some(Pass::Lighting(LightingPass::Ambient)),
+      "diffuse" => Some(Pass::Lighting(LightingPass::Diffuse)),
+      "specular" => Some(Pass::Lighting(LightingPass::Specular)),
+      "lights" => Some(Pass::Lights),
+      _ => None,
+    }
+  }
+
+  /// Worlds to render for this pass, with the suffix of their image names
+  fn worlds(pass: Option<Pass>, world: World) -> Vec<(String, World)> {
+    match pass {
+      None => vec![(String::new(), world)],
+      Some(Pass::Lighting(lighting_pass)) => {
+        let suffix = format!("_{:?}", lighting_pass).to_lowercase();
+        vec![(suffix, world.with_lighting_pass(lighting_pass))]
+      }
+      Some(Pass::Lights) => world
+        .lights()
+        .iter()
+        .enumerate()
+        .map(|(index, light)| {
+          let single_light = world.clone().with_lights(vec![*light]);
+          (format!("_light{}", index + 1), single_light)
+        })
+        .collect(),
+    }
+  }
+}
+
+#[derive(Default)]
+struct WatermarkOptions {
+  text: Option<String>,
+  image: Option<String>,
+  corner: Corner,
+}
+
+impl WatermarkOptions {
+  /// Parses a watermark option at the start of the arguments, returning the
+  /// arguments after it
+  fn parse<'a, 'b>(&mut self, arguments: &'b [&'a str]) -> Result<Option<&'b [&'a str]>> {
+    match arguments {
+      ["--watermark", text, rest @ ..] => {
+        self.text = Some(text.to_string());
+        Ok(Some(rest))
+      }
+      ["--watermark-image", file, rest @ ..] => {
+        self.image = Some(file.to_string());
+        Ok(Some(rest))
+      }
+      ["--watermark-corner", corner, rest @ ..] => {
+        self.corner = match *corner {
+          "top-left" => Corner::TopLeft,
+          "top-right" => Corner::TopRight,
+          "bottom-left" => Corner::BottomLeft,
+          "bottom-right" => Corner::BottomRight,
+          _ => return Err(anyhow!("Invalid watermark corner '{}'.", corner)),
+        };
+        Ok(Some(rest))
+      }
+      _ => Ok(None),
+    }
+  }
+
+  fn load_image(&self) -> Result<Option<Canvas>> {
+    self
+      .image
+      .as_ref()
+      .map(|file| {
+        let data = std::fs::read(file).context(format!("Could not read watermark {}", file))?;
+        Canvas::from_png(&data).context(format!("Could not decode watermark {}", file))
+      })
+      .transpose()
+  }
+
+  /// Watermarks of one image, with the placeholders of the text replaced
+  fn watermarks(
+    &self,
+    image: &Option<Canvas>,
+    source_file: &str,
+    camera: &str,
+    frame: Option<usize>,
+  ) -> Vec<Watermark> {
+    let mut watermarks = vec![];
+    if let Some(ref text) = self.text {
+      let scene = Path::new(source_file)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+      let text = text
+        .replace("{scene}", &scene)
+        .replace("{camera}", camera)
+        .replace("{frame}", &frame.map(|f| f.to_string()).unwrap_or_default());
+      watermarks.push(Watermark::text(text).with_corner(self.corner));
+    }
+    if let Some(ref image) = image {
+      let corner = match (self.text.is_some(), self.corner) {
+        (false, corner) => corner,
+        (true, Corner::TopLeft) => Corner::TopRight,
+        (true, Corner::TopRight) => Corner::TopLeft,
+        (true, Corner::BottomLeft) => Corner::BottomRight,
+        (true, Corner::BottomRight) => Corner::BottomLeft,
+      };
+      watermarks.push(Watermark::image(image.clone()).with_corner(corner));
+    }
+    watermarks
+  }
+}
+
+/// Applies the post processing of the camera followed by the watermarks
+fn finish_image(canvas: &Canvas, camera: &Camera, watermarks: &[Watermark]) -> Canvas {
+  watermarks
+    .iter()
+    .fold(camera.post_processing.apply(canvas), |canvas, watermark| {
+      watermark.apply(&canvas)
+    })
+}
+
+/// Parses the options of the render command followed by the world file
+fn parse_render_arguments<'a>(arguments: &[&'a str]) -> Result<(RenderOptions, &'a str)> {
+  let mut options = RenderOptions::default();
+  let mut remaining = arguments;
+
+  loop {
+    if let Some(rest) = options.watermark.parse(remaining)? {
+      remaining = rest;
+      continue;
+    }
+    match remaining {
+      ["--strict", rest @ ..] => {
+        options.strict = true;
+        remaining = rest;
+      }
+      ["--clay", rest @ ..] => {
+        options.clay = true;
+        remaining = rest;
+      }
+      ["--heatmap", rest @ ..] => {
+        options.heatmap = true;
+        remaining = rest;
+      }
+      ["--stats", rest @ ..] => {
+        options.stats = true;
+        remaining = rest;
+      }
+      ["--layers", layers, rest @ ..] => {
+        options.layers = Some(
+          layers
+            .split(',')
+            .map(|layer| layer.trim().to_string())
+            .collect(),
+        );
+        remaining = rest;
+      }
+      ["--pass", pass, rest @ ..] => {
+        options.pass = Some(Pass::parse(pass).ok_or_else(|| anyhow!("Invalid pass '{}'.", pass))?);
+        remaining = rest;
+      }
+      ["--time-budget", duration, rest @ ..] => {
+        options.time_budget = Some(
+          progressive::parse_duration(duration)
+            .ok_or_else(|| anyhow!("Invalid time budget '{}'.", duration))?,
+        );
+        remaining = rest;
+      }
+      ["--frames", frames, rest @ ..] => {
+        options.frames = Some(
+          frames
+            .parse()
+            .ok()
+            .filter(|frames| *frames > 0)
+            .ok_or_else(|| anyhow!("Invalid number of frames '{}'.", frames))?,
+        );
+        remaining = rest;
+      }
+      [source_file] => return Ok((options, source_file)),
+      _ => return Err(anyhow!("Invalid render arguments: {:?}.", arguments)),
+    }
+  }
+}
+
+/// Loads the world file and prints its warnings
+fn load_world(source_file: &str, strict: bool) -> Result<(World, HashMap<String, Camera>)> {
+  let base_directory = Path::new(source_file)
+    .parent()
+    .unwrap_or_else(|| Path::new("."));
+  let yaml_loader = yaml::Loader::default()
+    .with_strict_mode(strict)
+    .with_base_directory(base_directory.to_path_buf());
+  let source =
+    read_to_string(source_file).context(format!("Could not read world file {}", source_file))?;
+  let (world, cameras, warnings) =
+    yaml_loader
+      .load_world_with_warnings(source)
+      .context(format!(
+        "Tried parsing {} as YAML world definition, but failed",
+        source_file
+      ))?;
+
+  for warning in warnings.iter() {
+    eprintln!("Warning: {}", warning);
+  }
+  if world.lights().is_empty() {
+    eprintln!(
+      "Warning: World file {} defines no lights, every body is shown in its unlit color.",
+      source_file
+    );
+  }
+
+  Ok((world, cameras))
+}
+
+/// Loads the world file like `load_world`, which needs to define a camera
+fn load(source_file: &str, strict: bool) -> Result<(World, HashMap<String, Camera>)> {
+  let (world, cameras) = load_world(source_file, strict)?;
+  if cameras.is_empty() {
+    return Err(anyhow!(
+      "World file {} defines no camera, add one with a '- camera:' item.",
+      source_file
+    ));
+  }
+
+  Ok((world, cameras))
+}
+
+/// Prints the intersection tests counted since the last reset
+fn print_shape_counts() {
+  println!("{:<16} {:>12} {:>8}", "Shape", "Tests", "Hits");
+  for (kind, counts) in stats::shape_counts() {
+    println!(
+      "{:<16} {:>12} {:>7.1}%",
+      kind.name(),
+      counts.tests(),
+      100.0 * counts.hits as F / counts.tests() as F
+    );
+  }
+}
+
+/// Keeps only the given layers of the world
+fn only_layers(world: &World, layers: &[String]) -> Result<World> {
+  let known = world.layer_names();
+  for layer in layers.iter() {
+    if layer != DEFAULT_LAYER && !known.contains(&layer.as_str()) {
+      return Err(anyhow!(
+        "Unknown layer '{}', the world has the layers: {}.",
+        layer,
+        known.join(", ")
+      ));
+    }
+  }
+  let layers: Vec<&str> = layers.iter().map(String::as_str).collect();
+  let world = world.only_layers(&layers);
+  if world.lights().is_empty() {
+    return Err(anyhow!(
+      "No light left in the layers {}.",
+      layers.join(", ")
+    ));
+  }
+  Ok(world)
+}
+
+/// Every world at each frame of its sweeps, with the frame appended to the
+/// suffix of the image names. Worlds without sweeps are a single image.
+fn frames(
+  worlds: Vec<(String, World)>,
+  frames: Option<usize>,
+) -> Vec<(String, Option<usize>, World)> {
+  worlds
+    .into_iter()
+    .flat_map(|(suffix, world)| {
+      if world.sweeps.is_empty() {
+        return vec![(suffix, None, world)];
+      }
+      let count = frames.unwrap_or(25);
+      (0..count)
+        .map(|index| {
+          let t = index as F / (count - 1).max(1) as F;
+          // Numbered like the frames of turntables
+          (
+            format!("{}{:06}", suffix, index),
+            Some(index),
+            world.swept(t),
+          )
+        })
+        .collect()
+    })
+    .collect()
+}
+
+/// Loads the world file with the layers and materials chosen by the options
+fn load_for_render(
+  source_file: &str,
+  options: &RenderOptions,
+) -> Result<(World, HashMap<String, Camera>)> {
+  let (mut world, cameras) = load(source_file, options.strict)?;
+  if let Some(ref layers) = options.layers {
+    world = only_layers(&world, layers)?;
+  }
+  if options.clay {
+    world = world.with_material_override(Material::from(Phong::clay()));
+  }
+
+  Ok((world, cameras))
+}
+
+fn render(source_file: &str, options: &RenderOptions) -> Result<()> {
+  let (world, cameras) = load_for_render(source_file, options)?;
+
+  println!(
+    "Going to render {} camera perspectives for a world with {} lights, {} bodies and {} instances.",
+    cameras.len(),
+    world.lights().len(),
+    world.bodies().len(),
+    world.instances().len()
+  );
+
+  let watermark_image = options.watermark.load_image()?;
+  let worlds = frames(Pass::worlds(options.pass, world), options.frames);
+  let image_count = cameras.len() * worlds.len();
+  for ((camera_name, camera), (suffix, frame, world)) in cameras
+    .iter()
+    .flat_map(|camera| worlds.iter().map(move |world| (camera, world)))
+  {
+    let name = format!("{}{}", camera_name, suffix);
+    let pixel_count = camera.hsize * camera.vsize;
+
+    println!("Raytracing {} with {} pixels...", name, pixel_count);
+    stats::enable(options.stats);
+    stats::reset();
+    let fingerprint = world.fingerprint(camera);
+    let world = world.for_camera(camera);
+    if camera.frustum_culling {
+      println!(
+        "Skipping {} bodies and instances outside of the view.",
+        world.culled_count()
+      );
+    }
+    let world = world.as_ref();
+
+    let canvas = match (
+      options.heatmap,
+      options.time_budget,
+      camera.adaptive_sampling,
+    ) {
+      (true, _, _) => {
+        let progress = ProgressBar::new(pixel_count as u64);
+        progress.set_draw_rate(5);
+        let (canvas, costs) = cost::render(world, camera, |pixels| progress.inc(pixels as u64));
+        progress.finish();
+        println!(
+          "Writing ./{}_cost.png of up to {} rays per pixel",
+          name,
+          costs.max()
+        );
+        write(format!("./{}_cost.png", name), costs.to_heatmap().to_png())
+          .context(format!("Could not write {}_cost.png to disk.", name))?;
+        canvas
+      }
+      (false, Some(time_budget), _) => {
+        let camera_budget = time_budget / image_count as u32;
+        let progress = ProgressBar::new(camera_budget.as_millis() as u64);
+        progress.set_draw_rate(5);
+        let (canvas, report) =
+          progressive::render(world, camera, camera_budget, |passes, elapsed| {
+            progress.set_message(format!("{} passes", passes));
+            progress.set_position(elapsed.as_millis() as u64);
+          });
+        progress.finish();
+        println!(
+          "Rendered {} passes with {} samples per pixel in {:.1}s.",
+          report.passes,
+          report.samples_per_pixel,
+          report.duration.as_secs_f64()
+        );
+        canvas
+      }
+      (false, None, Some(ref adaptive_sampling)) => {
+        // The pilot pass visits every pixel once more
+        let progress = ProgressBar::new(2 * pixel_count as u64);
+        progress.set_draw_rate(5);
+        let (canvas, report) =
+          adaptive_sampling.render(world, camera, |pixels| progress.inc(pixels as u64));
+        progress.finish();
+        println!(
+          "Adaptive sampling used {:.1} samples per pixel on average (pilot pass {:.1}s, total {:.1}s).",
+          report.average_samples_per_pixel(),
+          report.pilot_duration.as_secs_f64(),
+          report.total_duration.as_secs_f64()
+        );
+        canvas
+      }
+      (false, None, None) => {
+        let progress = ProgressBar::new(pixel_count as u64);
+        progress.set_draw_rate(5);
+        let canvas = renderer::render(world, camera, |pixels| progress.inc(pixels as u64));
+        progress.finish();
+        canvas
+      }
+    };
+
+    if options.stats {
+      print_shape_counts();
+    }
+
+    println!("Writing ./{}.png with fingerprint {}", name, fingerprint);
+
+    let watermarks = options
+      .watermark
+      .watermarks(&watermark_image, source_file, &name, *frame);
+    let canvas = finish_image(&canvas, camera, &watermarks);
+    let png = canvas.to_png_with_text(&[
+      ("Fingerprint", &fingerprint.to_string()),
+      ("Scene", source_file),
+      ("Camera", camera_name),
+    ]);
+    write(format!("./{}.png", name), png)
+      .context(format!("Could not write {}.png to disk.", name))?;
+    write(
+      format!("./{}_fingerprint.txt", name),
+      fingerprint.report(source_file, camera_name, *frame),
+    )
+    .context(format!("Could not write {}_fingerprint.txt to disk.", name))?;
+  }
+
+  println!("Everything done.");
+
+  Ok(())
+}
+
+/// Parses the render options of the verify command followed by the image and
+/// the world file
+fn parse_verify_arguments<'a>(arguments: &[&'a str]) -> Result<(RenderOptions, &'a str, &'a str)> {
+  match arguments {
+    [options @ .., image_file, source_file] => {
+      let render_arguments = [options, &[*source_file]].concat();
+      let (options, source_file) = parse_render_arguments(&render_arguments)?;
+      Ok((options, image_file, source_file))
+    }
+    _ => Err(anyhow!("Invalid verify arguments: {:?}.", arguments)),
+  }
+}
+
+/// Checks whether the image was rendered from the world file with the given
+/// render options, by comparing the fingerprint stored in the image.
+///
+/// Only the fingerprint in the text chunk of the PNG file is compared, the
+/// pixels are neither hashed nor rendered again. An image edited after
+/// rendering still verifies, as long as its metadata is kept.
+fn verify(image_file: &str, source_file: &str, options: &RenderOptions) -> Result<()> {
+  let data = std::fs::read(image_file).context(format!("Could not read image {}", image_file))?;
+  let fingerprint = png_text(&data)
+    .into_iter()
+    .find(|(key, _)| key == "Fingerprint")
+    .and_then(|(_, value)| value.parse::<Fingerprint>().ok())
+    .ok_or_else(|| {
+      anyhow!(
+        "Image {} carries no fingerprint, it was not rendered by this renderer or its metadata was stripped.",
+        image_file
+      )
+    })?;
+
+  let (world, cameras) = load_for_render(source_file, options)?;
+  let worlds = frames(Pass::worlds(options.pass, world), options.frames);
+  let found = cameras
+    .iter()
+    .flat_map(|camera| worlds.iter().map(move |world| (camera, world)))
+    .find(|((_, camera), (_, _, world))| world.fingerprint(camera) == fingerprint);
+
+  match found {
+    Some(((camera_name, _), (suffix, _, _))) => {
+      println!(
+        "{} was rendered from {} as {}{} (fingerprint {}).",
+        image_file, source_file, camera_name, suffix, fingerprint
+      );
+      Ok(())
+    }
+    None => Err(anyhow!(
+      "{} was not rendered from {} with these options, no image of it has the fingerprint {}.",
+      image_file,
+      source_file,
+      fingerprint
+    )),
+  }
+}
+
+struct TurntableOptions {
+  seconds: F,
+  fps: usize,
+  radius: Option<F>,
+  height: Option<F>,
+  gif: bool,
+  contact_sheet: Option<usize>,
+  watermark: WatermarkOptions,
+}
+
+impl Default for TurntableOptions {
+  fn default() -> Self {
+    Self {
+      seconds: 4.0,
+      fps: 25,
+      radius: None,
+      height: None,
+      gif: false,
+      contact_sheet: None,
+      watermark: WatermarkOptions::default(),
+    }
+  }
+}
+
+/// Parses the options of the turntable command followed by the world file
+fn parse_turntable_arguments<'a>(arguments: &[&'a str]) -> Result<(TurntableOptions, &'a str)> {
+  let mut options = TurntableOptions::default();
+  let mut remaining = arguments;
+  let number = |name: &str, value: &str| -> Result<F> {
+    value
+      .parse::<F>()
+      .map_err(|_| anyhow!("Invalid {} '{}'.", name, value))
+  };
+
+  loop {
+    if let Some(rest) = options.watermark.parse(remaining)? {
+      remaining = rest;
+      continue;
+    }
+    match remaining {
+      ["--seconds", value, rest @ ..] => {
+        options.seconds = number("seconds", value)?;
+        remaining = rest;
+      }
+      ["--fps", value, rest @ ..] => {
+        options.fps = value
+          .parse()
+          .map_err(|_| anyhow!("Invalid fps '{}'.", value))?;
+        remaining = rest;
+      }
+      ["--radius", value, rest @ ..] => {
+        options.radius = Some(number("radius", value)?);
+        remaining = rest;
+      }
+      ["--height", value, rest @ ..] => {
+        options.height = Some(number("height", value)?);
+        remaining = rest;
+      }
+      ["--gif", rest @ ..] => {
+        options.gif = true;
+        remaining = rest;
+      }
+      ["--contact-sheet", value, rest @ ..] => {
+        options.contact_sheet = Some(
+          value
+            .parse()
+            .map_err(|_| anyhow!("Invalid contact sheet interval '{}'.", value))?,
+        );
+        remaining = rest;
+      }
+      [source_file] => return Ok((options, source_file)),
+      _ => return Err(anyhow!("Invalid turntable arguments: {:?}.", arguments)),
+    }
+  }
+}
+
+fn turntable(source_file: &str, options: &TurntableOptions) -> Result<()> {
+  let (world, cameras) = load(source_file, false)?;
+  let watermark_image = options.watermark.load_image()?;
+  let center = world
+    .bounds()
+    .map(|bounds| bounds.center())
+    .unwrap_or_else(|| Tuple::point(0.0, 0.0, 0.0));
+
+  for (name, camera) in cameras.iter() {
+    // Unless given, the orbit passes through the position of the camera
+    let offset = camera.position() - center;
+    let radius = options
+      .radius
+      .unwrap_or_else(|| (offset.x.powi(2) + offset.z.powi(2)).sqrt());
+    let height = options.height.unwrap_or(offset.y);
+
+    let transforms = animator::turntable(center, radius, height, options.seconds, options.fps);
+    println!(
+      "Rendering {} frames of {} orbiting ({:.2}, {:.2}, {:.2}) at radius {:.2}...",
+      transforms.len(),
+      name,
+      center.x,
+      center.y,
+      center.z,
+      radius
+    );
+
+    let mut animation = AnimatedGif::new(camera.hsize, camera.vsize, options.fps);
+    let mut contact_sheet = options.contact_sheet.map(ContactSheet::new);
+    let progress = ProgressBar::new(transforms.len() as u64);
+    for (index, transform) in transforms.iter().enumerate() {
+      let frame_camera = camera.clone().with_transform(*transform);
+      let canvas = renderer::render(&world.for_camera(&frame_camera), &frame_camera, |_| {});
+      let watermarks =
+        options
+          .watermark
+          .watermarks(&watermark_image, source_file, name, Some(index));
+      let canvas = finish_image(&canvas, &frame_camera, &watermarks);
+      let filename = Frame::new(transforms.len(), index).filename(".", name, ".png");
+      write(&filename, canvas.to_png())
+        .context(format!("Could not write {} to disk.", filename))?;
+      if options.gif {
+        animation.add_frame(&canvas);
+      }
+      if let Some(ref mut contact_sheet) = contact_sheet {
+        contact_sheet.add_frame(index, &canvas);
+      }
+      progress.inc(1);
+    }
+    progress.finish();
+
+    if options.gif {
+      println!("Writing ./{}.gif", name);
+      write(format!("./{}.gif", name), animation.to_gif())
+        .context(format!("Could not write {}.gif to disk.", name))?;
+    }
+    if let Some(contact_sheet) = contact_sheet {
+      println!("Writing ./{}_sheet.png", name);
+      write(
+        format!("./{}_sheet.png", name),
+        contact_sheet.to_canvas().to_png(),
+      )
+      .context(format!("Could not write {}_sheet.png to disk.", name))?;
+    }
+  }
+
+  println!("Everything done.");
+
+  Ok(())
+}
+
+struct RaysOptions {
+  projection: Projection,
+  pixels: Vec<(usize, usize)>,
+}
+
+/// Parses the options of the rays command followed by the world file and pixels
+fn parse_rays_arguments<'a>(arguments: &[&'a str]) -> Result<(RaysOptions, &'a str)> {
+  let (projection, remaining) = match arguments {
+    ["--view", "front", rest @ ..] => (Projection::Front, rest),
+    ["--view", "top", rest @ ..] => (Projection::Top, rest),
+    ["--view", "side", rest @ ..] => (Projection::Side, rest),
+    ["--view", view, ..] => return Err(anyhow!("Invalid view '{}'.", view)),
+    _ => (Projection::Side, arguments),
+  };
+
+  match remaining {
+    [source_file, pixels @ ..] if !pixels.is_empty() => {
+      let pixels = parse_pixels(pixels)?;
+      Ok((RaysOptions { projection, pixels }, source_file))
+    }
+    _ => Err(anyhow!("Invalid rays arguments: {:?}.", arguments)),
+  }
+}
+
+/// Parses pixel coordinates given as x,y
+fn parse_pixels(pixels: &[&str]) -> Result<Vec<(usize, usize)>> {
+  pixels
+    .iter()
+    .map(|pixel| {
+      let mut coordinates = pixel.split(',').map(|value| value.trim().parse::<usize>());
+      match (coordinates.next(), coordinates.next(), coordinates.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Ok((x, y)),
+        _ => Err(anyhow!("Invalid pixel '{}', expected x,y.", pixel)),
+      }
+    })
+    .collect()
+}
+
+fn rays(source_file: &str, options: &RaysOptions) -> Result<()> {
+  let (world, cameras) = load(source_file, false)?;
+
+  for (name, camera) in cameras.iter() {
+    let diagram = RayDiagram::record(&world, camera, &options.pixels);
+    for (extension, content) in [
+      ("svg", diagram.to_svg(options.projection)),
+      ("obj", diagram.to_obj()),
+    ]
+    .iter()
+    {
+      let filename = format!("./{}_rays.{}", name, extension);
+      write(&filename, content).context(format!("Could not write {} to disk.", filename))?;
+      println!(
+        "Wrote {} ray segments of {} to {}.",
+        diagram.segments.len(),
+        name,
+        filename
+      );
+    }
+  }
+
+  Ok(())
+}
+
+fn explain(source_file: &str, pixels: &[(usize, usize)]) -> Result<()> {
+  let (world, cameras) = load(source_file, false)?;
+
+  for (name, camera) in cameras.iter() {
+    println!("Camera {}:", name);
+    for (x, y) in pixels.iter() {
+      println!("{}", explain_pixel(&world, camera, *x, *y));
+    }
+  }
+
+  Ok(())
+}
+
+struct ProbeOptions {
+  at: Tuple,
+  size: usize,
+}
+
+/// Parses the options of the probe command followed by the world file
+fn parse_probe_arguments<'a>(arguments: &[&'a str]) -> Result<(ProbeOptions, &'a str)> {
+  let mut options = ProbeOptions {
+    at: Tuple::point(0.0, 0.0, 0.0),
+    size: 200,
+  };
+  let mut remaining = arguments;
+  loop {
+    match remaining {
+      ["--at", at, rest @ ..] => {
+        let coordinates = at
+          .split(',')
+          .map(|value| value.trim().parse::<F>())
+          .collect::<Result<Vec<F>, _>>();
+        options.at = match coordinates.as_deref() {
+          Ok([x, y, z]) => Tuple::point(*x, *y, *z),
+          _ => return Err(anyhow!("Invalid point '{}', expected x,y,z.", at)),
+        };
+        remaining = rest;
+      }
+      ["--size", size, rest @ ..] => {
+        options.size = size
+          .parse()
+          .ok()
+          .filter(|size| *size > 0)
+          .ok_or_else(|| anyhow!("Invalid size '{}'.", size))?;
+        remaining = rest;
+      }
+      [source_file] => return Ok((options, source_file)),
+      _ => return Err(anyhow!("Invalid probe arguments: {:?}.", arguments)),
+    }
+  }
+}
+
+fn probe(source_file: &str, options: &ProbeOptions) -> Result<()> {
+  let (world, cameras) = load(source_file, false)?;
+
+  for (name, camera) in cameras.iter() {
+    let canvas = Probe::new(options.at, camera.position()).render(&world, options.size);
+    let filename = format!("./{}_probe.png", name);
+    write(&filename, canvas.to_png()).context(format!("Could not write {} to disk.", filename))?;
+    println!("Wrote reference balls seen from {} to {}.", name, filename);
+  }
+
+  Ok(())
+}
+
+struct StudyOptions {
+  body: Option<String>,
+  columns: Option<StudyAxis>,
+  rows: Option<StudyAxis>,
+  cell_size: usize,
+}
+
+/// Parses an axis of a material study given as property:from:to:steps
+fn parse_study_axis(axis: &str) -> Result<StudyAxis> {
+  let invalid = || {
+    anyhow!(
+      "Invalid axis '{}', expected <property>:<from>:<to>:<steps>.",
+      axis
+    )
+  };
+  match axis.split(':').collect::<Vec<_>>().as_slice() {
+    [property, from, to, steps] => {
+      let property = MaterialProperty::from_name(property).ok_or_else(|| {
+        let names: Vec<&str> = MaterialProperty::ALL
+          .iter()
+          .map(|property| property.name())
+          .collect();
+        anyhow!(
+          "Unknown material property '{}', expected one of {}.",
+          property,
+          names.join(", ")
+        )
+      })?;
+      let from = from.parse().map_err(|_| invalid())?;
+      let to = to.parse().map_err(|_| invalid())?;
+      let steps = steps
+        .parse()
+        .ok()
+        .filter(|steps| *steps > 0)
+        .ok_or_else(invalid)?;
+      Ok(StudyAxis::new(property, from, to, steps))
+    }
+    _ => Err(invalid()),
+  }
+}
+
+/// Parses the options of the study command followed by the world file
+fn parse_study_arguments<'a>(arguments: &[&'a str]) -> Result<(StudyOptions, &'a str)> {
+  let mut options = StudyOptions {
+    body: None,
+    columns: None,
+    rows: None,
+    cell_size: 160,
+  };
+  let mut remaining = arguments;
+  loop {
+    match remaining {
+      ["--body", body, rest @ ..] => {
+        options.body = Some(body.to_string());
+        remaining = rest;
+      }
+      ["--columns", axis, rest @ ..] => {
+        options.columns = Some(parse_study_axis(axis)?);
+        remaining = rest;
+      }
+      ["--rows", axis, rest @ ..] => {
+        options.rows = Some(parse_study_axis(axis)?);
+        remaining = rest;
+      }
+      ["--cell-size", size, rest @ ..] => {
+        options.cell_size = size
+          .parse()
+          .ok()
+          .filter(|size| *size > 0)
+          .ok_or_else(|| anyhow!("Invalid cell size '{}'.", size))?;
+        remaining = rest;
+      }
+      [source_file] => return Ok((options, source_file)),
+      _ => return Err(anyhow!("Invalid study arguments: {:?}.", arguments)),
+    }
+  }
+}
+
+/// Renders the named bodies with two material properties changing across a
+/// grid, from each camera
+fn study(source_file: &str, options: &StudyOptions) -> Result<()> {
+  let (body, columns, rows) = match (&options.body, options.columns, options.rows) {
+    (Some(body), Some(columns), Some(rows)) => (body, columns, rows),
+    _ => {
+      return Err(anyhow!(
+        "A material study needs --body, --columns and --rows."
+      ))
+    }
+  };
+  let (world, cameras) = load(source_file, false)?;
+  if world.bodies_named(body).is_empty() {
+    return Err(anyhow!(
+      "World file {} contains no body named '{}'.",
+      source_file,
+      body
+    ));
+  }
+
+  let study = MaterialStudy::new(columns, rows);
+  for (name, camera) in cameras.iter() {
+    println!(
+      "Rendering {} cells of the study from {}.",
+      columns.steps * rows.steps,
+      name
+    );
+    let canvas = study.render(&world, body, &preview_camera(camera, options.cell_size));
+    let filename = format!("./{}_study.png", name);
+    write(&filename, canvas.to_png()).context(format!("Could not write {} to disk.", filename))?;
+    println!("Wrote material study seen from {} to {}.", name, filename);
+  }
+
+  Ok(())
+}
+
+/// Parses the options of the bake command followed by the world file
+fn parse_bake_arguments<'a>(arguments: &[&'a str]) -> Result<(Bake, &'a str)> {
+  match arguments {
+    ["--indirect", samples, source_file] => {
+      let samples = samples
+        .parse()
+        .map_err(|_| anyhow!("Invalid number of samples '{}'.", samples))?;
+      Ok((Bake::new().with_indirect_samples(samples), source_file))
+    }
+    [source_file] => Ok((Bake::new(), source_file)),
+    _ => Err(anyhow!("Invalid bake arguments: {:?}.", arguments)),
+  }
+}
+
+fn bake(source_file: &str, bake: &Bake) -> Result<()> {
+  let (world, _) = load_world(source_file, false)?;
+  if world.instances().is_empty() {
+    return Err(anyhow!(
+      "World file {} contains no meshes to bake.",
+      source_file
+    ));
+  }
+
+  let meshes = bake.instances(&world);
+  let stem = Path::new(source_file)
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let filename = format!("./{}_baked.obj", stem);
+  write(&filename, BakedMesh::to_obj(&meshes))
+    .context(format!("Could not write {} to disk.", filename))?;
+  println!("Wrote {} baked meshes to {}.", meshes.len(), filename);
+
+  Ok(())
+}
+
+fn parse_export_arguments<'a>(arguments: &[&'a str]) -> Result<(Tessellation, &'a str)> {
+  match arguments {
+    ["--segments", segments, source_file] => {
+      let segments = segments
+        .parse()
+        .map_err(|_| anyhow!("Invalid number of segments '{}'.", segments))?;
+      Ok((Tessellation::new().with_segments(segments), source_file))
+    }
+    [source_file] => Ok((Tessellation::new(), source_file)),
+    _ => Err(anyhow!("Invalid export arguments: {:?}.", arguments)),
+  }
+}
+
+/// Writes the bodies of the world as triangle meshes, to compare or continue
+/// with the scene in other tools
+fn export(source_file: &str, tessellation: &Tessellation) -> Result<()> {
+  let (world, _) = load_world(source_file, false)?;
+
+  let (meshes, skipped) = tessellation.world(&world);
+  let stem = Path::new(source_file)
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let filename = format!("./{}.obj", stem);
+  write(&filename, Mesh::to_obj(&meshes))
+    .context(format!("Could not write {} to disk.", filename))?;
+  println!("Wrote {} bodies to {}.", meshes.len(), filename);
+  if skipped > 0 {
+    println!(
+      "Skipped {} bodies without a finite surface, like planes, or of other signed distance shapes than tori.",
+      skipped
+    );
+  }
+
+  Ok(())
+}
+
+/// Writes Rust code building the world and its cameras, to continue with the
+/// scene in a binary of its own
+fn code(source_file: &str) -> Result<()> {
+  let (world, cameras) = load(source_file, false)?;
+
+  let stem = Path::new(source_file)
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let filename = format!("./{}.rs", stem);
+  write(&filename, to_rust(&world, &cameras))
+    .context(format!("Could not write {} to disk.", filename))?;
+  println!("Wrote Rust code of {} to {}.", source_file, filename);
+
+  Ok(())
+}
+
+/// Prints the performance problems of the world file with suggestions
+fn lint_world(source_file: &str) -> Result<()> {
+  let (world, cameras) = load_world(source_file, false)?;
+
+  let lints = lint(&world, &cameras);
+  if lints.is_empty() {
+    println!("No performance problems found in {}.", source_file);
+  }
+  for found in lints.iter() {
+    println!("- {}", found.problem);
+    println!("  {}", found.suggestion);
+  }
+
+  Ok(())
+}
+
+fn batch(manifest_file: &str) -> Result<()> {
+  let source = read_to_string(manifest_file)
+    .context(format!("Could not read batch manifest {}", manifest_file))?;
+  let base_directory = Path::new(manifest_file)
+    .parent()
+    .unwrap_or_else(|| Path::new("."));
+  let manifest = BatchManifest::from_yaml(&source, base_directory).context(format!(
+    "Tried parsing {} as batch manifest, but failed",
+    manifest_file
+  ))?;
+
+  println!(
+    "Going to render {} scenes with {} workers.",
+    manifest.jobs.len(),
+    manifest.workers
+  );
+
+  let results = manifest.run(|result| {
+    let camera = result.camera.as_deref().unwrap_or("-");
+    match &result.error {
+      None => println!(
+        "Rendered {} ({}) in {:.1}s{}.",
+        result.scene.display(),
+        camera,
+        result.duration.as_secs_f64(),
+        result
+          .fingerprint
+          .map(|fingerprint| format!(" with fingerprint {}", fingerprint))
+          .unwrap_or_default()
+      ),
+      Some(error) => eprintln!("Failed {} ({}): {}", result.scene.display(), camera, error),
+    }
+  })?;
+
+  println!();
+  println!("{:<40} {:<16} {:>8}  Result", "Scene", "Camera", "Time");
+  for result in results.iter() {
+    println!(
+      "{:<40} {:<16} {:>7.1}s  {}",
+      result.scene.display().to_string(),
+      result.camera.as_deref().unwrap_or("-"),
+      result.duration.as_secs_f64(),
+      match (&result.output, &result.error) {
+        (_, Some(error)) => format!("failed: {}", error),
+        (Some(output), None) => output.display().to_string(),
+        (None, None) => String::new(),
+      }
+    );
+  }
+
+  let failures = results.iter().filter(|result| !result.is_success()).count();
+  if failures > 0 {
+    return Err(anyhow!("{} of {} renders failed.", failures, results.len()));
+  }
+
+  println!("Everything done.");
+
+  Ok(())
+}
+
+fn main() -> Result<()> {
+  let args: Vec<String> = std::env::args().collect();
+
+  let arguments: Vec<&str> = args.iter().skip(1).map(String::as_str).collect();
+
+  match arguments.as_slice() {
+    ["render", render_arguments @ ..] => match parse_render_arguments(render_arguments) {
+      Ok((options, source_file)) => render(source_file, &options),
+      Err(error) => {
+        println!("{}", usage(&args[0]));
+        Err(error)
+      }
+    },
+    ["turntable", turntable_arguments @ ..] => {
+      match parse_turntable_arguments(turntable_arguments) {
+        Ok((options, source_file)) => turntable(source_file, &options),
+        Err(error) => {
+          println!("{}", usage(&args[0]));
+          Err(error)
+        }
+      }
+    }
+    ["rays", rays_arguments @ ..] => match parse_rays_arguments(rays_arguments) {
+      Ok((options, source_file)) => rays(source_file, &options),
+      Err(error) => {
+        println!("{}", usage(&args[0]));
+        Err(error)
+      }
+    },
+    ["explain", source_file, pixels @ ..] if !pixels.is_empty() => match parse_pixels(pixels) {
+      Ok(pixels) => explain(source_file, &pixels),
+      Err(error) => {
+        println!("{}", usage(&args[0]));
+        Err(error)
+      }
+    },
+    ["probe", probe_arguments @ ..] => match parse_probe_arguments(probe_arguments) {
+      Ok((options, source_file)) => probe(source_file, &options),
+      Err(error) => {
+        println!("{}", usage(&args[0]));
+        Err(error)
+      }
+    },
+    ["bake", bake_arguments @ ..] => match parse_bake_arguments(bake_arguments) {
+      Ok((options, source_file)) => bake(source_file, &options),
+      Err(error) => {
+        println!("{}", usage(&args[0]));
+        Err(error)
+      }
+    },
+    ["study", study_arguments @ ..] => match parse_study_arguments(study_arguments) {
+      Ok((options, source_file)) => study(source_file, &options),
+      Err(error) => {
+        println!("{}", usage(&args[0]));
+        Err(error)
+      }
+    },
+    ["export", export_arguments @ ..] => match parse_export_arguments(export_arguments) {
+      Ok((tessellation, source_file)) => export(source_file, &tessellation),
+      Err(error) => {
+        println!("{}", usage(&args[0]));
+        Err(error)
+      }
+    },
+    ["verify", verify_arguments @ ..] => match parse_verify_arguments(verify_arguments) {
+      Ok((options, image_file, source_file)) => verify(image_file, source_file, &options),
+      Err(error) => {
+        println!("{}", usage(&args[0]));
+        Err(error)
+      }
+    },
+    ["code", source_file] => code(source_file),
+    ["lint", source_file] => lint_world(source_file),
+    ["batch", manifest_file] => batch(manifest_file),
+    ["schema"] => {
+      print!("{}", schema::to_json_schema());
+      Ok(())
+    }
+    _ => {
+      println!("{}", usage(&args[0]));
+      Err(anyhow!("Invalid arguments: {:?}.", &args[1..]))
+    }
+  }
+}
@@ -0,0 +1,131 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::Float;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::F;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An angle, which knows about its unit.
+///
+/// Internally the angle is always stored in radians. Angles are only created
+/// through `Angle::radians` or `Angle::degrees`, so the unit of a number is
+/// always spelled out.
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+pub struct Angle {
+  radians: F,
+}
+
+impl Angle {
+  pub fn radians(radians: F) -> Self {
+    Self { radians }
+  }
+
+  pub fn degrees(degrees: F) -> Self {
+    Self {
+      radians: degrees.to_radians(),
+    }
+  }
+
+  pub fn as_radians(&self) -> F {
+    self.radians
+  }
+
+  pub fn as_degrees(&self) -> F {
+    self.radians.to_degrees()
+  }
+
+  pub fn sin(&self) -> F {
+    self.radians.sin()
+  }
+
+  pub fn cos(&self) -> F {
+    self.radians.cos()
+  }
+
+  pub fn tan(&self) -> F {
+    self.radians.tan()
+  }
+}
+
+impl From<Angle> for F {
+  fn from(angle: Angle) -> Self {
+    angle.as_radians()
+  }
+}
+
+impl FuzzyEq<Angle> for Angle {
+  fn fuzzy_eq(&self, other: Angle) -> bool {
+    self.radians.fuzzy_eq(other.radians)
+  }
+}
+
+impl Add for Angle {
+  type Output = Angle;
+
+  fn add(self, other: Angle) -> Self::Output {
+    Angle::radians(self.radians + other.radians)
+  }
+}
+
+impl Sub for Angle {
+  type Output = Angle;
+
+  fn sub(self, other: Angle) -> Self::Output {
+    Angle::radians(self.radians - other.radians)
+  }
+}
+
+impl Neg for Angle {
+  type Output = Angle;
+
+  fn neg(self) -> Self::Output {
+    Angle::radians(-self.radians)
+  }
+}
+
+impl Mul<F> for Angle {
+  type Output = Angle;
+
+  fn mul(self, other: F) -> Self::Output {
+    Angle::radians(self.radians * other)
+  }
+}
+
+impl Div<F> for Angle {
+  type Output = Angle;
+
+  fn div(self, other: F) -> Self::Output {
+    Angle::radians(self.radians / other)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::f64::consts::PI;
+
+  #[test]
+  fn degrees_are_converted_to_radians() {
+    assert_fuzzy_eq!(Angle::degrees(180.0).as_radians(), PI);
+    assert_fuzzy_eq!(Angle::degrees(90.0), Angle::radians(PI / 2.0));
+  }
+
+  #[test]
+  fn radians_are_converted_to_degrees() {
+    assert_fuzzy_eq!(Angle::radians(PI / 3.0).as_degrees(), 60.0);
+  }
+
+  #[test]
+  fn angles_can_be_combined() {
+    let angle = (Angle::degrees(30.0) + Angle::degrees(60.0)) * 2.0 - Angle::degrees(90.0);
+
+    assert_fuzzy_eq!(angle, Angle::degrees(90.0));
+    assert_fuzzy_eq!(-angle / 2.0, Angle::degrees(-45.0));
+  }
+
+  #[test]
+  fn trigonometric_functions_use_radians() {
+    assert_fuzzy_eq!(Angle::degrees(90.0).sin(), 1.0);
+    assert_fuzzy_eq!(Angle::degrees(180.0).cos(), -1.0);
+    assert_fuzzy_eq!(Angle::degrees(45.0).tan(), 1.0);
+  }
+}
@@ -0,0 +1,271 @@
+pub mod contact_sheet;
+
+use crate::angle::Angle;
+use crate::body::{Body, Intersectable};
+use crate::failure::catch_panic;
+use crate::matrix::Matrix;
+use crate::sweep::Kinematics;
+use crate::tuple::Tuple;
+use crate::F;
+use std::fmt;
+
+pub struct LinearScale {
+  domain: (F, F),
+  range: Vec<F>,
+}
+
+impl LinearScale {
+  pub fn new() -> Self {
+    Self {
+      domain: (0.0, 100.0),
+      range: vec![0.0, 1.0],
+    }
+  }
+
+  pub fn with_domain(mut self, start: F, end: F) -> Self {
+    self.domain = (start, end);
+    self
+  }
+
+  pub fn with_range(mut self, range: Vec<F>) -> Self {
+    self.range = range;
+    self
+  }
+
+  pub fn scale(&self, input: F) -> F {
+    let clamped_input = input.clamp(self.domain.0, self.domain.1);
+
+    let normalized_input = self.normalize(clamped_input);
+    let output = self.interpolate(normalized_input);
+
+    output.clamp(self.minimum_of_range(), self.maximum_of_range())
+  }
+
+  fn minimum_of_range(&self) -> F {
+    let mut minimum = F::INFINITY;
+    for value in self.range.iter() {
+      minimum = minimum.min(*value);
+    }
+
+    minimum
+  }
+
+  fn maximum_of_range(&self) -> F {
+    let mut maximum = -F::INFINITY;
+    for value in self.range.iter() {
+      maximum = maximum.max(*value);
+    }
+    maximum
+  }
+
+  fn normalize(&self, input: F) -> F {
+    let offsetted_input = input - self.domain.0;
+    offsetted_input / (self.domain.1 - self.domain.0)
+  }
+
+  fn interpolate(&self, normalized_input: F) -> F {
+    let slice_count = self.range.len() - 1;
+    let slice_index = (normalized_input * slice_count as F).floor() as usize;
+    let slice_normalized_input = normalized_input * slice_count as F - slice_index as F;
+
+    let offseted_output =
+      slice_normalized_input * (self.range[slice_index + 1] - self.range[slice_index]).abs();
+
+    if self.range[slice_index] > self.range[slice_index + 1] {
+      self.range[slice_index] - offseted_output
+    } else {
+      self.range[slice_index] + offseted_output
+    }
+  }
+}
+
+impl Default for LinearScale {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// A frame, which could not be rendered
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameFailure {
+  pub frame: usize,
+  pub message: String,
+}
+
+impl fmt::Display for FrameFailure {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "Frame {} failed: {}", self.frame, self.message)
+  }
+}
+
+pub struct Animator {
+  frame_count: usize,
+}
+
+pub struct Frame {
+  current: usize,
+  count: usize,
+}
+
+impl Frame {
+  pub fn new(count: usize, current: usize) -> Self {
+    Self { count, current }
+  }
+
+  pub fn filename(&self, path: &str, name: &str, suffix: &str) -> String {
+    format!("{}/{}{:06}{}", path, name, self.current, suffix)
+  }
+
+  pub fn linear_scale(&self) -> LinearScale {
+    LinearScale::new().with_domain(0.0, self.count as F)
+  }
+
+  pub fn current(&self) -> usize {
+    self.current
+  }
+
+  pub fn current_as_float(&self) -> F {
+    self.current as F
+  }
+}
+
+impl Animator {
+  pub fn new(frame_count: usize) -> Self {
+    Self { frame_count }
+  }
+
+  /// Calls `animate` for every frame.
+  ///
+  /// A panicking frame is skipped, so that the remaining frames are still
+  /// rendered. All failures are returned at the end for the caller to
+  /// report.
+  pub fn animate(&self, animate: fn(frame: Frame)) -> Vec<FrameFailure> {
+    (0..self.frame_count)
+      .filter_map(|current_frame| {
+        catch_panic(|| animate(Frame::new(self.frame_count, current_frame)))
+          .err()
+          .map(|error| FrameFailure {
+            frame: current_frame,
+            message: error,
+          })
+      })
+      .collect()
+  }
+}
+
+/// Camera transforms of a full orbit around the given center.
+///
+/// The camera starts in front of the center (towards negative z) at the given
+/// `radius` and `height` above it, and turns once around the y axis within
+/// `seconds`. Each frame always looks at the center.
+pub fn turntable(
+  world_center: Tuple,
+  radius: F,
+  height: F,
+  seconds: F,
+  fps: usize,
+) -> Vec<Matrix<4>> {
+  let frame_count = ((seconds * fps as F).round() as usize).max(1);
+  let up = Tuple::vector(0.0, 1.0, 0.0);
+
+  (0..frame_count)
+    .map(|frame| {
+      let angle = Angle::degrees(360.0 * frame as F / frame_count as F);
+      let from = world_center + Tuple::vector(radius * angle.sin(), height, -radius * angle.cos());
+      Matrix::view_transform(from, world_center, up)
+    })
+    .collect()
+}
+
+/// Transforms of a thrown body for every frame within `seconds`.
+///
+/// Unlike the projectile of the first chapters, which takes fixed steps,
+/// the flight is computed exactly at the time of each frame. The body
+/// bounces when its lowest point reaches the ground at y = 0.
+pub fn bouncing(body: &Body, kinematics: Kinematics, seconds: F, fps: usize) -> Vec<Matrix<4>> {
+  let frame_count = ((seconds * fps as F).round() as usize).max(1);
+  let transform = body.transform();
+  let bounds = body.bounds();
+  let height = if bounds.is_infinite() {
+    (transform * Tuple::point(0.0, 0.0, 0.0)).y
+  } else {
+    bounds.min.y
+  };
+
+  (0..frame_count)
+    .map(|frame| kinematics.transform(transform, height, frame as F / fps as F))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::sphere::Sphere;
+
+  #[test]
+  fn turntable_orbits_around_the_center() {
+    let center = Tuple::point(1.0, 0.0, 0.0);
+    let up = Tuple::vector(0.0, 1.0, 0.0);
+
+    let transforms = turntable(center, 5.0, 2.0, 2.0, 10);
+
+    assert_eq!(transforms.len(), 20);
+    assert_fuzzy_eq!(
+      transforms[0],
+      Matrix::view_transform(Tuple::point(1.0, 2.0, -5.0), center, up)
+    );
+    assert_fuzzy_eq!(
+      transforms[5],
+      Matrix::view_transform(Tuple::point(6.0, 2.0, 0.0), center, up)
+    );
+    assert_fuzzy_eq!(
+      transforms[10],
+      Matrix::view_transform(Tuple::point(1.0, 2.0, 5.0), center, up)
+    );
+  }
+
+  #[test]
+  fn bouncing_bodies_move_at_the_time_of_each_frame() {
+    let ball = Body::from(Sphere::default().with_transform(Matrix::translation(0.0, 3.0, 0.0)));
+    // Lands after one second and stops there
+    let kinematics = Kinematics::new(Tuple::vector(1.0, 0.0, 0.0))
+      .with_gravity(Tuple::vector(0.0, -4.0, 0.0))
+      .with_restitution(0.0);
+
+    let transforms = bouncing(&ball, kinematics, 2.0, 2);
+
+    assert_eq!(transforms.len(), 4);
+    assert_fuzzy_eq!(transforms[0], Matrix::translation(0.0, 3.0, 0.0));
+    assert_fuzzy_eq!(transforms[1], Matrix::translation(0.5, 2.5, 0.0));
+    assert_fuzzy_eq!(transforms[2], Matrix::translation(1.0, 1.0, 0.0));
+    assert_fuzzy_eq!(transforms[3], Matrix::translation(1.5, 1.0, 0.0));
+  }
+
+  #[test]
+  fn failing_frames_are_skipped_and_reported() {
+    let failures = Animator::new(4).animate(|frame| {
+      if frame.current() % 2 == 1 {
+        panic!("Broken frame");
+      }
+    });
+
+    assert_eq!(
+      failures,
+      vec![
+        FrameFailure {
+          frame: 1,
+          message: "Panicked: Broken frame".into()
+        },
+        FrameFailure {
+          frame: 3,
+          message: "Panicked: Broken frame".into()
+        },
+      ]
+    );
+    assert_eq!(
+      failures[0].to_string(),
+      "Frame 1 failed: Panicked: Broken frame"
+    );
+  }
+}
@@ -0,0 +1,156 @@
+use crate::canvas::{Canvas, Color};
+use crate::post_processing::watermark::{Corner, Watermark};
+use crate::post_processing::PostProcess;
+use std::vec::Vec;
+
+/// Grid of every nth frame of an animation, each labeled with its frame
+/// number, to review the timing at a glance.
+pub struct ContactSheet {
+  every: usize,
+  columns: usize,
+  thumbnail_width: usize,
+  spacing: usize,
+  background: Color,
+  thumbnails: Vec<Canvas>,
+}
+
+impl ContactSheet {
+  pub fn new(every: usize) -> Self {
+    Self {
+      every: every.max(1),
+      columns: 6,
+      thumbnail_width: 160,
+      spacing: 4,
+      background: Color::new(0.2, 0.2, 0.2),
+      thumbnails: vec![],
+    }
+  }
+
+  pub fn with_columns(mut self, columns: usize) -> Self {
+    self.columns = columns.max(1);
+    self
+  }
+
+  pub fn with_thumbnail_width(mut self, thumbnail_width: usize) -> Self {
+    self.thumbnail_width = thumbnail_width.max(1);
+    self
+  }
+
+  pub fn with_spacing(mut self, spacing: usize) -> Self {
+    self.spacing = spacing;
+    self
+  }
+
+  pub fn with_background(mut self, background: Color) -> Self {
+    self.background = background;
+    self
+  }
+
+  /// Whether the frame with the given number ends up on the sheet
+  pub fn includes(&self, frame: usize) -> bool {
+    frame.is_multiple_of(self.every)
+  }
+
+  /// Adds a thumbnail of the frame, if it is one of every nth frames
+  pub fn add_frame(&mut self, frame: usize, canvas: &Canvas) {
+    if !self.includes(frame) {
+      return;
+    }
+    let height = (canvas.height * self.thumbnail_width / canvas.width.max(1)).max(1);
+    let thumbnail = canvas.resized(self.thumbnail_width, height);
+    let thumbnail = Watermark::text(frame.to_string())
+      .with_corner(Corner::TopLeft)
+      .with_margin(2)
+      .apply(&thumbnail);
+    self.thumbnails.push(thumbnail);
+  }
+
+  pub fn len(&self) -> usize {
+    self.thumbnails.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.thumbnails.is_empty()
+  }
+
+  /// Lays out the thumbnails row by row, in the order they were added
+  pub fn to_canvas(&self) -> Canvas {
+    let columns = self.columns.min(self.thumbnails.len()).max(1);
+    let rows = self.thumbnails.len().div_ceil(columns);
+    let height = self
+      .thumbnails
+      .iter()
+      .map(|thumbnail| thumbnail.height)
+      .max()
+      .unwrap_or(0);
+
+    let mut sheet = Canvas::new(
+      columns * (self.thumbnail_width + self.spacing) + self.spacing,
+      rows * (height + self.spacing) + self.spacing,
+    );
+    sheet.fill(self.background);
+    for (index, thumbnail) in self.thumbnails.iter().enumerate() {
+      let left = self.spacing + (index % columns) * (self.thumbnail_width + self.spacing);
+      let top = self.spacing + (index / columns) * (height + self.spacing);
+      sheet.draw(thumbnail, left as isize, top as isize);
+    }
+
+    sheet
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn only_every_nth_frame_is_added() {
+    let mut sheet = ContactSheet::new(3);
+
+    for frame in 0..10 {
+      sheet.add_frame(frame, &Canvas::new(8, 4));
+    }
+
+    assert_eq!(sheet.len(), 4);
+    assert!(sheet.includes(6));
+    assert!(!sheet.includes(7));
+  }
+
+  #[test]
+  fn thumbnails_are_laid_out_in_rows() {
+    let mut frame = Canvas::new(40, 20);
+    frame.fill(Color::new(0.0, 0.0, 1.0));
+    let mut sheet = ContactSheet::new(1)
+      .with_columns(2)
+      .with_thumbnail_width(20)
+      .with_spacing(1);
+
+    for index in 0..3 {
+      sheet.add_frame(index, &frame);
+    }
+    let canvas = sheet.to_canvas();
+
+    assert_eq!(canvas.width, 2 * 21 + 1);
+    assert_eq!(canvas.height, 2 * 11 + 1);
+    // The bottom right corners of the thumbnails are clear of their labels
+    assert_fuzzy_eq!(canvas.pixel_at(20, 10), Color::new(0.0, 0.0, 1.0));
+    assert_fuzzy_eq!(canvas.pixel_at(20, 21), Color::new(0.0, 0.0, 1.0));
+    assert_fuzzy_eq!(canvas.pixel_at(41, 21), Color::new(0.2, 0.2, 0.2));
+    assert_fuzzy_eq!(canvas.pixel_at(0, 0), Color::new(0.2, 0.2, 0.2));
+  }
+
+  #[test]
+  fn thumbnails_carry_their_frame_number() {
+    let mut sheet = ContactSheet::new(1)
+      .with_thumbnail_width(20)
+      .with_spacing(0);
+
+    sheet.add_frame(1, &Canvas::new(20, 10));
+    let canvas = sheet.to_canvas();
+
+    // Top bar of the '1' starting at the margin
+    assert!(canvas.pixel_at(4, 3).red > 0.5);
+    assert_fuzzy_eq!(canvas.pixel_at(2, 3), Color::black());
+  }
+}
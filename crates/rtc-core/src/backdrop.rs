@@ -0,0 +1,192 @@
+use crate::angle::Angle;
+use crate::canvas::Color;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::{Material, Phong};
+use crate::matrix::Matrix;
+use crate::mesh::{Face, Mesh};
+use crate::pattern::{Gradient, Pattern};
+use crate::triangle::SmoothTriangle;
+use crate::tuple::Tuple;
+use crate::{EPSILON, F};
+
+/// Seamless studio backdrop, a floor curving up into a wall (cyclorama).
+///
+/// The floor lies in the xz plane in front of the origin, extending `depth`
+/// towards negative z. It bends up with the given `radius` into a wall facing
+/// negative z, which reaches up to `height`. The whole backdrop is `width`
+/// wide along the x axis.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Backdrop {
+  pub width: F,
+  pub depth: F,
+  pub height: F,
+  pub radius: F,
+  /// Number of strips approximating the curve
+  pub segments: usize,
+  pub floor_color: Color,
+  pub wall_color: Color,
+}
+
+impl Default for Backdrop {
+  fn default() -> Self {
+    Self {
+      width: 2.0,
+      depth: 2.0,
+      height: 2.0,
+      radius: 0.5,
+      segments: 16,
+      floor_color: Color::white(),
+      wall_color: Color::new(0.6, 0.6, 0.6),
+    }
+  }
+}
+
+impl Backdrop {
+  pub fn with_size(mut self, width: F, depth: F, height: F) -> Self {
+    self.width = width;
+    self.depth = depth;
+    self.height = height;
+    self
+  }
+
+  pub fn with_radius(mut self, radius: F) -> Self {
+    self.radius = radius;
+    self
+  }
+
+  pub fn with_segments(mut self, segments: usize) -> Self {
+    self.segments = segments;
+    self
+  }
+
+  pub fn with_colors(mut self, floor_color: Color, wall_color: Color) -> Self {
+    self.floor_color = floor_color;
+    self.wall_color = wall_color;
+    self
+  }
+
+  /// Soft matte gradient from the floor color up to the wall color
+  pub fn material(&self) -> Material {
+    // The gradient runs along x, so it is turned upwards and stretched over
+    // the height. The slight overshoot keeps the top edge from wrapping around.
+    let gradient = Gradient::default()
+      .with_colors(self.floor_color, self.wall_color)
+      .with_transform(
+        Matrix::rotation_z(Angle::degrees(90.0)) * Matrix::scaling(self.height + EPSILON, 1.0, 1.0),
+      );
+
+    Material::from(
+      Phong::default()
+        .with_pattern(Pattern::from(gradient))
+        .with_ambient(0.3)
+        .with_diffuse(0.7)
+        .with_specular(0.0),
+    )
+  }
+
+  /// Points along the side profile of the backdrop together with their normals
+  fn profile(&self) -> Vec<(Tuple, Tuple)> {
+    let radius = self.radius.max(0.0).min(self.height);
+    let segments = self.segments.max(1);
+    let up = Tuple::vector(0.0, 1.0, 0.0);
+    let front = Tuple::vector(0.0, 0.0, -1.0);
+
+    let mut profile = vec![(Tuple::point(0.0, 0.0, -self.depth), up)];
+    for segment in 0..=segments {
+      let angle = Angle::degrees(90.0 * segment as F / segments as F);
+      let point = Tuple::point(0.0, radius - radius * angle.cos(), radius * angle.sin());
+      profile.push((point, up * angle.cos() + front * angle.sin()));
+    }
+    profile.push((Tuple::point(0.0, self.height, radius), front));
+
+    profile
+  }
+
+  /// Mesh of the backdrop using its gradient material
+  pub fn mesh(&self) -> Mesh {
+    let offset = Tuple::vector(self.width / 2.0, 0.0, 0.0);
+    let faces = self
+      .profile()
+      .windows(2)
+      .filter(|pair| !pair[0].0.fuzzy_eq(pair[1].0))
+      .flat_map(|pair| {
+        let (p1, n1) = pair[0];
+        let (p2, n2) = pair[1];
+        vec![
+          Face::from(SmoothTriangle::new(
+            p1 - offset,
+            p2 - offset,
+            p2 + offset,
+            n1,
+            n2,
+            n2,
+          )),
+          Face::from(SmoothTriangle::new(
+            p1 - offset,
+            p2 + offset,
+            p1 + offset,
+            n1,
+            n2,
+            n1,
+          )),
+        ]
+      })
+      .collect();
+
+    Mesh::new(faces).with_material(self.material())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::body::Intersectable;
+  use crate::pattern::Stencil;
+  use crate::ray::Ray;
+  use crate::world::World;
+
+  #[test]
+  fn backdrop_is_built_from_two_triangles_per_strip() {
+    let backdrop = Backdrop::default().with_segments(4);
+
+    // floor, four curve strips and the wall
+    assert_eq!(backdrop.mesh().faces.len(), 12);
+  }
+
+  #[test]
+  fn rays_hit_the_floor_and_the_wall() {
+    let world = World::new(vec![Backdrop::default().mesh().body()], vec![]);
+
+    let down = Ray::new(Tuple::point(0.0, 1.0, -1.0), Tuple::vector(0.0, -1.0, 0.0));
+    let xs = world.intersect(down);
+    let floor = xs.hit().unwrap();
+    assert_fuzzy_eq!(floor.t, 1.0);
+    assert_fuzzy_eq!(
+      floor.body.normal_at(Tuple::point(0.0, 0.0, -1.0)),
+      Tuple::vector(0.0, 1.0, 0.0)
+    );
+
+    let forward = Ray::new(Tuple::point(0.0, 1.5, -1.0), Tuple::vector(0.0, 0.0, 1.0));
+    assert_fuzzy_eq!(world.intersect(forward).hit().unwrap().t, 1.5);
+  }
+
+  #[test]
+  fn gradient_blends_from_floor_to_wall_color() {
+    let backdrop = Backdrop::default().with_colors(Color::white(), Color::black());
+    let body = backdrop.mesh().body();
+    let phong = match backdrop.material() {
+      Material::Phong(phong) => phong,
+      _ => unreachable!(),
+    };
+    let pattern = phong.pattern.unwrap();
+
+    assert_fuzzy_eq!(
+      pattern.color_at(Tuple::point(0.0, 0.0, -1.0), &body),
+      Color::white()
+    );
+    assert_fuzzy_eq!(
+      pattern.color_at(Tuple::point(0.0, 1.0, 0.5), &body),
+      Color::new(0.5, 0.5, 0.5)
+    );
+  }
+}
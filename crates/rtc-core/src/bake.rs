@@ -0,0 +1,204 @@
+use crate::body::{Body, Intersectable};
+use crate::canvas::Color;
+use crate::instance::Instance;
+use crate::light::PointLight;
+use crate::material::Illuminated;
+use crate::ray::Ray;
+use crate::sampling::hemisphere_directions;
+use crate::tuple::Tuple;
+use crate::world::World;
+use crate::F;
+use std::fmt::Write;
+
+/// Bakes the light falling onto meshes into the colors of their vertices,
+/// e.g. to try out lighting in a game engine.
+///
+/// Only light independent of the viewer is baked: the ambient and diffuse
+/// light of the world, and with `indirect_samples` also the light bouncing
+/// off other bodies.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Bake {
+  pub indirect_samples: usize,
+}
+
+/// Triangles in world space with the color baked into each corner
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BakedMesh {
+  pub vertices: Vec<(Tuple, Color)>,
+  pub faces: Vec<[usize; 3]>,
+}
+
+impl Bake {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_indirect_samples(mut self, indirect_samples: usize) -> Self {
+    self.indirect_samples = indirect_samples;
+    self
+  }
+
+  /// Every instance of the world baked on its own
+  pub fn instances(&self, world: &World) -> Vec<BakedMesh> {
+    world
+      .instances()
+      .iter()
+      .map(|instance| self.instance(world, instance))
+      .collect()
+  }
+
+  /// The triangles of the instance, as placed into the world. Every corner
+  /// is lit from the side its normal points to.
+  pub fn instance(&self, world: &World, instance: &Instance) -> BakedMesh {
+    let mut baked = BakedMesh::default();
+    for body in instance.geometry.bodies().iter() {
+      let placed = instance.place(body.clone());
+      let corners = match placed {
+        Body::Triangle(ref triangle) => [triangle.p1, triangle.p2, triangle.p3],
+        Body::SmoothTriangle(ref smooth) => {
+          [smooth.triangle.p1, smooth.triangle.p2, smooth.triangle.p3]
+        }
+        _ => continue,
+      };
+      let first = baked.vertices.len();
+      for corner in corners.iter() {
+        let position = placed.transform() * *corner;
+        let color = self.light_at(world, &placed, position, placed.normal_at(position));
+        baked.vertices.push((position, color));
+      }
+      baked.faces.push([first, first + 1, first + 2]);
+    }
+    baked
+  }
+
+  fn light_at(&self, world: &World, body: &Body, position: Tuple, normalv: Tuple) -> Color {
+    // Keeps shadow rays from hitting the neighbouring triangles
+    let position = position + normalv * body.material().shadow_bias();
+    let direct = world.diffuse_light_at(body, position, normalv);
+    if self.indirect_samples == 0 {
+      return direct;
+    }
+
+    let directions = hemisphere_directions(normalv, self.indirect_samples);
+    let incoming = directions
+      .iter()
+      .map(|direction| world.color_at(Ray::new(position, *direction)))
+      .fold(Color::black(), |sum, color| sum + color)
+      * (1.0 / directions.len() as F);
+    // The directions are cosine weighted already, so the light bouncing off
+    // other bodies arrives like a light right above the surface
+    let bounce = PointLight::new(position + normalv, incoming);
+    let indirect = body
+      .material()
+      .lighting_terms(body, bounce.into(), position, normalv, normalv, false)
+      .diffuse;
+    direct + indirect
+  }
+}
+
+impl BakedMesh {
+  /// Wavefront OBJ with the colors appended to the vertices, as read by
+  /// most tools. Colors are clamped to [0, 1].
+  pub fn to_obj(meshes: &[BakedMesh]) -> String {
+    let mut obj = String::new();
+    let mut offset = 1;
+    for (index, mesh) in meshes.iter().enumerate() {
+      writeln!(obj, "o mesh_{}", index).unwrap();
+      for (position, color) in mesh.vertices.iter() {
+        let color = color.clamp(0.0, 1.0);
+        writeln!(
+          obj,
+          "v {} {} {} {:.4} {:.4} {:.4}",
+          position.x, position.y, position.z, color.red, color.green, color.blue
+        )
+        .unwrap();
+      }
+      for face in mesh.faces.iter() {
+        writeln!(
+          obj,
+          "f {} {} {}",
+          face[0] + offset,
+          face[1] + offset,
+          face[2] + offset
+        )
+        .unwrap();
+      }
+      offset += mesh.vertices.len();
+    }
+    obj
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::instance::Geometry;
+  use crate::material::{Material, Phong};
+  use crate::matrix::Matrix;
+  use crate::sphere::Sphere;
+  use crate::test_scene::white_light_at;
+  use crate::triangle::Triangle;
+  use std::sync::Arc;
+
+  fn floor() -> Instance {
+    // Its normal points up
+    let triangle = Triangle::new(
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::point(0.0, 0.0, 1.0),
+    );
+    Instance::new(Arc::new(Geometry::new(vec![triangle.into()])))
+      .with_transform(Matrix::scaling(2.0, 1.0, 2.0))
+  }
+
+  #[test]
+  fn corners_lit_from_above_get_ambient_and_diffuse_light() {
+    let world = World::new(vec![], vec![white_light_at(0.0, 100.0, 0.0)]);
+
+    let baked = Bake::new().instance(&world, &floor());
+
+    assert_eq!(baked.faces, vec![[0, 1, 2]]);
+    assert_fuzzy_eq!(baked.vertices[1].0, Tuple::point(2.0, 0.0, 0.0));
+    // Ambient 0.1 and almost all of the diffuse 0.9
+    assert!(baked.vertices[0].1.red > 0.99);
+  }
+
+  #[test]
+  fn shadows_and_bounced_light_are_baked() {
+    let blocker = Sphere::default()
+      .with_transform(Matrix::translation(0.0, 1.5, 0.0) * Matrix::scaling(0.5, 0.5, 0.5))
+      .with_material(Material::from(Phong::default().with_ambient(1.0)));
+    let world = World::new(vec![blocker.into()], vec![white_light_at(0.0, 100.0, 0.0)]);
+
+    let direct = Bake::new().instance(&world, &floor());
+    let indirect = Bake::new()
+      .with_indirect_samples(16)
+      .instance(&world, &floor());
+
+    // The corner below the ball only gets ambient light
+    assert_fuzzy_eq!(direct.vertices[0].1, Color::new(0.1, 0.1, 0.1));
+    assert!(direct.vertices[1].1.red > 0.9);
+    assert!(indirect.vertices[0].1.red > direct.vertices[0].1.red);
+  }
+
+  #[test]
+  fn meshes_are_written_as_obj_with_vertex_colors() {
+    let mesh = BakedMesh {
+      vertices: vec![
+        (Tuple::point(0.0, 0.0, 0.0), Color::new(2.0, 0.5, 0.0)),
+        (Tuple::point(1.0, 0.0, 0.0), Color::black()),
+        (Tuple::point(0.0, 1.0, 0.0), Color::black()),
+      ],
+      faces: vec![[0, 1, 2]],
+    };
+
+    let obj = BakedMesh::to_obj(&[mesh.clone(), mesh]);
+
+    let lines: Vec<&str> = obj.lines().collect();
+    assert_eq!(lines[0], "o mesh_0");
+    assert_eq!(lines[1], "v 0 0 0 1.0000 0.5000 0.0000");
+    assert_eq!(lines[4], "f 1 2 3");
+    assert_eq!(lines[9], "f 4 5 6");
+  }
+}
@@ -0,0 +1,501 @@
+use crate::bounds::Bounds;
+use crate::cone::Cone;
+use crate::cylinder::Cylinder;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::intersections::*;
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::mesh::MeshBody;
+use crate::mirrored::Mirrored;
+use crate::plane::Plane;
+use crate::quad::Quad;
+use crate::ray::*;
+use crate::repeated::Repeated;
+use crate::sdf::SdfBody;
+use crate::sphere::*;
+use crate::stats;
+use crate::triangle::{SmoothTriangle, Triangle};
+use crate::tuple::*;
+use crate::uv::{cylindrical_map, planar_map, spherical_map};
+use crate::F;
+
+/// Whether a body throws shadows onto others and is shadowed itself.
+///
+/// Switching these off helps with large floors or decorative glass.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Shadows {
+  pub cast: bool,
+  pub receive: bool,
+}
+
+impl Default for Shadows {
+  fn default() -> Self {
+    Self {
+      cast: true,
+      receive: true,
+    }
+  }
+}
+
+pub trait Intersectable {
+  fn material(&self) -> Material;
+  fn shadows(&self) -> Shadows;
+  fn transform(&self) -> Matrix<4>;
+  /// Inverse of the transform, which bodies cache, as every ray and shaded
+  /// point needs it
+  fn inverse_transform(&self) -> Matrix<4>;
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)>;
+  fn normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple;
+  fn bounds_in_object_space(&self) -> Bounds;
+
+  /// Most intersections a single ray can have with the body, to size the
+  /// collected intersections up front
+  fn max_intersections(&self) -> usize;
+
+  /// Normal of the actual surface, which only differs from the normal used
+  /// for shading if that one is interpolated, like on smooth triangles
+  fn geometric_normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    self.normal_at_in_object_space(object_space_point)
+  }
+
+  fn intersect(&self, ray: Ray) -> Intersections {
+    let object_space_ray = ray.transform(self.inverse_transform());
+    // Skip the exact intersection, if the ray misses the bounds anyway
+    if !self.bounds_in_object_space().intersects(object_space_ray) {
+      return Intersections::new(vec![]);
+    }
+    let ts = self.intersect_in_object_space(object_space_ray);
+    Intersections::new(
+      ts.into_iter()
+        .map(|(t, body)| Intersection::new(t, ray, body))
+        .collect(),
+    )
+  }
+
+  fn normal_at(&self, point: Tuple) -> Tuple {
+    let inverse = self.inverse_transform();
+    let object_space_point = inverse * point;
+    let object_normal = self.normal_at_in_object_space(object_space_point);
+    normal_to_world(inverse, object_normal)
+  }
+
+  /// Geometric and shading normal at the given point in world space
+  fn normals_at(&self, point: Tuple) -> (Tuple, Tuple) {
+    let inverse = self.inverse_transform();
+    let object_space_point = inverse * point;
+    (
+      normal_to_world(
+        inverse,
+        self.geometric_normal_at_in_object_space(object_space_point),
+      ),
+      normal_to_world(inverse, self.normal_at_in_object_space(object_space_point)),
+    )
+  }
+}
+
+/// Transforms a normal using the inverse of the transformation of its body
+fn normal_to_world(inverse: Matrix<4>, object_normal: Tuple) -> Tuple {
+  let mut world_normal = inverse.transpose() * object_normal;
+  // Hack, to ensure we have a clean vector, as due the inverse transpose the
+  // w component could be affected if the transformation matrix included a
+  // translation
+  world_normal.w = 0.0;
+  world_normal.normalize()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Body {
+  Sphere(Sphere),
+  Plane(Plane),
+  Quad(Quad),
+  Cylinder(Cylinder),
+  Cone(Cone),
+  Triangle(Triangle),
+  SmoothTriangle(SmoothTriangle),
+  Sdf(SdfBody),
+  Repeated(Repeated),
+  Mirrored(Mirrored),
+  Mesh(MeshBody),
+}
+
+impl From<Sphere> for Body {
+  fn from(sphere: Sphere) -> Self {
+    Body::Sphere(sphere)
+  }
+}
+
+impl From<Plane> for Body {
+  fn from(plane: Plane) -> Self {
+    Body::Plane(plane)
+  }
+}
+
+impl From<Quad> for Body {
+  fn from(quad: Quad) -> Self {
+    Body::Quad(quad)
+  }
+}
+
+impl From<Cylinder> for Body {
+  fn from(cylinder: Cylinder) -> Self {
+    Body::Cylinder(cylinder)
+  }
+}
+
+impl From<Cone> for Body {
+  fn from(cone: Cone) -> Self {
+    Body::Cone(cone)
+  }
+}
+
+impl From<Triangle> for Body {
+  fn from(triangle: Triangle) -> Self {
+    Body::Triangle(triangle)
+  }
+}
+
+impl From<SmoothTriangle> for Body {
+  fn from(smooth_triangle: SmoothTriangle) -> Self {
+    Body::SmoothTriangle(smooth_triangle)
+  }
+}
+
+impl From<SdfBody> for Body {
+  fn from(sdf_body: SdfBody) -> Self {
+    Body::Sdf(sdf_body)
+  }
+}
+
+impl From<Repeated> for Body {
+  fn from(repeated: Repeated) -> Self {
+    Body::Repeated(repeated)
+  }
+}
+
+impl From<Mirrored> for Body {
+  fn from(mirrored: Mirrored) -> Self {
+    Body::Mirrored(mirrored)
+  }
+}
+
+impl From<MeshBody> for Body {
+  fn from(mesh_body: MeshBody) -> Self {
+    Body::Mesh(mesh_body)
+  }
+}
+
+impl Intersectable for Body {
+  fn max_intersections(&self) -> usize {
+    match *self {
+      Body::Sphere(ref sphere) => sphere.max_intersections(),
+      Body::Plane(ref plane) => plane.max_intersections(),
+      Body::Quad(ref quad) => quad.max_intersections(),
+      Body::Cylinder(ref cylinder) => cylinder.max_intersections(),
+      Body::Cone(ref cone) => cone.max_intersections(),
+      Body::Triangle(ref triangle) => triangle.max_intersections(),
+      Body::SmoothTriangle(ref smooth_triangle) => smooth_triangle.max_intersections(),
+      Body::Sdf(ref sdf_body) => sdf_body.max_intersections(),
+      Body::Repeated(ref repeated) => repeated.max_intersections(),
+      Body::Mirrored(ref mirrored) => mirrored.max_intersections(),
+      Body::Mesh(ref mesh_body) => mesh_body.max_intersections(),
+    }
+  }
+
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    let xs = match *self {
+      Body::Sphere(ref sphere) => sphere.intersect_in_object_space(object_space_ray),
+      Body::Plane(ref plane) => plane.intersect_in_object_space(object_space_ray),
+      Body::Quad(ref quad) => quad.intersect_in_object_space(object_space_ray),
+      Body::Cylinder(ref cylinder) => cylinder.intersect_in_object_space(object_space_ray),
+      Body::Cone(ref cone) => cone.intersect_in_object_space(object_space_ray),
+      Body::Triangle(ref triangle) => triangle.intersect_in_object_space(object_space_ray),
+      Body::SmoothTriangle(ref smooth_triangle) => {
+        smooth_triangle.intersect_in_object_space(object_space_ray)
+      }
+      Body::Sdf(ref sdf_body) => sdf_body.intersect_in_object_space(object_space_ray),
+      Body::Repeated(ref repeated) => repeated.intersect_in_object_space(object_space_ray),
+      Body::Mirrored(ref mirrored) => mirrored.intersect_in_object_space(object_space_ray),
+      Body::Mesh(ref mesh_body) => mesh_body.intersect_in_object_space(object_space_ray),
+    };
+    stats::count_test(self, !xs.is_empty());
+    xs
+  }
+
+  fn normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    match *self {
+      Body::Sphere(ref sphere) => sphere.normal_at_in_object_space(object_space_point),
+      Body::Plane(ref plane) => plane.normal_at_in_object_space(object_space_point),
+      Body::Quad(ref quad) => quad.normal_at_in_object_space(object_space_point),
+      Body::Cylinder(ref cylinder) => cylinder.normal_at_in_object_space(object_space_point),
+      Body::Cone(ref cone) => cone.normal_at_in_object_space(object_space_point),
+      Body::Triangle(ref triangle) => triangle.normal_at_in_object_space(object_space_point),
+      Body::SmoothTriangle(ref smooth_triangle) => {
+        smooth_triangle.normal_at_in_object_space(object_space_point)
+      }
+      Body::Sdf(ref sdf_body) => sdf_body.normal_at_in_object_space(object_space_point),
+      Body::Repeated(ref repeated) => repeated.normal_at_in_object_space(object_space_point),
+      Body::Mirrored(ref mirrored) => mirrored.normal_at_in_object_space(object_space_point),
+      Body::Mesh(ref mesh_body) => mesh_body.normal_at_in_object_space(object_space_point),
+    }
+  }
+
+  fn geometric_normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    match *self {
+      Body::SmoothTriangle(ref smooth_triangle) => {
+        smooth_triangle.geometric_normal_at_in_object_space(object_space_point)
+      }
+      _ => self.normal_at_in_object_space(object_space_point),
+    }
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    match *self {
+      Body::Sphere(ref sphere) => sphere.bounds_in_object_space(),
+      Body::Plane(ref plane) => plane.bounds_in_object_space(),
+      Body::Quad(ref quad) => quad.bounds_in_object_space(),
+      Body::Cylinder(ref cylinder) => cylinder.bounds_in_object_space(),
+      Body::Cone(ref cone) => cone.bounds_in_object_space(),
+      Body::Triangle(ref triangle) => triangle.bounds_in_object_space(),
+      Body::SmoothTriangle(ref smooth_triangle) => smooth_triangle.bounds_in_object_space(),
+      Body::Sdf(ref sdf_body) => sdf_body.bounds_in_object_space(),
+      Body::Repeated(ref repeated) => repeated.bounds_in_object_space(),
+      Body::Mirrored(ref mirrored) => mirrored.bounds_in_object_space(),
+      Body::Mesh(ref mesh_body) => mesh_body.bounds_in_object_space(),
+    }
+  }
+
+  fn material(&self) -> Material {
+    match *self {
+      Body::Sphere(ref sphere) => sphere.material(),
+      Body::Plane(ref plane) => plane.material(),
+      Body::Quad(ref quad) => quad.material(),
+      Body::Cylinder(ref cylinder) => cylinder.material(),
+      Body::Cone(ref cone) => cone.material(),
+      Body::Triangle(ref triangle) => triangle.material(),
+      Body::SmoothTriangle(ref smooth_triangle) => smooth_triangle.material(),
+      Body::Sdf(ref sdf_body) => sdf_body.material(),
+      Body::Repeated(ref repeated) => repeated.material(),
+      Body::Mirrored(ref mirrored) => mirrored.material(),
+      Body::Mesh(ref mesh_body) => mesh_body.material(),
+    }
+  }
+
+  fn shadows(&self) -> Shadows {
+    match *self {
+      Body::Sphere(ref sphere) => sphere.shadows(),
+      Body::Plane(ref plane) => plane.shadows(),
+      Body::Quad(ref quad) => quad.shadows(),
+      Body::Cylinder(ref cylinder) => cylinder.shadows(),
+      Body::Cone(ref cone) => cone.shadows(),
+      Body::Triangle(ref triangle) => triangle.shadows(),
+      Body::SmoothTriangle(ref smooth_triangle) => smooth_triangle.shadows(),
+      Body::Sdf(ref sdf_body) => sdf_body.shadows(),
+      Body::Repeated(ref repeated) => repeated.shadows(),
+      Body::Mirrored(ref mirrored) => mirrored.shadows(),
+      Body::Mesh(ref mesh_body) => mesh_body.shadows(),
+    }
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    match *self {
+      Body::Sphere(ref sphere) => sphere.transform(),
+      Body::Plane(ref plane) => plane.transform(),
+      Body::Quad(ref quad) => quad.transform(),
+      Body::Cylinder(ref cylinder) => cylinder.transform(),
+      Body::Cone(ref cone) => cone.transform(),
+      Body::Triangle(ref triangle) => triangle.transform(),
+      Body::SmoothTriangle(ref smooth_triangle) => smooth_triangle.transform(),
+      Body::Sdf(ref sdf_body) => sdf_body.transform(),
+      Body::Repeated(ref repeated) => repeated.transform(),
+      Body::Mirrored(ref mirrored) => mirrored.transform(),
+      Body::Mesh(ref mesh_body) => mesh_body.transform(),
+    }
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    match *self {
+      Body::Sphere(ref sphere) => sphere.inverse_transform(),
+      Body::Plane(ref plane) => plane.inverse_transform(),
+      Body::Quad(ref quad) => quad.inverse_transform(),
+      Body::Cylinder(ref cylinder) => cylinder.inverse_transform(),
+      Body::Cone(ref cone) => cone.inverse_transform(),
+      Body::Triangle(ref triangle) => triangle.inverse_transform(),
+      Body::SmoothTriangle(ref smooth_triangle) => smooth_triangle.inverse_transform(),
+      Body::Sdf(ref sdf_body) => sdf_body.inverse_transform(),
+      Body::Repeated(ref repeated) => repeated.inverse_transform(),
+      Body::Mirrored(ref mirrored) => mirrored.inverse_transform(),
+      Body::Mesh(ref mesh_body) => mesh_body.inverse_transform(),
+    }
+  }
+}
+
+impl Body {
+  /// Bounds of the body in world space
+  pub fn bounds(&self) -> Bounds {
+    self.bounds_in_object_space().transform(self.transform())
+  }
+
+  /// Texture coordinates of a point in object space
+  pub fn uv_at(&self, object_space_point: Tuple) -> (F, F) {
+    match *self {
+      Body::Sphere(_) => spherical_map(object_space_point),
+      Body::Cylinder(_) | Body::Cone(_) => cylindrical_map(object_space_point),
+      // Quads show the texture exactly once
+      Body::Quad(ref quad) => (
+        (object_space_point.x / quad.width + 0.5).clamp(0.0, 1.0),
+        (object_space_point.z / quad.height + 0.5).clamp(0.0, 1.0),
+      ),
+      Body::Plane(_)
+      | Body::Triangle(_)
+      | Body::SmoothTriangle(_)
+      | Body::Sdf(_)
+      | Body::Mirrored(_)
+      | Body::Repeated(_)
+      | Body::Mesh(_) => planar_map(object_space_point),
+    }
+  }
+
+  pub fn with_material(self, material: Material) -> Self {
+    match self {
+      Body::Sphere(sphere) => Body::from(sphere.with_material(material)),
+      Body::Plane(plane) => Body::from(plane.with_material(material)),
+      Body::Quad(quad) => Body::from(quad.with_material(material)),
+      Body::Cylinder(cylinder) => Body::from(cylinder.with_material(material)),
+      Body::Cone(cone) => Body::from(cone.with_material(material)),
+      Body::Triangle(triangle) => Body::from(triangle.with_material(material)),
+      Body::SmoothTriangle(smooth_triangle) => Body::from(smooth_triangle.with_material(material)),
+      Body::Sdf(sdf_body) => Body::from(sdf_body.with_material(material)),
+      Body::Repeated(repeated) => Body::from(repeated.with_material(material)),
+      Body::Mirrored(mirrored) => Body::from(mirrored.with_material(material)),
+      Body::Mesh(mesh_body) => Body::from(mesh_body.with_material(material)),
+    }
+  }
+
+  pub fn with_shadows(self, shadows: Shadows) -> Self {
+    match self {
+      Body::Sphere(sphere) => Body::from(sphere.with_shadows(shadows)),
+      Body::Plane(plane) => Body::from(plane.with_shadows(shadows)),
+      Body::Quad(quad) => Body::from(quad.with_shadows(shadows)),
+      Body::Cylinder(cylinder) => Body::from(cylinder.with_shadows(shadows)),
+      Body::Cone(cone) => Body::from(cone.with_shadows(shadows)),
+      Body::Triangle(triangle) => Body::from(triangle.with_shadows(shadows)),
+      Body::SmoothTriangle(smooth_triangle) => Body::from(smooth_triangle.with_shadows(shadows)),
+      Body::Sdf(sdf_body) => Body::from(sdf_body.with_shadows(shadows)),
+      Body::Repeated(repeated) => Body::from(repeated.with_shadows(shadows)),
+      Body::Mirrored(mirrored) => Body::from(mirrored.with_shadows(shadows)),
+      Body::Mesh(mesh_body) => Body::from(mesh_body.with_shadows(shadows)),
+    }
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    match self {
+      Body::Sphere(sphere) => Body::from(sphere.with_transform(transform)),
+      Body::Plane(plane) => Body::from(plane.with_transform(transform)),
+      Body::Quad(quad) => Body::from(quad.with_transform(transform)),
+      Body::Cylinder(cylinder) => Body::from(cylinder.with_transform(transform)),
+      Body::Cone(cone) => Body::from(cone.with_transform(transform)),
+      Body::Triangle(triangle) => Body::from(triangle.with_transform(transform)),
+      Body::SmoothTriangle(smooth_triangle) => {
+        Body::from(smooth_triangle.with_transform(transform))
+      }
+      Body::Sdf(sdf_body) => Body::from(sdf_body.with_transform(transform)),
+      Body::Repeated(repeated) => Body::from(repeated.with_transform(transform)),
+      Body::Mirrored(mirrored) => Body::from(mirrored.with_transform(transform)),
+      Body::Mesh(mesh_body) => Body::from(mesh_body.with_transform(transform)),
+    }
+  }
+
+  /// Like `with_transform`, but takes the inverse from the caller, who often
+  /// can compose it from inverses known already instead of inverting again
+  pub fn with_transform_and_inverse(
+    self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    match self {
+      Body::Sphere(sphere) => {
+        Body::from(sphere.with_transform_and_inverse(transform, inverse_transform))
+      }
+      Body::Plane(plane) => {
+        Body::from(plane.with_transform_and_inverse(transform, inverse_transform))
+      }
+      Body::Quad(quad) => Body::from(quad.with_transform_and_inverse(transform, inverse_transform)),
+      Body::Cylinder(cylinder) => {
+        Body::from(cylinder.with_transform_and_inverse(transform, inverse_transform))
+      }
+      Body::Cone(cone) => Body::from(cone.with_transform_and_inverse(transform, inverse_transform)),
+      Body::Triangle(triangle) => {
+        Body::from(triangle.with_transform_and_inverse(transform, inverse_transform))
+      }
+      Body::SmoothTriangle(smooth_triangle) => {
+        Body::from(smooth_triangle.with_transform_and_inverse(transform, inverse_transform))
+      }
+      Body::Sdf(sdf_body) => {
+        Body::from(sdf_body.with_transform_and_inverse(transform, inverse_transform))
+      }
+      Body::Repeated(repeated) => {
+        Body::from(repeated.with_transform_and_inverse(transform, inverse_transform))
+      }
+      Body::Mirrored(mirrored) => {
+        Body::from(mirrored.with_transform_and_inverse(transform, inverse_transform))
+      }
+      Body::Mesh(mesh_body) => {
+        Body::from(mesh_body.with_transform_and_inverse(transform, inverse_transform))
+      }
+    }
+  }
+}
+
+impl FuzzyEq<Body> for Body {
+  fn fuzzy_eq(&self, other: Body) -> bool {
+    match (self, other) {
+      (Body::Sphere(ref sphere), Body::Sphere(ref other)) => sphere.fuzzy_eq(other),
+      (Body::Plane(ref plane), Body::Plane(ref other)) => plane.fuzzy_eq(other),
+      (Body::Quad(ref quad), Body::Quad(ref other)) => quad.fuzzy_eq(other),
+      (Body::Cylinder(ref cylinder), Body::Cylinder(ref other)) => cylinder.fuzzy_eq(other),
+      (Body::Cone(ref cone), Body::Cone(ref other)) => cone.fuzzy_eq(other),
+      (Body::Triangle(ref triangle), Body::Triangle(ref other)) => triangle.fuzzy_eq(other),
+      (Body::SmoothTriangle(ref smooth_triangle), Body::SmoothTriangle(ref other)) => {
+        smooth_triangle.fuzzy_eq(other)
+      }
+      (Body::Sdf(ref sdf_body), Body::Sdf(ref other)) => sdf_body.fuzzy_eq(other),
+      (Body::Repeated(ref repeated), Body::Repeated(ref other)) => repeated.fuzzy_eq(other),
+      (Body::Mirrored(ref mirrored), Body::Mirrored(ref other)) => mirrored.fuzzy_eq(other),
+      (Body::Mesh(ref mesh_body), Body::Mesh(ref other)) => mesh_body.fuzzy_eq(other),
+      _ => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn an_intersection_encapsulates_t_and_object() {
+    let s = Sphere::default();
+
+    let r = Ray::new(Tuple::point(1.0, 1.0, 1.0), Tuple::vector(0.0, 0.0, 1.0));
+    let i = Intersection::new(3.5, r, Body::from(s.clone()));
+    assert_fuzzy_eq!(i.t, 3.5);
+    assert_eq!(i.body, Body::from(s));
+  }
+
+  #[test]
+  fn bounds_are_transformed_into_world_space() {
+    let s = Sphere::default().with_transform(Matrix::translation(0.0, 2.0, 0.0));
+
+    assert_fuzzy_eq!(
+      Body::from(s).bounds(),
+      Bounds::new(Tuple::point(-1.0, 1.0, -1.0), Tuple::point(1.0, 3.0, 1.0))
+    );
+  }
+
+  #[test]
+  fn rays_missing_the_bounds_do_not_intersect() {
+    let s = Sphere::default();
+    let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    assert!(Body::from(s).intersect(r).is_empty());
+  }
+}
@@ -0,0 +1,225 @@
+use crate::fuzzy_eq::FuzzyEq;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::tuple::Tuple;
+use crate::{EPSILON, F};
+
+/// Axis aligned bounding box enclosing a body.
+///
+/// Unbounded bodies, like planes, use infinite bounds, which every ray hits.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bounds {
+  pub min: Tuple,
+  pub max: Tuple,
+}
+
+impl Bounds {
+  pub fn new(min: Tuple, max: Tuple) -> Self {
+    Self { min, max }
+  }
+
+  pub fn infinite() -> Self {
+    Self::new(
+      Tuple::point(F::NEG_INFINITY, F::NEG_INFINITY, F::NEG_INFINITY),
+      Tuple::point(F::INFINITY, F::INFINITY, F::INFINITY),
+    )
+  }
+
+  /// Smallest bounds enclosing all given points
+  pub fn from_points(points: &[Tuple]) -> Self {
+    let mut bounds = Self::new(
+      Tuple::point(F::INFINITY, F::INFINITY, F::INFINITY),
+      Tuple::point(F::NEG_INFINITY, F::NEG_INFINITY, F::NEG_INFINITY),
+    );
+    for point in points {
+      bounds.min = Tuple::point(
+        bounds.min.x.min(point.x),
+        bounds.min.y.min(point.y),
+        bounds.min.z.min(point.z),
+      );
+      bounds.max = Tuple::point(
+        bounds.max.x.max(point.x),
+        bounds.max.y.max(point.y),
+        bounds.max.z.max(point.z),
+      );
+    }
+    bounds
+  }
+
+  pub fn is_infinite(&self) -> bool {
+    [
+      self.min.x, self.min.y, self.min.z, self.max.x, self.max.y, self.max.z,
+    ]
+    .iter()
+    .any(|value| value.is_infinite())
+  }
+
+  pub fn center(&self) -> Tuple {
+    Tuple::point(
+      (self.min.x + self.max.x) / 2.0,
+      (self.min.y + self.max.y) / 2.0,
+      (self.min.z + self.max.z) / 2.0,
+    )
+  }
+
+  /// Smallest bounds enclosing both bounds
+  pub fn union(&self, other: Bounds) -> Self {
+    Self::from_points(&[self.min, self.max, other.min, other.max])
+  }
+
+  /// Axis aligned bounds enclosing these bounds after the transformation
+  pub fn transform(&self, transform: Matrix<4>) -> Self {
+    if self.is_infinite() {
+      return Self::infinite();
+    }
+
+    let corners: Vec<Tuple> = (0..8)
+      .map(|corner| {
+        transform
+          * Tuple::point(
+            if corner & 1 == 0 {
+              self.min.x
+            } else {
+              self.max.x
+            },
+            if corner & 2 == 0 {
+              self.min.y
+            } else {
+              self.max.y
+            },
+            if corner & 4 == 0 {
+              self.min.z
+            } else {
+              self.max.z
+            },
+          )
+      })
+      .collect();
+    Self::from_points(&corners)
+  }
+
+  /// Whether the bounds share some volume. Bounds only touching each other
+  /// do not overlap.
+  pub fn overlaps(&self, other: Bounds) -> bool {
+    self.min.x < other.max.x
+      && other.min.x < self.max.x
+      && self.min.y < other.max.y
+      && other.min.y < self.max.y
+      && self.min.z < other.max.z
+      && other.min.z < self.max.z
+  }
+
+  /// Whether the line of the ray passes through the bounds (slab test).
+  ///
+  /// Like intersections with bodies, this includes hits behind the origin of
+  /// the ray. The bounds are slightly padded, so flat bounds like those of a
+  /// triangle are still hit.
+  pub fn intersects(&self, ray: Ray) -> bool {
+    self.span(ray).is_some()
+  }
+
+  /// Distances along the ray, at which its line enters and leaves the
+  /// padded bounds, if it passes through them at all
+  pub fn span(&self, ray: Ray) -> Option<(F, F)> {
+    let axes = [
+      (ray.origin.x, ray.direction.x, self.min.x, self.max.x),
+      (ray.origin.y, ray.direction.y, self.min.y, self.max.y),
+      (ray.origin.z, ray.direction.z, self.min.z, self.max.z),
+    ];
+
+    let mut t_min = F::NEG_INFINITY;
+    let mut t_max = F::INFINITY;
+    for (origin, direction, min, max) in axes.iter() {
+      let (min, max) = (min - EPSILON, max + EPSILON);
+      // Only rays exactly parallel to the axis are checked by their origin, as
+      // the tiny directions in the space of huge bodies still divide fine
+      if *direction == 0.0 {
+        if *origin < min || *origin > max {
+          return None;
+        }
+        continue;
+      }
+
+      let t1 = (min - origin) / direction;
+      let t2 = (max - origin) / direction;
+      t_min = t_min.max(t1.min(t2));
+      t_max = t_max.min(t1.max(t2));
+    }
+
+    if t_min <= t_max {
+      Some((t_min, t_max))
+    } else {
+      None
+    }
+  }
+}
+
+impl FuzzyEq<Bounds> for Bounds {
+  fn fuzzy_eq(&self, other: Bounds) -> bool {
+    self.min.fuzzy_eq(other.min) && self.max.fuzzy_eq(other.max)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn unit_cube() -> Bounds {
+    Bounds::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+  }
+
+  #[test]
+  fn rays_hitting_and_missing_bounds() {
+    let bounds = unit_cube();
+
+    let hit = Ray::new(Tuple::point(0.5, 0.5, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let diagonal = Ray::new(Tuple::point(-5.0, -5.0, -5.0), Tuple::vector(1.0, 1.0, 1.0));
+    let inside = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+    let miss = Ray::new(Tuple::point(2.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let behind = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let parallel = Ray::new(Tuple::point(0.0, 2.0, 0.0), Tuple::vector(1.0, 0.0, 0.0));
+
+    assert!(bounds.intersects(hit));
+    assert!(bounds.intersects(diagonal));
+    assert!(bounds.intersects(inside));
+    assert!(!bounds.intersects(miss));
+    assert!(bounds.intersects(behind));
+    assert!(!bounds.intersects(parallel));
+  }
+
+  #[test]
+  fn flat_bounds_are_hit() {
+    let bounds = Bounds::new(Tuple::point(-1.0, 0.0, -1.0), Tuple::point(1.0, 0.0, 1.0));
+    let ray = Ray::new(Tuple::point(0.0, 2.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+    assert!(bounds.intersects(ray));
+  }
+
+  #[test]
+  fn tiny_directions_still_hit() {
+    let ray = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1e-7));
+
+    assert!(unit_cube().intersects(ray));
+  }
+
+  #[test]
+  fn overlapping_and_touching_bounds() {
+    let moved = |x: F| unit_cube().transform(Matrix::translation(x, 0.5, 0.0));
+
+    assert!(unit_cube().overlaps(moved(1.5)));
+    assert!(!unit_cube().overlaps(moved(2.0)));
+    assert!(!unit_cube().overlaps(moved(-3.0)));
+    assert!(unit_cube().overlaps(Bounds::infinite()));
+  }
+
+  #[test]
+  fn transformed_bounds_enclose_all_corners() {
+    let transform = Matrix::translation(1.0, 0.0, 0.0) * Matrix::scaling(2.0, 1.0, 1.0);
+
+    assert_fuzzy_eq!(
+      unit_cube().transform(transform),
+      Bounds::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(3.0, 1.0, 1.0))
+    );
+    assert!(Bounds::infinite().transform(transform).is_infinite());
+  }
+}
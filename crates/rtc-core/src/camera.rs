@@ -0,0 +1,609 @@
+use crate::angle::Angle;
+use crate::canvas::Color;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::matrix::Matrix;
+use crate::post_processing::Pipeline;
+use crate::ray::Ray;
+use crate::renderer::adaptive::AdaptiveSampling;
+use crate::sampling::Jitter;
+use crate::tuple::Tuple;
+use crate::F;
+
+/// Normalized sub rectangle of the film, which is actually rendered.
+///
+/// All coordinates are in the range [0, 1], with (0, 0) being the top left
+/// corner of the film.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CropWindow {
+  pub left: F,
+  pub top: F,
+  pub right: F,
+  pub bottom: F,
+}
+
+impl CropWindow {
+  pub fn new(left: F, top: F, right: F, bottom: F) -> Self {
+    Self {
+      left,
+      top,
+      right,
+      bottom,
+    }
+  }
+
+  pub fn width(&self) -> F {
+    self.right - self.left
+  }
+
+  pub fn height(&self) -> F {
+    self.bottom - self.top
+  }
+}
+
+impl Default for CropWindow {
+  fn default() -> Self {
+    Self::new(0.0, 0.0, 1.0, 1.0)
+  }
+}
+
+impl FuzzyEq<CropWindow> for CropWindow {
+  fn fuzzy_eq(&self, other: CropWindow) -> bool {
+    self.left.fuzzy_eq(other.left)
+      && self.top.fuzzy_eq(other.top)
+      && self.right.fuzzy_eq(other.right)
+      && self.bottom.fuzzy_eq(other.bottom)
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct Camera {
+  pub transform: Matrix<4>,
+  pub vsize: usize,
+  pub hsize: usize,
+  pub field_of_view: F,
+  /// Number of samples along each axis of a pixel (samples * samples rays)
+  pub samples: usize,
+  pub jitter: Jitter,
+  /// Chooses the samples per tile automatically, if set
+  pub adaptive_sampling: Option<AdaptiveSampling>,
+  /// Exposure compensation in stops (each stop doubles the brightness)
+  pub exposure: F,
+  /// Part of the film, which is scaled evenly to fit into the
+  /// `hsize * vsize` pixels
+  pub crop_window: CropWindow,
+  /// Effects applied to the rendered canvas
+  pub post_processing: Pipeline,
+  /// Radius of the lens, 0 renders everything sharp like a pinhole camera
+  pub aperture: F,
+  /// Distance from the camera at which the image is sharp
+  pub focal_distance: F,
+  /// Bytes all tiles rendered at the same time may use, tiles needing more
+  /// are split into smaller ones
+  pub memory_ceiling: Option<usize>,
+  /// Whether rays of the camera skip bodies outside of its view, see
+  /// `World::for_camera`
+  pub frustum_culling: bool,
+  half_width: F,
+  half_height: F,
+  pixel_size: F,
+}
+
+impl Camera {
+  pub fn new(hsize: usize, vsize: usize, field_of_view: Angle) -> Self {
+    let field_of_view = field_of_view.as_radians();
+    let half_size = (field_of_view / 2.0).tan();
+    let aspect_ratio = hsize as F / vsize as F;
+    let half_width;
+    let half_height;
+
+    if aspect_ratio >= 1.0 {
+      half_width = half_size;
+      half_height = half_size / aspect_ratio;
+    } else {
+      half_height = half_size;
+      half_width = half_size * aspect_ratio;
+    }
+
+    let pixel_size = (half_width * 2.0) / hsize as F;
+
+    Camera {
+      vsize,
+      hsize,
+      field_of_view,
+      transform: Matrix::identity(),
+      samples: 1,
+      jitter: Jitter::default(),
+      adaptive_sampling: None,
+      exposure: 0.0,
+      crop_window: CropWindow::default(),
+      post_processing: Pipeline::default(),
+      aperture: 0.0,
+      focal_distance: 1.0,
+      memory_ceiling: None,
+      frustum_culling: false,
+      half_width,
+      half_height,
+      pixel_size,
+    }
+  }
+
+  /// Tiny camera of the book, looking from (0, 0, -5) at the center of
+  /// `World::book_default`
+  pub fn book_default() -> Self {
+    Camera::new(11, 11, Angle::radians(std::f64::consts::PI / 2.0)).look_at_from_position(
+      Tuple::point(0.0, 0.0, -5.0),
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    )
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self
+  }
+
+  pub fn look_at_from_position(mut self, from: Tuple, to: Tuple, up: Tuple) -> Self {
+    self.transform = Matrix::view_transform(from, to, up);
+    self
+  }
+
+  pub fn with_samples(mut self, samples: usize) -> Self {
+    self.samples = samples.max(1);
+    self
+  }
+
+  pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+    self.jitter = jitter;
+    self
+  }
+
+  pub fn with_adaptive_sampling(mut self, adaptive_sampling: AdaptiveSampling) -> Self {
+    self.adaptive_sampling = Some(adaptive_sampling);
+    self
+  }
+
+  pub fn with_exposure(mut self, exposure: F) -> Self {
+    self.exposure = exposure;
+    self
+  }
+
+  pub fn with_crop_window(mut self, crop_window: CropWindow) -> Self {
+    self.crop_window = crop_window;
+    self
+  }
+
+  pub fn with_memory_ceiling(mut self, bytes: usize) -> Self {
+    self.memory_ceiling = Some(bytes);
+    self
+  }
+
+  pub fn with_frustum_culling(mut self, frustum_culling: bool) -> Self {
+    self.frustum_culling = frustum_culling;
+    self
+  }
+
+  pub fn with_post_processing(mut self, post_processing: Pipeline) -> Self {
+    self.post_processing = post_processing;
+    self
+  }
+
+  /// Blurs everything outside of the focal distance.
+  ///
+  /// The samples of each pixel are spread over the lens, so the blur needs
+  /// several samples per pixel to be smooth.
+  pub fn with_depth_of_field(mut self, aperture: F, focal_distance: F) -> Self {
+    self.aperture = aperture;
+    self.focal_distance = focal_distance;
+    self
+  }
+
+  pub fn with_focal_distance(mut self, focal_distance: F) -> Self {
+    self.focal_distance = focal_distance;
+    self
+  }
+
+  /// Position of the camera in world space
+  pub fn position(&self) -> Tuple {
+    self.transform.inverse() * Tuple::point(0.0, 0.0, 0.0)
+  }
+
+  /// Applies the exposure compensation of the camera to a rendered color.
+  pub fn expose(&self, color: Color) -> Color {
+    color * (2.0 as F).powf(self.exposure)
+  }
+
+  pub fn ray_for_pixel(&self, x: usize, y: usize) -> Ray {
+    self
+      .ray_for_film_position(0.5 + x as F, 0.5 + y as F, (0.0, 0.0))
+      .with_time(shutter_time(0, 1, 0))
+  }
+
+  /// Rays for all sub pixel samples of the given pixel.
+  ///
+  /// The pixel is divided into a grid of `samples * samples` cells. Each ray
+  /// passes through one of those cells, offset within the cell by the
+  /// configured jitter. With depth of field, the rays additionally start
+  /// from different points spread over the lens. Their times are spread over
+  /// the shutter interval to blur moving bodies.
+  pub fn rays_for_pixel(&self, x: usize, y: usize) -> Vec<Ray> {
+    let cell_count = self.samples * self.samples;
+    let stream = (y * self.hsize + x) as u64;
+    let offsets = self.jitter.offsets(stream, cell_count * 2);
+    let cell_size = 1.0 / self.samples as F;
+
+    (0..cell_count)
+      .map(|cell| {
+        let cell_x = (cell % self.samples) as F;
+        let cell_y = (cell / self.samples) as F;
+        self
+          .ray_for_film_position(
+            x as F + (cell_x + offsets[cell * 2]) * cell_size,
+            y as F + (cell_y + offsets[cell * 2 + 1]) * cell_size,
+            lens_position(cell, cell_count, stream),
+          )
+          .with_time(shutter_time(cell, cell_count, stream))
+      })
+      .collect()
+  }
+
+  /// Direction of the ray through the center of the lens and the given
+  /// position on the film, measured in pixels
+  pub fn direction_through_film(&self, film_x: F, film_y: F) -> Tuple {
+    self
+      .ray_for_film_position(film_x, film_y, (0.0, 0.0))
+      .direction
+  }
+
+  /// Ray through the given position on the film, measured in pixels.
+  ///
+  /// The position is relative to the crop window, which is scaled evenly to
+  /// fit into the pixels of the camera and centered. A crop window with
+  /// another aspect ratio than the camera thus keeps the pixels square and
+  /// shows some of the film beside it. The lens position lies within the unit
+  /// disk.
+  fn ray_for_film_position(&self, film_x: F, film_y: F, lens: (F, F)) -> Ray {
+    let crop = self.crop_window;
+    let scale = crop.width().max(crop.height());
+    let film_x =
+      (crop.left + crop.width() / 2.0) * self.hsize as F + (film_x - self.hsize as F / 2.0) * scale;
+    let film_y =
+      (crop.top + crop.height() / 2.0) * self.vsize as F + (film_y - self.vsize as F / 2.0) * scale;
+
+    let offset_x = film_x * self.pixel_size;
+    let offset_y = film_y * self.pixel_size;
+
+    let world_x = self.half_width - offset_x;
+    let world_y = self.half_height - offset_y;
+
+    let inverse_view_transform = self.transform.inverse();
+
+    // Rays from every point of the lens meet on the focal plane
+    let focal_point = inverse_view_transform
+      * Tuple::point(
+        world_x * self.focal_distance,
+        world_y * self.focal_distance,
+        -self.focal_distance,
+      );
+    let ray_origin =
+      inverse_view_transform * Tuple::point(lens.0 * self.aperture, lens.1 * self.aperture, 0.0);
+    let ray_direction = (focal_point - ray_origin).normalize();
+
+    Ray::new(ray_origin, ray_direction)
+  }
+}
+
+/// Time of the given sample within the shutter interval [0, 1].
+///
+/// The samples are stratified over the interval, shifted for every pixel so
+/// the time does not correlate with the position of the sample. A single
+/// sample is taken in the middle of the shutter interval.
+fn shutter_time(sample: usize, sample_count: usize, stream: u64) -> F {
+  if sample_count == 1 {
+    return 0.5;
+  }
+  const GOLDEN_RATIO_FRACTION: F = 0.618_033_988_749_895;
+  let time = (sample as F + 0.5) / sample_count as F + stream as F * GOLDEN_RATIO_FRACTION;
+  time - time.floor()
+}
+
+/// Position of the given sample on the unit disk of the lens.
+///
+/// The samples are evenly spread along a golden angle spiral, which is
+/// rotated for every pixel. A single sample sits at the center of the lens.
+fn lens_position(sample: usize, sample_count: usize, stream: u64) -> (F, F) {
+  const GOLDEN_ANGLE: F = 2.399_963_229_728_653;
+  let radius = (sample as F / sample_count as F).sqrt();
+  let angle = (sample as F + stream as F) * GOLDEN_ANGLE;
+  (radius * angle.cos(), radius * angle.sin())
+}
+
+impl FuzzyEq<Camera> for Camera {
+  fn fuzzy_eq(&self, other: Camera) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+      && self.vsize == other.vsize
+      && self.hsize == other.hsize
+      && self.field_of_view.fuzzy_eq(other.field_of_view)
+      && self.samples == other.samples
+      && self.jitter.fuzzy_eq(other.jitter)
+      && self.adaptive_sampling == other.adaptive_sampling
+      && self.exposure.fuzzy_eq(other.exposure)
+      && self.crop_window.fuzzy_eq(other.crop_window)
+      && self.post_processing == other.post_processing
+      && self.aperture.fuzzy_eq(other.aperture)
+      && self.focal_distance.fuzzy_eq(other.focal_distance)
+      && self.memory_ceiling == other.memory_ceiling
+      && self.frustum_culling == other.frustum_culling
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::f64::consts::PI;
+
+  #[test]
+  fn constructing_a_camera() {
+    let vsize = 160;
+    let hsize = 120;
+    let fov = PI / 2.0;
+
+    let camera = Camera::new(hsize, vsize, Angle::radians(fov));
+
+    assert_eq!(camera.vsize, vsize);
+    assert_eq!(camera.hsize, hsize);
+    assert_fuzzy_eq!(camera.field_of_view, fov);
+  }
+
+  #[test]
+  fn constructing_a_camera_with_a_field_of_view_in_degrees() {
+    let camera = Camera::new(160, 120, Angle::degrees(90.0));
+
+    assert_fuzzy_eq!(camera.field_of_view, PI / 2.0);
+  }
+
+  #[test]
+  fn constructed_a_camera_has_an_identity_transform() {
+    let vsize = 160;
+    let hsize = 120;
+    let fov = PI / 2.0;
+
+    let camera = Camera::new(hsize, vsize, Angle::radians(fov));
+
+    assert_fuzzy_eq!(camera.transform, Matrix::identity());
+  }
+
+  #[test]
+  fn constructing_a_camera_with_a_transform() {
+    let vsize = 160;
+    let hsize = 120;
+    let fov = PI / 2.0;
+    let transform = Matrix::translation(1.2, 3.4, -20.6);
+
+    let camera = Camera::new(hsize, vsize, Angle::radians(fov)).with_transform(transform);
+
+    assert_eq!(camera.vsize, vsize);
+    assert_eq!(camera.hsize, hsize);
+    assert_fuzzy_eq!(camera.field_of_view, fov);
+    assert_fuzzy_eq!(camera.transform, transform);
+  }
+
+  #[test]
+  fn constructing_a_ray_through_the_center_of_the_canvas() {
+    let c = Camera::new(201, 101, Angle::radians(PI / 2.0));
+    let r = c.ray_for_pixel(100, 50);
+
+    assert_fuzzy_eq!(r.origin, Tuple::point(0.0, 0.0, 0.0));
+    assert_fuzzy_eq!(r.direction, Tuple::vector(0.0, 0.0, -1.0));
+  }
+
+  #[test]
+  fn constructing_a_ray_through_the_corner_of_a_canvas() {
+    let c = Camera::new(201, 101, Angle::radians(PI / 2.0));
+    let r = c.ray_for_pixel(0, 0);
+
+    assert_fuzzy_eq!(r.origin, Tuple::point(0.0, 0.0, 0.0));
+    assert_fuzzy_eq!(r.direction, Tuple::vector(0.66519, 0.33259, -0.66851));
+  }
+
+  #[test]
+  fn constructing_a_ray_when_camera_is_transformed() {
+    let c = Camera::new(201, 101, Angle::radians(PI / 2.0)).with_transform(
+      Matrix::rotation_y(Angle::radians(PI / 4.0)) * Matrix::translation(0.0, -2.0, 5.0),
+    );
+    let r = c.ray_for_pixel(100, 50);
+
+    assert_fuzzy_eq!(r.origin, Tuple::point(0.0, 2.0, -5.0));
+    assert_fuzzy_eq!(
+      r.direction,
+      Tuple::vector((2.0 as F).sqrt() / 2.0, 0.0, -((2.0 as F).sqrt()) / 2.0)
+    );
+  }
+
+  #[test]
+  fn a_single_centered_sample_matches_the_pixel_center_ray() {
+    let c = Camera::new(201, 101, Angle::radians(PI / 2.0));
+    let rays = c.rays_for_pixel(0, 0);
+
+    assert_eq!(rays.len(), 1);
+    assert_fuzzy_eq!(rays[0].direction, c.ray_for_pixel(0, 0).direction);
+  }
+
+  #[test]
+  fn supersampling_creates_a_ray_for_each_sub_pixel() {
+    let c = Camera::new(201, 101, Angle::radians(PI / 2.0)).with_samples(3);
+    let rays = c.rays_for_pixel(100, 50);
+
+    assert_eq!(rays.len(), 9);
+    // The center cell of an uneven grid is the pixel center
+    assert_fuzzy_eq!(rays[4].direction, Tuple::vector(0.0, 0.0, -1.0));
+  }
+
+  #[test]
+  fn seeded_jitter_produces_reproducible_rays() {
+    let c = Camera::new(201, 101, Angle::radians(PI / 2.0))
+      .with_samples(2)
+      .with_jitter(Jitter::Seeded(42));
+
+    let first: Vec<Tuple> = c
+      .rays_for_pixel(10, 20)
+      .iter()
+      .map(|r| r.direction)
+      .collect();
+    let second: Vec<Tuple> = c
+      .rays_for_pixel(10, 20)
+      .iter()
+      .map(|r| r.direction)
+      .collect();
+
+    assert_fuzzy_eq!(first, second);
+  }
+
+  #[test]
+  fn pixel_size_for_horizontal_canvas() {
+    let c = Camera::new(200, 125, Angle::radians(PI / 2.0));
+    assert_fuzzy_eq!(c.pixel_size, 0.01);
+  }
+
+  #[test]
+  fn pixel_size_for_vertical() {
+    let c = Camera::new(125, 200, Angle::radians(PI / 2.0));
+    assert_fuzzy_eq!(c.pixel_size, 0.01);
+  }
+
+  #[test]
+  fn exposure_scales_colors_by_stops() {
+    let c = Camera::new(201, 101, Angle::radians(PI / 2.0));
+    assert_fuzzy_eq!(
+      c.expose(Color::new(0.2, 0.4, 0.1)),
+      Color::new(0.2, 0.4, 0.1)
+    );
+
+    let c = c.with_exposure(1.0);
+    assert_fuzzy_eq!(
+      c.expose(Color::new(0.2, 0.4, 0.1)),
+      Color::new(0.4, 0.8, 0.2)
+    );
+
+    let c = c.with_exposure(-2.0);
+    assert_fuzzy_eq!(
+      c.expose(Color::new(0.2, 0.4, 0.1)),
+      Color::new(0.05, 0.1, 0.025)
+    );
+  }
+
+  #[test]
+  fn crop_window_maps_pixels_onto_a_part_of_the_film() {
+    let full = Camera::new(200, 100, Angle::radians(PI / 2.0));
+    let cropped = Camera::new(200, 100, Angle::radians(PI / 2.0))
+      .with_crop_window(CropWindow::new(0.5, 0.5, 1.0, 1.0));
+
+    // The first pixel of the cropped camera lies in the center of the film
+    assert_fuzzy_eq!(
+      cropped
+        .ray_for_film_position(0.0, 0.0, (0.0, 0.0))
+        .direction,
+      full
+        .ray_for_film_position(100.0, 50.0, (0.0, 0.0))
+        .direction
+    );
+    // The last pixel corner still is the lower right corner of the film
+    assert_fuzzy_eq!(
+      cropped
+        .ray_for_film_position(200.0, 100.0, (0.0, 0.0))
+        .direction,
+      full
+        .ray_for_film_position(200.0, 100.0, (0.0, 0.0))
+        .direction
+    );
+  }
+
+  #[test]
+  fn crop_windows_of_another_aspect_ratio_keep_the_pixels_square() {
+    let full = Camera::new(200, 100, Angle::radians(PI / 2.0));
+    // The left half of the film is a square
+    let cropped = Camera::new(200, 100, Angle::radians(PI / 2.0))
+      .with_crop_window(CropWindow::new(0.0, 0.0, 0.5, 1.0));
+
+    let direction =
+      |camera: &Camera, x: F, y: F| camera.ray_for_film_position(x, y, (0.0, 0.0)).direction;
+    // The center of the crop window is the center of the image
+    assert_fuzzy_eq!(
+      direction(&cropped, 100.0, 50.0),
+      direction(&full, 50.0, 50.0)
+    );
+    // Steps along both axes cover the same distance on the film
+    assert_fuzzy_eq!(
+      direction(&cropped, 110.0, 50.0),
+      direction(&full, 60.0, 50.0)
+    );
+    assert_fuzzy_eq!(
+      direction(&cropped, 100.0, 60.0),
+      direction(&full, 50.0, 60.0)
+    );
+    // The whole crop window is visible
+    assert_fuzzy_eq!(direction(&cropped, 50.0, 0.0), direction(&full, 0.0, 0.0));
+    assert_fuzzy_eq!(
+      direction(&cropped, 150.0, 100.0),
+      direction(&full, 100.0, 100.0)
+    );
+  }
+
+  #[test]
+  fn default_crop_window_renders_the_whole_film() {
+    let c = Camera::new(201, 101, Angle::radians(PI / 2.0)).with_crop_window(CropWindow::default());
+    let r = c.ray_for_pixel(0, 0);
+
+    assert_fuzzy_eq!(r.direction, Tuple::vector(0.66519, 0.33259, -0.66851));
+  }
+
+  #[test]
+  fn rays_through_the_lens_meet_at_the_focal_distance() {
+    let c = Camera::new(101, 101, Angle::radians(PI / 2.0))
+      .look_at_from_position(
+        Tuple::point(0.0, 0.0, 5.0),
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::vector(0.0, 1.0, 0.0),
+      )
+      .with_depth_of_field(0.5, 5.0);
+
+    let center = c.ray_for_film_position(50.5, 50.5, (0.0, 0.0));
+    let edge = c.ray_for_film_position(50.5, 50.5, (1.0, 0.0));
+
+    assert_fuzzy_eq!(c.position(), Tuple::point(0.0, 0.0, 5.0));
+    assert_fuzzy_eq!(edge.origin, Tuple::point(0.5, 0.0, 5.0));
+    assert_fuzzy_eq!(center.position(5.0), Tuple::point(0.0, 0.0, 0.0));
+    assert_fuzzy_eq!(
+      edge.position((0.5 as F).hypot(5.0)),
+      Tuple::point(0.0, 0.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn samples_of_a_pixel_are_spread_over_the_lens() {
+    let c = Camera::new(11, 11, Angle::radians(PI / 2.0))
+      .with_samples(2)
+      .with_depth_of_field(0.5, 5.0);
+
+    let rays = c.rays_for_pixel(5, 5);
+    let origins: Vec<Tuple> = rays.iter().map(|ray| ray.origin).collect();
+
+    assert_fuzzy_eq!(origins[0], Tuple::point(0.0, 0.0, 0.0));
+    assert!(origins[1..]
+      .iter()
+      .all(|origin| origin.fuzzy_ne(Tuple::point(0.0, 0.0, 0.0))));
+  }
+
+  #[test]
+  fn samples_of_a_pixel_are_spread_over_the_shutter_interval() {
+    let c = Camera::new(11, 11, Angle::radians(PI / 2.0)).with_samples(2);
+
+    let mut times: Vec<F> = c.rays_for_pixel(5, 5).iter().map(|ray| ray.time).collect();
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    assert_fuzzy_eq!(c.ray_for_pixel(5, 5).time, 0.5);
+    assert!(times.iter().all(|time| (0.0..1.0).contains(time)));
+    for pair in times.windows(2) {
+      assert_fuzzy_eq!(pair[1] - pair[0], 0.25);
+    }
+  }
+}
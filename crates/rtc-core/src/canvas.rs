@@ -1,115 +1,25 @@
+pub mod composite;
+pub mod filter;
+#[cfg(feature = "png")]
+pub mod from_png;
+#[cfg(feature = "gif")]
+pub mod to_gif;
+#[cfg(feature = "png")]
 pub mod to_png;
 pub mod to_ppm;
 pub mod to_rgba32;
 
+pub use crate::color::Color;
+use crate::fingerprint::Fingerprint;
 use crate::F;
-use std::ops::{Add, Mul, Sub};
 use std::vec::Vec;
 
-use super::fuzzy_eq::*;
-
 pub trait Sized {
   fn width(&self) -> usize;
   fn height(&self) -> usize;
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Color {
-  pub red: F,
-  pub green: F,
-  pub blue: F,
-}
-
-impl Color {
-  pub fn new(red: F, green: F, blue: F) -> Self {
-    Color { red, green, blue }
-  }
-
-  pub fn black() -> Self {
-    Color::new(0.0, 0.0, 0.0)
-  }
-
-  pub fn white() -> Self {
-    Color::new(1.0, 1.0, 1.0)
-  }
-
-  pub fn red() -> Self {
-    Color::new(1.0, 0.0, 0.0)
-  }
-
-  pub fn green() -> Self {
-    Color::new(0.0, 1.0, 0.0)
-  }
-
-  pub fn blue() -> Self {
-    Color::new(0.0, 0.0, 1.0)
-  }
-
-  pub fn yellow() -> Self {
-    Color::new(1.0, 1.0, 0.0)
-  }
-
-  pub fn clamp(&self, lower_bound: F, upper_bound: F) -> Color {
-    Color::new(
-      self.red.min(upper_bound).max(lower_bound),
-      self.green.min(upper_bound).max(lower_bound),
-      self.blue.min(upper_bound).max(lower_bound),
-    )
-  }
-}
-
-impl Add for Color {
-  type Output = Color;
-
-  fn add(self, other: Color) -> Self::Output {
-    Color::new(
-      self.red + other.red,
-      self.green + other.green,
-      self.blue + other.blue,
-    )
-  }
-}
-
-impl Sub for Color {
-  type Output = Color;
-
-  fn sub(self, other: Color) -> Self::Output {
-    Color::new(
-      self.red - other.red,
-      self.green - other.green,
-      self.blue - other.blue,
-    )
-  }
-}
-
-impl Mul<F> for Color {
-  type Output = Color;
-
-  fn mul(self, other: F) -> Self::Output {
-    Color::new(self.red * other, self.green * other, self.blue * other)
-  }
-}
-
-impl Mul<Color> for Color {
-  type Output = Color;
-
-  fn mul(self, other: Color) -> Self::Output {
-    Color::new(
-      self.red * other.red,
-      self.green * other.green,
-      self.blue * other.blue,
-    )
-  }
-}
-
-impl FuzzyEq<Color> for Color {
-  fn fuzzy_eq(&self, other: Self) -> bool {
-    self.red.fuzzy_eq(other.red)
-      && self.green.fuzzy_eq(other.green)
-      && self.blue.fuzzy_eq(other.blue)
-  }
-}
-
+#[derive(Clone, Debug, PartialEq)]
 pub struct Canvas {
   pub width: usize,
   pub height: usize,
@@ -144,78 +54,46 @@ impl Canvas {
     self.pixels[index] = color;
   }
 
-  fn get_pixel_index(&self, x: usize, y: usize) -> usize {
-    y * self.width + x
-  }
-}
-
-#[cfg(test)]
-mod tests {
-  use super::to_ppm::ToPPM;
-  use super::*;
-
-  #[test]
-  fn colors_are_red_green_blue_tuples() {
-    let c = Color::new(-0.5, 0.4, 1.7);
-
-    assert_fuzzy_eq!(c.red, -0.5);
-    assert_fuzzy_eq!(c.green, 0.4);
-    assert_fuzzy_eq!(c.blue, 1.7);
+  /// Content hash of all pixels
+  pub fn fingerprint(&self) -> Fingerprint {
+    Fingerprint::of_colors(&self.pixels)
   }
 
-  #[test]
-  fn adding_colors() {
-    let c1 = Color::new(0.9, 0.6, 0.75);
-    let c2 = Color::new(0.7, 0.1, 0.25);
-
-    let expected_result = Color::new(1.6, 0.7, 1.0);
-    let actual_result = c1 + c2;
-
-    assert_fuzzy_eq!(actual_result, expected_result);
-  }
-
-  #[test]
-  fn substracting_colors() {
-    let c1 = Color::new(0.9, 0.6, 0.75);
-    let c2 = Color::new(0.7, 0.1, 0.25);
-
-    let expected_result = Color::new(0.2, 0.5, 0.5);
-    let actual_result = c1 - c2;
+  /// Bilinear interpolated color at the given position, measured in pixels.
+  ///
+  /// Pixel centers are located at `x + 0.5` and `y + 0.5`. Positions outside
+  /// of the canvas use the color of the nearest edge pixel. Empty canvases
+  /// are black everywhere.
+  pub fn sample_bilinear(&self, x: F, y: F) -> Color {
+    if self.width == 0 || self.height == 0 {
+      return Color::black();
+    }
+    let x = (x - 0.5).max(0.0).min((self.width - 1) as F);
+    let y = (y - 0.5).max(0.0).min((self.height - 1) as F);
 
-    assert_fuzzy_eq!(actual_result, expected_result);
-  }
+    let x0 = x.floor() as usize;
+    let y0 = y.floor() as usize;
+    let x1 = (x0 + 1).min(self.width - 1);
+    let y1 = (y0 + 1).min(self.height - 1);
+    let fraction_x = x - x0 as F;
+    let fraction_y = y - y0 as F;
 
-  #[test]
-  fn multiplying_a_color_by_a_scalar() {
-    let c = Color::new(0.2, 0.3, 0.4);
-    let multiplier = 2.0;
+    let top = self.pixel_at(x0, y0) * (1.0 - fraction_x) + self.pixel_at(x1, y0) * fraction_x;
+    let bottom = self.pixel_at(x0, y1) * (1.0 - fraction_x) + self.pixel_at(x1, y1) * fraction_x;
 
-    let expected_result = Color::new(0.4, 0.6, 0.8);
-    let actual_result = c * multiplier;
-
-    assert_fuzzy_eq!(actual_result, expected_result);
+    top * (1.0 - fraction_y) + bottom * fraction_y
   }
 
-  #[test]
-  fn multiplying_colors() {
-    let c1 = Color::new(1.0, 0.2, 0.4);
-    let c2 = Color::new(0.9, 1.0, 0.1);
-
-    let expected_result = Color::new(0.9, 0.2, 0.04);
-    let actual_result = c1 * c2;
-
-    assert_fuzzy_eq!(actual_result, expected_result);
+  fn get_pixel_index(&self, x: usize, y: usize) -> usize {
+    y * self.width + x
   }
+}
 
-  #[test]
-  fn clamping_colors() {
-    let c = Color::new(2.3, -6.7, 0.8);
-
-    let expected_result = Color::new(1.0, 0.0, 0.8);
-    let actual_result = c.clamp(0.0, 1.0);
-
-    assert_fuzzy_eq!(actual_result, expected_result);
-  }
+#[cfg(test)]
+mod tests {
+  use super::to_ppm::ToPPM;
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
 
   #[test]
   fn creating_a_canvas() {
@@ -304,4 +182,26 @@ mod tests {
 
     assert_eq!(actual_result, expected_result);
   }
+
+  #[test]
+  fn sampling_a_canvas_bilinear() {
+    let mut c = Canvas::new(2, 2);
+    c.write_pixel(0, 0, Color::black());
+    c.write_pixel(1, 0, Color::white());
+    c.write_pixel(0, 1, Color::red());
+    c.write_pixel(1, 1, Color::red());
+
+    assert_fuzzy_eq!(c.sample_bilinear(0.5, 0.5), Color::black());
+    assert_fuzzy_eq!(c.sample_bilinear(1.0, 0.5), Color::new(0.5, 0.5, 0.5));
+    assert_fuzzy_eq!(c.sample_bilinear(1.0, 1.0), Color::new(0.75, 0.25, 0.25));
+    // Positions outside of the canvas are clamped to the edge
+    assert_fuzzy_eq!(c.sample_bilinear(-4.0, 7.0), Color::red());
+  }
+
+  #[test]
+  fn sampling_an_empty_canvas_is_black() {
+    let c = Canvas::new(0, 3);
+
+    assert_fuzzy_eq!(c.sample_bilinear(0.5, 0.5), Color::black());
+  }
 }
@@ -0,0 +1,100 @@
+use super::{Canvas, Color};
+use crate::F;
+
+impl Canvas {
+  /// Copies the canvas to the given width and height, each pixel averaging
+  /// the pixels it covers, or the nearest pixel when enlarging.
+  pub fn resized(&self, width: usize, height: usize) -> Canvas {
+    let mut result = Canvas::new(width, height);
+    let scale_x = self.width as F / width as F;
+    let scale_y = self.height as F / height as F;
+    let span = |index: usize, scale: F, size: usize| {
+      let start = ((index as F * scale) as usize).min(size - 1);
+      let end = (((index + 1) as F * scale).ceil() as usize).clamp(start + 1, size);
+      start..end
+    };
+
+    for y in 0..height {
+      let rows = span(y, scale_y, self.height);
+      for x in 0..width {
+        let columns = span(x, scale_x, self.width);
+        let mut sum = Color::black();
+        for source_y in rows.clone() {
+          for source_x in columns.clone() {
+            sum = sum + self.pixel_at(source_x, source_y);
+          }
+        }
+        let count = (rows.len() * columns.len()) as F;
+        result.write_pixel(x, y, sum * (1.0 / count));
+      }
+    }
+
+    result
+  }
+
+  /// Copies the other canvas onto this one with its top left corner at the
+  /// given position, cutting off everything outside of this canvas
+  pub fn draw(&mut self, other: &Canvas, left: isize, top: isize) {
+    for y in 0..other.height {
+      for x in 0..other.width {
+        let (target_x, target_y) = (left + x as isize, top + y as isize);
+        if target_x >= 0
+          && target_y >= 0
+          && (target_x as usize) < self.width
+          && (target_y as usize) < self.height
+        {
+          self.write_pixel(target_x as usize, target_y as usize, other.pixel_at(x, y));
+        }
+      }
+    }
+  }
+
+  /// Fills the whole canvas with the color
+  pub fn fill(&mut self, color: Color) {
+    for pixel in self.pixels.iter_mut() {
+      *pixel = color;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn shrinking_averages_the_covered_pixels() {
+    let mut canvas = Canvas::new(4, 2);
+    canvas.write_pixel(0, 0, Color::white());
+    canvas.write_pixel(3, 1, Color::new(1.0, 0.0, 0.0));
+
+    let result = canvas.resized(2, 1);
+
+    assert_fuzzy_eq!(result.pixel_at(0, 0), Color::new(0.25, 0.25, 0.25));
+    assert_fuzzy_eq!(result.pixel_at(1, 0), Color::new(0.25, 0.0, 0.0));
+  }
+
+  #[test]
+  fn enlarging_repeats_the_nearest_pixel() {
+    let mut canvas = Canvas::new(2, 1);
+    canvas.write_pixel(1, 0, Color::white());
+
+    let result = canvas.resized(4, 2);
+
+    assert_fuzzy_eq!(result.pixel_at(1, 1), Color::black());
+    assert_fuzzy_eq!(result.pixel_at(2, 0), Color::white());
+  }
+
+  #[test]
+  fn drawing_cuts_off_what_lies_outside() {
+    let mut canvas = Canvas::new(3, 3);
+    let mut other = Canvas::new(2, 2);
+    other.fill(Color::white());
+
+    canvas.draw(&other, 2, -1);
+
+    assert_fuzzy_eq!(canvas.pixel_at(2, 0), Color::white());
+    assert_fuzzy_eq!(canvas.pixel_at(2, 1), Color::black());
+    assert_fuzzy_eq!(canvas.pixel_at(1, 0), Color::black());
+  }
+}
@@ -0,0 +1,190 @@
+use super::{Canvas, Color};
+use crate::F;
+
+/// How pixels outside of the canvas are treated by convolutions.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum EdgeMode {
+  /// Repeat the nearest edge pixel
+  #[default]
+  Clamp,
+  /// Continue on the opposite side of the canvas
+  Wrap,
+  /// Reflect the canvas at its edges
+  Mirror,
+  /// Treat everything outside as black
+  Black,
+}
+
+impl EdgeMode {
+  /// Maps a possibly out of bounds coordinate onto the canvas.
+  ///
+  /// Returns `None` if the coordinate does not map onto any pixel.
+  fn resolve(&self, coordinate: isize, size: usize) -> Option<usize> {
+    let size = size as isize;
+    if (0..size).contains(&coordinate) {
+      return Some(coordinate as usize);
+    }
+
+    match self {
+      EdgeMode::Clamp => Some(coordinate.clamp(0, size - 1) as usize),
+      EdgeMode::Wrap => Some(coordinate.rem_euclid(size) as usize),
+      EdgeMode::Mirror => {
+        let period = 2 * size;
+        let position = coordinate.rem_euclid(period);
+        if position < size {
+          Some(position as usize)
+        } else {
+          Some((period - 1 - position) as usize)
+        }
+      }
+      EdgeMode::Black => None,
+    }
+  }
+}
+
+/// Normalized gaussian kernel covering three standard deviations.
+pub fn gaussian_kernel(sigma: F) -> Vec<F> {
+  if sigma <= 0.0 {
+    return vec![1.0];
+  }
+
+  let radius = (sigma * 3.0).ceil() as isize;
+  let kernel: Vec<F> = (-radius..=radius)
+    .map(|offset| (-((offset * offset) as F) / (2.0 * sigma * sigma)).exp())
+    .collect();
+  let sum: F = kernel.iter().sum();
+  kernel.into_iter().map(|weight| weight / sum).collect()
+}
+
+/// Normalized box kernel with `2 * radius + 1` taps.
+pub fn box_kernel(radius: usize) -> Vec<F> {
+  let size = 2 * radius + 1;
+  vec![1.0 / size as F; size]
+}
+
+impl Canvas {
+  /// Convolves the canvas horizontally with the given kernel.
+  ///
+  /// The kernel needs an uneven number of taps, its center being the pixel
+  /// itself.
+  pub fn convolve_horizontal(&self, kernel: &[F], edge_mode: EdgeMode) -> Canvas {
+    self.convolve_1d(kernel, edge_mode, true)
+  }
+
+  /// Convolves the canvas vertically with the given kernel.
+  pub fn convolve_vertical(&self, kernel: &[F], edge_mode: EdgeMode) -> Canvas {
+    self.convolve_1d(kernel, edge_mode, false)
+  }
+
+  /// Convolves the canvas with the two dimensional kernel, which is the outer
+  /// product of the given one with itself.
+  pub fn convolve_separable(&self, kernel: &[F], edge_mode: EdgeMode) -> Canvas {
+    self
+      .convolve_horizontal(kernel, edge_mode)
+      .convolve_vertical(kernel, edge_mode)
+  }
+
+  pub fn box_blur(&self, radius: usize, edge_mode: EdgeMode) -> Canvas {
+    self.convolve_separable(&box_kernel(radius), edge_mode)
+  }
+
+  pub fn gaussian_blur(&self, sigma: F, edge_mode: EdgeMode) -> Canvas {
+    self.convolve_separable(&gaussian_kernel(sigma), edge_mode)
+  }
+
+  fn convolve_1d(&self, kernel: &[F], edge_mode: EdgeMode, horizontal: bool) -> Canvas {
+    let radius = (kernel.len() / 2) as isize;
+    let mut result = Canvas::new(self.width, self.height);
+
+    for y in 0..self.height {
+      for x in 0..self.width {
+        let color = kernel
+          .iter()
+          .enumerate()
+          .fold(Color::black(), |sum, (index, weight)| {
+            let offset = index as isize - radius;
+            let sample = if horizontal {
+              edge_mode
+                .resolve(x as isize + offset, self.width)
+                .map(|sample_x| self.pixel_at(sample_x, y))
+            } else {
+              edge_mode
+                .resolve(y as isize + offset, self.height)
+                .map(|sample_y| self.pixel_at(x, sample_y))
+            };
+            match sample {
+              Some(color) => sum + color * *weight,
+              None => sum,
+            }
+          });
+        result.write_pixel(x, y, color);
+      }
+    }
+
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  fn create_uniform_canvas(color: Color) -> Canvas {
+    let mut canvas = Canvas::new(4, 3);
+    for y in 0..3 {
+      for x in 0..4 {
+        canvas.write_pixel(x, y, color);
+      }
+    }
+    canvas
+  }
+
+  #[test]
+  fn kernels_are_normalized() {
+    assert_fuzzy_eq!(gaussian_kernel(1.5).iter().sum::<F>(), 1.0);
+    assert_eq!(gaussian_kernel(1.5).len(), 11);
+    assert_fuzzy_eq!(box_kernel(2), vec![0.2; 5]);
+  }
+
+  #[test]
+  fn edge_modes_resolve_coordinates_outside_of_the_canvas() {
+    assert_eq!(EdgeMode::Clamp.resolve(-2, 4), Some(0));
+    assert_eq!(EdgeMode::Clamp.resolve(5, 4), Some(3));
+    assert_eq!(EdgeMode::Wrap.resolve(-1, 4), Some(3));
+    assert_eq!(EdgeMode::Wrap.resolve(5, 4), Some(1));
+    assert_eq!(EdgeMode::Mirror.resolve(-1, 4), Some(0));
+    assert_eq!(EdgeMode::Mirror.resolve(5, 4), Some(2));
+    assert_eq!(EdgeMode::Black.resolve(-1, 4), None);
+    assert_eq!(EdgeMode::Black.resolve(2, 4), Some(2));
+  }
+
+  #[test]
+  fn blurring_keeps_uniform_canvases_unless_edges_are_black() {
+    let color = Color::new(0.2, 0.4, 0.6);
+    let canvas = create_uniform_canvas(color);
+
+    for edge_mode in [EdgeMode::Clamp, EdgeMode::Wrap, EdgeMode::Mirror] {
+      let gaussian = canvas.gaussian_blur(2.0, edge_mode);
+      let boxed = canvas.box_blur(1, edge_mode);
+      assert_fuzzy_eq!(gaussian.pixel_at(0, 0), color);
+      assert_fuzzy_eq!(boxed.pixel_at(3, 2), color);
+    }
+
+    let darkened = canvas.box_blur(1, EdgeMode::Black);
+    assert_fuzzy_eq!(darkened.pixel_at(0, 0), color * (4.0 / 9.0));
+    assert_fuzzy_eq!(darkened.pixel_at(1, 1), color);
+  }
+
+  #[test]
+  fn box_blur_averages_the_neighbourhood() {
+    let mut canvas = Canvas::new(3, 1);
+    canvas.write_pixel(1, 0, Color::new(3.0, 3.0, 3.0));
+
+    let result = canvas.convolve_horizontal(&box_kernel(1), EdgeMode::Black);
+
+    assert_fuzzy_eq!(result.pixel_at(0, 0), Color::white());
+    assert_fuzzy_eq!(result.pixel_at(1, 0), Color::white());
+    assert_fuzzy_eq!(result.pixel_at(2, 0), Color::white());
+  }
+}
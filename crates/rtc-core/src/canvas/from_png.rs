@@ -0,0 +1,110 @@
+use super::{Canvas, Color};
+use crate::F;
+
+impl Canvas {
+  /// Decodes a PNG image. Alpha is ignored, 16 bit channels are reduced to
+  /// their high byte.
+  pub fn from_png(data: &[u8]) -> Result<Canvas, png::DecodingError> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let (info, mut reader) = decoder.read_info()?;
+    let mut buffer = vec![0; info.buffer_size()];
+    reader.next_frame(&mut buffer)?;
+
+    let bytes_per_channel = match info.bit_depth {
+      png::BitDepth::Sixteen => 2,
+      _ => 1,
+    };
+    let channels = match info.color_type {
+      png::ColorType::Grayscale => 1,
+      png::ColorType::GrayscaleAlpha => 2,
+      png::ColorType::RGB => 3,
+      _ => 4,
+    };
+
+    let width = info.width as usize;
+    let height = info.height as usize;
+    let channel = |x: usize, y: usize, c: usize| {
+      let index = (y * width + x) * channels + c;
+      buffer[index * bytes_per_channel] as F / 255.0
+    };
+
+    let mut canvas = Canvas::new(width, height);
+    for y in 0..height {
+      for x in 0..width {
+        let color = if channels < 3 {
+          let gray = channel(x, y, 0);
+          Color::new(gray, gray, gray)
+        } else {
+          Color::new(channel(x, y, 0), channel(x, y, 1), channel(x, y, 2))
+        };
+        canvas.write_pixel(x, y, color);
+      }
+    }
+
+    Ok(canvas)
+  }
+}
+
+/// Keys and values of the text chunks of a PNG image, in their order. Text
+/// after broken or truncated chunks is ignored.
+pub fn png_text(data: &[u8]) -> Vec<(String, String)> {
+  let latin1 = |bytes: &[u8]| bytes.iter().map(|&byte| byte as char).collect::<String>();
+  let mut text = vec![];
+  // Chunks follow the 8 byte signature, each with length, type, data and CRC
+  let mut offset = 8;
+  while offset + 8 <= data.len() {
+    let mut length = [0; 4];
+    length.copy_from_slice(&data[offset..offset + 4]);
+    let length = u32::from_be_bytes(length) as usize;
+    let start = offset + 8;
+    if start + length > data.len() {
+      break;
+    }
+    if &data[offset + 4..start] == b"tEXt" {
+      let chunk = &data[start..start + length];
+      if let Some(separator) = chunk.iter().position(|&byte| byte == 0) {
+        text.push((latin1(&chunk[..separator]), latin1(&chunk[separator + 1..])));
+      }
+    }
+    offset = start + length + 4;
+  }
+  text
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::to_png::ToPNG;
+  use crate::fuzzy_eq::*;
+
+  #[test]
+  fn a_canvas_survives_a_png_round_trip() {
+    let mut canvas = Canvas::new(2, 1);
+    canvas.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0));
+    canvas.write_pixel(1, 0, Color::new(0.2, 0.4, 0.6));
+
+    let decoded = Canvas::from_png(&canvas.to_png()).unwrap();
+
+    assert_eq!(decoded.width, 2);
+    assert_eq!(decoded.height, 1);
+    assert_fuzzy_eq!(decoded.pixel_at(0, 0), Color::new(1.0, 0.0, 0.0));
+    assert_fuzzy_eq!(decoded.pixel_at(1, 0), Color::new(0.2, 0.4, 0.6));
+  }
+
+  #[test]
+  fn text_chunks_survive_a_png_round_trip() {
+    let canvas = Canvas::new(2, 1);
+    let png = canvas.to_png_with_text(&[("Fingerprint", "00abcdef01234567"), ("Camera", "main")]);
+
+    assert_eq!(
+      png_text(&png),
+      vec![
+        ("Fingerprint".to_string(), "00abcdef01234567".to_string()),
+        ("Camera".to_string(), "main".to_string())
+      ]
+    );
+    assert!(png_text(&canvas.to_png()).is_empty());
+    assert_eq!(Canvas::from_png(&png).unwrap().width, 2);
+  }
+}
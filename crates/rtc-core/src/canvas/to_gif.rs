@@ -0,0 +1,253 @@
+use super::Canvas;
+use std::collections::HashMap;
+use std::vec::Vec;
+
+type Rgb = [u8; 3];
+
+/// Sequence of equally sized frames, encoded as looping animated GIF.
+///
+/// All frames share one palette of at most 256 colors, so colors do not
+/// flicker between frames.
+pub struct AnimatedGif {
+  width: usize,
+  height: usize,
+  /// Delay between frames in hundredths of a second
+  delay: u16,
+  frames: Vec<Vec<Rgb>>,
+}
+
+impl AnimatedGif {
+  pub fn new(width: usize, height: usize, fps: usize) -> Self {
+    Self {
+      width,
+      height,
+      delay: (100.0 / fps.max(1) as f64).round() as u16,
+      frames: vec![],
+    }
+  }
+
+  pub fn add_frame(&mut self, canvas: &Canvas) {
+    assert!(
+      canvas.width == self.width && canvas.height == self.height,
+      "Frames of an animated GIF need to be {}x{}, but got {}x{}",
+      self.width,
+      self.height,
+      canvas.width,
+      canvas.height
+    );
+    let rgb = canvas
+      .pixels
+      .iter()
+      .map(|pixel| {
+        let color = pixel.clamp(0.0, 1.0);
+        [
+          (color.red * 255.0).round() as u8,
+          (color.green * 255.0).round() as u8,
+          (color.blue * 255.0).round() as u8,
+        ]
+      })
+      .collect();
+    self.frames.push(rgb);
+  }
+
+  pub fn len(&self) -> usize {
+    self.frames.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.frames.is_empty()
+  }
+
+  pub fn to_gif(&self) -> Vec<u8> {
+    let palette = Palette::median_cut(self.frames.iter().flatten().copied(), 256);
+    let global_palette: Vec<u8> = palette.colors.iter().flatten().copied().collect();
+
+    let mut data = Vec::new();
+    let mut encoder = gif::Encoder::new(
+      &mut data,
+      self.width as u16,
+      self.height as u16,
+      &global_palette,
+    )
+    .unwrap();
+    encoder.set_repeat(gif::Repeat::Infinite).unwrap();
+
+    let mut indices = HashMap::new();
+    for rgb in self.frames.iter() {
+      let pixels: Vec<u8> = rgb
+        .iter()
+        .map(|color| {
+          *indices
+            .entry(*color)
+            .or_insert_with(|| palette.nearest(*color))
+        })
+        .collect();
+      let mut frame =
+        gif::Frame::from_indexed_pixels(self.width as u16, self.height as u16, &pixels, None);
+      frame.delay = self.delay;
+      encoder.write_frame(&frame).unwrap();
+    }
+    drop(encoder);
+
+    data
+  }
+}
+
+/// Colors an image is reduced to
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+  pub colors: Vec<Rgb>,
+}
+
+impl Palette {
+  /// Splits the colors into up to `size` boxes, always cutting the box with
+  /// the widest channel at the median of that channel. Each box becomes its
+  /// average color, weighted by how often the colors occur.
+  pub fn median_cut<I>(pixels: I, size: usize) -> Self
+  where
+    I: IntoIterator<Item = Rgb>,
+  {
+    let mut counts: HashMap<Rgb, usize> = HashMap::new();
+    for pixel in pixels {
+      *counts.entry(pixel).or_insert(0) += 1;
+    }
+    let mut colors: Vec<(Rgb, usize)> = counts.into_iter().collect();
+    // The hash map has no stable order
+    colors.sort_unstable();
+
+    let mut boxes = vec![colors];
+    while boxes.len() < size {
+      let widest = boxes
+        .iter()
+        .enumerate()
+        .filter(|(_, colors)| colors.len() > 1)
+        .map(|(index, colors)| (index, widest_channel(colors)))
+        .max_by_key(|(_, (_, range))| *range);
+      let (index, channel) = match widest {
+        Some((index, (channel, _))) => (index, channel),
+        None => break,
+      };
+
+      let mut colors = boxes.swap_remove(index);
+      colors.sort_unstable_by_key(|(color, _)| color[channel]);
+      let total: usize = colors.iter().map(|(_, count)| count).sum();
+      let mut seen = 0;
+      let median = colors
+        .iter()
+        .position(|(_, count)| {
+          seen += count;
+          seen * 2 >= total
+        })
+        .unwrap_or(0);
+      // Both halves keep at least one color
+      let split = (median + 1).min(colors.len() - 1);
+      let upper = colors.split_off(split);
+      boxes.push(colors);
+      boxes.push(upper);
+    }
+
+    Self {
+      colors: boxes
+        .iter()
+        .filter(|colors| !colors.is_empty())
+        .map(|colors| average(colors))
+        .collect(),
+    }
+  }
+
+  /// Index of the closest palette color
+  pub fn nearest(&self, color: Rgb) -> u8 {
+    let distance = |other: &Rgb| -> i32 {
+      (0..3)
+        .map(|channel| (color[channel] as i32 - other[channel] as i32).pow(2))
+        .sum()
+    };
+    self
+      .colors
+      .iter()
+      .enumerate()
+      .min_by_key(|(_, other)| distance(other))
+      .map(|(index, _)| index as u8)
+      .unwrap_or(0)
+  }
+}
+
+/// Channel with the largest spread of values and that spread
+fn widest_channel(colors: &[(Rgb, usize)]) -> (usize, u8) {
+  (0..3)
+    .map(|channel| {
+      let values = colors.iter().map(|(color, _)| color[channel]);
+      let range = values.clone().max().unwrap_or(0) - values.min().unwrap_or(0);
+      (channel, range)
+    })
+    .max_by_key(|(_, range)| *range)
+    .unwrap()
+}
+
+fn average(colors: &[(Rgb, usize)]) -> Rgb {
+  let total: usize = colors.iter().map(|(_, count)| count).sum();
+  let mut average = [0; 3];
+  for (channel, value) in average.iter_mut().enumerate() {
+    let sum: usize = colors
+      .iter()
+      .map(|(color, count)| color[channel] as usize * count)
+      .sum();
+    *value = ((sum + total / 2) / total) as u8;
+  }
+  average
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::Color;
+
+  #[test]
+  fn a_palette_keeps_few_colors_exactly() {
+    let pixels = vec![[255, 0, 0], [0, 255, 0], [0, 0, 255], [255, 0, 0]];
+
+    let palette = Palette::median_cut(pixels, 256);
+
+    assert_eq!(palette.colors.len(), 3);
+    assert_eq!(
+      palette.colors[palette.nearest([255, 0, 0]) as usize],
+      [255, 0, 0]
+    );
+    assert_eq!(
+      palette.colors[palette.nearest([0, 0, 250]) as usize],
+      [0, 0, 255]
+    );
+  }
+
+  #[test]
+  fn a_palette_reduces_many_colors_to_its_size() {
+    let pixels = (0..=255).map(|value| [value, value, value]);
+
+    let palette = Palette::median_cut(pixels, 4);
+
+    assert_eq!(palette.colors.len(), 4);
+    assert_eq!(palette.nearest([0, 0, 0]), palette.nearest([10, 10, 10]));
+    assert_ne!(palette.nearest([0, 0, 0]), palette.nearest([255, 255, 255]));
+  }
+
+  #[test]
+  fn frames_are_encoded_as_gif() {
+    let mut animation = AnimatedGif::new(2, 2, 25);
+    let mut canvas = Canvas::new(2, 2);
+    animation.add_frame(&canvas);
+    canvas.write_pixel(1, 1, Color::new(1.0, 0.0, 0.0));
+    animation.add_frame(&canvas);
+
+    let gif = animation.to_gif();
+
+    assert_eq!(animation.len(), 2);
+    assert_eq!(&gif[0..6], b"GIF89a");
+    assert_eq!(gif.last(), Some(&0x3b));
+  }
+
+  #[test]
+  #[should_panic]
+  fn frames_need_the_size_of_the_animation() {
+    AnimatedGif::new(2, 2, 25).add_frame(&Canvas::new(3, 2));
+  }
+}
@@ -2,7 +2,13 @@ use crate::canvas::to_rgba32::ToRGBA32;
 use crate::canvas::Sized;
 
 pub trait ToPNG {
-  fn to_png(&self) -> Vec<u8>;
+  fn to_png(&self) -> Vec<u8> {
+    self.to_png_with_text(&[])
+  }
+
+  /// PNG image carrying the given keys and values as text chunks, e.g. what
+  /// the image was rendered from. Both need to be Latin-1.
+  fn to_png_with_text(&self, text: &[(&str, &str)]) -> Vec<u8>;
 }
 
 impl<T> ToPNG for T
@@ -10,12 +16,16 @@ where
   T: ToRGBA32,
   T: Sized,
 {
-  fn to_png(&self) -> Vec<u8> {
+  fn to_png_with_text(&self, text: &[(&str, &str)]) -> Vec<u8> {
     let mut data = Vec::new();
     let mut encoder = png::Encoder::new(&mut data, self.width() as u32, self.height() as u32);
     encoder.set_color(png::ColorType::RGBA);
     encoder.set_depth(png::BitDepth::Eight);
     let mut writer = encoder.write_header().unwrap();
+    for (key, value) in text.iter() {
+      let chunk = [key.as_bytes(), &[0], value.as_bytes()].concat();
+      writer.write_chunk(*b"tEXt", &chunk).unwrap();
+    }
     writer.write_image_data(&self.to_rgba32()).unwrap();
     drop(writer);
 
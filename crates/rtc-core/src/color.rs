@@ -0,0 +1,180 @@
+use crate::fuzzy_eq::*;
+use crate::F;
+use core::ops::{Add, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+  pub red: F,
+  pub green: F,
+  pub blue: F,
+}
+
+impl Color {
+  pub fn new(red: F, green: F, blue: F) -> Self {
+    Color { red, green, blue }
+  }
+
+  pub fn black() -> Self {
+    Color::new(0.0, 0.0, 0.0)
+  }
+
+  pub fn white() -> Self {
+    Color::new(1.0, 1.0, 1.0)
+  }
+
+  pub fn red() -> Self {
+    Color::new(1.0, 0.0, 0.0)
+  }
+
+  pub fn green() -> Self {
+    Color::new(0.0, 1.0, 0.0)
+  }
+
+  pub fn blue() -> Self {
+    Color::new(0.0, 0.0, 1.0)
+  }
+
+  pub fn yellow() -> Self {
+    Color::new(1.0, 1.0, 0.0)
+  }
+
+  /// Relative luminance using the Rec. 709 coefficients
+  pub fn luminance(&self) -> F {
+    0.2126 * self.red + 0.7152 * self.green + 0.0722 * self.blue
+  }
+
+  pub fn clamp(&self, lower_bound: F, upper_bound: F) -> Color {
+    Color::new(
+      self.red.min(upper_bound).max(lower_bound),
+      self.green.min(upper_bound).max(lower_bound),
+      self.blue.min(upper_bound).max(lower_bound),
+    )
+  }
+}
+
+impl Add for Color {
+  type Output = Color;
+
+  fn add(self, other: Color) -> Self::Output {
+    Color::new(
+      self.red + other.red,
+      self.green + other.green,
+      self.blue + other.blue,
+    )
+  }
+}
+
+impl Sub for Color {
+  type Output = Color;
+
+  fn sub(self, other: Color) -> Self::Output {
+    Color::new(
+      self.red - other.red,
+      self.green - other.green,
+      self.blue - other.blue,
+    )
+  }
+}
+
+impl Mul<F> for Color {
+  type Output = Color;
+
+  fn mul(self, other: F) -> Self::Output {
+    Color::new(self.red * other, self.green * other, self.blue * other)
+  }
+}
+
+impl Mul<Color> for Color {
+  type Output = Color;
+
+  fn mul(self, other: Color) -> Self::Output {
+    Color::new(
+      self.red * other.red,
+      self.green * other.green,
+      self.blue * other.blue,
+    )
+  }
+}
+
+impl FuzzyEq<Color> for Color {
+  fn fuzzy_eq(&self, other: Self) -> bool {
+    self.red.fuzzy_eq(other.red)
+      && self.green.fuzzy_eq(other.green)
+      && self.blue.fuzzy_eq(other.blue)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn colors_are_red_green_blue_tuples() {
+    let c = Color::new(-0.5, 0.4, 1.7);
+
+    assert_fuzzy_eq!(c.red, -0.5);
+    assert_fuzzy_eq!(c.green, 0.4);
+    assert_fuzzy_eq!(c.blue, 1.7);
+  }
+
+  #[test]
+  fn adding_colors() {
+    let c1 = Color::new(0.9, 0.6, 0.75);
+    let c2 = Color::new(0.7, 0.1, 0.25);
+
+    let expected_result = Color::new(1.6, 0.7, 1.0);
+    let actual_result = c1 + c2;
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn substracting_colors() {
+    let c1 = Color::new(0.9, 0.6, 0.75);
+    let c2 = Color::new(0.7, 0.1, 0.25);
+
+    let expected_result = Color::new(0.2, 0.5, 0.5);
+    let actual_result = c1 - c2;
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn multiplying_a_color_by_a_scalar() {
+    let c = Color::new(0.2, 0.3, 0.4);
+    let multiplier = 2.0;
+
+    let expected_result = Color::new(0.4, 0.6, 0.8);
+    let actual_result = c * multiplier;
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn multiplying_colors() {
+    let c1 = Color::new(1.0, 0.2, 0.4);
+    let c2 = Color::new(0.9, 1.0, 0.1);
+
+    let expected_result = Color::new(0.9, 0.2, 0.04);
+    let actual_result = c1 * c2;
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn clamping_colors() {
+    let c = Color::new(2.3, -6.7, 0.8);
+
+    let expected_result = Color::new(1.0, 0.0, 0.8);
+    let actual_result = c.clamp(0.0, 1.0);
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn luminance_of_colors() {
+    assert_fuzzy_eq!(Color::white().luminance(), 1.0);
+    assert_fuzzy_eq!(Color::black().luminance(), 0.0);
+    assert_fuzzy_eq!(Color::green().luminance(), 0.7152);
+  }
+}
@@ -0,0 +1,92 @@
+use crate::intersections::Intersection;
+use crate::tuple::Tuple;
+use crate::F;
+
+#[derive(Debug, Clone)]
+pub struct ComputedIntersection<'a> {
+  pub intersection: &'a Intersection,
+  pub point: Tuple,
+  pub over_point: Tuple,
+  /// Just below the surface, where refracted rays start
+  pub under_point: Tuple,
+  /// Normal used for shading, interpolated on smooth triangles and
+  /// perturbed by bumps
+  pub normalv: Tuple,
+  /// Normal of the actual surface, the over and under points are offset
+  /// along it so no rays start on the wrong side of the surface
+  pub geometric_normalv: Tuple,
+  pub eyev: Tuple,
+  pub reflectv: Tuple,
+  pub inside: bool,
+  /// Refractive index of the material the ray leaves
+  pub n1: F,
+  /// Refractive index of the material the ray enters
+  pub n2: F,
+}
+
+impl<'a> ComputedIntersection<'a> {
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(
+    intersection: &'a Intersection,
+    point: Tuple,
+    over_point: Tuple,
+    under_point: Tuple,
+    normalv: Tuple,
+    geometric_normalv: Tuple,
+    eyev: Tuple,
+    reflectv: Tuple,
+    inside: bool,
+  ) -> Self {
+    ComputedIntersection {
+      intersection,
+      point,
+      over_point,
+      under_point,
+      normalv,
+      geometric_normalv,
+      eyev,
+      reflectv,
+      inside,
+      n1: 1.0,
+      n2: 1.0,
+    }
+  }
+
+  pub fn with_refractive_indices(mut self, n1: F, n2: F) -> Self {
+    self.n1 = n1;
+    self.n2 = n2;
+    self
+  }
+
+  /// Direction of the ray refracted according to Snell's law, when passing
+  /// from the material with index `n1` into the one with index `n2`.
+  ///
+  /// Nothing is refracted on total internal reflection.
+  pub fn refracted_direction(&self) -> Option<Tuple> {
+    let n_ratio = self.n1 / self.n2;
+    let cos_i = self.eyev.dot(self.normalv);
+    let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+    if sin2_t > 1.0 {
+      return None;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(self.normalv * (n_ratio * cos_i - cos_t) - self.eyev * n_ratio)
+  }
+
+  /// Schlick's approximation of the fraction of light being reflected
+  pub fn schlick(&self) -> F {
+    let (n1, n2) = (self.n1, self.n2);
+    let mut cos = self.eyev.dot(self.normalv);
+    if n1 > n2 {
+      let sin2_t = (n1 / n2).powi(2) * (1.0 - cos.powi(2));
+      if sin2_t > 1.0 {
+        return 1.0;
+      }
+      cos = (1.0 - sin2_t).sqrt();
+    }
+
+    let r0 = ((n1 - n2) / (n1 + n2)).powi(2);
+    r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+  }
+}
@@ -0,0 +1,347 @@
+use crate::body::*;
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::*;
+use crate::matrix::*;
+use crate::numerics::solve_quadratic;
+use crate::ray::*;
+use crate::tuple::*;
+use crate::{EPSILON, F};
+
+/// Double napped cone around the y axis with its apex at the origin.
+///
+/// The radius at any height equals the absolute y value. It is truncated at
+/// `minimum` and `maximum` (both exclusive), and infinite by default. Closed
+/// cones have caps at both ends.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cone {
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+  pub material: Material,
+  pub shadows: Shadows,
+  pub minimum: F,
+  pub maximum: F,
+  pub closed: bool,
+}
+
+impl Default for Cone {
+  fn default() -> Self {
+    Self {
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+      material: Default::default(),
+      shadows: Default::default(),
+      minimum: F::NEG_INFINITY,
+      maximum: F::INFINITY,
+      closed: false,
+    }
+  }
+}
+
+impl Cone {
+  pub fn new(material: Material, transform: Matrix<4>) -> Self {
+    Cone {
+      material,
+      transform,
+      inverse_transform: transform.inverse(),
+      ..Default::default()
+    }
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.material = material;
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.shadows = shadows;
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    self.transform = transform;
+    self.inverse_transform = inverse_transform;
+    self
+  }
+
+  pub fn with_minimum(mut self, minimum: F) -> Self {
+    self.minimum = minimum;
+    self
+  }
+
+  pub fn with_maximum(mut self, maximum: F) -> Self {
+    self.maximum = maximum;
+    self
+  }
+
+  pub fn with_closed(mut self, closed: bool) -> Self {
+    self.closed = closed;
+    self
+  }
+
+  /// Checks if the intersection at `t` is within the radius of a cap at the
+  /// given height
+  fn check_cap(ray: Ray, t: F, y: F) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+    x.powi(2) + z.powi(2) <= y.powi(2)
+  }
+
+  fn intersect_caps(&self, ray: Ray) -> Vec<(F, Body)> {
+    if !self.closed || ray.direction.y.abs() < EPSILON {
+      return vec![];
+    }
+
+    [self.minimum, self.maximum]
+      .iter()
+      .map(|cap| (*cap, (cap - ray.origin.y) / ray.direction.y))
+      .filter(|(cap, t)| Self::check_cap(ray, *t, *cap))
+      .map(|(_, t)| (t, Body::from(self.clone())))
+      .collect()
+  }
+}
+
+impl FuzzyEq<&Cone> for Cone {
+  fn fuzzy_eq(&self, other: &Cone) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+      && self.material.fuzzy_eq(other.material.clone())
+      && self.shadows == other.shadows
+      && self.minimum.fuzzy_eq(other.minimum)
+      && self.maximum.fuzzy_eq(other.maximum)
+      && self.closed == other.closed
+  }
+}
+
+impl Intersectable for Cone {
+  // Both nappes and both caps
+  fn max_intersections(&self) -> usize {
+    4
+  }
+
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    let mut xs = Vec::with_capacity(self.max_intersections());
+    let origin = object_space_ray.origin;
+    let direction = object_space_ray.direction;
+
+    let a = direction.x.powi(2) - direction.y.powi(2) + direction.z.powi(2);
+    let b =
+      2.0 * origin.x * direction.x - 2.0 * origin.y * direction.y + 2.0 * origin.z * direction.z;
+    let c = origin.x.powi(2) - origin.y.powi(2) + origin.z.powi(2);
+
+    // The direction is scaled by the transform, so its length is taken into
+    // account
+    let length = direction.dot(direction);
+    let mut ts = vec![];
+    if a.abs() < EPSILON * length {
+      // The ray is parallel to one of the halves, hitting the other one once
+      if b.abs() >= EPSILON * length.sqrt() {
+        ts.push(-c / (2.0 * b));
+      }
+    } else if let Some((t0, t1)) = solve_quadratic(a, b, c) {
+      ts.push(t0);
+      ts.push(t1);
+    }
+
+    for t in ts {
+      let y = origin.y + t * direction.y;
+      if self.minimum < y && y < self.maximum {
+        xs.push((t, Body::from(self.clone())));
+      }
+    }
+
+    xs.append(&mut self.intersect_caps(object_space_ray));
+    xs
+  }
+
+  fn normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    let distance = object_space_point.x.powi(2) + object_space_point.z.powi(2);
+
+    if distance < self.maximum.powi(2) && object_space_point.y >= self.maximum - EPSILON {
+      Tuple::vector(0.0, 1.0, 0.0)
+    } else if distance < self.minimum.powi(2) && object_space_point.y <= self.minimum + EPSILON {
+      Tuple::vector(0.0, -1.0, 0.0)
+    } else {
+      let mut y = distance.sqrt();
+      if object_space_point.y > 0.0 {
+        y = -y;
+      }
+      Tuple::vector(object_space_point.x, y, object_space_point.z)
+    }
+  }
+
+  fn material(&self) -> Material {
+    self.material.clone()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.shadows
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    // The radius of a cone equals the distance from its tip
+    let radius = self.minimum.abs().max(self.maximum.abs());
+    Bounds::new(
+      Tuple::point(-radius, self.minimum, -radius),
+      Tuple::point(radius, self.maximum, radius),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rays_hit_huge_cones_precisely_close_to_their_surface() {
+    let shape = Cone::default().with_transform(Matrix::scaling(1e6, 1e6, 1e6));
+    let r = Ray::new(
+      Tuple::point(-1e6 - 0.5, 1e6, 0.0),
+      Tuple::vector(1.0, 0.0, 0.0),
+    );
+
+    let xs = shape.intersect(r);
+
+    assert_eq!(xs.len(), 2);
+    assert_fuzzy_eq!(xs[0].t, 0.5);
+    assert_fuzzy_eq!(xs[1].t, 2e6 + 0.5);
+  }
+
+  #[test]
+  fn intersecting_a_cone_with_a_ray() {
+    let shape = Cone::default();
+    let examples = [
+      (
+        Tuple::point(0.0, 0.0, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+        5.0,
+        5.0,
+      ),
+      (
+        Tuple::point(0.0, 0.0, -5.0),
+        Tuple::vector(1.0, 1.0, 1.0),
+        8.66025,
+        8.66025,
+      ),
+      (
+        Tuple::point(1.0, 1.0, -5.0),
+        Tuple::vector(-0.5, -1.0, 1.0),
+        4.55006,
+        49.44994,
+      ),
+    ];
+
+    for (origin, direction, t0, t1) in examples {
+      let r = Ray::new(origin, direction.normalize());
+      let xs = shape.intersect_in_object_space(r);
+
+      assert_eq!(xs.len(), 2);
+      assert_fuzzy_eq!(xs[0].0, t0);
+      assert_fuzzy_eq!(xs[1].0, t1);
+    }
+  }
+
+  #[test]
+  fn intersecting_a_cone_with_a_ray_parallel_to_one_of_its_halves() {
+    let shape = Cone::default();
+    let r = Ray::new(
+      Tuple::point(0.0, 0.0, -1.0),
+      Tuple::vector(0.0, 1.0, 1.0).normalize(),
+    );
+    let xs = shape.intersect_in_object_space(r);
+
+    assert_eq!(xs.len(), 1);
+    assert_fuzzy_eq!(xs[0].0, 0.35355);
+  }
+
+  #[test]
+  fn intersecting_a_cones_end_caps() {
+    let shape = Cone::default()
+      .with_minimum(-0.5)
+      .with_maximum(0.5)
+      .with_closed(true);
+    let examples = [
+      (
+        Tuple::point(0.0, 0.0, -5.0),
+        Tuple::vector(0.0, 1.0, 0.0),
+        0,
+      ),
+      (
+        Tuple::point(0.0, 0.0, -0.25),
+        Tuple::vector(0.0, 1.0, 1.0),
+        2,
+      ),
+      (
+        Tuple::point(0.0, 0.0, -0.25),
+        Tuple::vector(0.0, 1.0, 0.0),
+        4,
+      ),
+    ];
+
+    for (origin, direction, count) in examples {
+      let r = Ray::new(origin, direction.normalize());
+      let xs = shape.intersect_in_object_space(r);
+
+      assert_eq!(xs.len(), count);
+    }
+  }
+
+  #[test]
+  fn computing_the_normal_vector_on_a_cone() {
+    let shape = Cone::default();
+    let examples = [
+      (Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 0.0)),
+      (
+        Tuple::point(1.0, 1.0, 1.0),
+        Tuple::vector(1.0, -(2.0 as F).sqrt(), 1.0),
+      ),
+      (Tuple::point(-1.0, -1.0, 0.0), Tuple::vector(-1.0, 1.0, 0.0)),
+    ];
+
+    for (point, normal) in examples {
+      assert_fuzzy_eq!(shape.normal_at_in_object_space(point), normal);
+    }
+  }
+
+  #[test]
+  fn normal_vector_on_a_cones_end_caps() {
+    let shape = Cone::default()
+      .with_minimum(-1.0)
+      .with_maximum(2.0)
+      .with_closed(true);
+
+    assert_fuzzy_eq!(
+      shape.normal_at_in_object_space(Tuple::point(0.5, 2.0, 0.0)),
+      Tuple::vector(0.0, 1.0, 0.0)
+    );
+    assert_fuzzy_eq!(
+      shape.normal_at_in_object_space(Tuple::point(0.0, -1.0, 0.5)),
+      Tuple::vector(0.0, -1.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn intersections_reference_the_cone() {
+    let shape = Cone::default().with_minimum(-1.0).with_maximum(0.0);
+    let r = Ray::new(Tuple::point(0.0, -0.5, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = shape.intersect(r);
+
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].body, Body::from(shape));
+  }
+}
@@ -0,0 +1,120 @@
+use crate::fuzzy_eq::FuzzyEq;
+use crate::ray::Ray;
+use crate::sampling::hemisphere_directions;
+use crate::tuple::Tuple;
+use crate::world::World;
+use crate::F;
+
+/// Cheap approximation of ambient occlusion.
+///
+/// Only a small number of short rays is cast into the hemisphere above a
+/// shaded point. The fraction of rays hitting a body within `distance`
+/// darkens the ambient term, which grounds bodies resting on each other
+/// without the cost of a full occlusion pass.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ContactShadows {
+  pub distance: F,
+  pub samples: usize,
+  pub strength: F,
+}
+
+impl Default for ContactShadows {
+  fn default() -> Self {
+    Self {
+      distance: 0.5,
+      samples: 8,
+      strength: 1.0,
+    }
+  }
+}
+
+impl ContactShadows {
+  pub fn new(distance: F, samples: usize) -> Self {
+    Self {
+      distance,
+      samples,
+      ..Default::default()
+    }
+  }
+
+  pub fn with_strength(mut self, strength: F) -> Self {
+    self.strength = strength;
+    self
+  }
+
+  /// Amount in the range [0, strength] the ambient light is reduced by.
+  ///
+  /// Moving bodies occlude at the given point in time, bodies not casting
+  /// shadows do not occlude at all.
+  pub fn occlusion(&self, world: &World, position: Tuple, normalv: Tuple, time: F) -> F {
+    if self.samples == 0 || self.distance.fuzzy_eq(0.0) {
+      return 0.0;
+    }
+
+    let occluded = hemisphere_directions(normalv, self.samples)
+      .into_iter()
+      .filter(|direction| {
+        world.is_blocked(
+          Ray::new(position, *direction).with_time(time),
+          self.distance,
+        )
+      })
+      .count();
+
+    occluded as F / self.samples as F * self.strength
+  }
+}
+
+impl FuzzyEq<ContactShadows> for ContactShadows {
+  fn fuzzy_eq(&self, other: ContactShadows) -> bool {
+    self.distance.fuzzy_eq(other.distance)
+      && self.samples == other.samples
+      && self.strength.fuzzy_eq(other.strength)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::body::Body;
+  use crate::plane::Plane;
+  use crate::test_scene::sphere_at;
+
+  #[test]
+  fn unobstructed_points_are_not_occluded() {
+    let world = World::new(vec![Body::from(Plane::default())], vec![]);
+    let contact_shadows = ContactShadows::new(1.0, 8);
+
+    let occlusion = contact_shadows.occlusion(
+      &world,
+      Tuple::point(0.0, 0.0001, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+      0.0,
+    );
+
+    assert_fuzzy_eq!(occlusion, 0.0);
+  }
+
+  #[test]
+  fn bodies_close_by_occlude_the_point() {
+    let world = World::new(
+      vec![
+        Body::from(Plane::default()),
+        Body::from(sphere_at(0.0, 1.1, 0.0)),
+      ],
+      vec![],
+    );
+    let position = Tuple::point(0.0, 0.0001, 0.0);
+    let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+    let near = ContactShadows::new(1.0, 8).occlusion(&world, position, normalv, 0.0);
+    let far = ContactShadows::new(0.01, 8).occlusion(&world, position, normalv, 0.0);
+    let weak = ContactShadows::new(1.0, 8)
+      .with_strength(0.5)
+      .occlusion(&world, position, normalv, 0.0);
+
+    assert!(near > 0.0);
+    assert_fuzzy_eq!(far, 0.0);
+    assert_fuzzy_eq!(weak, near * 0.5);
+  }
+}
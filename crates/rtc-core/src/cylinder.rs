@@ -0,0 +1,396 @@
+use crate::body::*;
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::*;
+use crate::matrix::*;
+use crate::numerics::solve_quadratic;
+use crate::ray::*;
+use crate::tuple::*;
+use crate::{EPSILON, F};
+
+/// Cylinder with a radius of 1 around the y axis.
+///
+/// It extends from `minimum` to `maximum` (both exclusive) along the y axis
+/// and is infinite by default. Closed cylinders have caps at both ends.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cylinder {
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+  pub material: Material,
+  pub shadows: Shadows,
+  pub minimum: F,
+  pub maximum: F,
+  pub closed: bool,
+}
+
+impl Default for Cylinder {
+  fn default() -> Self {
+    Self {
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+      material: Default::default(),
+      shadows: Default::default(),
+      minimum: F::NEG_INFINITY,
+      maximum: F::INFINITY,
+      closed: false,
+    }
+  }
+}
+
+impl Cylinder {
+  pub fn new(material: Material, transform: Matrix<4>) -> Self {
+    Cylinder {
+      material,
+      transform,
+      inverse_transform: transform.inverse(),
+      ..Default::default()
+    }
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.material = material;
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.shadows = shadows;
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    self.transform = transform;
+    self.inverse_transform = inverse_transform;
+    self
+  }
+
+  pub fn with_minimum(mut self, minimum: F) -> Self {
+    self.minimum = minimum;
+    self
+  }
+
+  pub fn with_maximum(mut self, maximum: F) -> Self {
+    self.maximum = maximum;
+    self
+  }
+
+  pub fn with_closed(mut self, closed: bool) -> Self {
+    self.closed = closed;
+    self
+  }
+
+  /// Checks if the intersection at `t` is within the radius of a cap
+  fn check_cap(ray: Ray, t: F) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+    x.powi(2) + z.powi(2) <= 1.0
+  }
+
+  fn intersect_caps(&self, ray: Ray) -> Vec<(F, Body)> {
+    if !self.closed || ray.direction.y.abs() < EPSILON {
+      return vec![];
+    }
+
+    [self.minimum, self.maximum]
+      .iter()
+      .map(|cap| (cap - ray.origin.y) / ray.direction.y)
+      .filter(|t| Self::check_cap(ray, *t))
+      .map(|t| (t, Body::from(self.clone())))
+      .collect()
+  }
+}
+
+impl FuzzyEq<&Cylinder> for Cylinder {
+  fn fuzzy_eq(&self, other: &Cylinder) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+      && self.material.fuzzy_eq(other.material.clone())
+      && self.shadows == other.shadows
+      && self.minimum.fuzzy_eq(other.minimum)
+      && self.maximum.fuzzy_eq(other.maximum)
+      && self.closed == other.closed
+  }
+}
+
+impl Intersectable for Cylinder {
+  // Both walls and both caps, open ends let a ray pass through all of them
+  fn max_intersections(&self) -> usize {
+    4
+  }
+
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    let mut xs = Vec::with_capacity(self.max_intersections());
+    let a = object_space_ray.direction.x.powi(2) + object_space_ray.direction.z.powi(2);
+
+    // Rays parallel to the y axis can only hit the caps. The direction is
+    // scaled by the transform, so its length is taken into account.
+    let length = object_space_ray.direction.dot(object_space_ray.direction);
+    if a.abs() >= EPSILON * length {
+      let b = 2.0 * object_space_ray.origin.x * object_space_ray.direction.x
+        + 2.0 * object_space_ray.origin.z * object_space_ray.direction.z;
+      let c = object_space_ray.origin.x.powi(2) + object_space_ray.origin.z.powi(2) - 1.0;
+      let (t0, t1) = match solve_quadratic(a, b, c) {
+        Some(roots) => roots,
+        None => return vec![],
+      };
+
+      for t in [t0, t1] {
+        let y = object_space_ray.origin.y + t * object_space_ray.direction.y;
+        if self.minimum < y && y < self.maximum {
+          xs.push((t, Body::from(self.clone())));
+        }
+      }
+    }
+
+    xs.append(&mut self.intersect_caps(object_space_ray));
+    xs
+  }
+
+  fn normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    let distance = object_space_point.x.powi(2) + object_space_point.z.powi(2);
+
+    if distance < 1.0 && object_space_point.y >= self.maximum - EPSILON {
+      Tuple::vector(0.0, 1.0, 0.0)
+    } else if distance < 1.0 && object_space_point.y <= self.minimum + EPSILON {
+      Tuple::vector(0.0, -1.0, 0.0)
+    } else {
+      Tuple::vector(object_space_point.x, 0.0, object_space_point.z)
+    }
+  }
+
+  fn material(&self) -> Material {
+    self.material.clone()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.shadows
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    Bounds::new(
+      Tuple::point(-1.0, self.minimum, -1.0),
+      Tuple::point(1.0, self.maximum, 1.0),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rays_hit_huge_cylinders_precisely_close_to_their_surface() {
+    let cyl = Cylinder::default().with_transform(Matrix::scaling(1e7, 1.0, 1e7));
+    let r = Ray::new(
+      Tuple::point(-1e7 - 0.5, 0.0, 0.0),
+      Tuple::vector(1.0, 0.0, 0.0),
+    );
+
+    let xs = cyl.intersect(r);
+
+    assert_eq!(xs.len(), 2);
+    assert_fuzzy_eq!(xs[0].t, 0.5);
+    assert_fuzzy_eq!(xs[1].t, 2e7 + 0.5);
+  }
+
+  #[test]
+  fn a_ray_misses_a_cylinder() {
+    let cyl = Cylinder::default();
+    let examples = [
+      (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+      (Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+      (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(1.0, 1.0, 1.0)),
+    ];
+
+    for (origin, direction) in examples {
+      let r = Ray::new(origin, direction.normalize());
+      let xs = cyl.intersect_in_object_space(r);
+
+      assert_eq!(xs.len(), 0);
+    }
+  }
+
+  #[test]
+  fn a_ray_strikes_a_cylinder() {
+    let cyl = Cylinder::default();
+    let examples = [
+      (
+        Tuple::point(1.0, 0.0, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+        5.0,
+        5.0,
+      ),
+      (
+        Tuple::point(0.0, 0.0, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+        4.0,
+        6.0,
+      ),
+      (
+        Tuple::point(0.5, 0.0, -5.0),
+        Tuple::vector(0.1, 1.0, 1.0),
+        6.80798,
+        7.08872,
+      ),
+    ];
+
+    for (origin, direction, t0, t1) in examples {
+      let r = Ray::new(origin, direction.normalize());
+      let xs = cyl.intersect_in_object_space(r);
+
+      assert_eq!(xs.len(), 2);
+      assert_fuzzy_eq!(xs[0].0, t0);
+      assert_fuzzy_eq!(xs[1].0, t1);
+    }
+  }
+
+  #[test]
+  fn normal_vector_on_a_cylinder() {
+    let cyl = Cylinder::default();
+    let examples = [
+      (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0)),
+      (Tuple::point(0.0, 5.0, -1.0), Tuple::vector(0.0, 0.0, -1.0)),
+      (Tuple::point(0.0, -2.0, 1.0), Tuple::vector(0.0, 0.0, 1.0)),
+      (Tuple::point(-1.0, 1.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0)),
+    ];
+
+    for (point, normal) in examples {
+      assert_fuzzy_eq!(cyl.normal_at_in_object_space(point), normal);
+    }
+  }
+
+  #[test]
+  fn the_default_minimum_and_maximum_for_a_cylinder() {
+    let cyl = Cylinder::default();
+
+    assert_eq!(cyl.minimum, F::NEG_INFINITY);
+    assert_eq!(cyl.maximum, F::INFINITY);
+    assert!(!cyl.closed);
+  }
+
+  #[test]
+  fn intersecting_a_constrained_cylinder() {
+    let cyl = Cylinder::default().with_minimum(1.0).with_maximum(2.0);
+    let examples = [
+      (Tuple::point(0.0, 1.5, 0.0), Tuple::vector(0.1, 1.0, 0.0), 0),
+      (
+        Tuple::point(0.0, 3.0, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+        0,
+      ),
+      (
+        Tuple::point(0.0, 0.0, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+        0,
+      ),
+      (
+        Tuple::point(0.0, 2.0, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+        0,
+      ),
+      (
+        Tuple::point(0.0, 1.0, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+        0,
+      ),
+      (
+        Tuple::point(0.0, 1.5, -2.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+        2,
+      ),
+    ];
+
+    for (origin, direction, count) in examples {
+      let r = Ray::new(origin, direction.normalize());
+      let xs = cyl.intersect_in_object_space(r);
+
+      assert_eq!(xs.len(), count);
+    }
+  }
+
+  #[test]
+  fn intersecting_the_caps_of_a_closed_cylinder() {
+    let cyl = Cylinder::default()
+      .with_minimum(1.0)
+      .with_maximum(2.0)
+      .with_closed(true);
+    let examples = [
+      (
+        Tuple::point(0.0, 3.0, 0.0),
+        Tuple::vector(0.0, -1.0, 0.0),
+        2,
+      ),
+      (
+        Tuple::point(0.0, 3.0, -2.0),
+        Tuple::vector(0.0, -1.0, 2.0),
+        2,
+      ),
+      (
+        Tuple::point(0.0, 4.0, -2.0),
+        Tuple::vector(0.0, -1.0, 1.0),
+        2,
+      ),
+      (
+        Tuple::point(0.0, 0.0, -2.0),
+        Tuple::vector(0.0, 1.0, 2.0),
+        2,
+      ),
+      (
+        Tuple::point(0.0, -1.0, -2.0),
+        Tuple::vector(0.0, 1.0, 1.0),
+        2,
+      ),
+    ];
+
+    for (origin, direction, count) in examples {
+      let r = Ray::new(origin, direction.normalize());
+      let xs = cyl.intersect_in_object_space(r);
+
+      assert_eq!(xs.len(), count);
+    }
+  }
+
+  #[test]
+  fn normal_vector_on_a_cylinders_end_caps() {
+    let cyl = Cylinder::default()
+      .with_minimum(1.0)
+      .with_maximum(2.0)
+      .with_closed(true);
+    let examples = [
+      (Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+      (Tuple::point(0.5, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+      (Tuple::point(0.0, 1.0, 0.5), Tuple::vector(0.0, -1.0, 0.0)),
+      (Tuple::point(0.0, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+      (Tuple::point(0.5, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+      (Tuple::point(0.0, 2.0, 0.5), Tuple::vector(0.0, 1.0, 0.0)),
+    ];
+
+    for (point, normal) in examples {
+      assert_fuzzy_eq!(cyl.normal_at_in_object_space(point), normal);
+    }
+  }
+
+  #[test]
+  fn intersections_reference_the_cylinder() {
+    let cyl = Cylinder::default().with_maximum(1.0).with_closed(true);
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = cyl.intersect(r);
+
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].body, Body::from(cyl));
+  }
+}
@@ -0,0 +1,503 @@
+use crate::body::{Body, Intersectable};
+use crate::camera::Camera;
+use crate::canvas::Color;
+use crate::material::{Illuminated, Reflective, Refractive};
+use crate::ray::Ray;
+use crate::ray_diagram::RayKind;
+use crate::tuple::Tuple;
+use crate::world::World;
+use crate::F;
+use std::fmt;
+
+/// Step by step breakdown of how a pixel got its color.
+///
+/// The primary ray through the center of the pixel is followed through all
+/// of its reflections and refractions, using the same rules as
+/// `World::color_at`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelExplanation {
+  pub x: usize,
+  pub y: usize,
+  /// The primary ray followed by its reflections and refractions, depth first
+  pub bounces: Vec<Bounce>,
+  pub color: Color,
+}
+
+/// A ray of the reflection chain and what it hit
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bounce {
+  pub kind: RayKind,
+  pub depth: usize,
+  pub ray: Ray,
+  /// Nothing is shaded, if the ray misses every body
+  pub shading: Option<Shading>,
+  /// Color of the ray including all further reflections
+  pub color: Color,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shading {
+  pub body: Body,
+  pub name: Option<String>,
+  pub t: F,
+  pub point: Tuple,
+  pub normal: Tuple,
+  pub shadow: ShadowTest,
+  pub ambient: Color,
+  /// Diffuse and specular light, after the clear coat if there is one
+  pub direct: Color,
+  /// Fraction of the ambient light removed by contact shadows
+  pub occlusion: F,
+  pub surface: Color,
+  pub reflectiveness: F,
+  /// Color of the reflection, already weighted by the reflectiveness
+  pub reflected: Color,
+  pub transparency: F,
+  /// Refractive indices of the materials the ray leaves and enters
+  pub refractive_indices: (F, F),
+  /// Color of the refraction, already weighted by the transparency
+  pub refracted: Color,
+  /// Fraction of the light reflected by transparent and reflective bodies,
+  /// which weights the reflection against the refraction
+  pub reflectance: Option<F>,
+}
+
+// Only a handful of explanations exist at a time, so the blocker is not boxed
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShadowTest {
+  /// The body does not receive shadows
+  Skipped,
+  Lit,
+  Shadowed {
+    by: Body,
+    name: Option<String>,
+    /// Fraction of the light let through by transparent blockers
+    transmittance: F,
+  },
+}
+
+pub fn explain_pixel(world: &World, camera: &Camera, x: usize, y: usize) -> PixelExplanation {
+  let mut bounces = vec![];
+  let color = explain_ray(
+    world,
+    camera.ray_for_pixel(x, y),
+    RayKind::Primary,
+    0,
+    1.0,
+    &mut world.secondary_rays(),
+    &mut bounces,
+  );
+  PixelExplanation {
+    x,
+    y,
+    bounces,
+    color,
+  }
+}
+
+fn explain_ray(
+  world: &World,
+  ray: Ray,
+  kind: RayKind,
+  depth: usize,
+  weight: F,
+  secondary_rays: &mut usize,
+  bounces: &mut Vec<Bounce>,
+) -> Color {
+  let remaining_reflections = world.reflection_limit() - depth;
+  let index = bounces.len();
+  bounces.push(Bounce {
+    kind,
+    depth,
+    ray,
+    shading: None,
+    color: Color::black(),
+  });
+
+  let xs = world.intersect(ray);
+  let hit = match xs.hit() {
+    Some(hit) => hit,
+    None => return world.background,
+  };
+  let light = match world.lights().first() {
+    Some(light) => *light,
+    None => {
+      bounces[index].color = world.unlit_color;
+      return world.unlit_color;
+    }
+  };
+  let c = hit.get_computed_with(&xs);
+  let material = hit.body.material();
+
+  let shadow = if !hit.body.shadows().receive {
+    ShadowTest::Skipped
+  } else {
+    shadow_test(world, c.over_point, ray.time)
+  };
+  let transmittance = match shadow {
+    ShadowTest::Shadowed { transmittance, .. } => transmittance,
+    _ => 1.0,
+  };
+  let terms = material
+    .lighting_terms(
+      &hit.body,
+      light,
+      c.over_point,
+      c.eyev,
+      c.normalv,
+      transmittance <= 0.0,
+    )
+    .dimmed(transmittance)
+    .only(world.lighting_pass);
+  let lighting = terms.total();
+  let ambient = terms.ambient;
+  let occlusion = match world.contact_shadows {
+    Some(contact_shadows) if shadow != ShadowTest::Skipped => {
+      contact_shadows.occlusion(world, c.over_point, c.normalv, ray.time)
+    }
+    _ => 0.0,
+  };
+  let surface = lighting - ambient * occlusion;
+
+  let reflectiveness = material.reflectiveness_at(&hit.body, c.point, c.eyev, c.normalv);
+  let reflected = if reflectiveness == 0.0
+    || remaining_reflections == 0
+    || weight * reflectiveness < world.contribution_cutoff
+  {
+    Color::black()
+  } else if *secondary_rays == 0 {
+    world.background * reflectiveness
+  } else {
+    *secondary_rays -= 1;
+    let reflected_ray = Ray::new(c.over_point, c.reflectv).with_time(ray.time);
+    explain_ray(
+      world,
+      reflected_ray,
+      RayKind::Reflection,
+      depth + 1,
+      weight * reflectiveness,
+      secondary_rays,
+      bounces,
+    ) * reflectiveness
+  };
+
+  let transparency = material.transparency_at(&hit.body, c.point);
+  let refracted = match c.refracted_direction() {
+    Some(_)
+      if transparency == 0.0
+        || remaining_reflections == 0
+        || weight * transparency < world.contribution_cutoff =>
+    {
+      Color::black()
+    }
+    Some(_) if *secondary_rays == 0 => world.background * transparency,
+    Some(direction) => {
+      *secondary_rays -= 1;
+      let refracted_ray = Ray::new(c.under_point, direction).with_time(ray.time);
+      explain_ray(
+        world,
+        refracted_ray,
+        RayKind::Refraction,
+        depth + 1,
+        weight * transparency,
+        secondary_rays,
+        bounces,
+      ) * transparency
+    }
+    // Total internal reflection
+    None => Color::black(),
+  };
+
+  let reflectance = if reflectiveness > 0.0 && transparency > 0.0 {
+    Some(c.schlick())
+  } else {
+    None
+  };
+  let color = match reflectance {
+    Some(reflectance) => surface + reflected * reflectance + refracted * (1.0 - reflectance),
+    None => surface + reflected + refracted,
+  };
+  bounces[index].shading = Some(Shading {
+    body: hit.body.clone(),
+    name: world.name_of(hit).map(String::from),
+    t: hit.t,
+    point: c.point,
+    normal: c.normalv,
+    shadow,
+    ambient,
+    direct: lighting - ambient,
+    occlusion,
+    surface,
+    reflectiveness,
+    reflected,
+    transparency,
+    refractive_indices: (c.n1, c.n2),
+    refracted,
+    reflectance,
+  });
+  bounces[index].color = color;
+  color
+}
+
+fn shadow_test(world: &World, position: Tuple, time: F) -> ShadowTest {
+  // The shadow ray towards the first direction the light arrives from
+  let sample = world.lights()[0].sample_directions(position)[0];
+  let distance = sample.distance;
+  let shadow_ray = Ray::new(position, sample.direction).with_time(time);
+
+  match world.intersect(shadow_ray).shadow_hit() {
+    Some(blocker) if blocker.t < distance => ShadowTest::Shadowed {
+      by: blocker.body.clone(),
+      name: world.name_of(blocker).map(String::from),
+      transmittance: world.transmittance(shadow_ray, distance),
+    },
+    _ => ShadowTest::Lit,
+  }
+}
+
+fn kind(body: &Body) -> &'static str {
+  match body {
+    Body::Sphere(_) => "sphere",
+    Body::Plane(_) => "plane",
+    Body::Quad(_) => "quad",
+    Body::Cylinder(_) => "cylinder",
+    Body::Cone(_) => "cone",
+    Body::Triangle(_) => "triangle",
+    Body::SmoothTriangle(_) => "smooth triangle",
+    Body::Sdf(_) => "sdf",
+    Body::Repeated(_) => "repeated",
+    Body::Mirrored(_) => "mirrored",
+    Body::Mesh(_) => "mesh",
+  }
+}
+
+struct Named<'a>(&'a Body, &'a Option<String>);
+
+impl fmt::Display for Named<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.1 {
+      Some(name) => write!(f, "{} '{}'", kind(self.0), name),
+      None => write!(f, "{}", kind(self.0)),
+    }
+  }
+}
+
+struct Rgb(Color);
+
+impl fmt::Display for Rgb {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "({:.3}, {:.3}, {:.3})",
+      self.0.red, self.0.green, self.0.blue
+    )
+  }
+}
+
+struct Xyz(Tuple);
+
+impl fmt::Display for Xyz {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "({:.3}, {:.3}, {:.3})", self.0.x, self.0.y, self.0.z)
+  }
+}
+
+impl fmt::Display for PixelExplanation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(
+      f,
+      "Pixel ({}, {}) has the color {}",
+      self.x,
+      self.y,
+      Rgb(self.color)
+    )?;
+    for (index, bounce) in self.bounces.iter().enumerate() {
+      let kind = match bounce.kind {
+        RayKind::Primary => "Primary",
+        RayKind::Shadow => "Shadow",
+        RayKind::Reflection => "Reflected",
+        RayKind::Refraction => "Refracted",
+      };
+      writeln!(
+        f,
+        "#{} {} ray at depth {} from {} towards {}",
+        index,
+        kind,
+        bounce.depth,
+        Xyz(bounce.ray.origin),
+        Xyz(bounce.ray.direction)
+      )?;
+      let shading = match &bounce.shading {
+        Some(shading) => shading,
+        None => {
+          writeln!(f, "   misses every body, which is black")?;
+          continue;
+        }
+      };
+      writeln!(
+        f,
+        "   hits {} at t = {:.3}, point {}, normal {}",
+        Named(&shading.body, &shading.name),
+        shading.t,
+        Xyz(shading.point),
+        Xyz(shading.normal)
+      )?;
+      match &shading.shadow {
+        ShadowTest::Skipped => writeln!(f, "   shadow test skipped, the body receives no shadows")?,
+        ShadowTest::Lit => writeln!(f, "   shadow test: lit by the light")?,
+        ShadowTest::Shadowed {
+          by,
+          name,
+          transmittance,
+        } if *transmittance > 0.0 => writeln!(
+          f,
+          "   shadow test: in the shadow of {}, letting {:.0}% of the light through",
+          Named(by, name),
+          transmittance * 100.0
+        )?,
+        ShadowTest::Shadowed { by, name, .. } => {
+          writeln!(f, "   shadow test: in the shadow of {}", Named(by, name))?
+        }
+      }
+      writeln!(
+        f,
+        "   ambient {} + direct {} - {:.0}% ambient occlusion = surface {}",
+        Rgb(shading.ambient),
+        Rgb(shading.direct),
+        shading.occlusion * 100.0,
+        Rgb(shading.surface)
+      )?;
+      writeln!(
+        f,
+        "   reflectiveness {:.3}, transparency {:.3}, refractive index {:.3} to {:.3}",
+        shading.reflectiveness,
+        shading.transparency,
+        shading.refractive_indices.0,
+        shading.refractive_indices.1
+      )?;
+      match shading.reflectance {
+        Some(reflectance) => writeln!(
+          f,
+          "   surface {} + {:.3} * reflection {} + {:.3} * refraction {} = {}",
+          Rgb(shading.surface),
+          reflectance,
+          Rgb(shading.reflected),
+          1.0 - reflectance,
+          Rgb(shading.refracted),
+          Rgb(bounce.color)
+        )?,
+        None => writeln!(
+          f,
+          "   surface {} + reflection {} + refraction {} = {}",
+          Rgb(shading.surface),
+          Rgb(shading.reflected),
+          Rgb(shading.refracted),
+          Rgb(bounce.color)
+        )?,
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::contact_shadows::ContactShadows;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::material::Material;
+  use crate::test_scene::{camera_facing_ball, floor_and_ball, glass, white_light_at};
+
+  #[test]
+  fn the_explained_color_is_the_rendered_color() {
+    let c = camera_facing_ball();
+    let mut glass_ball = floor_and_ball();
+    glass_ball.bodies_mut()[1] = glass_ball.bodies()[1].clone().with_material(Material::from(
+      glass().with_reflectiveness(0.9).with_transparency(0.9),
+    ));
+
+    for w in [
+      floor_and_ball(),
+      floor_and_ball().with_contact_shadows(ContactShadows::new(0.5, 8)),
+      glass_ball
+        .clone()
+        .with_background(Color::new(0.2, 0.4, 0.6))
+        .with_secondary_ray_budget(1),
+      glass_ball.clone().with_contribution_cutoff(0.5),
+      glass_ball,
+    ]
+    .iter()
+    {
+      for (x, y) in [(5, 5), (5, 10), (0, 0), (8, 9)].iter() {
+        let explanation = explain_pixel(w, &c, *x, *y);
+
+        assert_fuzzy_eq!(explanation.color, w.color_at(c.ray_for_pixel(*x, *y)));
+      }
+    }
+  }
+
+  #[test]
+  fn the_hit_body_is_explained() {
+    let explanation = explain_pixel(&floor_and_ball(), &camera_facing_ball(), 5, 5);
+
+    assert_eq!(explanation.bounces.len(), 1);
+    let shading = explanation.bounces[0].shading.as_ref().unwrap();
+    assert_eq!(shading.name.as_deref(), Some("ball"));
+    assert_fuzzy_eq!(shading.t, 4.0);
+    assert_fuzzy_eq!(shading.normal, Tuple::vector(0.0, 0.0, -1.0));
+    assert_eq!(shading.shadow, ShadowTest::Lit);
+    assert_fuzzy_eq!(shading.ambient + shading.direct, shading.surface);
+  }
+
+  #[test]
+  fn reflections_are_explained_at_each_depth() {
+    // The floor in front of the ball reflects the sky
+    let explanation = explain_pixel(&floor_and_ball(), &camera_facing_ball(), 5, 10);
+
+    assert_eq!(explanation.bounces.len(), 2);
+    let floor = explanation.bounces[0].shading.as_ref().unwrap();
+    assert_fuzzy_eq!(floor.reflectiveness, 0.5);
+    assert_fuzzy_eq!(floor.reflected, Color::black());
+    assert_eq!(explanation.bounces[1].depth, 1);
+    assert!(explanation.bounces[1].shading.is_none());
+  }
+
+  #[test]
+  fn refractions_are_explained() {
+    let mut w = floor_and_ball();
+    w.bodies_mut()[1] = w.bodies()[1].clone().with_material(Material::from(glass()));
+
+    let explanation = explain_pixel(&w, &camera_facing_ball(), 5, 5);
+
+    let ball = explanation.bounces[0].shading.as_ref().unwrap();
+    assert_fuzzy_eq!(ball.refractive_indices.0, 1.0);
+    assert_fuzzy_eq!(ball.refractive_indices.1, 1.5);
+    assert_eq!(explanation.bounces[1].kind, RayKind::Refraction);
+    assert_eq!(explanation.bounces[1].depth, 1);
+    assert_eq!(explanation.bounces[2].depth, 2);
+  }
+
+  #[test]
+  fn blocked_shadow_tests_name_the_blocker() {
+    let w = floor_and_ball().with_lights(vec![white_light_at(0.0, 10.0, 0.0)]);
+
+    let explanation = explain_pixel(&w, &camera_facing_ball(), 5, 5);
+
+    let shading = explanation.bounces[0].shading.as_ref().unwrap();
+    assert!(
+      matches!(&shading.shadow, ShadowTest::Shadowed { name, .. } if name.as_deref() == Some("ball"))
+    );
+  }
+
+  #[test]
+  fn explanations_are_printable_as_text() {
+    let text = explain_pixel(&floor_and_ball(), &camera_facing_ball(), 5, 10).to_string();
+
+    assert!(text.starts_with("Pixel (5, 10) has the color"));
+    assert!(text.contains("#0 Primary ray at depth 0"));
+    assert!(text.contains("hits plane at t ="));
+    assert!(text.contains("#1 Reflected ray at depth 1"));
+    assert!(text.contains("misses every body"));
+  }
+}
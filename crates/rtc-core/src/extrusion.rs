@@ -0,0 +1,354 @@
+use crate::fuzzy_eq::FuzzyEq;
+use crate::mesh::{Face, Mesh};
+use crate::triangle::Triangle;
+use crate::tuple::Tuple;
+use crate::{EPSILON, F};
+
+/// Flat outlines extruded into 3D, e.g. logos imported from SVG paths.
+///
+/// The outlines lie in the xy plane and are filled like SVG's `evenodd` fill
+/// rule, so outlines inside others cut holes. The front faces the negative z
+/// axis and is extruded by `depth` towards positive z. A `bevel` chamfers
+/// the edges of the front and back, it should be small compared to the
+/// details of the outlines.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Extrusion {
+  pub contours: Vec<Vec<Tuple>>,
+  pub depth: F,
+  pub bevel: F,
+}
+
+impl Extrusion {
+  pub fn new(contours: Vec<Vec<Tuple>>) -> Self {
+    let contours = contours
+      .into_iter()
+      .map(|mut contour| {
+        contour.dedup_by(|point, previous| point.fuzzy_eq(*previous));
+        if contour.len() > 1 && contour[0].fuzzy_eq(contour[contour.len() - 1]) {
+          contour.pop();
+        }
+        contour
+      })
+      .filter(|contour| contour.len() >= 3)
+      .collect();
+    Self {
+      contours,
+      depth: 0.2,
+      bevel: 0.0,
+    }
+  }
+
+  pub fn with_depth(mut self, depth: F) -> Self {
+    self.depth = depth;
+    self
+  }
+
+  pub fn with_bevel(mut self, bevel: F) -> Self {
+    self.bevel = bevel;
+    self
+  }
+
+  /// Whether the point lies inside the filled outlines
+  pub fn contains(&self, x: F, y: F) -> bool {
+    contains(&self.contours, x, y)
+  }
+
+  /// Triangles of the caps on the front and back, and of the sides along
+  /// every outline
+  pub fn mesh(&self) -> Mesh {
+    let depth = self.depth;
+    let bevel = self.bevel.clamp(0.0, depth / 2.0);
+    let normals: Vec<Vec<Tuple>> = self
+      .contours
+      .iter()
+      .map(|contour| self.inward_normals(contour))
+      .collect();
+    // Outlines of the caps, moved inwards by the bevel
+    let insets: Vec<Vec<Tuple>> = self
+      .contours
+      .iter()
+      .zip(normals.iter())
+      .map(|(contour, normals)| inset(contour, normals, bevel))
+      .collect();
+
+    let at = |point: Tuple, z: F| Tuple::point(point.x, point.y, z);
+    let front = Tuple::vector(0.0, 0.0, -1.0);
+    let back = Tuple::vector(0.0, 0.0, 1.0);
+    let mut faces = vec![];
+    for [a, b, c, d] in trapezoids(&insets) {
+      quad(
+        &mut faces,
+        [at(a, 0.0), at(b, 0.0), at(c, 0.0), at(d, 0.0)],
+        front,
+      );
+      quad(
+        &mut faces,
+        [at(a, depth), at(b, depth), at(c, depth), at(d, depth)],
+        back,
+      );
+    }
+
+    for (index, contour) in self.contours.iter().enumerate() {
+      let (normals, inset) = (&normals[index], &insets[index]);
+      for i in 0..contour.len() {
+        let j = (i + 1) % contour.len();
+        let (p, q) = (contour[i], contour[j]);
+        let outward = -normals[i];
+        if depth - 2.0 * bevel > EPSILON {
+          quad(
+            &mut faces,
+            [
+              at(p, bevel),
+              at(q, bevel),
+              at(q, depth - bevel),
+              at(p, depth - bevel),
+            ],
+            outward,
+          );
+        }
+        if bevel > EPSILON {
+          quad(
+            &mut faces,
+            [
+              at(inset[i], 0.0),
+              at(inset[j], 0.0),
+              at(q, bevel),
+              at(p, bevel),
+            ],
+            outward + front,
+          );
+          quad(
+            &mut faces,
+            [
+              at(p, depth - bevel),
+              at(q, depth - bevel),
+              at(inset[j], depth),
+              at(inset[i], depth),
+            ],
+            outward + back,
+          );
+        }
+      }
+    }
+
+    Mesh::new(faces)
+  }
+
+  /// Normals of the edges starting at each point of the contour, pointing
+  /// into the filled area. Which side is filled is probed next to the
+  /// longest edge.
+  fn inward_normals(&self, contour: &[Tuple]) -> Vec<Tuple> {
+    let lefts: Vec<Tuple> = (0..contour.len())
+      .map(|i| {
+        let edge = contour[(i + 1) % contour.len()] - contour[i];
+        Tuple::vector(-edge.y, edge.x, 0.0).normalize()
+      })
+      .collect();
+    let longest = (0..contour.len())
+      .max_by(|a, b| {
+        let length = |i: usize| (contour[(i + 1) % contour.len()] - contour[i]).magnitude();
+        length(*a).total_cmp(&length(*b))
+      })
+      .unwrap();
+    let start = contour[longest];
+    let edge = contour[(longest + 1) % contour.len()] - start;
+    let probe = start + edge * 0.5 + lefts[longest] * (edge.magnitude() * 1e-4);
+    let sign = if self.contains(probe.x, probe.y) {
+      1.0
+    } else {
+      -1.0
+    };
+    lefts.into_iter().map(|left| left * sign).collect()
+  }
+}
+
+/// Even-odd test counting the edges crossed to the right of the point
+fn contains(contours: &[Vec<Tuple>], x: F, y: F) -> bool {
+  let mut inside = false;
+  for contour in contours {
+    for i in 0..contour.len() {
+      let (a, b) = (contour[i], contour[(i + 1) % contour.len()]);
+      if (a.y > y) != (b.y > y) && x < a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x) {
+        inside = !inside;
+      }
+    }
+  }
+  inside
+}
+
+/// Moves every point inwards by the distance, keeping the edges parallel
+fn inset(contour: &[Tuple], normals: &[Tuple], distance: F) -> Vec<Tuple> {
+  (0..contour.len())
+    .map(|i| {
+      let before = normals[(i + contour.len() - 1) % contour.len()];
+      let after = normals[i];
+      // Keeps sharp spikes from shooting off too far
+      let miter = (before + after) * (1.0 / (1.0 + before.dot(after)).max(0.1));
+      contour[i] + miter * distance
+    })
+    .collect()
+}
+
+/// Splits the filled area into trapezoids between the heights of all
+/// points, each given by its four corners
+fn trapezoids(contours: &[Vec<Tuple>]) -> Vec<[Tuple; 4]> {
+  let edges: Vec<(Tuple, Tuple)> = contours
+    .iter()
+    .flat_map(|contour| {
+      (0..contour.len()).map(move |i| {
+        let (a, b) = (contour[i], contour[(i + 1) % contour.len()]);
+        if a.y < b.y {
+          (a, b)
+        } else {
+          (b, a)
+        }
+      })
+    })
+    .filter(|(bottom, top)| top.y - bottom.y > EPSILON)
+    .collect();
+  let mut heights: Vec<F> = edges.iter().flat_map(|(a, b)| [a.y, b.y]).collect();
+  heights.sort_by(|a, b| a.total_cmp(b));
+  heights.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+
+  let x_at = |(bottom, top): &(Tuple, Tuple), y: F| {
+    bottom.x + (y - bottom.y) / (top.y - bottom.y) * (top.x - bottom.x)
+  };
+  let mut trapezoids = vec![];
+  for slab in heights.windows(2) {
+    let (y0, y1) = (slab[0], slab[1]);
+    let middle = (y0 + y1) / 2.0;
+    let mut crossing: Vec<&(Tuple, Tuple)> = edges
+      .iter()
+      .filter(|(bottom, top)| bottom.y < middle && top.y > middle)
+      .collect();
+    crossing.sort_by(|a, b| x_at(a, middle).total_cmp(&x_at(b, middle)));
+    for pair in crossing.chunks_exact(2) {
+      let (left, right) = (pair[0], pair[1]);
+      trapezoids.push([
+        Tuple::point(x_at(left, y0), y0, 0.0),
+        Tuple::point(x_at(right, y0), y0, 0.0),
+        Tuple::point(x_at(right, y1), y1, 0.0),
+        Tuple::point(x_at(left, y1), y1, 0.0),
+      ]);
+    }
+  }
+  trapezoids
+}
+
+/// Two triangles of the quad, wound to face the given direction. Triangles
+/// collapsed into a line are left out.
+fn quad(faces: &mut Vec<Face>, [a, b, c, d]: [Tuple; 4], facing: Tuple) {
+  for (p1, p2, p3) in [(a, b, c), (a, c, d)] {
+    let normal = (p3 - p1).cross(p2 - p1);
+    if normal.magnitude() < EPSILON * EPSILON {
+      continue;
+    }
+    let face = if normal.dot(facing) >= 0.0 {
+      Triangle::new(p1, p2, p3)
+    } else {
+      Triangle::new(p1, p3, p2)
+    };
+    faces.push(Face::from(face));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::body::{Body, Intersectable};
+  use crate::ray::Ray;
+
+  fn square(x: F, y: F, size: F) -> Vec<Tuple> {
+    vec![
+      Tuple::point(x, y, 0.0),
+      Tuple::point(x + size, y, 0.0),
+      Tuple::point(x + size, y + size, 0.0),
+      Tuple::point(x, y + size, 0.0),
+    ]
+  }
+
+  /// Normals of all the bodies hit by the ray, from near to far
+  fn hits(bodies: &[Body], ray: Ray) -> Vec<Tuple> {
+    let mut intersections: Vec<_> = bodies
+      .iter()
+      .flat_map(|body| body.intersect(ray).into_iter())
+      .collect();
+    intersections.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+    intersections
+      .iter()
+      .map(|intersection| intersection.body.normal_at(ray.position(intersection.t)))
+      .collect()
+  }
+
+  #[test]
+  fn inner_outlines_cut_holes() {
+    let frame = Extrusion::new(vec![square(0.0, 0.0, 3.0), square(1.0, 1.0, 1.0)]);
+    let bodies = vec![frame.mesh().body()];
+    let towards_back = Tuple::vector(0.0, 0.0, 1.0);
+
+    let solid = hits(
+      &bodies,
+      Ray::new(Tuple::point(0.4, 1.3, -1.0), towards_back),
+    );
+    let hole = hits(
+      &bodies,
+      Ray::new(Tuple::point(1.4, 1.3, -1.0), towards_back),
+    );
+    let through_side = hits(
+      &bodies,
+      Ray::new(Tuple::point(-1.0, 1.3, 0.1), Tuple::vector(1.0, 0.0, 0.0)),
+    );
+
+    assert_eq!(
+      solid,
+      vec![Tuple::vector(0.0, 0.0, -1.0), Tuple::vector(0.0, 0.0, 1.0)]
+    );
+    assert!(hole.is_empty());
+    // Out of the frame into the hole and back into the frame again
+    assert_eq!(
+      through_side,
+      vec![
+        Tuple::vector(-1.0, 0.0, 0.0),
+        Tuple::vector(1.0, 0.0, 0.0),
+        Tuple::vector(-1.0, 0.0, 0.0),
+        Tuple::vector(1.0, 0.0, 0.0),
+      ]
+    );
+  }
+
+  #[test]
+  fn the_winding_of_outlines_does_not_matter() {
+    let mut clockwise = square(0.0, 0.0, 1.0);
+    clockwise.reverse();
+
+    let bodies = vec![Extrusion::new(vec![clockwise]).mesh().body()];
+    let front = hits(
+      &bodies,
+      Ray::new(Tuple::point(0.3, 0.6, -1.0), Tuple::vector(0.0, 0.0, 1.0)),
+    );
+
+    assert_eq!(front[0], Tuple::vector(0.0, 0.0, -1.0));
+  }
+
+  #[test]
+  fn bevels_chamfer_the_edges_of_front_and_back() {
+    let bodies = vec![Extrusion::new(vec![square(0.0, 0.0, 1.0)])
+      .with_depth(1.0)
+      .with_bevel(0.1)
+      .mesh()
+      .body()];
+
+    let at_edge = hits(
+      &bodies,
+      Ray::new(Tuple::point(0.05, 0.5, -1.0), Tuple::vector(0.0, 0.0, 1.0)),
+    );
+    let inside_bevel = hits(
+      &bodies,
+      Ray::new(Tuple::point(0.15, 0.5, -1.0), Tuple::vector(0.0, 0.0, 1.0)),
+    );
+
+    let half = (0.5 as F).sqrt();
+    assert_fuzzy_eq!(at_edge[0], Tuple::vector(-half, 0.0, -half));
+    assert_fuzzy_eq!(inside_bevel[0], Tuple::vector(0.0, 0.0, -1.0));
+  }
+}
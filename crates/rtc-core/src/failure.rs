@@ -0,0 +1,43 @@
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+  match (
+    payload.downcast_ref::<&str>(),
+    payload.downcast_ref::<String>(),
+  ) {
+    (Some(message), _) => message.to_string(),
+    (None, Some(message)) => message.clone(),
+    (None, None) => "unknown panic".into(),
+  }
+}
+
+/// Runs `operation`, turning a panic into an error message.
+///
+/// Used to keep a single broken scene or frame (e.g. a non invertible
+/// transformation) from taking down a whole batch or animation.
+pub fn catch_panic<T, O>(operation: O) -> Result<T, String>
+where
+  O: FnOnce() -> T,
+{
+  catch_unwind(AssertUnwindSafe(operation))
+    .map_err(|payload| format!("Panicked: {}", panic_message(payload)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn panics_are_turned_into_errors() {
+    assert_eq!(catch_panic(|| 42), Ok(42));
+    assert_eq!(
+      catch_panic(|| -> usize { panic!("Matrix is not invertible") }),
+      Err("Panicked: Matrix is not invertible".into())
+    );
+    assert_eq!(
+      catch_panic(|| -> usize { panic!("Frame {} failed", 7) }),
+      Err("Panicked: Frame 7 failed".into())
+    );
+  }
+}
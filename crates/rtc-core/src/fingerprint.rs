@@ -0,0 +1,124 @@
+//! Content hashes telling which world and camera an image was rendered from
+use crate::color::Color;
+use std::fmt;
+use std::str::FromStr;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64 bit FNV-1a hash of the debug representation of values.
+///
+/// Unlike `std::hash`, the result is the same in every run and on every
+/// machine, as long as the renderer itself does not change. Values hashed
+/// this way must not contain hash maps, whose order varies between runs.
+/// Images of textures and lookup tables are too large to be formatted, they
+/// enter with the hash of their colors from `of_colors` instead.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fingerprint(pub u64);
+
+impl Fingerprint {
+  pub fn of(values: &[&dyn fmt::Debug]) -> Self {
+    let mut hasher = Hasher(FNV_OFFSET_BASIS);
+    for value in values.iter() {
+      // Writing into the hasher never fails
+      fmt::write(&mut hasher, format_args!("{:?};", value)).unwrap();
+    }
+    Fingerprint(hasher.0)
+  }
+
+  /// Hash of the exact bits of the colors, e.g. of all pixels of an image
+  pub fn of_colors<'a>(colors: impl IntoIterator<Item = &'a Color>) -> Self {
+    let mut hasher = Hasher(FNV_OFFSET_BASIS);
+    for color in colors {
+      for channel in [color.red, color.green, color.blue].iter() {
+        hasher.write_bytes(&channel.to_bits().to_le_bytes());
+      }
+    }
+    Fingerprint(hasher.0)
+  }
+
+  /// Lines of the sidecar file written next to a rendered image
+  pub fn report(&self, scene: &str, camera: &str, frame: Option<usize>) -> String {
+    let mut report = format!("scene: {}\ncamera: {}\n", scene, camera);
+    if let Some(frame) = frame {
+      report += &format!("frame: {}\n", frame);
+    }
+    report + &format!("fingerprint: {}\n", self)
+  }
+}
+
+impl fmt::Display for Fingerprint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:016x}", self.0)
+  }
+}
+
+impl FromStr for Fingerprint {
+  type Err = std::num::ParseIntError;
+
+  fn from_str(hex: &str) -> Result<Self, Self::Err> {
+    u64::from_str_radix(hex.trim(), 16).map(Fingerprint)
+  }
+}
+
+/// Hashes the text written into it, so large worlds are never formatted
+/// into a string as a whole
+struct Hasher(u64);
+
+impl Hasher {
+  fn write_bytes(&mut self, bytes: &[u8]) {
+    for byte in bytes.iter() {
+      self.0 ^= *byte as u64;
+      self.0 = self.0.wrapping_mul(FNV_PRIME);
+    }
+  }
+}
+
+impl fmt::Write for Hasher {
+  fn write_str(&mut self, text: &str) -> fmt::Result {
+    self.write_bytes(text.as_bytes());
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fingerprints_only_depend_on_the_values() {
+    let fingerprint = Fingerprint::of(&[&1.5, &"sphere"]);
+
+    assert_eq!(fingerprint, Fingerprint::of(&[&1.5, &"sphere"]));
+    assert_ne!(fingerprint, Fingerprint::of(&[&1.25, &"sphere"]));
+    assert_ne!(fingerprint, Fingerprint::of(&[&"sphere", &1.5]));
+    assert_eq!(Fingerprint::of(&[]).to_string(), "cbf29ce484222325");
+  }
+
+  #[test]
+  fn colors_are_hashed_by_their_bits() {
+    let colors = [Color::new(0.1, 0.2, 0.3), Color::white()];
+
+    assert_eq!(
+      Fingerprint::of_colors(&colors),
+      Fingerprint::of_colors(&colors)
+    );
+    assert_ne!(
+      Fingerprint::of_colors(&colors),
+      Fingerprint::of_colors(&[Color::new(0.1, 0.2, 0.3 + 1e-12), Color::white()])
+    );
+  }
+
+  #[test]
+  fn fingerprints_are_written_as_hex() {
+    let fingerprint = Fingerprint(0x00ab_cdef_0123_4567);
+
+    assert_eq!(fingerprint.to_string(), "00abcdef01234567");
+    assert_eq!("00abcdef01234567".parse(), Ok(fingerprint));
+    assert!("sphere".parse::<Fingerprint>().is_err());
+    assert_eq!(
+      fingerprint.report("world.yaml", "main", Some(3)),
+      "scene: world.yaml\ncamera: main\nframe: 3\nfingerprint: 00abcdef01234567\n"
+    );
+  }
+}
@@ -0,0 +1,41 @@
+//! Floating point functions for `no_std` builds.
+//!
+//! Without `std` the float methods like `sqrt` or `sin` are not available, so
+//! they are provided by `libm` instead.
+
+use crate::F;
+
+pub trait Float {
+  fn sqrt(self) -> Self;
+  fn powi(self, exponent: i32) -> Self;
+  fn sin(self) -> Self;
+  fn cos(self) -> Self;
+  fn tan(self) -> Self;
+  fn acos(self) -> Self;
+}
+
+impl Float for F {
+  fn sqrt(self) -> Self {
+    libm::sqrt(self)
+  }
+
+  fn powi(self, exponent: i32) -> Self {
+    libm::pow(self, exponent as F)
+  }
+
+  fn sin(self) -> Self {
+    libm::sin(self)
+  }
+
+  fn cos(self) -> Self {
+    libm::cos(self)
+  }
+
+  fn tan(self) -> Self {
+    libm::tan(self)
+  }
+
+  fn acos(self) -> Self {
+    libm::acos(self)
+  }
+}
@@ -0,0 +1,139 @@
+use crate::bounds::Bounds;
+use crate::camera::Camera;
+use crate::tuple::Tuple;
+use crate::{EPSILON, F};
+
+/// Volume seen by a pinhole camera: the pyramid spanned by the rays through
+/// the corners of its image, starting at the camera.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frustum {
+  pub apex: Tuple,
+  /// Normals of the four sides and the plane through the apex facing the
+  /// viewing direction, all pointing inwards
+  pub normals: [Tuple; 5],
+}
+
+impl Frustum {
+  /// Frustum of the image of the camera, including its crop window, or
+  /// `None` for cameras with depth of field. Their rays start anywhere on
+  /// the lens and leave the frustum of the pinhole behind the focal plane.
+  pub fn of_camera(camera: &Camera) -> Option<Self> {
+    if camera.aperture > 0.0 {
+      return None;
+    }
+    let (hsize, vsize) = (camera.hsize as F, camera.vsize as F);
+    let corners = [
+      camera.direction_through_film(0.0, 0.0),
+      camera.direction_through_film(hsize, 0.0),
+      camera.direction_through_film(hsize, vsize),
+      camera.direction_through_film(0.0, vsize),
+    ];
+    let forward = camera.direction_through_film(hsize / 2.0, vsize / 2.0);
+
+    let mut normals = [forward; 5];
+    for side in 0..4 {
+      let normal = corners[side].cross(corners[(side + 1) % 4]).normalize();
+      normals[side] = if normal.dot(forward) < 0.0 {
+        -normal
+      } else {
+        normal
+      };
+    }
+
+    Some(Self {
+      apex: camera.position(),
+      normals,
+    })
+  }
+
+  /// Whether some part of the bounds may lie within the frustum. Bounds
+  /// close to its sides count as within, infinite bounds always do.
+  pub fn may_contain(&self, bounds: Bounds) -> bool {
+    if bounds.is_infinite() {
+      return true;
+    }
+
+    self.normals.iter().all(|normal| {
+      // Corner of the bounds furthest along the normal
+      let corner = Tuple::point(
+        if normal.x >= 0.0 {
+          bounds.max.x
+        } else {
+          bounds.min.x
+        },
+        if normal.y >= 0.0 {
+          bounds.max.y
+        } else {
+          bounds.min.y
+        },
+        if normal.z >= 0.0 {
+          bounds.max.z
+        } else {
+          bounds.min.z
+        },
+      );
+      normal.dot(corner - self.apex) >= -EPSILON
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::angle::Angle;
+  use crate::camera::CropWindow;
+  use std::f64::consts::PI;
+
+  fn camera() -> Camera {
+    Camera::new(20, 10, Angle::radians(PI / 2.0)).look_at_from_position(
+      Tuple::point(0.0, 0.0, -5.0),
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    )
+  }
+
+  fn cube_at(x: F, y: F, z: F) -> Bounds {
+    Bounds::new(
+      Tuple::point(x - 1.0, y - 1.0, z - 1.0),
+      Tuple::point(x + 1.0, y + 1.0, z + 1.0),
+    )
+  }
+
+  #[test]
+  fn bounds_in_view_are_contained() {
+    let frustum = Frustum::of_camera(&camera()).unwrap();
+
+    assert!(frustum.may_contain(cube_at(0.0, 0.0, 0.0)));
+    // Partly visible at the left edge of the image
+    assert!(frustum.may_contain(cube_at(-5.5, 0.0, 0.0)));
+    // Surrounding the camera
+    assert!(frustum.may_contain(cube_at(0.0, 0.0, -5.0)));
+    assert!(frustum.may_contain(Bounds::infinite()));
+  }
+
+  #[test]
+  fn bounds_out_of_view_are_not_contained() {
+    let frustum = Frustum::of_camera(&camera()).unwrap();
+
+    assert!(!frustum.may_contain(cube_at(0.0, 0.0, -10.0)));
+    assert!(!frustum.may_contain(cube_at(-10.0, 0.0, 0.0)));
+    // The image is half as high as wide
+    assert!(!frustum.may_contain(cube_at(0.0, 5.0, 0.0)));
+  }
+
+  #[test]
+  fn the_frustum_follows_the_crop_window() {
+    let cropped = camera().with_crop_window(CropWindow::new(0.5, 0.25, 1.0, 0.75));
+    let frustum = Frustum::of_camera(&cropped).unwrap();
+
+    assert!(frustum.may_contain(cube_at(3.0, 0.0, 0.0)));
+    assert!(!frustum.may_contain(cube_at(-3.0, 0.0, 0.0)));
+  }
+
+  #[test]
+  fn cameras_with_depth_of_field_have_no_frustum() {
+    let camera = camera().with_depth_of_field(0.1, 5.0);
+
+    assert!(Frustum::of_camera(&camera).is_none());
+  }
+}
@@ -1,3 +1,6 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
 
 use crate::EPSILON;
@@ -12,7 +15,8 @@ pub trait FuzzyEq<T: Clone> {
 
 impl FuzzyEq<f64> for f64 {
   fn fuzzy_eq(&self, other: f64) -> bool {
-    (*self - other).abs() < EPSILON
+    // Exact comparison first, so equal infinities are considered equal
+    *self == other || (*self - other).abs() < EPSILON
   }
 }
 
@@ -36,6 +40,7 @@ where
   }
 }
 
+#[cfg(feature = "std")]
 impl<T, U> FuzzyEq<HashMap<T, U>> for HashMap<T, U>
 where
   T: FuzzyEq<T> + std::cmp::Eq + std::hash::Hash,
@@ -112,7 +117,7 @@ macro_rules! assert_fuzzy_ne {
   ($left:expr, $right:expr $(,)?) => {{
     match (&$left, $right) {
       (left_val, right_val) => {
-        if left_val.fuzzy_eq(right_val) {
+        if left_val.fuzzy_eq(right_val.clone()) {
           panic!(
             "asserting fuzzy in-equality. {:?} is fuzzy equal to {:?}",
             left_val, right_val
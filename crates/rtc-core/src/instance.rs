@@ -0,0 +1,203 @@
+use crate::body::{Body, Intersectable, Shadows};
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::intersections::Intersection;
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use std::sync::Arc;
+
+/// Bodies shared between several instances, e.g. the triangles of a mesh.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Geometry {
+  bodies: Vec<Body>,
+  bounds: Bounds,
+  max_intersections: usize,
+}
+
+impl Geometry {
+  pub fn new(bodies: Vec<Body>) -> Self {
+    let bounds = bodies
+      .iter()
+      .map(|body| body.bounds())
+      .reduce(|bounds, other| bounds.union(other))
+      .unwrap_or_else(Bounds::infinite);
+    let max_intersections = bodies.iter().map(|body| body.max_intersections()).sum();
+    Self {
+      bodies,
+      bounds,
+      max_intersections,
+    }
+  }
+
+  pub fn bodies(&self) -> &[Body] {
+    &self.bodies
+  }
+
+  pub fn bounds(&self) -> Bounds {
+    self.bounds
+  }
+
+  /// Most intersections a single ray can have with all bodies together
+  pub fn max_intersections(&self) -> usize {
+    self.max_intersections
+  }
+}
+
+/// Placement of a shared geometry in the world.
+///
+/// Instances only store their own transformation and optionally a material
+/// and shadow flags overriding those of the geometry, so a forest of identical trees keeps a
+/// single copy of the tree in memory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instance {
+  pub geometry: Arc<Geometry>,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+  pub material: Option<Material>,
+  pub shadows: Option<Shadows>,
+}
+
+impl Instance {
+  pub fn new(geometry: Arc<Geometry>) -> Self {
+    Self {
+      geometry,
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+      material: None,
+      shadows: None,
+    }
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+
+  pub fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.material = Some(material);
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.shadows = Some(shadows);
+    self
+  }
+
+  /// Bounds of the placed geometry in world space
+  pub fn bounds(&self) -> Bounds {
+    self.geometry.bounds.transform(self.transform)
+  }
+
+  /// A body of the geometry as it is placed by this instance
+  pub fn place(&self, body: Body) -> Body {
+    let transform = self.transform * body.transform();
+    let inverse_transform = body.inverse_transform() * self.inverse_transform;
+    let mut placed = body.with_transform_and_inverse(transform, inverse_transform);
+    if let Some(material) = self.material.clone() {
+      placed = placed.with_material(material);
+    }
+    if let Some(shadows) = self.shadows {
+      placed = placed.with_shadows(shadows);
+    }
+    placed
+  }
+
+  /// Intersections with the geometry, whose bodies are placed by this instance
+  pub fn intersect(&self, ray: Ray) -> Vec<Intersection> {
+    let instance_space_ray = ray.transform(self.inverse_transform);
+    if !self.geometry.bounds.intersects(instance_space_ray) {
+      return vec![];
+    }
+
+    self
+      .geometry
+      .bodies
+      .iter()
+      .flat_map(|body| body.intersect(instance_space_ray))
+      .map(|intersection| Intersection::new(intersection.t, ray, self.place(intersection.body)))
+      .collect()
+  }
+}
+
+impl FuzzyEq<Instance> for Instance {
+  fn fuzzy_eq(&self, other: Instance) -> bool {
+    (Arc::ptr_eq(&self.geometry, &other.geometry)
+      || self.geometry.bodies.fuzzy_eq(other.geometry.bodies.clone()))
+      && self.transform.fuzzy_eq(other.transform)
+      && self.material.fuzzy_eq(other.material.clone())
+      && self.shadows == other.shadows
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::material::Phong;
+  use crate::sphere::Sphere;
+  use crate::tuple::Tuple;
+
+  fn two_spheres() -> Arc<Geometry> {
+    Arc::new(Geometry::new(vec![
+      Body::from(Sphere::default()),
+      Body::from(Sphere::default().with_transform(Matrix::translation(3.0, 0.0, 0.0))),
+    ]))
+  }
+
+  #[test]
+  fn geometry_bounds_enclose_all_bodies() {
+    assert_fuzzy_eq!(
+      two_spheres().bounds(),
+      Bounds::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(4.0, 1.0, 1.0))
+    );
+  }
+
+  #[test]
+  fn intersections_hold_the_placed_bodies() {
+    let material = Material::from(Phong::default().with_ambient(1.0));
+    let shadows = Shadows {
+      cast: false,
+      ..Default::default()
+    };
+    let instance = Instance::new(two_spheres())
+      .with_transform(Matrix::translation(0.0, 5.0, 0.0))
+      .with_material(material.clone())
+      .with_shadows(shadows);
+    let ray = Ray::new(Tuple::point(3.0, 5.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let xs = instance.intersect(ray);
+
+    assert_eq!(xs.len(), 2);
+    assert_fuzzy_eq!(xs[0].t, 4.0);
+    assert_fuzzy_eq!(
+      xs[0].body,
+      Body::from(
+        Sphere::default()
+          .with_transform(Matrix::translation(3.0, 5.0, 0.0))
+          .with_material(material)
+          .with_shadows(shadows)
+      )
+    );
+    assert_fuzzy_eq!(
+      xs[0].body.inverse_transform(),
+      Matrix::translation(-3.0, -5.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn instances_share_their_geometry() {
+    let geometry = two_spheres();
+    let a = Instance::new(geometry.clone());
+    let b = Instance::new(geometry.clone()).with_transform(Matrix::translation(0.0, 5.0, 0.0));
+    let ray = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    assert_eq!(Arc::strong_count(&geometry), 3);
+    assert_eq!(a.intersect(ray).len(), 2);
+    assert!(b.intersect(ray).is_empty());
+  }
+}
@@ -0,0 +1,476 @@
+use crate::body::*;
+use crate::computed_intersection::ComputedIntersection;
+use crate::material::Refractive;
+use crate::ray::Ray;
+use crate::F;
+use core::ops::Index;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Intersection {
+  pub t: F,
+  pub ray: Ray,
+  pub body: Body,
+}
+
+impl Intersection {
+  pub fn new(t: F, ray: Ray, body: Body) -> Intersection {
+    Intersection { t, ray, body }
+  }
+
+  /// Whether the cutout of the material removes the surface at this point,
+  /// so the ray passes through
+  pub fn is_cut_out(&self) -> bool {
+    match self.body.material().cutout() {
+      Some(cutout) => cutout.is_cut_out(&self.body, self.ray.position(self.t)),
+      None => false,
+    }
+  }
+
+  pub fn get_computed(&self) -> ComputedIntersection<'_> {
+    let position = self.ray.position(self.t);
+    let (mut geometric_normalv, mut normalv) = self.body.normals_at(position);
+    let eyev = -self.ray.direction;
+    let inside = geometric_normalv.dot(eyev) < 0.0;
+
+    if inside {
+      geometric_normalv = -geometric_normalv;
+    }
+
+    // Offset along the true surface, interpolated normals and bumps only
+    // change the shading
+    let material = self.body.material();
+    let bias = material.shadow_bias();
+    let over_point = position + geometric_normalv * bias;
+    let under_point = position - geometric_normalv * bias;
+
+    if normalv.dot(geometric_normalv) < 0.0 {
+      normalv = -normalv;
+    }
+    let normalv = material.shading_normal(&self.body, position, normalv);
+
+    let reflectv = self.ray.direction.reflect(normalv);
+
+    ComputedIntersection::new(
+      self,
+      position,
+      over_point,
+      under_point,
+      normalv,
+      geometric_normalv,
+      eyev,
+      reflectv,
+      inside,
+    )
+  }
+
+  /// Like `get_computed`, but with the refractive indices on both sides of
+  /// the surface taken from all the intersections of the ray
+  pub fn get_computed_with(&self, intersections: &Intersections) -> ComputedIntersection<'_> {
+    let (n1, n2) = intersections.refractive_indices(self);
+    self.get_computed().with_refractive_indices(n1, n2)
+  }
+}
+
+pub struct Intersections {
+  data: Vec<Intersection>,
+}
+
+impl Intersections {
+  pub fn new(mut intersections: Vec<Intersection>) -> Self {
+    intersections.sort_unstable_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+
+    Intersections {
+      data: intersections,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.data.is_empty()
+  }
+
+  pub fn hit(&self) -> Option<&Intersection> {
+    self.data.iter().find(|intersection| intersection.t > 0.0)
+  }
+
+  /// Refractive indices of the materials the ray leaves and enters at the
+  /// given intersection.
+  ///
+  /// Bodies are tracked while the ray passes them in order, so overlapping
+  /// bodies, like water in a glass, get the right pair of indices.
+  pub fn refractive_indices(&self, hit: &Intersection) -> (F, F) {
+    let mut containers: Vec<Body> = vec![];
+    let index_of_innermost = |containers: &Vec<Body>| {
+      containers
+        .last()
+        .map(|body| body.material().refractive_index())
+        .unwrap_or(1.0)
+    };
+
+    for intersection in self.data.iter() {
+      let is_hit = intersection == hit;
+      let n1 = index_of_innermost(&containers);
+
+      match containers
+        .iter()
+        .position(|body| *body == intersection.body)
+      {
+        Some(position) => {
+          containers.remove(position);
+        }
+        None => containers.push(intersection.body.clone()),
+      }
+
+      if is_hit {
+        return (n1, index_of_innermost(&containers));
+      }
+    }
+
+    (1.0, 1.0)
+  }
+
+  /// The hit, ignoring bodies which do not cast shadows
+  pub fn shadow_hit(&self) -> Option<&Intersection> {
+    self
+      .data
+      .iter()
+      .find(|intersection| intersection.t > 0.0 && intersection.body.shadows().cast)
+  }
+}
+
+impl From<Vec<Intersection>> for Intersections {
+  fn from(v: Vec<Intersection>) -> Self {
+    Self::new(v)
+  }
+}
+
+impl Index<usize> for Intersections {
+  type Output = Intersection;
+  fn index(&self, index: usize) -> &Self::Output {
+    &self.data[index]
+  }
+}
+
+impl IntoIterator for Intersections {
+  type Item = Intersection;
+  type IntoIter = std::vec::IntoIter<Self::Item>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.data.into_iter()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::*;
+  use crate::material::{Bump, Material, Phong};
+  use crate::matrix::Matrix;
+  use crate::plane::Plane;
+  use crate::sphere::Sphere;
+  use crate::test_scene::glass;
+  use crate::triangle::SmoothTriangle;
+  use crate::tuple::Tuple;
+
+  #[test]
+  fn the_hit_when_all_intersections_have_positive_t() {
+    let s = Sphere::default();
+
+    let r = Ray::new(Tuple::point(1.0, 1.0, 1.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let i1 = Intersection::new(1.0, r, Body::from(s.clone()));
+    let i2 = Intersection::new(2.0, r, Body::from(s));
+
+    let xs = Intersections::new(vec![i2, i1.clone()]);
+
+    assert_eq!(xs.hit(), Some(&i1));
+  }
+
+  #[test]
+  fn the_hit_when_some_intersections_have_negative_t() {
+    let s = Sphere::default();
+
+    let r = Ray::new(Tuple::point(1.0, 1.0, 1.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let i1 = Intersection::new(-1.0, r, Body::from(s.clone()));
+    let i2 = Intersection::new(1.0, r, Body::from(s));
+
+    let xs = Intersections::new(vec![i2.clone(), i1]);
+
+    assert_eq!(xs.hit(), Some(&i2));
+  }
+
+  #[test]
+  fn the_hit_when_all_intersections_have_negative_t() {
+    let s = Sphere::default();
+
+    let r = Ray::new(Tuple::point(1.0, 1.0, 1.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let i1 = Intersection::new(-2.0, r, Body::from(s.clone()));
+    let i2 = Intersection::new(-1.0, r, Body::from(s));
+
+    let xs = Intersections::new(vec![i2, i1]);
+
+    assert_eq!(xs.hit(), None);
+  }
+
+  #[test]
+  fn the_shadow_hit_skips_bodies_not_casting_shadows() {
+    let s = Sphere::default();
+    let glass = Sphere::default().with_shadows(Shadows {
+      cast: false,
+      ..Default::default()
+    });
+
+    let r = Ray::new(Tuple::point(1.0, 1.0, 1.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let i1 = Intersection::new(1.0, r, Body::from(glass));
+    let i2 = Intersection::new(2.0, r, Body::from(s));
+
+    let xs = Intersections::new(vec![i2.clone(), i1.clone()]);
+
+    assert_eq!(xs.hit(), Some(&i1));
+    assert_eq!(xs.shadow_hit(), Some(&i2));
+  }
+
+  #[test]
+  fn precomputing_the_state_of_an_intersection() {
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let body = Body::from(Sphere::default());
+    let i = Intersection::new(4.0, r, body);
+    let c = i.get_computed();
+
+    assert_eq!(c.intersection, &i);
+    assert_fuzzy_eq!(c.point, Tuple::point(0.0, 0.0, -1.0));
+    assert_fuzzy_eq!(c.eyev, Tuple::vector(0.0, 0.0, -1.0));
+    assert_fuzzy_eq!(c.normalv, Tuple::vector(0.0, 0.0, -1.0));
+  }
+
+  #[test]
+  fn precomputing_reflection_vector() {
+    let body = Body::from(Plane::default());
+    let r = Ray::new(
+      Tuple::point(0.0, 1.0, -1.0),
+      Tuple::vector(0.0, -(2.0 as F).sqrt() / 2.0, (2.0 as F).sqrt() / 2.0),
+    );
+    let intersection = Intersection::new((2.0 as F).sqrt(), r, body);
+
+    let computations = intersection.get_computed();
+
+    assert_fuzzy_eq!(
+      computations.reflectv,
+      Tuple::vector(0.0, (2.0 as F).sqrt() / 2.0, (2.0 as F).sqrt() / 2.0)
+    );
+  }
+
+  #[test]
+  fn the_hit_when_an_intersection_occurs_on_the_outside() {
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let body = Body::from(Sphere::default());
+    let i = Intersection::new(4.0, r, body);
+    let c = i.get_computed();
+
+    assert!(!c.inside);
+  }
+
+  #[test]
+  fn the_hit_when_an_intersection_occurs_on_the_inside() {
+    let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+    let body = Body::from(Sphere::default());
+    let i = Intersection::new(1.0, r, body);
+    let c = i.get_computed();
+
+    assert!(c.inside);
+    assert_eq!(c.normalv, Tuple::vector(0.0, 0.0, -1.0));
+  }
+
+  #[test]
+  fn the_hit_should_offset_the_point() {
+    let material = Material::default();
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let s1 = Sphere::new(material, Matrix::translation(0.0, 0.0, 1.0));
+    let i = Intersection::new(5.0, r, s1.into());
+    let c = i.get_computed();
+    assert!(c.over_point.z < -crate::EPSILON / 2.0);
+    assert!(c.point.z > c.over_point.z);
+  }
+
+  #[test]
+  fn the_under_point_is_offset_below_the_surface() {
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let s1 = Sphere::default()
+      .with_transform(Matrix::translation(0.0, 0.0, 1.0))
+      .with_material(Material::from(glass()));
+    let i = Intersection::new(5.0, r, s1.into());
+    let c = i.get_computed();
+    assert!(c.under_point.z > crate::EPSILON / 2.0);
+    assert!(c.point.z < c.under_point.z);
+  }
+
+  #[test]
+  fn the_points_are_offset_by_the_shadow_bias_of_the_material() {
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let material = Phong::default().with_shadow_bias(0.01);
+    let s = Sphere::default().with_material(Material::from(material));
+    let i = Intersection::new(4.0, r, s.into());
+    let c = i.get_computed();
+
+    assert_fuzzy_eq!(c.over_point, Tuple::point(0.0, 0.0, -1.01));
+    assert_fuzzy_eq!(c.under_point, Tuple::point(0.0, 0.0, -0.99));
+  }
+
+  #[test]
+  fn bumps_change_the_shading_normal_but_not_the_offset_points() {
+    let r = Ray::new(Tuple::point(0.3, 0.2, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let bumpy = Phong::default().with_bump(Bump::noise(0.1, 0.5));
+    let smooth = Body::from(Sphere::default());
+    let bumped = Body::from(Sphere::default().with_material(Material::from(bumpy)));
+    let t = smooth.intersect(r).hit().unwrap().t;
+
+    let smooth_hit = Intersection::new(t, r, smooth);
+    let bumped_hit = Intersection::new(t, r, bumped);
+    let expected = smooth_hit.get_computed();
+    let c = bumped_hit.get_computed();
+
+    assert_fuzzy_ne!(c.normalv, expected.normalv);
+    assert_fuzzy_eq!(c.over_point, expected.over_point);
+    assert_fuzzy_eq!(c.under_point, expected.under_point);
+    assert_fuzzy_eq!(c.reflectv, r.direction.reflect(c.normalv));
+  }
+
+  #[test]
+  fn smooth_triangles_offset_the_points_along_their_face() {
+    let triangle = SmoothTriangle::new(
+      Tuple::point(0.0, 1.0, 0.0),
+      Tuple::point(-1.0, 0.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+      Tuple::vector(-1.0, 0.0, 0.0),
+      Tuple::vector(1.0, 0.0, 0.0),
+    );
+    let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+    let i = Intersection::new(2.0, r, Body::from(triangle));
+
+    let c = i.get_computed();
+
+    assert_fuzzy_eq!(c.geometric_normalv, Tuple::vector(0.0, 0.0, -1.0));
+    assert_fuzzy_ne!(c.normalv, c.geometric_normalv);
+    assert_fuzzy_eq!(c.over_point, Tuple::point(-0.2, 0.3, -crate::EPSILON));
+    assert_fuzzy_eq!(c.under_point, Tuple::point(-0.2, 0.3, crate::EPSILON));
+  }
+
+  fn glass_sphere(refractive_index: F, transform: Matrix<4>) -> Body {
+    Body::from(
+      Sphere::default()
+        .with_transform(transform)
+        .with_material(Material::from(
+          glass().with_refractive_index(refractive_index),
+        )),
+    )
+  }
+
+  #[test]
+  fn refractive_indices_at_various_intersections() {
+    let a = glass_sphere(1.5, Matrix::scaling(2.0, 2.0, 2.0));
+    let b = glass_sphere(2.0, Matrix::translation(0.0, 0.0, -0.25));
+    let c = glass_sphere(2.5, Matrix::translation(0.0, 0.0, 0.25));
+    let r = Ray::new(Tuple::point(0.0, 0.0, -4.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = Intersections::new(vec![
+      Intersection::new(2.0, r, a.clone()),
+      Intersection::new(2.75, r, b.clone()),
+      Intersection::new(3.25, r, c.clone()),
+      Intersection::new(4.75, r, b),
+      Intersection::new(5.25, r, c),
+      Intersection::new(6.0, r, a),
+    ]);
+
+    let expected = [
+      (1.0, 1.5),
+      (1.5, 2.0),
+      (2.0, 2.5),
+      (2.5, 2.5),
+      (2.5, 1.5),
+      (1.5, 1.0),
+    ];
+    for (index, (n1, n2)) in expected.iter().enumerate() {
+      let (actual_n1, actual_n2) = xs.refractive_indices(&xs[index]);
+      assert_fuzzy_eq!(actual_n1, *n1);
+      assert_fuzzy_eq!(actual_n2, *n2);
+    }
+  }
+
+  #[test]
+  fn computing_a_hit_in_a_bubble_inside_a_marble() {
+    let marble = glass_sphere(1.5, Matrix::scaling(2.0, 2.0, 2.0));
+    let bubble = glass_sphere(1.0, Matrix::identity());
+    let r = Ray::new(Tuple::point(0.0, 0.0, -4.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = Intersections::new(vec![
+      Intersection::new(2.0, r, marble.clone()),
+      Intersection::new(3.0, r, bubble.clone()),
+      Intersection::new(5.0, r, bubble),
+      Intersection::new(6.0, r, marble),
+    ]);
+
+    let into_bubble = xs[1].get_computed_with(&xs);
+    let out_of_bubble = xs[2].get_computed_with(&xs);
+
+    assert_fuzzy_eq!(into_bubble.n1, 1.5);
+    assert_fuzzy_eq!(into_bubble.n2, 1.0);
+    assert_fuzzy_eq!(out_of_bubble.n1, 1.0);
+    assert_fuzzy_eq!(out_of_bubble.n2, 1.5);
+  }
+
+  #[test]
+  fn the_refracted_direction_bends_towards_the_normal() {
+    let body = glass_sphere(1.5, Matrix::identity());
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = Intersections::new(vec![Intersection::new(4.0, r, body)]);
+
+    // Perpendicular rays pass straight through
+    let direction = xs[0].get_computed_with(&xs).refracted_direction();
+
+    assert_fuzzy_eq!(direction.unwrap(), Tuple::vector(0.0, 0.0, 1.0));
+  }
+
+  #[test]
+  fn the_schlick_approximation_under_total_internal_reflection() {
+    let body = glass_sphere(1.5, Matrix::identity());
+    let r = Ray::new(
+      Tuple::point(0.0, 0.0, std::f64::consts::SQRT_2 / 2.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    );
+    let xs = Intersections::new(vec![
+      Intersection::new(-std::f64::consts::SQRT_2 / 2.0, r, body.clone()),
+      Intersection::new(std::f64::consts::SQRT_2 / 2.0, r, body),
+    ]);
+
+    let c = xs[1].get_computed_with(&xs);
+
+    assert!(c.refracted_direction().is_none());
+    assert_fuzzy_eq!(c.schlick(), 1.0);
+  }
+
+  #[test]
+  fn the_schlick_approximation_with_a_perpendicular_viewing_angle() {
+    let body = glass_sphere(1.5, Matrix::identity());
+    let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+    let xs = Intersections::new(vec![
+      Intersection::new(-1.0, r, body.clone()),
+      Intersection::new(1.0, r, body),
+    ]);
+
+    assert_fuzzy_eq!(xs[1].get_computed_with(&xs).schlick(), 0.04);
+  }
+
+  #[test]
+  fn the_schlick_approximation_with_small_angle_and_n2_greater_n1() {
+    let body = glass_sphere(1.5, Matrix::identity());
+    let r = Ray::new(Tuple::point(0.0, 0.99, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = Intersections::new(vec![Intersection::new(1.8589, r, body)]);
+
+    assert_fuzzy_eq!(xs[0].get_computed_with(&xs).schlick(), 0.48873);
+  }
+}
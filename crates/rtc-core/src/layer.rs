@@ -0,0 +1,85 @@
+use std::vec::Vec;
+
+/// Name of the layer everything without a layer of its own belongs to
+pub const DEFAULT_LAYER: &str = "default";
+
+/// Indices of the bodies, instances, moving bodies and lights of a world
+/// belonging to a layer, to render the layers separately for compositing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Layer {
+  pub bodies: Vec<usize>,
+  pub instances: Vec<usize>,
+  pub moving_bodies: Vec<usize>,
+  pub lights: Vec<usize>,
+}
+
+impl Layer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_bodies(mut self, indices: impl IntoIterator<Item = usize>) -> Self {
+    self.bodies.extend(indices);
+    self
+  }
+
+  pub fn with_instances(mut self, indices: impl IntoIterator<Item = usize>) -> Self {
+    self.instances.extend(indices);
+    self
+  }
+
+  pub fn with_moving_bodies(mut self, indices: impl IntoIterator<Item = usize>) -> Self {
+    self.moving_bodies.extend(indices);
+    self
+  }
+
+  pub fn with_lights(mut self, indices: impl IntoIterator<Item = usize>) -> Self {
+    self.lights.extend(indices);
+    self
+  }
+
+  /// Adds the members of the other layer to this one
+  pub fn merge(&mut self, other: Layer) {
+    self.bodies.extend(other.bodies);
+    self.instances.extend(other.instances);
+    self.moving_bodies.extend(other.moving_bodies);
+    self.lights.extend(other.lights);
+  }
+}
+
+/// New index of every old one, `None` for those not kept
+pub(crate) fn reindex(keep: &[bool]) -> Vec<Option<usize>> {
+  let mut next = 0;
+  keep
+    .iter()
+    .map(|kept| {
+      if *kept {
+        next += 1;
+        Some(next - 1)
+      } else {
+        None
+      }
+    })
+    .collect()
+}
+
+/// Indices after reindexing, leaving out the ones not kept
+pub(crate) fn remap(indices: &[usize], new_indices: &[Option<usize>]) -> Vec<usize> {
+  indices
+    .iter()
+    .filter_map(|index| new_indices.get(*index).copied().flatten())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn indices_are_remapped_to_the_kept_elements() {
+    let new_indices = reindex(&[true, false, true, true]);
+
+    assert_eq!(new_indices, vec![Some(0), None, Some(1), Some(2)]);
+    assert_eq!(remap(&[3, 1, 0], &new_indices), vec![2, 0]);
+  }
+}
@@ -0,0 +1,120 @@
+//! Only `angle`, `color`, `matrix`, `ray` and `tuple` are available without the
+//! default `std` feature, which allows using the core math with `no_std` and
+//! `alloc`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[macro_use]
+pub mod fuzzy_eq;
+#[cfg(not(any(feature = "std", test)))]
+mod float;
+
+pub type F = f64;
+pub const EPSILON: f64 = 0.00001;
+
+pub mod angle;
+#[cfg(feature = "animator")]
+pub mod animator;
+#[cfg(feature = "std")]
+pub mod backdrop;
+#[cfg(feature = "std")]
+pub mod bake;
+#[cfg(feature = "std")]
+pub mod body;
+#[cfg(feature = "std")]
+pub mod bounds;
+#[cfg(feature = "std")]
+pub mod camera;
+#[cfg(feature = "std")]
+pub mod canvas;
+pub mod color;
+#[cfg(feature = "std")]
+pub mod computed_intersection;
+#[cfg(feature = "std")]
+pub mod cone;
+#[cfg(feature = "std")]
+pub mod contact_shadows;
+#[cfg(feature = "std")]
+pub mod cylinder;
+#[cfg(feature = "std")]
+pub mod explain;
+#[cfg(feature = "std")]
+pub mod extrusion;
+#[cfg(feature = "std")]
+pub mod failure;
+#[cfg(feature = "std")]
+pub mod fingerprint;
+#[cfg(feature = "std")]
+pub mod font;
+#[cfg(feature = "std")]
+pub mod frustum;
+#[cfg(feature = "std")]
+pub mod instance;
+#[cfg(feature = "std")]
+pub mod intersections;
+#[cfg(feature = "std")]
+pub mod layer;
+#[cfg(feature = "std")]
+pub mod light;
+#[cfg(feature = "std")]
+pub mod material;
+#[cfg(feature = "std")]
+pub mod material_study;
+pub mod matrix;
+#[cfg(feature = "std")]
+pub mod mesh;
+#[cfg(feature = "std")]
+pub mod mirrored;
+#[cfg(feature = "std")]
+pub mod motion;
+#[cfg(feature = "std")]
+pub mod numerics;
+#[cfg(feature = "std")]
+pub mod pattern;
+#[cfg(feature = "std")]
+pub mod photon_map;
+#[cfg(feature = "std")]
+pub mod plane;
+#[cfg(feature = "std")]
+pub mod post_processing;
+#[cfg(feature = "std")]
+pub mod probe;
+#[cfg(feature = "std")]
+pub mod quad;
+pub mod ray;
+#[cfg(feature = "std")]
+pub mod ray_diagram;
+#[cfg(feature = "std")]
+pub mod renderer;
+#[cfg(feature = "std")]
+pub mod repeated;
+#[cfg(feature = "std")]
+pub mod sampling;
+#[cfg(feature = "std")]
+pub mod scatter;
+#[cfg(feature = "std")]
+pub mod sdf;
+#[cfg(feature = "std")]
+pub mod sphere;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod sweep;
+#[cfg(feature = "std")]
+pub mod tessellation;
+#[cfg(all(test, feature = "std"))]
+mod test_scene;
+#[cfg(feature = "std")]
+pub mod text;
+#[cfg(feature = "std")]
+pub mod texture;
+#[cfg(feature = "std")]
+pub mod triangle;
+#[cfg(feature = "std")]
+pub mod tube;
+pub mod tuple;
+#[cfg(feature = "std")]
+pub mod uv;
+#[cfg(feature = "std")]
+pub mod world;
@@ -0,0 +1,239 @@
+use crate::canvas::Color;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::tuple::*;
+use crate::F;
+
+/// Factor a light intensity is scaled by for the given exposure value (EV).
+///
+/// Each stop doubles the intensity, so +1 EV is twice and -1 EV half as bright.
+pub fn ev_to_scale(ev: F) -> F {
+  (2.0 as F).powf(ev)
+}
+
+/// Exposure value (EV) in stops corresponding to the given intensity factor.
+pub fn scale_to_ev(scale: F) -> F {
+  scale.log2()
+}
+
+/// Direction from a lit point towards (a part of) a light source
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightSample {
+  /// Normalized vector pointing from the lit point to the light
+  pub direction: Tuple,
+  /// Distance the light is away along the direction, shadow rays are only
+  /// blocked by bodies in between
+  pub distance: F,
+}
+
+/// Any light source of a world
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Light {
+  Point(PointLight),
+}
+
+impl Light {
+  /// Intensity of the light arriving at the given point
+  pub fn intensity_at(&self, point: Tuple) -> Color {
+    match *self {
+      Light::Point(ref light) => {
+        let distance = (light.position - point).magnitude();
+        light.intensity * light.attenuation.factor(distance)
+      }
+    }
+  }
+
+  /// Directions the light arrives from at the given point, a single one for
+  /// lights without any extent. Lighting is averaged over all of them.
+  pub fn sample_directions(&self, point: Tuple) -> Vec<LightSample> {
+    match *self {
+      Light::Point(ref light) => {
+        let vector = light.position - point;
+        vec![LightSample {
+          direction: vector.normalize(),
+          distance: vector.magnitude(),
+        }]
+      }
+    }
+  }
+
+  /// Point the light is emitted from
+  pub fn position(&self) -> Tuple {
+    match *self {
+      Light::Point(ref light) => light.position,
+    }
+  }
+
+  /// Scales the intensity by the given stops relative to its current value
+  pub fn with_ev(self, ev: F) -> Self {
+    match self {
+      Light::Point(light) => Light::Point(light.with_ev(ev)),
+    }
+  }
+}
+
+impl From<PointLight> for Light {
+  fn from(light: PointLight) -> Self {
+    Light::Point(light)
+  }
+}
+
+impl FuzzyEq<Light> for Light {
+  fn fuzzy_eq(&self, other: Light) -> bool {
+    match (self, other) {
+      (Light::Point(ref light), Light::Point(other)) => light.fuzzy_eq(other),
+    }
+  }
+}
+
+/// Falloff of a light with the distance, dividing its intensity by
+/// `constant + linear * d + quadratic * d²`. Physically correct is a purely
+/// quadratic falloff, the default keeps the intensity constant.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attenuation {
+  pub constant: F,
+  pub linear: F,
+  pub quadratic: F,
+}
+
+impl Default for Attenuation {
+  fn default() -> Self {
+    Self::new(1.0, 0.0, 0.0)
+  }
+}
+
+impl Attenuation {
+  pub fn new(constant: F, linear: F, quadratic: F) -> Self {
+    Self {
+      constant,
+      linear,
+      quadratic,
+    }
+  }
+
+  /// Factor the intensity is scaled by at the given distance
+  pub fn factor(&self, distance: F) -> F {
+    let divisor = self.constant + self.linear * distance + self.quadratic * distance * distance;
+    if divisor <= 0.0 {
+      return 1.0;
+    }
+    1.0 / divisor
+  }
+}
+
+impl FuzzyEq<Attenuation> for Attenuation {
+  fn fuzzy_eq(&self, other: Attenuation) -> bool {
+    self.constant.fuzzy_eq(other.constant)
+      && self.linear.fuzzy_eq(other.linear)
+      && self.quadratic.fuzzy_eq(other.quadratic)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointLight {
+  pub position: Tuple,
+  pub intensity: Color,
+  pub attenuation: Attenuation,
+}
+
+impl Default for PointLight {
+  fn default() -> Self {
+    Self::new(Tuple::point(0.0, 0.0, 0.0), Color::white())
+  }
+}
+
+impl PointLight {
+  pub fn new(position: Tuple, intensity: Color) -> Self {
+    PointLight {
+      position,
+      intensity,
+      attenuation: Attenuation::default(),
+    }
+  }
+
+  pub fn with_attenuation(mut self, attenuation: Attenuation) -> Self {
+    self.attenuation = attenuation;
+    self
+  }
+
+  /// Scales the intensity by the given stops relative to its current value
+  pub fn with_ev(mut self, ev: F) -> Self {
+    self.intensity = self.intensity * ev_to_scale(ev);
+    self
+  }
+}
+
+impl FuzzyEq<PointLight> for PointLight {
+  fn fuzzy_eq(&self, other: PointLight) -> bool {
+    self.position.fuzzy_eq(other.position)
+      && self.intensity.fuzzy_eq(other.intensity)
+      && self.attenuation.fuzzy_eq(other.attenuation)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn a_point_light_has_a_position_and_intensity() {
+    let intensity = Color::new(1.0, 1.0, 1.0);
+    let position = Tuple::point(0.0, 0.0, 0.0);
+    let l = PointLight::new(position, intensity);
+
+    assert_fuzzy_eq!(l.position, position);
+    assert_fuzzy_eq!(l.intensity, intensity);
+  }
+
+  #[test]
+  fn ev_stops_double_the_intensity() {
+    assert_fuzzy_eq!(ev_to_scale(0.0), 1.0);
+    assert_fuzzy_eq!(ev_to_scale(2.0), 4.0);
+    assert_fuzzy_eq!(ev_to_scale(-1.0), 0.5);
+    assert_fuzzy_eq!(scale_to_ev(8.0), 3.0);
+    assert_fuzzy_eq!(scale_to_ev(ev_to_scale(-1.5)), -1.5);
+  }
+
+  #[test]
+  fn lights_are_balanced_in_ev() {
+    let key = PointLight::new(Tuple::point(0.0, 0.0, 0.0), Color::new(1.0, 0.9, 0.8));
+    let fill = key.with_ev(-2.0);
+
+    assert_fuzzy_eq!(fill.intensity, Color::new(0.25, 0.225, 0.2));
+  }
+
+  #[test]
+  fn attenuated_lights_fall_off_with_the_distance() {
+    let light = Light::from(
+      PointLight::new(Tuple::point(0.0, 10.0, 0.0), Color::white())
+        .with_attenuation(Attenuation::new(1.0, 0.0, 0.01)),
+    );
+
+    assert_fuzzy_eq!(
+      light.intensity_at(Tuple::point(0.0, 0.0, 0.0)),
+      Color::new(0.5, 0.5, 0.5)
+    );
+    assert_fuzzy_eq!(
+      light.intensity_at(Tuple::point(0.0, 10.0, 0.0)),
+      Color::white()
+    );
+    assert_fuzzy_eq!(Attenuation::default().factor(100.0), 1.0);
+  }
+
+  #[test]
+  fn a_point_light_arrives_from_a_single_direction() {
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 10.0, 0.0),
+      Color::new(0.5, 0.5, 0.5),
+    ));
+    let point = Tuple::point(0.0, 0.0, 0.0);
+
+    assert_fuzzy_eq!(light.intensity_at(point), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(
+      light.sample_directions(point),
+      vec![LightSample {
+        direction: Tuple::vector(0.0, 1.0, 0.0),
+        distance: 10.0,
+      }]
+    );
+  }
+}
@@ -0,0 +1,837 @@
+pub mod bump;
+pub mod coat;
+pub mod cutout;
+pub mod lambert;
+pub mod layered;
+pub mod registry;
+
+use crate::body::Body;
+use crate::canvas::Color;
+use crate::fuzzy_eq::*;
+use crate::light::Light;
+use crate::pattern::{Pattern, Stencil};
+use crate::tuple::Tuple;
+use crate::{EPSILON, F};
+pub use bump::Bump;
+pub use coat::Coat;
+pub use cutout::Cutout;
+pub use lambert::Lambert;
+pub use layered::{LayerMask, Layered};
+pub use registry::MaterialRegistry;
+
+pub trait Illuminated {
+  /// Ambient, diffuse and specular light at the given position
+  fn lighting_terms(
+    &self,
+    body: &Body,
+    light: Light,
+    position: Tuple,
+    eyev: Tuple,
+    normalv: Tuple,
+    in_shadow: bool,
+  ) -> LightingTerms;
+
+  fn lighting(
+    &self,
+    body: &Body,
+    light: Light,
+    position: Tuple,
+    eyev: Tuple,
+    normalv: Tuple,
+    in_shadow: bool,
+  ) -> Color {
+    self
+      .lighting_terms(body, light, position, eyev, normalv, in_shadow)
+      .total()
+  }
+
+  /// Ambient part of the lighting at the given position
+  fn ambient(&self, body: &Body, light: Light, position: Tuple) -> Color;
+}
+
+pub trait Reflective {
+  fn reflectiveness(&self) -> F;
+
+  /// Reflectiveness at the given position and viewing angle, for materials
+  /// varying across the surface or with the angle.
+  fn reflectiveness_at(&self, _body: &Body, _position: Tuple, _eyev: Tuple, _normalv: Tuple) -> F {
+    self.reflectiveness()
+  }
+}
+
+pub trait Refractive {
+  /// Fraction of the light passing through the surface
+  fn transparency(&self) -> F;
+  fn refractive_index(&self) -> F;
+
+  /// Transparency at the given position, for materials varying across the
+  /// surface.
+  fn transparency_at(&self, _body: &Body, _position: Tuple) -> F {
+    self.transparency()
+  }
+}
+
+/// The terms of the Phong reflection model, which sum up to the light at a
+/// position.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LightingTerms {
+  pub ambient: Color,
+  pub diffuse: Color,
+  pub specular: Color,
+}
+
+impl LightingTerms {
+  pub fn total(&self) -> Color {
+    self.ambient + self.diffuse + self.specular
+  }
+
+  /// Direct light dimmed by the fraction passing the way to the light, the
+  /// ambient light stays as it is
+  pub fn dimmed(self, transmittance: F) -> Self {
+    LightingTerms {
+      ambient: self.ambient,
+      diffuse: self.diffuse * transmittance,
+      specular: self.specular * transmittance,
+    }
+  }
+
+  /// Just the terms of the given pass, all others are black
+  pub fn only(self, pass: LightingPass) -> Self {
+    let keep = |term: LightingPass, color: Color| {
+      if pass == LightingPass::All || pass == term {
+        color
+      } else {
+        Color::black()
+      }
+    };
+    LightingTerms {
+      ambient: keep(LightingPass::Ambient, self.ambient),
+      diffuse: keep(LightingPass::Diffuse, self.diffuse),
+      specular: keep(LightingPass::Specular, self.specular),
+    }
+  }
+}
+
+/// Lighting terms rendered, to tune materials one term at a time
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum LightingPass {
+  #[default]
+  All,
+  Ambient,
+  Diffuse,
+  Specular,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Material {
+  Phong(Phong),
+  Layered(Layered),
+  Lambert(Lambert),
+}
+
+impl From<Phong> for Material {
+  fn from(phong: Phong) -> Self {
+    Material::Phong(phong)
+  }
+}
+
+impl From<Layered> for Material {
+  fn from(layered: Layered) -> Self {
+    Material::Layered(layered)
+  }
+}
+
+impl From<Lambert> for Material {
+  fn from(lambert: Lambert) -> Self {
+    Material::Lambert(lambert)
+  }
+}
+
+impl Default for Material {
+  fn default() -> Self {
+    Material::from(Phong::default())
+  }
+}
+
+impl Material {
+  /// Bump of the surface, layers lie on top of the bumps of their base
+  pub fn bump(&self) -> Option<&Bump> {
+    match *self {
+      Material::Phong(ref m) => m.bump.as_ref(),
+      Material::Layered(ref m) => m.base.bump(),
+      Material::Lambert(_) => None,
+    }
+  }
+
+  /// Opacity mask of the surface, layers are cut out along with their base
+  pub fn cutout(&self) -> Option<&Cutout> {
+    match *self {
+      Material::Phong(ref m) => m.cutout.as_ref(),
+      Material::Layered(ref m) => m.base.cutout(),
+      Material::Lambert(_) => None,
+    }
+  }
+
+  /// Offset of the points shadow and secondary rays start from, layers use
+  /// the bias of their base
+  pub fn shadow_bias(&self) -> F {
+    match *self {
+      Material::Phong(ref m) => m.shadow_bias,
+      Material::Layered(ref m) => m.base.shadow_bias(),
+      Material::Lambert(ref m) => m.shadow_bias,
+    }
+  }
+
+  /// Normal used for shading, which is the surface normal perturbed by the
+  /// bump of the material
+  pub fn shading_normal(&self, body: &Body, position: Tuple, normalv: Tuple) -> Tuple {
+    match self.bump() {
+      Some(bump) => bump.perturb(body, position, normalv),
+      None => normalv,
+    }
+  }
+}
+
+impl FuzzyEq<Material> for Material {
+  fn fuzzy_eq(&self, other: Material) -> bool {
+    match (self, other) {
+      (Material::Phong(ref m), Material::Phong(other)) => m.fuzzy_eq(other),
+      (Material::Layered(ref m), Material::Layered(other)) => m.fuzzy_eq(other),
+      (Material::Lambert(ref m), Material::Lambert(other)) => m.fuzzy_eq(other),
+      _ => false,
+    }
+  }
+}
+
+impl Illuminated for Material {
+  fn lighting_terms(
+    &self,
+    body: &Body,
+    light: Light,
+    position: Tuple,
+    eyev: Tuple,
+    normalv: Tuple,
+    in_shadow: bool,
+  ) -> LightingTerms {
+    match *self {
+      Material::Phong(ref m) => m.lighting_terms(body, light, position, eyev, normalv, in_shadow),
+      Material::Layered(ref m) => m.lighting_terms(body, light, position, eyev, normalv, in_shadow),
+      Material::Lambert(ref m) => m.lighting_terms(body, light, position, eyev, normalv, in_shadow),
+    }
+  }
+
+  fn ambient(&self, body: &Body, light: Light, position: Tuple) -> Color {
+    match *self {
+      Material::Phong(ref m) => m.ambient(body, light, position),
+      Material::Layered(ref m) => m.ambient(body, light, position),
+      Material::Lambert(ref m) => m.ambient(body, light, position),
+    }
+  }
+}
+
+impl Reflective for Material {
+  fn reflectiveness(&self) -> F {
+    match *self {
+      Material::Phong(ref m) => m.reflectiveness(),
+      Material::Layered(ref m) => m.reflectiveness(),
+      Material::Lambert(ref m) => m.reflectiveness(),
+    }
+  }
+
+  fn reflectiveness_at(&self, body: &Body, position: Tuple, eyev: Tuple, normalv: Tuple) -> F {
+    match *self {
+      Material::Phong(ref m) => m.reflectiveness_at(body, position, eyev, normalv),
+      Material::Layered(ref m) => m.reflectiveness_at(body, position, eyev, normalv),
+      Material::Lambert(ref m) => m.reflectiveness_at(body, position, eyev, normalv),
+    }
+  }
+}
+
+impl Refractive for Material {
+  fn transparency(&self) -> F {
+    match *self {
+      Material::Phong(ref m) => m.transparency(),
+      Material::Layered(ref m) => m.transparency(),
+      Material::Lambert(ref m) => m.transparency(),
+    }
+  }
+
+  fn refractive_index(&self) -> F {
+    match *self {
+      Material::Phong(ref m) => m.refractive_index(),
+      Material::Layered(ref m) => m.refractive_index(),
+      Material::Lambert(ref m) => m.refractive_index(),
+    }
+  }
+
+  fn transparency_at(&self, body: &Body, position: Tuple) -> F {
+    match *self {
+      Material::Phong(ref m) => m.transparency_at(body, position),
+      Material::Layered(ref m) => m.transparency_at(body, position),
+      Material::Lambert(ref m) => m.transparency_at(body, position),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Phong {
+  pub color: Color,
+  pub pattern: Option<Pattern>,
+  pub ambient: F,
+  pub diffuse: F,
+  pub specular: F,
+  pub shininess: F,
+  pub reflectiveness: F,
+  pub transparency: F,
+  pub refractive_index: F,
+  pub coat: Option<Coat>,
+  pub bump: Option<Bump>,
+  pub cutout: Option<Cutout>,
+  /// Distance shadow, reflection and refraction rays start off the surface,
+  /// to not hit it again due to rounding errors
+  pub shadow_bias: F,
+}
+
+impl Default for Phong {
+  fn default() -> Self {
+    Phong {
+      color: Color::new(1.0, 1.0, 1.0),
+      pattern: None,
+      ambient: 0.1,
+      diffuse: 0.9,
+      specular: 0.9,
+      shininess: 200.0,
+      reflectiveness: 0.0,
+      transparency: 0.0,
+      refractive_index: 1.0,
+      coat: None,
+      bump: None,
+      cutout: None,
+      shadow_bias: EPSILON,
+    }
+  }
+}
+
+impl Phong {
+  /// Neutral matte gray, to judge lighting and composition of a scene
+  /// independent of its materials
+  pub fn clay() -> Self {
+    Phong::default()
+      .with_color(Color::new(0.7, 0.7, 0.7))
+      .with_specular(0.0)
+  }
+
+  pub fn with_color(mut self, color: Color) -> Self {
+    self.color = color;
+    self
+  }
+
+  pub fn with_ambient(mut self, ambient: F) -> Self {
+    self.ambient = ambient;
+    self
+  }
+
+  pub fn with_diffuse(mut self, diffuse: F) -> Self {
+    self.diffuse = diffuse;
+    self
+  }
+
+  pub fn with_specular(mut self, specular: F) -> Self {
+    self.specular = specular;
+    self
+  }
+
+  pub fn with_shininess(mut self, shininess: F) -> Self {
+    self.shininess = shininess;
+    self
+  }
+
+  pub fn with_pattern(mut self, pattern: Pattern) -> Self {
+    self.pattern = Some(pattern);
+    self
+  }
+
+  pub fn with_reflectiveness(mut self, reflectiveness: F) -> Self {
+    self.reflectiveness = reflectiveness;
+    self
+  }
+
+  pub fn with_transparency(mut self, transparency: F) -> Self {
+    self.transparency = transparency;
+    self
+  }
+
+  pub fn with_refractive_index(mut self, refractive_index: F) -> Self {
+    self.refractive_index = refractive_index;
+    self
+  }
+
+  pub fn with_coat(mut self, coat: Coat) -> Self {
+    self.coat = Some(coat);
+    self
+  }
+
+  pub fn with_bump(mut self, bump: Bump) -> Self {
+    self.bump = Some(bump);
+    self
+  }
+
+  pub fn with_cutout(mut self, cutout: Cutout) -> Self {
+    self.cutout = Some(cutout);
+    self
+  }
+
+  pub fn with_shadow_bias(mut self, shadow_bias: F) -> Self {
+    self.shadow_bias = shadow_bias;
+    self
+  }
+}
+
+impl Phong {
+  /// Lists every parameter outside of its sensible range.
+  ///
+  /// Ambient, diffuse, specular, reflectiveness and transparency are
+  /// fractions within [0, 1], while the shininess needs to be positive and
+  /// the refractive index at least 1. Out of range values
+  /// still render, but rarely the way they were meant to.
+  pub fn validate(&self) -> Vec<String> {
+    let fractions = [
+      ("ambient", self.ambient),
+      ("diffuse", self.diffuse),
+      ("specular", self.specular),
+      ("reflectiveness", self.reflectiveness),
+      ("transparency", self.transparency),
+    ];
+
+    let mut problems: Vec<String> = fractions
+      .iter()
+      .filter(|(_, value)| !(0.0..=1.0).contains(value))
+      .map(|(name, value)| format!("{} needs to be within [0, 1], but is {}", name, value))
+      .collect();
+
+    if self.shininess <= 0.0 || !self.shininess.is_finite() {
+      problems.push(format!(
+        "shininess needs to be positive, but is {}",
+        self.shininess
+      ));
+    }
+
+    if self.refractive_index < 1.0 || !self.refractive_index.is_finite() {
+      problems.push(format!(
+        "refractive_index needs to be at least 1, but is {}",
+        self.refractive_index
+      ));
+    }
+
+    if self.shadow_bias <= 0.0 || !self.shadow_bias.is_finite() {
+      problems.push(format!(
+        "shadow_bias needs to be positive, but is {}",
+        self.shadow_bias
+      ));
+    }
+
+    if self.color.red < 0.0 || self.color.green < 0.0 || self.color.blue < 0.0 {
+      problems.push(format!(
+        "color must not be negative, but is {:?}",
+        self.color
+      ));
+    }
+
+    if let Some(coat) = self.coat {
+      if !(0.0..=1.0).contains(&coat.reflectiveness) {
+        problems.push(format!(
+          "coat reflectiveness needs to be within [0, 1], but is {}",
+          coat.reflectiveness
+        ));
+      }
+      if !(0.0..=1.0).contains(&coat.roughness) {
+        problems.push(format!(
+          "coat roughness needs to be within [0, 1], but is {}",
+          coat.roughness
+        ));
+      }
+    }
+
+    problems
+  }
+
+  /// Surface color at the given position, as seen under the given light
+  fn effective_color(&self, body: &Body, light: Light, position: Tuple) -> Color {
+    let color = match self.pattern {
+      Some(ref pattern) => pattern.color_at(position, body),
+      None => self.color,
+    };
+    color * light.intensity_at(position)
+  }
+}
+
+impl FuzzyEq<Phong> for Phong {
+  fn fuzzy_eq(&self, other: Phong) -> bool {
+    self.color.fuzzy_eq(other.color)
+      && self.ambient.fuzzy_eq(other.ambient)
+      && self.diffuse.fuzzy_eq(other.diffuse)
+      && self.specular.fuzzy_eq(other.specular)
+      && self.shininess.fuzzy_eq(other.shininess)
+      && self.pattern.fuzzy_eq(other.pattern)
+      && self.transparency.fuzzy_eq(other.transparency)
+      && self.refractive_index.fuzzy_eq(other.refractive_index)
+      && self.coat.fuzzy_eq(other.coat)
+      && self.bump.fuzzy_eq(other.bump)
+      && self.cutout.fuzzy_eq(other.cutout)
+      && self.shadow_bias.fuzzy_eq(other.shadow_bias)
+  }
+}
+
+impl Illuminated for Phong {
+  fn lighting_terms(
+    &self,
+    body: &Body,
+    light: Light,
+    position: Tuple,
+    eyev: Tuple,
+    normalv: Tuple,
+    in_shadow: bool,
+  ) -> LightingTerms {
+    let mut diffuse_light = Color::black();
+    let mut specular_light = Color::black();
+    let mut highlight = Color::black();
+
+    let effective_color = self.effective_color(body, light, position);
+    let intensity = light.intensity_at(position);
+
+    let ambient_light = self.ambient(body, light, position);
+
+    if in_shadow {
+      return LightingTerms {
+        ambient: ambient_light,
+        diffuse: Color::black(),
+        specular: Color::black(),
+      };
+    }
+
+    let samples = light.sample_directions(position);
+    for sample in samples.iter() {
+      let lightv = sample.direction;
+      let light_dot_normal = lightv.dot(normalv);
+      if light_dot_normal < 0.0 {
+        // Light is on the other side of the surface
+        continue;
+      }
+
+      // Light is on the side the surface is pointing to.
+      diffuse_light = diffuse_light + effective_color * self.diffuse * light_dot_normal;
+
+      let reflectv = -lightv.reflect(normalv);
+      let reflect_dot_eye = reflectv.dot(eyev);
+      if reflect_dot_eye > 0.0 {
+        let factor = reflect_dot_eye.powf(self.shininess);
+        specular_light = specular_light + intensity * self.specular * factor;
+      }
+
+      if let Some(coat) = self.coat {
+        highlight = highlight + coat.highlight(intensity, lightv, eyev, normalv);
+      }
+    }
+    let share = 1.0 / samples.len().max(1) as F;
+
+    match self.coat {
+      None => LightingTerms {
+        ambient: ambient_light,
+        diffuse: diffuse_light * share,
+        specular: specular_light * share,
+      },
+      // The highlight of the coat counts as specular light
+      Some(coat) => LightingTerms {
+        ambient: ambient_light,
+        diffuse: coat.transmitted(diffuse_light * share, eyev, normalv),
+        specular: coat.transmitted(specular_light * share, eyev, normalv) + highlight * share,
+      },
+    }
+  }
+
+  fn ambient(&self, body: &Body, light: Light, position: Tuple) -> Color {
+    let ambient_light = self.effective_color(body, light, position) * self.ambient;
+    match self.coat {
+      None => ambient_light,
+      Some(coat) => ambient_light * coat.tint,
+    }
+  }
+}
+
+impl Reflective for Phong {
+  fn reflectiveness(&self) -> F {
+    self.reflectiveness
+  }
+
+  /// The coat reflects its Fresnel fraction, the base the rest of the light
+  fn reflectiveness_at(&self, _body: &Body, _position: Tuple, eyev: Tuple, normalv: Tuple) -> F {
+    match self.coat {
+      None => self.reflectiveness,
+      Some(coat) => {
+        let fresnel = coat.fresnel(eyev, normalv);
+        fresnel + self.reflectiveness * (1.0 - fresnel)
+      }
+    }
+  }
+}
+
+impl Refractive for Phong {
+  fn transparency(&self) -> F {
+    self.transparency
+  }
+
+  fn refractive_index(&self) -> F {
+    self.refractive_index
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::light::PointLight;
+  use crate::sphere::Sphere;
+
+  use super::*;
+
+  #[test]
+  fn default_phong_material() {
+    let m = Phong::default();
+
+    assert_fuzzy_eq!(m.color, Color::new(1.0, 1.0, 1.0));
+    assert_fuzzy_eq!(m.ambient, 0.1);
+    assert_fuzzy_eq!(m.diffuse, 0.9);
+    assert_fuzzy_eq!(m.specular, 0.9);
+    assert_fuzzy_eq!(m.shininess, 200.0);
+  }
+
+  #[test]
+  fn phong_material_can_be_constructed_with_builder() {
+    let color = Color::new(1.0, 1.0, 0.0);
+    let ambient = 0.05;
+    let diffuse = 0.7;
+    let specular = 0.95;
+    let shininess = 400.0;
+
+    let m = Phong::default()
+      .with_color(color)
+      .with_ambient(ambient)
+      .with_diffuse(diffuse)
+      .with_specular(specular)
+      .with_shininess(shininess);
+
+    assert_fuzzy_eq!(m.color, color);
+    assert_fuzzy_eq!(m.ambient, ambient);
+    assert_fuzzy_eq!(m.diffuse, diffuse);
+    assert_fuzzy_eq!(m.specular, specular);
+    assert_fuzzy_eq!(m.shininess, shininess);
+  }
+
+  #[test]
+  fn phong_parameters_are_validated() {
+    assert!(Phong::default().validate().is_empty());
+
+    let m = Phong::default()
+      .with_specular(1.8)
+      .with_ambient(-0.1)
+      .with_shininess(0.0)
+      .with_shadow_bias(-0.5);
+
+    assert_eq!(
+      m.validate(),
+      vec![
+        "ambient needs to be within [0, 1], but is -0.1",
+        "specular needs to be within [0, 1], but is 1.8",
+        "shininess needs to be positive, but is 0",
+        "shadow_bias needs to be positive, but is -0.5",
+      ]
+    );
+  }
+
+  #[test]
+  fn lighting_with_the_eye_between_the_light_and_the_surface() {
+    let m = Phong::default();
+    let body = Body::from(Sphere::default());
+    let position = Tuple::point(0.0, 0.0, 0.0);
+
+    let eyev = Tuple::vector(0.0, 0.0, -1.0);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 0.0, -10.0),
+      Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let actual_result = m.lighting(&body, light, position, eyev, normalv, false);
+
+    let expected_result = Color::new(1.9, 1.9, 1.9);
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn lighting_terms_of_the_eye_between_the_light_and_the_surface() {
+    let m = Phong::default();
+    let body = Body::from(Sphere::default());
+    let position = Tuple::point(0.0, 0.0, 0.0);
+    let eyev = Tuple::vector(0.0, 0.0, -1.0);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 0.0, -10.0),
+      Color::white(),
+    ));
+
+    let terms = m.lighting_terms(&body, light, position, eyev, normalv, false);
+
+    assert_fuzzy_eq!(terms.ambient, Color::new(0.1, 0.1, 0.1));
+    assert_fuzzy_eq!(terms.diffuse, Color::new(0.9, 0.9, 0.9));
+    assert_fuzzy_eq!(terms.specular, Color::new(0.9, 0.9, 0.9));
+    assert_fuzzy_eq!(
+      terms.only(LightingPass::Diffuse).total(),
+      Color::new(0.9, 0.9, 0.9)
+    );
+    assert_fuzzy_eq!(terms.only(LightingPass::All).total(), terms.total());
+  }
+
+  #[test]
+  fn coated_lighting_terms_sum_up_to_the_lighting() {
+    let m = Phong::default().with_coat(Coat::new(0.04, 0.1));
+    let body = Body::from(Sphere::default());
+    let position = Tuple::point(0.0, 0.0, 0.0);
+    let eyev = Tuple::vector(0.0, 1.0, -1.0).normalize();
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 10.0, -10.0),
+      Color::white(),
+    ));
+
+    let terms = m.lighting_terms(&body, light, position, eyev, normalv, false);
+    let coat = m.coat.unwrap();
+    let lightv = light.sample_directions(position)[0].direction;
+    let uncoated = Phong::default().lighting_terms(&body, light, position, eyev, normalv, false);
+    let expected = uncoated.ambient
+      + coat.cover(
+        uncoated.diffuse + uncoated.specular,
+        Color::white(),
+        lightv,
+        eyev,
+        normalv,
+      );
+
+    assert_fuzzy_eq!(terms.total(), expected);
+  }
+
+  #[test]
+  fn lighting_with_the_eye_between_the_light_and_the_surface_eye_offset_by_45_degrees() {
+    let m = Phong::default();
+    let body = Body::from(Sphere::default());
+    let position = Tuple::point(0.0, 0.0, 0.0);
+
+    let sqrt2_over_2 = (2.0 as F).sqrt() / 2.0;
+    let eyev = Tuple::vector(0.0, sqrt2_over_2, -sqrt2_over_2);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 0.0, -10.0),
+      Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let actual_result = m.lighting(&body, light, position, eyev, normalv, false);
+
+    let expected_result = Color::new(1.0, 1.0, 1.0);
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn lighting_with_the_eye_opposite_surface_light_offset_by_45_degrees() {
+    let m = Phong::default();
+    let body = Body::from(Sphere::default());
+    let position = Tuple::point(0.0, 0.0, 0.0);
+
+    let eyev = Tuple::vector(0.0, 0.0, -1.0);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 10.0, -10.0),
+      Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let actual_result = m.lighting(&body, light, position, eyev, normalv, false);
+
+    let expected_result = Color::new(0.7364, 0.7364, 0.7364);
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn lighting_with_the_eye_in_path_of_the_reflection_vector() {
+    let m = Phong::default();
+    let body = Body::from(Sphere::default());
+    let position = Tuple::point(0.0, 0.0, 0.0);
+
+    let sqrt2_over_2 = (2.0 as F).sqrt() / 2.0;
+    let eyev = Tuple::vector(0.0, -sqrt2_over_2, -sqrt2_over_2);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 10.0, -10.0),
+      Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let actual_result = m.lighting(&body, light, position, eyev, normalv, false);
+
+    let expected_result = Color::new(1.6364, 1.6364, 1.6364);
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn lighting_with_light_behind_the_surface() {
+    let m = Phong::default();
+    let body = Body::from(Sphere::default());
+    let position = Tuple::point(0.0, 0.0, 0.0);
+
+    let eyev = Tuple::vector(0.0, 0.0, -1.0);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 0.0, 10.0),
+      Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let actual_result = m.lighting(&body, light, position, eyev, normalv, false);
+
+    let expected_result = Color::new(0.1, 0.1, 0.1);
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn lighting_with_the_surface_in_shadow() {
+    let m = Phong::default();
+    let body = Body::from(Sphere::default());
+    let position = Tuple::point(0.0, 0.0, 0.0);
+
+    let eyev = Tuple::vector(0.0, 0.0, -1.0);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 0.0, -10.0),
+      Color::new(1.0, 1.0, 1.0),
+    ));
+
+    let actual_result = m.lighting(&body, light, position, eyev, normalv, true);
+
+    let expected_result = Color::new(0.1, 0.1, 0.1);
+
+    assert_fuzzy_eq!(actual_result, expected_result);
+  }
+
+  #[test]
+  fn phong_material_has_reflective_zero_by_default() {
+    let m = Phong::default();
+
+    assert_fuzzy_eq!(0.0, m.reflectiveness);
+  }
+
+  #[test]
+  fn phong_material_has_builder_function_for_reflectiveness() {
+    let m = Phong::default().with_reflectiveness(0.42);
+
+    assert_fuzzy_eq!(0.42, m.reflectiveness);
+  }
+}
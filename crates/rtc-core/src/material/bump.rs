@@ -0,0 +1,151 @@
+use crate::body::{Body, Intersectable};
+use crate::fuzzy_eq::FuzzyEq;
+use crate::pattern::Stencil;
+use crate::texture::Texture;
+use crate::tuple::Tuple;
+use crate::F;
+
+/// Perturbs the shading normal of a surface, which adds detail without any
+/// additional geometry. Silhouettes and shadows stay smooth.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Bump {
+  /// Procedural bumps repeating every `scale` units, tilting the normal by
+  /// up to `strength`
+  Noise { scale: F, strength: F },
+  /// Normals read from an image in tangent space: red and green tilt along
+  /// the texture's u and v axes, blue points away from the surface
+  NormalMap { texture: Texture, strength: F },
+}
+
+impl Bump {
+  pub fn noise(scale: F, strength: F) -> Self {
+    Bump::Noise { scale, strength }
+  }
+
+  pub fn normal_map(texture: Texture) -> Self {
+    Bump::NormalMap {
+      texture,
+      strength: 1.0,
+    }
+  }
+
+  /// Shading normal at the given position of the body
+  pub fn perturb(&self, body: &Body, position: Tuple, normalv: Tuple) -> Tuple {
+    let perturbed = match *self {
+      Bump::Noise { scale, strength } => {
+        // Slope of sin(x) * sin(y) * sin(z) in object space, transformed
+        // like a normal and flattened onto the surface
+        let p = (body.inverse_transform() * position) * (1.0 / scale);
+        let slope = Tuple::vector(
+          p.x.cos() * p.y.sin() * p.z.sin(),
+          p.x.sin() * p.y.cos() * p.z.sin(),
+          p.x.sin() * p.y.sin() * p.z.cos(),
+        );
+        let mut slope = body.inverse_transform().transpose() * slope;
+        slope.w = 0.0;
+        let slope = slope - normalv * slope.dot(normalv);
+        normalv - slope * strength
+      }
+      Bump::NormalMap {
+        ref texture,
+        strength,
+      } => {
+        let (tangent, bitangent) = tangent_frame(body, normalv);
+        let color = texture.color_at(position, body);
+        let u = (color.red * 2.0 - 1.0) * strength;
+        let v = (color.green * 2.0 - 1.0) * strength;
+        let n = (color.blue * 2.0 - 1.0).max(0.0);
+        tangent * u + bitangent * v + normalv * n
+      }
+    };
+
+    if perturbed.magnitude() <= crate::EPSILON {
+      return normalv;
+    }
+    perturbed.normalize()
+  }
+}
+
+/// Two vectors perpendicular to the normal and each other, following the x
+/// and z axes of the body as close as possible
+fn tangent_frame(body: &Body, normalv: Tuple) -> (Tuple, Tuple) {
+  let mut axis = body.transform() * Tuple::vector(1.0, 0.0, 0.0);
+  if axis.normalize().cross(normalv).magnitude() < 0.1 {
+    axis = body.transform() * Tuple::vector(0.0, 0.0, 1.0);
+  }
+  let tangent = (axis - normalv * axis.dot(normalv)).normalize();
+  (tangent, tangent.cross(normalv))
+}
+
+impl FuzzyEq<Bump> for Bump {
+  fn fuzzy_eq(&self, other: Bump) -> bool {
+    match (self, other) {
+      (
+        Bump::Noise { scale, strength },
+        Bump::Noise {
+          scale: other_scale,
+          strength: other_strength,
+        },
+      ) => scale.fuzzy_eq(other_scale) && strength.fuzzy_eq(other_strength),
+      (
+        Bump::NormalMap { texture, strength },
+        Bump::NormalMap {
+          texture: other_texture,
+          strength: other_strength,
+        },
+      ) => texture.fuzzy_eq(other_texture) && strength.fuzzy_eq(other_strength),
+      _ => false,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::{Canvas, Color};
+  use crate::plane::Plane;
+  use crate::sphere::Sphere;
+
+  #[test]
+  fn noise_tilts_the_normal_but_keeps_it_facing_outwards() {
+    let sphere = Body::from(Sphere::default());
+    let position = Tuple::point(0.6, 0.0, -0.8);
+    let normalv = sphere.normal_at(position);
+
+    let perturbed = Bump::noise(0.1, 0.3).perturb(&sphere, position, normalv);
+
+    assert_fuzzy_eq!(perturbed.magnitude(), 1.0);
+    assert_fuzzy_ne!(perturbed, normalv);
+    assert!(perturbed.dot(normalv) > 0.5);
+  }
+
+  #[test]
+  fn a_flat_normal_map_keeps_the_normal() {
+    let mut image = Canvas::new(1, 1);
+    image.write_pixel(0, 0, Color::new(0.5, 0.5, 1.0));
+    let plane = Body::from(Plane::default());
+    let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+    let perturbed =
+      Bump::normal_map(Texture::new(image)).perturb(&plane, Tuple::point(0.3, 0.0, 0.7), normalv);
+
+    assert_fuzzy_eq!(perturbed, normalv);
+  }
+
+  #[test]
+  fn a_normal_map_tilts_along_the_texture_axes() {
+    let mut image = Canvas::new(1, 1);
+    image.write_pixel(0, 0, Color::new(1.0, 0.5, 1.0));
+    let plane = Body::from(Plane::default());
+
+    let perturbed = Bump::normal_map(Texture::new(image)).perturb(
+      &plane,
+      Tuple::point(0.3, 0.0, 0.7),
+      Tuple::vector(0.0, 1.0, 0.0),
+    );
+
+    let sqrt2_2 = (2.0 as F).sqrt() / 2.0;
+    assert_fuzzy_eq!(perturbed, Tuple::vector(sqrt2_2, sqrt2_2, 0.0));
+  }
+}
@@ -0,0 +1,136 @@
+use crate::canvas::Color;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::tuple::Tuple;
+use crate::F;
+
+/// Clear coat layered over a material, like the varnish on wood or car paint.
+///
+/// The coat reflects more light at grazing angles (Fresnel effect) and lets
+/// the remaining light through to the base material, tinted by its color.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Coat {
+  /// Reflectance when looking straight at the surface
+  pub reflectiveness: F,
+  /// Spread of the highlight of the coat between 0 (sharp) and 1 (blurry)
+  pub roughness: F,
+  pub tint: Color,
+}
+
+impl Default for Coat {
+  fn default() -> Self {
+    Self {
+      reflectiveness: 0.04,
+      roughness: 0.05,
+      tint: Color::white(),
+    }
+  }
+}
+
+impl Coat {
+  pub fn new(reflectiveness: F, roughness: F) -> Self {
+    Self {
+      reflectiveness,
+      roughness,
+      ..Default::default()
+    }
+  }
+
+  pub fn with_tint(mut self, tint: Color) -> Self {
+    self.tint = tint;
+    self
+  }
+
+  /// Fraction of light reflected by the coat (Schlick's approximation)
+  pub fn fresnel(&self, eyev: Tuple, normalv: Tuple) -> F {
+    let cos = eyev.dot(normalv).clamp(0.0, 1.0);
+    self.reflectiveness + (1.0 - self.reflectiveness) * (1.0 - cos).powi(5)
+  }
+
+  /// Exponent of the highlight derived from the roughness
+  fn shininess(&self) -> F {
+    let roughness = self.roughness.clamp(0.01, 1.0);
+    2.0 / roughness.powi(2) - 2.0
+  }
+
+  /// Direct light of the base material seen through the coat plus the
+  /// highlight of the coat itself.
+  pub fn cover(
+    &self,
+    base: Color,
+    intensity: Color,
+    lightv: Tuple,
+    eyev: Tuple,
+    normalv: Tuple,
+  ) -> Color {
+    self.transmitted(base, eyev, normalv) + self.highlight(intensity, lightv, eyev, normalv)
+  }
+
+  /// Light of the base material passing through the coat
+  pub fn transmitted(&self, base: Color, eyev: Tuple, normalv: Tuple) -> Color {
+    base * self.tint * (1.0 - self.fresnel(eyev, normalv))
+  }
+
+  /// Highlight of the light source on the coat itself
+  pub fn highlight(&self, intensity: Color, lightv: Tuple, eyev: Tuple, normalv: Tuple) -> Color {
+    if lightv.dot(normalv) <= 0.0 {
+      return Color::black();
+    }
+
+    let halfway = (lightv + eyev).normalize();
+    let highlight = halfway.dot(normalv).max(0.0).powf(self.shininess());
+    intensity * self.fresnel(eyev, normalv) * highlight
+  }
+}
+
+impl FuzzyEq<Coat> for Coat {
+  fn fuzzy_eq(&self, other: Coat) -> bool {
+    self.reflectiveness.fuzzy_eq(other.reflectiveness)
+      && self.roughness.fuzzy_eq(other.roughness)
+      && self.tint.fuzzy_eq(other.tint)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fresnel_increases_at_grazing_angles() {
+    let coat = Coat::new(0.04, 0.1);
+    let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+    assert_fuzzy_eq!(coat.fresnel(Tuple::vector(0.0, 1.0, 0.0), normalv), 0.04);
+    assert_fuzzy_eq!(coat.fresnel(Tuple::vector(1.0, 0.0, 0.0), normalv), 1.0);
+    let half = Tuple::vector(1.0, 1.0, 0.0).normalize();
+    assert!(coat.fresnel(half, normalv) > 0.04);
+    assert!(coat.fresnel(half, normalv) < 0.1);
+  }
+
+  #[test]
+  fn coat_tints_the_base_and_adds_a_highlight() {
+    let coat = Coat::new(0.04, 0.1).with_tint(Color::new(1.0, 0.5, 0.5));
+    let normalv = Tuple::vector(0.0, 1.0, 0.0);
+    let lightv = Tuple::vector(0.0, 1.0, 0.0);
+    let eyev = Tuple::vector(0.0, 1.0, 0.0);
+
+    let result = coat.cover(Color::white(), Color::white(), lightv, eyev, normalv);
+
+    assert_fuzzy_eq!(result, Color::new(1.0, 0.52, 0.52));
+  }
+
+  #[test]
+  fn coat_has_no_highlight_from_behind() {
+    let coat = Coat::new(0.04, 0.1);
+    let normalv = Tuple::vector(0.0, 1.0, 0.0);
+
+    let result = coat.cover(
+      Color::black(),
+      Color::white(),
+      Tuple::vector(0.0, -1.0, 0.0),
+      normalv,
+      normalv,
+    );
+
+    assert_fuzzy_eq!(result, Color::black());
+  }
+}
@@ -0,0 +1,70 @@
+use crate::body::Body;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::pattern::{Pattern, Stencil};
+use crate::tuple::Tuple;
+use crate::F;
+use std::sync::Arc;
+
+/// Holes cut into a surface by an opacity mask, like the outline of a leaf
+/// painted onto a flat card.
+///
+/// The brightness of the mask is the opacity. Where it falls below the
+/// threshold there is no surface at all: camera, reflected and shadow rays
+/// pass right through.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cutout {
+  pub mask: Arc<Pattern>,
+  pub threshold: F,
+}
+
+impl Cutout {
+  pub fn new(mask: Pattern) -> Self {
+    Self {
+      mask: Arc::new(mask),
+      threshold: 0.5,
+    }
+  }
+
+  pub fn with_threshold(mut self, threshold: F) -> Self {
+    self.threshold = threshold;
+    self
+  }
+
+  /// Opacity of the mask at the given position of the body
+  pub fn opacity_at(&self, body: &Body, position: Tuple) -> F {
+    let color = self.mask.color_at(position, body);
+    (color.red + color.green + color.blue) / 3.0
+  }
+
+  /// Whether the surface is cut away at the given position of the body
+  pub fn is_cut_out(&self, body: &Body, position: Tuple) -> bool {
+    self.opacity_at(body, position) < self.threshold
+  }
+}
+
+impl FuzzyEq<Cutout> for Cutout {
+  fn fuzzy_eq(&self, other: Cutout) -> bool {
+    self.mask.fuzzy_eq(Pattern::clone(&other.mask)) && self.threshold.fuzzy_eq(other.threshold)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::Color;
+  use crate::pattern::Striped;
+  use crate::sphere::Sphere;
+
+  #[test]
+  fn surfaces_are_cut_out_where_the_mask_is_dark() {
+    let mask = Pattern::from(Striped::default().with_colors(Color::white(), Color::black()));
+    let cutout = Cutout::new(mask);
+    let body = Body::from(Sphere::default());
+
+    assert!(!cutout.is_cut_out(&body, Tuple::point(0.5, 0.0, 0.0)));
+    assert!(cutout.is_cut_out(&body, Tuple::point(1.5, 0.0, 0.0)));
+    assert!(!cutout
+      .with_threshold(0.0)
+      .is_cut_out(&body, Tuple::point(1.5, 0.0, 0.0)));
+  }
+}
@@ -0,0 +1,166 @@
+use super::{Illuminated, LightingTerms, Reflective, Refractive};
+use crate::body::Body;
+use crate::canvas::Color;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::light::Light;
+use crate::pattern::{Pattern, Stencil};
+use crate::tuple::Tuple;
+use crate::{EPSILON, F};
+
+/// Matte material with only ambient and diffuse light.
+///
+/// Without highlights, reflections and refractions it is cheap to shade,
+/// which suits quick previews and passes only interested in the shape.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lambert {
+  pub color: Color,
+  pub pattern: Option<Pattern>,
+  pub ambient: F,
+  pub diffuse: F,
+  /// Offset of the points shadow rays start from, see `Phong::shadow_bias`
+  pub shadow_bias: F,
+}
+
+impl Default for Lambert {
+  fn default() -> Self {
+    Lambert {
+      color: Color::white(),
+      pattern: None,
+      ambient: 0.1,
+      diffuse: 0.9,
+      shadow_bias: EPSILON,
+    }
+  }
+}
+
+impl Lambert {
+  pub fn with_color(mut self, color: Color) -> Self {
+    self.color = color;
+    self
+  }
+
+  pub fn with_pattern(mut self, pattern: Pattern) -> Self {
+    self.pattern = Some(pattern);
+    self
+  }
+
+  pub fn with_ambient(mut self, ambient: F) -> Self {
+    self.ambient = ambient;
+    self
+  }
+
+  pub fn with_diffuse(mut self, diffuse: F) -> Self {
+    self.diffuse = diffuse;
+    self
+  }
+
+  pub fn with_shadow_bias(mut self, shadow_bias: F) -> Self {
+    self.shadow_bias = shadow_bias;
+    self
+  }
+
+  fn effective_color(&self, body: &Body, light: Light, position: Tuple) -> Color {
+    let color = match self.pattern {
+      Some(ref pattern) => pattern.color_at(position, body),
+      None => self.color,
+    };
+    color * light.intensity_at(position)
+  }
+}
+
+impl FuzzyEq<Lambert> for Lambert {
+  fn fuzzy_eq(&self, other: Lambert) -> bool {
+    self.color.fuzzy_eq(other.color)
+      && self.pattern.fuzzy_eq(other.pattern)
+      && self.ambient.fuzzy_eq(other.ambient)
+      && self.diffuse.fuzzy_eq(other.diffuse)
+      && self.shadow_bias.fuzzy_eq(other.shadow_bias)
+  }
+}
+
+impl Illuminated for Lambert {
+  fn lighting_terms(
+    &self,
+    body: &Body,
+    light: Light,
+    position: Tuple,
+    _eyev: Tuple,
+    normalv: Tuple,
+    in_shadow: bool,
+  ) -> LightingTerms {
+    let samples = light.sample_directions(position);
+    let diffuse = if in_shadow || samples.is_empty() {
+      Color::black()
+    } else {
+      let light_dot_normal: F = samples
+        .iter()
+        .map(|sample| sample.direction.dot(normalv).max(0.0))
+        .sum::<F>()
+        / samples.len() as F;
+      self.effective_color(body, light, position) * self.diffuse * light_dot_normal
+    };
+
+    LightingTerms {
+      ambient: self.ambient(body, light, position),
+      diffuse,
+      specular: Color::black(),
+    }
+  }
+
+  fn ambient(&self, body: &Body, light: Light, position: Tuple) -> Color {
+    self.effective_color(body, light, position) * self.ambient
+  }
+}
+
+impl Reflective for Lambert {
+  fn reflectiveness(&self) -> F {
+    0.0
+  }
+}
+
+impl Refractive for Lambert {
+  fn transparency(&self) -> F {
+    0.0
+  }
+
+  fn refractive_index(&self) -> F {
+    1.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::light::PointLight;
+  use crate::material::Phong;
+  use crate::sphere::Sphere;
+
+  #[test]
+  fn lambert_matches_phong_without_highlights() {
+    let lambert = Lambert::default().with_color(Color::new(1.0, 0.2, 0.2));
+    let phong = Phong::default()
+      .with_color(Color::new(1.0, 0.2, 0.2))
+      .with_specular(0.0);
+    let body = Body::from(Sphere::default());
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 10.0, -10.0),
+      Color::white(),
+    ));
+    let position = Tuple::point(0.0, 0.0, -1.0);
+    let eyev = Tuple::vector(0.0, 0.0, -1.0);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+
+    for in_shadow in [false, true].iter() {
+      assert_fuzzy_eq!(
+        lambert.lighting(&body, light, position, eyev, normalv, *in_shadow),
+        phong.lighting(&body, light, position, eyev, normalv, *in_shadow)
+      );
+    }
+    assert_fuzzy_eq!(
+      lambert
+        .lighting_terms(&body, light, position, eyev, normalv, false)
+        .specular,
+      Color::black()
+    );
+  }
+}
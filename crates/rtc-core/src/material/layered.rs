@@ -0,0 +1,240 @@
+use super::{Illuminated, LightingTerms, Material, Reflective, Refractive};
+use crate::body::Body;
+use crate::canvas::Color;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::light::Light;
+use crate::pattern::{Pattern, Stencil};
+use crate::tuple::Tuple;
+use crate::F;
+
+/// Defines how much of the layer covers the base material.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayerMask {
+  /// Constant coverage between 0 (only base) and 1 (only layer)
+  Factor(F),
+  /// Coverage given by the luminance of the pattern at each position
+  Pattern(Pattern),
+}
+
+impl FuzzyEq<LayerMask> for LayerMask {
+  fn fuzzy_eq(&self, other: LayerMask) -> bool {
+    match (self, other) {
+      (LayerMask::Factor(ref factor), LayerMask::Factor(other)) => factor.fuzzy_eq(other),
+      (LayerMask::Pattern(ref pattern), LayerMask::Pattern(other)) => pattern.fuzzy_eq(other),
+      _ => false,
+    }
+  }
+}
+
+/// Blend of two materials, e.g. matte rust masked by a pattern over metal.
+///
+/// Both materials may be of any kind, including other layered materials.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layered {
+  pub base: Box<Material>,
+  pub layer: Box<Material>,
+  pub mask: LayerMask,
+}
+
+impl Layered {
+  pub fn new(base: Material, layer: Material, mask: LayerMask) -> Self {
+    Self {
+      base: Box::new(base),
+      layer: Box::new(layer),
+      mask,
+    }
+  }
+
+  /// Coverage of the layer at the given position
+  pub fn weight_at(&self, body: &Body, position: Tuple) -> F {
+    let weight = match self.mask {
+      LayerMask::Factor(factor) => factor,
+      LayerMask::Pattern(ref pattern) => pattern.color_at(position, body).luminance(),
+    };
+    weight.clamp(0.0, 1.0)
+  }
+
+  fn blend(&self, weight: F, base: Color, layer: Color) -> Color {
+    base * (1.0 - weight) + layer * weight
+  }
+}
+
+impl FuzzyEq<Layered> for Layered {
+  fn fuzzy_eq(&self, other: Layered) -> bool {
+    self.base.fuzzy_eq(*other.base)
+      && self.layer.fuzzy_eq(*other.layer)
+      && self.mask.fuzzy_eq(other.mask)
+  }
+}
+
+impl Illuminated for Layered {
+  fn lighting_terms(
+    &self,
+    body: &Body,
+    light: Light,
+    position: Tuple,
+    eyev: Tuple,
+    normalv: Tuple,
+    in_shadow: bool,
+  ) -> LightingTerms {
+    let weight = self.weight_at(body, position);
+    let base = self
+      .base
+      .lighting_terms(body, light, position, eyev, normalv, in_shadow);
+    let layer = self
+      .layer
+      .lighting_terms(body, light, position, eyev, normalv, in_shadow);
+    LightingTerms {
+      ambient: self.blend(weight, base.ambient, layer.ambient),
+      diffuse: self.blend(weight, base.diffuse, layer.diffuse),
+      specular: self.blend(weight, base.specular, layer.specular),
+    }
+  }
+
+  fn ambient(&self, body: &Body, light: Light, position: Tuple) -> Color {
+    self.blend(
+      self.weight_at(body, position),
+      self.base.ambient(body, light, position),
+      self.layer.ambient(body, light, position),
+    )
+  }
+}
+
+impl Reflective for Layered {
+  /// Reflectiveness independent of the position.
+  ///
+  /// With a pattern mask this is the larger one of both materials.
+  fn reflectiveness(&self) -> F {
+    match self.mask {
+      LayerMask::Factor(factor) => {
+        let weight = factor.clamp(0.0, 1.0);
+        self.base.reflectiveness() * (1.0 - weight) + self.layer.reflectiveness() * weight
+      }
+      LayerMask::Pattern(_) => self.base.reflectiveness().max(self.layer.reflectiveness()),
+    }
+  }
+
+  fn reflectiveness_at(&self, body: &Body, position: Tuple, eyev: Tuple, normalv: Tuple) -> F {
+    let weight = self.weight_at(body, position);
+    self.base.reflectiveness_at(body, position, eyev, normalv) * (1.0 - weight)
+      + self.layer.reflectiveness_at(body, position, eyev, normalv) * weight
+  }
+}
+
+impl Refractive for Layered {
+  /// Transparency independent of the position, like the reflectiveness
+  fn transparency(&self) -> F {
+    match self.mask {
+      LayerMask::Factor(factor) => {
+        let weight = factor.clamp(0.0, 1.0);
+        self.base.transparency() * (1.0 - weight) + self.layer.transparency() * weight
+      }
+      LayerMask::Pattern(_) => self.base.transparency().max(self.layer.transparency()),
+    }
+  }
+
+  /// Light is bent by the base material, the layer is considered too thin
+  fn refractive_index(&self) -> F {
+    self.base.refractive_index()
+  }
+
+  fn transparency_at(&self, body: &Body, position: Tuple) -> F {
+    let weight = self.weight_at(body, position);
+    self.base.transparency_at(body, position) * (1.0 - weight)
+      + self.layer.transparency_at(body, position) * weight
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::light::PointLight;
+  use crate::material::{Lambert, Phong};
+  use crate::pattern::Striped;
+  use crate::sphere::Sphere;
+
+  fn materials() -> (Phong, Phong) {
+    let base = Phong::default()
+      .with_color(Color::red())
+      .with_reflectiveness(0.8);
+    let layer = Phong::default().with_color(Color::blue());
+    (base, layer)
+  }
+
+  #[test]
+  fn factor_blends_lighting_of_both_materials() {
+    let (base, layer) = materials();
+    let m = Layered::new(
+      Material::from(base.clone()),
+      Material::from(layer.clone()),
+      LayerMask::Factor(0.25),
+    );
+    let body = Body::from(Sphere::default());
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 0.0, -10.0),
+      Color::white(),
+    ));
+    let position = Tuple::point(0.0, 0.0, -1.0);
+    let eyev = Tuple::vector(0.0, 0.0, -1.0);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+
+    let expected = base.lighting(&body, light, position, eyev, normalv, false) * 0.75
+      + layer.lighting(&body, light, position, eyev, normalv, false) * 0.25;
+
+    assert_fuzzy_eq!(
+      m.lighting(&body, light, position, eyev, normalv, false),
+      expected
+    );
+    assert_fuzzy_eq!(m.reflectiveness(), 0.6);
+  }
+
+  #[test]
+  fn other_kinds_of_materials_are_layered_as_well() {
+    let base = Material::from(Lambert::default().with_color(Color::red()));
+    let layer = Material::from(Phong::default().with_transparency(0.5));
+    let m = Layered::new(base.clone(), layer.clone(), LayerMask::Factor(0.5));
+    let body = Body::from(Sphere::default());
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 0.0, -10.0),
+      Color::white(),
+    ));
+    let position = Tuple::point(0.0, 0.0, -1.0);
+    let eyev = Tuple::vector(0.0, 0.0, -1.0);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+
+    let expected = base.lighting(&body, light, position, eyev, normalv, false) * 0.5
+      + layer.lighting(&body, light, position, eyev, normalv, false) * 0.5;
+
+    assert_fuzzy_eq!(
+      m.lighting(&body, light, position, eyev, normalv, false),
+      expected
+    );
+    assert_fuzzy_eq!(m.transparency_at(&body, position), 0.25);
+  }
+
+  #[test]
+  fn pattern_mask_selects_material_per_position() {
+    let (base, layer) = materials();
+    let mask = Pattern::from(Striped::default().with_colors(Color::black(), Color::white()));
+    let m = Layered::new(
+      Material::from(base),
+      Material::from(layer),
+      LayerMask::Pattern(mask),
+    );
+    let body = Body::from(Sphere::default());
+
+    assert_fuzzy_eq!(m.weight_at(&body, Tuple::point(0.5, 0.0, 0.0)), 0.0);
+    assert_fuzzy_eq!(m.weight_at(&body, Tuple::point(1.5, 0.0, 0.0)), 1.0);
+    let v = Tuple::vector(0.0, 0.0, -1.0);
+    assert_fuzzy_eq!(
+      m.reflectiveness_at(&body, Tuple::point(0.5, 0.0, 0.0), v, v),
+      0.8
+    );
+    assert_fuzzy_eq!(
+      m.reflectiveness_at(&body, Tuple::point(1.5, 0.0, 0.0), v, v),
+      0.0
+    );
+    assert_fuzzy_eq!(m.reflectiveness(), 0.8);
+  }
+}
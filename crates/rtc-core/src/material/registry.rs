@@ -0,0 +1,80 @@
+use super::Material;
+use crate::fuzzy_eq::FuzzyEq;
+use std::collections::HashMap;
+
+/// Materials shared by name between bodies, like "steel" or "floor".
+///
+/// The world keeps track of the bodies referencing each name, so replacing
+/// an entry restyles all of them at once.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MaterialRegistry {
+  materials: HashMap<String, Material>,
+}
+
+impl MaterialRegistry {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_material(mut self, name: impl Into<String>, material: Material) -> Self {
+    self.insert(name, material);
+    self
+  }
+
+  /// Registers the material, replacing any previous one of the same name
+  pub fn insert(&mut self, name: impl Into<String>, material: Material) {
+    self.materials.insert(name.into(), material);
+  }
+
+  pub fn get(&self, name: &str) -> Option<Material> {
+    self.materials.get(name).cloned()
+  }
+
+  pub fn contains(&self, name: &str) -> bool {
+    self.materials.contains_key(name)
+  }
+
+  /// Registered names in alphabetical order
+  pub fn names(&self) -> Vec<&str> {
+    let mut names: Vec<&str> = self.materials.keys().map(|name| name.as_str()).collect();
+    names.sort_unstable();
+    names
+  }
+
+  pub fn len(&self) -> usize {
+    self.materials.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.materials.is_empty()
+  }
+}
+
+impl FuzzyEq<MaterialRegistry> for MaterialRegistry {
+  fn fuzzy_eq(&self, other: MaterialRegistry) -> bool {
+    self.materials.fuzzy_eq(other.materials)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::Color;
+  use crate::material::Phong;
+
+  #[test]
+  fn registering_a_name_twice_replaces_the_material() {
+    let red = Material::from(Phong::default().with_color(Color::new(1.0, 0.0, 0.0)));
+    let blue = Material::from(Phong::default().with_color(Color::new(0.0, 0.0, 1.0)));
+
+    let registry = MaterialRegistry::new()
+      .with_material("paint", red.clone())
+      .with_material("floor", red)
+      .with_material("paint", blue.clone());
+
+    assert_eq!(registry.len(), 2);
+    assert_eq!(registry.names(), vec!["floor", "paint"]);
+    assert_eq!(registry.get("paint"), Some(blue));
+    assert_eq!(registry.get("steel"), None);
+  }
+}
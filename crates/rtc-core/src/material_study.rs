@@ -0,0 +1,268 @@
+use crate::body::Intersectable;
+use crate::camera::Camera;
+use crate::canvas::{Canvas, Color};
+use crate::material::{Coat, Material, Phong};
+use crate::post_processing::watermark::{Corner, Watermark};
+use crate::post_processing::PostProcess;
+use crate::renderer;
+use crate::world::World;
+use crate::F;
+
+/// Property of a Phong material varied by a material study, named like in
+/// world files
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MaterialProperty {
+  Ambient,
+  Diffuse,
+  Specular,
+  Shininess,
+  Reflectiveness,
+  Transparency,
+  RefractiveIndex,
+  /// Roughness of the clear coat, which is added if the material has none
+  CoatRoughness,
+}
+
+impl MaterialProperty {
+  pub const ALL: [MaterialProperty; 8] = [
+    MaterialProperty::Ambient,
+    MaterialProperty::Diffuse,
+    MaterialProperty::Specular,
+    MaterialProperty::Shininess,
+    MaterialProperty::Reflectiveness,
+    MaterialProperty::Transparency,
+    MaterialProperty::RefractiveIndex,
+    MaterialProperty::CoatRoughness,
+  ];
+
+  pub fn from_name(name: &str) -> Option<Self> {
+    Self::ALL
+      .iter()
+      .copied()
+      .find(|property| property.name() == name)
+  }
+
+  pub fn name(&self) -> &'static str {
+    match *self {
+      MaterialProperty::Ambient => "ambient",
+      MaterialProperty::Diffuse => "diffuse",
+      MaterialProperty::Specular => "specular",
+      MaterialProperty::Shininess => "shininess",
+      MaterialProperty::Reflectiveness => "reflectiveness",
+      MaterialProperty::Transparency => "transparency",
+      MaterialProperty::RefractiveIndex => "refractive_index",
+      MaterialProperty::CoatRoughness => "coat_roughness",
+    }
+  }
+
+  /// The material with the property set to the value
+  pub fn apply(&self, phong: Phong, value: F) -> Phong {
+    match *self {
+      MaterialProperty::Ambient => phong.with_ambient(value),
+      MaterialProperty::Diffuse => phong.with_diffuse(value),
+      MaterialProperty::Specular => phong.with_specular(value),
+      MaterialProperty::Shininess => phong.with_shininess(value),
+      MaterialProperty::Reflectiveness => phong.with_reflectiveness(value),
+      MaterialProperty::Transparency => phong.with_transparency(value),
+      MaterialProperty::RefractiveIndex => phong.with_refractive_index(value),
+      MaterialProperty::CoatRoughness => {
+        let coat = phong.coat.unwrap_or_default();
+        phong.with_coat(Coat::new(coat.reflectiveness, value).with_tint(coat.tint))
+      }
+    }
+  }
+}
+
+/// Values of a property stepping from one value to another, both included
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StudyAxis {
+  pub property: MaterialProperty,
+  pub from: F,
+  pub to: F,
+  pub steps: usize,
+}
+
+impl StudyAxis {
+  pub fn new(property: MaterialProperty, from: F, to: F, steps: usize) -> Self {
+    Self {
+      property,
+      from,
+      to,
+      steps: steps.max(1),
+    }
+  }
+
+  pub fn value_at(&self, step: usize) -> F {
+    if self.steps <= 1 {
+      return self.from;
+    }
+    self.from + (self.to - self.from) * step as F / (self.steps - 1) as F
+  }
+
+  /// Name and value of the property at the step, as printed on the sheet
+  fn label(&self, step: usize) -> String {
+    let value = (self.value_at(step) * 100.0).round() / 100.0;
+    format!("{} {}", self.property.name(), value)
+  }
+}
+
+/// Grid of renders of the same bodies, with one property of their material
+/// changing along the columns and another one along the rows, to pick good
+/// values at a glance instead of rendering them one by one.
+///
+/// Every cell is labeled with its two values. Bodies without a Phong
+/// material start from the default one.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MaterialStudy {
+  pub columns: StudyAxis,
+  pub rows: StudyAxis,
+  pub spacing: usize,
+  pub background: Color,
+}
+
+impl MaterialStudy {
+  pub fn new(columns: StudyAxis, rows: StudyAxis) -> Self {
+    Self {
+      columns,
+      rows,
+      spacing: 4,
+      background: Color::new(0.2, 0.2, 0.2),
+    }
+  }
+
+  pub fn with_spacing(mut self, spacing: usize) -> Self {
+    self.spacing = spacing;
+    self
+  }
+
+  pub fn with_background(mut self, background: Color) -> Self {
+    self.background = background;
+    self
+  }
+
+  /// Material of the cell, based on the given one
+  pub fn material_at(&self, material: Material, column: usize, row: usize) -> Material {
+    let phong = match material {
+      Material::Phong(phong) => phong,
+      _ => Phong::default(),
+    };
+    let phong = self
+      .columns
+      .property
+      .apply(phong, self.columns.value_at(column));
+    Material::from(self.rows.property.apply(phong, self.rows.value_at(row)))
+  }
+
+  /// Renders every cell with the materials of the bodies carrying the name
+  /// changed, each the size of the camera
+  pub fn render(&self, world: &World, name: &str, camera: &Camera) -> Canvas {
+    let mut sheet = Canvas::new(
+      self.columns.steps * (camera.hsize + self.spacing) + self.spacing,
+      self.rows.steps * (camera.vsize + self.spacing) + self.spacing,
+    );
+    sheet.fill(self.background);
+
+    for row in 0..self.rows.steps {
+      for column in 0..self.columns.steps {
+        let mut cell_world = world.clone();
+        for body in cell_world.bodies_named_mut(name) {
+          let material = self.material_at(body.material(), column, row);
+          *body = body.clone().with_material(material);
+        }
+
+        let cell = renderer::render(&cell_world, camera, |_| {});
+        let cell = Watermark::text(self.columns.label(column))
+          .with_corner(Corner::TopLeft)
+          .with_margin(2)
+          .apply(&cell);
+        let cell = Watermark::text(self.rows.label(row))
+          .with_corner(Corner::BottomLeft)
+          .with_margin(2)
+          .apply(&cell);
+
+        let left = self.spacing + column * (camera.hsize + self.spacing);
+        let top = self.spacing + row * (camera.vsize + self.spacing);
+        sheet.draw(&cell, left as isize, top as isize);
+      }
+    }
+
+    sheet
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::angle::Angle;
+  use crate::body::Body;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::matrix::Matrix;
+  use crate::sphere::Sphere;
+  use crate::test_scene::white_light_at;
+  use crate::tuple::Tuple;
+
+  #[test]
+  fn properties_are_found_by_their_name() {
+    for property in MaterialProperty::ALL {
+      assert_eq!(MaterialProperty::from_name(property.name()), Some(property));
+    }
+    assert_eq!(MaterialProperty::from_name("roughness"), None);
+  }
+
+  #[test]
+  fn axes_step_from_the_first_to_the_last_value() {
+    let axis = StudyAxis::new(MaterialProperty::Shininess, 10.0, 210.0, 3);
+
+    assert_fuzzy_eq!(axis.value_at(0), 10.0);
+    assert_fuzzy_eq!(axis.value_at(1), 110.0);
+    assert_fuzzy_eq!(axis.value_at(2), 210.0);
+    assert_fuzzy_eq!(
+      StudyAxis::new(MaterialProperty::Shininess, 10.0, 210.0, 1).value_at(0),
+      10.0
+    );
+  }
+
+  #[test]
+  fn cells_change_both_properties() {
+    let study = MaterialStudy::new(
+      StudyAxis::new(MaterialProperty::Reflectiveness, 0.0, 1.0, 5),
+      StudyAxis::new(MaterialProperty::CoatRoughness, 0.0, 0.5, 2),
+    );
+    let base = Material::from(Phong::default().with_color(Color::new(1.0, 0.0, 0.0)));
+
+    let material = study.material_at(base, 2, 1);
+
+    let expected = Phong::default()
+      .with_color(Color::new(1.0, 0.0, 0.0))
+      .with_reflectiveness(0.5)
+      .with_coat(Coat::new(Coat::default().reflectiveness, 0.5));
+    assert_fuzzy_eq!(material, Material::from(expected));
+  }
+
+  #[test]
+  fn cells_are_rendered_side_by_side() {
+    let world = World::new(
+      vec![Body::from(Sphere::default())],
+      vec![white_light_at(-10.0, 10.0, -10.0)],
+    )
+    .with_name("ball", vec![0]);
+    let camera = Camera::new(41, 41, Angle::radians(0.5)).with_transform(Matrix::view_transform(
+      Tuple::point(0.0, 0.0, -5.0),
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    ));
+    let study = MaterialStudy::new(
+      StudyAxis::new(MaterialProperty::Ambient, 0.0, 1.0, 2),
+      StudyAxis::new(MaterialProperty::Diffuse, 0.0, 0.0, 1),
+    )
+    .with_spacing(1);
+
+    let sheet = study.render(&world, "ball", &camera);
+
+    assert_eq!((sheet.width, sheet.height), (2 * 42 + 1, 42 + 1));
+    // Lit by the ambient light only, none in the first column
+    assert_fuzzy_eq!(sheet.pixel_at(21, 21), Color::black());
+    assert_fuzzy_eq!(sheet.pixel_at(63, 21), Color::white());
+    assert_fuzzy_eq!(sheet.pixel_at(0, 0), Color::new(0.2, 0.2, 0.2));
+  }
+}
@@ -1,7 +1,9 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::Float;
 use crate::F;
-use std::convert::From;
-use std::ops::{Index, IndexMut, Mul};
+use core::ops::{Index, IndexMut, Mul};
 
+use crate::angle::Angle;
 use crate::fuzzy_eq::*;
 use crate::tuple::*;
 
@@ -142,7 +144,7 @@ impl Matrix<3> {
 
   pub fn cofactor(&self, row: usize, column: usize) -> F {
     let minor = self.minor(row, column);
-    if (row + column) % 2 == 0 {
+    if (row + column).is_multiple_of(2) {
       // Even value
       minor
     } else {
@@ -173,6 +175,24 @@ impl Mul<Tuple> for Matrix<4> {
   }
 }
 
+impl Mul<Point> for Matrix<4> {
+  type Output = Point;
+
+  fn mul(self, other: Point) -> Self::Output {
+    let Tuple { x, y, z, .. } = self * Tuple::from(other);
+    Point::new(x, y, z)
+  }
+}
+
+impl Mul<Vector> for Matrix<4> {
+  type Output = Vector;
+
+  fn mul(self, other: Vector) -> Self::Output {
+    let Tuple { x, y, z, .. } = self * Tuple::from(other);
+    Vector::new(x, y, z)
+  }
+}
+
 impl Matrix<4> {
   // @FIXME: Find a nicer way to do this.
   pub fn submatrix(&self, row: usize, column: usize) -> Matrix<3> {
@@ -212,7 +232,7 @@ impl Matrix<4> {
 
   pub fn cofactor(&self, row: usize, column: usize) -> F {
     let minor = self.minor(row, column);
-    if (row + column) % 2 == 0 {
+    if (row + column).is_multiple_of(2) {
       // Even value
       minor
     } else {
@@ -275,8 +295,9 @@ impl Matrix<4> {
   }
 
   #[rustfmt::skip]
-  pub fn rotation_x(r: F) -> Matrix<4>
+  pub fn rotation_x(angle: Angle) -> Matrix<4>
   {
+    let r = angle.as_radians();
     Matrix::from([
       [1.0,     0.0,      0.0, 0.0],
       [0.0, r.cos(), -r.sin(), 0.0],
@@ -286,8 +307,9 @@ impl Matrix<4> {
   }
 
   #[rustfmt::skip]
-  pub fn rotation_y(r: F) -> Matrix<4>
+  pub fn rotation_y(angle: Angle) -> Matrix<4>
   {
+    let r = angle.as_radians();
     Matrix::from([
       [ r.cos(), 0.0, r.sin(), 0.0],
       [     0.0, 1.0,     0.0, 0.0],
@@ -297,8 +319,9 @@ impl Matrix<4> {
   }
 
   #[rustfmt::skip]
-  pub fn rotation_z(r: F) -> Matrix<4>
+  pub fn rotation_z(angle: Angle) -> Matrix<4>
   {
+    let r = angle.as_radians();
     Matrix::from([
       [r.cos(), -r.sin(), 0.0, 0.0],
       [r.sin(),  r.cos(), 0.0, 0.0],
@@ -318,6 +341,21 @@ impl Matrix<4> {
     ])
   }
 
+  /// Mirrors points across the plane through `point`, which is
+  /// perpendicular to `normal`
+  #[rustfmt::skip]
+  pub fn reflection(point: Tuple, normal: Tuple) -> Matrix<4>
+  {
+    let n = normal.normalize();
+    let d = 2.0 * point.dot(n);
+    Matrix::from([
+      [1.0 - 2.0 * n.x * n.x,      -2.0 * n.x * n.y,      -2.0 * n.x * n.z, d * n.x],
+      [     -2.0 * n.y * n.x, 1.0 - 2.0 * n.y * n.y,      -2.0 * n.y * n.z, d * n.y],
+      [     -2.0 * n.z * n.x,      -2.0 * n.z * n.y, 1.0 - 2.0 * n.z * n.z, d * n.z],
+      [                  0.0,                   0.0,                   0.0,     1.0],
+    ])
+  }
+
   pub fn view_transform(from: Tuple, to: Tuple, up: Tuple) -> Self {
     let forward = (to - from).normalize();
     let left = forward.cross(up.normalize());
@@ -338,6 +376,7 @@ impl Matrix<4> {
 }
 
 #[cfg(test)]
+#[allow(clippy::excessive_precision)]
 mod tests {
   use super::*;
   use std::f64::consts::PI;
@@ -841,6 +880,20 @@ mod tests {
     assert_fuzzy_eq!(actual_result, expected_result);
   }
 
+  #[test]
+  fn translation_moves_typed_points_but_not_typed_vectors() {
+    let transform = Matrix::translation(5.0, -3.0, 2.0);
+
+    assert_fuzzy_eq!(
+      transform * Point::new(-3.0, 4.0, 5.0),
+      Point::new(2.0, 1.0, 7.0)
+    );
+    assert_fuzzy_eq!(
+      transform * Vector::new(-3.0, 4.0, 5.0),
+      Vector::new(-3.0, 4.0, 5.0)
+    );
+  }
+
   #[test]
   fn a_scaling_matrix_applied_to_a_point() {
     let transform = Matrix::scaling(2.0, 3.0, 4.0);
@@ -882,10 +935,23 @@ mod tests {
     assert_fuzzy_eq!(actual_result, expected_result);
   }
 
+  #[test]
+  fn reflecting_a_point_across_a_plane() {
+    let transform = Matrix::reflection(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(1.0, 1.0, 0.0));
+    let p = Tuple::point(2.0, 3.0, 4.0);
+
+    assert_fuzzy_eq!(transform * p, Tuple::point(-2.0, -1.0, 4.0));
+    assert_fuzzy_eq!(transform * transform, Matrix::identity());
+    assert_fuzzy_eq!(
+      Matrix::reflection(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0)),
+      Matrix::scaling(-1.0, 1.0, 1.0)
+    );
+  }
+
   #[test]
   fn rotating_a_point_around_the_x_axis() {
-    let half_quarter = Matrix::rotation_x(PI / 4.0);
-    let full_quarter = Matrix::rotation_x(PI / 2.0);
+    let half_quarter = Matrix::rotation_x(Angle::radians(PI / 4.0));
+    let full_quarter = Matrix::rotation_x(Angle::radians(PI / 2.0));
     let p = Tuple::point(0.0, 1.0, 0.0);
 
     assert_fuzzy_eq!(
@@ -898,8 +964,8 @@ mod tests {
 
   #[test]
   fn the_inverse_of_an_x_rotation_rotates_in_the_opposite_direction() {
-    let half_quarter = Matrix::rotation_x(PI / 4.0);
-    let full_quarter = Matrix::rotation_x(PI / 2.0);
+    let half_quarter = Matrix::rotation_x(Angle::radians(PI / 4.0));
+    let full_quarter = Matrix::rotation_x(Angle::radians(PI / 2.0));
     let inverse_half_quarter = half_quarter.inverse();
     let inverse_full_quarter = full_quarter.inverse();
 
@@ -915,8 +981,8 @@ mod tests {
 
   #[test]
   fn rotating_a_point_around_the_y_axis() {
-    let half_quarter = Matrix::rotation_y(PI / 4.0);
-    let full_quarter = Matrix::rotation_y(PI / 2.0);
+    let half_quarter = Matrix::rotation_y(Angle::radians(PI / 4.0));
+    let full_quarter = Matrix::rotation_y(Angle::radians(PI / 2.0));
     let p = Tuple::point(0.0, 0.0, 1.0);
 
     assert_fuzzy_eq!(
@@ -927,10 +993,26 @@ mod tests {
     assert_fuzzy_eq!(full_quarter * p, Tuple::point(1.0, 0.0, 0.0));
   }
 
+  #[test]
+  fn rotations_can_be_given_as_angles() {
+    assert_fuzzy_eq!(
+      Matrix::rotation_x(Angle::degrees(90.0)),
+      Matrix::rotation_x(Angle::radians(PI / 2.0))
+    );
+    assert_fuzzy_eq!(
+      Matrix::rotation_y(Angle::degrees(45.0)),
+      Matrix::rotation_y(Angle::radians(PI / 4.0))
+    );
+    assert_fuzzy_eq!(
+      Matrix::rotation_z(Angle::degrees(-30.0)),
+      Matrix::rotation_z(Angle::radians(-PI / 6.0))
+    );
+  }
+
   #[test]
   fn rotating_a_point_around_the_z_axis() {
-    let half_quarter = Matrix::rotation_z(PI / 4.0);
-    let full_quarter = Matrix::rotation_z(PI / 2.0);
+    let half_quarter = Matrix::rotation_z(Angle::radians(PI / 4.0));
+    let full_quarter = Matrix::rotation_z(Angle::radians(PI / 2.0));
     let p = Tuple::point(0.0, 1.0, 0.0);
 
     assert_fuzzy_eq!(
@@ -992,7 +1074,7 @@ mod tests {
   #[test]
   fn individual_transformation_are_applied_in_sequence() {
     let p = Tuple::point(1.0, 0.0, 1.0);
-    let a = Matrix::rotation_x(PI / 2.0);
+    let a = Matrix::rotation_x(Angle::radians(PI / 2.0));
     let b = Matrix::scaling(5.0, 5.0, 5.0);
     let c = Matrix::translation(10.0, 5.0, 7.0);
 
@@ -1009,7 +1091,7 @@ mod tests {
   #[test]
   fn chained_transformations_must_be_applied_in_reverse_order() {
     let p = Tuple::point(1.0, 0.0, 1.0);
-    let a = Matrix::rotation_x(PI / 2.0);
+    let a = Matrix::rotation_x(Angle::radians(PI / 2.0));
     let b = Matrix::scaling(5.0, 5.0, 5.0);
     let c = Matrix::translation(10.0, 5.0, 7.0);
 
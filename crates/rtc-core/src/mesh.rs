@@ -0,0 +1,395 @@
+use crate::body::*;
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::triangle::{SmoothTriangle, Triangle};
+use crate::tuple::Tuple;
+use crate::F;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// A single triangle of a mesh, with or without vertex normals.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Face {
+  Flat(Triangle),
+  Smooth(SmoothTriangle),
+}
+
+impl From<Triangle> for Face {
+  fn from(triangle: Triangle) -> Self {
+    Face::Flat(triangle)
+  }
+}
+
+impl From<SmoothTriangle> for Face {
+  fn from(smooth_triangle: SmoothTriangle) -> Self {
+    Face::Smooth(smooth_triangle)
+  }
+}
+
+/// Triangle mesh sharing one material and transformation, e.g. a loaded model.
+///
+/// A mesh is placed into a world as a single mesh body.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mesh {
+  pub faces: Vec<Face>,
+  pub material: Material,
+  pub transform: Matrix<4>,
+}
+
+impl Default for Mesh {
+  fn default() -> Self {
+    Self {
+      faces: vec![],
+      material: Default::default(),
+      transform: Matrix::identity(),
+    }
+  }
+}
+
+impl Mesh {
+  pub fn new(faces: Vec<Face>) -> Self {
+    Self {
+      faces,
+      ..Default::default()
+    }
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.material = material;
+    self
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self
+  }
+
+  /// Mesh body with the faces, material and transform of the mesh
+  pub fn body(&self) -> Body {
+    Body::from(
+      MeshBody::new(self.faces.clone())
+        .with_material(self.material.clone())
+        .with_transform(self.transform),
+    )
+  }
+
+  /// Wavefront OBJ of the meshes, with their transformations applied to the
+  /// vertices. Smooth faces get their vertex normals, materials are left out.
+  pub fn to_obj(meshes: &[Mesh]) -> String {
+    let mut obj = String::new();
+    let (mut vertices, mut normals) = (1, 1);
+    for (index, mesh) in meshes.iter().enumerate() {
+      writeln!(obj, "o mesh_{}", index).unwrap();
+      let normal_transform = mesh.transform.inverse().transpose();
+      for face in mesh.faces.iter() {
+        let (triangle, corner_normals) = match face {
+          Face::Flat(triangle) => (triangle, None),
+          Face::Smooth(smooth) => (&smooth.triangle, Some([smooth.n1, smooth.n2, smooth.n3])),
+        };
+        for corner in [triangle.p1, triangle.p2, triangle.p3] {
+          let position = mesh.transform * corner;
+          writeln!(obj, "v {} {} {}", position.x, position.y, position.z).unwrap();
+        }
+        match corner_normals {
+          None => writeln!(obj, "f {} {} {}", vertices, vertices + 1, vertices + 2).unwrap(),
+          Some(corner_normals) => {
+            for normal in corner_normals {
+              let mut normal = normal_transform * normal;
+              normal.w = 0.0;
+              let normal = normal.normalize();
+              writeln!(obj, "vn {} {} {}", normal.x, normal.y, normal.z).unwrap();
+            }
+            writeln!(
+              obj,
+              "f {}//{} {}//{} {}//{}",
+              vertices,
+              normals,
+              vertices + 1,
+              normals + 1,
+              vertices + 2,
+              normals + 2
+            )
+            .unwrap();
+            normals += 3;
+          }
+        }
+        vertices += 3;
+      }
+    }
+    obj
+  }
+}
+
+/// Triangles of a mesh placed as a single body.
+///
+/// The triangles are shared, so copies of the body, e.g. for shadows or
+/// animation frames, do not copy them. Rays missing the bounds of the whole
+/// mesh are not tested against any of its triangles, and hits are reported
+/// on the triangle, placed with the transformation of the mesh.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshBody {
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+  pub triangles: Arc<Vec<Body>>,
+  material: Material,
+  shadows: Shadows,
+  bounds: Bounds,
+}
+
+impl MeshBody {
+  pub fn new(faces: Vec<Face>) -> Self {
+    let triangles: Vec<Body> = faces
+      .into_iter()
+      .map(|face| match face {
+        Face::Flat(triangle) => Body::from(triangle),
+        Face::Smooth(smooth_triangle) => Body::from(smooth_triangle),
+      })
+      .collect();
+    let bounds = triangles
+      .iter()
+      .map(|triangle| triangle.bounds())
+      .reduce(|bounds, other| bounds.union(other))
+      .unwrap_or_else(|| Bounds::from_points(&[]));
+    Self {
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+      triangles: Arc::new(triangles),
+      material: Material::default(),
+      shadows: Shadows::default(),
+      bounds,
+    }
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.triangles = Arc::new(
+      self
+        .triangles
+        .iter()
+        .map(|triangle| triangle.clone().with_material(material.clone()))
+        .collect(),
+    );
+    self.material = material;
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.triangles = Arc::new(
+      self
+        .triangles
+        .iter()
+        .map(|triangle| triangle.clone().with_shadows(shadows))
+        .collect(),
+    );
+    self.shadows = shadows;
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    self.transform = transform;
+    self.inverse_transform = inverse_transform;
+    self
+  }
+
+  /// The triangle placed with the transformation of the mesh
+  fn place(&self, triangle: Body) -> Body {
+    let transform = self.transform * triangle.transform();
+    let inverse_transform = triangle.inverse_transform() * self.inverse_transform;
+    triangle.with_transform_and_inverse(transform, inverse_transform)
+  }
+}
+
+/// Distance of the point to the plane of the triangle plus its distance to
+/// the bounds of the triangle, which is zero only on the triangle itself
+fn distance_to_triangle(body: &Body, object_space_point: Tuple) -> F {
+  let triangle = match body {
+    Body::Triangle(triangle) => triangle,
+    Body::SmoothTriangle(smooth_triangle) => &smooth_triangle.triangle,
+    _ => return F::INFINITY,
+  };
+  let normal = (triangle.p3 - triangle.p1)
+    .cross(triangle.p2 - triangle.p1)
+    .normalize();
+  let bounds = triangle.bounds_in_object_space();
+  let outside = Tuple::vector(
+    (bounds.min.x - object_space_point.x)
+      .max(object_space_point.x - bounds.max.x)
+      .max(0.0),
+    (bounds.min.y - object_space_point.y)
+      .max(object_space_point.y - bounds.max.y)
+      .max(0.0),
+    (bounds.min.z - object_space_point.z)
+      .max(object_space_point.z - bounds.max.z)
+      .max(0.0),
+  );
+  (object_space_point - triangle.p1).dot(normal).abs() + outside.magnitude()
+}
+
+impl FuzzyEq<&MeshBody> for MeshBody {
+  fn fuzzy_eq(&self, other: &MeshBody) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+      && self.triangles.len() == other.triangles.len()
+      && self
+        .triangles
+        .iter()
+        .zip(other.triangles.iter())
+        .all(|(triangle, other)| triangle.fuzzy_eq(other.clone()))
+  }
+}
+
+impl Intersectable for MeshBody {
+  fn max_intersections(&self) -> usize {
+    self.triangles.len()
+  }
+
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    self
+      .triangles
+      .iter()
+      .flat_map(|triangle| triangle.intersect(object_space_ray).into_iter())
+      .map(|hit| (hit.t, self.place(hit.body)))
+      .collect()
+  }
+
+  /// Normal of the triangle closest to the point. Hits already report their
+  /// triangle, so this is only needed for points found otherwise.
+  fn normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    self
+      .triangles
+      .iter()
+      .min_by(|a, b| {
+        distance_to_triangle(a, object_space_point)
+          .total_cmp(&distance_to_triangle(b, object_space_point))
+      })
+      .map(|triangle| triangle.normal_at(object_space_point))
+      .unwrap_or_else(|| Tuple::vector(0.0, 1.0, 0.0))
+  }
+
+  fn material(&self) -> Material {
+    self.material.clone()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.shadows
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    self.bounds
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::material::Phong;
+  use crate::tuple::Tuple;
+
+  #[test]
+  fn hits_are_the_triangles_with_the_material_and_transform_of_the_mesh() {
+    let p1 = Tuple::point(0.0, 1.0, 0.0);
+    let p2 = Tuple::point(-1.0, 0.0, 0.0);
+    let p3 = Tuple::point(1.0, 0.0, 0.0);
+    let n = Tuple::vector(0.0, 0.0, -1.0);
+    let material = Material::from(Phong::default().with_ambient(0.5));
+    let transform = Matrix::translation(0.0, 2.0, 0.0);
+
+    let mesh = Mesh::new(vec![
+      Face::from(Triangle::new(p1, p2, p3)),
+      Face::from(
+        SmoothTriangle::new(p1, p2, p3, n, n, n).with_transform(Matrix::translation(0.0, 0.0, 1.0)),
+      ),
+    ])
+    .with_material(material.clone())
+    .with_transform(transform);
+    let r = Ray::new(Tuple::point(0.0, 2.5, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let xs = mesh.body().intersect(r);
+
+    assert_eq!(xs.len(), 2);
+    assert_fuzzy_eq!(xs[0].t, 5.0);
+    assert_fuzzy_eq!(
+      xs[0].body,
+      Body::from(
+        Triangle::new(p1, p2, p3)
+          .with_material(material.clone())
+          .with_transform(transform)
+      )
+    );
+    assert_fuzzy_eq!(xs[1].t, 6.0);
+    assert_fuzzy_eq!(
+      xs[1].body.inverse_transform(),
+      Matrix::translation(0.0, -2.0, -1.0)
+    );
+    assert_eq!(xs[1].body.material(), material);
+  }
+
+  #[test]
+  fn rays_missing_the_bounds_of_the_mesh_miss_it() {
+    let mesh = Mesh::new(vec![Face::from(Triangle::new(
+      Tuple::point(0.0, 1.0, 0.0),
+      Tuple::point(-1.0, 0.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+    ))])
+    .with_transform(Matrix::translation(0.0, 2.0, 0.0));
+    let r = Ray::new(Tuple::point(0.0, 0.5, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    assert_fuzzy_eq!(
+      mesh.body().bounds(),
+      Bounds::new(Tuple::point(-1.0, 2.0, 0.0), Tuple::point(1.0, 3.0, 0.0))
+    );
+    assert!(mesh.body().intersect(r).is_empty());
+  }
+
+  #[test]
+  fn meshes_are_written_as_obj_in_world_space() {
+    let p1 = Tuple::point(0.0, 0.0, 0.0);
+    let p2 = Tuple::point(1.0, 0.0, 0.0);
+    let p3 = Tuple::point(0.0, 1.0, 0.0);
+    let n = Tuple::vector(0.0, 0.0, 1.0);
+    let flat = Mesh::new(vec![Face::from(Triangle::new(p1, p2, p3))])
+      .with_transform(Matrix::translation(0.0, 2.0, 0.0));
+    let smooth = Mesh::new(vec![Face::from(SmoothTriangle::new(p1, p2, p3, n, n, n))])
+      .with_transform(Matrix::scaling(1.0, 1.0, 2.0));
+
+    let obj = Mesh::to_obj(&[flat, smooth]);
+
+    let lines: Vec<&str> = obj.lines().collect();
+    assert_eq!(
+      lines,
+      vec![
+        "o mesh_0",
+        "v 0 2 0",
+        "v 1 2 0",
+        "v 0 3 0",
+        "f 1 2 3",
+        "o mesh_1",
+        "v 0 0 0",
+        "v 1 0 0",
+        "v 0 1 0",
+        "vn 0 0 1",
+        "vn 0 0 1",
+        "vn 0 0 1",
+        "f 4//1 5//2 6//3",
+      ]
+    );
+  }
+}
@@ -0,0 +1,202 @@
+use crate::body::*;
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::tuple::Tuple;
+use crate::F;
+use std::sync::Arc;
+
+/// Body together with its mirror image across a plane, like the two halves
+/// of a symmetric vase, while only one half is built.
+///
+/// Rays are tested against the body and, reflected across the plane,
+/// against it once more. The plane runs through `point` perpendicular to
+/// `normal`, and the body is expected on the side the normal points to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mirrored {
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+  pub body: Arc<Body>,
+  pub point: Tuple,
+  pub normal: Tuple,
+  reflection: Matrix<4>,
+}
+
+impl Mirrored {
+  /// Mirrors the body across the yz plane
+  pub fn new(body: Body) -> Self {
+    let point = Tuple::point(0.0, 0.0, 0.0);
+    let normal = Tuple::vector(1.0, 0.0, 0.0);
+    Self {
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+      body: Arc::new(body),
+      point,
+      normal,
+      reflection: Matrix::reflection(point, normal),
+    }
+  }
+
+  pub fn with_plane(mut self, point: Tuple, normal: Tuple) -> Self {
+    self.point = point;
+    self.normal = normal.normalize();
+    self.reflection = Matrix::reflection(point, normal);
+    self
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.body = Arc::new(Body::clone(&self.body).with_material(material));
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.body = Arc::new(Body::clone(&self.body).with_shadows(shadows));
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    self.transform = transform;
+    self.inverse_transform = inverse_transform;
+    self
+  }
+
+  /// Whether the point lies on the side of the mirror image
+  fn is_mirrored(&self, object_space_point: Tuple) -> bool {
+    (object_space_point - self.point).dot(self.normal) < 0.0
+  }
+}
+
+impl FuzzyEq<&Mirrored> for Mirrored {
+  fn fuzzy_eq(&self, other: &Mirrored) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+      && self.body.fuzzy_eq(Body::clone(&other.body))
+      && self.point.fuzzy_eq(other.point)
+      && self.normal.fuzzy_eq(other.normal)
+  }
+}
+
+impl Intersectable for Mirrored {
+  fn max_intersections(&self) -> usize {
+    2 * self.body.max_intersections()
+  }
+
+  /// Hits of the mirror image are its copy of the body, placed with the
+  /// reflection, so they shade like any other body
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    let body_transform = self.transform;
+    let image_transform = self.transform * self.reflection;
+    let body_hits = self
+      .body
+      .intersect(object_space_ray)
+      .into_iter()
+      .map(|hit| {
+        let transform = body_transform * hit.body.transform();
+        (hit.t, hit.body.with_transform(transform))
+      });
+    let image_hits = self
+      .body
+      .intersect(object_space_ray.transform(self.reflection))
+      .into_iter()
+      .map(|hit| {
+        let transform = image_transform * hit.body.transform();
+        (hit.t, hit.body.with_transform(transform))
+      });
+    body_hits.chain(image_hits).collect()
+  }
+
+  fn normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    if self.is_mirrored(object_space_point) {
+      let normal = self.body.normal_at(self.reflection * object_space_point);
+      (self.reflection * normal).normalize()
+    } else {
+      self.body.normal_at(object_space_point)
+    }
+  }
+
+  fn material(&self) -> Material {
+    self.body.material()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.body.shadows()
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    let bounds = self.body.bounds();
+    bounds.union(bounds.transform(self.reflection))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sphere::Sphere;
+
+  fn mirrored_sphere() -> Mirrored {
+    Mirrored::new(Body::from(
+      Sphere::default().with_transform(Matrix::translation(2.0, 0.0, 0.0)),
+    ))
+  }
+
+  #[test]
+  fn rays_hit_the_body_and_its_mirror_image() {
+    let mirrored = mirrored_sphere();
+    let r = Ray::new(Tuple::point(-5.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0));
+
+    let xs = mirrored.intersect(r);
+
+    assert_eq!(xs.len(), 4);
+    assert_fuzzy_eq!(xs[0].t, 2.0);
+    assert_fuzzy_eq!(xs[1].t, 4.0);
+    assert_fuzzy_eq!(xs[2].t, 6.0);
+    assert_fuzzy_eq!(xs[3].t, 8.0);
+    assert_fuzzy_eq!(
+      Body::from(mirrored).bounds(),
+      Bounds::new(Tuple::point(-3.0, -1.0, -1.0), Tuple::point(3.0, 1.0, 1.0))
+    );
+  }
+
+  #[test]
+  fn the_mirror_image_is_placed_with_the_reflection() {
+    let mirrored = mirrored_sphere()
+      .with_plane(Tuple::point(0.0, 0.0, 1.0), Tuple::vector(0.0, 0.0, -2.0))
+      .with_transform(Matrix::translation(0.0, 1.0, 0.0));
+    let r = Ray::new(Tuple::point(2.0, 1.0, 5.0), Tuple::vector(0.0, 0.0, -1.0));
+
+    let xs = mirrored.intersect(r);
+
+    // The mirror image lies between the ray and the body
+    assert_eq!(xs.len(), 4);
+    assert_fuzzy_eq!(xs[0].t, 2.0);
+    assert_fuzzy_eq!(
+      xs[0].body.normal_at(Tuple::point(2.0, 1.0, 3.0)),
+      Tuple::vector(0.0, 0.0, 1.0)
+    );
+    assert_fuzzy_eq!(
+      mirrored.normal_at(Tuple::point(2.0, 1.0, 3.0)),
+      Tuple::vector(0.0, 0.0, 1.0)
+    );
+    assert_fuzzy_eq!(
+      mirrored.normal_at(Tuple::point(2.0, 1.0, -1.0)),
+      Tuple::vector(0.0, 0.0, -1.0)
+    );
+  }
+}
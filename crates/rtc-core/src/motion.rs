@@ -0,0 +1,362 @@
+use crate::body::{Body, Intersectable};
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::intersections::Intersection;
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::tuple::Tuple;
+use crate::{EPSILON, F};
+
+/// Body moving from its own transformation to an end transformation while
+/// the shutter of the camera is open.
+///
+/// Rays hit the body at the position given by their time. Both
+/// transformations are split into translation, rotation and scaling, which
+/// are interpolated on their own, so rotations keep the size of the body even
+/// for half turns.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovingBody {
+  body: Body,
+  end_transform: Matrix<4>,
+  start: Pose,
+  end: Pose,
+}
+
+impl MovingBody {
+  /// `None` if one of the transformations shears the body, or only one of
+  /// them mirrors it, as the body would be flattened on its way
+  pub fn new(body: Body, end_transform: Matrix<4>) -> Option<Self> {
+    let start = Pose::of(body.transform())?;
+    let end = Pose::of(end_transform)?;
+    if start.mirrored() != end.mirrored() {
+      return None;
+    }
+    Some(Self {
+      body,
+      end_transform,
+      start,
+      end,
+    })
+  }
+
+  pub fn body(&self) -> &Body {
+    &self.body
+  }
+
+  pub fn end_transform(&self) -> Matrix<4> {
+    self.end_transform
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.body = self.body.with_material(material);
+    self
+  }
+
+  /// Transformation at the given time within the shutter interval [0, 1]
+  pub fn transform_at(&self, time: F) -> Matrix<4> {
+    self.pose_at(time).transform()
+  }
+
+  fn pose_at(&self, time: F) -> Pose {
+    self.start.interpolate(&self.end, time.clamp(0.0, 1.0))
+  }
+
+  /// The body frozen at the given time
+  pub fn body_at(&self, time: F) -> Body {
+    let pose = self.pose_at(time);
+    self
+      .body
+      .clone()
+      .with_transform_and_inverse(pose.transform(), pose.inverse_transform())
+  }
+
+  /// Bounds of all positions the body passes through.
+  ///
+  /// Without rotation every point moves on a straight line, so the bounds at
+  /// both ends cover the motion. Rotating bodies stay within the reach of
+  /// their farthest point around their moving origin.
+  pub fn bounds(&self) -> Bounds {
+    let ends = self.body.bounds().union(self.body_at(1.0).bounds());
+    let rotates = (0..4).any(|i| self.start.rotation[i].fuzzy_ne(self.end.rotation[i]));
+    if !rotates || ends.is_infinite() {
+      return ends;
+    }
+    let object_bounds = self.body.bounds_in_object_space();
+    let farthest = Tuple::vector(
+      object_bounds.min.x.abs().max(object_bounds.max.x.abs()),
+      object_bounds.min.y.abs().max(object_bounds.max.y.abs()),
+      object_bounds.min.z.abs().max(object_bounds.max.z.abs()),
+    );
+    let reach = farthest.magnitude()
+      * self
+        .start
+        .scale
+        .abs()
+        .max_component()
+        .max(self.end.scale.abs().max_component());
+    let reach = Tuple::vector(reach, reach, reach);
+    Bounds::from_points(&[
+      self.start.translation - reach,
+      self.start.translation + reach,
+      self.end.translation - reach,
+      self.end.translation + reach,
+    ])
+  }
+
+  pub fn max_intersections(&self) -> usize {
+    self.body.max_intersections()
+  }
+
+  pub fn intersect(&self, ray: Ray) -> Vec<Intersection> {
+    self.body_at(ray.time).intersect(ray).into_iter().collect()
+  }
+}
+
+impl FuzzyEq<MovingBody> for MovingBody {
+  fn fuzzy_eq(&self, other: MovingBody) -> bool {
+    self.body.fuzzy_eq(other.body) && self.end_transform.fuzzy_eq(other.end_transform)
+  }
+}
+
+/// Translation, rotation and scaling a transformation is made of
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Pose {
+  translation: Tuple,
+  /// Unit quaternion as (w, x, y, z)
+  rotation: [F; 4],
+  scale: Tuple,
+}
+
+impl Pose {
+  /// `None` if the transformation shears, flattens or projects
+  fn of(transform: Matrix<4>) -> Option<Self> {
+    let projects = [0.0, 0.0, 0.0, 1.0]
+      .iter()
+      .enumerate()
+      .any(|(column, value)| transform[3][column].fuzzy_ne(*value));
+    if projects {
+      return None;
+    }
+
+    let column = |column: usize| {
+      Tuple::vector(
+        transform[0][column],
+        transform[1][column],
+        transform[2][column],
+      )
+    };
+    let mut axes = [column(0), column(1), column(2)];
+    let mut scale = Tuple::vector(
+      axes[0].magnitude(),
+      axes[1].magnitude(),
+      axes[2].magnitude(),
+    );
+    for axis in 0..3 {
+      if scale[axis].fuzzy_eq(0.0) {
+        return None;
+      }
+      axes[axis] = axes[axis] / scale[axis];
+    }
+    // Mirroring is kept in the scaling, so the rest is a proper rotation
+    if axes[0].cross(axes[1]).dot(axes[2]) < 0.0 {
+      scale.x = -scale.x;
+      axes[0] = -axes[0];
+    }
+    let shears = axes[0].dot(axes[1]).fuzzy_ne(0.0)
+      || axes[0].dot(axes[2]).fuzzy_ne(0.0)
+      || axes[1].dot(axes[2]).fuzzy_ne(0.0);
+    if shears {
+      return None;
+    }
+
+    Some(Self {
+      translation: Tuple::point(transform[0][3], transform[1][3], transform[2][3]),
+      rotation: quaternion(axes),
+      scale,
+    })
+  }
+
+  fn mirrored(&self) -> bool {
+    self.scale.x < 0.0
+  }
+
+  fn interpolate(&self, other: &Pose, t: F) -> Pose {
+    Pose {
+      translation: self.translation.lerp(other.translation, t),
+      rotation: slerp(self.rotation, other.rotation, t),
+      scale: self.scale.lerp(other.scale, t),
+    }
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    let Tuple { x, y, z, .. } = self.translation;
+    Matrix::translation(x, y, z)
+      * rotation_matrix(self.rotation)
+      * Matrix::scaling(self.scale.x, self.scale.y, self.scale.z)
+  }
+
+  /// Inverse of `transform`, built from the inverses of its parts
+  fn inverse_transform(&self) -> Matrix<4> {
+    let Tuple { x, y, z, .. } = self.translation;
+    Matrix::scaling(1.0 / self.scale.x, 1.0 / self.scale.y, 1.0 / self.scale.z)
+      * rotation_matrix(self.rotation).transpose()
+      * Matrix::translation(-x, -y, -z)
+  }
+}
+
+/// Unit quaternion of the rotation with the given axes as columns
+fn quaternion(axes: [Tuple; 3]) -> [F; 4] {
+  let m = |row: usize, column: usize| axes[column][row];
+  let trace = m(0, 0) + m(1, 1) + m(2, 2);
+  // Divide by the largest component, which keeps the result precise
+  if trace > 0.0 {
+    let s = 2.0 * (trace + 1.0).sqrt();
+    [
+      0.25 * s,
+      (m(2, 1) - m(1, 2)) / s,
+      (m(0, 2) - m(2, 0)) / s,
+      (m(1, 0) - m(0, 1)) / s,
+    ]
+  } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+    let s = 2.0 * (1.0 + m(0, 0) - m(1, 1) - m(2, 2)).sqrt();
+    [
+      (m(2, 1) - m(1, 2)) / s,
+      0.25 * s,
+      (m(0, 1) + m(1, 0)) / s,
+      (m(0, 2) + m(2, 0)) / s,
+    ]
+  } else if m(1, 1) > m(2, 2) {
+    let s = 2.0 * (1.0 + m(1, 1) - m(0, 0) - m(2, 2)).sqrt();
+    [
+      (m(0, 2) - m(2, 0)) / s,
+      (m(0, 1) + m(1, 0)) / s,
+      0.25 * s,
+      (m(1, 2) + m(2, 1)) / s,
+    ]
+  } else {
+    let s = 2.0 * (1.0 + m(2, 2) - m(0, 0) - m(1, 1)).sqrt();
+    [
+      (m(1, 0) - m(0, 1)) / s,
+      (m(0, 2) + m(2, 0)) / s,
+      (m(1, 2) + m(2, 1)) / s,
+      0.25 * s,
+    ]
+  }
+}
+
+#[rustfmt::skip]
+fn rotation_matrix([w, x, y, z]: [F; 4]) -> Matrix<4> {
+  Matrix::from([
+    [1.0 - 2.0 * (y * y + z * z),       2.0 * (x * y - z * w),       2.0 * (x * z + y * w), 0.0],
+    [      2.0 * (x * y + z * w), 1.0 - 2.0 * (x * x + z * z),       2.0 * (y * z - x * w), 0.0],
+    [      2.0 * (x * z - y * w),       2.0 * (y * z + x * w), 1.0 - 2.0 * (x * x + y * y), 0.0],
+    [                        0.0,                         0.0,                         0.0, 1.0],
+  ])
+}
+
+/// Rotation between two unit quaternions at constant speed, taking the
+/// shorter way around
+fn slerp(from: [F; 4], to: [F; 4], t: F) -> [F; 4] {
+  let mut to = to;
+  let mut cos = (0..4).map(|i| from[i] * to[i]).sum::<F>();
+  if cos < 0.0 {
+    to = to.map(|component| -component);
+    cos = -cos;
+  }
+  let (a, b) = if cos > 1.0 - EPSILON {
+    // Nearly the same rotation, where the sine below vanishes
+    (1.0 - t, t)
+  } else {
+    let angle = cos.acos();
+    (
+      ((1.0 - t) * angle).sin() / angle.sin(),
+      (t * angle).sin() / angle.sin(),
+    )
+  };
+  let q: [F; 4] = [0, 1, 2, 3].map(|i| from[i] * a + to[i] * b);
+  let length = q.iter().map(|c| c * c).sum::<F>().sqrt();
+  q.map(|component| component / length)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::angle::Angle;
+  use crate::sphere::Sphere;
+
+  #[test]
+  fn transformation_is_interpolated_over_time() {
+    let moving = MovingBody::new(
+      Body::from(Sphere::default()),
+      Matrix::translation(4.0, 0.0, 0.0),
+    )
+    .unwrap();
+
+    assert_fuzzy_eq!(moving.transform_at(0.0), Matrix::identity());
+    assert_fuzzy_eq!(moving.transform_at(0.5), Matrix::translation(2.0, 0.0, 0.0));
+    assert_fuzzy_eq!(moving.transform_at(1.0), Matrix::translation(4.0, 0.0, 0.0));
+  }
+
+  #[test]
+  fn rays_hit_the_body_at_their_time() {
+    let moving = MovingBody::new(
+      Body::from(Sphere::default()),
+      Matrix::translation(4.0, 0.0, 0.0),
+    )
+    .unwrap();
+    let ray = Ray::new(Tuple::point(4.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    assert!(moving.intersect(ray).is_empty());
+    let xs = moving.intersect(ray.with_time(1.0));
+    assert_eq!(xs.len(), 2);
+    assert_fuzzy_eq!(
+      xs[0].body,
+      Body::from(Sphere::default().with_transform(Matrix::translation(4.0, 0.0, 0.0)))
+    );
+  }
+
+  #[test]
+  fn bounds_cover_the_whole_motion() {
+    let moving = MovingBody::new(
+      Body::from(Sphere::default()),
+      Matrix::translation(4.0, 0.0, 0.0),
+    )
+    .unwrap();
+
+    assert_fuzzy_eq!(
+      moving.bounds(),
+      Bounds::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(5.0, 1.0, 1.0))
+    );
+  }
+
+  #[test]
+  fn half_turns_keep_the_size_of_the_body() {
+    let ellipsoid = Sphere::default().with_transform(Matrix::scaling(2.0, 1.0, 1.0));
+    let moving = MovingBody::new(
+      Body::from(ellipsoid),
+      Matrix::rotation_y(Angle::degrees(180.0)) * Matrix::scaling(2.0, 1.0, 1.0),
+    )
+    .unwrap();
+    let ray = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    // Halfway through the long axis of the body points along z
+    let xs = moving.intersect(ray.with_time(0.5));
+    assert_eq!(xs.len(), 2);
+    assert_fuzzy_eq!(xs[0].t, 3.0);
+    assert_fuzzy_eq!(xs[1].t, 7.0);
+
+    let bounds = moving.bounds();
+    assert!(bounds.min.z <= -2.0 && bounds.max.z >= 2.0);
+  }
+
+  #[test]
+  fn shearing_motion_is_rejected() {
+    let moving = MovingBody::new(
+      Body::from(Sphere::default()),
+      Matrix::shearing(1.0, 0.0, 0.0, 0.0, 0.0, 0.0),
+    );
+
+    assert!(moving.is_none());
+  }
+}
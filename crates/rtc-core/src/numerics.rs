@@ -0,0 +1,52 @@
+//! Root solvers for the intersection equations of analytic bodies
+use crate::F;
+
+/// Real roots of `a * t² + b * t + c = 0` in ascending order, or `None` if
+/// there are none. `a` must not be zero.
+///
+/// The textbook formula subtracts two almost equal numbers for the root
+/// closer to zero, whenever `b²` is much larger than `4ac`. This loses most
+/// of its digits, e.g. for rays starting close to a surface, which shows as
+/// acne at grazing angles. Therefore only the root further away is computed
+/// that way, and the other one from their product `c / a`.
+pub fn solve_quadratic(a: F, b: F, c: F) -> Option<(F, F)> {
+  let discriminant = b * b - 4.0 * a * c;
+  if discriminant < 0.0 {
+    return None;
+  }
+
+  let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+  if q == 0.0 {
+    // Both b and the discriminant vanish, which leaves c at zero as well
+    return Some((0.0, 0.0));
+  }
+
+  let (t0, t1) = (q / a, c / q);
+  Some((t0.min(t1), t0.max(t1)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn quadratics_have_up_to_two_real_roots() {
+    assert_eq!(solve_quadratic(1.0, 0.0, 1.0), None);
+    assert_eq!(solve_quadratic(1.0, -2.0, 1.0), Some((1.0, 1.0)));
+    assert_eq!(solve_quadratic(2.0, 0.0, 0.0), Some((0.0, 0.0)));
+
+    let (t0, t1) = solve_quadratic(-1.0, 1.0, 6.0).unwrap();
+    assert_fuzzy_eq!(t0, -2.0);
+    assert_fuzzy_eq!(t1, 3.0);
+  }
+
+  #[test]
+  fn roots_close_to_zero_keep_their_precision() {
+    // The textbook formula rounds the smaller root to 0 or 1.49e-8
+    let (t0, t1) = solve_quadratic(1.0, -1e8, 1.0).unwrap();
+
+    assert!((t0 - 1e-8).abs() < 1e-20);
+    assert!((t1 - 1e8).abs() < 1e-6);
+  }
+}
@@ -0,0 +1,1005 @@
+use crate::body::{Body, Intersectable};
+use crate::canvas::Color;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::matrix::Matrix;
+use crate::texture::Texture;
+use crate::tuple::Tuple;
+use crate::uv::{CubeMap, TextureMap};
+use crate::F;
+use std::sync::Arc;
+
+pub trait Stencil {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color;
+  fn transform(&self) -> Matrix<4>;
+  /// Inverse of the transform, which patterns cache, as every shaded point
+  /// needs it
+  fn inverse_transform(&self) -> Matrix<4>;
+
+  fn color_at(&self, position: Tuple, body: &Body) -> Color {
+    // Transform into object space
+    let object_position = body.inverse_transform() * position;
+
+    // Transform into pattern space
+    let pattern_position = self.inverse_transform() * object_position;
+
+    self.color_at_in_pattern_space(pattern_position)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+  Striped(Striped),
+  Gradient(Gradient),
+  Ring(Ring),
+  CheckerBoard(CheckerBoard),
+  Noise(Noise),
+  Texture(Texture),
+  TextureMap(TextureMap),
+  CubeMap(CubeMap),
+  Test(TestPattern),
+}
+
+impl FuzzyEq<Pattern> for Pattern {
+  fn fuzzy_eq(&self, other: Pattern) -> bool {
+    match (self, other) {
+      (Pattern::Striped(ref striped), Pattern::Striped(other)) => striped.fuzzy_eq(other),
+      (Pattern::Gradient(ref gradient), Pattern::Gradient(other)) => gradient.fuzzy_eq(other),
+      (Pattern::Ring(ref ring), Pattern::Ring(other)) => ring.fuzzy_eq(other),
+      (Pattern::CheckerBoard(ref checkerboard), Pattern::CheckerBoard(other)) => {
+        checkerboard.fuzzy_eq(other)
+      }
+      (Pattern::Noise(ref noise), Pattern::Noise(other)) => noise.fuzzy_eq(other),
+      (Pattern::Texture(ref texture), Pattern::Texture(other)) => texture.fuzzy_eq(other),
+      (Pattern::TextureMap(ref map), Pattern::TextureMap(other)) => map.fuzzy_eq(other),
+      (Pattern::CubeMap(ref map), Pattern::CubeMap(other)) => map.fuzzy_eq(other),
+      (Pattern::Test(ref test), Pattern::Test(other)) => test.fuzzy_eq(other),
+      _ => false,
+    }
+  }
+}
+
+impl Stencil for Pattern {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    match *self {
+      Pattern::Striped(ref striped) => striped.color_at_in_pattern_space(position),
+      Pattern::Gradient(ref gradient) => gradient.color_at_in_pattern_space(position),
+      Pattern::Ring(ref ring) => ring.color_at_in_pattern_space(position),
+      Pattern::CheckerBoard(ref checkerboard) => checkerboard.color_at_in_pattern_space(position),
+      Pattern::Noise(ref noise) => noise.color_at_in_pattern_space(position),
+      Pattern::Texture(ref texture) => texture.color_at_in_pattern_space(position),
+      Pattern::TextureMap(ref map) => map.color_at_in_pattern_space(position),
+      Pattern::CubeMap(ref map) => map.color_at_in_pattern_space(position),
+      Pattern::Test(ref test) => test.color_at_in_pattern_space(position),
+    }
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    match *self {
+      Pattern::Striped(ref striped) => striped.transform(),
+      Pattern::Gradient(ref gradient) => gradient.transform(),
+      Pattern::Ring(ref ring) => ring.transform(),
+      Pattern::CheckerBoard(ref checkerboard) => checkerboard.transform(),
+      Pattern::Noise(ref noise) => noise.transform(),
+      Pattern::Texture(ref texture) => texture.transform(),
+      Pattern::TextureMap(ref map) => map.transform(),
+      Pattern::CubeMap(ref map) => map.transform(),
+      Pattern::Test(ref test) => test.transform(),
+    }
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    match *self {
+      Pattern::Striped(ref striped) => striped.inverse_transform(),
+      Pattern::Gradient(ref gradient) => gradient.inverse_transform(),
+      Pattern::Ring(ref ring) => ring.inverse_transform(),
+      Pattern::CheckerBoard(ref checkerboard) => checkerboard.inverse_transform(),
+      Pattern::Noise(ref noise) => noise.inverse_transform(),
+      Pattern::Texture(ref texture) => texture.inverse_transform(),
+      Pattern::TextureMap(ref map) => map.inverse_transform(),
+      Pattern::CubeMap(ref map) => map.inverse_transform(),
+      Pattern::Test(ref test) => test.inverse_transform(),
+    }
+  }
+
+  fn color_at(&self, position: Tuple, body: &Body) -> Color {
+    match *self {
+      // Textures are mapped by UV coordinates
+      Pattern::Texture(ref texture) => texture.color_at(position, body),
+      Pattern::TextureMap(ref map) => map.color_at(position, body),
+      _ => {
+        let object_position = body.inverse_transform() * position;
+        let pattern_position = self.inverse_transform() * object_position;
+        self.color_at_in_pattern_space(pattern_position)
+      }
+    }
+  }
+}
+
+impl From<Striped> for Pattern {
+  fn from(striped: Striped) -> Self {
+    Pattern::Striped(striped)
+  }
+}
+
+impl From<Gradient> for Pattern {
+  fn from(gradient: Gradient) -> Self {
+    Pattern::Gradient(gradient)
+  }
+}
+
+impl From<Ring> for Pattern {
+  fn from(ring: Ring) -> Self {
+    Pattern::Ring(ring)
+  }
+}
+
+impl From<CheckerBoard> for Pattern {
+  fn from(checkerboard: CheckerBoard) -> Self {
+    Pattern::CheckerBoard(checkerboard)
+  }
+}
+
+impl From<Noise> for Pattern {
+  fn from(noise: Noise) -> Self {
+    Pattern::Noise(noise)
+  }
+}
+
+impl From<Texture> for Pattern {
+  fn from(texture: Texture) -> Self {
+    Pattern::Texture(texture)
+  }
+}
+
+impl From<TextureMap> for Pattern {
+  fn from(map: TextureMap) -> Self {
+    Pattern::TextureMap(map)
+  }
+}
+
+impl From<CubeMap> for Pattern {
+  fn from(map: CubeMap) -> Self {
+    Pattern::CubeMap(map)
+  }
+}
+
+impl From<TestPattern> for Pattern {
+  fn from(test: TestPattern) -> Self {
+    Pattern::Test(test)
+  }
+}
+
+/// Content of one of the two color slots of a pattern: a flat color, or
+/// another pattern evaluated at the same point, e.g. for a checkerboard of
+/// gradients.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fill {
+  Color(Color),
+  Pattern(Arc<Pattern>),
+}
+
+impl Fill {
+  /// Color at the given point in the space of the pattern owning the slot,
+  /// further transformed by the transformation of a nested pattern
+  pub fn color_at(&self, position: Tuple) -> Color {
+    match self {
+      Fill::Color(color) => *color,
+      Fill::Pattern(pattern) => {
+        pattern.color_at_in_pattern_space(pattern.inverse_transform() * position)
+      }
+    }
+  }
+}
+
+impl From<Color> for Fill {
+  fn from(color: Color) -> Self {
+    Fill::Color(color)
+  }
+}
+
+impl From<Pattern> for Fill {
+  fn from(pattern: Pattern) -> Self {
+    Fill::Pattern(Arc::new(pattern))
+  }
+}
+
+impl FuzzyEq<Fill> for Fill {
+  fn fuzzy_eq(&self, other: Fill) -> bool {
+    match (self, other) {
+      (Fill::Color(color), Fill::Color(other)) => color.fuzzy_eq(other),
+      (Fill::Pattern(pattern), Fill::Pattern(other)) => pattern.fuzzy_eq(Pattern::clone(&other)),
+      _ => false,
+    }
+  }
+}
+
+/// Stripes of alternating colors, each one unit wide, running across the
+/// direction
+#[derive(Clone, Debug, PartialEq)]
+pub struct Striped {
+  pub color_a: Fill,
+  pub color_b: Fill,
+  pub direction: Tuple,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+}
+
+impl Default for Striped {
+  fn default() -> Self {
+    Self {
+      color_a: Fill::Color(Color::black()),
+      color_b: Fill::Color(Color::white()),
+      direction: Tuple::vector(1.0, 0.0, 0.0),
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+    }
+  }
+}
+
+impl Striped {
+  pub fn with_colors(mut self, color_a: impl Into<Fill>, color_b: impl Into<Fill>) -> Self {
+    self.color_a = color_a.into();
+    self.color_b = color_b.into();
+    self
+  }
+
+  /// Direction the colors alternate along, the x axis by default. It is
+  /// normalized, so stripes stay one unit wide along diagonals.
+  pub fn with_direction(mut self, direction: Tuple) -> Self {
+    self.direction = direction.normalize();
+    self
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+}
+
+impl FuzzyEq<Striped> for Striped {
+  fn fuzzy_eq(&self, other: Striped) -> bool {
+    self.color_a.fuzzy_eq(other.color_a)
+      && self.color_b.fuzzy_eq(other.color_b)
+      && self.direction.fuzzy_eq(other.direction)
+      && self.transform.fuzzy_eq(other.transform)
+  }
+}
+
+impl Stencil for Striped {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    let distance = position.dot(self.direction);
+    if distance.floor() as isize % 2 == 0 {
+      self.color_a.color_at(position)
+    } else {
+      self.color_b.color_at(position)
+    }
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+}
+
+/// Ramp of a gradient between its two colors along the x axis
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+  /// Linear ramp, starting over at every whole number
+  #[default]
+  Linear,
+  /// Ramp easing in and out of both colors, starting over at every whole
+  /// number
+  Smoothstep,
+  /// Linear ramp going back and forth between both colors, without hard
+  /// edges
+  Mirrored,
+  /// Single linear ramp from 0 to 1, with the colors continuing beyond it
+  Clamped,
+}
+
+impl Easing {
+  /// Share of the second color at the given position along the ramp
+  pub fn fraction(&self, x: F) -> F {
+    match *self {
+      Easing::Linear => x - x.floor(),
+      Easing::Smoothstep => {
+        let fraction = x - x.floor();
+        fraction * fraction * (3.0 - 2.0 * fraction)
+      }
+      Easing::Mirrored => {
+        let fraction = x - 2.0 * (x / 2.0).floor();
+        if fraction > 1.0 {
+          2.0 - fraction
+        } else {
+          fraction
+        }
+      }
+      Easing::Clamped => x.clamp(0.0, 1.0),
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+  pub color_a: Fill,
+  pub color_b: Fill,
+  pub easing: Easing,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+}
+
+impl Default for Gradient {
+  fn default() -> Self {
+    Self {
+      color_a: Fill::Color(Color::red()),
+      color_b: Fill::Color(Color::green()),
+      easing: Easing::Linear,
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+    }
+  }
+}
+
+impl Gradient {
+  pub fn with_colors(mut self, color_a: impl Into<Fill>, color_b: impl Into<Fill>) -> Self {
+    self.color_a = color_a.into();
+    self.color_b = color_b.into();
+    self
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+
+  pub fn with_easing(mut self, easing: Easing) -> Self {
+    self.easing = easing;
+    self
+  }
+}
+
+impl FuzzyEq<Gradient> for Gradient {
+  fn fuzzy_eq(&self, other: Gradient) -> bool {
+    self.color_a.fuzzy_eq(other.color_a)
+      && self.color_b.fuzzy_eq(other.color_b)
+      && self.easing == other.easing
+      && self.transform.fuzzy_eq(other.transform)
+  }
+}
+
+impl Stencil for Gradient {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    let fraction_of_x = self.easing.fraction(position.x);
+    let color_a = self.color_a.color_at(position);
+    let distance_of_colors = self.color_b.color_at(position) - color_a;
+
+    color_a + distance_of_colors * fraction_of_x
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ring {
+  pub color_a: Fill,
+  pub color_b: Fill,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+}
+
+impl Default for Ring {
+  fn default() -> Self {
+    Self {
+      color_a: Fill::Color(Color::yellow()),
+      color_b: Fill::Color(Color::blue()),
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+    }
+  }
+}
+
+impl Ring {
+  pub fn with_colors(mut self, color_a: impl Into<Fill>, color_b: impl Into<Fill>) -> Self {
+    self.color_a = color_a.into();
+    self.color_b = color_b.into();
+    self
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+}
+
+impl FuzzyEq<Ring> for Ring {
+  fn fuzzy_eq(&self, other: Ring) -> bool {
+    self.color_a.fuzzy_eq(other.color_a)
+      && self.color_b.fuzzy_eq(other.color_b)
+      && self.transform.fuzzy_eq(other.transform)
+  }
+}
+
+impl Stencil for Ring {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    let x = position.x;
+    let y = position.y;
+
+    let distance_from_center = (x.powf(2.0) + y.powf(2.0)).sqrt();
+
+    if distance_from_center.floor() as i64 % 2 == 0 {
+      self.color_a.color_at(position)
+    } else {
+      self.color_b.color_at(position)
+    }
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckerBoard {
+  pub color_a: Fill,
+  pub color_b: Fill,
+  pub third_dimension: bool,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+}
+
+impl Default for CheckerBoard {
+  fn default() -> Self {
+    Self {
+      color_a: Fill::Color(Color::black()),
+      color_b: Fill::Color(Color::white()),
+      third_dimension: true,
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+    }
+  }
+}
+
+impl CheckerBoard {
+  pub fn with_colors(mut self, color_a: impl Into<Fill>, color_b: impl Into<Fill>) -> Self {
+    self.color_a = color_a.into();
+    self.color_b = color_b.into();
+    self
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+
+  pub fn with_third_dimension(mut self, third_dimension: bool) -> Self {
+    self.third_dimension = third_dimension;
+    self
+  }
+}
+
+impl FuzzyEq<CheckerBoard> for CheckerBoard {
+  fn fuzzy_eq(&self, other: CheckerBoard) -> bool {
+    self.color_a.fuzzy_eq(other.color_a)
+      && self.color_b.fuzzy_eq(other.color_b)
+      && self.transform.fuzzy_eq(other.transform)
+      && self.third_dimension == other.third_dimension
+  }
+}
+
+impl Stencil for CheckerBoard {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    let x = position.x;
+    let y = position.y;
+    let z = position.z;
+
+    if (self.third_dimension && ((x.floor() + y.floor() + z.floor()) as i64 % 2) == 0)
+      || (!self.third_dimension && ((x.floor() + y.floor()) as i64 % 2) == 0)
+    {
+      self.color_a.color_at(position)
+    } else {
+      self.color_b.color_at(position)
+    }
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+}
+
+/// Smooth pseudo random blend between two colors, based on Perlin noise.
+///
+/// Every octave adds noise of twice the frequency of the previous one, with
+/// its amplitude scaled by `persistence`. More octaves add finer detail,
+/// a higher persistence makes it rougher.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Noise {
+  pub color_a: Fill,
+  pub color_b: Fill,
+  pub octaves: usize,
+  pub persistence: F,
+  pub seed: u64,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+}
+
+impl Default for Noise {
+  fn default() -> Self {
+    Self {
+      color_a: Fill::Color(Color::black()),
+      color_b: Fill::Color(Color::white()),
+      octaves: 4,
+      persistence: 0.5,
+      seed: 0,
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+    }
+  }
+}
+
+impl Noise {
+  pub fn with_colors(mut self, color_a: impl Into<Fill>, color_b: impl Into<Fill>) -> Self {
+    self.color_a = color_a.into();
+    self.color_b = color_b.into();
+    self
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+
+  pub fn with_octaves(mut self, octaves: usize) -> Self {
+    self.octaves = octaves;
+    self
+  }
+
+  pub fn with_persistence(mut self, persistence: F) -> Self {
+    self.persistence = persistence;
+    self
+  }
+
+  pub fn with_seed(mut self, seed: u64) -> Self {
+    self.seed = seed;
+    self
+  }
+
+  /// Noise of all octaves at the position, in the range [0, 1]
+  pub fn value_at(&self, position: Tuple) -> F {
+    let mut sum = 0.0;
+    let mut amplitude = 1.0;
+    let mut amplitudes = 0.0;
+    let mut frequency = 1.0;
+    for octave in 0..self.octaves.max(1) {
+      let seed = self.seed.wrapping_add(octave as u64);
+      sum += perlin(position * frequency, seed) * amplitude;
+      amplitudes += amplitude;
+      amplitude *= self.persistence;
+      frequency *= 2.0;
+    }
+    (sum / amplitudes * 0.5 + 0.5).clamp(0.0, 1.0)
+  }
+}
+
+/// Gradients along the edges of a cube, as in improved Perlin noise
+const GRADIENTS: [(F, F, F); 12] = [
+  (1.0, 1.0, 0.0),
+  (-1.0, 1.0, 0.0),
+  (1.0, -1.0, 0.0),
+  (-1.0, -1.0, 0.0),
+  (1.0, 0.0, 1.0),
+  (-1.0, 0.0, 1.0),
+  (1.0, 0.0, -1.0),
+  (-1.0, 0.0, -1.0),
+  (0.0, 1.0, 1.0),
+  (0.0, -1.0, 1.0),
+  (0.0, 1.0, -1.0),
+  (0.0, -1.0, -1.0),
+];
+
+/// Gradient of a lattice point. Hashing the coordinates saves storing a
+/// permutation table in each pattern.
+fn gradient(x: i64, y: i64, z: i64, seed: u64) -> (F, F, F) {
+  let mut hash = seed
+    ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+    ^ (z as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+  hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+  hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+  hash ^= hash >> 31;
+  GRADIENTS[(hash % 12) as usize]
+}
+
+/// Single octave of Perlin noise, roughly in the range [-1, 1]
+fn perlin(position: Tuple, seed: u64) -> F {
+  let fade = |t: F| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+  let lerp = |t: F, a: F, b: F| a + t * (b - a);
+  let (x0, y0, z0) = (position.x.floor(), position.y.floor(), position.z.floor());
+  let (x, y, z) = (position.x - x0, position.y - y0, position.z - z0);
+  let (xi, yi, zi) = (x0 as i64, y0 as i64, z0 as i64);
+
+  let corner = |dx: i64, dy: i64, dz: i64| {
+    let (gx, gy, gz) = gradient(xi + dx, yi + dy, zi + dz, seed);
+    gx * (x - dx as F) + gy * (y - dy as F) + gz * (z - dz as F)
+  };
+  let (u, v, w) = (fade(x), fade(y), fade(z));
+  lerp(
+    w,
+    lerp(
+      v,
+      lerp(u, corner(0, 0, 0), corner(1, 0, 0)),
+      lerp(u, corner(0, 1, 0), corner(1, 1, 0)),
+    ),
+    lerp(
+      v,
+      lerp(u, corner(0, 0, 1), corner(1, 0, 1)),
+      lerp(u, corner(0, 1, 1), corner(1, 1, 1)),
+    ),
+  )
+}
+
+impl FuzzyEq<Noise> for Noise {
+  fn fuzzy_eq(&self, other: Noise) -> bool {
+    self.color_a.fuzzy_eq(other.color_a)
+      && self.color_b.fuzzy_eq(other.color_b)
+      && self.octaves == other.octaves
+      && self.persistence.fuzzy_eq(other.persistence)
+      && self.seed == other.seed
+      && self.transform.fuzzy_eq(other.transform)
+  }
+}
+
+impl Stencil for Noise {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    let color_a = self.color_a.color_at(position);
+    color_a + (self.color_b.color_at(position) - color_a) * self.value_at(position)
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+}
+
+/// Colors every point with its coordinates in pattern space, to see how the
+/// transformations of a body and its pattern play together.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TestPattern {
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+}
+
+impl Default for TestPattern {
+  fn default() -> Self {
+    Self {
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+    }
+  }
+}
+
+impl TestPattern {
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+}
+
+impl FuzzyEq<TestPattern> for TestPattern {
+  fn fuzzy_eq(&self, other: TestPattern) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+  }
+}
+
+impl Stencil for TestPattern {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    Color::new(position.x, position.y, position.z)
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::sphere::Sphere;
+
+  use super::*;
+
+  #[test]
+  fn a_stripe_pattern_is_constant_in_y() {
+    let pattern = Striped::default();
+    let body = Body::from(Sphere::default());
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(0.0, 0.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(0.0, 1.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(0.0, 2.0, 0.0), &body)
+    );
+  }
+
+  #[test]
+  fn a_stripe_pattern_is_constant_in_z() {
+    let pattern = Striped::default();
+    let body = Body::from(Sphere::default());
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(0.0, 0.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(0.0, 0.0, 1.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(0.0, 0.0, 2.0), &body)
+    );
+  }
+
+  #[test]
+  fn a_stripe_pattern_alternates_in_x() {
+    let pattern = Striped::default();
+    let body = Body::from(Sphere::default());
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(0.0, 0.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(0.9, 0.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::white(),
+      pattern.color_at(Tuple::point(1.0, 0.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(0.1, 0.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::white(),
+      pattern.color_at(Tuple::point(-0.1, 0.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::white(),
+      pattern.color_at(Tuple::point(-1.0, 0.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(-1.1, 0.0, 0.0), &body)
+    );
+  }
+
+  #[test]
+  fn a_stripe_pattern_alternates_along_its_direction() {
+    let body = Body::from(Sphere::default());
+    let along_y = Striped::default().with_direction(Tuple::vector(0.0, 2.0, 0.0));
+    let diagonal = Striped::default().with_direction(Tuple::vector(1.0, 1.0, 0.0));
+
+    assert_fuzzy_eq!(
+      Color::black(),
+      along_y.color_at(Tuple::point(5.0, 0.5, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::white(),
+      along_y.color_at(Tuple::point(0.0, 1.5, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::black(),
+      diagonal.color_at(Tuple::point(1.0, -1.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::white(),
+      diagonal.color_at(Tuple::point(0.8, 0.8, 0.0), &body)
+    );
+  }
+
+  #[test]
+  fn striped_pattern_adheres_to_object_transform() {
+    let transform = Matrix::scaling(2.0, 2.0, 2.0);
+    let pattern = Pattern::from(Striped::default().with_colors(Color::black(), Color::white()));
+    let body = Body::from(Sphere::default().with_transform(transform));
+
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(1.5, 0.0, 0.0), &body)
+    );
+  }
+
+  #[test]
+  fn striped_pattern_adheres_to_pattern_transform() {
+    let transform = Matrix::scaling(2.0, 2.0, 2.0);
+    let pattern = Pattern::from(
+      Striped::default()
+        .with_colors(Color::black(), Color::white())
+        .with_transform(transform),
+    );
+    let body = Body::from(Sphere::default());
+
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(1.5, 0.0, 0.0), &body)
+    );
+  }
+
+  #[test]
+  fn striped_pattern_adheres_to_object_and_pattern_transform() {
+    let transform = Matrix::scaling(2.0, 2.0, 2.0);
+    let pattern = Pattern::from(
+      Striped::default()
+        .with_colors(Color::black(), Color::white())
+        .with_transform(transform),
+    );
+    let body = Body::from(Sphere::default().with_transform(transform));
+
+    assert_fuzzy_eq!(
+      Color::black(),
+      pattern.color_at(Tuple::point(3.5, 0.0, 0.0), &body)
+    );
+    assert_fuzzy_eq!(
+      Color::white(),
+      pattern.color_at(Tuple::point(4.0, 0.0, 0.0), &body)
+    );
+  }
+
+  #[test]
+  fn the_default_pattern_transformation_is_the_identity() {
+    assert_fuzzy_eq!(TestPattern::default().transform(), Matrix::identity());
+  }
+
+  #[test]
+  fn patterns_cache_the_inverse_of_their_transformation() {
+    let transform = Matrix::translation(1.0, 2.0, 3.0) * Matrix::scaling(2.0, 2.0, 2.0);
+    let pattern = Pattern::from(Striped::default().with_transform(transform));
+
+    assert_fuzzy_eq!(
+      TestPattern::default().inverse_transform(),
+      Matrix::identity()
+    );
+    assert_fuzzy_eq!(pattern.inverse_transform(), transform.inverse());
+  }
+
+  #[test]
+  fn test_patterns_show_the_position_in_pattern_space() {
+    let scaling = Matrix::scaling(2.0, 2.0, 2.0);
+    let scaled_body = Body::from(Sphere::default().with_transform(scaling));
+    let scaled_pattern = Pattern::from(TestPattern::default().with_transform(scaling));
+    let translated_pattern =
+      Pattern::from(TestPattern::default().with_transform(Matrix::translation(0.5, 1.0, 1.5)));
+
+    assert_fuzzy_eq!(
+      Pattern::from(TestPattern::default()).color_at(Tuple::point(2.0, 3.0, 4.0), &scaled_body),
+      Color::new(1.0, 1.5, 2.0)
+    );
+    assert_fuzzy_eq!(
+      scaled_pattern.color_at(Tuple::point(2.0, 3.0, 4.0), &Body::from(Sphere::default())),
+      Color::new(1.0, 1.5, 2.0)
+    );
+    assert_fuzzy_eq!(
+      translated_pattern.color_at(Tuple::point(2.5, 3.0, 3.5), &scaled_body),
+      Color::new(0.75, 0.5, 0.25)
+    );
+  }
+
+  #[test]
+  fn gradients_ramp_according_to_their_easing() {
+    let body = Body::from(Sphere::default());
+    let gradient = |easing: Easing, x: F| {
+      Pattern::from(
+        Gradient::default()
+          .with_colors(Color::black(), Color::white())
+          .with_easing(easing),
+      )
+      .color_at(Tuple::point(x, 0.0, 0.0), &body)
+      .red
+    };
+
+    assert_fuzzy_eq!(gradient(Easing::Linear, 0.25), 0.25);
+    assert_fuzzy_eq!(gradient(Easing::Linear, 1.25), 0.25);
+    assert_fuzzy_eq!(gradient(Easing::Smoothstep, 0.25), 0.15625);
+    assert_fuzzy_eq!(gradient(Easing::Smoothstep, 0.5), 0.5);
+    assert_fuzzy_eq!(gradient(Easing::Mirrored, 1.25), 0.75);
+    assert_fuzzy_eq!(gradient(Easing::Mirrored, -0.25), 0.25);
+    assert_fuzzy_eq!(gradient(Easing::Clamped, 0.25), 0.25);
+    assert_fuzzy_eq!(gradient(Easing::Clamped, 1.25), 1.0);
+    assert_fuzzy_eq!(gradient(Easing::Clamped, -0.25), 0.0);
+  }
+
+  #[test]
+  fn noise_is_smooth_and_reproducible() {
+    let noise = Noise::default();
+    let body = Body::from(Sphere::default());
+    let position = Tuple::point(0.3, 0.7, 0.1);
+
+    let color = noise.color_at(position, &body);
+    let nearby = noise.color_at(position + Tuple::vector(0.001, 0.0, 0.0), &body);
+
+    assert_fuzzy_eq!(color, noise.color_at(position, &body));
+    assert!((color.red - nearby.red).abs() < 0.01);
+    assert_fuzzy_eq!(color.red, color.green);
+  }
+
+  #[test]
+  fn noise_varies_between_the_two_colors() {
+    let noise = Noise::default().with_octaves(1);
+    let values: Vec<F> = (0..100)
+      .map(|index| noise.value_at(Tuple::point(index as F * 0.37, 0.5, 0.25)))
+      .collect();
+
+    assert!(values.iter().all(|value| (0.0..=1.0).contains(value)));
+    assert!(values.iter().any(|value| *value < 0.4));
+    assert!(values.iter().any(|value| *value > 0.6));
+    // Noise is zero on the lattice points
+    assert_fuzzy_eq!(noise.value_at(Tuple::point(3.0, -2.0, 5.0)), 0.5);
+    assert_fuzzy_ne!(
+      noise.value_at(Tuple::point(0.5, 0.5, 0.5)),
+      noise.with_seed(1).value_at(Tuple::point(0.5, 0.5, 0.5))
+    );
+  }
+
+  #[test]
+  fn patterns_can_be_nested_into_the_slots_of_others() {
+    let stripes = Striped::default()
+      .with_colors(Color::red(), Color::blue())
+      .with_transform(Matrix::scaling(0.5, 1.0, 1.0));
+    let checkers =
+      CheckerBoard::default().with_colors(Pattern::from(stripes.clone()), Color::white());
+    let body = Body::from(Sphere::default());
+
+    // The stripes are half as wide as the squares of the checkerboard
+    assert_fuzzy_eq!(
+      checkers.color_at(Tuple::point(0.25, 0.0, 0.0), &body),
+      Color::red()
+    );
+    assert_fuzzy_eq!(
+      checkers.color_at(Tuple::point(0.75, 0.0, 0.0), &body),
+      Color::blue()
+    );
+    assert_fuzzy_eq!(
+      checkers.color_at(Tuple::point(1.5, 0.0, 0.0), &body),
+      Color::white()
+    );
+    assert_fuzzy_ne!(
+      Fill::from(Pattern::from(stripes.clone())),
+      Fill::from(Pattern::from(
+        stripes.with_colors(Color::red(), Color::green())
+      ))
+    );
+  }
+}
@@ -0,0 +1,404 @@
+use crate::body::Intersectable;
+use crate::canvas::Color;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::light::Light;
+use crate::material::{Material, Reflective, Refractive};
+use crate::ray::Ray;
+use crate::sampling::XorShift;
+use crate::tuple::Tuple;
+use crate::world::World;
+use crate::F;
+use std::f64::consts::PI;
+
+/// Light focused by glass and mirrors onto other surfaces.
+///
+/// Before rendering, `photons` photons per light are shot at the reflective
+/// and transparent bodies and followed until they land on a matte surface.
+/// Shading a point gathers the photons within `radius` around it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Caustics {
+  pub photons: usize,
+  pub radius: F,
+  pub seed: u64,
+}
+
+impl Default for Caustics {
+  fn default() -> Self {
+    Self {
+      photons: 50_000,
+      radius: 0.1,
+      seed: 0,
+    }
+  }
+}
+
+impl Caustics {
+  pub fn new(photons: usize, radius: F) -> Self {
+    Self {
+      photons,
+      radius,
+      ..Default::default()
+    }
+  }
+
+  pub fn with_seed(mut self, seed: u64) -> Self {
+    self.seed = seed;
+    self
+  }
+}
+
+impl FuzzyEq<Caustics> for Caustics {
+  fn fuzzy_eq(&self, other: Caustics) -> bool {
+    self.photons == other.photons && self.radius.fuzzy_eq(other.radius) && self.seed == other.seed
+  }
+}
+
+/// Light arriving at a position after at least one reflection or refraction
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Photon {
+  pub position: Tuple,
+  /// Direction the photon travelled in
+  pub direction: Tuple,
+  pub power: Color,
+}
+
+/// Photons stored in a kd-tree. Every range of the list is split at its
+/// middle photon, with the smaller ones along its axis before it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PhotonMap {
+  photons: Vec<Photon>,
+  axes: Vec<usize>,
+}
+
+fn coordinate(point: Tuple, axis: usize) -> F {
+  match axis {
+    0 => point.x,
+    1 => point.y,
+    _ => point.z,
+  }
+}
+
+impl PhotonMap {
+  pub fn new(mut photons: Vec<Photon>) -> Self {
+    let mut axes = vec![0; photons.len()];
+    Self::build(&mut photons, &mut axes);
+    Self { photons, axes }
+  }
+
+  fn build(photons: &mut [Photon], axes: &mut [usize]) {
+    if photons.len() <= 1 {
+      return;
+    }
+    // Split along the axis the photons spread out most on
+    let extent = |axis: usize| {
+      let (min, max) = photons
+        .iter()
+        .fold((F::INFINITY, F::NEG_INFINITY), |(min, max), photon| {
+          let value = coordinate(photon.position, axis);
+          (min.min(value), max.max(value))
+        });
+      max - min
+    };
+    let axis = (0..3)
+      .max_by(|a, b| extent(*a).partial_cmp(&extent(*b)).unwrap())
+      .unwrap();
+
+    let middle = photons.len() / 2;
+    photons.select_nth_unstable_by(middle, |a, b| {
+      coordinate(a.position, axis)
+        .partial_cmp(&coordinate(b.position, axis))
+        .unwrap()
+    });
+    axes[middle] = axis;
+    let (lower_photons, upper_photons) = photons.split_at_mut(middle);
+    let (lower_axes, upper_axes) = axes.split_at_mut(middle);
+    Self::build(lower_photons, lower_axes);
+    Self::build(&mut upper_photons[1..], &mut upper_axes[1..]);
+  }
+
+  /// Shoots photons from every light of the world, keeping those landing on
+  /// a matte surface after at least one reflection or refraction.
+  ///
+  /// Photons are only shot towards bounded reflective and transparent
+  /// bodies, everything else is lit directly anyway.
+  pub fn trace(world: &World, caustics: &Caustics) -> Self {
+    let mut random = XorShift::new(caustics.seed);
+    let mut photons = vec![];
+    let targets = focusing_bounds(world);
+    for light in world.lights().iter() {
+      for (center, radius) in targets.iter() {
+        trace_towards(
+          world,
+          *light,
+          *center,
+          *radius,
+          caustics.photons / targets.len(),
+          &mut random,
+          &mut photons,
+        );
+      }
+    }
+    Self::new(photons)
+  }
+
+  pub fn len(&self) -> usize {
+    self.photons.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.photons.is_empty()
+  }
+
+  /// All photons within the radius around the point
+  pub fn within(&self, point: Tuple, radius: F) -> Vec<&Photon> {
+    let mut found = vec![];
+    self.collect_within(0, self.photons.len(), point, radius, &mut found);
+    found
+  }
+
+  fn collect_within<'a>(
+    &'a self,
+    start: usize,
+    end: usize,
+    point: Tuple,
+    radius: F,
+    found: &mut Vec<&'a Photon>,
+  ) {
+    if start >= end {
+      return;
+    }
+    let middle = start + (end - start) / 2;
+    let photon = &self.photons[middle];
+    if (photon.position - point).magnitude() <= radius {
+      found.push(photon);
+    }
+    if end - start == 1 {
+      return;
+    }
+    let axis = self.axes[middle];
+    let offset = coordinate(point, axis) - coordinate(photon.position, axis);
+    if offset <= radius {
+      self.collect_within(start, middle, point, radius, found);
+    }
+    if offset >= -radius {
+      self.collect_within(middle + 1, end, point, radius, found);
+    }
+  }
+
+  /// Light per area arriving at the point from the front of the surface, and
+  /// the direction it mainly travels in. None if no photon is close enough.
+  pub fn irradiance(&self, point: Tuple, normalv: Tuple, radius: F) -> Option<(Color, Tuple)> {
+    let mut power = Color::black();
+    let mut direction = Tuple::vector(0.0, 0.0, 0.0);
+    for photon in self.within(point, radius) {
+      if photon.direction.dot(normalv) >= 0.0 {
+        continue;
+      }
+      power = power + photon.power;
+      direction = direction + photon.direction * photon.power.luminance();
+    }
+    if direction.magnitude() == 0.0 {
+      return None;
+    }
+    Some((
+      power * (1.0 / (PI * radius * radius)),
+      direction.normalize(),
+    ))
+  }
+}
+
+fn is_focusing(material: &Material) -> bool {
+  material.reflectiveness() > 0.0 || material.transparency() > 0.0
+}
+
+/// Spheres around the reflective and transparent bodies photons are shot at
+fn focusing_bounds(world: &World) -> Vec<(Tuple, F)> {
+  let bodies = world
+    .bodies()
+    .iter()
+    .filter(|body| is_focusing(&body.material()))
+    .map(|body| body.bounds());
+  let instances = world
+    .instances()
+    .iter()
+    .filter(|instance| instance.material.as_ref().is_some_and(is_focusing))
+    .map(|instance| instance.bounds());
+  bodies
+    .chain(instances)
+    .filter(|bounds| !bounds.is_infinite())
+    .map(|bounds| (bounds.center(), (bounds.max - bounds.center()).magnitude()))
+    .collect()
+}
+
+/// Shoots photons into the cone from the light around the sphere
+fn trace_towards(
+  world: &World,
+  light: Light,
+  center: Tuple,
+  radius: F,
+  count: usize,
+  random: &mut XorShift,
+  photons: &mut Vec<Photon>,
+) {
+  let origin = light.position();
+  let axis = center - origin;
+  let distance = axis.magnitude();
+  if count == 0 || distance <= radius {
+    return;
+  }
+  let axis = axis.normalize();
+  let helper = if axis.x.abs() > 0.9 {
+    Tuple::vector(0.0, 1.0, 0.0)
+  } else {
+    Tuple::vector(1.0, 0.0, 0.0)
+  };
+  let tangent = helper.cross(axis).normalize();
+  let bitangent = axis.cross(tangent);
+  let cos_max = (1.0 - (radius / distance).powi(2)).sqrt();
+  let solid_angle = 2.0 * PI * (1.0 - cos_max);
+
+  for _ in 0..count {
+    let cos_theta = 1.0 - random.next_float() * (1.0 - cos_max);
+    let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+    let phi = 2.0 * PI * random.next_float();
+    let direction = axis * cos_theta + (tangent * phi.cos() + bitangent * phi.sin()) * sin_theta;
+    let ray = Ray::new(origin, direction);
+    if let Some(photon) = follow(world, light, ray, solid_angle / count as F, random) {
+      photons.push(photon);
+    }
+  }
+}
+
+/// Follows a photon through reflections and refractions, choosing between
+/// them at random, until it lands on a matte surface
+fn follow(
+  world: &World,
+  light: Light,
+  mut ray: Ray,
+  solid_angle: F,
+  random: &mut XorShift,
+) -> Option<Photon> {
+  let mut power = Color::black();
+  for bounce in 0..=world.reflection_limit() {
+    let xs = world.intersect(ray);
+    let hit = xs.hit()?;
+    let c = hit.get_computed_with(&xs);
+    let material = hit.body.material();
+    if bounce == 0 {
+      // Lights don't fall off with the distance in this renderer, so the
+      // photons spread out over the distance to the first hit are
+      // compensated for, to match the direct light.
+      power = light.intensity_at(c.point) * solid_angle * hit.t * hit.t;
+    }
+
+    let reflectiveness = material.reflectiveness_at(&hit.body, c.point, c.eyev, c.normalv);
+    let transparency = material.transparency_at(&hit.body, c.point);
+    let choice = random.next_float();
+    let (reflect, refract) = if reflectiveness > 0.0 && transparency > 0.0 {
+      let reflectance = c.schlick();
+      (choice < reflectance, choice >= reflectance)
+    } else {
+      (
+        choice < reflectiveness,
+        choice >= reflectiveness && choice < reflectiveness + transparency,
+      )
+    };
+
+    ray = match (reflect, refract, c.refracted_direction()) {
+      (false, true, Some(direction)) => Ray::new(c.under_point, direction),
+      (false, false, _) if bounce == 0 => return None,
+      (false, false, _) => {
+        return Some(Photon {
+          position: c.point,
+          direction: ray.direction.normalize(),
+          power,
+        })
+      }
+      // Also on total internal reflection
+      _ => Ray::new(c.over_point, c.reflectv),
+    };
+  }
+  None
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::body::Body;
+  use crate::material::Phong;
+  use crate::plane::Plane;
+  use crate::test_scene::{glass, sphere_at, white_light_at};
+
+  fn photon_at(x: F, y: F, z: F) -> Photon {
+    Photon {
+      position: Tuple::point(x, y, z),
+      direction: Tuple::vector(0.0, -1.0, 0.0),
+      power: Color::white(),
+    }
+  }
+
+  #[test]
+  fn the_kd_tree_finds_all_photons_within_the_radius() {
+    let mut random = XorShift::new(7);
+    let photons: Vec<Photon> = (0..500)
+      .map(|_| {
+        photon_at(
+          random.next_float(),
+          random.next_float(),
+          random.next_float(),
+        )
+      })
+      .collect();
+    let point = Tuple::point(0.5, 0.5, 0.5);
+
+    let map = PhotonMap::new(photons.clone());
+
+    let expected = photons
+      .iter()
+      .filter(|photon| (photon.position - point).magnitude() <= 0.2)
+      .count();
+    assert_eq!(map.len(), 500);
+    assert_eq!(map.within(point, 0.2).len(), expected);
+  }
+
+  #[test]
+  fn only_photons_arriving_at_the_front_count() {
+    let mut from_behind = photon_at(0.0, 0.0, 0.0);
+    from_behind.direction = Tuple::vector(0.0, 1.0, 0.0);
+    let map = PhotonMap::new(vec![photon_at(0.0, 0.0, 0.0), from_behind]);
+
+    let (irradiance, direction) = map
+      .irradiance(
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::vector(0.0, 1.0, 0.0),
+        1.0,
+      )
+      .unwrap();
+
+    assert_fuzzy_eq!(irradiance, Color::white() * (1.0 / PI));
+    assert_fuzzy_eq!(direction, Tuple::vector(0.0, -1.0, 0.0));
+  }
+
+  #[test]
+  fn a_glass_ball_focuses_photons_below_it() {
+    let ball = sphere_at(0.0, 2.0, 0.0).with_material(Material::from(glass()));
+    let floor = Plane::default().with_material(Material::from(Phong::default()));
+    let world = World::new(
+      vec![Body::from(floor), Body::from(ball)],
+      vec![white_light_at(0.0, 10.0, 0.0)],
+    );
+
+    let map = PhotonMap::trace(&world, &Caustics::new(2_000, 0.2));
+
+    let below = map.within(Tuple::point(0.0, 0.0, 0.0), 0.5).len();
+    let aside = map.within(Tuple::point(2.0, 0.0, 0.0), 0.5).len();
+    assert!(below > 10 * aside.max(1));
+  }
+
+  #[test]
+  fn matte_worlds_have_no_caustics() {
+    let map = PhotonMap::trace(&World::book_default(), &Caustics::default());
+
+    assert!(map.is_empty());
+  }
+}
@@ -1,21 +1,26 @@
-use crate::body::{Body, Intersectable};
+use crate::body::{Body, Intersectable, Shadows};
+use crate::bounds::Bounds;
 use crate::fuzzy_eq::FuzzyEq;
 use crate::material::Material;
 use crate::matrix::Matrix;
 use crate::tuple::Tuple;
 use crate::EPSILON;
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Plane {
   material: Material,
+  shadows: Shadows,
   transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
 }
 
 impl Default for Plane {
   fn default() -> Self {
     Self {
       transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
       material: Default::default(),
+      shadows: Default::default(),
     }
   }
 }
@@ -25,6 +30,8 @@ impl Plane {
     Self {
       material,
       transform,
+      inverse_transform: transform.inverse(),
+      shadows: Default::default(),
     }
   }
 
@@ -33,21 +40,47 @@ impl Plane {
     self
   }
 
-  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.shadows = shadows;
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
     self.transform = transform;
+    self.inverse_transform = inverse_transform;
     self
   }
 }
 
 impl Intersectable for Plane {
+  fn max_intersections(&self) -> usize {
+    1
+  }
+
   fn material(&self) -> Material {
-    self.material
+    self.material.clone()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.shadows
   }
 
   fn transform(&self) -> Matrix<4> {
     self.transform
   }
 
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
   fn intersect_in_object_space(
     &self,
     object_space_ray: crate::ray::Ray,
@@ -57,7 +90,7 @@ impl Intersectable for Plane {
     }
 
     let t = -object_space_ray.origin.y / object_space_ray.direction.y;
-    vec![(t, Body::from(*self))]
+    vec![(t, Body::from(self.clone()))]
   }
 
   fn normal_at_in_object_space(
@@ -66,11 +99,17 @@ impl Intersectable for Plane {
   ) -> crate::tuple::Tuple {
     Tuple::vector(0.0, 1.0, 0.0)
   }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    Bounds::infinite()
+  }
 }
 
 impl FuzzyEq<&Plane> for Plane {
   fn fuzzy_eq(&self, other: &Plane) -> bool {
-    self.transform.fuzzy_eq(other.transform) && self.material.fuzzy_eq(other.material)
+    self.transform.fuzzy_eq(other.transform)
+      && self.material.fuzzy_eq(other.material.clone())
+      && self.shadows == other.shadows
   }
 }
 
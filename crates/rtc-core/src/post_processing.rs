@@ -0,0 +1,142 @@
+pub mod auto_exposure;
+pub mod bloom;
+pub mod glare;
+pub mod lens_distortion;
+pub mod lut;
+pub mod vignette;
+pub mod watermark;
+
+use crate::canvas::Canvas;
+use auto_exposure::AutoExposure;
+use bloom::Bloom;
+use glare::Glare;
+use lens_distortion::LensDistortion;
+use lut::Lut;
+use vignette::Vignette;
+
+/// An effect, which is applied to an already rendered canvas.
+pub trait PostProcess {
+  fn apply(&self, canvas: &Canvas) -> Canvas;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Effect {
+  AutoExposure(AutoExposure),
+  Bloom(Bloom),
+  Glare(Glare),
+  LensDistortion(LensDistortion),
+  Lut(Lut),
+  Vignette(Vignette),
+}
+
+impl From<AutoExposure> for Effect {
+  fn from(auto_exposure: AutoExposure) -> Self {
+    Effect::AutoExposure(auto_exposure)
+  }
+}
+
+impl From<Bloom> for Effect {
+  fn from(bloom: Bloom) -> Self {
+    Effect::Bloom(bloom)
+  }
+}
+
+impl From<Glare> for Effect {
+  fn from(glare: Glare) -> Self {
+    Effect::Glare(glare)
+  }
+}
+
+impl From<LensDistortion> for Effect {
+  fn from(lens_distortion: LensDistortion) -> Self {
+    Effect::LensDistortion(lens_distortion)
+  }
+}
+
+impl From<Lut> for Effect {
+  fn from(lut: Lut) -> Self {
+    Effect::Lut(lut)
+  }
+}
+
+impl From<Vignette> for Effect {
+  fn from(vignette: Vignette) -> Self {
+    Effect::Vignette(vignette)
+  }
+}
+
+impl PostProcess for Effect {
+  fn apply(&self, canvas: &Canvas) -> Canvas {
+    match self {
+      Effect::AutoExposure(ref auto_exposure) => auto_exposure.apply(canvas),
+      Effect::Bloom(ref bloom) => bloom.apply(canvas),
+      Effect::Glare(ref glare) => glare.apply(canvas),
+      Effect::LensDistortion(ref lens_distortion) => lens_distortion.apply(canvas),
+      Effect::Lut(ref lut) => lut.apply(canvas),
+      Effect::Vignette(ref vignette) => vignette.apply(canvas),
+    }
+  }
+}
+
+/// Ordered list of effects, which are applied one after the other.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Pipeline {
+  pub effects: Vec<Effect>,
+}
+
+impl Pipeline {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_effect(mut self, effect: impl Into<Effect>) -> Self {
+    self.effects.push(effect.into());
+    self
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.effects.is_empty()
+  }
+}
+
+impl PostProcess for Pipeline {
+  fn apply(&self, canvas: &Canvas) -> Canvas {
+    self
+      .effects
+      .iter()
+      .fold(canvas.clone(), |canvas, effect| effect.apply(&canvas))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::Color;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn empty_pipeline_does_not_change_the_canvas() {
+    let mut canvas = Canvas::new(3, 3);
+    canvas.write_pixel(1, 1, Color::new(4.0, 0.5, 0.25));
+
+    let result = Pipeline::new().apply(&canvas);
+
+    assert_fuzzy_eq!(result.pixel_at(1, 1), Color::new(4.0, 0.5, 0.25));
+  }
+
+  #[test]
+  fn pipeline_applies_effects_in_order() {
+    let mut canvas = Canvas::new(11, 11);
+    canvas.write_pixel(0, 0, Color::new(4.0, 4.0, 4.0));
+
+    let pipeline = Pipeline::new()
+      .with_effect(Vignette::new(1.0, 0.0))
+      .with_effect(Bloom::new(1.0, 1.0, 1.0));
+
+    let result = pipeline.apply(&canvas);
+
+    // The vignette removed the bright corner before bloom could spread it
+    assert_eq!(pipeline.effects.len(), 2);
+    assert_fuzzy_eq!(result.pixel_at(1, 0), Color::black());
+  }
+}
@@ -0,0 +1,106 @@
+use super::PostProcess;
+use crate::canvas::Canvas;
+use crate::F;
+
+/// Scales the canvas so its log-average luminance ends up at `key`, like
+/// the automatic exposure of a camera. `compensation` adds stops on top.
+///
+/// Bright highlights hardly influence the log-average, so a few lights in
+/// view don't darken the rest of the image. Put it in front of effects
+/// depending on the brightness, like bloom, glare or a LUT.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AutoExposure {
+  pub key: F,
+  pub compensation: F,
+}
+
+impl AutoExposure {
+  pub fn new(key: F, compensation: F) -> Self {
+    Self { key, compensation }
+  }
+
+  /// Factor the colors of the canvas are scaled by
+  pub fn scale(&self, canvas: &Canvas) -> F {
+    let pixels = canvas.width * canvas.height;
+    if pixels == 0 {
+      return 1.0;
+    }
+    // Offset keeping black pixels from pulling the average down to zero
+    let delta = 0.0001;
+    let mut log_sum = 0.0;
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        log_sum += (delta + canvas.pixel_at(x, y).luminance().max(0.0)).ln();
+      }
+    }
+    let log_average = (log_sum / pixels as F).exp();
+    self.key / log_average * (2.0 as F).powf(self.compensation)
+  }
+}
+
+impl Default for AutoExposure {
+  /// Middle gray, as in photography
+  fn default() -> Self {
+    Self::new(0.18, 0.0)
+  }
+}
+
+impl PostProcess for AutoExposure {
+  fn apply(&self, canvas: &Canvas) -> Canvas {
+    let scale = self.scale(canvas);
+    let mut result = Canvas::new(canvas.width, canvas.height);
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        result.write_pixel(x, y, canvas.pixel_at(x, y) * scale);
+      }
+    }
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::Color;
+
+  fn filled(width: usize, height: usize, color: Color) -> Canvas {
+    let mut canvas = Canvas::new(width, height);
+    for y in 0..height {
+      for x in 0..width {
+        canvas.write_pixel(x, y, color);
+      }
+    }
+    canvas
+  }
+
+  #[test]
+  fn uniform_images_are_brought_to_the_key() {
+    let dark = filled(4, 4, Color::new(0.01, 0.01, 0.01));
+    let bright = filled(4, 4, Color::new(20.0, 20.0, 20.0));
+
+    let exposure = AutoExposure::default();
+
+    // Only the small offset for black pixels keeps them from hitting it exactly
+    assert!((exposure.apply(&dark).pixel_at(0, 0).red - 0.18).abs() < 0.002);
+    assert!((exposure.apply(&bright).pixel_at(3, 3).red - 0.18).abs() < 0.002);
+  }
+
+  #[test]
+  fn compensation_adds_stops() {
+    let canvas = filled(2, 2, Color::new(0.5, 0.5, 0.5));
+
+    let result = AutoExposure::new(0.18, 1.0).apply(&canvas);
+
+    assert!((result.pixel_at(1, 1).red - 0.36).abs() < 0.001);
+  }
+
+  #[test]
+  fn a_small_highlight_hardly_changes_the_exposure() {
+    let mut canvas = filled(10, 10, Color::new(0.18, 0.18, 0.18));
+    canvas.write_pixel(0, 0, Color::new(100.0, 100.0, 100.0));
+
+    let scale = AutoExposure::default().scale(&canvas);
+
+    assert!(scale > 0.9);
+  }
+}
@@ -0,0 +1,90 @@
+use super::PostProcess;
+use crate::canvas::filter::EdgeMode;
+use crate::canvas::Canvas;
+use crate::F;
+
+/// Lets bright parts of the canvas bleed into their surroundings.
+///
+/// Everything above the luminance `threshold` is blurred using a gaussian
+/// kernel and added back onto the canvas, scaled by `intensity`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bloom {
+  pub threshold: F,
+  pub sigma: F,
+  pub intensity: F,
+}
+
+impl Bloom {
+  pub fn new(threshold: F, sigma: F, intensity: F) -> Self {
+    Self {
+      threshold,
+      sigma,
+      intensity,
+    }
+  }
+}
+
+impl Default for Bloom {
+  fn default() -> Self {
+    Self::new(1.0, 4.0, 0.5)
+  }
+}
+
+impl PostProcess for Bloom {
+  fn apply(&self, canvas: &Canvas) -> Canvas {
+    let mut bright = Canvas::new(canvas.width, canvas.height);
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        let color = canvas.pixel_at(x, y);
+        let luminance = color.luminance();
+        if luminance > self.threshold {
+          bright.write_pixel(x, y, color * ((luminance - self.threshold) / luminance));
+        }
+      }
+    }
+
+    let glow = bright.gaussian_blur(self.sigma, EdgeMode::Clamp);
+
+    let mut result = Canvas::new(canvas.width, canvas.height);
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        result.write_pixel(
+          x,
+          y,
+          canvas.pixel_at(x, y) + glow.pixel_at(x, y) * self.intensity,
+        );
+      }
+    }
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::Color;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn dark_canvas_is_not_changed_by_bloom() {
+    let mut canvas = Canvas::new(5, 5);
+    canvas.write_pixel(2, 2, Color::new(0.5, 0.5, 0.5));
+
+    let result = Bloom::new(1.0, 1.0, 1.0).apply(&canvas);
+
+    assert_fuzzy_eq!(result.pixel_at(2, 2), Color::new(0.5, 0.5, 0.5));
+    assert_fuzzy_eq!(result.pixel_at(1, 2), Color::black());
+  }
+
+  #[test]
+  fn bright_pixels_bleed_into_their_neighbours() {
+    let mut canvas = Canvas::new(9, 9);
+    canvas.write_pixel(4, 4, Color::new(10.0, 10.0, 10.0));
+
+    let result = Bloom::new(1.0, 1.0, 1.0).apply(&canvas);
+
+    assert!(result.pixel_at(3, 4).red > 0.0);
+    assert!(result.pixel_at(4, 5).red > 0.0);
+    assert!(result.pixel_at(3, 4).red < result.pixel_at(4, 4).red);
+  }
+}
@@ -0,0 +1,104 @@
+use super::PostProcess;
+use crate::canvas::Canvas;
+use crate::F;
+use std::f64::consts::PI;
+
+/// Star shaped streaks around bright parts of the canvas.
+///
+/// Every pixel above the luminance `threshold` emits `streaks` evenly spaced
+/// rays of `length` pixels, which fade out quadratically.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Glare {
+  pub threshold: F,
+  pub streaks: usize,
+  pub length: usize,
+  pub intensity: F,
+}
+
+impl Glare {
+  pub fn new(threshold: F, streaks: usize, length: usize, intensity: F) -> Self {
+    Self {
+      threshold,
+      streaks,
+      length,
+      intensity,
+    }
+  }
+}
+
+impl Default for Glare {
+  fn default() -> Self {
+    Self::new(1.0, 4, 16, 0.25)
+  }
+}
+
+impl PostProcess for Glare {
+  fn apply(&self, canvas: &Canvas) -> Canvas {
+    let mut result = Canvas::new(canvas.width, canvas.height);
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        result.write_pixel(x, y, canvas.pixel_at(x, y));
+      }
+    }
+
+    let directions: Vec<(F, F)> = (0..self.streaks)
+      .map(|streak| {
+        let angle = PI / 4.0 + 2.0 * PI * streak as F / self.streaks as F;
+        (angle.cos(), angle.sin())
+      })
+      .collect();
+
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        let color = canvas.pixel_at(x, y);
+        let luminance = color.luminance();
+        if luminance <= self.threshold {
+          continue;
+        }
+        let excess = color * ((luminance - self.threshold) / luminance * self.intensity);
+
+        for (direction_x, direction_y) in directions.iter() {
+          for step in 1..=self.length {
+            let target_x = (x as F + 0.5 + direction_x * step as F).floor();
+            let target_y = (y as F + 0.5 + direction_y * step as F).floor();
+            if target_x < 0.0
+              || target_y < 0.0
+              || target_x >= canvas.width as F
+              || target_y >= canvas.height as F
+            {
+              break;
+            }
+            let fade = 1.0 - step as F / (self.length + 1) as F;
+            let (target_x, target_y) = (target_x as usize, target_y as usize);
+            let current = result.pixel_at(target_x, target_y);
+            result.write_pixel(target_x, target_y, current + excess * (fade * fade));
+          }
+        }
+      }
+    }
+
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::Color;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn glare_streaks_start_at_bright_pixels() {
+    let mut canvas = Canvas::new(9, 9);
+    canvas.write_pixel(4, 4, Color::new(5.0, 5.0, 5.0));
+
+    let result = Glare::new(1.0, 4, 3, 1.0).apply(&canvas);
+
+    // Streaks run diagonally by default
+    assert!(result.pixel_at(5, 5).red > 0.0);
+    assert!(result.pixel_at(3, 3).red > 0.0);
+    assert!(result.pixel_at(5, 5).red > result.pixel_at(6, 6).red);
+    assert_fuzzy_eq!(result.pixel_at(4, 5), Color::black());
+    assert_fuzzy_eq!(result.pixel_at(4, 4), Color::new(5.0, 5.0, 5.0));
+  }
+}
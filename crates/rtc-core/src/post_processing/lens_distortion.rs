@@ -0,0 +1,173 @@
+use super::PostProcess;
+use crate::canvas::{Canvas, Color};
+use crate::fuzzy_eq::FuzzyEq;
+use crate::F;
+
+/// Radial lens distortion using the polynomial model `r * (1 + k1 * r^2 + k2 * r^4)`.
+///
+/// Radii are normalized, so that the corners of the canvas are at a distance
+/// of 1 from its center. Positive coefficients create a barrel distortion,
+/// while negative ones create a pincushion distortion.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LensDistortion {
+  pub k1: F,
+  pub k2: F,
+  inverted: bool,
+}
+
+impl LensDistortion {
+  pub fn new(k1: F, k2: F) -> Self {
+    Self {
+      k1,
+      k2,
+      inverted: false,
+    }
+  }
+
+  /// Distortion, which undoes this one (e.g. to remove the distortion of real
+  /// camera footage).
+  pub fn inverse(&self) -> Self {
+    Self {
+      inverted: !self.inverted,
+      ..*self
+    }
+  }
+
+  pub fn is_inverted(&self) -> bool {
+    self.inverted
+  }
+
+  fn distort_radius(&self, radius: F) -> F {
+    let radius_squared = radius * radius;
+    radius * (1.0 + self.k1 * radius_squared + self.k2 * radius_squared * radius_squared)
+  }
+
+  /// Solves `distort_radius(x) = radius` for x using newton iterations.
+  fn undistort_radius(&self, radius: F) -> F {
+    let mut guess = radius;
+    for _ in 0..20 {
+      let guess_squared = guess * guess;
+      let derivative =
+        1.0 + 3.0 * self.k1 * guess_squared + 5.0 * self.k2 * guess_squared * guess_squared;
+      if derivative.abs() < crate::EPSILON {
+        break;
+      }
+      let step = (self.distort_radius(guess) - radius) / derivative;
+      guess -= step;
+      if step.abs() < crate::EPSILON {
+        break;
+      }
+    }
+    guess
+  }
+
+  /// Radius in the source canvas, which is visible at the given target radius.
+  fn source_radius(&self, radius: F) -> F {
+    if self.inverted {
+      self.undistort_radius(radius)
+    } else {
+      self.distort_radius(radius)
+    }
+  }
+}
+
+impl PostProcess for LensDistortion {
+  fn apply(&self, canvas: &Canvas) -> Canvas {
+    let mut result = Canvas::new(canvas.width, canvas.height);
+    let center_x = canvas.width as F / 2.0;
+    let center_y = canvas.height as F / 2.0;
+    let half_diagonal = (center_x * center_x + center_y * center_y).sqrt();
+
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        let offset_x = (x as F + 0.5 - center_x) / half_diagonal;
+        let offset_y = (y as F + 0.5 - center_y) / half_diagonal;
+        let radius = (offset_x * offset_x + offset_y * offset_y).sqrt();
+
+        let scale = if radius.fuzzy_eq(0.0) {
+          1.0
+        } else {
+          self.source_radius(radius) / radius
+        };
+
+        let source_x = center_x + offset_x * scale * half_diagonal;
+        let source_y = center_y + offset_y * scale * half_diagonal;
+
+        let color = if (0.0..=canvas.width as F).contains(&source_x)
+          && (0.0..=canvas.height as F).contains(&source_y)
+        {
+          canvas.sample_bilinear(source_x, source_y)
+        } else {
+          Color::black()
+        };
+
+        result.write_pixel(x, y, color);
+      }
+    }
+
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_gradient_canvas() -> Canvas {
+    let mut canvas = Canvas::new(20, 10);
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        canvas.write_pixel(x, y, Color::new(x as F / 20.0, y as F / 10.0, 0.5));
+      }
+    }
+    canvas
+  }
+
+  #[test]
+  fn zero_coefficients_do_not_change_the_canvas() {
+    let canvas = create_gradient_canvas();
+    let distorted = LensDistortion::new(0.0, 0.0).apply(&canvas);
+
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        assert_fuzzy_eq!(distorted.pixel_at(x, y), canvas.pixel_at(x, y));
+      }
+    }
+  }
+
+  #[test]
+  fn inverse_radius_undoes_the_distortion() {
+    let distortion = LensDistortion::new(0.2, -0.05);
+    let inverse = distortion.inverse();
+
+    for radius in [0.0, 0.1, 0.5, 0.9, 1.0] {
+      assert_fuzzy_eq!(
+        inverse.source_radius(distortion.source_radius(radius)),
+        radius
+      );
+    }
+  }
+
+  #[test]
+  fn inverting_twice_restores_the_distortion() {
+    let distortion = LensDistortion::new(0.2, 0.1);
+
+    assert!(distortion.inverse().is_inverted());
+    assert_eq!(distortion.inverse().inverse(), distortion);
+  }
+
+  #[test]
+  fn barrel_distortion_leaves_the_corners_empty() {
+    let mut canvas = Canvas::new(20, 10);
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        canvas.write_pixel(x, y, Color::white());
+      }
+    }
+
+    let distorted = LensDistortion::new(0.5, 0.0).apply(&canvas);
+
+    assert_fuzzy_eq!(distorted.pixel_at(0, 0), Color::black());
+    assert_fuzzy_eq!(distorted.pixel_at(10, 5), Color::white());
+  }
+}
@@ -0,0 +1,178 @@
+use super::PostProcess;
+use crate::canvas::{Canvas, Color};
+use crate::fingerprint::Fingerprint;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::F;
+use std::fmt;
+use std::sync::Arc;
+
+/// Color grading with a 3D lookup table, e.g. loaded from a `.cube` file.
+///
+/// Colors are clamped to the domain of the table and interpolated
+/// trilinearly between its entries.
+#[derive(Clone)]
+pub struct Lut {
+  pub size: usize,
+  pub domain_min: Color,
+  pub domain_max: Color,
+  table: Arc<[Color]>,
+  /// Hash of the table, which is too large to be part of the debug output
+  entries: Fingerprint,
+}
+
+impl Lut {
+  /// Table of `size`³ entries, with red changing fastest and blue slowest
+  pub fn new(size: usize, table: Vec<Color>) -> Self {
+    assert!(size >= 2, "A LUT needs at least two entries per axis");
+    assert_eq!(
+      table.len(),
+      size * size * size,
+      "A LUT of size {} needs {} entries",
+      size,
+      size * size * size
+    );
+    Self {
+      size,
+      domain_min: Color::black(),
+      domain_max: Color::white(),
+      entries: Fingerprint::of_colors(&table),
+      table: table.into(),
+    }
+  }
+
+  pub fn with_domain(mut self, domain_min: Color, domain_max: Color) -> Self {
+    self.domain_min = domain_min;
+    self.domain_max = domain_max;
+    self
+  }
+
+  fn entry(&self, r: usize, g: usize, b: usize) -> Color {
+    self.table[(b * self.size + g) * self.size + r]
+  }
+
+  pub fn lookup(&self, color: Color) -> Color {
+    let last = (self.size - 1) as F;
+    let position = |value: F, min: F, max: F| {
+      let scaled = ((value - min) / (max - min)).clamp(0.0, 1.0) * last;
+      let index = (scaled.floor() as usize).min(self.size - 2);
+      (index, scaled - index as F)
+    };
+    let (r, fr) = position(color.red, self.domain_min.red, self.domain_max.red);
+    let (g, fg) = position(color.green, self.domain_min.green, self.domain_max.green);
+    let (b, fb) = position(color.blue, self.domain_min.blue, self.domain_max.blue);
+
+    let lerp = |a: Color, b: Color, t: F| a * (1.0 - t) + b * t;
+    let plane = |b: usize| {
+      let low = lerp(self.entry(r, g, b), self.entry(r + 1, g, b), fr);
+      let high = lerp(self.entry(r, g + 1, b), self.entry(r + 1, g + 1, b), fr);
+      lerp(low, high, fg)
+    };
+    lerp(plane(b), plane(b + 1), fb)
+  }
+}
+
+impl fmt::Debug for Lut {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Lut")
+      .field("size", &self.size)
+      .field("domain_min", &self.domain_min)
+      .field("domain_max", &self.domain_max)
+      .field("entries", &self.entries)
+      .finish()
+  }
+}
+
+impl PartialEq for Lut {
+  fn eq(&self, other: &Lut) -> bool {
+    self.size == other.size
+      && self.domain_min == other.domain_min
+      && self.domain_max == other.domain_max
+      && self.table == other.table
+  }
+}
+
+impl FuzzyEq<Lut> for Lut {
+  fn fuzzy_eq(&self, other: Lut) -> bool {
+    self.size == other.size
+      && self.domain_min.fuzzy_eq(other.domain_min)
+      && self.domain_max.fuzzy_eq(other.domain_max)
+      && self.table.to_vec().fuzzy_eq(other.table.to_vec())
+  }
+}
+
+impl PostProcess for Lut {
+  fn apply(&self, canvas: &Canvas) -> Canvas {
+    let mut result = Canvas::new(canvas.width, canvas.height);
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        result.write_pixel(x, y, self.lookup(canvas.pixel_at(x, y)));
+      }
+    }
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Table mapping every color to the given function of it
+  fn table(size: usize, f: impl Fn(Color) -> Color) -> Vec<Color> {
+    let last = (size - 1) as F;
+    let mut table = vec![];
+    for b in 0..size {
+      for g in 0..size {
+        for r in 0..size {
+          table.push(f(Color::new(r as F / last, g as F / last, b as F / last)));
+        }
+      }
+    }
+    table
+  }
+
+  #[test]
+  fn an_identity_lut_keeps_the_colors() {
+    let lut = Lut::new(2, table(2, |color| color));
+
+    assert_fuzzy_eq!(
+      lut.lookup(Color::new(0.2, 0.5, 0.9)),
+      Color::new(0.2, 0.5, 0.9)
+    );
+  }
+
+  #[test]
+  fn colors_are_interpolated_between_entries() {
+    let lut = Lut::new(
+      3,
+      table(3, |color| Color::new(color.blue, color.green, color.red)),
+    );
+
+    assert_fuzzy_eq!(
+      lut.lookup(Color::new(0.25, 0.5, 0.75)),
+      Color::new(0.75, 0.5, 0.25)
+    );
+  }
+
+  #[test]
+  fn colors_outside_of_the_domain_are_clamped() {
+    let lut =
+      Lut::new(2, table(2, |color| color)).with_domain(Color::black(), Color::new(2.0, 2.0, 2.0));
+
+    assert_fuzzy_eq!(
+      lut.lookup(Color::new(1.0, 3.0, -1.0)),
+      Color::new(0.5, 1.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn the_lut_is_applied_to_every_pixel() {
+    let lut = Lut::new(2, table(2, |color| color * 0.5));
+    let mut canvas = Canvas::new(2, 1);
+    canvas.write_pixel(0, 0, Color::white());
+
+    let result = lut.apply(&canvas);
+
+    assert_fuzzy_eq!(result.pixel_at(0, 0), Color::new(0.5, 0.5, 0.5));
+    assert_fuzzy_eq!(result.pixel_at(1, 0), Color::black());
+  }
+}
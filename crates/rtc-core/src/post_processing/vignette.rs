@@ -0,0 +1,77 @@
+use super::PostProcess;
+use crate::canvas::Canvas;
+use crate::F;
+
+/// Darkens the canvas towards its corners.
+///
+/// The darkening starts at the normalized distance `radius` from the center
+/// (the corners are at distance 1) and reaches `strength` at the corners.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vignette {
+  pub strength: F,
+  pub radius: F,
+}
+
+impl Vignette {
+  pub fn new(strength: F, radius: F) -> Self {
+    Self { strength, radius }
+  }
+}
+
+impl Default for Vignette {
+  fn default() -> Self {
+    Self::new(0.5, 0.5)
+  }
+}
+
+impl PostProcess for Vignette {
+  fn apply(&self, canvas: &Canvas) -> Canvas {
+    let mut result = Canvas::new(canvas.width, canvas.height);
+    let center_x = canvas.width as F / 2.0;
+    let center_y = canvas.height as F / 2.0;
+    let half_diagonal = (center_x * center_x + center_y * center_y).sqrt();
+
+    for y in 0..canvas.height {
+      for x in 0..canvas.width {
+        let offset_x = x as F + 0.5 - center_x;
+        let offset_y = y as F + 0.5 - center_y;
+        let distance = (offset_x * offset_x + offset_y * offset_y).sqrt() / half_diagonal;
+
+        let t =
+          ((distance - self.radius) / (1.0 - self.radius).max(crate::EPSILON)).clamp(0.0, 1.0);
+        let falloff = t * t * (3.0 - 2.0 * t);
+
+        result.write_pixel(
+          x,
+          y,
+          canvas.pixel_at(x, y) * (1.0 - self.strength * falloff),
+        );
+      }
+    }
+
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::canvas::Color;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn vignette_darkens_the_corners_only() {
+    let mut canvas = Canvas::new(11, 11);
+    for y in 0..11 {
+      for x in 0..11 {
+        canvas.write_pixel(x, y, Color::white());
+      }
+    }
+
+    let result = Vignette::new(0.8, 0.5).apply(&canvas);
+
+    assert_fuzzy_eq!(result.pixel_at(5, 5), Color::white());
+    assert!(result.pixel_at(0, 0).red < 0.3);
+    assert!(result.pixel_at(0, 0).red > 0.2 - crate::EPSILON);
+  }
+}
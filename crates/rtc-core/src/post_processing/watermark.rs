@@ -0,0 +1,215 @@
+use super::PostProcess;
+use crate::canvas::{Canvas, Color};
+use crate::font::{glyph, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::F;
+
+/// Corner of the canvas a watermark is placed in
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Corner {
+  TopLeft,
+  TopRight,
+  BottomLeft,
+  #[default]
+  BottomRight,
+}
+
+#[derive(Clone, Debug)]
+pub enum Stamp {
+  /// Text in a built-in 5x7 pixel font. Lowercase letters are drawn as
+  /// uppercase ones, unknown characters as '?'.
+  Text(String),
+  Image(Canvas),
+}
+
+/// Small text or image stamped into a corner of the canvas, like the scene
+/// name or frame number of a work in progress.
+#[derive(Clone, Debug)]
+pub struct Watermark {
+  pub stamp: Stamp,
+  pub corner: Corner,
+  /// Size of a font pixel, ignored for images
+  pub scale: usize,
+  /// Distance to the edges of the canvas in pixels
+  pub margin: usize,
+  pub color: Color,
+  pub opacity: F,
+}
+
+impl Watermark {
+  pub fn text(text: impl Into<String>) -> Self {
+    Self::new(Stamp::Text(text.into()))
+  }
+
+  pub fn image(image: Canvas) -> Self {
+    Self::new(Stamp::Image(image))
+  }
+
+  fn new(stamp: Stamp) -> Self {
+    Self {
+      stamp,
+      corner: Corner::default(),
+      scale: 1,
+      margin: 4,
+      color: Color::white(),
+      opacity: 0.8,
+    }
+  }
+
+  pub fn with_corner(mut self, corner: Corner) -> Self {
+    self.corner = corner;
+    self
+  }
+
+  pub fn with_scale(mut self, scale: usize) -> Self {
+    self.scale = scale.max(1);
+    self
+  }
+
+  pub fn with_margin(mut self, margin: usize) -> Self {
+    self.margin = margin;
+    self
+  }
+
+  pub fn with_color(mut self, color: Color) -> Self {
+    self.color = color;
+    self
+  }
+
+  pub fn with_opacity(mut self, opacity: F) -> Self {
+    self.opacity = opacity;
+    self
+  }
+
+  /// The stamp as colors, `None` where the canvas shows through
+  fn render_stamp(&self) -> (usize, usize, Vec<Option<Color>>) {
+    match self.stamp {
+      Stamp::Image(ref image) => {
+        let mut pixels = Vec::with_capacity(image.width * image.height);
+        for y in 0..image.height {
+          for x in 0..image.width {
+            pixels.push(Some(image.pixel_at(x, y)));
+          }
+        }
+        (image.width, image.height, pixels)
+      }
+      Stamp::Text(ref text) => {
+        let characters: Vec<char> = text.chars().collect();
+        let columns = (characters.len() * (GLYPH_WIDTH + 1)).saturating_sub(1);
+        let width = columns * self.scale;
+        let height = GLYPH_HEIGHT * self.scale;
+        let mut pixels = vec![None; width * height];
+        for (index, character) in characters.iter().enumerate() {
+          let rows = glyph(*character);
+          for (row, bits) in rows.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+              if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                continue;
+              }
+              let x = (index * (GLYPH_WIDTH + 1) + column) * self.scale;
+              let y = row * self.scale;
+              for dy in 0..self.scale {
+                for dx in 0..self.scale {
+                  pixels[(y + dy) * width + x + dx] = Some(self.color);
+                }
+              }
+            }
+          }
+        }
+        (width, height, pixels)
+      }
+    }
+  }
+}
+
+impl PostProcess for Watermark {
+  fn apply(&self, canvas: &Canvas) -> Canvas {
+    let mut result = canvas.clone();
+    let (width, height, pixels) = self.render_stamp();
+    let left = match self.corner {
+      Corner::TopLeft | Corner::BottomLeft => self.margin as isize,
+      Corner::TopRight | Corner::BottomRight => {
+        canvas.width as isize - (width + self.margin) as isize
+      }
+    };
+    let top = match self.corner {
+      Corner::TopLeft | Corner::TopRight => self.margin as isize,
+      Corner::BottomLeft | Corner::BottomRight => {
+        canvas.height as isize - (height + self.margin) as isize
+      }
+    };
+
+    for y in 0..height {
+      for x in 0..width {
+        let (target_x, target_y) = (left + x as isize, top + y as isize);
+        if target_x < 0
+          || target_y < 0
+          || target_x >= canvas.width as isize
+          || target_y >= canvas.height as isize
+        {
+          continue;
+        }
+        if let Some(color) = pixels[y * width + x] {
+          let (target_x, target_y) = (target_x as usize, target_y as usize);
+          let below = canvas.pixel_at(target_x, target_y);
+          result.write_pixel(
+            target_x,
+            target_y,
+            below * (1.0 - self.opacity) + color * self.opacity,
+          );
+        }
+      }
+    }
+
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn text_is_stamped_into_the_bottom_right_corner() {
+    let canvas = Canvas::new(20, 10);
+
+    let result = Watermark::text("I")
+      .with_margin(1)
+      .with_opacity(1.0)
+      .apply(&canvas);
+
+    // The top bar of the 'I' spans the columns 15 to 17 of the glyph row 2
+    assert_fuzzy_eq!(result.pixel_at(15, 2), Color::white());
+    assert_fuzzy_eq!(result.pixel_at(17, 2), Color::white());
+    assert_fuzzy_eq!(result.pixel_at(14, 2), Color::black());
+    assert_fuzzy_eq!(result.pixel_at(16, 5), Color::white());
+    assert_fuzzy_eq!(result.pixel_at(15, 5), Color::black());
+    assert_fuzzy_eq!(result.pixel_at(19, 9), Color::black());
+  }
+
+  #[test]
+  fn the_stamp_is_blended_by_its_opacity() {
+    let canvas = Canvas::new(4, 4);
+    let mut image = Canvas::new(1, 1);
+    image.write_pixel(0, 0, Color::new(1.0, 0.5, 0.0));
+
+    let result = Watermark::image(image)
+      .with_corner(Corner::TopLeft)
+      .with_margin(0)
+      .with_opacity(0.5)
+      .apply(&canvas);
+
+    assert_fuzzy_eq!(result.pixel_at(0, 0), Color::new(0.5, 0.25, 0.0));
+    assert_fuzzy_eq!(result.pixel_at(1, 0), Color::black());
+  }
+
+  #[test]
+  fn stamps_larger_than_the_canvas_are_cut_off() {
+    let canvas = Canvas::new(3, 3);
+
+    let result = Watermark::text("WIDE TEXT").with_scale(2).apply(&canvas);
+
+    assert_eq!(result.width, 3);
+    assert_eq!(result.height, 3);
+  }
+}
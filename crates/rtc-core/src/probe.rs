@@ -0,0 +1,137 @@
+use crate::angle::Angle;
+use crate::body::Body;
+use crate::camera::Camera;
+use crate::canvas::{Canvas, Color};
+use crate::material::{Material, Phong};
+use crate::matrix::Matrix;
+use crate::renderer;
+use crate::sphere::Sphere;
+use crate::tuple::Tuple;
+use crate::world::World;
+use crate::F;
+
+/// Chrome and gray reference balls as used on film sets, placed at a point
+/// of the world and looked at from another one. Comparing them to photos of
+/// real reference balls helps matching the lighting to real footage.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Probe {
+  pub at: Tuple,
+  pub from: Tuple,
+  pub radius: F,
+}
+
+impl Probe {
+  pub fn new(at: Tuple, from: Tuple) -> Self {
+    Self {
+      at,
+      from,
+      radius: 1.0,
+    }
+  }
+
+  pub fn with_radius(mut self, radius: F) -> Self {
+    self.radius = radius;
+    self
+  }
+
+  /// Unit vectors pointing from the viewer towards the balls and to the left
+  /// side of the image
+  fn axes(&self) -> (Tuple, Tuple) {
+    let forward = (self.at - self.from).normalize();
+    let up = if forward.cross(Tuple::vector(0.0, 1.0, 0.0)).magnitude() < 0.001 {
+      Tuple::vector(0.0, 0.0, 1.0)
+    } else {
+      Tuple::vector(0.0, 1.0, 0.0)
+    };
+    (forward, forward.cross(up).normalize())
+  }
+
+  fn up(&self) -> Tuple {
+    let (forward, left) = self.axes();
+    left.cross(forward)
+  }
+
+  /// The two balls side by side, chrome on the left and 18% gray on the
+  /// right, lit by the lights of the given world only. Its bodies are left
+  /// out, so they can't hide the balls.
+  pub fn world(&self, world: &World) -> World {
+    let (_, left) = self.axes();
+    let offset = left * (self.radius * 1.25);
+    let ball = |center: Tuple, material: Phong| {
+      Body::from(
+        Sphere::default()
+          .with_transform(
+            Matrix::translation(center.x, center.y, center.z)
+              * Matrix::scaling(self.radius, self.radius, self.radius),
+          )
+          .with_material(Material::from(material)),
+      )
+    };
+    let chrome = Phong::default()
+      .with_color(Color::black())
+      .with_ambient(0.0)
+      .with_diffuse(0.0)
+      .with_specular(1.0)
+      .with_shininess(300.0)
+      .with_reflectiveness(1.0);
+    let gray = Phong::default()
+      .with_color(Color::new(0.18, 0.18, 0.18))
+      .with_specular(0.0);
+
+    World::new(
+      vec![ball(self.at + offset, chrome), ball(self.at - offset, gray)],
+      world.lights().to_vec(),
+    )
+    .with_background(world.background)
+  }
+
+  /// Camera of the given height framing both balls, twice as wide as high
+  pub fn camera(&self, height: usize) -> Camera {
+    let (forward, _) = self.axes();
+    let from = self.at - forward * (self.radius * 6.0);
+    Camera::new(
+      height * 2,
+      height,
+      Angle::radians(std::f64::consts::FRAC_PI_3),
+    )
+    .with_transform(Matrix::view_transform(from, self.at, self.up()))
+  }
+
+  /// Renders the balls lit by the lights of the given world
+  pub fn render(&self, world: &World, height: usize) -> Canvas {
+    renderer::render(&self.world(world), &self.camera(height), |_| {})
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+
+  #[test]
+  fn the_probe_keeps_only_the_lighting_of_the_world() {
+    let world = World::book_default().with_background(Color::new(0.1, 0.2, 0.3));
+    let probe = Probe::new(Tuple::point(0.0, 0.0, 0.0), Tuple::point(0.0, 0.0, -5.0));
+
+    let probe_world = probe.world(&world);
+
+    assert_eq!(probe_world.bodies().len(), 2);
+    assert_eq!(probe_world.lights(), world.lights());
+    assert_fuzzy_eq!(probe_world.background, world.background);
+  }
+
+  #[test]
+  fn the_gray_ball_is_shown_right_of_the_chrome_ball() {
+    let probe = Probe::new(Tuple::point(0.0, 0.0, 0.0), Tuple::point(0.0, 0.0, -5.0));
+
+    let canvas = probe.render(&World::book_default(), 20);
+
+    // The chrome ball reflects the black background straight back
+    assert_eq!((canvas.width, canvas.height), (40, 20));
+    let chrome = canvas.pixel_at(12, 10);
+    let gray = canvas.pixel_at(28, 10);
+    assert!(chrome.red < 0.01);
+    assert!(gray.red > 0.05);
+    assert_fuzzy_eq!(gray.red, gray.green);
+  }
+}
@@ -0,0 +1,191 @@
+use crate::body::*;
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::*;
+use crate::matrix::*;
+use crate::ray::*;
+use crate::tuple::*;
+use crate::{EPSILON, F};
+
+/// Finite rectangle in the xz plane, centered at the origin.
+///
+/// It extends `width` along the x axis and `height` along the z axis, which
+/// makes it the bounded counterpart of a `Plane`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quad {
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+  pub material: Material,
+  pub shadows: Shadows,
+  pub width: F,
+  pub height: F,
+}
+
+impl Default for Quad {
+  fn default() -> Self {
+    Self {
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+      material: Default::default(),
+      shadows: Default::default(),
+      width: 2.0,
+      height: 2.0,
+    }
+  }
+}
+
+impl Quad {
+  pub fn new(material: Material, transform: Matrix<4>) -> Self {
+    Quad {
+      material,
+      transform,
+      inverse_transform: transform.inverse(),
+      ..Default::default()
+    }
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.material = material;
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.shadows = shadows;
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    self.transform = transform;
+    self.inverse_transform = inverse_transform;
+    self
+  }
+
+  pub fn with_width(mut self, width: F) -> Self {
+    self.width = width;
+    self
+  }
+
+  pub fn with_height(mut self, height: F) -> Self {
+    self.height = height;
+    self
+  }
+}
+
+impl FuzzyEq<&Quad> for Quad {
+  fn fuzzy_eq(&self, other: &Quad) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+      && self.material.fuzzy_eq(other.material.clone())
+      && self.shadows == other.shadows
+      && self.width.fuzzy_eq(other.width)
+      && self.height.fuzzy_eq(other.height)
+  }
+}
+
+impl Intersectable for Quad {
+  fn max_intersections(&self) -> usize {
+    1
+  }
+
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    if object_space_ray.direction.y.abs() <= EPSILON {
+      return vec![];
+    }
+
+    let t = -object_space_ray.origin.y / object_space_ray.direction.y;
+    let x = object_space_ray.origin.x + t * object_space_ray.direction.x;
+    let z = object_space_ray.origin.z + t * object_space_ray.direction.z;
+    if x.abs() > self.width / 2.0 || z.abs() > self.height / 2.0 {
+      return vec![];
+    }
+
+    vec![(t, Body::from(self.clone()))]
+  }
+
+  fn normal_at_in_object_space(&self, _object_space_point: Tuple) -> Tuple {
+    Tuple::vector(0.0, 1.0, 0.0)
+  }
+
+  fn material(&self) -> Material {
+    self.material.clone()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.shadows
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    Bounds::new(
+      Tuple::point(-self.width / 2.0, 0.0, -self.height / 2.0),
+      Tuple::point(self.width / 2.0, 0.0, self.height / 2.0),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn normal_of_a_quad_is_constant_everywhere() {
+    let q = Quad::default();
+
+    assert_fuzzy_eq!(
+      q.normal_at_in_object_space(Tuple::point(0.0, 0.0, 0.0)),
+      Tuple::vector(0.0, 1.0, 0.0)
+    );
+    assert_fuzzy_eq!(
+      q.normal_at_in_object_space(Tuple::point(0.5, 0.0, -0.7)),
+      Tuple::vector(0.0, 1.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn intersect_with_a_ray_parallel_to_the_quad() {
+    let q = Quad::default();
+    let r = Ray::new(Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    assert_eq!(q.intersect_in_object_space(r).len(), 0);
+  }
+
+  #[test]
+  fn a_ray_strikes_the_quad_within_its_extents() {
+    let q = Quad::default().with_width(4.0).with_height(1.0);
+    let r = Ray::new(Tuple::point(1.9, 2.0, -0.4), Tuple::vector(0.0, -1.0, 0.0));
+    let xs = q.intersect_in_object_space(r);
+
+    assert_eq!(xs.len(), 1);
+    assert_fuzzy_eq!(xs[0].0, 2.0);
+    assert_eq!(xs[0].1, Body::from(q));
+  }
+
+  #[test]
+  fn a_ray_misses_the_quad_outside_of_its_extents() {
+    let q = Quad::default().with_width(4.0).with_height(1.0);
+    let examples = vec![
+      Tuple::point(2.1, 1.0, 0.0),
+      Tuple::point(0.0, 1.0, 0.6),
+      Tuple::point(-2.1, 1.0, -0.6),
+    ];
+
+    for origin in examples {
+      let r = Ray::new(origin, Tuple::vector(0.0, -1.0, 0.0));
+      assert_eq!(q.intersect_in_object_space(r).len(), 0);
+    }
+  }
+}
@@ -6,6 +6,9 @@ use crate::F;
 pub struct Ray {
   pub origin: Tuple,
   pub direction: Tuple,
+  /// Point in time within the shutter interval [0, 1], at which moving
+  /// bodies are intersected
+  pub time: F,
 }
 
 impl Ray {
@@ -13,7 +16,16 @@ impl Ray {
     if !origin.is_point() || !direction.is_vector() {
       panic!("origin argument needs to be a point and direction needs to be a vector!");
     }
-    Ray { origin, direction }
+    Ray {
+      origin,
+      direction,
+      time: 0.0,
+    }
+  }
+
+  pub fn with_time(mut self, time: F) -> Self {
+    self.time = time;
+    self
   }
 
   pub fn position(&self, t: F) -> Tuple {
@@ -24,6 +36,7 @@ impl Ray {
     Ray {
       origin: m * self.origin,
       direction: m * self.direction,
+      time: self.time,
     }
   }
 }
@@ -0,0 +1,381 @@
+use crate::body::Intersectable;
+use crate::camera::Camera;
+use crate::material::{Reflective, Refractive};
+use crate::ray::Ray;
+use crate::tuple::Tuple;
+use crate::world::World;
+use crate::F;
+use std::fmt::Write;
+
+/// Length rays missing every body are drawn with
+const MISS_LENGTH: F = 10.0;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RayKind {
+  Primary,
+  Shadow,
+  Reflection,
+  Refraction,
+}
+
+impl RayKind {
+  fn name(&self) -> &'static str {
+    match self {
+      RayKind::Primary => "primary",
+      RayKind::Shadow => "shadow",
+      RayKind::Reflection => "reflection",
+      RayKind::Refraction => "refraction",
+    }
+  }
+
+  fn color(&self) -> &'static str {
+    match self {
+      RayKind::Primary => "#1f77b4",
+      RayKind::Shadow => "#7f7f7f",
+      RayKind::Reflection => "#2ca02c",
+      RayKind::Refraction => "#ff7f0e",
+    }
+  }
+}
+
+/// Part of a traced ray between its origin and where it ended.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RaySegment {
+  pub kind: RayKind,
+  pub from: Tuple,
+  pub to: Tuple,
+  /// Whether the ray hit a body, for shadow rays whether the light is blocked
+  pub hit: bool,
+}
+
+/// Plane the diagram is drawn in
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Projection {
+  /// Looking along the z axis
+  Front,
+  /// Looking down the y axis
+  Top,
+  /// Looking along the x axis
+  Side,
+}
+
+impl Projection {
+  /// Position in the diagram, with y growing downwards like in SVG
+  fn project(&self, point: Tuple) -> (F, F) {
+    match self {
+      Projection::Front => (point.x, -point.y),
+      Projection::Top => (point.x, -point.z),
+      Projection::Side => (point.z, -point.y),
+    }
+  }
+}
+
+/// Rays traced for a few pixels, to show how the tracer works.
+///
+/// It follows the same path as `World::color_at`, recording the primary
+/// ray, the shadow ray towards the light and all reflections and refractions
+/// of each pixel.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RayDiagram {
+  pub segments: Vec<RaySegment>,
+}
+
+impl RayDiagram {
+  pub fn record(world: &World, camera: &Camera, pixels: &[(usize, usize)]) -> Self {
+    let mut diagram = Self::default();
+    for (x, y) in pixels.iter() {
+      diagram.trace(
+        world,
+        camera.ray_for_pixel(*x, *y),
+        RayKind::Primary,
+        world.reflection_limit(),
+        1.0,
+        &mut world.secondary_rays(),
+      );
+    }
+    diagram
+  }
+
+  fn trace(
+    &mut self,
+    world: &World,
+    ray: Ray,
+    kind: RayKind,
+    remaining_reflections: usize,
+    weight: F,
+    secondary_rays: &mut usize,
+  ) {
+    let xs = world.intersect(ray);
+    let hit = match xs.hit() {
+      Some(hit) => hit,
+      None => {
+        self.segments.push(RaySegment {
+          kind,
+          from: ray.origin,
+          to: ray.position(MISS_LENGTH / ray.direction.magnitude()),
+          hit: false,
+        });
+        return;
+      }
+    };
+
+    let c = hit.get_computed_with(&xs);
+    self.segments.push(RaySegment {
+      kind,
+      from: ray.origin,
+      to: c.point,
+      hit: true,
+    });
+
+    let sample = world
+      .lights()
+      .first()
+      .and_then(|light| light.sample_directions(c.over_point).first().copied());
+    if let Some(sample) = sample {
+      let shadow_ray = Ray::new(c.over_point, sample.direction);
+      let distance = sample.distance;
+      let blocker = world
+        .intersect(shadow_ray)
+        .shadow_hit()
+        .filter(|blocker| blocker.t < distance)
+        .map(|blocker| shadow_ray.position(blocker.t));
+      self.segments.push(RaySegment {
+        kind: RayKind::Shadow,
+        from: c.over_point,
+        to: blocker.unwrap_or_else(|| shadow_ray.position(distance)),
+        hit: blocker.is_some(),
+      });
+    }
+
+    if remaining_reflections == 0 {
+      return;
+    }
+    let material = hit.body.material();
+    let worth_tracing =
+      |fraction: F| fraction > 0.0 && weight * fraction >= world.contribution_cutoff;
+    let reflectiveness = material.reflectiveness_at(&hit.body, c.point, c.eyev, c.normalv);
+    if worth_tracing(reflectiveness) && *secondary_rays > 0 {
+      *secondary_rays -= 1;
+      self.trace(
+        world,
+        Ray::new(c.over_point, c.reflectv),
+        RayKind::Reflection,
+        remaining_reflections - 1,
+        weight * reflectiveness,
+        secondary_rays,
+      );
+    }
+    let transparency = material.transparency_at(&hit.body, c.point);
+    match c.refracted_direction() {
+      Some(direction) if worth_tracing(transparency) && *secondary_rays > 0 => {
+        *secondary_rays -= 1;
+        self.trace(
+          world,
+          Ray::new(c.under_point, direction),
+          RayKind::Refraction,
+          remaining_reflections - 1,
+          weight * transparency,
+          secondary_rays,
+        )
+      }
+      _ => (),
+    }
+  }
+
+  /// Segments as polylines of a Wavefront OBJ file, grouped by their kind
+  pub fn to_obj(&self) -> String {
+    let mut obj = String::from("# Rays recorded by the ray tracer\n");
+    for (index, segment) in self.segments.iter().enumerate() {
+      let vertex = index * 2 + 1;
+      writeln!(obj, "g {}", segment.kind.name()).unwrap();
+      for point in [segment.from, segment.to].iter() {
+        writeln!(obj, "v {} {} {}", point.x, point.y, point.z).unwrap();
+      }
+      writeln!(obj, "l {} {}", vertex, vertex + 1).unwrap();
+    }
+    obj
+  }
+
+  /// Segments projected onto a plane as an SVG drawing.
+  ///
+  /// Blocked shadow rays are dashed, hits are marked with a dot.
+  pub fn to_svg(&self, projection: Projection) -> String {
+    let points: Vec<(F, F)> = self
+      .segments
+      .iter()
+      .flat_map(|segment| {
+        vec![
+          projection.project(segment.from),
+          projection.project(segment.to),
+        ]
+      })
+      .collect();
+    let (min_x, min_y, max_x, max_y) = points.iter().fold(
+      (F::INFINITY, F::INFINITY, F::NEG_INFINITY, F::NEG_INFINITY),
+      |(min_x, min_y, max_x, max_y), (x, y)| {
+        (min_x.min(*x), min_y.min(*y), max_x.max(*x), max_y.max(*y))
+      },
+    );
+    let (min_x, min_y, max_x, max_y) = if points.is_empty() {
+      (-1.0, -1.0, 1.0, 1.0)
+    } else {
+      (min_x, min_y, max_x, max_y)
+    };
+    let margin = 0.05 * (max_x - min_x).max(max_y - min_y).max(1.0);
+    let stroke = margin / 5.0;
+
+    let mut svg = String::new();
+    writeln!(
+      svg,
+      r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+      min_x - margin,
+      min_y - margin,
+      max_x - min_x + 2.0 * margin,
+      max_y - min_y + 2.0 * margin
+    )
+    .unwrap();
+    for segment in self.segments.iter() {
+      let (x1, y1) = projection.project(segment.from);
+      let (x2, y2) = projection.project(segment.to);
+      let dash = if segment.kind == RayKind::Shadow && segment.hit {
+        format!(r#" stroke-dasharray="{} {}""#, stroke * 3.0, stroke * 2.0)
+      } else {
+        String::new()
+      };
+      writeln!(
+        svg,
+        r#"  <line class="{}" x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}"{}/>"#,
+        segment.kind.name(),
+        x1,
+        y1,
+        x2,
+        y2,
+        segment.kind.color(),
+        stroke,
+        dash
+      )
+      .unwrap();
+      if segment.hit && segment.kind != RayKind::Shadow {
+        writeln!(
+          svg,
+          r#"  <circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+          x2,
+          y2,
+          stroke * 2.0,
+          segment.kind.color()
+        )
+        .unwrap();
+      }
+    }
+    svg.push_str("</svg>\n");
+    svg
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::material::Material;
+  use crate::test_scene::{camera_facing_ball, floor_and_ball, glass, white_light_at};
+
+  #[test]
+  fn primary_and_shadow_rays_are_recorded() {
+    let w = floor_and_ball();
+    let diagram = RayDiagram::record(&w, &camera_facing_ball(), &[(5, 5)]);
+
+    assert_eq!(diagram.segments.len(), 2);
+    assert_eq!(diagram.segments[0].kind, RayKind::Primary);
+    assert!(diagram.segments[0].hit);
+    assert_fuzzy_eq!(diagram.segments[0].to, Tuple::point(0.0, 1.0, -1.0));
+    assert_eq!(diagram.segments[1].kind, RayKind::Shadow);
+    assert!(!diagram.segments[1].hit);
+    assert_fuzzy_eq!(diagram.segments[1].to, Tuple::point(-10.0, 10.0, -10.0));
+  }
+
+  #[test]
+  fn blocked_shadow_rays_end_at_the_blocker() {
+    // The light above the sphere is hidden by the sphere itself
+    let w = floor_and_ball().with_lights(vec![white_light_at(0.0, 10.0, 0.0)]);
+    let diagram = RayDiagram::record(&w, &camera_facing_ball(), &[(5, 5)]);
+
+    assert_eq!(diagram.segments[1].kind, RayKind::Shadow);
+    assert!(diagram.segments[1].hit);
+    assert!(diagram.segments[1].to.y < 2.0);
+  }
+
+  #[test]
+  fn reflections_are_recorded() {
+    // The lowest pixel looks at the mirroring floor in front of the sphere
+    let diagram = RayDiagram::record(&floor_and_ball(), &camera_facing_ball(), &[(5, 10)]);
+    let kinds: Vec<RayKind> = diagram
+      .segments
+      .iter()
+      .map(|segment| segment.kind)
+      .collect();
+
+    assert_eq!(
+      kinds,
+      vec![RayKind::Primary, RayKind::Shadow, RayKind::Reflection]
+    );
+    // The reflection passes over the sphere and leaves the scene
+    assert!(!diagram.segments[2].hit);
+    assert_fuzzy_eq!(diagram.segments[2].from.z, -3.9);
+  }
+
+  #[test]
+  fn no_reflections_are_recorded_without_secondary_ray_budget() {
+    let w = floor_and_ball().with_secondary_ray_budget(0);
+    let diagram = RayDiagram::record(&w, &camera_facing_ball(), &[(5, 10)]);
+
+    assert_eq!(diagram.segments.len(), 2);
+    assert_eq!(diagram.segments[1].kind, RayKind::Shadow);
+  }
+
+  #[test]
+  fn refractions_are_recorded() {
+    let mut w = floor_and_ball();
+    w.bodies_mut()[1] = w.bodies()[1].clone().with_material(Material::from(glass()));
+
+    let diagram = RayDiagram::record(&w, &camera_facing_ball(), &[(5, 5)]);
+
+    // Into the ball, out of it again and finally away into the sky
+    let refractions: Vec<&RaySegment> = diagram
+      .segments
+      .iter()
+      .filter(|segment| segment.kind == RayKind::Refraction)
+      .collect();
+    assert_eq!(refractions.len(), 2);
+    assert_fuzzy_eq!(refractions[0].to, Tuple::point(0.0, 1.0, 1.0));
+    assert!(!refractions[1].hit);
+  }
+
+  #[test]
+  fn segments_are_exported_as_obj_polylines() {
+    let diagram = RayDiagram {
+      segments: vec![RaySegment {
+        kind: RayKind::Shadow,
+        from: Tuple::point(0.0, 0.0, 0.0),
+        to: Tuple::point(1.0, 2.0, 3.0),
+        hit: true,
+      }],
+    };
+
+    assert_eq!(
+      diagram.to_obj(),
+      "# Rays recorded by the ray tracer\ng shadow\nv 0 0 0\nv 1 2 3\nl 1 2\n"
+    );
+  }
+
+  #[test]
+  fn segments_are_drawn_as_svg_lines() {
+    let diagram = RayDiagram::record(&floor_and_ball(), &camera_facing_ball(), &[(5, 5), (5, 10)]);
+
+    let svg = diagram.to_svg(Projection::Side);
+
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert_eq!(svg.matches("<line").count(), diagram.segments.len());
+    assert_eq!(svg.matches(r#"class="primary""#).count(), 2);
+    assert!(svg.ends_with("</svg>\n"));
+  }
+}
@@ -0,0 +1,313 @@
+pub mod adaptive;
+pub mod cost;
+pub mod progressive;
+
+use crate::camera::Camera;
+use crate::canvas::{Canvas, Color};
+use crate::world::World;
+use crate::F;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Rectangular part of the canvas, which is rendered as one unit of work.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tile {
+  pub x: usize,
+  pub y: usize,
+  pub width: usize,
+  pub height: usize,
+}
+
+impl Tile {
+  pub fn pixel_count(&self) -> usize {
+    self.width * self.height
+  }
+
+  pub fn pixels(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (self.y..self.y + self.height)
+      .flat_map(move |y| (self.x..self.x + self.width).map(move |x| (x, y)))
+  }
+
+  /// Splits the tile into quarters, or halves if it is a single pixel wide
+  /// or high. A single pixel can not be split.
+  pub fn split(&self) -> Vec<Tile> {
+    let widths = halves(self.width);
+    let heights = halves(self.height);
+    let mut tiles = vec![];
+    let mut y = self.y;
+    for height in heights.iter().copied() {
+      let mut x = self.x;
+      for width in widths.iter().copied() {
+        tiles.push(Tile {
+          x,
+          y,
+          width,
+          height,
+        });
+        x += width;
+      }
+      y += height;
+    }
+    tiles
+  }
+
+  /// Bytes needed while rendering the tile: the finished pixels and the
+  /// samples of the pixel currently rendered
+  pub fn memory_needed(&self, camera: &Camera) -> usize {
+    self.pixel_count() * std::mem::size_of::<(usize, usize, Color)>()
+      + camera.samples.pow(2) * std::mem::size_of::<Color>()
+  }
+}
+
+fn halves(length: usize) -> Vec<usize> {
+  if length > 1 {
+    vec![length / 2, length - length / 2]
+  } else {
+    vec![length]
+  }
+}
+
+/// Splits a canvas of the given size into tiles of at most `tile_size` pixels
+/// along each axis.
+pub fn tiles(width: usize, height: usize, tile_size: usize) -> Vec<Tile> {
+  let tile_size = tile_size.max(1);
+  let mut tiles = vec![];
+  for y in (0..height).step_by(tile_size) {
+    for x in (0..width).step_by(tile_size) {
+      tiles.push(Tile {
+        x,
+        y,
+        width: tile_size.min(width - x),
+        height: tile_size.min(height - y),
+      });
+    }
+  }
+  tiles
+}
+
+/// Colors of all sub pixel samples of the given pixel, which share the
+/// secondary ray budget of the pixel.
+pub fn sample_pixel(world: &World, camera: &Camera, x: usize, y: usize) -> Vec<Color> {
+  let mut secondary_rays = world.secondary_rays();
+  camera
+    .rays_for_pixel(x, y)
+    .into_iter()
+    .map(|ray| world.color_at_within_budget(ray, &mut secondary_rays))
+    .collect()
+}
+
+/// Averaged and exposed color of the given pixel.
+pub fn render_pixel(world: &World, camera: &Camera, x: usize, y: usize) -> Color {
+  let samples = sample_pixel(world, camera, x, y);
+  let sum = samples
+    .iter()
+    .fold(Color::black(), |sum, color| sum + *color);
+  camera.expose(sum * (1.0 / samples.len() as F))
+}
+
+/// Renders all pixels of a camera in parallel.
+///
+/// `progress` is called with the number of pixels finished since its last
+/// call. If the camera uses adaptive sampling a pilot pass is rendered first,
+/// reporting progress for every pixel twice.
+pub fn render<P>(world: &World, camera: &Camera, progress: P) -> Canvas
+where
+  P: Fn(usize) + Sync,
+{
+  match camera.adaptive_sampling {
+    Some(ref adaptive_sampling) => adaptive_sampling.render(world, camera, progress).0,
+    None => render_tiles(
+      world,
+      &tiles(camera.hsize, camera.vsize, 16),
+      progress,
+      |_| camera,
+    ),
+  }
+}
+
+/// Iterates the tiles in parallel, if the `parallel` feature is enabled.
+#[cfg(feature = "parallel")]
+pub(crate) fn iterate_tiles(tiles: &[Tile]) -> rayon::slice::Iter<'_, Tile> {
+  tiles.par_iter()
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn iterate_tiles(tiles: &[Tile]) -> std::slice::Iter<'_, Tile> {
+  tiles.iter()
+}
+
+/// Number of tiles rendered at the same time
+#[cfg(feature = "parallel")]
+fn tiles_at_once() -> usize {
+  rayon::current_num_threads()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn tiles_at_once() -> usize {
+  1
+}
+
+/// Renders the pixels of a tile into `rendered`.
+///
+/// Tiles above their share of the memory ceiling of the camera, or whose
+/// pixels can not be allocated, are split and rendered piece by piece, so
+/// huge renders degrade to smaller tiles instead of aborting.
+fn render_tile(
+  world: &World,
+  camera: &Camera,
+  tile: &Tile,
+  rendered: &mut Vec<Vec<(usize, usize, Color)>>,
+) {
+  let within_ceiling = camera
+    .memory_ceiling
+    .map(|ceiling| tile.memory_needed(camera) <= ceiling / tiles_at_once())
+    .unwrap_or(true);
+  let mut pixels = Vec::new();
+  let allocated = within_ceiling && pixels.try_reserve_exact(tile.pixel_count()).is_ok();
+
+  if !allocated && tile.pixel_count() > 1 {
+    for part in tile.split() {
+      render_tile(world, camera, &part, rendered);
+    }
+    return;
+  }
+
+  pixels.extend(
+    tile
+      .pixels()
+      .map(|(x, y)| (x, y, render_pixel(world, camera, x, y))),
+  );
+  rendered.push(pixels);
+}
+
+/// Renders the given tiles using the camera selected for each tile.
+fn render_tiles<'c, P, C>(world: &World, tiles: &[Tile], progress: P, camera_for_tile: C) -> Canvas
+where
+  P: Fn(usize) + Sync,
+  C: Fn(usize) -> &'c Camera + Sync,
+{
+  let camera = camera_for_tile(0);
+  let mut canvas = Canvas::new(camera.hsize, camera.vsize);
+
+  let rendered: Vec<Vec<Vec<(usize, usize, Color)>>> = iterate_tiles(tiles)
+    .enumerate()
+    .map(|(index, tile)| {
+      let mut rendered = vec![];
+      render_tile(world, camera_for_tile(index), tile, &mut rendered);
+      progress(tile.pixel_count());
+      rendered
+    })
+    .collect();
+
+  for (x, y, color) in rendered.into_iter().flatten().flatten() {
+    canvas.write_pixel(x, y, color);
+  }
+
+  canvas
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::angle::Angle;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::test_scene::{camera_facing_ball, floor_and_ball};
+  use std::f64::consts::PI;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  #[test]
+  fn tiles_cover_the_whole_canvas() {
+    let tiles = tiles(10, 5, 4);
+
+    assert_eq!(tiles.len(), 6);
+    assert_eq!(
+      tiles[2],
+      Tile {
+        x: 8,
+        y: 0,
+        width: 2,
+        height: 4
+      }
+    );
+    assert_eq!(tiles.iter().map(Tile::pixel_count).sum::<usize>(), 50);
+  }
+
+  #[test]
+  fn tiles_are_split_into_quarters() {
+    let tile = Tile {
+      x: 2,
+      y: 4,
+      width: 5,
+      height: 1,
+    };
+
+    assert_eq!(tile.split().len(), 2);
+    assert_eq!(
+      tile.split()[1],
+      Tile {
+        x: 4,
+        y: 4,
+        width: 3,
+        height: 1
+      }
+    );
+
+    let tiles = tiles(5, 3, 8)[0].split();
+    assert_eq!(tiles.len(), 4);
+    assert_eq!(tiles.iter().map(Tile::pixel_count).sum::<usize>(), 15);
+    assert_eq!(tiles[0].split().len(), 2);
+    assert_eq!(tiles[0].split()[0].split().len(), 1);
+  }
+
+  #[test]
+  fn tiles_above_the_memory_ceiling_are_split() {
+    let world = World::book_default();
+    let camera = Camera::book_default();
+    let tile = tiles(11, 11, 16)[0];
+    let constrained =
+      Camera::book_default().with_memory_ceiling(tiles_at_once() * tile.memory_needed(&camera) / 4);
+
+    let mut rendered = vec![];
+    render_tile(&world, &constrained, &tile, &mut rendered);
+
+    assert!(rendered.len() > 4);
+    assert_eq!(rendered.iter().map(Vec::len).sum::<usize>(), 121);
+    let canvas = render(&world, &constrained, |_| ());
+    let expected = render(&world, &camera, |_| ());
+    assert_fuzzy_eq!(canvas.pixel_at(5, 5), expected.pixel_at(5, 5));
+  }
+
+  #[test]
+  fn samples_of_a_pixel_share_the_secondary_ray_budget() {
+    let world = floor_and_ball();
+    let camera = camera_facing_ball().with_samples(2);
+    // Looks at the floor right in front of the ball, which it mirrors
+    let (x, y) = (5, 8);
+
+    let unlimited = sample_pixel(&world, &camera, x, y);
+    let exhausted = sample_pixel(&world.clone().with_secondary_ray_budget(0), &camera, x, y);
+    let shared = sample_pixel(&world.with_secondary_ray_budget(1), &camera, x, y);
+
+    assert_eq!(shared.len(), 4);
+    assert!(!shared[0].fuzzy_eq(exhausted[0]));
+    assert_fuzzy_eq!(shared[0], unlimited[0]);
+    for sample in 1..4 {
+      assert!(!shared[sample].fuzzy_eq(unlimited[sample]));
+      assert_fuzzy_eq!(shared[sample], exhausted[sample]);
+    }
+  }
+
+  #[test]
+  fn rendering_reports_progress_for_every_pixel() {
+    let world = World::default();
+    let camera = Camera::new(11, 7, Angle::radians(PI / 2.0));
+    let finished = AtomicUsize::new(0);
+
+    let canvas = render(&world, &camera, |pixels| {
+      finished.fetch_add(pixels, Ordering::Relaxed);
+    });
+
+    assert_eq!(finished.load(Ordering::Relaxed), 77);
+    assert_fuzzy_eq!(canvas.pixel_at(10, 6), Color::black());
+  }
+}
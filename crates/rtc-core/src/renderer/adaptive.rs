@@ -0,0 +1,255 @@
+use super::{iterate_tiles, render_tiles, sample_pixel, tiles, Tile};
+use crate::camera::Camera;
+use crate::canvas::Canvas;
+use crate::world::World;
+use crate::F;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::time::{Duration, Instant};
+
+/// Chooses the number of samples per tile based on the measured noise.
+///
+/// A pilot pass renders every tile with `pilot_samples` per axis and measures
+/// the variance of the luminance within each pixel. Each tile then gets
+/// enough samples for the variance of its pixel colors to fall below
+/// `target_variance`, limited by `max_samples` per axis and, if given, by the
+/// time budget.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AdaptiveSampling {
+  pub tile_size: usize,
+  pub pilot_samples: usize,
+  pub max_samples: usize,
+  pub target_variance: F,
+  pub time_budget: Option<Duration>,
+}
+
+impl Default for AdaptiveSampling {
+  fn default() -> Self {
+    Self {
+      tile_size: 16,
+      pilot_samples: 2,
+      max_samples: 8,
+      target_variance: 0.0005,
+      time_budget: None,
+    }
+  }
+}
+
+/// Outcome of an adaptive render
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveReport {
+  /// Tiles together with their chosen samples per axis
+  pub tiles: Vec<(Tile, usize)>,
+  pub pilot_duration: Duration,
+  pub total_duration: Duration,
+}
+
+impl AdaptiveReport {
+  /// Average number of rays per pixel of the final pass
+  pub fn average_samples_per_pixel(&self) -> F {
+    let pixels: usize = self.tiles.iter().map(|(tile, _)| tile.pixel_count()).sum();
+    let rays: usize = self
+      .tiles
+      .iter()
+      .map(|(tile, samples)| tile.pixel_count() * samples * samples)
+      .sum();
+    rays as F / pixels.max(1) as F
+  }
+}
+
+impl AdaptiveSampling {
+  pub fn with_tile_size(mut self, tile_size: usize) -> Self {
+    self.tile_size = tile_size.max(1);
+    self
+  }
+
+  pub fn with_pilot_samples(mut self, pilot_samples: usize) -> Self {
+    self.pilot_samples = pilot_samples.max(1);
+    self
+  }
+
+  pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+    self.max_samples = max_samples.max(1);
+    self
+  }
+
+  pub fn with_target_variance(mut self, target_variance: F) -> Self {
+    self.target_variance = target_variance;
+    self
+  }
+
+  pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+    self.time_budget = Some(time_budget);
+    self
+  }
+
+  /// Average variance of the pixel luminance within the tile
+  fn measure_noise(&self, world: &World, camera: &Camera, tile: &Tile) -> F {
+    let total: F = tile
+      .pixels()
+      .map(|(x, y)| {
+        let luminances: Vec<F> = sample_pixel(world, camera, x, y)
+          .iter()
+          .map(|color| color.luminance())
+          .collect();
+        variance(&luminances)
+      })
+      .sum();
+    total / tile.pixel_count() as F
+  }
+
+  /// Samples per axis needed to reach the target variance for the given noise
+  fn samples_for_noise(&self, noise: F) -> usize {
+    let minimum = self.pilot_samples.min(self.max_samples);
+    if self.target_variance <= 0.0 {
+      return self.max_samples;
+    }
+    let rays = (noise / self.target_variance).max(0.0);
+    (rays.sqrt().ceil() as usize).clamp(minimum, self.max_samples)
+  }
+
+  /// Renders the camera using the adaptive sample counts.
+  ///
+  /// `progress` is called for each finished tile of both the pilot and the
+  /// final pass.
+  pub fn render<P>(&self, world: &World, camera: &Camera, progress: P) -> (Canvas, AdaptiveReport)
+  where
+    P: Fn(usize) + Sync,
+  {
+    let start = Instant::now();
+    let tiles = tiles(camera.hsize, camera.vsize, self.tile_size);
+    let pilot_camera = camera.clone().with_samples(self.pilot_samples);
+
+    let noise: Vec<F> = iterate_tiles(&tiles)
+      .map(|tile| {
+        let noise = self.measure_noise(world, &pilot_camera, tile);
+        progress(tile.pixel_count());
+        noise
+      })
+      .collect();
+    let pilot_duration = start.elapsed();
+
+    let mut samples: Vec<usize> = noise
+      .iter()
+      .map(|noise| self.samples_for_noise(*noise))
+      .collect();
+
+    if let Some(time_budget) = self.time_budget {
+      let pilot_rays = (camera.hsize * camera.vsize * self.pilot_samples * self.pilot_samples) as F;
+      let planned_rays: usize = tiles
+        .iter()
+        .zip(samples.iter())
+        .map(|(tile, samples)| tile.pixel_count() * samples * samples)
+        .sum();
+      let seconds_per_ray = pilot_duration.as_secs_f64() / pilot_rays.max(1.0);
+      let remaining = time_budget.saturating_sub(pilot_duration).as_secs_f64();
+      let estimated = planned_rays as F * seconds_per_ray;
+      if estimated > remaining {
+        let factor = remaining / estimated;
+        for samples in samples.iter_mut() {
+          *samples = (((*samples * *samples) as F * factor).sqrt().floor() as usize).max(1);
+        }
+      }
+    }
+
+    let cameras: Vec<Camera> = samples
+      .iter()
+      .map(|samples| camera.clone().with_samples(*samples))
+      .collect();
+    let canvas = render_tiles(world, &tiles, progress, |index| &cameras[index]);
+
+    let report = AdaptiveReport {
+      tiles: tiles.into_iter().zip(samples).collect(),
+      pilot_duration,
+      total_duration: start.elapsed(),
+    };
+
+    (canvas, report)
+  }
+}
+
+fn variance(values: &[F]) -> F {
+  if values.len() < 2 {
+    return 0.0;
+  }
+  let mean = values.iter().sum::<F>() / values.len() as F;
+  values.iter().map(|value| (value - mean).powi(2)).sum::<F>() / (values.len() - 1) as F
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::angle::Angle;
+  use crate::body::Body;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::sphere::Sphere;
+  use crate::test_scene::white_light_at;
+  use crate::tuple::Tuple;
+  use std::f64::consts::PI;
+
+  fn create_world_and_camera() -> (World, Camera) {
+    let world = World::new(
+      vec![Body::from(Sphere::default())],
+      vec![white_light_at(-10.0, 10.0, -10.0)],
+    );
+    let camera = Camera::new(16, 8, Angle::radians(PI / 3.0)).look_at_from_position(
+      Tuple::point(1.0, 0.0, -5.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    );
+    (world, camera)
+  }
+
+  #[test]
+  fn variance_of_samples() {
+    assert_fuzzy_eq!(variance(&[1.0]), 0.0);
+    assert_fuzzy_eq!(variance(&[1.0, 1.0, 1.0]), 0.0);
+    assert_fuzzy_eq!(variance(&[0.0, 1.0]), 0.5);
+  }
+
+  #[test]
+  fn sample_count_follows_the_noise() {
+    let adaptive = AdaptiveSampling::default()
+      .with_pilot_samples(2)
+      .with_max_samples(6)
+      .with_target_variance(0.01);
+
+    assert_eq!(adaptive.samples_for_noise(0.0), 2);
+    assert_eq!(adaptive.samples_for_noise(0.16), 4);
+    assert_eq!(adaptive.samples_for_noise(10.0), 6);
+  }
+
+  #[test]
+  fn noisy_tiles_receive_more_samples() {
+    let (world, camera) = create_world_and_camera();
+    let adaptive = AdaptiveSampling::default()
+      .with_tile_size(4)
+      .with_pilot_samples(2)
+      .with_max_samples(4)
+      .with_target_variance(0.00001);
+
+    let (_, report) = adaptive.render(&world, &camera, |_| {});
+
+    let minimum = report.tiles.iter().map(|(_, samples)| *samples).min();
+    let maximum = report.tiles.iter().map(|(_, samples)| *samples).max();
+    // The empty corner stays at the pilot sample count, while the silhouette
+    // of the sphere needs the maximum
+    assert_eq!(minimum, Some(2));
+    assert_eq!(maximum, Some(4));
+    assert!(report.average_samples_per_pixel() > 4.0);
+  }
+
+  #[test]
+  fn exhausted_time_budget_falls_back_to_few_samples() {
+    let (world, camera) = create_world_and_camera();
+    let adaptive = AdaptiveSampling::default()
+      .with_tile_size(4)
+      .with_max_samples(8)
+      .with_target_variance(0.0)
+      .with_time_budget(Duration::from_secs(0));
+
+    let (_, report) = adaptive.render(&world, &camera, |_| {});
+
+    assert!(report.tiles.iter().all(|(_, samples)| *samples == 1));
+  }
+}
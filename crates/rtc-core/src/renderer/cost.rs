@@ -0,0 +1,149 @@
+use super::{iterate_tiles, render_pixel, tiles};
+use crate::camera::Camera;
+use crate::canvas::{Canvas, Color};
+use crate::world::World;
+use crate::F;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cell::Cell;
+
+thread_local! {
+  /// Rays intersected with the world on this thread. A pixel is always
+  /// rendered on a single thread, so the difference before and after it is
+  /// its cost.
+  static RAYS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts a ray intersected with the world
+pub(crate) fn count_ray() {
+  RAYS.with(|rays| rays.set(rays.get() + 1));
+}
+
+fn rays_counted() -> usize {
+  RAYS.with(|rays| rays.get())
+}
+
+/// Number of rays traced for each pixel of a render: camera rays, shadow
+/// rays, reflections, refractions and contact shadow rays.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostMap {
+  pub width: usize,
+  pub height: usize,
+  rays: Vec<usize>,
+}
+
+impl CostMap {
+  pub fn new(width: usize, height: usize) -> Self {
+    Self {
+      width,
+      height,
+      rays: vec![0; width * height],
+    }
+  }
+
+  pub fn rays_at(&self, x: usize, y: usize) -> usize {
+    self.rays[y * self.width + x]
+  }
+
+  pub fn write_rays(&mut self, x: usize, y: usize, rays: usize) {
+    self.rays[y * self.width + x] = rays;
+  }
+
+  /// Rays of the most expensive pixel
+  pub fn max(&self) -> usize {
+    self.rays.iter().copied().max().unwrap_or(0)
+  }
+
+  /// Heatmap of the cost relative to the most expensive pixel, going from
+  /// black over red and yellow to white.
+  pub fn to_heatmap(&self) -> Canvas {
+    let max = self.max().max(1) as F;
+    let mut canvas = Canvas::new(self.width, self.height);
+    for y in 0..self.height {
+      for x in 0..self.width {
+        canvas.write_pixel(x, y, heat(self.rays_at(x, y) as F / max));
+      }
+    }
+    canvas
+  }
+}
+
+/// Color of a heat between 0 and 1
+fn heat(heat: F) -> Color {
+  let channel = |offset: F| (heat * 3.0 - offset).clamp(0.0, 1.0);
+  Color::new(channel(0.0), channel(1.0), channel(2.0))
+}
+
+/// Renders all pixels of a camera like `renderer::render`, counting the rays
+/// traced for each of them.
+///
+/// Adaptive sampling is ignored, every pixel gets the samples of the camera.
+pub fn render<P>(world: &World, camera: &Camera, progress: P) -> (Canvas, CostMap)
+where
+  P: Fn(usize) + Sync,
+{
+  let mut canvas = Canvas::new(camera.hsize, camera.vsize);
+  let mut costs = CostMap::new(camera.hsize, camera.vsize);
+
+  let tiles = tiles(camera.hsize, camera.vsize, 16);
+  let rendered: Vec<Vec<(usize, usize, Color, usize)>> = iterate_tiles(&tiles)
+    .map(|tile| {
+      let pixels = tile
+        .pixels()
+        .map(|(x, y)| {
+          let before = rays_counted();
+          let color = render_pixel(world, camera, x, y);
+          (x, y, color, rays_counted() - before)
+        })
+        .collect();
+      progress(tile.pixel_count());
+      pixels
+    })
+    .collect();
+
+  for (x, y, color, rays) in rendered.into_iter().flatten() {
+    canvas.write_pixel(x, y, color);
+    costs.write_rays(x, y, rays);
+  }
+
+  (canvas, costs)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::test_scene::{camera_facing_ball, floor_and_ball};
+
+  #[test]
+  fn every_pixel_costs_at_least_its_camera_ray() {
+    let (_, costs) = render(&World::default(), &camera_facing_ball(), |_| ());
+
+    assert_eq!(costs.rays_at(0, 0), 1);
+    assert_eq!(costs.max(), 1);
+  }
+
+  #[test]
+  fn reflections_and_shadows_make_pixels_expensive() {
+    let (_, costs) = render(&floor_and_ball(), &camera_facing_ball(), |_| ());
+
+    // The ball needs a shadow ray, the mirroring floor also a reflection
+    assert_eq!(costs.rays_at(5, 0), 1);
+    assert_eq!(costs.rays_at(5, 5), 2);
+    assert!(costs.rays_at(5, 10) > costs.rays_at(5, 5));
+  }
+
+  #[test]
+  fn the_heatmap_is_relative_to_the_most_expensive_pixel() {
+    let mut costs = CostMap::new(3, 1);
+    costs.write_rays(0, 0, 0);
+    costs.write_rays(1, 0, 3);
+    costs.write_rays(2, 0, 6);
+
+    let heatmap = costs.to_heatmap();
+
+    assert_fuzzy_eq!(heatmap.pixel_at(0, 0), Color::black());
+    assert_fuzzy_eq!(heatmap.pixel_at(1, 0), Color::new(1.0, 0.5, 0.0));
+    assert_fuzzy_eq!(heatmap.pixel_at(2, 0), Color::white());
+  }
+}
@@ -0,0 +1,200 @@
+use super::{render_tiles, tiles};
+use crate::camera::Camera;
+use crate::canvas::Canvas;
+use crate::sampling::Jitter;
+use crate::world::World;
+use crate::F;
+use std::time::{Duration, Instant};
+
+/// Outcome of a progressive render
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgressiveReport {
+  pub passes: usize,
+  /// Rays traced per pixel over all passes
+  pub samples_per_pixel: usize,
+  pub duration: Duration,
+}
+
+/// Renders one pass after the other until the time budget is exhausted.
+///
+/// Every pass traces one jittered ray per pixel (or the configured samples of
+/// the camera for the first pass) and is accumulated into the result. At least
+/// one pass is always rendered, additional passes are only started if they are
+/// expected to finish within the budget.
+pub fn render<P>(
+  world: &World,
+  camera: &Camera,
+  time_budget: Duration,
+  on_pass: P,
+) -> (Canvas, ProgressiveReport)
+where
+  P: Fn(usize, Duration),
+{
+  let start = Instant::now();
+  let seed = match camera.jitter {
+    Jitter::Seeded(seed) => seed,
+    _ => 0,
+  };
+  let tiles = tiles(camera.hsize, camera.vsize, 16);
+
+  let mut sum = Canvas::new(camera.hsize, camera.vsize);
+  let mut passes = 0;
+  let mut samples_per_pixel = 0;
+  let mut slowest_pass = Duration::ZERO;
+
+  loop {
+    let pass_start = Instant::now();
+    let pass_camera = if passes == 0 {
+      camera.clone()
+    } else {
+      camera
+        .clone()
+        .with_samples(1)
+        .with_jitter(Jitter::Seeded(seed.wrapping_add(passes as u64)))
+    };
+    let pass = render_tiles(world, &tiles, |_| {}, |_| &pass_camera);
+    let weight = (pass_camera.samples * pass_camera.samples) as F;
+
+    for y in 0..camera.vsize {
+      for x in 0..camera.hsize {
+        let color = sum.pixel_at(x, y) + pass.pixel_at(x, y) * weight;
+        sum.write_pixel(x, y, color);
+      }
+    }
+
+    passes += 1;
+    samples_per_pixel += pass_camera.samples * pass_camera.samples;
+    slowest_pass = slowest_pass.max(pass_start.elapsed());
+    on_pass(passes, start.elapsed());
+
+    if start.elapsed() + slowest_pass > time_budget {
+      break;
+    }
+  }
+
+  let mut canvas = Canvas::new(camera.hsize, camera.vsize);
+  for y in 0..camera.vsize {
+    for x in 0..camera.hsize {
+      canvas.write_pixel(x, y, sum.pixel_at(x, y) * (1.0 / samples_per_pixel as F));
+    }
+  }
+
+  let report = ProgressiveReport {
+    passes,
+    samples_per_pixel,
+    duration: start.elapsed(),
+  };
+
+  (canvas, report)
+}
+
+/// Parses durations like `90`, `90s`, `10m`, `1h` or `1h30m`.
+///
+/// Plain numbers are interpreted as seconds.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+  let text = text.trim();
+  if text.is_empty() {
+    return None;
+  }
+  if let Ok(seconds) = text.parse::<F>() {
+    return Duration::try_from_secs_f64(seconds).ok();
+  }
+
+  let mut total = 0.0;
+  let mut number = String::new();
+  for character in text.chars() {
+    if character.is_ascii_digit() || character == '.' {
+      number.push(character);
+      continue;
+    }
+    let value = number.parse::<F>().ok()?;
+    number.clear();
+    total += value
+      * match character {
+        'h' => 3600.0,
+        'm' => 60.0,
+        's' => 1.0,
+        _ => return None,
+      };
+  }
+
+  if !number.is_empty() {
+    return None;
+  }
+
+  Duration::try_from_secs_f64(total).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::angle::Angle;
+  use crate::body::Body;
+  use crate::canvas::Color;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::light::{Light, PointLight};
+  use crate::matrix::Matrix;
+  use crate::sphere::Sphere;
+  use crate::tuple::Tuple;
+  use std::cell::Cell;
+  use std::f64::consts::PI;
+
+  #[test]
+  fn parsing_durations() {
+    assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+    assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+    assert_eq!(parse_duration("10m"), Some(Duration::from_secs(600)));
+    assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+    assert_eq!(parse_duration("0.5m"), Some(Duration::from_secs(30)));
+    assert_eq!(parse_duration("10x"), None);
+    assert_eq!(parse_duration("m"), None);
+    assert_eq!(parse_duration("10m5"), None);
+    assert_eq!(parse_duration(""), None);
+  }
+
+  #[test]
+  fn durations_out_of_range_are_rejected() {
+    assert_eq!(parse_duration("-5"), None);
+    assert_eq!(parse_duration("inf"), None);
+    assert_eq!(parse_duration("NaN"), None);
+    assert_eq!(parse_duration("1e30"), None);
+    assert_eq!(parse_duration("1e30h"), None);
+    assert_eq!(parse_duration("100000000000000000000h"), None);
+  }
+
+  #[test]
+  fn at_least_one_pass_is_rendered() {
+    let world = World::default();
+    let camera = Camera::new(8, 4, Angle::radians(PI / 2.0));
+    let calls = Cell::new(0);
+
+    let (canvas, report) = render(&world, &camera, Duration::ZERO, |_, _| {
+      calls.set(calls.get() + 1)
+    });
+
+    assert_eq!(report.passes, 1);
+    assert_eq!(report.samples_per_pixel, 1);
+    assert_eq!(calls.get(), 1);
+    assert_fuzzy_eq!(canvas.pixel_at(0, 0), Color::black());
+  }
+
+  #[test]
+  fn passes_are_accumulated_within_the_budget() {
+    // The camera is enclosed by a sphere, which shadows the light outside of
+    // it. Every ray therefore sees the same ambient color.
+    let light = Light::from(PointLight::new(
+      Tuple::point(-20.0, 20.0, -20.0),
+      Color::white(),
+    ));
+    let enclosure = Sphere::default().with_transform(Matrix::scaling(10.0, 10.0, 10.0));
+    let world = World::new(vec![Body::from(enclosure)], vec![light]);
+    let camera = Camera::new(4, 4, Angle::radians(PI / 3.0)).with_exposure(1.0);
+
+    let (canvas, report) = render(&world, &camera, Duration::from_millis(50), |_, _| {});
+
+    assert!(report.passes > 1);
+    assert_eq!(report.samples_per_pixel, report.passes);
+    // Exposure is applied exactly once to the accumulated result
+    assert_fuzzy_eq!(canvas.pixel_at(2, 2), Color::new(0.2, 0.2, 0.2));
+  }
+}
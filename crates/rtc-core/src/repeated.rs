@@ -0,0 +1,319 @@
+use crate::body::*;
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::ray::Ray;
+use crate::tuple::Tuple;
+use crate::F;
+use std::sync::Arc;
+
+/// Cells a single ray walks through at most, beyond which the copies of an
+/// endless lattice are too small to make out anyway
+const MAX_CELLS: usize = 1024;
+
+/// Body tiled on a lattice, like a field of columns or an endless grid of
+/// spheres, while only a single copy of it is kept.
+///
+/// Rays walk through the cells of the lattice and are only tested against
+/// the copy in the cells they pass, so the body needs to fit into the cell
+/// around the origin. Axes with a spacing of zero are not repeated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Repeated {
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+  pub body: Arc<Body>,
+  pub spacing: Tuple,
+  /// Copies along each repeated axis, starting at the origin, or endless
+  pub count: Option<[usize; 3]>,
+}
+
+impl Repeated {
+  pub fn new(body: Body, spacing: Tuple) -> Self {
+    Self {
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+      body: Arc::new(body),
+      spacing,
+      count: None,
+    }
+  }
+
+  pub fn with_count(mut self, x: usize, y: usize, z: usize) -> Self {
+    self.count = Some([x.max(1), y.max(1), z.max(1)]);
+    self
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.body = Arc::new(Body::clone(&self.body).with_material(material));
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.body = Arc::new(Body::clone(&self.body).with_shadows(shadows));
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    self.transform = transform;
+    self.inverse_transform = inverse_transform;
+    self
+  }
+
+  fn spacings(&self) -> [F; 3] {
+    [self.spacing.x, self.spacing.y, self.spacing.z]
+  }
+
+  /// Index of the cell along the axis, which contains the coordinate
+  fn cell_at(&self, axis: usize, coordinate: F) -> i64 {
+    let spacing = self.spacings()[axis];
+    if spacing <= 0.0 {
+      return 0;
+    }
+    let cell = (coordinate / spacing + 0.5).floor() as i64;
+    match self.count {
+      Some(count) => cell.clamp(0, count[axis] as i64 - 1),
+      None => cell,
+    }
+  }
+
+  fn contains_cell(&self, axis: usize, cell: i64) -> bool {
+    match self.count {
+      Some(count) => (0..count[axis] as i64).contains(&cell),
+      None => true,
+    }
+  }
+
+  fn offset(&self, cell: [i64; 3]) -> Tuple {
+    let [x, y, z] = self.spacings();
+    Tuple::vector(
+      cell[0] as F * x.max(0.0),
+      cell[1] as F * y.max(0.0),
+      cell[2] as F * z.max(0.0),
+    )
+  }
+
+  /// The copy of the body in the cell, placed in world space
+  fn place(&self, body: Body, cell: [i64; 3]) -> Body {
+    let offset = self.offset(cell);
+    let translation = Matrix::translation(offset.x, offset.y, offset.z);
+    let inverse_translation = Matrix::translation(-offset.x, -offset.y, -offset.z);
+    let transform = self.transform * translation * body.transform();
+    let inverse_transform = body.inverse_transform() * inverse_translation * self.inverse_transform;
+    body.with_transform_and_inverse(transform, inverse_transform)
+  }
+}
+
+impl FuzzyEq<&Repeated> for Repeated {
+  fn fuzzy_eq(&self, other: &Repeated) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+      && self.body.fuzzy_eq(Body::clone(&other.body))
+      && self.spacing.fuzzy_eq(other.spacing)
+      && self.count == other.count
+  }
+}
+
+impl Intersectable for Repeated {
+  fn max_intersections(&self) -> usize {
+    // The cell of the origin of the ray and the one with the hit
+    2 * self.body.max_intersections()
+  }
+
+  /// Walks the cells along the ray (Amanatides and Woo), until a copy is hit
+  /// in front of the origin
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    let (enter, exit) = match self.bounds_in_object_space().span(object_space_ray) {
+      Some(span) if span.1 >= 0.0 => span,
+      _ => return vec![],
+    };
+    let start = object_space_ray.position(enter.max(0.0));
+    let origin = object_space_ray.origin;
+    let direction = object_space_ray.direction;
+    let axes = [
+      (start.x, origin.x, direction.x),
+      (start.y, origin.y, direction.y),
+      (start.z, origin.z, direction.z),
+    ];
+
+    let mut cell = [0; 3];
+    let mut step = [0; 3];
+    let mut next = [F::INFINITY; 3];
+    let mut delta = [F::INFINITY; 3];
+    for (axis, &(start, origin, direction)) in axes.iter().enumerate() {
+      let spacing = self.spacings()[axis];
+      cell[axis] = self.cell_at(axis, start);
+      if spacing > 0.0 && direction != 0.0 {
+        step[axis] = direction.signum() as i64;
+        let boundary = (cell[axis] as F + 0.5 * direction.signum()) * spacing;
+        next[axis] = (boundary - origin) / direction;
+        delta[axis] = spacing / direction.abs();
+      }
+    }
+
+    let mut xs = vec![];
+    for _ in 0..MAX_CELLS {
+      let offset = self.offset(cell);
+      let cell_ray = Ray::new(origin - offset, direction);
+      let hits = self.body.intersect(cell_ray);
+      let in_front = hits.into_iter().fold(false, |in_front, hit| {
+        xs.push((hit.t, self.place(hit.body, cell)));
+        in_front || hit.t > 0.0
+      });
+      if in_front {
+        break;
+      }
+
+      let axis = (0..3).min_by(|a, b| next[*a].total_cmp(&next[*b])).unwrap();
+      if next[axis] > exit || !self.contains_cell(axis, cell[axis] + step[axis]) {
+        break;
+      }
+      cell[axis] += step[axis];
+      next[axis] += delta[axis];
+    }
+    xs
+  }
+
+  fn normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    let cell = [
+      self.cell_at(0, object_space_point.x),
+      self.cell_at(1, object_space_point.y),
+      self.cell_at(2, object_space_point.z),
+    ];
+    self.body.normal_at(object_space_point - self.offset(cell))
+  }
+
+  fn material(&self) -> Material {
+    self.body.material()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.body.shadows()
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  /// Endless along the repeated axes, unless the copies are counted
+  fn bounds_in_object_space(&self) -> Bounds {
+    let body = self.body.bounds();
+    let mut min = [body.min.x, body.min.y, body.min.z];
+    let mut max = [body.max.x, body.max.y, body.max.z];
+    for (axis, spacing) in self.spacings().iter().enumerate() {
+      if *spacing <= 0.0 {
+        continue;
+      }
+      match self.count {
+        Some(count) => max[axis] += (count[axis] - 1) as F * spacing,
+        None => {
+          min[axis] = F::NEG_INFINITY;
+          max[axis] = F::INFINITY;
+        }
+      }
+    }
+    Bounds::new(
+      Tuple::point(min[0], min[1], min[2]),
+      Tuple::point(max[0], max[1], max[2]),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::material::Phong;
+  use crate::sphere::Sphere;
+
+  fn spheres(spacing: Tuple) -> Repeated {
+    Repeated::new(
+      Body::from(Sphere::default().with_transform(Matrix::scaling(0.5, 0.5, 0.5))),
+      spacing,
+    )
+  }
+
+  #[test]
+  fn rays_hit_the_copy_in_the_first_cell_they_pass() {
+    let grid = spheres(Tuple::vector(2.0, 0.0, 2.0));
+    let r = Ray::new(Tuple::point(4.0, 0.0, -101.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let xs = grid.intersect(r);
+
+    assert_eq!(xs.len(), 2);
+    assert_fuzzy_eq!(xs[0].t, 0.5);
+    assert_fuzzy_eq!(xs[1].t, 1.5);
+    assert_fuzzy_eq!(
+      xs[0].body,
+      Body::from(
+        Sphere::default()
+          .with_transform(Matrix::translation(4.0, 0.0, -100.0) * Matrix::scaling(0.5, 0.5, 0.5))
+      )
+    );
+  }
+
+  #[test]
+  fn rays_between_the_copies_miss() {
+    let grid = spheres(Tuple::vector(2.0, 0.0, 2.0));
+    let between = Ray::new(Tuple::point(1.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let above = Ray::new(Tuple::point(0.0, 1.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    assert!(grid.intersect(between).is_empty());
+    assert!(grid.intersect(above).is_empty());
+  }
+
+  #[test]
+  fn diagonal_rays_walk_through_the_cells() {
+    let grid = spheres(Tuple::vector(2.0, 2.0, 2.0))
+      .with_transform(Matrix::translation(0.0, 10.0, 0.0))
+      .with_material(Material::from(Phong::default().with_ambient(1.0)));
+    let r = Ray::new(
+      Tuple::point(-9.0, 1.0, -9.0),
+      Tuple::vector(1.0, 1.0, 1.0).normalize(),
+    );
+
+    let xs = grid.intersect(r);
+
+    // The first copy on the diagonal is the one at (-8, -8, -8) below the
+    // translation
+    assert_eq!(xs.len(), 2);
+    assert_fuzzy_eq!(
+      xs[0].body.transform(),
+      Matrix::translation(-8.0, 2.0, -8.0) * Matrix::scaling(0.5, 0.5, 0.5)
+    );
+    assert_fuzzy_eq!(
+      xs[0].body.inverse_transform(),
+      Matrix::scaling(2.0, 2.0, 2.0) * Matrix::translation(8.0, -2.0, 8.0)
+    );
+    assert_eq!(
+      xs[0].body.material(),
+      Material::from(Phong::default().with_ambient(1.0))
+    );
+  }
+
+  #[test]
+  fn counted_copies_end_after_the_last_one() {
+    let row = spheres(Tuple::vector(2.0, 0.0, 0.0)).with_count(3, 1, 1);
+    let along = Ray::new(Tuple::point(-5.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0));
+    let past = Ray::new(Tuple::point(6.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let last = Ray::new(Tuple::point(4.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    assert_fuzzy_eq!(
+      row.bounds_in_object_space(),
+      Bounds::new(Tuple::point(-0.5, -0.5, -0.5), Tuple::point(4.5, 0.5, 0.5))
+    );
+    assert_fuzzy_eq!(row.intersect(along)[0].t, 4.5);
+    assert!(row.intersect(past).is_empty());
+    assert_eq!(row.intersect(last).len(), 2);
+  }
+}
@@ -0,0 +1,288 @@
+use crate::fuzzy_eq::FuzzyEq;
+use crate::tuple::Tuple;
+use crate::F;
+use std::f64::consts::PI;
+
+/// Small xorshift based pseudo random number generator.
+///
+/// It is fully deterministic for a given seed and does not depend on any
+/// operating system entropy, which allows renders to be reproduced exactly.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct XorShift {
+  state: u64,
+}
+
+impl XorShift {
+  pub fn new(seed: u64) -> Self {
+    // A state of zero would only ever produce zeros. Mixing the seed ensures
+    // similar seeds (0, 1, 2, ...) still result in unrelated sequences.
+    let mut state = seed ^ 0x9E37_79B9_7F4A_7C15;
+    state = (state ^ (state >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    state = (state ^ (state >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    state ^= state >> 31;
+    if state == 0 {
+      state = 0x9E37_79B9_7F4A_7C15;
+    }
+
+    Self { state }
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    let mut x = self.state;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    self.state = x;
+    x
+  }
+
+  /// Next value in the range [0, 1)
+  pub fn next_float(&mut self) -> F {
+    // Use the upper 53 bits, which is the precision of an f64 mantissa
+    (self.next_u64() >> 11) as F / (1u64 << 53) as F
+  }
+}
+
+/// Strategy used to place samples inside of their cell (e.g. sub pixels)
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum Jitter {
+  /// Every sample is placed at the center of its cell.
+  #[default]
+  Centered,
+  /// The given offsets are used one after the other, wrapping around at the
+  /// end of the sequence.
+  Sequence(Vec<F>),
+  /// Offsets are generated pseudo randomly based on the given seed.
+  Seeded(u64),
+}
+
+impl Jitter {
+  /// Creates `count` offsets in the range [0, 1) for the given stream.
+  ///
+  /// The same stream (e.g. the index of a pixel) always produces the same
+  /// offsets, independent of the order streams are requested in. This keeps
+  /// parallel renders reproducible.
+  pub fn offsets(&self, stream: u64, count: usize) -> Vec<F> {
+    match self {
+      Jitter::Centered => vec![0.5; count],
+      Jitter::Sequence(sequence) if sequence.is_empty() => vec![0.5; count],
+      Jitter::Sequence(sequence) => {
+        let start = (stream as usize).wrapping_mul(count) % sequence.len();
+        (0..count)
+          .map(|index| sequence[(start + index) % sequence.len()])
+          .collect()
+      }
+      Jitter::Seeded(seed) => {
+        let mut random =
+          XorShift::new(seed.wrapping_add(stream.wrapping_mul(0x2545_F491_4F6C_DD1D)));
+        (0..count).map(|_| random.next_float()).collect()
+      }
+    }
+  }
+}
+
+impl FuzzyEq<Jitter> for Jitter {
+  fn fuzzy_eq(&self, other: Jitter) -> bool {
+    match (self, other) {
+      (Jitter::Centered, Jitter::Centered) => true,
+      (Jitter::Sequence(ref sequence), Jitter::Sequence(other)) => sequence.fuzzy_eq(other),
+      (Jitter::Seeded(seed), Jitter::Seeded(other)) => *seed == other,
+      _ => false,
+    }
+  }
+}
+
+/// Angle between neighbouring points of the spirals below, which keeps
+/// them from lining up
+fn golden_angle() -> F {
+  PI * (3.0 - (5.0 as F).sqrt())
+}
+
+/// Two directions perpendicular to the normal and to each other
+fn tangents(normalv: Tuple) -> (Tuple, Tuple) {
+  let helper = if normalv.x.abs() > 0.9 {
+    Tuple::vector(0.0, 1.0, 0.0)
+  } else {
+    Tuple::vector(1.0, 0.0, 0.0)
+  };
+  let tangent = helper.cross(normalv).normalize();
+  (tangent, normalv.cross(tangent))
+}
+
+/// Direction at the given height above the plane of the tangents and angle
+/// around the normal
+fn around(normalv: Tuple, height: F, angle: F) -> Tuple {
+  let (tangent, bitangent) = tangents(normalv);
+  let radius = (1.0 - height * height).max(0.0).sqrt();
+  (tangent * (radius * angle.cos()) + bitangent * (radius * angle.sin()) + normalv * height)
+    .normalize()
+}
+
+/// Directions evenly spread over the whole sphere (a Fibonacci lattice),
+/// each standing for the same area of it
+pub fn fibonacci_sphere(count: usize) -> Vec<Tuple> {
+  let up = Tuple::vector(0.0, 1.0, 0.0);
+  (0..count)
+    .map(|index| {
+      let height = 1.0 - 2.0 * (index as F + 0.5) / count as F;
+      around(up, height, golden_angle() * index as F)
+    })
+    .collect()
+}
+
+/// Directions evenly spread over the hemisphere around the normal, each
+/// standing for the same area of it
+pub fn fibonacci_hemisphere(normalv: Tuple, count: usize) -> Vec<Tuple> {
+  (0..count)
+    .map(|index| {
+      let height = 1.0 - (index as F + 0.5) / count as F;
+      around(normalv, height, golden_angle() * index as F)
+    })
+    .collect()
+}
+
+/// Cosine weighted directions on the hemisphere around the normal.
+///
+/// The directions are placed on a golden angle spiral, so they are well
+/// distributed and identical for every render.
+pub fn hemisphere_directions(normalv: Tuple, count: usize) -> Vec<Tuple> {
+  (0..count)
+    .map(|index| {
+      let radius = ((index as F + 0.5) / count as F).sqrt();
+      let height = (1.0 - radius * radius).sqrt();
+      around(normalv, height, golden_angle() * index as F)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn xorshift_is_deterministic_for_the_same_seed() {
+    let mut a = XorShift::new(42);
+    let mut b = XorShift::new(42);
+
+    for _ in 0..100 {
+      assert_eq!(a.next_u64(), b.next_u64());
+    }
+  }
+
+  #[test]
+  fn xorshift_floats_are_in_unit_range() {
+    let mut random = XorShift::new(0);
+
+    for _ in 0..1000 {
+      let value = random.next_float();
+      assert!((0.0..1.0).contains(&value));
+    }
+  }
+
+  #[test]
+  fn different_seeds_produce_different_sequences() {
+    let mut a = XorShift::new(1);
+    let mut b = XorShift::new(2);
+
+    assert_ne!(a.next_u64(), b.next_u64());
+  }
+
+  #[test]
+  fn centered_jitter_places_samples_in_the_middle() {
+    let jitter = Jitter::Centered;
+
+    assert_fuzzy_eq!(jitter.offsets(7, 3), vec![0.5, 0.5, 0.5]);
+  }
+
+  #[test]
+  fn sequence_jitter_cycles_through_the_sequence() {
+    let jitter = Jitter::Sequence(vec![0.7, 0.3, 0.9, 0.1, 0.5]);
+
+    assert_fuzzy_eq!(jitter.offsets(0, 3), vec![0.7, 0.3, 0.9]);
+    assert_fuzzy_eq!(jitter.offsets(1, 3), vec![0.1, 0.5, 0.7]);
+  }
+
+  #[test]
+  fn seeded_jitter_is_reproducible_per_stream() {
+    let jitter = Jitter::Seeded(1234);
+
+    assert_fuzzy_eq!(jitter.offsets(5, 8), jitter.offsets(5, 8));
+    assert_fuzzy_ne!(jitter.offsets(5, 8), jitter.offsets(6, 8));
+  }
+
+  /// Average cosine between the directions and the normal
+  fn mean_cosine(directions: &[Tuple], normalv: Tuple) -> F {
+    directions
+      .iter()
+      .map(|direction| direction.dot(normalv))
+      .sum::<F>()
+      / directions.len() as F
+  }
+
+  #[test]
+  fn fibonacci_spheres_cover_every_octant_equally() {
+    let directions = fibonacci_sphere(800);
+
+    let mut octants = [0; 8];
+    for direction in directions.iter() {
+      assert_fuzzy_eq!(direction.magnitude(), 1.0);
+      let octant = (direction.x > 0.0) as usize
+        + 2 * (direction.y > 0.0) as usize
+        + 4 * (direction.z > 0.0) as usize;
+      octants[octant] += 1;
+    }
+    for count in octants {
+      assert!((90..=110).contains(&count), "{:?}", octants);
+    }
+    let sum = directions
+      .iter()
+      .fold(Tuple::vector(0.0, 0.0, 0.0), |sum, direction| {
+        sum + *direction
+      });
+    assert!(sum.magnitude() / 800.0 < 0.01);
+  }
+
+  #[test]
+  fn fibonacci_points_keep_their_distance() {
+    let directions = fibonacci_sphere(200);
+    // Every point covers the area of a circle of this radius
+    let spacing = (4.0 / 200.0 as F).sqrt();
+
+    for (index, direction) in directions.iter().enumerate() {
+      let nearest = directions
+        .iter()
+        .enumerate()
+        .filter(|(other, _)| *other != index)
+        .map(|(_, other)| (*other - *direction).magnitude())
+        .fold(F::INFINITY, F::min);
+      assert!(nearest > 0.9 * spacing, "{} at {}", nearest, index);
+    }
+  }
+
+  #[test]
+  fn hemispheres_are_weighted_uniformly_or_by_cosine() {
+    let normalv = Tuple::vector(1.0, 1.0, 0.0).normalize();
+
+    let uniform = fibonacci_hemisphere(normalv, 500);
+    let cosine = hemisphere_directions(normalv, 500);
+
+    assert!(uniform.iter().all(|direction| direction.dot(normalv) > 0.0));
+    // The averages of the cosine over a hemisphere, 1/2 uniformly and 2/3
+    // weighted by the cosine
+    assert!((mean_cosine(&uniform, normalv) - 0.5).abs() < 0.01);
+    assert!((mean_cosine(&cosine, normalv) - 2.0 / 3.0).abs() < 0.01);
+  }
+
+  #[test]
+  fn directions_lie_on_the_hemisphere_around_the_normal() {
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+
+    let directions = hemisphere_directions(normalv, 16);
+
+    assert_eq!(directions.len(), 16);
+    for direction in directions {
+      assert_fuzzy_eq!(direction.magnitude(), 1.0);
+      assert!(direction.dot(normalv) > 0.0);
+    }
+  }
+}
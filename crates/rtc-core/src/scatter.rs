@@ -0,0 +1,179 @@
+use crate::angle::Angle;
+use crate::body::{Body, Intersectable};
+use crate::bounds::Bounds;
+use crate::matrix::Matrix;
+use crate::sampling::XorShift;
+use crate::tuple::Tuple;
+use crate::F;
+
+/// Random placement of copies of bodies inside a region, e.g. for showcase
+/// scenes full of spheres.
+///
+/// Every copy is scaled, turned around the y axis, and moved to stand with
+/// the bottom center of its bounds on a random point of the region. Copies
+/// whose bounds overlap those of earlier ones are tried again, up to
+/// `attempts` times each. The same seed always gives the same placement.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Scatter {
+  pub count: usize,
+  pub region: Bounds,
+  pub min_scale: F,
+  pub max_scale: F,
+  pub rotate: bool,
+  pub seed: u64,
+  pub attempts: usize,
+}
+
+impl Scatter {
+  pub fn new(count: usize, region: Bounds) -> Self {
+    Self {
+      count,
+      region,
+      min_scale: 1.0,
+      max_scale: 1.0,
+      rotate: true,
+      seed: 0,
+      attempts: 100,
+    }
+  }
+
+  pub fn with_scale(mut self, min_scale: F, max_scale: F) -> Self {
+    self.min_scale = min_scale;
+    self.max_scale = max_scale;
+    self
+  }
+
+  pub fn with_rotation(mut self, rotate: bool) -> Self {
+    self.rotate = rotate;
+    self
+  }
+
+  pub fn with_seed(mut self, seed: u64) -> Self {
+    self.seed = seed;
+    self
+  }
+
+  pub fn with_attempts(mut self, attempts: usize) -> Self {
+    self.attempts = attempts;
+    self
+  }
+
+  /// Transformations placing copies of something with the given bounds,
+  /// applied on top of its own transformation. Fewer than `count` are
+  /// returned if the region is too crowded, and none for infinite bounds.
+  pub fn transforms(&self, bounds: Bounds) -> Vec<Matrix<4>> {
+    if bounds.is_infinite() {
+      return vec![];
+    }
+    let center = bounds.center();
+    let anchor = Matrix::translation(-center.x, -bounds.min.y, -center.z);
+    let (min, max) = (self.region.min, self.region.max);
+    let mut random = XorShift::new(self.seed);
+    let mut between = |from: F, to: F| from + (to - from) * random.next_float();
+
+    let mut placed: Vec<Bounds> = vec![];
+    let mut transforms = vec![];
+    let mut attempts = 0;
+    while transforms.len() < self.count && attempts < self.attempts {
+      attempts += 1;
+      let scale = between(self.min_scale, self.max_scale);
+      let angle = if self.rotate {
+        between(0.0, 360.0)
+      } else {
+        0.0
+      };
+      let position = Tuple::point(
+        between(min.x, max.x),
+        between(min.y, max.y),
+        between(min.z, max.z),
+      );
+      let transform = Matrix::translation(position.x, position.y, position.z)
+        * Matrix::rotation_y(Angle::degrees(angle))
+        * Matrix::scaling(scale, scale, scale)
+        * anchor;
+
+      let copy = bounds.transform(transform);
+      if placed.iter().any(|other| other.overlaps(copy)) {
+        continue;
+      }
+      placed.push(copy);
+      transforms.push(transform);
+      attempts = 0;
+    }
+    transforms
+  }
+
+  /// Copies of all the bodies, placed together as one group
+  pub fn bodies(&self, bodies: &[Body]) -> Vec<Body> {
+    let bounds = match bodies
+      .iter()
+      .map(|body| body.bounds())
+      .reduce(|bounds, other| bounds.union(other))
+    {
+      Some(bounds) => bounds,
+      None => return vec![],
+    };
+    self
+      .transforms(bounds)
+      .into_iter()
+      .flat_map(|transform| {
+        bodies
+          .iter()
+          .map(move |body| body.clone().with_transform(transform * body.transform()))
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sphere::Sphere;
+
+  fn region(size: F) -> Bounds {
+    Bounds::new(
+      Tuple::point(-size, 0.0, -size),
+      Tuple::point(size, 0.0, size),
+    )
+  }
+
+  #[test]
+  fn scattered_bodies_do_not_overlap() {
+    let bodies = Scatter::new(20, region(10.0))
+      .with_scale(0.5, 1.5)
+      .bodies(&[Body::from(Sphere::default())]);
+
+    assert_eq!(bodies.len(), 20);
+    for (index, body) in bodies.iter().enumerate() {
+      let bounds = body.bounds();
+      // Standing on the region, which lies on the floor
+      assert!(bounds.min.y.abs() < 1e-6);
+      assert!(bodies[index + 1..]
+        .iter()
+        .all(|other| !other.bounds().overlaps(bounds)));
+    }
+  }
+
+  #[test]
+  fn the_same_seed_gives_the_same_placement() {
+    let sphere = [Body::from(Sphere::default())];
+    let scatter = Scatter::new(5, region(10.0));
+
+    assert_eq!(
+      scatter.with_seed(3).bodies(&sphere),
+      scatter.with_seed(3).bodies(&sphere)
+    );
+    assert_ne!(
+      scatter.with_seed(3).bodies(&sphere),
+      scatter.with_seed(4).bodies(&sphere)
+    );
+  }
+
+  #[test]
+  fn crowded_regions_get_fewer_bodies() {
+    let bodies = Scatter::new(10, region(1.0)).bodies(&[Body::from(Sphere::default())]);
+
+    assert!(!bodies.is_empty());
+    assert!(bodies.len() < 10);
+  }
+}
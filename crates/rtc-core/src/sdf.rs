@@ -0,0 +1,541 @@
+use crate::body::*;
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::*;
+use crate::matrix::*;
+use crate::ray::*;
+use crate::tuple::*;
+use crate::{EPSILON, F};
+
+/// Maximum number of steps while marching along a ray
+const MAX_STEPS: usize = 256;
+/// Distance below which the surface counts as hit.
+///
+/// It needs to be smaller than the offset of the over point, so that rays
+/// leaving the surface do not hit it again right away.
+const HIT_DISTANCE: F = EPSILON / 10.0;
+/// Number of growing steps taken to get behind a surface, before the ray
+/// counts as only grazing it
+const INSIDE_STEPS: usize = 8;
+/// Step used for the central differences of the normal
+const NORMAL_DELTA: F = 0.0001;
+/// Distance (in object space) from the point where the ray enters the bounds,
+/// after which the ray counts as miss
+const MAX_DISTANCE: F = 100.0;
+
+/// Built-in signed distance functions, all centered at the origin.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Sdf {
+  Sphere {
+    radius: F,
+  },
+  /// Box with the given half extents along each axis
+  Box {
+    size: Tuple,
+  },
+  /// Torus lying in the xz plane
+  Torus {
+    major_radius: F,
+    minor_radius: F,
+  },
+  /// Mandelbulb fractal with a radius of about one
+  Mandelbulb {
+    power: F,
+    iterations: usize,
+  },
+}
+
+impl Sdf {
+  /// Signed distance to the surface, negative inside of the shape
+  pub fn distance(&self, point: Tuple) -> F {
+    match *self {
+      Sdf::Sphere { radius } => Tuple::vector(point.x, point.y, point.z).magnitude() - radius,
+      Sdf::Box { size } => {
+        let q = Tuple::vector(
+          point.x.abs() - size.x,
+          point.y.abs() - size.y,
+          point.z.abs() - size.z,
+        );
+        let outside = Tuple::vector(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).magnitude();
+        let inside = q.x.max(q.y).max(q.z).min(0.0);
+        outside + inside
+      }
+      Sdf::Torus {
+        major_radius,
+        minor_radius,
+      } => {
+        let ring = (point.x.powi(2) + point.z.powi(2)).sqrt() - major_radius;
+        (ring.powi(2) + point.y.powi(2)).sqrt() - minor_radius
+      }
+      Sdf::Mandelbulb { power, iterations } => mandelbulb_distance(point, power, iterations),
+    }
+  }
+}
+
+impl Sdf {
+  /// Bounds enclosing the shape
+  pub fn bounds(&self) -> Bounds {
+    let extent = match *self {
+      Sdf::Sphere { radius } => Tuple::vector(radius, radius, radius),
+      Sdf::Box { size } => size,
+      Sdf::Torus {
+        major_radius,
+        minor_radius,
+      } => Tuple::vector(
+        major_radius + minor_radius,
+        minor_radius,
+        major_radius + minor_radius,
+      ),
+      // Radius of the bounding sphere used by the distance estimation
+      Sdf::Mandelbulb { .. } => Tuple::vector(1.5, 1.5, 1.5),
+    };
+    let origin = Tuple::point(0.0, 0.0, 0.0);
+    Bounds::new(origin - extent, origin + extent)
+  }
+}
+
+impl FuzzyEq<Sdf> for Sdf {
+  fn fuzzy_eq(&self, other: Sdf) -> bool {
+    match (*self, other) {
+      (Sdf::Sphere { radius }, Sdf::Sphere { radius: other }) => radius.fuzzy_eq(other),
+      (Sdf::Box { size }, Sdf::Box { size: other }) => size.fuzzy_eq(other),
+      (
+        Sdf::Torus {
+          major_radius,
+          minor_radius,
+        },
+        Sdf::Torus {
+          major_radius: other_major_radius,
+          minor_radius: other_minor_radius,
+        },
+      ) => major_radius.fuzzy_eq(other_major_radius) && minor_radius.fuzzy_eq(other_minor_radius),
+      (
+        Sdf::Mandelbulb { power, iterations },
+        Sdf::Mandelbulb {
+          power: other_power,
+          iterations: other_iterations,
+        },
+      ) => power.fuzzy_eq(other_power) && iterations == other_iterations,
+      _ => false,
+    }
+  }
+}
+
+/// Distance estimation of the mandelbulb
+fn mandelbulb_distance(point: Tuple, power: F, iterations: usize) -> F {
+  // The estimation overshoots far away from the fractal, so a bounding
+  // sphere is used there instead.
+  let bounding_distance = Tuple::vector(point.x, point.y, point.z).magnitude() - 1.5;
+  if bounding_distance > 0.0 {
+    return bounding_distance + 0.1;
+  }
+
+  let mut z = Tuple::vector(point.x, point.y, point.z);
+  let mut dr = 1.0;
+  let mut r = 0.0;
+  for _ in 0..iterations {
+    r = z.magnitude();
+    if r > 2.0 {
+      break;
+    }
+
+    let theta = (z.z / r).acos() * power;
+    let phi = z.y.atan2(z.x) * power;
+    dr = r.powf(power - 1.0) * power * dr + 1.0;
+
+    let zr = r.powf(power);
+    z = Tuple::vector(
+      theta.sin() * phi.cos(),
+      phi.sin() * theta.sin(),
+      theta.cos(),
+    ) * zr
+      + Tuple::vector(point.x, point.y, point.z);
+  }
+
+  if r == 0.0 {
+    return 0.0;
+  }
+  0.5 * r.ln() * r / dr
+}
+
+/// Second shape smoothly merged into the first one
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SdfBlend {
+  pub shape: Sdf,
+  /// Position of the second shape relative to the first one
+  pub offset: Tuple,
+  /// Size of the smooth transition between both shapes
+  pub smoothness: F,
+}
+
+impl FuzzyEq<SdfBlend> for SdfBlend {
+  fn fuzzy_eq(&self, other: SdfBlend) -> bool {
+    self.shape.fuzzy_eq(other.shape)
+      && self.offset.fuzzy_eq(other.offset)
+      && self.smoothness.fuzzy_eq(other.smoothness)
+  }
+}
+
+/// Body defined by a signed distance function, intersected by sphere tracing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SdfBody {
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+  pub material: Material,
+  pub shadows: Shadows,
+  pub shape: Sdf,
+  pub blend: Option<SdfBlend>,
+}
+
+impl Default for SdfBody {
+  fn default() -> Self {
+    Self {
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+      material: Default::default(),
+      shadows: Default::default(),
+      shape: Sdf::Sphere { radius: 1.0 },
+      blend: None,
+    }
+  }
+}
+
+impl SdfBody {
+  pub fn new(shape: Sdf) -> Self {
+    Self {
+      shape,
+      ..Default::default()
+    }
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.material = material;
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.shadows = shadows;
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    self.transform = transform;
+    self.inverse_transform = inverse_transform;
+    self
+  }
+
+  /// Smoothly merges another shape at the given offset into this one
+  pub fn with_blend(mut self, shape: Sdf, offset: Tuple, smoothness: F) -> Self {
+    self.blend = Some(SdfBlend {
+      shape,
+      offset,
+      smoothness,
+    });
+    self
+  }
+
+  /// Signed distance of the (blended) shape in object space
+  pub fn distance(&self, point: Tuple) -> F {
+    let distance = self.shape.distance(point);
+    match self.blend {
+      None => distance,
+      Some(blend) => {
+        let other = blend.shape.distance(point - blend.offset);
+        if blend.smoothness <= 0.0 {
+          return distance.min(other);
+        }
+        // Polynomial smooth minimum
+        let h = (0.5 + 0.5 * (other - distance) / blend.smoothness).clamp(0.0, 1.0);
+        other * (1.0 - h) + distance * h - blend.smoothness * h * (1.0 - h)
+      }
+    }
+  }
+}
+
+impl SdfBody {
+  /// Distance along the ray to the first surface between `from` and `to`
+  fn march(&self, ray: Ray, from: F, to: F) -> Option<F> {
+    let speed = ray.direction.magnitude();
+    // Rays starting inside of the body march towards the surface from within
+    let sign = self.distance(ray.position(from)).signum();
+
+    let mut t = from;
+    for _ in 0..MAX_STEPS {
+      let distance = sign * self.distance(ray.position(t));
+      if distance < HIT_DISTANCE {
+        return Some(t);
+      }
+      t += distance / speed;
+      if t > to || (t - from) * speed > MAX_DISTANCE {
+        break;
+      }
+    }
+
+    None
+  }
+
+  /// Distance along the ray just behind the surface at `t`, where the ray is
+  /// inside of the shape. `None` if the ray does not enter the shape there.
+  fn step_inside(&self, ray: Ray, t: F) -> Option<F> {
+    let speed = ray.direction.magnitude();
+    let mut step = HIT_DISTANCE;
+    let mut inside = t;
+    for _ in 0..INSIDE_STEPS {
+      inside += step / speed;
+      if self.distance(ray.position(inside)) < -HIT_DISTANCE {
+        return Some(inside);
+      }
+      step *= 2.0;
+    }
+    None
+  }
+}
+
+impl FuzzyEq<&SdfBody> for SdfBody {
+  fn fuzzy_eq(&self, other: &SdfBody) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+      && self.material.fuzzy_eq(other.material.clone())
+      && self.shadows == other.shadows
+      && self.shape.fuzzy_eq(other.shape)
+      && self.blend.fuzzy_eq(other.blend)
+  }
+}
+
+impl Intersectable for SdfBody {
+  // Marching stops at the first surface and the exit behind it
+  fn max_intersections(&self) -> usize {
+    2
+  }
+
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    // The surface lies within the bounds, so marching starts where the ray
+    // enters them instead of at its origin, however far away that is
+    let (enter, leave) = match self.bounds_in_object_space().span(object_space_ray) {
+      Some(span) => span,
+      None => return vec![],
+    };
+    if leave < 0.0 {
+      return vec![];
+    }
+
+    // Like for every other body the entry and the exit are reported, so the
+    // refractive indices of transparent bodies are tracked correctly
+    let (entry, exit) = if self.distance(object_space_ray.origin) < 0.0 {
+      // Rays starting inside find the entry behind their origin
+      let backwards = Ray {
+        direction: -object_space_ray.direction,
+        ..object_space_ray
+      };
+      (
+        self.march(backwards, 0.0, -enter).map(|t| -t),
+        self.march(object_space_ray, 0.0, leave),
+      )
+    } else {
+      let entry = match self.march(object_space_ray, enter.max(0.0), leave) {
+        Some(t) => t,
+        None => return vec![],
+      };
+      // Rays only grazing the surface enter and leave at the same point
+      let exit = self
+        .step_inside(object_space_ray, entry)
+        .and_then(|inside| self.march(object_space_ray, inside, leave))
+        .unwrap_or(entry);
+      (Some(entry), Some(exit))
+    };
+
+    let body = Body::from(self.clone());
+    entry
+      .into_iter()
+      .chain(exit)
+      .map(|t| (t, body.clone()))
+      .collect()
+  }
+
+  /// Gradient of the distance function using central differences
+  fn normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    let h = NORMAL_DELTA;
+    let dx = Tuple::vector(h, 0.0, 0.0);
+    let dy = Tuple::vector(0.0, h, 0.0);
+    let dz = Tuple::vector(0.0, 0.0, h);
+    Tuple::vector(
+      self.distance(object_space_point + dx) - self.distance(object_space_point - dx),
+      self.distance(object_space_point + dy) - self.distance(object_space_point - dy),
+      self.distance(object_space_point + dz) - self.distance(object_space_point - dz),
+    )
+    .normalize()
+  }
+
+  fn material(&self) -> Material {
+    self.material.clone()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.shadows
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    let bounds = self.shape.bounds();
+    match self.blend {
+      None => bounds,
+      Some(blend) => {
+        // The smooth transition may bulge out a little beyond both shapes
+        let padding = Tuple::vector(blend.smoothness, blend.smoothness, blend.smoothness);
+        let blend_bounds = blend.shape.bounds();
+        bounds
+          .union(Bounds::new(
+            blend_bounds.min + blend.offset,
+            blend_bounds.max + blend.offset,
+          ))
+          .union(Bounds::new(bounds.min - padding, bounds.max + padding))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::test_scene::glass;
+
+  #[test]
+  fn distances_of_built_in_shapes() {
+    let sphere = Sdf::Sphere { radius: 1.0 };
+    let cube = Sdf::Box {
+      size: Tuple::vector(1.0, 1.0, 1.0),
+    };
+    let torus = Sdf::Torus {
+      major_radius: 2.0,
+      minor_radius: 0.5,
+    };
+
+    assert_fuzzy_eq!(sphere.distance(Tuple::point(0.0, 3.0, 0.0)), 2.0);
+    assert_fuzzy_eq!(sphere.distance(Tuple::point(0.0, 0.0, 0.0)), -1.0);
+    assert_fuzzy_eq!(cube.distance(Tuple::point(3.0, 0.5, 0.0)), 2.0);
+    assert_fuzzy_eq!(cube.distance(Tuple::point(2.0, 2.0, 1.0)), 2.0_f64.sqrt());
+    assert_fuzzy_eq!(cube.distance(Tuple::point(0.5, 0.0, 0.0)), -0.5);
+    assert_fuzzy_eq!(torus.distance(Tuple::point(2.0, 0.0, 0.0)), -0.5);
+    assert_fuzzy_eq!(torus.distance(Tuple::point(0.0, 1.0, 2.0)), 0.5);
+  }
+
+  #[test]
+  fn a_ray_marches_onto_a_sphere() {
+    let body = SdfBody::new(Sdf::Sphere { radius: 1.0 });
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = body.intersect_in_object_space(r);
+
+    assert_eq!(xs.len(), 2);
+    assert!((xs[0].0 - 4.0).abs() < 0.001);
+    assert!((xs[1].0 - 6.0).abs() < 0.001);
+    assert_eq!(xs[0].1, Body::from(body));
+  }
+
+  #[test]
+  fn a_ray_inside_finds_the_exit() {
+    let body = SdfBody::new(Sdf::Sphere { radius: 1.0 });
+    let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 2.0));
+    let xs = body.intersect_in_object_space(r);
+
+    // The entry lies behind the origin of the ray
+    assert_eq!(xs.len(), 2);
+    assert!((xs[0].0 + 0.5).abs() < 0.001);
+    assert!((xs[1].0 - 0.5).abs() < 0.001);
+  }
+
+  #[test]
+  fn transparent_shapes_are_entered_and_left() {
+    let body =
+      Body::from(SdfBody::new(Sdf::Sphere { radius: 1.0 }).with_material(Material::from(glass())));
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = body.intersect(r);
+
+    assert_eq!(xs.refractive_indices(&xs[0]), (1.0, 1.5));
+    assert_eq!(xs.refractive_indices(&xs[1]), (1.5, 1.0));
+  }
+
+  #[test]
+  fn far_away_rays_march_from_the_bounds() {
+    let body = SdfBody::new(Sdf::Sphere { radius: 1.0 });
+    let r = Ray::new(Tuple::point(0.0, 0.0, -150.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = body.intersect_in_object_space(r);
+
+    assert_eq!(xs.len(), 2);
+    assert!((xs[0].0 - 149.0).abs() < 0.001);
+  }
+
+  #[test]
+  fn shapes_behind_the_ray_are_missed() {
+    let body = SdfBody::new(Sdf::Sphere { radius: 1.0 });
+    let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    assert_eq!(body.intersect_in_object_space(r).len(), 0);
+  }
+
+  #[test]
+  fn a_ray_misses_the_shape() {
+    let body = SdfBody::new(Sdf::Torus {
+      major_radius: 1.0,
+      minor_radius: 0.25,
+    });
+    let r = Ray::new(Tuple::point(0.0, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+    assert_eq!(body.intersect_in_object_space(r).len(), 0);
+  }
+
+  #[test]
+  fn normals_are_the_gradient_of_the_distance() {
+    let body = SdfBody::new(Sdf::Box {
+      size: Tuple::vector(1.0, 1.0, 1.0),
+    });
+
+    assert_fuzzy_eq!(
+      body.normal_at_in_object_space(Tuple::point(1.0, 0.2, 0.3)),
+      Tuple::vector(1.0, 0.0, 0.0)
+    );
+    assert_fuzzy_eq!(
+      body.normal_at_in_object_space(Tuple::point(0.1, -1.0, 0.3)),
+      Tuple::vector(0.0, -1.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn blended_shapes_merge_smoothly() {
+    let sphere = Sdf::Sphere { radius: 1.0 };
+    let union = SdfBody::new(sphere).with_blend(sphere, Tuple::vector(3.0, 0.0, 0.0), 0.0);
+    let smooth = SdfBody::new(sphere).with_blend(sphere, Tuple::vector(3.0, 0.0, 0.0), 1.0);
+    let between = Tuple::point(1.5, 0.0, 0.0);
+
+    assert_fuzzy_eq!(union.distance(between), 0.5);
+    assert_fuzzy_eq!(union.distance(Tuple::point(3.0, 0.0, 0.0)), -1.0);
+    assert!(smooth.distance(between) < union.distance(between));
+  }
+
+  #[test]
+  fn the_mandelbulb_is_hit() {
+    let body = SdfBody::new(Sdf::Mandelbulb {
+      power: 8.0,
+      iterations: 8,
+    });
+    let r = Ray::new(Tuple::point(0.0, 0.0, -3.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = body.intersect_in_object_space(r);
+
+    assert_eq!(xs.len(), 2);
+    assert!(xs[0].0 > 1.5 && xs[0].0 < 3.0);
+    assert!(xs[1].0 > 3.0 && xs[1].0 < 4.5);
+  }
+}
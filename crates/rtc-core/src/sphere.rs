@@ -1,21 +1,27 @@
 use crate::body::*;
+use crate::bounds::Bounds;
 use crate::fuzzy_eq::FuzzyEq;
 use crate::material::*;
 use crate::matrix::*;
+use crate::numerics::solve_quadratic;
 use crate::ray::*;
 use crate::tuple::*;
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Sphere {
-  pub transform: Matrix<4>,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
   pub material: Material,
+  pub shadows: Shadows,
 }
 
 impl Default for Sphere {
   fn default() -> Self {
     Self {
       transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
       material: Default::default(),
+      shadows: Default::default(),
     }
   }
 }
@@ -25,6 +31,8 @@ impl Sphere {
     Sphere {
       material,
       transform,
+      inverse_transform: transform.inverse(),
+      shadows: Default::default(),
     }
   }
 
@@ -33,32 +41,51 @@ impl Sphere {
     self
   }
 
-  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.shadows = shadows;
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
     self.transform = transform;
+    self.inverse_transform = inverse_transform;
     self
   }
 }
 
 impl FuzzyEq<&Sphere> for Sphere {
   fn fuzzy_eq(&self, other: &Sphere) -> bool {
-    self.transform.fuzzy_eq(other.transform) && self.material.fuzzy_eq(other.material)
+    self.transform.fuzzy_eq(other.transform)
+      && self.material.fuzzy_eq(other.material.clone())
+      && self.shadows == other.shadows
   }
 }
 
 impl Intersectable for Sphere {
+  fn max_intersections(&self) -> usize {
+    2
+  }
+
   fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(crate::F, Body)> {
     let sphere_to_ray = object_space_ray.origin - Tuple::point(0.0, 0.0, 0.0);
     let a = object_space_ray.direction.dot(object_space_ray.direction);
     let b = 2.0 * object_space_ray.direction.dot(sphere_to_ray);
     let c = sphere_to_ray.dot(sphere_to_ray) - 1.0;
-    let descriminant = b.powi(2) - 4.0 * a * c;
-
-    if descriminant < 0.0 {
-      vec![]
-    } else {
-      let t1 = (-b - descriminant.sqrt()) / (2.0 * a);
-      let t2 = (-b + descriminant.sqrt()) / (2.0 * a);
-      vec![(t1, Body::from(*self)), (t2, Body::from(*self))]
+
+    match solve_quadratic(a, b, c) {
+      Some((t1, t2)) => vec![
+        (t1, Body::from(self.clone())),
+        (t2, Body::from(self.clone())),
+      ],
+      None => vec![],
     }
   }
 
@@ -67,17 +94,30 @@ impl Intersectable for Sphere {
   }
 
   fn material(&self) -> Material {
-    self.material
+    self.material.clone()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.shadows
   }
 
   fn transform(&self) -> Matrix<4> {
     self.transform
   }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    Bounds::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0))
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::angle::Angle;
   use crate::canvas::Color;
   use crate::F;
   use std::f64::consts::PI;
@@ -94,6 +134,25 @@ mod tests {
     assert_fuzzy_eq!(6.0, xs[1].t);
   }
 
+  #[test]
+  fn rays_hit_spheres_precisely_at_extreme_scales() {
+    // A planet seen from just above the ground, and one far away
+    let planet = Sphere::default().with_transform(Matrix::scaling(1e7, 1e7, 1e7));
+    let down = Ray::new(
+      Tuple::point(0.0, 1e7 + 0.5, 0.0),
+      Tuple::vector(0.0, -1.0, 0.0),
+    );
+    let ahead = Ray::new(Tuple::point(0.0, 0.0, -1e9), Tuple::vector(0.0, 0.0, 1.0));
+
+    let xs = planet.intersect(down);
+    assert_fuzzy_eq!(xs[0].t, 0.5);
+    assert_fuzzy_eq!(xs[1].t, 2e7 + 0.5);
+
+    let xs = planet.intersect(ahead);
+    assert_fuzzy_eq!(xs[0].t, 1e9 - 1e7);
+    assert_fuzzy_eq!(xs[1].t, 1e9 + 1e7);
+  }
+
   #[test]
   fn a_ray_intersects_a_sphere_at_a_tangent() {
     let r = Ray::new(Tuple::point(0.0, 1.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
@@ -143,16 +202,27 @@ mod tests {
   #[test]
   fn a_spheres_default_transform() {
     let s = Sphere::default();
-    assert_fuzzy_eq!(s.transform, Matrix::identity());
+    assert_fuzzy_eq!(s.transform(), Matrix::identity());
   }
 
   #[test]
   fn changing_a_spheres_transform() {
-    let mut s = Sphere::default();
     let m = Matrix::translation(2.0, 3.0, 4.0);
-    s.transform = m;
+    let s = Sphere::default().with_transform(m);
+
+    assert_fuzzy_eq!(s.transform(), m);
+  }
+
+  #[test]
+  fn spheres_cache_the_inverse_of_their_transform() {
+    let m = Matrix::translation(2.0, 3.0, 4.0);
+    let s = Body::from(Sphere::new(Material::default(), m));
 
-    assert_fuzzy_eq!(s.transform, m);
+    assert_fuzzy_eq!(s.inverse_transform(), m.inverse());
+    assert_fuzzy_eq!(
+      s.with_transform(Matrix::identity()).inverse_transform(),
+      Matrix::identity()
+    );
   }
 
   #[test]
@@ -220,6 +290,7 @@ mod tests {
   }
 
   #[test]
+  #[allow(clippy::approx_constant)]
   fn computing_the_normal_on_a_translated_sphere() {
     let s = Sphere::default().with_transform(Matrix::translation(0.0, 1.0, 0.0));
     let p = Tuple::point(0.0, 1.70711, -0.70711);
@@ -232,8 +303,9 @@ mod tests {
 
   #[test]
   fn computing_the_normal_on_a_scaled_and_rotated_sphere() {
-    let s = Sphere::default()
-      .with_transform(Matrix::scaling(1.0, 0.5, 1.0) * Matrix::rotation_z(PI / 5.0));
+    let s = Sphere::default().with_transform(
+      Matrix::scaling(1.0, 0.5, 1.0) * Matrix::rotation_z(Angle::radians(PI / 5.0)),
+    );
     let sqrt2_over_2 = (2.0 as F).sqrt() / 2.0;
     let p = Tuple::point(0.0, sqrt2_over_2, -sqrt2_over_2);
     let n = s.normal_at(p);
@@ -255,8 +327,9 @@ mod tests {
 
   #[test]
   fn the_normal_vector_is_normalized_on_transformed_sphere() {
-    let s = Sphere::default()
-      .with_transform(Matrix::scaling(1.0, 0.5, 1.0) * Matrix::rotation_z(PI / 5.0));
+    let s = Sphere::default().with_transform(
+      Matrix::scaling(1.0, 0.5, 1.0) * Matrix::rotation_z(Angle::radians(PI / 5.0)),
+    );
     let sqrt2_over_2 = (2.0 as F).sqrt() / 2.0;
     let p = Tuple::point(0.0, sqrt2_over_2, -sqrt2_over_2);
     let n = s.normal_at(p);
@@ -281,7 +354,7 @@ mod tests {
       .with_specular(0.95)
       .with_shininess(400.0);
     let m = Material::from(phong);
-    let s = Sphere::default().with_material(m);
+    let s = Sphere::default().with_material(m.clone());
 
     assert_fuzzy_eq!(s.material, m);
   }
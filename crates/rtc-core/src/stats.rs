@@ -0,0 +1,158 @@
+use crate::body::Body;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Kind of a body, as counted by the statistics
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShapeKind {
+  Sphere,
+  Plane,
+  Quad,
+  Cylinder,
+  Cone,
+  Triangle,
+  SmoothTriangle,
+  Sdf,
+  Repeated,
+  Mirrored,
+  Mesh,
+}
+
+impl ShapeKind {
+  pub const ALL: [ShapeKind; 11] = [
+    ShapeKind::Sphere,
+    ShapeKind::Plane,
+    ShapeKind::Quad,
+    ShapeKind::Cylinder,
+    ShapeKind::Cone,
+    ShapeKind::Triangle,
+    ShapeKind::SmoothTriangle,
+    ShapeKind::Sdf,
+    ShapeKind::Repeated,
+    ShapeKind::Mirrored,
+    ShapeKind::Mesh,
+  ];
+
+  pub fn of(body: &Body) -> Self {
+    match *body {
+      Body::Sphere(_) => ShapeKind::Sphere,
+      Body::Plane(_) => ShapeKind::Plane,
+      Body::Quad(_) => ShapeKind::Quad,
+      Body::Cylinder(_) => ShapeKind::Cylinder,
+      Body::Cone(_) => ShapeKind::Cone,
+      Body::Triangle(_) => ShapeKind::Triangle,
+      Body::SmoothTriangle(_) => ShapeKind::SmoothTriangle,
+      Body::Sdf(_) => ShapeKind::Sdf,
+      Body::Repeated(_) => ShapeKind::Repeated,
+      Body::Mirrored(_) => ShapeKind::Mirrored,
+      Body::Mesh(_) => ShapeKind::Mesh,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      ShapeKind::Sphere => "sphere",
+      ShapeKind::Plane => "plane",
+      ShapeKind::Quad => "quad",
+      ShapeKind::Cylinder => "cylinder",
+      ShapeKind::Cone => "cone",
+      ShapeKind::Triangle => "triangle",
+      ShapeKind::SmoothTriangle => "smooth_triangle",
+      ShapeKind::Sdf => "sdf",
+      ShapeKind::Repeated => "repeated",
+      ShapeKind::Mirrored => "mirrored",
+      ShapeKind::Mesh => "mesh",
+    }
+  }
+
+  fn index(&self) -> usize {
+    *self as usize
+  }
+}
+
+/// Exact intersection tests of one kind of shape. Rays already rejected by
+/// the bounds of a body are not tested, so many misses point to bounds
+/// fitting their bodies badly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShapeCounts {
+  pub hits: usize,
+  pub misses: usize,
+}
+
+impl ShapeCounts {
+  pub fn tests(&self) -> usize {
+    self.hits + self.misses
+  }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static HITS: [AtomicUsize; ShapeKind::ALL.len()] =
+  [const { AtomicUsize::new(0) }; ShapeKind::ALL.len()];
+static MISSES: [AtomicUsize; ShapeKind::ALL.len()] =
+  [const { AtomicUsize::new(0) }; ShapeKind::ALL.len()];
+
+/// Starts or stops counting. Counting is shared between all threads, so it
+/// is off by default to keep it from slowing down renders.
+pub fn enable(enabled: bool) {
+  ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Sets all counters back to zero
+pub fn reset() {
+  for counter in HITS.iter().chain(MISSES.iter()) {
+    counter.store(0, Ordering::Relaxed);
+  }
+}
+
+/// Counts of every kind of shape tested at least once since the last reset
+pub fn shape_counts() -> Vec<(ShapeKind, ShapeCounts)> {
+  ShapeKind::ALL
+    .iter()
+    .map(|kind| {
+      let counts = ShapeCounts {
+        hits: HITS[kind.index()].load(Ordering::Relaxed),
+        misses: MISSES[kind.index()].load(Ordering::Relaxed),
+      };
+      (*kind, counts)
+    })
+    .filter(|(_, counts)| counts.tests() > 0)
+    .collect()
+}
+
+/// Counts an exact intersection test of the body
+pub(crate) fn count_test(body: &Body, hit: bool) {
+  if !ENABLED.load(Ordering::Relaxed) {
+    return;
+  }
+  let counters = if hit { &HITS } else { &MISSES };
+  counters[ShapeKind::of(body).index()].fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::body::Intersectable;
+  use crate::ray::Ray;
+  use crate::sphere::Sphere;
+  use crate::tuple::Tuple;
+
+  #[test]
+  fn exact_tests_are_counted_per_shape() {
+    let sphere = Body::from(Sphere::default());
+    let hit = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    // Passes the corner of the bounding box, but misses the sphere itself
+    let miss = Ray::new(Tuple::point(0.95, 0.95, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    enable(true);
+    reset();
+    sphere.intersect(hit);
+    sphere.intersect(miss);
+    let counts = shape_counts();
+    enable(false);
+
+    // Other tests may intersect spheres at the same time
+    let (kind, spheres) = counts[0];
+    assert_eq!(kind, ShapeKind::Sphere);
+    assert!(spheres.hits >= 1);
+    assert!(spheres.misses >= 1);
+  }
+}
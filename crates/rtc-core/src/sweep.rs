@@ -0,0 +1,294 @@
+use crate::angle::Angle;
+use crate::body::Intersectable;
+use crate::matrix::Matrix;
+use crate::tuple::Tuple;
+use crate::world::World;
+use crate::{EPSILON, F};
+
+/// Axis of the world or of a body
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Axis {
+  X,
+  Y,
+  Z,
+}
+
+/// Part of the transformation of a body changed by a sweep
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SweepProperty {
+  /// Moves the body along the axis of the world
+  Translate(Axis),
+  /// Rotates the body around its own axis, in degrees
+  Rotate(Axis),
+  /// Scales the body around its own origin, uniformly without an axis
+  Scale(Option<Axis>),
+  /// Moves the body like a thrown ball, the swept value is the time in
+  /// seconds
+  Kinematics(Kinematics),
+}
+
+/// Flight of a body under gravity, bouncing off the ground at y = 0.
+///
+/// Each bounce keeps `restitution` of the speed the body hit the ground
+/// with, until it comes to rest. The speed along the ground is kept.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Kinematics {
+  pub velocity: Tuple,
+  pub gravity: Tuple,
+  pub restitution: F,
+}
+
+impl Kinematics {
+  pub fn new(velocity: Tuple) -> Self {
+    Self {
+      velocity,
+      gravity: Tuple::vector(0.0, -9.81, 0.0),
+      restitution: 0.7,
+    }
+  }
+
+  pub fn with_gravity(mut self, gravity: Tuple) -> Self {
+    self.gravity = gravity;
+    self
+  }
+
+  pub fn with_restitution(mut self, restitution: F) -> Self {
+    self.restitution = restitution;
+    self
+  }
+
+  /// Distance moved after the given time by a body starting at the given
+  /// height above the ground
+  pub fn offset_at(&self, height: F, time: F) -> Tuple {
+    let (velocity, gravity) = (self.velocity, self.gravity);
+    let along = |start: F, speed: F, acceleration: F, time: F| {
+      start + speed * time + 0.5 * acceleration * time * time
+    };
+    let x = along(0.0, velocity.x, gravity.x, time);
+    let z = along(0.0, velocity.z, gravity.z, time);
+
+    let (mut y, mut speed, mut remaining) = (height, velocity.y, time);
+    loop {
+      // Bodies starting below the ground, or pulled away from it, fly freely
+      if gravity.y >= 0.0 || y < 0.0 {
+        y = along(y, speed, gravity.y, remaining);
+        break;
+      }
+      let landing = (-speed - (speed * speed - 2.0 * gravity.y * y).sqrt()) / gravity.y;
+      if landing >= remaining {
+        y = along(y, speed, gravity.y, remaining);
+        break;
+      }
+      remaining -= landing;
+      speed = -(speed + gravity.y * landing) * self.restitution;
+      y = 0.0;
+      if speed < EPSILON {
+        break;
+      }
+    }
+
+    Tuple::vector(x, y - height, z)
+  }
+
+  /// Transformation of a body after the given time, with its lowest point
+  /// starting at the given height above the ground
+  pub fn transform(&self, transform: Matrix<4>, height: F, time: F) -> Matrix<4> {
+    let offset = self.offset_at(height, time);
+    Matrix::translation(offset.x, offset.y, offset.z) * transform
+  }
+}
+
+/// Linear change of a property of the bodies with the given name, from one
+/// value at the first frame of an animation to another at the last one.
+///
+/// Sweeps are applied on top of the transformation the bodies already have,
+/// so basic motion needs no keyframes or scripts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sweep {
+  pub body: String,
+  pub property: SweepProperty,
+  pub from: F,
+  pub to: F,
+}
+
+impl Sweep {
+  pub fn new(body: impl Into<String>, property: SweepProperty, from: F, to: F) -> Self {
+    Self {
+      body: body.into(),
+      property,
+      from,
+      to,
+    }
+  }
+
+  /// The swept value at `t` between 0 (first frame) and 1 (last frame)
+  pub fn value_at(&self, t: F) -> F {
+    self.from + (self.to - self.from) * t
+  }
+
+  /// Transformation of the property at `t`, combined with the one of a body.
+  /// Kinematics bounce when the origin of the body reaches the ground.
+  pub fn transform(&self, transform: Matrix<4>, t: F) -> Matrix<4> {
+    let height = (transform * Tuple::point(0.0, 0.0, 0.0)).y;
+    self.transform_above(transform, height, t)
+  }
+
+  fn transform_above(&self, transform: Matrix<4>, height: F, t: F) -> Matrix<4> {
+    let value = self.value_at(t);
+    let along = |axis: Axis, default: F| match axis {
+      Axis::X => [value, default, default],
+      Axis::Y => [default, value, default],
+      Axis::Z => [default, default, value],
+    };
+    match self.property {
+      SweepProperty::Translate(axis) => {
+        let [x, y, z] = along(axis, 0.0);
+        Matrix::translation(x, y, z) * transform
+      }
+      SweepProperty::Rotate(axis) => {
+        let angle = Angle::degrees(value);
+        let rotation = match axis {
+          Axis::X => Matrix::rotation_x(angle),
+          Axis::Y => Matrix::rotation_y(angle),
+          Axis::Z => Matrix::rotation_z(angle),
+        };
+        transform * rotation
+      }
+      SweepProperty::Scale(axis) => {
+        let [x, y, z] = match axis {
+          Some(axis) => along(axis, 1.0),
+          None => [value, value, value],
+        };
+        transform * Matrix::scaling(x, y, z)
+      }
+      SweepProperty::Kinematics(kinematics) => kinematics.transform(transform, height, value),
+    }
+  }
+
+  /// Moves the bodies of the world carrying the name of the sweep. Kinematics
+  /// move them together, bouncing when their lowest point reaches the
+  /// ground.
+  pub fn apply(&self, world: &mut World, t: F) {
+    let mut bodies = world.bodies_named_mut(&self.body);
+    let bottom = bodies
+      .iter()
+      .map(|body| body.bounds())
+      .filter(|bounds| !bounds.is_infinite())
+      .map(|bounds| bounds.min.y)
+      .reduce(F::min);
+    for body in bodies.iter_mut() {
+      let transform = body.transform();
+      let height = bottom.unwrap_or((transform * Tuple::point(0.0, 0.0, 0.0)).y);
+      **body = body
+        .clone()
+        .with_transform(self.transform_above(transform, height, t));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::body::Body;
+  use crate::fuzzy_eq::FuzzyEq;
+  use crate::sphere::Sphere;
+  use crate::tuple::Tuple;
+
+  #[test]
+  fn values_are_interpolated_from_the_first_to_the_last_frame() {
+    let sweep = Sweep::new("ball", SweepProperty::Translate(Axis::Y), 1.0, 3.0);
+
+    assert_fuzzy_eq!(sweep.value_at(0.0), 1.0);
+    assert_fuzzy_eq!(sweep.value_at(0.25), 1.5);
+    assert_fuzzy_eq!(sweep.value_at(1.0), 3.0);
+  }
+
+  #[test]
+  fn translations_move_along_the_world_and_rotations_around_the_body() {
+    let placed = Matrix::translation(5.0, 0.0, 0.0);
+    let translate = Sweep::new("ball", SweepProperty::Translate(Axis::Y), 0.0, 2.0);
+    let rotate = Sweep::new("ball", SweepProperty::Rotate(Axis::Z), 0.0, 180.0);
+    let scale = Sweep::new("ball", SweepProperty::Scale(None), 1.0, 3.0);
+    let origin = Tuple::point(0.0, 0.0, 0.0);
+    let corner = Tuple::point(1.0, 0.0, 0.0);
+
+    assert_fuzzy_eq!(
+      translate.transform(placed, 0.5) * origin,
+      Tuple::point(5.0, 1.0, 0.0)
+    );
+    assert_fuzzy_eq!(
+      rotate.transform(placed, 1.0) * corner,
+      Tuple::point(4.0, 0.0, 0.0)
+    );
+    assert_fuzzy_eq!(
+      scale.transform(placed, 1.0) * corner,
+      Tuple::point(8.0, 0.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn thrown_bodies_bounce_off_the_ground() {
+    let kinematics = Kinematics::new(Tuple::vector(2.0, 0.0, 0.0)).with_restitution(0.5);
+
+    // Dropped from a height reached in one second of falling, and back at
+    // the top of the first bounce half a second later
+    let height = 0.5 * 9.81;
+    assert_fuzzy_eq!(
+      kinematics.offset_at(height, 1.0),
+      Tuple::vector(2.0, -height, 0.0)
+    );
+    assert_fuzzy_eq!(
+      kinematics.offset_at(height, 1.5),
+      Tuple::vector(3.0, height / 4.0 - height, 0.0)
+    );
+  }
+
+  #[test]
+  fn bodies_without_bounce_come_to_rest() {
+    let kinematics = Kinematics::new(Tuple::vector(0.0, 1.0, 0.0)).with_restitution(0.0);
+
+    assert_fuzzy_eq!(
+      kinematics.offset_at(2.0, 10.0),
+      Tuple::vector(0.0, -2.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn kinematics_bounce_at_the_lowest_point_of_the_bodies() {
+    let mut world = World::new(
+      vec![Body::from(
+        Sphere::default().with_transform(Matrix::translation(0.0, 5.0, 0.0)),
+      )],
+      vec![],
+    )
+    .with_name("ball", vec![0]);
+    let kinematics =
+      Kinematics::new(Tuple::vector(0.0, 0.0, 0.0)).with_gravity(Tuple::vector(0.0, -8.0, 0.0));
+    let sweep = Sweep::new("ball", SweepProperty::Kinematics(kinematics), 0.0, 1.0);
+
+    sweep.apply(&mut world, 1.0);
+
+    assert_fuzzy_eq!(
+      world.bodies()[0].transform(),
+      Matrix::translation(0.0, 1.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn only_bodies_with_the_name_are_swept() {
+    let mut world = World::new(
+      vec![Body::from(Sphere::default()), Body::from(Sphere::default())],
+      vec![],
+    )
+    .with_name("ball", vec![1]);
+    let sweep = Sweep::new("ball", SweepProperty::Translate(Axis::X), 0.0, 4.0);
+
+    sweep.apply(&mut world, 1.0);
+
+    assert_fuzzy_eq!(world.bodies()[0].transform(), Matrix::identity());
+    assert_fuzzy_eq!(
+      world.bodies()[1].transform(),
+      Matrix::translation(4.0, 0.0, 0.0)
+    );
+  }
+}
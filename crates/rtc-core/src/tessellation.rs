@@ -0,0 +1,409 @@
+use crate::body::{Body, Intersectable};
+use crate::fuzzy_eq::FuzzyEq;
+use crate::mesh::{Face, Mesh};
+use crate::sdf::Sdf;
+use crate::triangle::{SmoothTriangle, Triangle};
+use crate::tuple::Tuple;
+use crate::world::World;
+use crate::F;
+use std::f64::consts::PI;
+
+/// Point on a surface together with its normal
+type Vertex = (Tuple, Tuple);
+
+/// Triangle meshes approximating the analytic bodies, e.g. to export a world
+/// to other tools like Blender.
+///
+/// Curved surfaces are split into `segments` around their axis, spheres and
+/// tori into half as many rings in the other direction. Curved faces are
+/// smooth triangles with the exact normals of the body, caps and quads are
+/// flat. Corners are ordered counter-clockwise seen from outside, as OBJ
+/// expects it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tessellation {
+  pub segments: usize,
+}
+
+impl Default for Tessellation {
+  fn default() -> Self {
+    Self { segments: 32 }
+  }
+}
+
+impl Tessellation {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_segments(mut self, segments: usize) -> Self {
+    self.segments = segments;
+    self
+  }
+
+  /// Mesh with the material and transformation of the body, or `None` for
+  /// bodies without a finite surface, like planes or infinite cylinders, and
+  /// signed distance shapes other than a plain torus
+  pub fn body(&self, body: &Body) -> Option<Mesh> {
+    let segments = self.segments.max(3);
+    let faces = match body {
+      Body::Sphere(_) => sphere(segments),
+      Body::Cylinder(cylinder) => {
+        if cylinder.minimum.is_infinite() || cylinder.maximum.is_infinite() {
+          return None;
+        }
+        cylinder_faces(
+          segments,
+          cylinder.minimum,
+          cylinder.maximum,
+          cylinder.closed,
+        )
+      }
+      Body::Cone(cone) => {
+        if cone.minimum.is_infinite() || cone.maximum.is_infinite() {
+          return None;
+        }
+        cone_faces(segments, cone.minimum, cone.maximum, cone.closed)
+      }
+      Body::Sdf(sdf_body) => match (sdf_body.shape, sdf_body.blend) {
+        (
+          Sdf::Torus {
+            major_radius,
+            minor_radius,
+          },
+          None,
+        ) => torus(segments, major_radius, minor_radius),
+        _ => return None,
+      },
+      Body::Quad(quad) => {
+        let (x, z) = (quad.width / 2.0, quad.height / 2.0);
+        let corners = [
+          Tuple::point(-x, 0.0, -z),
+          Tuple::point(x, 0.0, -z),
+          Tuple::point(x, 0.0, z),
+          Tuple::point(-x, 0.0, z),
+        ];
+        vec![
+          Face::from(Triangle::new(corners[0], corners[2], corners[1])),
+          Face::from(Triangle::new(corners[0], corners[3], corners[2])),
+        ]
+      }
+      Body::Triangle(triangle) => vec![Face::from(triangle.clone())],
+      Body::SmoothTriangle(smooth_triangle) => vec![Face::from(smooth_triangle.clone())],
+      Body::Mesh(mesh_body) => mesh_body
+        .triangles
+        .iter()
+        .filter_map(|triangle| match triangle {
+          Body::Triangle(triangle) => Some(Face::from(triangle.clone())),
+          Body::SmoothTriangle(smooth_triangle) => Some(Face::from(smooth_triangle.clone())),
+          _ => None,
+        })
+        .collect(),
+      Body::Plane(_) | Body::Repeated(_) | Body::Mirrored(_) => return None,
+    };
+    Some(
+      Mesh::new(faces)
+        .with_material(body.material())
+        .with_transform(body.transform()),
+    )
+  }
+
+  /// Meshes of all bodies of the world, including moving bodies at the
+  /// start of their motion and the bodies of instances as placed by them.
+  /// The number of bodies which could not be tessellated is returned as
+  /// well.
+  pub fn world(&self, world: &World) -> (Vec<Mesh>, usize) {
+    let bodies = world
+      .bodies()
+      .iter()
+      .cloned()
+      .chain(
+        world
+          .moving_bodies()
+          .iter()
+          .map(|moving_body| moving_body.body().clone()),
+      )
+      .chain(world.instances().iter().flat_map(|instance| {
+        instance
+          .geometry
+          .bodies()
+          .iter()
+          .map(move |body| instance.place(body.clone()))
+      }));
+
+    let mut meshes = vec![];
+    let mut skipped = 0;
+    for body in bodies {
+      match self.body(&body) {
+        Some(mesh) => meshes.push(mesh),
+        None => skipped += 1,
+      }
+    }
+    (meshes, skipped)
+  }
+}
+
+/// Angle of the given segment around the axis
+fn angle(segment: usize, segments: usize) -> F {
+  2.0 * PI * segment as F / segments as F
+}
+
+/// Triangles between two rings of vertices, with the first one above the
+/// second one when seen from outside. Triangles collapsing into a pole are
+/// left out.
+fn band(upper: &[Vertex], lower: &[Vertex]) -> Vec<Face> {
+  let mut faces = vec![];
+  for index in 0..upper.len() {
+    let next = (index + 1) % upper.len();
+    let (a, b) = (upper[index], upper[next]);
+    let (c, d) = (lower[index], lower[next]);
+    if !a.0.fuzzy_eq(b.0) {
+      faces.push(Face::from(SmoothTriangle::new(
+        a.0, b.0, c.0, a.1, b.1, c.1,
+      )));
+    }
+    if !c.0.fuzzy_eq(d.0) {
+      faces.push(Face::from(SmoothTriangle::new(
+        b.0, d.0, c.0, b.1, d.1, c.1,
+      )));
+    }
+  }
+  faces
+}
+
+/// Flat disk around the y axis at the given height, facing up or down
+fn cap(segments: usize, height: F, radius: F, up: bool) -> Vec<Face> {
+  if radius.fuzzy_eq(0.0) {
+    return vec![];
+  }
+  let center = Tuple::point(0.0, height, 0.0);
+  let rim = |segment: usize| {
+    let angle = angle(segment, segments);
+    Tuple::point(radius * angle.cos(), height, radius * angle.sin())
+  };
+  (0..segments)
+    .map(|segment| {
+      let (current, next) = (rim(segment), rim(segment + 1));
+      if up {
+        Face::from(Triangle::new(center, next, current))
+      } else {
+        Face::from(Triangle::new(center, current, next))
+      }
+    })
+    .collect()
+}
+
+fn sphere(segments: usize) -> Vec<Face> {
+  let rings = (segments / 2).max(2);
+  let ring = |ring: usize| -> Vec<Vertex> {
+    let polar = PI * ring as F / rings as F;
+    (0..segments)
+      .map(|segment| {
+        let angle = angle(segment, segments);
+        let normal = Tuple::vector(
+          polar.sin() * angle.cos(),
+          polar.cos(),
+          polar.sin() * angle.sin(),
+        );
+        (Tuple::point(normal.x, normal.y, normal.z), normal)
+      })
+      .collect()
+  };
+  (0..rings)
+    .flat_map(|index| band(&ring(index), &ring(index + 1)))
+    .collect()
+}
+
+fn cylinder_faces(segments: usize, minimum: F, maximum: F, closed: bool) -> Vec<Face> {
+  let ring = |height: F| -> Vec<Vertex> {
+    (0..segments)
+      .map(|segment| {
+        let angle = angle(segment, segments);
+        let (x, z) = (angle.cos(), angle.sin());
+        (Tuple::point(x, height, z), Tuple::vector(x, 0.0, z))
+      })
+      .collect()
+  };
+  let mut faces = band(&ring(maximum), &ring(minimum));
+  if closed {
+    faces.extend(cap(segments, maximum, 1.0, true));
+    faces.extend(cap(segments, minimum, 1.0, false));
+  }
+  faces
+}
+
+fn cone_faces(segments: usize, minimum: F, maximum: F, closed: bool) -> Vec<Face> {
+  // The normal only changes around the axis, and between the two halves
+  let ring = |height: F, above_apex: bool| -> Vec<Vertex> {
+    (0..segments)
+      .map(|segment| {
+        let angle = angle(segment, segments);
+        let (x, z) = (angle.cos(), angle.sin());
+        let radius = height.abs();
+        let slope = if above_apex { -1.0 } else { 1.0 };
+        (
+          Tuple::point(radius * x, height, radius * z),
+          Tuple::vector(x, slope, z).normalize(),
+        )
+      })
+      .collect()
+  };
+  let mut faces = vec![];
+  if maximum > 0.0 {
+    let bottom = minimum.max(0.0);
+    faces.extend(band(&ring(maximum, true), &ring(bottom, true)));
+  }
+  if minimum < 0.0 {
+    let top = maximum.min(0.0);
+    faces.extend(band(&ring(top, false), &ring(minimum, false)));
+  }
+  if closed {
+    faces.extend(cap(segments, maximum, maximum.abs(), true));
+    faces.extend(cap(segments, minimum, minimum.abs(), false));
+  }
+  faces
+}
+
+fn torus(segments: usize, major_radius: F, minor_radius: F) -> Vec<Face> {
+  let rings = (segments / 2).max(3);
+  // Rings around the tube, going up on its outside
+  let ring = |ring: usize| -> Vec<Vertex> {
+    let around_tube = 2.0 * PI * ring as F / rings as F;
+    (0..segments)
+      .map(|segment| {
+        let angle = angle(segment, segments);
+        let normal = Tuple::vector(
+          around_tube.cos() * angle.cos(),
+          around_tube.sin(),
+          around_tube.cos() * angle.sin(),
+        );
+        let center = Tuple::point(major_radius * angle.cos(), 0.0, major_radius * angle.sin());
+        (center + normal * minor_radius, normal)
+      })
+      .collect()
+  };
+  (0..rings)
+    .flat_map(|index| band(&ring(index + 1), &ring(index)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::cone::Cone;
+  use crate::cylinder::Cylinder;
+  use crate::matrix::Matrix;
+  use crate::plane::Plane;
+  use crate::sdf::SdfBody;
+  use crate::sphere::Sphere;
+
+  /// Corners of a face and the normal given by their order
+  fn corners(face: &Face) -> ([Tuple; 3], Tuple) {
+    let triangle = match face {
+      Face::Flat(triangle) => triangle,
+      Face::Smooth(smooth_triangle) => &smooth_triangle.triangle,
+    };
+    let (p1, p2, p3) = (triangle.p1, triangle.p2, triangle.p3);
+    ([p1, p2, p3], (p2 - p1).cross(p3 - p1))
+  }
+
+  #[test]
+  fn spheres_are_closed_at_the_poles() {
+    let mesh = Tessellation::new()
+      .with_segments(8)
+      .body(&Body::from(Sphere::default()))
+      .unwrap();
+
+    // One triangle per segment at each pole, two in between
+    assert_eq!(mesh.faces.len(), 8 + 8 * 2 * 2 + 8);
+    for face in mesh.faces.iter() {
+      let (points, _) = corners(face);
+      for point in points.iter() {
+        assert_fuzzy_eq!(Tuple::vector(point.x, point.y, point.z).magnitude(), 1.0);
+      }
+    }
+  }
+
+  #[test]
+  fn faces_are_ordered_counter_clockwise_seen_from_outside() {
+    let bodies = [
+      Body::from(Sphere::default()),
+      Body::from(
+        Cylinder::default()
+          .with_minimum(-1.0)
+          .with_maximum(2.0)
+          .with_closed(true),
+      ),
+      Body::from(
+        Cone::default()
+          .with_minimum(-1.0)
+          .with_maximum(2.0)
+          .with_closed(true),
+      ),
+      Body::from(SdfBody::new(Sdf::Torus {
+        major_radius: 2.0,
+        minor_radius: 0.5,
+      })),
+    ];
+
+    for body in bodies.iter() {
+      let mesh = Tessellation::new().with_segments(12).body(body).unwrap();
+      for face in mesh.faces.iter() {
+        let (points, normal) = corners(face);
+        let normals = match face {
+          Face::Flat(_) => {
+            let center = Tuple::point(
+              (points[0].x + points[1].x + points[2].x) / 3.0,
+              (points[0].y + points[1].y + points[2].y) / 3.0,
+              (points[0].z + points[1].z + points[2].z) / 3.0,
+            );
+            [body.normal_at(center); 3]
+          }
+          Face::Smooth(smooth_triangle) => {
+            [smooth_triangle.n1, smooth_triangle.n2, smooth_triangle.n3]
+          }
+        };
+        for vertex_normal in normals.iter() {
+          assert!(normal.dot(*vertex_normal) > 0.0, "{:?}", body);
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn torus_vertices_lie_on_its_surface() {
+    let shape = Sdf::Torus {
+      major_radius: 2.0,
+      minor_radius: 0.5,
+    };
+    let mesh = Tessellation::new()
+      .body(&Body::from(SdfBody::new(shape)))
+      .unwrap();
+
+    assert_eq!(mesh.faces.len(), 32 * 16 * 2);
+    for face in mesh.faces.iter() {
+      let (points, _) = corners(face);
+      assert!(points
+        .iter()
+        .all(|point| shape.distance(*point).abs() < 1e-9));
+    }
+  }
+
+  #[test]
+  fn infinite_bodies_are_not_tessellated() {
+    let transform = Matrix::translation(0.0, 1.0, 0.0);
+    let world = World::new(
+      vec![
+        Body::from(Plane::default()),
+        Body::from(Cylinder::default()),
+        Body::from(Sphere::default().with_transform(transform)),
+      ],
+      vec![],
+    );
+
+    let (meshes, skipped) = Tessellation::new().world(&world);
+
+    assert_eq!(meshes.len(), 1);
+    assert_eq!(skipped, 2);
+    assert_fuzzy_eq!(meshes[0].transform, transform);
+  }
+}
@@ -0,0 +1,55 @@
+//! Shortcuts for the small worlds the tests are built around, so a test only
+//! spells out what sets it apart.
+
+use crate::angle::Angle;
+use crate::body::Body;
+use crate::camera::Camera;
+use crate::canvas::Color;
+use crate::light::{Light, PointLight};
+use crate::material::{Material, Phong};
+use crate::matrix::Matrix;
+use crate::plane::Plane;
+use crate::sphere::Sphere;
+use crate::tuple::Tuple;
+use crate::world::World;
+use crate::F;
+
+/// A half mirroring floor with a ball named "ball" standing on it, lit from
+/// the top left like the default world
+pub fn floor_and_ball() -> World {
+  let floor = Plane::default().with_material(Material::from(mirror(0.5)));
+  World::new(
+    vec![Body::from(floor), Body::from(sphere_at(0.0, 1.0, 0.0))],
+    vec![white_light_at(-10.0, 10.0, -10.0)],
+  )
+  .with_name("ball", vec![1])
+}
+
+/// Tiny camera in front of the ball of `floor_and_ball`, whose center pixel
+/// looks straight at it
+pub fn camera_facing_ball() -> Camera {
+  Camera::new(11, 11, Angle::radians(std::f64::consts::PI / 2.0)).look_at_from_position(
+    Tuple::point(0.0, 1.0, -5.0),
+    Tuple::point(0.0, 1.0, 0.0),
+    Tuple::vector(0.0, 1.0, 0.0),
+  )
+}
+
+pub fn white_light_at(x: F, y: F, z: F) -> Light {
+  PointLight::new(Tuple::point(x, y, z), Color::white()).into()
+}
+
+/// Unit sphere around the given point
+pub fn sphere_at(x: F, y: F, z: F) -> Sphere {
+  Sphere::default().with_transform(Matrix::translation(x, y, z))
+}
+
+pub fn glass() -> Phong {
+  Phong::default()
+    .with_transparency(1.0)
+    .with_refractive_index(1.5)
+}
+
+pub fn mirror(reflectiveness: F) -> Phong {
+  Phong::default().with_reflectiveness(reflectiveness)
+}
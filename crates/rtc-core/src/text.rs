@@ -0,0 +1,225 @@
+use crate::font::{is_set, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::mesh::{Face, Mesh};
+use crate::triangle::Triangle;
+use crate::tuple::Tuple;
+use crate::F;
+
+/// Text extruded into 3D, built from the pixels of the font of watermarks,
+/// e.g. for title cards and labels in demo renders.
+///
+/// Letters are one unit high and stand on the x axis, starting at the
+/// origin. Their fronts face the negative z axis and are extruded by `depth`
+/// towards positive z. Every newline starts a line further down.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Text {
+  pub text: String,
+  pub depth: F,
+}
+
+impl Text {
+  pub fn new(text: impl Into<String>) -> Self {
+    Self {
+      text: text.into(),
+      depth: 0.2,
+    }
+  }
+
+  pub fn with_depth(mut self, depth: F) -> Self {
+    self.depth = depth;
+    self
+  }
+
+  /// Whether the font pixel in the given column and row of the whole text,
+  /// counted from the top left, is set
+  fn is_set(lines: &[Vec<char>], column: isize, row: isize) -> bool {
+    if column < 0 || row < 0 {
+      return false;
+    }
+    let (column, row) = (column as usize, row as usize);
+    let (line, row) = (row / (GLYPH_HEIGHT + 2), row % (GLYPH_HEIGHT + 2));
+    let (index, column) = (column / (GLYPH_WIDTH + 1), column % (GLYPH_WIDTH + 1));
+    lines
+      .get(line)
+      .and_then(|characters| characters.get(index))
+      .is_some_and(|character| is_set(*character, column, row))
+  }
+
+  /// Triangles of the extruded letters. Fronts and backs of neighbouring
+  /// pixels are merged, and only their outer sides are closed.
+  pub fn mesh(&self) -> Mesh {
+    let lines: Vec<Vec<char>> = self
+      .text
+      .lines()
+      .map(|line| line.chars().collect())
+      .collect();
+    let columns = lines
+      .iter()
+      .map(|characters| characters.len() * (GLYPH_WIDTH + 1))
+      .max()
+      .unwrap_or(0) as isize;
+    let rows = (lines.len() * (GLYPH_HEIGHT + 2)) as isize;
+    let size = 1.0 / GLYPH_HEIGHT as F;
+    let depth = self.depth;
+    // Corner of a font pixel, the bottom row of the first line lies on y = 0
+    let corner = |column: isize, row: isize, z: F| {
+      Tuple::point(
+        column as F * size,
+        (GLYPH_HEIGHT as isize - row) as F * size,
+        z,
+      )
+    };
+    let set = |column: isize, row: isize| Self::is_set(&lines, column, row);
+
+    let mut faces = vec![];
+    // Corners in counter clockwise order, seen from the outside
+    let mut quad = |a: Tuple, b: Tuple, c: Tuple, d: Tuple| {
+      faces.push(Face::from(Triangle::new(a, b, c)));
+      faces.push(Face::from(Triangle::new(a, c, d)));
+    };
+    for row in 0..rows {
+      let mut column = 0;
+      while column < columns {
+        if !set(column, row) {
+          column += 1;
+          continue;
+        }
+        let start = column;
+        while set(column, row) {
+          column += 1;
+        }
+        let (left, right, top, bottom) = (start, column, row, row + 1);
+        quad(
+          corner(left, bottom, 0.0),
+          corner(right, bottom, 0.0),
+          corner(right, top, 0.0),
+          corner(left, top, 0.0),
+        );
+        quad(
+          corner(left, bottom, depth),
+          corner(left, top, depth),
+          corner(right, top, depth),
+          corner(right, bottom, depth),
+        );
+      }
+
+      for column in 0..columns {
+        if !set(column, row) {
+          continue;
+        }
+        let (left, right, top, bottom) = (column, column + 1, row, row + 1);
+        if !set(column - 1, row) {
+          quad(
+            corner(left, bottom, 0.0),
+            corner(left, top, 0.0),
+            corner(left, top, depth),
+            corner(left, bottom, depth),
+          );
+        }
+        if !set(column + 1, row) {
+          quad(
+            corner(right, bottom, 0.0),
+            corner(right, bottom, depth),
+            corner(right, top, depth),
+            corner(right, top, 0.0),
+          );
+        }
+        if !set(column, row + 1) {
+          quad(
+            corner(left, bottom, 0.0),
+            corner(left, bottom, depth),
+            corner(right, bottom, depth),
+            corner(right, bottom, 0.0),
+          );
+        }
+        if !set(column, row - 1) {
+          quad(
+            corner(left, top, 0.0),
+            corner(right, top, 0.0),
+            corner(right, top, depth),
+            corner(left, top, depth),
+          );
+        }
+      }
+    }
+
+    Mesh::new(faces)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::body::{Body, Intersectable};
+  use crate::ray::Ray;
+
+  /// Normals of all the bodies hit by the ray
+  fn hits(bodies: &[Body], ray: Ray) -> Vec<Tuple> {
+    bodies
+      .iter()
+      .flat_map(|body| body.intersect(ray).into_iter())
+      .map(|intersection| intersection.body.normal_at(ray.position(intersection.t)))
+      .collect()
+  }
+
+  #[test]
+  fn a_single_pixel_is_a_closed_box() {
+    let mesh = Text::new(".").mesh();
+
+    // The period is a square of 2x2 pixels: two runs on the front and the
+    // back, and two pixel edges on each of the four sides
+    assert_eq!(mesh.faces.len(), 2 * 2 * 2 + 2 * 2 * 4);
+  }
+
+  #[test]
+  fn letters_are_one_unit_high_and_face_the_viewer() {
+    let bodies = vec![Text::new("I").with_depth(0.5).mesh().body()];
+
+    // The stem of the 'I' is the middle column of the glyph
+    let x = 2.3 / 7.0;
+    let through_stem = hits(
+      &bodies,
+      Ray::new(Tuple::point(x, 0.45, -5.0), Tuple::vector(0.0, 0.0, 1.0)),
+    );
+    let beside_stem = hits(
+      &bodies,
+      Ray::new(
+        Tuple::point(x + 0.2, 0.45, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+      ),
+    );
+    let above = hits(
+      &bodies,
+      Ray::new(Tuple::point(x, 1.01, -5.0), Tuple::vector(0.0, 0.0, 1.0)),
+    );
+
+    assert_eq!(through_stem.len(), 2);
+    assert!(through_stem.contains(&Tuple::vector(0.0, 0.0, -1.0)));
+    assert!(through_stem.contains(&Tuple::vector(0.0, 0.0, 1.0)));
+    assert!(beside_stem.is_empty());
+    assert!(above.is_empty());
+  }
+
+  #[test]
+  fn lines_are_stacked_downwards() {
+    let bodies = vec![Text::new("-\n-").mesh().body()];
+
+    // The bar of the '-' is the fourth row of the glyph
+    let first = hits(
+      &bodies,
+      Ray::new(
+        Tuple::point(0.3, 3.5 / 7.0, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+      ),
+    );
+    let second = hits(
+      &bodies,
+      Ray::new(
+        Tuple::point(0.3, -5.5 / 7.0, -5.0),
+        Tuple::vector(0.0, 0.0, 1.0),
+      ),
+    );
+
+    assert_eq!(first.len(), 2);
+    assert_eq!(second.len(), 2);
+  }
+}
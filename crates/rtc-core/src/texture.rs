@@ -0,0 +1,187 @@
+//! Images wrapped around bodies using UV coordinates
+use crate::body::{Body, Intersectable};
+use crate::canvas::{Canvas, Color};
+use crate::fingerprint::Fingerprint;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::matrix::Matrix;
+use crate::pattern::Stencil;
+use crate::tuple::Tuple;
+use crate::uv::planar_map;
+use crate::F;
+use std::fmt;
+use std::sync::Arc;
+
+/// An image, which is wrapped around a body.
+#[derive(Clone)]
+pub struct Texture {
+  image: Arc<Canvas>,
+  /// Hash of the pixels, so fingerprints of worlds tell images apart
+  /// without hashing them again for every render
+  pixels: Fingerprint,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+}
+
+impl Texture {
+  pub fn new(image: Canvas) -> Self {
+    Self {
+      pixels: image.fingerprint(),
+      image: Arc::new(image),
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+    }
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+
+  pub fn image(&self) -> &Canvas {
+    &self.image
+  }
+
+  /// Whether the image has the same pixels as the given one, which is cheap
+  /// for clones of a texture sharing the image
+  fn same_image(&self, image: &Arc<Canvas>) -> bool {
+    Arc::ptr_eq(&self.image, image) || self.image == *image
+  }
+
+  /// Nearest pixel at the given UV coordinates, with `v = 0` being the bottom
+  /// row of the image. Empty images are black.
+  pub fn color_at_uv(&self, u: F, v: F) -> Color {
+    if self.image.width == 0 || self.image.height == 0 {
+      return Color::black();
+    }
+    let x = (u.clamp(0.0, 1.0) * (self.image.width - 1) as F).round() as usize;
+    let y = ((1.0 - v.clamp(0.0, 1.0)) * (self.image.height - 1) as F).round() as usize;
+    self.image.pixel_at(x, y)
+  }
+}
+
+impl fmt::Debug for Texture {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Texture")
+      .field("width", &self.image.width)
+      .field("height", &self.image.height)
+      .field("pixels", &self.pixels)
+      .field("transform", &self.transform)
+      .finish()
+  }
+}
+
+impl PartialEq for Texture {
+  fn eq(&self, other: &Texture) -> bool {
+    self.same_image(&other.image) && self.transform == other.transform
+  }
+}
+
+impl FuzzyEq<Texture> for Texture {
+  fn fuzzy_eq(&self, other: Texture) -> bool {
+    self.same_image(&other.image) && self.transform.fuzzy_eq(other.transform)
+  }
+}
+
+impl Stencil for Texture {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    let (u, v) = planar_map(position);
+    self.color_at_uv(u, v)
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn color_at(&self, position: Tuple, body: &Body) -> Color {
+    let object_position = body.inverse_transform() * position;
+    let pattern_position = self.inverse_transform * object_position;
+
+    let (u, v) = body.uv_at(pattern_position);
+    self.color_at_uv(u, v)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::quad::Quad;
+  use crate::sphere::Sphere;
+
+  fn four_colors() -> Texture {
+    let mut image = Canvas::new(2, 2);
+    image.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0));
+    image.write_pixel(1, 0, Color::new(0.0, 1.0, 0.0));
+    image.write_pixel(0, 1, Color::new(0.0, 0.0, 1.0));
+    image.write_pixel(1, 1, Color::white());
+    Texture::new(image)
+  }
+
+  #[test]
+  fn the_top_row_of_the_image_is_at_the_top_of_the_uv_space() {
+    let texture = four_colors();
+
+    assert_fuzzy_eq!(texture.color_at_uv(0.0, 1.0), Color::new(1.0, 0.0, 0.0));
+    assert_fuzzy_eq!(texture.color_at_uv(1.0, 1.0), Color::new(0.0, 1.0, 0.0));
+    assert_fuzzy_eq!(texture.color_at_uv(0.0, 0.0), Color::new(0.0, 0.0, 1.0));
+    assert_fuzzy_eq!(texture.color_at_uv(1.0, 0.0), Color::white());
+  }
+
+  #[test]
+  fn empty_images_are_black() {
+    let texture = Texture::new(Canvas::new(0, 0));
+
+    assert_fuzzy_eq!(texture.color_at_uv(0.5, 0.5), Color::black());
+  }
+
+  #[test]
+  fn a_texture_covers_a_whole_quad() {
+    let texture = four_colors();
+    let quad = Body::from(
+      Quad::default()
+        .with_width(4.0)
+        .with_height(2.0)
+        .with_transform(Matrix::translation(0.0, 1.0, 0.0)),
+    );
+
+    assert_fuzzy_eq!(
+      texture.color_at(Tuple::point(-1.9, 1.0, 0.9), &quad),
+      Color::new(1.0, 0.0, 0.0)
+    );
+    assert_fuzzy_eq!(
+      texture.color_at(Tuple::point(1.9, 1.0, -0.9), &quad),
+      Color::white()
+    );
+  }
+
+  #[test]
+  fn a_texture_wraps_around_a_sphere() {
+    let texture = four_colors();
+    let sphere = Body::from(Sphere::default());
+
+    // The north pole shows the top row, the south pole the bottom one
+    assert_fuzzy_eq!(
+      texture.color_at(Tuple::point(0.0, 1.0, 0.0), &sphere).blue,
+      0.0
+    );
+    assert_fuzzy_eq!(
+      texture.color_at(Tuple::point(0.0, -1.0, 0.0), &sphere).blue,
+      1.0
+    );
+  }
+
+  #[test]
+  fn textures_are_equal_by_their_pixels() {
+    let texture = four_colors();
+    let mut image = Canvas::new(2, 2);
+    image.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0));
+
+    assert_eq!(texture, texture.clone());
+    assert_eq!(texture, four_colors());
+    assert_ne!(texture, Texture::new(image));
+  }
+}
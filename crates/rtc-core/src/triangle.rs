@@ -0,0 +1,381 @@
+use crate::body::*;
+use crate::bounds::Bounds;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::*;
+use crate::matrix::*;
+use crate::ray::*;
+use crate::tuple::*;
+use crate::{EPSILON, F};
+
+/// Flat triangle spanned by three points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Triangle {
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+  pub material: Material,
+  pub shadows: Shadows,
+  pub p1: Tuple,
+  pub p2: Tuple,
+  pub p3: Tuple,
+  e1: Tuple,
+  e2: Tuple,
+  normal: Tuple,
+}
+
+impl Triangle {
+  pub fn new(p1: Tuple, p2: Tuple, p3: Tuple) -> Self {
+    let e1 = p2 - p1;
+    let e2 = p3 - p1;
+    Triangle {
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+      material: Material::default(),
+      shadows: Shadows::default(),
+      p1,
+      p2,
+      p3,
+      e1,
+      e2,
+      normal: e2.cross(e1).normalize(),
+    }
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.material = material;
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.shadows = shadows;
+    self
+  }
+
+  pub fn with_transform(self, transform: Matrix<4>) -> Self {
+    self.with_transform_and_inverse(transform, transform.inverse())
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    self.transform = transform;
+    self.inverse_transform = inverse_transform;
+    self
+  }
+
+  /// Barycentric coordinates (u, v) of a point on the triangle.
+  ///
+  /// `u` is the weight of `p2` and `v` the weight of `p3`.
+  pub fn barycentric(&self, point: Tuple) -> (F, F) {
+    barycentric(self.p1, self.e1, self.e2, point)
+  }
+}
+
+/// Möller-Trumbore ray triangle intersection, returning t, u and v of the hit.
+fn intersect_triangle(p1: Tuple, e1: Tuple, e2: Tuple, ray: Ray) -> Option<(F, F, F)> {
+  let dir_cross_e2 = ray.direction.cross(e2);
+  let det = e1.dot(dir_cross_e2);
+  if det.abs() < EPSILON {
+    return None;
+  }
+
+  let f = 1.0 / det;
+  let p1_to_origin = ray.origin - p1;
+  let u = f * p1_to_origin.dot(dir_cross_e2);
+  if !(0.0..=1.0).contains(&u) {
+    return None;
+  }
+
+  let origin_cross_e1 = p1_to_origin.cross(e1);
+  let v = f * ray.direction.dot(origin_cross_e1);
+  if v < 0.0 || (u + v) > 1.0 {
+    return None;
+  }
+
+  Some((f * e2.dot(origin_cross_e1), u, v))
+}
+
+fn barycentric(p1: Tuple, e1: Tuple, e2: Tuple, point: Tuple) -> (F, F) {
+  let p1_to_point = point - p1;
+  let d11 = e1.dot(e1);
+  let d12 = e1.dot(e2);
+  let d22 = e2.dot(e2);
+  let d1p = e1.dot(p1_to_point);
+  let d2p = e2.dot(p1_to_point);
+  let denominator = d11 * d22 - d12 * d12;
+
+  let u = (d22 * d1p - d12 * d2p) / denominator;
+  let v = (d11 * d2p - d12 * d1p) / denominator;
+  (u, v)
+}
+
+impl FuzzyEq<&Triangle> for Triangle {
+  fn fuzzy_eq(&self, other: &Triangle) -> bool {
+    self.transform.fuzzy_eq(other.transform)
+      && self.material.fuzzy_eq(other.material.clone())
+      && self.shadows == other.shadows
+      && self.p1.fuzzy_eq(other.p1)
+      && self.p2.fuzzy_eq(other.p2)
+      && self.p3.fuzzy_eq(other.p3)
+  }
+}
+
+impl Intersectable for Triangle {
+  fn max_intersections(&self) -> usize {
+    1
+  }
+
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    match intersect_triangle(self.p1, self.e1, self.e2, object_space_ray) {
+      Some((t, _, _)) => vec![(t, Body::from(self.clone()))],
+      None => vec![],
+    }
+  }
+
+  fn normal_at_in_object_space(&self, _object_space_point: Tuple) -> Tuple {
+    self.normal
+  }
+
+  fn material(&self) -> Material {
+    self.material.clone()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.shadows
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    Bounds::from_points(&[self.p1, self.p2, self.p3])
+  }
+}
+
+/// Triangle with a normal for each of its points.
+///
+/// The shading normal is interpolated between the vertex normals using the
+/// barycentric coordinates of the hit, which hides the facets of meshes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SmoothTriangle {
+  pub triangle: Triangle,
+  pub n1: Tuple,
+  pub n2: Tuple,
+  pub n3: Tuple,
+}
+
+impl SmoothTriangle {
+  pub fn new(p1: Tuple, p2: Tuple, p3: Tuple, n1: Tuple, n2: Tuple, n3: Tuple) -> Self {
+    SmoothTriangle {
+      triangle: Triangle::new(p1, p2, p3),
+      n1,
+      n2,
+      n3,
+    }
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.triangle = self.triangle.with_material(material);
+    self
+  }
+
+  pub fn with_shadows(mut self, shadows: Shadows) -> Self {
+    self.triangle = self.triangle.with_shadows(shadows);
+    self
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.triangle = self.triangle.with_transform(transform);
+    self
+  }
+
+  pub fn with_transform_and_inverse(
+    mut self,
+    transform: Matrix<4>,
+    inverse_transform: Matrix<4>,
+  ) -> Self {
+    self.triangle = self
+      .triangle
+      .with_transform_and_inverse(transform, inverse_transform);
+    self
+  }
+
+  /// Normal interpolated for the given barycentric coordinates
+  pub fn normal_at_uv(&self, u: F, v: F) -> Tuple {
+    self.n2 * u + self.n3 * v + self.n1 * (1.0 - u - v)
+  }
+}
+
+impl FuzzyEq<&SmoothTriangle> for SmoothTriangle {
+  fn fuzzy_eq(&self, other: &SmoothTriangle) -> bool {
+    self.triangle.fuzzy_eq(&other.triangle)
+      && self.n1.fuzzy_eq(other.n1)
+      && self.n2.fuzzy_eq(other.n2)
+      && self.n3.fuzzy_eq(other.n3)
+  }
+}
+
+impl Intersectable for SmoothTriangle {
+  fn max_intersections(&self) -> usize {
+    1
+  }
+
+  fn intersect_in_object_space(&self, object_space_ray: Ray) -> Vec<(F, Body)> {
+    let triangle = &self.triangle;
+    match intersect_triangle(triangle.p1, triangle.e1, triangle.e2, object_space_ray) {
+      Some((t, _, _)) => vec![(t, Body::from(self.clone()))],
+      None => vec![],
+    }
+  }
+
+  fn normal_at_in_object_space(&self, object_space_point: Tuple) -> Tuple {
+    let (u, v) = self.triangle.barycentric(object_space_point);
+    self.normal_at_uv(u, v).normalize()
+  }
+
+  fn geometric_normal_at_in_object_space(&self, _object_space_point: Tuple) -> Tuple {
+    self.triangle.normal
+  }
+
+  fn material(&self) -> Material {
+    self.triangle.material.clone()
+  }
+
+  fn shadows(&self) -> Shadows {
+    self.triangle.shadows
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.triangle.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.triangle.inverse_transform
+  }
+
+  fn bounds_in_object_space(&self) -> Bounds {
+    self.triangle.bounds_in_object_space()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn create_triangle() -> Triangle {
+    Triangle::new(
+      Tuple::point(0.0, 1.0, 0.0),
+      Tuple::point(-1.0, 0.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+    )
+  }
+
+  fn create_smooth_triangle() -> SmoothTriangle {
+    SmoothTriangle::new(
+      Tuple::point(0.0, 1.0, 0.0),
+      Tuple::point(-1.0, 0.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+      Tuple::vector(-1.0, 0.0, 0.0),
+      Tuple::vector(1.0, 0.0, 0.0),
+    )
+  }
+
+  #[test]
+  fn constructing_a_triangle() {
+    let t = create_triangle();
+
+    assert_fuzzy_eq!(t.e1, Tuple::vector(-1.0, -1.0, 0.0));
+    assert_fuzzy_eq!(t.e2, Tuple::vector(1.0, -1.0, 0.0));
+    assert_fuzzy_eq!(t.normal, Tuple::vector(0.0, 0.0, -1.0));
+  }
+
+  #[test]
+  fn finding_the_normal_on_a_triangle() {
+    let t = create_triangle();
+
+    assert_fuzzy_eq!(
+      t.normal_at_in_object_space(Tuple::point(0.0, 0.5, 0.0)),
+      t.normal
+    );
+    assert_fuzzy_eq!(
+      t.normal_at_in_object_space(Tuple::point(-0.5, 0.75, 0.0)),
+      t.normal
+    );
+  }
+
+  #[test]
+  fn a_ray_misses_a_triangle() {
+    let t = create_triangle();
+    let examples = [
+      // Parallel to the triangle
+      (Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 1.0, 0.0)),
+      // Beyond the p1-p3 edge
+      (Tuple::point(1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0)),
+      // Beyond the p1-p2 edge
+      (Tuple::point(-1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0)),
+      // Beyond the p2-p3 edge
+      (Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0)),
+    ];
+
+    for (origin, direction) in examples {
+      let xs = t.intersect_in_object_space(Ray::new(origin, direction));
+      assert_eq!(xs.len(), 0);
+    }
+  }
+
+  #[test]
+  fn a_ray_strikes_a_triangle() {
+    let t = create_triangle();
+    let r = Ray::new(Tuple::point(0.0, 0.5, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = t.intersect_in_object_space(r);
+
+    assert_eq!(xs.len(), 1);
+    assert_fuzzy_eq!(xs[0].0, 2.0);
+  }
+
+  #[test]
+  fn intersection_with_a_smooth_triangle_stores_u_and_v() {
+    let tri = create_smooth_triangle();
+    let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+    let (_, u, v) =
+      intersect_triangle(tri.triangle.p1, tri.triangle.e1, tri.triangle.e2, r).unwrap();
+
+    assert_fuzzy_eq!(u, 0.45);
+    assert_fuzzy_eq!(v, 0.25);
+  }
+
+  #[test]
+  fn barycentric_coordinates_match_the_intersection() {
+    let tri = create_smooth_triangle();
+    let (u, v) = tri.triangle.barycentric(Tuple::point(-0.2, 0.3, 0.0));
+
+    assert_fuzzy_eq!(u, 0.45);
+    assert_fuzzy_eq!(v, 0.25);
+  }
+
+  #[test]
+  fn a_smooth_triangle_uses_u_and_v_to_interpolate_the_normal() {
+    let tri = create_smooth_triangle();
+    let n = tri.normal_at_in_object_space(Tuple::point(-0.2, 0.3, 0.0));
+
+    assert_fuzzy_eq!(n, Tuple::vector(-0.5547, 0.83205, 0.0));
+  }
+
+  #[test]
+  fn smooth_triangle_normals_are_transformed_to_world_space() {
+    let tri = create_smooth_triangle().with_transform(Matrix::translation(0.0, 0.0, 5.0));
+    let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = tri.intersect(r);
+    let point = r.position(xs[0].t);
+
+    assert_fuzzy_eq!(xs[0].t, 7.0);
+    assert_fuzzy_eq!(tri.normal_at(point), Tuple::vector(-0.5547, 0.83205, 0.0));
+  }
+}
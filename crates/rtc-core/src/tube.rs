@@ -0,0 +1,229 @@
+use crate::body::Body;
+use crate::cylinder::Cylinder;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::material::Material;
+use crate::matrix::Matrix;
+use crate::sphere::Sphere;
+use crate::tuple::Tuple;
+use crate::F;
+
+/// Tube swept along a polyline, to show trajectories, graphs or wireframes
+/// in 3D.
+///
+/// Every segment is a capsule: an open cylinder between its two points, with
+/// spheres rounding off the joints and both ends. Curves are flattened into
+/// polylines first, see `Tube::bezier`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tube {
+  pub points: Vec<Tuple>,
+  pub radius: F,
+  pub material: Material,
+  pub transform: Matrix<4>,
+}
+
+impl Default for Tube {
+  fn default() -> Self {
+    Self {
+      points: vec![],
+      radius: 0.1,
+      material: Default::default(),
+      transform: Matrix::identity(),
+    }
+  }
+}
+
+impl Tube {
+  pub fn new(points: Vec<Tuple>) -> Self {
+    Self {
+      points,
+      ..Default::default()
+    }
+  }
+
+  /// Tube along a chain of cubic Bezier curves: the start point, followed by
+  /// two control points and the end point of each curve, which is the start
+  /// of the next one. Curves are split until they deviate less than
+  /// `tolerance` from the straight segments, so only bends get many of them.
+  pub fn bezier(controls: &[Tuple], tolerance: F) -> Self {
+    let mut points = controls.iter().take(1).copied().collect::<Vec<_>>();
+    for curve in controls.windows(4).step_by(3) {
+      flatten(
+        [curve[0], curve[1], curve[2], curve[3]],
+        tolerance,
+        0,
+        &mut points,
+      );
+    }
+    Self::new(points)
+  }
+
+  pub fn with_radius(mut self, radius: F) -> Self {
+    self.radius = radius;
+    self
+  }
+
+  pub fn with_material(mut self, material: Material) -> Self {
+    self.material = material;
+    self
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self
+  }
+
+  /// A sphere for each point and a cylinder for each segment between them,
+  /// using the material and transform of the tube
+  pub fn bodies(&self) -> Vec<Body> {
+    let mut points = self.points.clone();
+    points.dedup_by(|point, previous| point.fuzzy_eq(*previous));
+
+    let r = self.radius;
+    let joints = points.iter().map(|point| {
+      Body::from(
+        Sphere::default()
+          .with_material(self.material.clone())
+          .with_transform(
+            self.transform
+              * Matrix::translation(point.x, point.y, point.z)
+              * Matrix::scaling(r, r, r),
+          ),
+      )
+    });
+    let segments = points.windows(2).map(|segment| {
+      Body::from(
+        Cylinder::default()
+          .with_minimum(0.0)
+          .with_maximum(1.0)
+          .with_material(self.material.clone())
+          .with_transform(self.transform * segment_transform(segment[0], segment[1], r)),
+      )
+    });
+    joints.chain(segments).collect()
+  }
+}
+
+/// Transformation of the unit cylinder between y = 0 and y = 1 into a
+/// cylinder of the given radius from `start` to `end`
+fn segment_transform(start: Tuple, end: Tuple, radius: F) -> Matrix<4> {
+  let axis = end - start;
+  let length = axis.magnitude();
+  let y = axis * (1.0 / length);
+  let helper = if y.x.abs() < 0.9 {
+    Tuple::vector(1.0, 0.0, 0.0)
+  } else {
+    Tuple::vector(0.0, 1.0, 0.0)
+  };
+  let x = y.cross(helper).normalize();
+  let z = x.cross(y);
+  Matrix::from([
+    [x.x * radius, y.x * length, z.x * radius, start.x],
+    [x.y * radius, y.y * length, z.y * radius, start.y],
+    [x.z * radius, y.z * length, z.z * radius, start.z],
+    [0.0, 0.0, 0.0, 1.0],
+  ])
+}
+
+/// Appends the end points of straight pieces of the curve to `points`,
+/// splitting it in halves (de Casteljau) until it is flat enough
+fn flatten(curve: [Tuple; 4], tolerance: F, depth: usize, points: &mut Vec<Tuple>) {
+  let [p0, p1, p2, p3] = curve;
+  if depth >= 16 || (deviation(p1, p0, p3) < tolerance && deviation(p2, p0, p3) < tolerance) {
+    points.push(p3);
+    return;
+  }
+  let half = |a: Tuple, b: Tuple| a + (b - a) * 0.5;
+  let (p01, p12, p23) = (half(p0, p1), half(p1, p2), half(p2, p3));
+  let (p012, p123) = (half(p01, p12), half(p12, p23));
+  let middle = half(p012, p123);
+  flatten([p0, p01, p012, middle], tolerance, depth + 1, points);
+  flatten([middle, p123, p23, p3], tolerance, depth + 1, points);
+}
+
+/// Distance of the point from the line through `start` and `end`
+fn deviation(point: Tuple, start: Tuple, end: Tuple) -> F {
+  let line = end - start;
+  let length = line.magnitude();
+  if length.fuzzy_eq(0.0) {
+    return (point - start).magnitude();
+  }
+  (point - start).cross(line).magnitude() / length
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::body::Intersectable;
+  use crate::ray::Ray;
+
+  #[test]
+  fn a_tube_has_a_joint_for_each_point_and_a_segment_between_them() {
+    let tube = Tube::new(vec![
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::point(1.0, 2.0, 0.0),
+    ]);
+
+    let bodies = tube.bodies();
+
+    // The repeated point is left out
+    assert_eq!(bodies.len(), 5);
+    assert_eq!(
+      bodies
+        .iter()
+        .filter(|body| matches!(body, Body::Cylinder(_)))
+        .count(),
+      2
+    );
+  }
+
+  #[test]
+  fn segments_reach_from_point_to_point_with_the_radius_of_the_tube() {
+    let tube = Tube::new(vec![
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::point(4.0, 0.0, 0.0),
+    ])
+    .with_radius(0.5);
+    let segment = &tube.bodies()[2];
+
+    let side = segment.intersect(Ray::new(
+      Tuple::point(3.0, 0.0, -5.0),
+      Tuple::vector(0.0, 0.0, 1.0),
+    ));
+    let beyond = segment.intersect(Ray::new(
+      Tuple::point(4.2, 0.0, -5.0),
+      Tuple::vector(0.0, 0.0, 1.0),
+    ));
+
+    assert_eq!(side.len(), 2);
+    assert_fuzzy_eq!(side[0].t, 4.5);
+    assert_eq!(beyond.len(), 0);
+  }
+
+  #[test]
+  fn bezier_curves_are_flattened_within_the_tolerance() {
+    let controls = [
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::point(0.0, 1.0, 0.0),
+      Tuple::point(1.0, 1.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::point(1.0, -1.0, 0.0),
+      Tuple::point(2.0, -1.0, 0.0),
+      Tuple::point(2.0, 0.0, 0.0),
+    ];
+
+    let coarse = Tube::bezier(&controls, 0.1);
+    let fine = Tube::bezier(&controls, 0.001);
+
+    assert_fuzzy_eq!(coarse.points[0], controls[0]);
+    assert_fuzzy_eq!(*coarse.points.last().unwrap(), controls[6]);
+    assert!(coarse.points.contains(&controls[3]));
+    assert!(fine.points.len() > coarse.points.len());
+    // The middle of the first curve lies at (0.5, 0.75)
+    assert!(fine
+      .points
+      .iter()
+      .any(|point| (point.x - 0.5).abs() < 0.001 && (point.y - 0.75).abs() < 0.001));
+  }
+}
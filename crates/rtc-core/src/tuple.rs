@@ -1,5 +1,9 @@
+use crate::angle::Angle;
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::Float;
 use crate::F;
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use core::convert::TryFrom;
+use core::ops::{Add, Div, Index, IndexMut, Mul, Neg, Sub};
 
 use crate::fuzzy_eq::*;
 
@@ -138,6 +142,268 @@ impl Tuple {
   pub fn reflect(&self, normal: Tuple) -> Tuple {
     *self - normal * 2.0 * self.dot(normal)
   }
+
+  /// Angle between two vectors
+  pub fn angle_between(&self, other: Tuple) -> Angle {
+    let cos = self.dot(other) / (self.magnitude() * other.magnitude());
+    Angle::radians(cos.clamp(-1.0, 1.0).acos())
+  }
+
+  /// Part of the tuple pointing along the given vector
+  pub fn project_onto(&self, other: Tuple) -> Tuple {
+    other * (self.dot(other) / other.dot(other))
+  }
+
+  /// Linear interpolation, returning `self` at `t = 0` and `other` at `t = 1`
+  pub fn lerp(&self, other: Tuple, t: F) -> Tuple {
+    *self + (other - *self) * t
+  }
+}
+
+/**
+ * Component wise operations, which keep w untouched
+ */
+impl Tuple {
+  pub fn min_component(&self) -> F {
+    self.x.min(self.y).min(self.z)
+  }
+
+  pub fn max_component(&self) -> F {
+    self.x.max(self.y).max(self.z)
+  }
+
+  pub fn abs(&self) -> Tuple {
+    Tuple::new(self.x.abs(), self.y.abs(), self.z.abs(), self.w)
+  }
+
+  pub fn clamp(&self, min: F, max: F) -> Tuple {
+    Tuple::new(
+      self.x.clamp(min, max),
+      self.y.clamp(min, max),
+      self.z.clamp(min, max),
+      self.w,
+    )
+  }
+}
+
+impl Index<usize> for Tuple {
+  type Output = F;
+
+  fn index(&self, index: usize) -> &Self::Output {
+    match index {
+      0 => &self.x,
+      1 => &self.y,
+      2 => &self.z,
+      3 => &self.w,
+      _ => panic!("Tuple index {} out of range", index),
+    }
+  }
+}
+
+impl IndexMut<usize> for Tuple {
+  fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+    match index {
+      0 => &mut self.x,
+      1 => &mut self.y,
+      2 => &mut self.z,
+      3 => &mut self.w,
+      _ => panic!("Tuple index {} out of range", index),
+    }
+  }
+}
+
+impl From<[F; 4]> for Tuple {
+  fn from(components: [F; 4]) -> Self {
+    let [x, y, z, w] = components;
+    Tuple::new(x, y, z, w)
+  }
+}
+
+impl From<Tuple> for [F; 4] {
+  fn from(tuple: Tuple) -> Self {
+    [tuple.x, tuple.y, tuple.z, tuple.w]
+  }
+}
+
+/// Three components are taken as a vector
+impl From<(F, F, F)> for Tuple {
+  fn from((x, y, z): (F, F, F)) -> Self {
+    Tuple::vector(x, y, z)
+  }
+}
+
+impl From<Tuple> for (F, F, F) {
+  fn from(tuple: Tuple) -> Self {
+    (tuple.x, tuple.y, tuple.z)
+  }
+}
+
+/// A position in space. Unlike a raw `Tuple` only the operations meaningful
+/// for positions are available, e.g. two points can not be added up.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Point {
+  pub x: F,
+  pub y: F,
+  pub z: F,
+}
+
+/// A direction in space, which can be scaled and combined with points and
+/// other vectors.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Vector {
+  pub x: F,
+  pub y: F,
+  pub z: F,
+}
+
+impl Point {
+  pub fn new(x: F, y: F, z: F) -> Self {
+    Self { x, y, z }
+  }
+}
+
+impl Vector {
+  pub fn new(x: F, y: F, z: F) -> Self {
+    Self { x, y, z }
+  }
+
+  pub fn magnitude(&self) -> F {
+    Tuple::from(*self).magnitude()
+  }
+
+  pub fn normalize(&self) -> Self {
+    *self / self.magnitude()
+  }
+
+  pub fn dot(&self, other: Vector) -> F {
+    Tuple::from(*self).dot(other.into())
+  }
+
+  pub fn cross(&self, other: Vector) -> Vector {
+    Vector::new(
+      self.y * other.z - self.z * other.y,
+      self.z * other.x - self.x * other.z,
+      self.x * other.y - self.y * other.x,
+    )
+  }
+
+  pub fn reflect(&self, normal: Vector) -> Vector {
+    *self - normal * 2.0 * self.dot(normal)
+  }
+}
+
+impl From<Point> for Tuple {
+  fn from(point: Point) -> Self {
+    Tuple::point(point.x, point.y, point.z)
+  }
+}
+
+impl From<Vector> for Tuple {
+  fn from(vector: Vector) -> Self {
+    Tuple::vector(vector.x, vector.y, vector.z)
+  }
+}
+
+/// Fails with the given tuple, if it is not a point
+impl TryFrom<Tuple> for Point {
+  type Error = Tuple;
+
+  fn try_from(tuple: Tuple) -> Result<Self, Self::Error> {
+    if tuple.is_point() {
+      Ok(Point::new(tuple.x, tuple.y, tuple.z))
+    } else {
+      Err(tuple)
+    }
+  }
+}
+
+/// Fails with the given tuple, if it is not a vector
+impl TryFrom<Tuple> for Vector {
+  type Error = Tuple;
+
+  fn try_from(tuple: Tuple) -> Result<Self, Self::Error> {
+    if tuple.is_vector() {
+      Ok(Vector::new(tuple.x, tuple.y, tuple.z))
+    } else {
+      Err(tuple)
+    }
+  }
+}
+
+impl FuzzyEq<Point> for Point {
+  fn fuzzy_eq(&self, other: Self) -> bool {
+    Tuple::from(*self).fuzzy_eq(other.into())
+  }
+}
+
+impl FuzzyEq<Vector> for Vector {
+  fn fuzzy_eq(&self, other: Self) -> bool {
+    Tuple::from(*self).fuzzy_eq(other.into())
+  }
+}
+
+impl Sub<Point> for Point {
+  type Output = Vector;
+
+  fn sub(self, other: Point) -> Self::Output {
+    Vector::new(self.x - other.x, self.y - other.y, self.z - other.z)
+  }
+}
+
+impl Add<Vector> for Point {
+  type Output = Point;
+
+  fn add(self, other: Vector) -> Self::Output {
+    Point::new(self.x + other.x, self.y + other.y, self.z + other.z)
+  }
+}
+
+impl Sub<Vector> for Point {
+  type Output = Point;
+
+  fn sub(self, other: Vector) -> Self::Output {
+    Point::new(self.x - other.x, self.y - other.y, self.z - other.z)
+  }
+}
+
+impl Add<Vector> for Vector {
+  type Output = Vector;
+
+  fn add(self, other: Vector) -> Self::Output {
+    Vector::new(self.x + other.x, self.y + other.y, self.z + other.z)
+  }
+}
+
+impl Sub<Vector> for Vector {
+  type Output = Vector;
+
+  fn sub(self, other: Vector) -> Self::Output {
+    Vector::new(self.x - other.x, self.y - other.y, self.z - other.z)
+  }
+}
+
+impl Neg for Vector {
+  type Output = Vector;
+
+  fn neg(self) -> Self::Output {
+    Vector::new(-self.x, -self.y, -self.z)
+  }
+}
+
+impl Mul<F> for Vector {
+  type Output = Vector;
+
+  fn mul(self, other: F) -> Self::Output {
+    Vector::new(self.x * other, self.y * other, self.z * other)
+  }
+}
+
+impl Div<F> for Vector {
+  type Output = Vector;
+
+  fn div(self, other: F) -> Self::Output {
+    Vector::new(self.x / other, self.y / other, self.z / other)
+  }
 }
 
 #[cfg(test)]
@@ -323,7 +589,7 @@ mod tests {
   fn compute_the_magnitude_of_vector_1_2_3() {
     let v = Tuple::vector(1.0, 2.0, 3.0);
 
-    let expected_result = (14.0 as f64).sqrt();
+    let expected_result = (14.0 as F).sqrt();
     let actual_result = v.magnitude();
 
     assert_fuzzy_eq!(actual_result, expected_result);
@@ -333,7 +599,7 @@ mod tests {
   fn compute_the_magnitude_of_negative_vector_1_2_3() {
     let v = Tuple::vector(-1.0, -2.0, -3.0);
 
-    let expected_result = (14.0 as f64).sqrt();
+    let expected_result = (14.0 as F).sqrt();
     let actual_result = v.magnitude();
 
     assert_fuzzy_eq!(actual_result, expected_result);
@@ -425,4 +691,110 @@ mod tests {
 
     assert_fuzzy_eq!(r, expected_result);
   }
+
+  #[test]
+  fn the_angle_between_two_vectors() {
+    let a = Tuple::vector(1.0, 0.0, 0.0);
+    let b = Tuple::vector(0.0, 2.0, 0.0);
+
+    assert_fuzzy_eq!(a.angle_between(b), Angle::degrees(90.0));
+    assert_fuzzy_eq!(a.angle_between(a), Angle::degrees(0.0));
+    assert_fuzzy_eq!(a.angle_between(-a), Angle::degrees(180.0));
+  }
+
+  #[test]
+  fn projecting_a_vector_onto_another() {
+    let v = Tuple::vector(2.0, 3.0, 0.0);
+
+    assert_fuzzy_eq!(
+      v.project_onto(Tuple::vector(0.0, 5.0, 0.0)),
+      Tuple::vector(0.0, 3.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn interpolating_between_two_points() {
+    let a = Tuple::point(0.0, 2.0, 4.0);
+    let b = Tuple::point(2.0, 4.0, 0.0);
+
+    assert_fuzzy_eq!(a.lerp(b, 0.0), a);
+    assert_fuzzy_eq!(a.lerp(b, 0.25), Tuple::point(0.5, 2.5, 3.0));
+    assert_fuzzy_eq!(a.lerp(b, 1.0), b);
+  }
+
+  #[test]
+  fn component_wise_operations_keep_w() {
+    let v = Tuple::vector(-3.0, 0.5, 2.0);
+
+    assert_fuzzy_eq!(v.min_component(), -3.0);
+    assert_fuzzy_eq!(v.max_component(), 2.0);
+    assert_fuzzy_eq!(v.abs(), Tuple::vector(3.0, 0.5, 2.0));
+    assert_fuzzy_eq!(
+      Tuple::point(-3.0, 0.5, 2.0).clamp(0.0, 1.0),
+      Tuple::point(0.0, 0.5, 1.0)
+    );
+  }
+
+  #[test]
+  fn tuples_are_indexed_by_component() {
+    let mut t = Tuple::new(1.0, 2.0, 3.0, 4.0);
+    t[2] = 5.0;
+
+    assert_fuzzy_eq!(t[0], 1.0);
+    assert_fuzzy_eq!(t[1], 2.0);
+    assert_fuzzy_eq!(t[2], 5.0);
+    assert_fuzzy_eq!(t[3], 4.0);
+  }
+
+  #[test]
+  fn tuples_are_converted_from_and_to_arrays_and_triples() {
+    let t = Tuple::from([1.0, 2.0, 3.0, 1.0]);
+
+    assert_fuzzy_eq!(t, Tuple::point(1.0, 2.0, 3.0));
+    assert_eq!(<[F; 4]>::from(t), [1.0, 2.0, 3.0, 1.0]);
+    assert_fuzzy_eq!(Tuple::from((1.0, 2.0, 3.0)), Tuple::vector(1.0, 2.0, 3.0));
+    assert_eq!(<(F, F, F)>::from(t), (1.0, 2.0, 3.0));
+  }
+
+  #[test]
+  fn subtracting_two_points_results_in_a_vector() {
+    let a = Point::new(3.0, 2.0, 1.0);
+    let b = Point::new(5.0, 6.0, 7.0);
+
+    assert_fuzzy_eq!(a - b, Vector::new(-2.0, -4.0, -6.0));
+  }
+
+  #[test]
+  fn moving_a_point_along_a_vector() {
+    let p = Point::new(3.0, 2.0, 1.0);
+    let v = Vector::new(5.0, 6.0, 7.0);
+
+    assert_fuzzy_eq!(p + v, Point::new(8.0, 8.0, 8.0));
+    assert_fuzzy_eq!(p - v, Point::new(-2.0, -4.0, -6.0));
+  }
+
+  #[test]
+  fn typed_vectors_match_the_tuple_math() {
+    let a = Vector::new(1.0, 2.0, 3.0);
+    let b = Vector::new(2.0, 3.0, 4.0);
+
+    assert_fuzzy_eq!(a.dot(b), Tuple::from(a).dot(b.into()));
+    assert_fuzzy_eq!(Tuple::from(a.cross(b)), Tuple::from(a).cross(b.into()));
+    assert_fuzzy_eq!(Tuple::from(a.normalize()), Tuple::from(a).normalize());
+    assert_fuzzy_eq!(
+      Tuple::from(a.reflect(Vector::new(0.0, 1.0, 0.0))),
+      Tuple::from(a).reflect(Tuple::vector(0.0, 1.0, 0.0))
+    );
+  }
+
+  #[test]
+  fn tuples_are_only_converted_into_the_matching_type() {
+    let point = Tuple::point(1.0, 2.0, 3.0);
+    let vector = Tuple::vector(1.0, 2.0, 3.0);
+
+    assert_eq!(Point::try_from(point), Ok(Point::new(1.0, 2.0, 3.0)));
+    assert_eq!(Vector::try_from(point), Err(point));
+    assert_eq!(Vector::try_from(vector), Ok(Vector::new(1.0, 2.0, 3.0)));
+    assert_eq!(Point::try_from(vector), Err(vector));
+  }
 }
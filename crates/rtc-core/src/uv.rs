@@ -0,0 +1,520 @@
+//! Texture coordinates of points on bodies, and patterns painted in them
+use crate::body::{Body, Intersectable};
+use crate::canvas::Color;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::matrix::Matrix;
+use crate::pattern::Stencil;
+use crate::texture::Texture;
+use crate::tuple::Tuple;
+use crate::F;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+/// Projection of points in object space onto (u, v) coordinates between 0
+/// and 1
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UvMap {
+  Spherical,
+  Planar,
+  Cylindrical,
+  Cubic,
+}
+
+impl UvMap {
+  pub fn uv_at(&self, point: Tuple) -> (F, F) {
+    match *self {
+      UvMap::Spherical => spherical_map(point),
+      UvMap::Planar => planar_map(point),
+      UvMap::Cylindrical => cylindrical_map(point),
+      UvMap::Cubic => cubic_map(point),
+    }
+  }
+}
+
+/// Maps a point on a sphere around the origin. `u` runs around the equator,
+/// `v` from the south to the north pole.
+pub fn spherical_map(point: Tuple) -> (F, F) {
+  let theta = point.x.atan2(point.z);
+  let radius = Tuple::vector(point.x, point.y, point.z).magnitude();
+  let phi = (point.y / radius).acos();
+
+  let raw_u = theta / (2.0 * PI);
+  (1.0 - (raw_u + 0.5), 1.0 - phi / PI)
+}
+
+/// Maps a point on the xz plane, repeating the texture every unit.
+pub fn planar_map(point: Tuple) -> (F, F) {
+  (point.x.rem_euclid(1.0), point.z.rem_euclid(1.0))
+}
+
+/// Maps a point on a cylinder around the y axis. `u` runs around the axis,
+/// `v` repeats every unit of height.
+pub fn cylindrical_map(point: Tuple) -> (F, F) {
+  let theta = point.x.atan2(point.z);
+  let raw_u = theta / (2.0 * PI);
+  (1.0 - (raw_u + 0.5), point.y.rem_euclid(1.0))
+}
+
+/// Maps a point onto the cube from -1 to 1 around the origin, showing the
+/// whole texture on each of its faces. Points off the cube, e.g. on a
+/// sphere, are projected onto it from its center.
+pub fn cubic_map(point: Tuple) -> (F, F) {
+  let largest = point.x.abs().max(point.y.abs()).max(point.z.abs());
+  if largest == 0.0 {
+    return (0.0, 0.0);
+  }
+  CubeFace::from_point(point).uv_at(Tuple::point(
+    point.x / largest,
+    point.y / largest,
+    point.z / largest,
+  ))
+}
+
+/// Face of the cube from -1 to 1, in the order of the faces of a `CubeMap`.
+/// The front faces the negative z axis.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CubeFace {
+  Left,
+  Front,
+  Right,
+  Back,
+  Up,
+  Down,
+}
+
+impl CubeFace {
+  /// Face crossed by the line from the center of the cube to the point
+  pub fn from_point(point: Tuple) -> Self {
+    let (x, y, z) = (point.x, point.y, point.z);
+    let largest = x.abs().max(y.abs()).max(z.abs());
+    if largest == x {
+      CubeFace::Right
+    } else if largest == -x {
+      CubeFace::Left
+    } else if largest == y {
+      CubeFace::Up
+    } else if largest == -y {
+      CubeFace::Down
+    } else if largest == z {
+      CubeFace::Back
+    } else {
+      CubeFace::Front
+    }
+  }
+
+  /// UV coordinates of a point on the face. Seen from outside, `v` points
+  /// up on the sides, like on a cube unfolded around its front: away from
+  /// the front on the top and towards it on the bottom.
+  pub fn uv_at(&self, point: Tuple) -> (F, F) {
+    let (x, y, z) = (point.x, point.y, point.z);
+    let half = |value: F| value.rem_euclid(2.0) / 2.0;
+    match *self {
+      CubeFace::Left => (half(1.0 - z), half(y + 1.0)),
+      CubeFace::Front => (half(x + 1.0), half(y + 1.0)),
+      CubeFace::Right => (half(z + 1.0), half(y + 1.0)),
+      CubeFace::Back => (half(1.0 - x), half(y + 1.0)),
+      CubeFace::Up => (half(x + 1.0), half(z + 1.0)),
+      CubeFace::Down => (half(x + 1.0), half(1.0 - z)),
+    }
+  }
+}
+
+/// Flat pattern in UV space, wrapped around bodies by a `TextureMap`
+#[derive(Clone, Debug, PartialEq)]
+pub enum UvPattern {
+  /// Checkers with the given number of squares along `u` and `v`
+  Checkers {
+    columns: usize,
+    rows: usize,
+    color_a: Color,
+    color_b: Color,
+  },
+  Image(Texture),
+  /// Main color with different corners, showing how a texture is aligned
+  AlignCheck {
+    main: Color,
+    upper_left: Color,
+    upper_right: Color,
+    bottom_left: Color,
+    bottom_right: Color,
+  },
+}
+
+impl UvPattern {
+  pub fn checkers(columns: usize, rows: usize, color_a: Color, color_b: Color) -> Self {
+    UvPattern::Checkers {
+      columns,
+      rows,
+      color_a,
+      color_b,
+    }
+  }
+
+  pub fn color_at_uv(&self, u: F, v: F) -> Color {
+    match *self {
+      UvPattern::Checkers {
+        columns,
+        rows,
+        color_a,
+        color_b,
+      } => {
+        let column = (u * columns as F).floor() as i64;
+        let row = (v * rows as F).floor() as i64;
+        if (column + row) % 2 == 0 {
+          color_a
+        } else {
+          color_b
+        }
+      }
+      UvPattern::Image(ref texture) => texture.color_at_uv(u, v),
+      UvPattern::AlignCheck {
+        main,
+        upper_left,
+        upper_right,
+        bottom_left,
+        bottom_right,
+      } => match (u < 0.2, u > 0.8, v < 0.2, v > 0.8) {
+        (true, _, _, true) => upper_left,
+        (_, true, _, true) => upper_right,
+        (true, _, true, _) => bottom_left,
+        (_, true, true, _) => bottom_right,
+        _ => main,
+      },
+    }
+  }
+}
+
+impl FuzzyEq<UvPattern> for UvPattern {
+  fn fuzzy_eq(&self, other: UvPattern) -> bool {
+    match (self, other) {
+      (
+        UvPattern::Checkers {
+          columns,
+          rows,
+          color_a,
+          color_b,
+        },
+        UvPattern::Checkers {
+          columns: other_columns,
+          rows: other_rows,
+          color_a: other_a,
+          color_b: other_b,
+        },
+      ) => {
+        *columns == other_columns
+          && *rows == other_rows
+          && color_a.fuzzy_eq(other_a)
+          && color_b.fuzzy_eq(other_b)
+      }
+      (UvPattern::Image(texture), UvPattern::Image(other)) => texture.fuzzy_eq(other),
+      (
+        UvPattern::AlignCheck {
+          main,
+          upper_left,
+          upper_right,
+          bottom_left,
+          bottom_right,
+        },
+        UvPattern::AlignCheck {
+          main: other_main,
+          upper_left: other_upper_left,
+          upper_right: other_upper_right,
+          bottom_left: other_bottom_left,
+          bottom_right: other_bottom_right,
+        },
+      ) => {
+        main.fuzzy_eq(other_main)
+          && upper_left.fuzzy_eq(other_upper_left)
+          && upper_right.fuzzy_eq(other_upper_right)
+          && bottom_left.fuzzy_eq(other_bottom_left)
+          && bottom_right.fuzzy_eq(other_bottom_right)
+      }
+      _ => false,
+    }
+  }
+}
+
+/// Pattern painting a flat UV pattern onto bodies, using either a chosen
+/// projection or the usual one of each body (see `Body::uv_at`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextureMap {
+  pub pattern: UvPattern,
+  pub map: Option<UvMap>,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+}
+
+impl TextureMap {
+  pub fn new(pattern: UvPattern) -> Self {
+    Self {
+      pattern,
+      map: None,
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+    }
+  }
+
+  pub fn with_map(mut self, map: UvMap) -> Self {
+    self.map = Some(map);
+    self
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+}
+
+impl FuzzyEq<TextureMap> for TextureMap {
+  fn fuzzy_eq(&self, other: TextureMap) -> bool {
+    self.pattern.fuzzy_eq(other.pattern)
+      && self.map == other.map
+      && self.transform.fuzzy_eq(other.transform)
+  }
+}
+
+impl Stencil for TextureMap {
+  /// Without a body, points are mapped onto the xz plane unless another
+  /// projection is chosen
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    let (u, v) = self.map.unwrap_or(UvMap::Planar).uv_at(position);
+    self.pattern.color_at_uv(u, v)
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+
+  fn color_at(&self, position: Tuple, body: &Body) -> Color {
+    let object_position = body.inverse_transform() * position;
+    let pattern_position = self.inverse_transform * object_position;
+
+    let (u, v) = match self.map {
+      Some(map) => map.uv_at(pattern_position),
+      None => body.uv_at(pattern_position),
+    };
+    self.pattern.color_at_uv(u, v)
+  }
+}
+
+/// Pattern showing a separate UV pattern on each face of the cube around
+/// the origin, e.g. the six images of a skybox.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CubeMap {
+  pub faces: Arc<[UvPattern; 6]>,
+  transform: Matrix<4>,
+  inverse_transform: Matrix<4>,
+}
+
+impl CubeMap {
+  /// Faces in the order left, front, right, back, up and down
+  pub fn new(faces: [UvPattern; 6]) -> Self {
+    Self {
+      faces: Arc::new(faces),
+      transform: Matrix::identity(),
+      inverse_transform: Matrix::identity(),
+    }
+  }
+
+  pub fn with_transform(mut self, transform: Matrix<4>) -> Self {
+    self.transform = transform;
+    self.inverse_transform = transform.inverse();
+    self
+  }
+
+  pub fn face(&self, face: CubeFace) -> &UvPattern {
+    &self.faces[face as usize]
+  }
+}
+
+impl FuzzyEq<CubeMap> for CubeMap {
+  fn fuzzy_eq(&self, other: CubeMap) -> bool {
+    self
+      .faces
+      .iter()
+      .zip(other.faces.iter())
+      .all(|(face, other)| face.fuzzy_eq(other.clone()))
+      && self.transform.fuzzy_eq(other.transform)
+  }
+}
+
+impl Stencil for CubeMap {
+  fn color_at_in_pattern_space(&self, position: Tuple) -> Color {
+    let (u, v) = cubic_map(position);
+    self.face(CubeFace::from_point(position)).color_at_uv(u, v)
+  }
+
+  fn transform(&self) -> Matrix<4> {
+    self.transform
+  }
+
+  fn inverse_transform(&self) -> Matrix<4> {
+    self.inverse_transform
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::sphere::Sphere;
+
+  #[test]
+  fn using_a_spherical_mapping_on_a_3d_point() {
+    let examples = [
+      (Tuple::point(0.0, 0.0, -1.0), (0.0, 0.5)),
+      (Tuple::point(1.0, 0.0, 0.0), (0.25, 0.5)),
+      (Tuple::point(0.0, 0.0, 1.0), (0.5, 0.5)),
+      (Tuple::point(-1.0, 0.0, 0.0), (0.75, 0.5)),
+      (Tuple::point(0.0, 1.0, 0.0), (0.5, 1.0)),
+      (Tuple::point(0.0, -1.0, 0.0), (0.5, 0.0)),
+    ];
+
+    for (point, (u, v)) in examples.iter() {
+      let (actual_u, actual_v) = spherical_map(*point);
+      assert_fuzzy_eq!(actual_u, *u);
+      assert_fuzzy_eq!(actual_v, *v);
+    }
+  }
+
+  #[test]
+  fn using_a_planar_mapping_on_a_3d_point() {
+    assert_eq!(planar_map(Tuple::point(0.25, 0.0, 0.5)), (0.25, 0.5));
+    assert_eq!(planar_map(Tuple::point(-0.25, 0.0, -0.75)), (0.75, 0.25));
+    assert_eq!(planar_map(Tuple::point(1.25, 0.5, -1.75)), (0.25, 0.25));
+  }
+
+  #[test]
+  fn using_a_cylindrical_mapping_on_a_3d_point() {
+    let (u, v) = cylindrical_map(Tuple::point(1.0, 0.25, 0.0));
+
+    assert_fuzzy_eq!(u, 0.25);
+    assert_fuzzy_eq!(v, 0.25);
+  }
+
+  #[test]
+  fn using_a_cubic_mapping_on_each_face() {
+    let examples = [
+      // Front, the lower left corner seen from outside
+      (Tuple::point(-0.5, -0.5, -1.0), (0.25, 0.25)),
+      // Right, next to the front
+      (Tuple::point(1.0, 0.5, -0.5), (0.25, 0.75)),
+      // Back
+      (Tuple::point(0.5, 0.5, 1.0), (0.25, 0.75)),
+      // Left, away from the front
+      (Tuple::point(-1.0, -0.5, 0.5), (0.25, 0.25)),
+      // Top, next to the front
+      (Tuple::point(-0.5, 1.0, -0.5), (0.25, 0.25)),
+      // Bottom, next to the front
+      (Tuple::point(-0.5, -1.0, -0.5), (0.25, 0.75)),
+    ];
+
+    for (point, (u, v)) in examples.iter() {
+      let (actual_u, actual_v) = cubic_map(*point);
+      assert_fuzzy_eq!(actual_u, *u);
+      assert_fuzzy_eq!(actual_v, *v);
+    }
+  }
+
+  #[test]
+  fn points_far_from_the_center_are_projected_onto_the_cube() {
+    assert_eq!(
+      CubeFace::from_point(Tuple::point(-1.0, 0.5, -0.25)),
+      CubeFace::Left
+    );
+    assert_eq!(
+      CubeFace::from_point(Tuple::point(0.5, -0.1, -0.9)),
+      CubeFace::Front
+    );
+    assert_eq!(
+      CubeFace::from_point(Tuple::point(-0.4, 1.1, 0.2)),
+      CubeFace::Up
+    );
+
+    let (u, v) = cubic_map(Tuple::point(-10.0, -10.0, -20.0));
+    assert_fuzzy_eq!(u, 0.25);
+    assert_fuzzy_eq!(v, 0.25);
+  }
+
+  #[test]
+  fn align_checks_mark_the_corners_of_the_texture() {
+    let red = Color::new(1.0, 0.0, 0.0);
+    let yellow = Color::new(1.0, 1.0, 0.0);
+    let green = Color::new(0.0, 1.0, 0.0);
+    let cyan = Color::new(0.0, 1.0, 1.0);
+    let pattern = UvPattern::AlignCheck {
+      main: Color::white(),
+      upper_left: red,
+      upper_right: yellow,
+      bottom_left: green,
+      bottom_right: cyan,
+    };
+
+    assert_fuzzy_eq!(pattern.color_at_uv(0.5, 0.5), Color::white());
+    assert_fuzzy_eq!(pattern.color_at_uv(0.1, 0.9), red);
+    assert_fuzzy_eq!(pattern.color_at_uv(0.9, 0.9), yellow);
+    assert_fuzzy_eq!(pattern.color_at_uv(0.1, 0.1), green);
+    assert_fuzzy_eq!(pattern.color_at_uv(0.9, 0.1), cyan);
+  }
+
+  #[test]
+  fn cube_maps_show_a_pattern_on_each_face() {
+    let solid = |color: Color| UvPattern::checkers(1, 1, color, color);
+    let colors = [
+      Color::new(1.0, 1.0, 0.0),
+      Color::new(0.0, 1.0, 1.0),
+      Color::new(1.0, 0.0, 0.0),
+      Color::new(0.0, 1.0, 0.0),
+      Color::new(1.0, 0.5, 0.0),
+      Color::new(1.0, 0.0, 1.0),
+    ];
+    let pattern = CubeMap::new(colors.map(solid));
+
+    let examples = [
+      (Tuple::point(-1.0, 0.5, 0.0), colors[0]),
+      (Tuple::point(0.0, -0.5, -1.0), colors[1]),
+      (Tuple::point(1.0, 0.5, 0.5), colors[2]),
+      (Tuple::point(0.5, 0.5, 1.0), colors[3]),
+      (Tuple::point(0.0, 1.0, 0.5), colors[4]),
+      (Tuple::point(0.5, -1.0, 0.0), colors[5]),
+    ];
+    for (point, color) in examples.iter() {
+      assert_fuzzy_eq!(pattern.color_at_in_pattern_space(*point), *color);
+    }
+  }
+
+  #[test]
+  fn checkers_in_uv_space_have_the_same_count_of_squares_around_a_sphere() {
+    let pattern = TextureMap::new(UvPattern::checkers(16, 8, Color::black(), Color::white()));
+    let sphere = Body::from(Sphere::default().with_transform(Matrix::scaling(2.0, 2.0, 2.0)));
+
+    // Near the pole, a short way around the sphere crosses several squares
+    let angle = |degrees: F| {
+      let radians = degrees.to_radians();
+      Tuple::point(0.2 * radians.sin(), 1.98, -0.2 * radians.cos())
+    };
+    assert_fuzzy_eq!(pattern.color_at(angle(10.0), &sphere), Color::white());
+    assert_fuzzy_eq!(pattern.color_at(angle(30.0), &sphere), Color::black());
+  }
+
+  #[test]
+  fn a_chosen_projection_replaces_the_one_of_the_body() {
+    let pattern = TextureMap::new(UvPattern::checkers(2, 2, Color::black(), Color::white()))
+      .with_map(UvMap::Cubic);
+    let sphere = Body::from(Sphere::default());
+
+    // The front of the cube around the sphere
+    assert_fuzzy_eq!(
+      pattern.color_at(Tuple::point(-0.2, -0.2, -0.9), &sphere),
+      Color::black()
+    );
+    assert_fuzzy_eq!(
+      pattern.color_at(Tuple::point(0.2, -0.2, -0.9), &sphere),
+      Color::white()
+    );
+  }
+}
@@ -0,0 +1,1859 @@
+use crate::body::{Body, Intersectable};
+use crate::bounds::Bounds;
+use crate::camera::Camera;
+use crate::canvas::Color;
+use crate::computed_intersection::ComputedIntersection;
+use crate::contact_shadows::ContactShadows;
+use crate::fingerprint::Fingerprint;
+use crate::frustum::Frustum;
+use crate::fuzzy_eq::FuzzyEq;
+use crate::instance::Instance;
+use crate::intersections::{Intersection, Intersections};
+use crate::layer::{self, Layer, DEFAULT_LAYER};
+use crate::light::{Light, PointLight};
+use crate::material::{
+  Illuminated, LightingPass, Material, MaterialRegistry, Phong, Reflective, Refractive,
+};
+use crate::matrix::Matrix;
+use crate::motion::MovingBody;
+use crate::photon_map::{Caustics, PhotonMap};
+use crate::ray::Ray;
+use crate::renderer::cost;
+use crate::sphere::Sphere;
+use crate::sweep::Sweep;
+use crate::tuple::Tuple;
+use crate::F;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct World {
+  bodies: Vec<Body>,
+  instances: Vec<Instance>,
+  moving_bodies: Vec<MovingBody>,
+  lights: Vec<Light>,
+  reflection_limit: usize,
+  pub contact_shadows: Option<ContactShadows>,
+  /// Light focused by glass and mirrors, see `with_caustics`
+  pub caustics: Option<Caustics>,
+  /// Photons traced for the caustics, shared between clones of the world
+  photon_map: Option<Arc<PhotonMap>>,
+  /// Color of rays leaving the world without hitting anything
+  pub background: Color,
+  /// Color of every body hit while the world has no lights at all, to still
+  /// show their silhouettes
+  pub unlit_color: Color,
+  /// How many reflected and refracted rays the samples of a single pixel may
+  /// spawn in total, unlimited if not set
+  pub secondary_ray_budget: Option<usize>,
+  /// Reflections and refractions contributing less than this fraction to the
+  /// color of a camera ray are not traced
+  pub contribution_cutoff: F,
+  /// Lighting terms taken into account when shading
+  pub lighting_pass: LightingPass,
+  /// Bodies, instances and moving bodies carrying each name
+  names: HashMap<String, Layer>,
+  /// Materials shared by name between bodies
+  pub materials: MaterialRegistry,
+  /// Indices of the bodies referencing each registered material
+  material_references: HashMap<String, Vec<usize>>,
+  /// Members of each layer, see `only_layers`
+  layers: HashMap<String, Layer>,
+  /// Bodies and instances camera rays skip, as they lie outside of the
+  /// frustum of the camera
+  outside_frustum: OutsideFrustum,
+  /// Changes of named bodies over the frames of an animation, see `swept`
+  pub sweeps: Vec<Sweep>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct OutsideFrustum {
+  bodies: Vec<bool>,
+  instances: Vec<bool>,
+}
+
+/// Elements at the given indices, leaving out indices out of range
+fn members<'a, T>(elements: &'a [T], indices: &[usize]) -> Vec<&'a T> {
+  indices
+    .iter()
+    .filter_map(|index| elements.get(*index))
+    .collect()
+}
+
+/// Whether each of `count` elements is one at the given indices
+fn flags(count: usize, indices: &[usize]) -> Vec<bool> {
+  let mut flags = vec![false; count];
+  for index in indices.iter().filter(|index| **index < count) {
+    flags[*index] = true;
+  }
+  flags
+}
+
+impl World {
+  pub fn new(bodies: Vec<Body>, lights: Vec<Light>) -> Self {
+    // FIXME: Make reflection_limit configurable
+    // FIXME: Switch to builder pattern
+    World {
+      bodies,
+      lights,
+      ..Default::default()
+    }
+  }
+
+  /// The default world of the book: two nested unit spheres, the outer one
+  /// greenish, lit by a white light from the top left. Pairs with
+  /// `Camera::book_default`.
+  pub fn book_default() -> Self {
+    let outer = Phong::default()
+      .with_color(Color::new(0.8, 1.0, 0.6))
+      .with_diffuse(0.7)
+      .with_specular(0.2);
+    World::new(
+      vec![
+        Body::from(Sphere::default().with_material(Material::from(outer))),
+        Body::from(Sphere::default().with_transform(Matrix::scaling(0.5, 0.5, 0.5))),
+      ],
+      vec![PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::white()).into()],
+    )
+  }
+
+  /// Bodies of the world, which can be changed in place. Names, material
+  /// references and layers refer to bodies, instances, moving bodies and
+  /// lights by their index, so none are added or removed afterwards.
+  pub fn bodies(&self) -> &[Body] {
+    &self.bodies
+  }
+
+  pub fn bodies_mut(&mut self) -> &mut [Body] {
+    &mut self.bodies
+  }
+
+  pub fn instances(&self) -> &[Instance] {
+    &self.instances
+  }
+
+  pub fn instances_mut(&mut self) -> &mut [Instance] {
+    &mut self.instances
+  }
+
+  pub fn moving_bodies(&self) -> &[MovingBody] {
+    &self.moving_bodies
+  }
+
+  pub fn moving_bodies_mut(&mut self) -> &mut [MovingBody] {
+    &mut self.moving_bodies
+  }
+
+  pub fn lights(&self) -> &[Light] {
+    &self.lights
+  }
+
+  pub fn lights_mut(&mut self) -> &mut [Light] {
+    &mut self.lights
+  }
+
+  /// Replaces the lights, which then light every layer
+  pub fn with_lights(mut self, lights: Vec<Light>) -> Self {
+    self.lights = lights;
+    for layer in self.layers.values_mut() {
+      layer.lights.clear();
+    }
+    self
+  }
+
+  pub fn with_instances(mut self, instances: Vec<Instance>) -> Self {
+    self.instances = instances;
+    self
+  }
+
+  pub fn with_moving_bodies(mut self, moving_bodies: Vec<MovingBody>) -> Self {
+    self.moving_bodies = moving_bodies;
+    self
+  }
+
+  pub fn with_contact_shadows(mut self, contact_shadows: ContactShadows) -> Self {
+    self.contact_shadows = Some(contact_shadows);
+    self
+  }
+
+  /// Traces photons through the reflective and transparent bodies of the
+  /// world to render the light they focus. Needs to be called after all
+  /// bodies and lights are added, as they are only traced once.
+  pub fn with_caustics(mut self, caustics: Caustics) -> Self {
+    self.photon_map = Some(Arc::new(PhotonMap::trace(&self, &caustics)));
+    self.caustics = Some(caustics);
+    self
+  }
+
+  /// Traces the photons of the caustics again, after bodies changed
+  fn retrace_caustics(self) -> Self {
+    match self.caustics {
+      Some(caustics) => self.with_caustics(caustics),
+      None => self,
+    }
+  }
+
+  /// Replaces the material of every body, instance and moving body, like
+  /// for a clay render with `Phong::clay`
+  pub fn with_material_override(mut self, material: Material) -> Self {
+    for body in self.bodies.iter_mut() {
+      *body = body.clone().with_material(material.clone());
+    }
+    for instance in self.instances.iter_mut() {
+      instance.material = Some(material.clone());
+    }
+    for moving_body in self.moving_bodies.iter_mut() {
+      *moving_body = moving_body.clone().with_material(material.clone());
+    }
+    self.retrace_caustics()
+  }
+
+  /// Renders only the given terms of the lighting, both on surfaces and in
+  /// their reflections and refractions
+  pub fn with_lighting_pass(mut self, lighting_pass: LightingPass) -> Self {
+    self.lighting_pass = lighting_pass;
+    self
+  }
+
+  pub fn with_background(mut self, background: Color) -> Self {
+    self.background = background;
+    self
+  }
+
+  pub fn with_unlit_color(mut self, unlit_color: Color) -> Self {
+    self.unlit_color = unlit_color;
+    self
+  }
+
+  /// Bounds the work done for a single pixel, so mirrors facing each other
+  /// do not multiply the rays with every bounce. The budget is shared by all
+  /// samples of the pixel traced together. Once spent, further reflections
+  /// and refractions see the background instead.
+  pub fn with_secondary_ray_budget(mut self, secondary_ray_budget: usize) -> Self {
+    self.secondary_ray_budget = Some(secondary_ray_budget);
+    self
+  }
+
+  /// Skips reflections and refractions, whose share of the final color,
+  /// like the product of the reflectiveness along a chain of mirrors, falls
+  /// below the given fraction. A cutoff of 0 traces everything.
+  pub fn with_contribution_cutoff(mut self, contribution_cutoff: F) -> Self {
+    self.contribution_cutoff = contribution_cutoff;
+    self
+  }
+
+  /// Names the bodies at the given indices, e.g. all triangles of a mesh
+  pub fn with_name(
+    mut self,
+    name: impl Into<String>,
+    indices: impl IntoIterator<Item = usize>,
+  ) -> Self {
+    self
+      .names
+      .entry(name.into())
+      .or_default()
+      .bodies
+      .extend(indices);
+    self
+  }
+
+  /// Names the instances at the given indices, e.g. all copies of a mesh
+  pub fn with_instance_name(
+    mut self,
+    name: impl Into<String>,
+    indices: impl IntoIterator<Item = usize>,
+  ) -> Self {
+    self
+      .names
+      .entry(name.into())
+      .or_default()
+      .instances
+      .extend(indices);
+    self
+  }
+
+  pub fn with_moving_body_name(
+    mut self,
+    name: impl Into<String>,
+    indices: impl IntoIterator<Item = usize>,
+  ) -> Self {
+    self
+      .names
+      .entry(name.into())
+      .or_default()
+      .moving_bodies
+      .extend(indices);
+    self
+  }
+
+  pub fn with_sweeps(mut self, sweeps: Vec<Sweep>) -> Self {
+    self.sweeps = sweeps;
+    self
+  }
+
+  /// The world at `t` between 0 (first frame) and 1 (last frame) of its
+  /// sweeps
+  pub fn swept(&self, t: F) -> World {
+    if self.sweeps.is_empty() {
+      return self.clone();
+    }
+    let mut world = self.clone();
+    for sweep in self.sweeps.iter() {
+      sweep.apply(&mut world, t);
+    }
+    world.retrace_caustics()
+  }
+
+  /// Adds the members to the layer of the given name
+  pub fn with_layer(mut self, name: impl Into<String>, members: Layer) -> Self {
+    self.layers.entry(name.into()).or_default().merge(members);
+    self
+  }
+
+  /// Names of all layers, sorted alphabetically. The default layer is only
+  /// listed if something is explicitly assigned to it.
+  pub fn layer_names(&self) -> Vec<&str> {
+    let mut names: Vec<&str> = self.layers.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    names
+  }
+
+  /// Copy of the world with only the members of the given layers.
+  ///
+  /// Bodies, instances and moving bodies without a layer belong to the
+  /// `DEFAULT_LAYER`. Lights without a layer light every layer and are
+  /// always kept.
+  pub fn only_layers(&self, names: &[&str]) -> World {
+    let keep = |count: usize, members: fn(&Layer) -> &Vec<usize>, untagged_kept: bool| {
+      let mut tagged = vec![false; count];
+      let mut kept = vec![false; count];
+      for (name, layer) in self.layers.iter() {
+        let selected = names.contains(&name.as_str());
+        for index in members(layer).iter().filter(|index| **index < count) {
+          tagged[*index] = true;
+          kept[*index] |= selected;
+        }
+      }
+      kept
+        .iter()
+        .zip(tagged.iter())
+        .map(|(kept, tagged)| *kept || (!tagged && untagged_kept))
+        .collect::<Vec<bool>>()
+    };
+    let default_selected = names.contains(&DEFAULT_LAYER);
+    let bodies = layer::reindex(&keep(self.bodies.len(), |l| &l.bodies, default_selected));
+    let instances = layer::reindex(&keep(
+      self.instances.len(),
+      |l| &l.instances,
+      default_selected,
+    ));
+    let moving_bodies = layer::reindex(&keep(
+      self.moving_bodies.len(),
+      |l| &l.moving_bodies,
+      default_selected,
+    ));
+    let lights = layer::reindex(&keep(self.lights.len(), |l| &l.lights, true));
+
+    fn kept<T: Clone>(elements: &[T], new_indices: &[Option<usize>]) -> Vec<T> {
+      elements
+        .iter()
+        .zip(new_indices.iter())
+        .filter(|(_, index)| index.is_some())
+        .map(|(element, _)| element.clone())
+        .collect()
+    }
+    let remap_members = |members: &HashMap<String, Layer>| -> HashMap<String, Layer> {
+      members
+        .iter()
+        .map(|(name, members)| {
+          let members = Layer {
+            bodies: layer::remap(&members.bodies, &bodies),
+            instances: layer::remap(&members.instances, &instances),
+            moving_bodies: layer::remap(&members.moving_bodies, &moving_bodies),
+            lights: layer::remap(&members.lights, &lights),
+          };
+          (name.clone(), members)
+        })
+        .collect()
+    };
+
+    World {
+      bodies: kept(&self.bodies, &bodies),
+      instances: kept(&self.instances, &instances),
+      moving_bodies: kept(&self.moving_bodies, &moving_bodies),
+      lights: kept(&self.lights, &lights),
+      names: remap_members(&self.names),
+      material_references: self
+        .material_references
+        .iter()
+        .map(|(name, indices)| (name.clone(), layer::remap(indices, &bodies)))
+        .collect(),
+      layers: remap_members(&self.layers),
+      outside_frustum: OutsideFrustum::default(),
+      reflection_limit: self.reflection_limit,
+      contact_shadows: self.contact_shadows,
+      caustics: self.caustics,
+      photon_map: None,
+      background: self.background,
+      unlit_color: self.unlit_color,
+      secondary_ray_budget: self.secondary_ray_budget,
+      contribution_cutoff: self.contribution_cutoff,
+      lighting_pass: self.lighting_pass,
+      materials: self.materials.clone(),
+      sweeps: self.sweeps.clone(),
+    }
+    .retrace_caustics()
+  }
+
+  /// Registered materials, restyling every body already referencing one
+  pub fn with_materials(mut self, materials: MaterialRegistry) -> Self {
+    self.materials = materials;
+    let names: Vec<String> = self.material_references.keys().cloned().collect();
+    for name in names {
+      self.restyle(&name);
+    }
+    self
+  }
+
+  /// Lets the bodies at the given indices use the registered material of
+  /// the given name, now and whenever it is replaced
+  pub fn with_material_reference(
+    mut self,
+    name: impl Into<String>,
+    indices: impl IntoIterator<Item = usize>,
+  ) -> Self {
+    let name = name.into();
+    self
+      .material_references
+      .entry(name.clone())
+      .or_default()
+      .extend(indices);
+    self.restyle(&name);
+    self
+  }
+
+  /// Registers the material under the given name and restyles every body
+  /// referencing it
+  pub fn set_material(&mut self, name: &str, material: Material) {
+    self.materials.insert(name, material);
+    self.restyle(name);
+  }
+
+  /// Name of the registered material the body at the given index references
+  pub fn material_name_of(&self, index: usize) -> Option<&str> {
+    self
+      .material_references
+      .iter()
+      .find(|(_, indices)| indices.contains(&index))
+      .map(|(name, _)| name.as_str())
+  }
+
+  fn restyle(&mut self, name: &str) {
+    let (material, indices) = match (self.materials.get(name), self.material_references.get(name)) {
+      (Some(material), Some(indices)) => (material, indices),
+      _ => return,
+    };
+    for index in indices.iter() {
+      if let Some(body) = self.bodies.get_mut(*index) {
+        *body = body.clone().with_material(material.clone());
+      }
+    }
+  }
+
+  /// Members carrying the given name, none if nothing does
+  fn named(&self, name: &str) -> Cow<'_, Layer> {
+    match self.names.get(name) {
+      Some(members) => Cow::Borrowed(members),
+      None => Cow::Owned(Layer::default()),
+    }
+  }
+
+  /// First body carrying the given name
+  pub fn body_by_name(&self, name: &str) -> Option<&Body> {
+    let index = *self.names.get(name)?.bodies.first()?;
+    self.bodies.get(index)
+  }
+
+  pub fn body_by_name_mut(&mut self, name: &str) -> Option<&mut Body> {
+    let index = *self.names.get(name)?.bodies.first()?;
+    self.bodies.get_mut(index)
+  }
+
+  /// All bodies carrying the given name
+  pub fn bodies_named(&self, name: &str) -> Vec<&Body> {
+    members(&self.bodies, &self.named(name).bodies)
+  }
+
+  pub fn bodies_named_mut(&mut self, name: &str) -> Vec<&mut Body> {
+    let named = flags(self.bodies.len(), &self.named(name).bodies);
+    self
+      .bodies
+      .iter_mut()
+      .zip(named)
+      .filter(|(_, named)| *named)
+      .map(|(body, _)| body)
+      .collect()
+  }
+
+  /// All instances carrying the given name
+  pub fn instances_named(&self, name: &str) -> Vec<&Instance> {
+    members(&self.instances, &self.named(name).instances)
+  }
+
+  /// All moving bodies carrying the given name
+  pub fn moving_bodies_named(&self, name: &str) -> Vec<&MovingBody> {
+    members(&self.moving_bodies, &self.named(name).moving_bodies)
+  }
+
+  /// Name of the body, instance or moving body the intersection lies on,
+  /// the alphabetically first one if it carries several. Equal bodies lie
+  /// on top of each other, the name of the first one is taken for them.
+  pub fn name_of(&self, intersection: &Intersection) -> Option<&str> {
+    let hit = &intersection.body;
+    let time = intersection.ray.time;
+    let is_hit = |members: &Layer| {
+      members
+        .bodies
+        .iter()
+        .filter_map(|index| self.bodies.get(*index))
+        .any(|body| body == hit)
+        || members
+          .moving_bodies
+          .iter()
+          .filter_map(|index| self.moving_bodies.get(*index))
+          .any(|moving_body| moving_body.body_at(time) == *hit)
+        || members
+          .instances
+          .iter()
+          .filter_map(|index| self.instances.get(*index))
+          .any(|instance| {
+            instance
+              .geometry
+              .bodies()
+              .iter()
+              .any(|body| instance.place(body.clone()) == *hit)
+          })
+    };
+    self
+      .names
+      .iter()
+      .filter(|(_, members)| is_hit(members))
+      .map(|(name, _)| name.as_str())
+      .min()
+  }
+
+  pub fn intersect(&self, ray: Ray) -> Intersections {
+    cost::count_ray();
+    let mut xs = Vec::with_capacity(self.intersection_capacity());
+    xs.extend(self.bodies.iter().flat_map(|body| body.intersect(ray)));
+    xs.extend(
+      self
+        .instances
+        .iter()
+        .flat_map(|instance| instance.intersect(ray)),
+    );
+    xs.extend(
+      self
+        .moving_bodies
+        .iter()
+        .flat_map(|moving_body| moving_body.intersect(ray)),
+    );
+    xs.retain(|intersection| !intersection.is_cut_out());
+    Intersections::new(xs)
+  }
+
+  /// Most intersections a single ray can have with the whole world
+  pub fn max_intersections(&self) -> usize {
+    self
+      .bodies
+      .iter()
+      .map(|body| body.max_intersections())
+      .chain(
+        self
+          .instances
+          .iter()
+          .map(|instance| instance.geometry.max_intersections()),
+      )
+      .chain(
+        self
+          .moving_bodies
+          .iter()
+          .map(|moving_body| moving_body.max_intersections()),
+      )
+      .sum()
+  }
+
+  /// Intersections reserved up front for every ray. Worlds of a few bodies
+  /// get all they could need, while large meshes, whose rays hit only a
+  /// fraction of their triangles, grow the vector as needed instead.
+  fn intersection_capacity(&self) -> usize {
+    const MAX_RESERVED: usize = 64;
+    if self.bodies.len() + self.instances.len() + self.moving_bodies.len() > MAX_RESERVED {
+      return MAX_RESERVED;
+    }
+    self.max_intersections().min(MAX_RESERVED)
+  }
+
+  /// Flags the bodies and instances lying entirely outside of the frustum of
+  /// the camera, which its rays then skip. They still cast shadows and show
+  /// up in reflections and refractions.
+  ///
+  /// Cameras with depth of field see more than their frustum, nothing is
+  /// flagged for them.
+  pub fn with_frustum_culling(mut self, camera: &Camera) -> Self {
+    let frustum = match Frustum::of_camera(camera) {
+      Some(frustum) => frustum,
+      None => {
+        self.outside_frustum = OutsideFrustum::default();
+        return self;
+      }
+    };
+    self.outside_frustum = OutsideFrustum {
+      bodies: self
+        .bodies
+        .iter()
+        .map(|body| !frustum.may_contain(body.bounds()))
+        .collect(),
+      instances: self
+        .instances
+        .iter()
+        .map(|instance| !frustum.may_contain(instance.bounds()))
+        .collect(),
+    };
+    self
+  }
+
+  /// The world to render the camera with, culled to its frustum if the
+  /// camera asks for it
+  pub fn for_camera(&self, camera: &Camera) -> Cow<'_, World> {
+    if camera.frustum_culling {
+      Cow::Owned(self.clone().with_frustum_culling(camera))
+    } else {
+      Cow::Borrowed(self)
+    }
+  }
+
+  /// Content hash of everything deciding how the world looks through the
+  /// camera. Names, layers and the registered materials are left out, as
+  /// they do not change the image on their own.
+  pub fn fingerprint(&self, camera: &Camera) -> Fingerprint {
+    Fingerprint::of(&[
+      &self.bodies,
+      &self.instances,
+      &self.moving_bodies,
+      &self.lights,
+      &self.reflection_limit,
+      &self.contact_shadows,
+      &self.caustics,
+      &self.background,
+      &self.unlit_color,
+      &self.secondary_ray_budget,
+      &self.contribution_cutoff,
+      &self.lighting_pass,
+      camera,
+    ])
+  }
+
+  /// Number of bodies and instances flagged by `with_frustum_culling`
+  pub fn culled_count(&self) -> usize {
+    let flagged = |flags: &[bool]| flags.iter().filter(|outside| **outside).count();
+    flagged(&self.outside_frustum.bodies) + flagged(&self.outside_frustum.instances)
+  }
+
+  /// Intersects the ray like `intersect`, but leaves out the bodies and
+  /// instances outside of the camera frustum
+  fn intersect_camera_ray(&self, ray: Ray) -> Intersections {
+    cost::count_ray();
+    let outside = |flags: &[bool], index: usize| flags.get(index).copied().unwrap_or(false);
+    let mut xs = Vec::with_capacity(self.intersection_capacity());
+    xs.extend(
+      self
+        .bodies
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !outside(&self.outside_frustum.bodies, *index))
+        .flat_map(|(_, body)| body.intersect(ray)),
+    );
+    xs.extend(
+      self
+        .instances
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !outside(&self.outside_frustum.instances, *index))
+        .flat_map(|(_, instance)| instance.intersect(ray)),
+    );
+    xs.extend(
+      self
+        .moving_bodies
+        .iter()
+        .flat_map(|moving_body| moving_body.intersect(ray)),
+    );
+    xs.retain(|intersection| !intersection.is_cut_out());
+    Intersections::new(xs)
+  }
+
+  /// Whether a body casting shadows lies on the ray closer than the given
+  /// distance. Rays pass through the parts cut out of bodies.
+  ///
+  /// Unlike `intersect`, the intersections are neither collected nor sorted,
+  /// the search stops at the first blocker found.
+  pub fn is_blocked(&self, ray: Ray, distance: F) -> bool {
+    cost::count_ray();
+    let blocks = |intersection: &Intersection| {
+      intersection.t > 0.0
+        && intersection.t < distance
+        && intersection.body.shadows().cast
+        && !intersection.is_cut_out()
+    };
+    self
+      .bodies
+      .iter()
+      .any(|body| body.intersect(ray).into_iter().any(|hit| blocks(&hit)))
+      || self
+        .instances
+        .iter()
+        .any(|instance| instance.intersect(ray).iter().any(blocks))
+      || self
+        .moving_bodies
+        .iter()
+        .any(|moving_body| moving_body.intersect(ray).iter().any(blocks))
+  }
+
+  /// Fraction of the light passing along the ray up to the given distance.
+  ///
+  /// Every surface of a body casting shadows lets its transparency through,
+  /// so glass throws a lighter shadow. Like `is_blocked`, the search stops
+  /// at the first opaque blocker.
+  pub fn transmittance(&self, ray: Ray, distance: F) -> F {
+    cost::count_ray();
+    let blockers = self
+      .bodies
+      .iter()
+      .flat_map(|body| body.intersect(ray))
+      .chain(
+        self
+          .instances
+          .iter()
+          .flat_map(|instance| instance.intersect(ray)),
+      )
+      .chain(
+        self
+          .moving_bodies
+          .iter()
+          .flat_map(|moving_body| moving_body.intersect(ray)),
+      )
+      .filter(|intersection| {
+        intersection.t > 0.0
+          && intersection.t < distance
+          && intersection.body.shadows().cast
+          && !intersection.is_cut_out()
+      });
+
+    let mut transmittance = 1.0;
+    for blocker in blockers {
+      let material = blocker.body.material();
+      transmittance *= material.transparency_at(&blocker.body, ray.position(blocker.t));
+      if transmittance <= 0.0 {
+        return 0.0;
+      }
+    }
+    transmittance
+  }
+
+  /// Bounds of all finite bodies and instances, unbounded ones like planes
+  /// are left out
+  pub fn bounds(&self) -> Option<Bounds> {
+    self
+      .bodies
+      .iter()
+      .map(|body| body.bounds())
+      .chain(self.instances.iter().map(|instance| instance.bounds()))
+      .chain(
+        self
+          .moving_bodies
+          .iter()
+          .map(|moving_body| moving_body.bounds()),
+      )
+      .filter(|bounds| !bounds.is_infinite())
+      .reduce(|bounds, other| bounds.union(other))
+  }
+
+  /// How often a ray is reflected or refracted at most
+  pub fn reflection_limit(&self) -> usize {
+    self.reflection_limit
+  }
+
+  /// Color seen along a camera ray, which has the secondary ray budget of a
+  /// whole pixel to itself
+  pub fn color_at(&self, ray: Ray) -> Color {
+    self.color_at_within_budget(ray, &mut self.secondary_rays())
+  }
+
+  /// Color seen along a camera ray, spending the secondary rays left in the
+  /// budget of its pixel
+  pub fn color_at_within_budget(&self, ray: Ray, secondary_rays: &mut usize) -> Color {
+    let xs = self.intersect_camera_ray(ray);
+    self.color_of_intersections(ray, xs, self.reflection_limit, 1.0, secondary_rays)
+  }
+
+  /// Secondary rays a pixel may spawn, see `with_secondary_ray_budget`
+  pub fn secondary_rays(&self) -> usize {
+    self.secondary_ray_budget.unwrap_or(usize::MAX)
+  }
+
+  fn color_at_with_reflection_limit(
+    &self,
+    ray: Ray,
+    remaining_reflections: usize,
+    weight: F,
+    secondary_rays: &mut usize,
+  ) -> Color {
+    let xs = self.intersect(ray);
+    self.color_of_intersections(ray, xs, remaining_reflections, weight, secondary_rays)
+  }
+
+  fn color_of_intersections(
+    &self,
+    ray: Ray,
+    xs: Intersections,
+    remaining_reflections: usize,
+    weight: F,
+    secondary_rays: &mut usize,
+  ) -> Color {
+    let hit = xs.hit();
+    if hit.is_some() && self.lights.is_empty() {
+      return self.unlit_color;
+    }
+    if let Some(hit) = hit {
+      let c = hit.get_computed_with(&xs);
+      let material = hit.body.material();
+      let receives_shadows = hit.body.shadows().receive;
+      // @TODO: Implement proper lighting using multiple light sources
+      let transmittance = if receives_shadows {
+        self.light_transmittance(c.over_point, ray.time)
+      } else {
+        1.0
+      };
+      let mut terms = material
+        .lighting_terms(
+          &hit.body,
+          self.lights[0],
+          c.over_point,
+          c.eyev,
+          c.normalv,
+          transmittance <= 0.0,
+        )
+        .dimmed(transmittance);
+      terms.diffuse = terms.diffuse + self.caustic_light(&material, &c);
+      let terms = terms.only(self.lighting_pass);
+      let mut surface_color = terms.total();
+
+      if let Some(contact_shadows) = self.contact_shadows.filter(|_| receives_shadows) {
+        let occlusion = contact_shadows.occlusion(self, c.over_point, c.normalv, ray.time);
+        surface_color = surface_color - terms.ambient * occlusion;
+      }
+
+      let reflected_color =
+        self.reflected_color_at(&material, &c, remaining_reflections, weight, secondary_rays);
+      let refracted_color =
+        self.refracted_color_at(&material, &c, remaining_reflections, weight, secondary_rays);
+
+      let reflectiveness = material.reflectiveness_at(&hit.body, c.point, c.eyev, c.normalv);
+      if reflectiveness > 0.0 && material.transparency_at(&hit.body, c.point) > 0.0 {
+        // Like glass, the surface reflects more of the light at grazing angles
+        let reflectance = c.schlick();
+        surface_color + reflected_color * reflectance + refracted_color * (1.0 - reflectance)
+      } else {
+        surface_color + reflected_color + refracted_color
+      }
+    } else {
+      self.background
+    }
+  }
+
+  /// Ambient and diffuse light at a point of the body facing the normal,
+  /// independent of the viewer, e.g. to bake it into a mesh
+  pub fn diffuse_light_at(&self, body: &Body, position: Tuple, normalv: Tuple) -> Color {
+    if self.lights.is_empty() {
+      return self.unlit_color;
+    }
+    let transmittance = if body.shadows().receive {
+      self.light_transmittance(position, 0.0)
+    } else {
+      1.0
+    };
+    let terms = body
+      .material()
+      .lighting_terms(
+        body,
+        self.lights[0],
+        position,
+        normalv,
+        normalv,
+        transmittance <= 0.0,
+      )
+      .dimmed(transmittance);
+    terms.ambient + terms.diffuse
+  }
+
+  /// Diffuse light of the caustics reaching the surface
+  fn caustic_light(&self, material: &Material, c: &ComputedIntersection) -> Color {
+    let (photon_map, caustics) = match (&self.photon_map, self.caustics) {
+      (Some(photon_map), Some(caustics)) => (photon_map, caustics),
+      _ => return Color::black(),
+    };
+    match photon_map.irradiance(c.point, c.normalv, caustics.radius) {
+      Some((irradiance, direction)) => {
+        // Shaded like a light in the direction the photons come from, whose
+        // intensity is divided by the cosine the shading multiplies with
+        let cos = (-direction).dot(c.normalv).max(0.1);
+        let light = PointLight::new(c.over_point - direction, irradiance * (1.0 / cos));
+        material
+          .lighting_terms(
+            &c.intersection.body,
+            light.into(),
+            c.over_point,
+            c.eyev,
+            c.normalv,
+            false,
+          )
+          .diffuse
+      }
+      None => Color::black(),
+    }
+  }
+
+  fn reflected_color_at(
+    &self,
+    material: &Material,
+    computed_intersection: &ComputedIntersection,
+    remaining_reflections: usize,
+    weight: F,
+    secondary_rays: &mut usize,
+  ) -> Color {
+    let reflectiveness = material.reflectiveness_at(
+      &computed_intersection.intersection.body,
+      computed_intersection.point,
+      computed_intersection.eyev,
+      computed_intersection.normalv,
+    );
+    if reflectiveness == 0.0 || remaining_reflections == 0 {
+      // We hit a non reflective body
+      return Color::black();
+    }
+    let weight = weight * reflectiveness;
+    if weight < self.contribution_cutoff {
+      return Color::black();
+    }
+    if *secondary_rays == 0 {
+      return self.background * reflectiveness;
+    }
+    *secondary_rays -= 1;
+    let reflected_ray = Ray::new(
+      computed_intersection.over_point,
+      computed_intersection.reflectv,
+    )
+    .with_time(computed_intersection.intersection.ray.time);
+    let reflected_color = self.color_at_with_reflection_limit(
+      reflected_ray,
+      remaining_reflections - 1,
+      weight,
+      secondary_rays,
+    );
+
+    reflected_color * reflectiveness
+  }
+
+  fn refracted_color_at(
+    &self,
+    material: &Material,
+    computed_intersection: &ComputedIntersection,
+    remaining_reflections: usize,
+    weight: F,
+    secondary_rays: &mut usize,
+  ) -> Color {
+    let transparency = material.transparency_at(
+      &computed_intersection.intersection.body,
+      computed_intersection.point,
+    );
+    let weight = weight * transparency;
+    if transparency == 0.0 || remaining_reflections == 0 || weight < self.contribution_cutoff {
+      return Color::black();
+    }
+    let direction = match computed_intersection.refracted_direction() {
+      Some(direction) => direction,
+      // Total internal reflection
+      None => return Color::black(),
+    };
+    if *secondary_rays == 0 {
+      return self.background * transparency;
+    }
+    *secondary_rays -= 1;
+    let refracted_ray = Ray::new(computed_intersection.under_point, direction)
+      .with_time(computed_intersection.intersection.ray.time);
+    let refracted_color = self.color_at_with_reflection_limit(
+      refracted_ray,
+      remaining_reflections - 1,
+      weight,
+      secondary_rays,
+    );
+
+    refracted_color * transparency
+  }
+
+  /// Fraction of the light reaching the position, averaged over all
+  /// directions it arrives from. Zero if the position lies in full shadow.
+  fn light_transmittance(&self, position: Tuple, time: F) -> F {
+    let samples = self.lights[0].sample_directions(position);
+    let transmitted: F = samples
+      .iter()
+      .map(|sample| {
+        let shadow_ray = Ray::new(position, sample.direction).with_time(time);
+        self.transmittance(shadow_ray, sample.distance)
+      })
+      .sum();
+    transmitted / samples.len().max(1) as F
+  }
+}
+
+impl Default for World {
+  fn default() -> Self {
+    World {
+      bodies: vec![],
+      instances: vec![],
+      moving_bodies: vec![],
+      lights: vec![],
+      reflection_limit: 5,
+      contact_shadows: None,
+      caustics: None,
+      photon_map: None,
+      background: Color::black(),
+      unlit_color: Color::black(),
+      secondary_ray_budget: None,
+      contribution_cutoff: 0.01,
+      lighting_pass: LightingPass::All,
+      names: HashMap::new(),
+      materials: MaterialRegistry::new(),
+      material_references: HashMap::new(),
+      outside_frustum: OutsideFrustum::default(),
+      layers: HashMap::new(),
+      sweeps: vec![],
+    }
+  }
+}
+
+impl FuzzyEq<World> for World {
+  fn fuzzy_eq(&self, other: World) -> bool {
+    self.bodies.fuzzy_eq(other.bodies)
+      && self.instances.fuzzy_eq(other.instances)
+      && self.moving_bodies.fuzzy_eq(other.moving_bodies)
+      && self.names == other.names
+      && self.materials.fuzzy_eq(other.materials)
+      && self.material_references == other.material_references
+      && self.lights.fuzzy_eq(other.lights)
+      && self.contact_shadows.fuzzy_eq(other.contact_shadows)
+      && self.caustics.fuzzy_eq(other.caustics)
+      && self.background.fuzzy_eq(other.background)
+      && self.unlit_color.fuzzy_eq(other.unlit_color)
+      && self.secondary_ray_budget == other.secondary_ray_budget
+      && self.contribution_cutoff.fuzzy_eq(other.contribution_cutoff)
+      && self.lighting_pass == other.lighting_pass
+      && self.layers == other.layers
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::body::Shadows;
+  use crate::camera::Camera;
+  use crate::canvas::{Canvas, Color};
+  use crate::fuzzy_eq::*;
+  use crate::instance::Geometry;
+  use crate::intersections::{Intersection, Intersections};
+  use crate::material::{Cutout, Material, MaterialRegistry, Phong};
+  use crate::matrix::Matrix;
+  use crate::pattern::{Pattern, Striped};
+  use crate::plane::Plane;
+  use crate::quad::Quad;
+  use crate::sphere::Sphere;
+  use crate::test_scene::{
+    camera_facing_ball, floor_and_ball, glass, mirror, sphere_at, white_light_at,
+  };
+  use crate::texture::Texture;
+  use crate::tuple::Tuple;
+  use std::f64::consts::SQRT_2;
+  use std::sync::Arc;
+
+  use super::*;
+
+  #[test]
+  fn the_default_world() {
+    let light = Light::from(PointLight::new(
+      Tuple::point(-10.0, 10.0, -10.0),
+      Color::new(1.0, 1.0, 1.0),
+    ));
+    let material = Phong {
+      color: Color::new(0.8, 1.0, 0.6),
+      diffuse: 0.7,
+      specular: 0.2,
+      ..Phong::default()
+    };
+    let s1 = Body::from(Sphere::default().with_material(Material::from(material)));
+    let s2 = Body::from(Sphere::default().with_transform(Matrix::scaling(0.5, 0.5, 0.5)));
+
+    let world = World::book_default();
+
+    assert_eq!(2, world.bodies.len());
+    assert_eq!(1, world.lights.len());
+
+    assert!(world.bodies.contains(&s1));
+    assert!(world.bodies.contains(&s2));
+
+    assert!(world.lights.contains(&light));
+  }
+
+  #[test]
+  fn fingerprints_change_with_the_image_but_not_with_names() {
+    let w = World::book_default();
+    let c = Camera::book_default();
+    let mut moved = World::book_default();
+    moved.bodies[1] = moved.bodies[1]
+      .clone()
+      .with_transform(Matrix::translation(0.0, 0.1, 0.0));
+
+    assert_eq!(w.fingerprint(&c), World::book_default().fingerprint(&c));
+    assert_eq!(
+      w.fingerprint(&c),
+      World::book_default()
+        .with_name("ball", [0])
+        .with_name("core", [1])
+        .fingerprint(&c)
+    );
+    assert_ne!(w.fingerprint(&c), moved.fingerprint(&c));
+    assert_ne!(
+      w.fingerprint(&c),
+      w.fingerprint(&Camera::book_default().with_samples(2))
+    );
+  }
+
+  #[test]
+  fn fingerprints_change_with_the_pixels_of_textures() {
+    let textured = |color: Color| {
+      let mut image = Canvas::new(2, 2);
+      image.write_pixel(1, 1, color);
+      let material = Phong::default().with_pattern(Pattern::from(Texture::new(image)));
+      let sphere = Sphere::default().with_material(Material::from(material));
+      World::new(vec![Body::from(sphere)], vec![])
+    };
+    let c = Camera::book_default();
+
+    assert_eq!(
+      textured(Color::white()).fingerprint(&c),
+      textured(Color::white()).fingerprint(&c)
+    );
+    assert_ne!(
+      textured(Color::white()).fingerprint(&c),
+      textured(Color::red()).fingerprint(&c)
+    );
+  }
+
+  #[test]
+  fn the_book_default_camera_looks_at_the_default_world() {
+    let w = World::book_default();
+    let c = Camera::book_default();
+
+    let color = w.color_at(c.ray_for_pixel(5, 5));
+
+    assert_fuzzy_eq!(color, Color::new(0.38066, 0.47583, 0.2855));
+  }
+
+  #[test]
+  fn intersect_a_world_with_instances() {
+    let geometry = Arc::new(Geometry::new(vec![Body::from(Sphere::default())]));
+    let w = World::book_default().with_instances(vec![
+      Instance::new(geometry.clone()).with_transform(Matrix::translation(0.0, 0.0, 3.0))
+    ]);
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let xs = w.intersect(r);
+
+    assert_eq!(6, xs.len());
+    assert_fuzzy_eq!(xs[5].t, 9.0);
+  }
+
+  #[test]
+  fn a_material_override_applies_to_everything_in_the_world() {
+    let geometry = Arc::new(Geometry::new(vec![Body::from(Sphere::default())]));
+    let clay = Material::from(Phong::clay());
+    let w = World::book_default()
+      .with_instances(vec![Instance::new(geometry)])
+      .with_moving_bodies(vec![MovingBody::new(
+        Body::from(Sphere::default().with_material(Material::from(mirror(1.0)))),
+        Matrix::translation(0.0, 1.0, 0.0),
+      )
+      .unwrap()])
+      .with_material_override(clay.clone());
+
+    assert!(w.bodies.iter().all(|body| body.material() == clay));
+    assert_eq!(w.instances[0].material, Some(clay.clone()));
+    assert_eq!(w.moving_bodies[0].body().material(), clay);
+  }
+
+  #[test]
+  fn world_bounds_leave_out_unbounded_bodies() {
+    let mut w = World::book_default();
+    w.bodies.push(Body::from(Plane::default()));
+    w.bodies.push(Body::from(sphere_at(0.0, 4.0, 0.0)));
+
+    let bounds = w.bounds().unwrap();
+
+    assert_fuzzy_eq!(
+      bounds,
+      Bounds::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 5.0, 1.0))
+    );
+    assert_fuzzy_eq!(bounds.center(), Tuple::point(0.0, 2.0, 0.0));
+    assert!(World::default().bounds().is_none());
+  }
+
+  #[test]
+  fn the_most_intersections_add_up_over_all_bodies() {
+    let geometry = Arc::new(Geometry::new(vec![
+      Plane::default().into(),
+      Sphere::default().into(),
+    ]));
+    let w = World::book_default().with_instances(vec![Instance::new(geometry)]);
+
+    assert_eq!(w.max_intersections(), 7);
+    assert_eq!(World::default().max_intersections(), 0);
+  }
+
+  #[test]
+  fn bodies_are_looked_up_by_name() {
+    let mut w = World::book_default()
+      .with_name("outer", vec![0])
+      .with_name("spheres", 0..2);
+
+    assert_eq!(w.body_by_name("outer"), Some(&w.bodies[0]));
+    assert_eq!(w.bodies_named("spheres").len(), 2);
+    assert!(w.body_by_name("missing").is_none());
+    assert!(w.bodies_named("missing").is_empty());
+    let xs = w.intersect(Ray::new(
+      Tuple::point(0.0, 0.0, -5.0),
+      Tuple::vector(0.0, 0.0, 1.0),
+    ));
+    assert_eq!(w.name_of(&xs[0]), Some("outer"));
+    assert_eq!(w.name_of(&xs[1]), Some("spheres"));
+
+    let moved = w.bodies[0]
+      .clone()
+      .with_transform(Matrix::translation(0.0, 1.0, 0.0));
+    *w.body_by_name_mut("outer").unwrap() = moved.clone();
+    assert_eq!(w.bodies[0], moved);
+    for body in w.bodies_named_mut("spheres") {
+      *body = moved.clone();
+    }
+    assert_eq!(w.bodies[1], moved);
+  }
+
+  #[test]
+  fn instances_and_moving_bodies_are_named_too() {
+    let geometry = Arc::new(Geometry::new(vec![Sphere::default().into()]));
+    let instance = Instance::new(geometry).with_transform(Matrix::translation(0.0, 0.0, 5.0));
+    let moving_body = MovingBody::new(
+      Body::from(sphere_at(0.0, -5.0, 0.0)),
+      Matrix::translation(0.0, 5.0, 0.0),
+    )
+    .unwrap();
+    let w = World::default()
+      .with_instances(vec![instance])
+      .with_moving_bodies(vec![moving_body])
+      .with_instance_name("copy", vec![0])
+      .with_moving_body_name("ball", vec![0]);
+
+    assert_eq!(w.instances_named("copy").len(), 1);
+    assert_eq!(w.moving_bodies_named("ball").len(), 1);
+    assert!(w.bodies_named("copy").is_empty());
+
+    // The moving body passes the origin halfway through its motion
+    let ray = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = w.intersect(ray.with_time(0.5));
+    assert_eq!(w.name_of(&xs[0]), Some("ball"));
+    assert_eq!(w.name_of(&xs[2]), Some("copy"));
+  }
+
+  #[test]
+  fn only_the_selected_layers_are_kept() {
+    let bodies = vec![
+      sphere_at(0.0, 0.0, 0.0).into(),
+      sphere_at(2.0, 0.0, 0.0).into(),
+      sphere_at(4.0, 0.0, 0.0).into(),
+    ];
+    let lights = vec![white_light_at(0.0, 5.0, 0.0), white_light_at(5.0, 5.0, 0.0)];
+    let w = World::new(bodies, lights)
+      .with_name("last", vec![2])
+      .with_layer("furniture", Layer::new().with_bodies(vec![1]))
+      .with_layer(
+        "characters",
+        Layer::new().with_bodies(vec![2]).with_lights(vec![1]),
+      );
+
+    assert_eq!(w.layer_names(), vec!["characters", "furniture"]);
+
+    let characters = w.only_layers(&["characters"]);
+    assert_eq!(characters.bodies, vec![w.bodies[2].clone()]);
+    assert_eq!(characters.lights, w.lights);
+    assert_eq!(characters.body_by_name("last"), Some(&w.bodies[2]));
+    assert_eq!(characters.layers["characters"].bodies, vec![0]);
+    assert!(characters.layers["furniture"].bodies.is_empty());
+
+    let furniture = w.only_layers(&["furniture", DEFAULT_LAYER]);
+    assert_eq!(
+      furniture.bodies,
+      vec![w.bodies[0].clone(), w.bodies[1].clone()]
+    );
+    assert_eq!(furniture.lights, vec![w.lights[0]]);
+    assert!(furniture.body_by_name("last").is_none());
+  }
+
+  #[test]
+  fn replacing_a_registered_material_restyles_its_bodies() {
+    let red = Material::from(Phong::default().with_color(Color::new(1.0, 0.0, 0.0)));
+    let blue = Material::from(Phong::default().with_color(Color::new(0.0, 0.0, 1.0)));
+    let untouched = World::book_default().bodies[1].material();
+    let mut w = World::book_default()
+      .with_material_reference("paint", vec![0])
+      .with_materials(MaterialRegistry::new().with_material("paint", red.clone()));
+
+    assert_eq!(w.bodies[0].material(), red);
+    assert_eq!(w.material_name_of(0), Some("paint"));
+
+    w.set_material("paint", blue.clone());
+
+    assert_eq!(w.bodies[0].material(), blue);
+    assert_eq!(w.bodies[1].material(), untouched);
+    assert_eq!(w.material_name_of(1), None);
+  }
+
+  #[test]
+  fn equal_bodies_keep_their_own_material_references() {
+    let material = World::book_default().bodies[1].material();
+    let mut w = World::new(
+      vec![Sphere::default().into(), Sphere::default().into()],
+      vec![],
+    )
+    .with_material_reference("paint", vec![1])
+    .with_materials(MaterialRegistry::new().with_material("paint", material));
+
+    assert_eq!(w.bodies[0], w.bodies[1]);
+    assert_eq!(w.material_name_of(0), None);
+    assert_eq!(w.material_name_of(1), Some("paint"));
+
+    let red = Material::from(Phong::default().with_color(Color::new(1.0, 0.0, 0.0)));
+    w.set_material("paint", red.clone());
+    assert_ne!(w.bodies[0].material(), red);
+    assert_eq!(w.bodies[1].material(), red);
+  }
+
+  #[test]
+  fn frustum_culling_only_applies_to_camera_rays() {
+    let camera = camera_facing_ball();
+    let mut w = floor_and_ball();
+    w.bodies.push(Body::from(sphere_at(0.0, 1.0, -10.0)));
+    w.lights = vec![white_light_at(0.0, 1.0, -20.0)];
+    let r = camera.ray_for_pixel(5, 5);
+
+    let culled = w.clone().with_frustum_culling(&camera);
+
+    assert_eq!(culled.culled_count(), 1);
+    assert_eq!(culled.intersect_camera_ray(r).len(), 2);
+    assert_eq!(culled.intersect(r).len(), 4);
+    // The ball still lies in the shadow of the culled sphere
+    assert_fuzzy_eq!(culled.color_at(r), w.color_at(r));
+  }
+
+  #[test]
+  fn cameras_choose_whether_the_world_is_culled() {
+    let w = World::book_default();
+    let camera = Camera::book_default().with_transform(Matrix::translation(0.0, 0.0, 5.0));
+
+    assert_eq!(w.for_camera(&camera).culled_count(), 0);
+    let culling = camera.clone().with_frustum_culling(true);
+    assert_eq!(w.for_camera(&culling).culled_count(), 2);
+    let lens = culling.with_depth_of_field(0.1, 5.0);
+    assert_eq!(w.for_camera(&lens).culled_count(), 0);
+  }
+
+  #[test]
+  fn intersect_a_world_with_a_ray() {
+    let w = World::book_default();
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let xs = w.intersect(r);
+
+    assert_eq!(4, xs.len());
+    assert_fuzzy_eq!(4.0, xs[0].t);
+    assert_fuzzy_eq!(4.5, xs[1].t);
+    assert_fuzzy_eq!(5.5, xs[2].t);
+    assert_fuzzy_eq!(6.0, xs[3].t);
+  }
+
+  #[test]
+  fn the_color_when_a_ray_misses() {
+    let w = World::book_default();
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+    let c = w.color_at(r);
+
+    assert_fuzzy_eq!(c, Color::black());
+  }
+
+  #[test]
+  fn the_color_when_a_ray_hits() {
+    let w = World::book_default();
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let c = w.color_at(r);
+
+    assert_fuzzy_eq!(c, Color::new(0.38066, 0.47583, 0.2855));
+  }
+
+  #[test]
+  fn there_is_no_shadow_when_nothing_is_colinear_with_point_and_light() {
+    let w = World::book_default();
+    let p = Tuple::point(0.0, 10.0, 0.0);
+    let transmittance = w.light_transmittance(p, 0.0);
+
+    assert_fuzzy_eq!(transmittance, 1.0);
+  }
+
+  #[test]
+  fn there_is_shadow_when_an_object_is_between_the_point_and_the_light() {
+    let w = World::book_default();
+    let p = Tuple::point(10.0, -10.0, 10.0);
+    let transmittance = w.light_transmittance(p, 0.0);
+
+    assert_fuzzy_eq!(transmittance, 0.0);
+  }
+
+  #[test]
+  fn there_is_no_shadow_when_an_object_is_behind_the_light() {
+    let w = World::book_default();
+    let p = Tuple::point(-20.0, 20.0, -20.0);
+    let transmittance = w.light_transmittance(p, 0.0);
+
+    assert_fuzzy_eq!(transmittance, 1.0);
+  }
+
+  #[test]
+  fn there_is_no_shadow_when_an_object_is_behind_the_point() {
+    let w = World::book_default();
+    let p = Tuple::point(-2.0, 2.0, -2.0);
+    let transmittance = w.light_transmittance(p, 0.0);
+
+    assert_fuzzy_eq!(transmittance, 1.0);
+  }
+
+  #[test]
+  fn the_color_when_a_ray_hits_something_in_shadow() {
+    let material = Material::default();
+    let s1 = Sphere::new(material.clone(), Matrix::identity());
+    let s2 = Sphere::new(material, Matrix::translation(0.0, 0.0, 10.0));
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 0.0, -10.0),
+      Color::new(1.0, 1.0, 1.0),
+    ));
+    let w = World::new(vec![s1.into(), s2.into()], vec![light]);
+
+    let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let c = w.color_at(r);
+
+    assert_fuzzy_eq!(c, Color::new(0.1, 0.1, 0.1));
+  }
+
+  #[test]
+  fn there_is_no_shadow_of_bodies_not_casting_shadows() {
+    let mut w = World::book_default();
+    let no_shadows = Shadows {
+      cast: false,
+      ..Default::default()
+    };
+    w.bodies = w
+      .bodies
+      .iter()
+      .map(|body| body.clone().with_shadows(no_shadows))
+      .collect();
+    let p = Tuple::point(10.0, -10.0, 10.0);
+    let transmittance = w.light_transmittance(p, 0.0);
+
+    assert_fuzzy_eq!(transmittance, 1.0);
+  }
+
+  #[test]
+  fn transparent_bodies_let_part_of_the_light_through() {
+    let glass = Phong::default().with_transparency(0.5);
+    let w = World::new(
+      vec![Sphere::default().with_material(glass.into()).into()],
+      vec![white_light_at(0.0, 0.0, -10.0)],
+    );
+    let behind = Tuple::point(0.0, 0.0, 5.0);
+
+    // Entering and leaving the sphere halves the light twice
+    assert_fuzzy_eq!(w.light_transmittance(behind, 0.0), 0.25);
+
+    let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, -1.0));
+    let opaque = World::new(
+      vec![Sphere::default().into()],
+      vec![white_light_at(0.0, 0.0, -10.0)],
+    );
+    assert_fuzzy_eq!(w.transmittance(r, 15.0), 0.25);
+    assert_fuzzy_eq!(opaque.transmittance(r, 15.0), 0.0);
+  }
+
+  #[test]
+  fn rays_pass_through_the_cut_out_parts_of_bodies() {
+    let mask = Pattern::from(Striped::default().with_colors(Color::white(), Color::black()));
+    let card = Quad::default()
+      .with_width(4.0)
+      .with_material(Phong::default().with_cutout(Cutout::new(mask)).into());
+    let w = World::new(vec![card.into()], vec![white_light_at(0.0, 10.0, 0.0)]);
+    let down = |x: F| Ray::new(Tuple::point(x, 5.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+
+    assert_eq!(w.intersect(down(0.5)).len(), 1);
+    assert_eq!(w.intersect(down(1.5)).len(), 0);
+    assert!(w.is_blocked(down(0.5), 10.0));
+    assert!(!w.is_blocked(down(1.5), 10.0));
+    assert_fuzzy_eq!(w.transmittance(down(1.5), 10.0), 1.0);
+  }
+
+  #[test]
+  fn caustics_brighten_the_floor_below_a_glass_ball() {
+    let ball = sphere_at(0.0, 2.0, 0.0).with_material(glass().into());
+    let w = World::new(
+      vec![Plane::default().into(), ball.into()],
+      vec![white_light_at(0.0, 10.0, 0.0)],
+    );
+    let with_caustics = w.clone().with_caustics(Caustics::new(5_000, 0.2));
+    // Looks at the floor right below the ball, passing it by
+    let r = Ray::new(
+      Tuple::point(3.0, 1.0, 0.0),
+      Tuple::vector(-3.0, -1.0, 0.0).normalize(),
+    );
+
+    let plain = w.color_at(r);
+    let focused = with_caustics.color_at(r);
+
+    assert!(focused.red > plain.red + 0.5);
+    assert_fuzzy_eq!(
+      with_caustics
+        .with_lighting_pass(LightingPass::Ambient)
+        .color_at(r),
+      { w.with_lighting_pass(LightingPass::Ambient).color_at(r) }
+    );
+  }
+
+  #[test]
+  fn rays_are_blocked_by_bodies_closer_than_the_distance() {
+    let w = World::book_default();
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    assert!(w.is_blocked(r, 4.5));
+    assert!(!w.is_blocked(r, 3.9));
+    // Intersections behind the origin do not block
+    let inside = Ray::new(Tuple::point(0.0, 0.0, 2.0), Tuple::vector(0.0, 0.0, 1.0));
+    assert!(!w.is_blocked(inside, 10.0));
+  }
+
+  #[test]
+  fn the_color_when_a_ray_hits_a_body_not_receiving_shadows() {
+    let material = Material::default();
+    let s1 = Sphere::new(material.clone(), Matrix::identity());
+    let s2 = Sphere::new(material, Matrix::translation(0.0, 0.0, 10.0)).with_shadows(Shadows {
+      receive: false,
+      ..Default::default()
+    });
+    let light = Light::from(PointLight::new(
+      Tuple::point(0.0, 0.0, -10.0),
+      Color::new(1.0, 1.0, 1.0),
+    ));
+    let w = World::new(vec![s1.into(), s2.into()], vec![light]);
+
+    let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let c = w.color_at(r);
+
+    assert_fuzzy_eq!(c, Color::new(1.9, 1.9, 1.9));
+  }
+
+  #[test]
+  fn reflection_color_if_non_reflective_body_is_hit() {
+    let non_reflective_material = Material::from(
+      Phong::default()
+        .with_color(Color::new(0.8, 1.0, 0.6))
+        .with_ambient(1.0)
+        .with_reflectiveness(0.0),
+    );
+    let s1 = Body::from(Sphere::default().with_material(non_reflective_material));
+    let world = World::new(vec![s1.clone()], vec![]);
+    let ray = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let intersection = Intersection::new(1.0, ray, s1);
+    let reflected_color = world.reflected_color_at(
+      &intersection.body.material(),
+      &intersection.get_computed(),
+      1,
+      1.0,
+      &mut world.secondary_rays(),
+    );
+
+    assert_fuzzy_eq!(reflected_color, Color::black());
+  }
+
+  #[test]
+  fn reflection_color_if_reflective_body_is_hit() {
+    let non_reflective_material = Material::from(
+      Phong::default()
+        .with_color(Color::new(0.5, 0.25, 0.125))
+        .with_ambient(1.0)
+        .with_reflectiveness(0.5),
+    );
+    let s1 = Body::from(Sphere::default().with_material(non_reflective_material));
+    let world = World::new(
+      vec![s1.clone()],
+      vec![PointLight::new(Tuple::point(10.0, 10.0, 10.0), Color::white()).into()],
+    );
+    let ray = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+
+    let intersection = Intersection::new(1.0, ray, s1);
+    let reflected_color = world.reflected_color_at(
+      &intersection.body.material(),
+      &intersection.get_computed(),
+      2,
+      1.0,
+      &mut world.secondary_rays(),
+    );
+
+    assert_fuzzy_eq!(reflected_color, Color::new(0.375, 0.1875, 0.09375));
+  }
+
+  #[test]
+  fn rays_missing_everything_show_the_background() {
+    let w = World::book_default().with_background(Color::new(0.2, 0.4, 0.6));
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+
+    assert_fuzzy_eq!(w.color_at(r), Color::new(0.2, 0.4, 0.6));
+  }
+
+  #[test]
+  fn bodies_show_the_unlit_color_without_lights() {
+    let mut w = World::book_default().with_unlit_color(Color::new(1.0, 0.0, 1.0));
+    w.lights.clear();
+
+    let hit = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let miss = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 1.0, 0.0));
+
+    assert_fuzzy_eq!(w.color_at(hit), Color::new(1.0, 0.0, 1.0));
+    assert_fuzzy_eq!(w.color_at(miss), Color::black());
+  }
+
+  #[test]
+  fn an_exhausted_secondary_ray_budget_reflects_the_background() {
+    let lower = Body::from(
+      Plane::default()
+        .with_transform(Matrix::translation(0.0, -1.0, 0.0))
+        .with_material(Material::from(mirror(1.0))),
+    );
+    let upper = Body::from(
+      Plane::default()
+        .with_transform(Matrix::translation(0.0, 1.0, 0.0))
+        .with_material(Material::from(mirror(1.0))),
+    );
+    let background = Color::new(0.2, 0.4, 0.6);
+    let mirror_room = World::new(
+      vec![lower, upper.clone()],
+      vec![white_light_at(0.0, 0.0, 0.0)],
+    )
+    .with_background(background)
+    .with_secondary_ray_budget(0);
+    // Without the lower mirror the reflection leaves the world
+    let single_mirror =
+      World::new(vec![upper], vec![white_light_at(0.0, 0.0, 0.0)]).with_background(background);
+    let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+
+    assert_fuzzy_eq!(mirror_room.color_at(r), single_mirror.color_at(r));
+  }
+
+  #[test]
+  fn negligible_reflections_are_cut_off() {
+    let faint_mirror = |y| {
+      Body::from(
+        Plane::default()
+          .with_transform(Matrix::translation(0.0, y, 0.0))
+          .with_material(Material::from(mirror(0.05))),
+      )
+    };
+    let w = World::new(
+      vec![faint_mirror(-1.0), faint_mirror(1.0)],
+      vec![white_light_at(0.0, 0.0, 0.0)],
+    );
+    let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0));
+
+    // The second reflection would only contribute 0.05 * 0.05
+    let single_reflection = w.clone().with_secondary_ray_budget(1).color_at(r);
+    assert_fuzzy_eq!(w.color_at(r), single_reflection);
+    assert!(w
+      .with_contribution_cutoff(0.0)
+      .color_at(r)
+      .fuzzy_ne(single_reflection));
+  }
+
+  #[test]
+  fn lighting_passes_sum_up_to_the_full_color() {
+    let w = floor_and_ball().with_contact_shadows(ContactShadows::new(0.5, 8));
+    // Looks at the mirroring floor, which reflects the ball
+    let r = Ray::new(
+      Tuple::point(0.0, 1.0, -5.0),
+      Tuple::vector(0.0, -0.3, 1.0).normalize(),
+    );
+
+    let passes = [
+      LightingPass::Ambient,
+      LightingPass::Diffuse,
+      LightingPass::Specular,
+    ];
+    let sum = passes.iter().fold(Color::black(), |sum, pass| {
+      sum + w.clone().with_lighting_pass(*pass).color_at(r)
+    });
+
+    assert_fuzzy_eq!(sum, w.color_at(r));
+  }
+
+  #[test]
+  fn contact_shadows_darken_the_ambient_term_only() {
+    let floor = Body::from(Plane::default());
+    let sphere = Body::from(sphere_at(0.0, 1.05, 0.0));
+    // The light is hidden below the floor, so only ambient light remains
+    let world = World::new(vec![floor, sphere], vec![white_light_at(0.0, -10.0, 0.0)]);
+    let r = Ray::new(Tuple::point(0.5, 1.0, -0.5), Tuple::vector(0.0, -1.0, 0.0));
+
+    let without = world.color_at(r);
+    let with = world
+      .clone()
+      .with_contact_shadows(ContactShadows::new(1.0, 16))
+      .color_at(r);
+
+    assert_fuzzy_eq!(without, Color::new(0.1, 0.1, 0.1));
+    assert!(with.red < without.red);
+    assert!(with.red >= 0.0);
+  }
+
+  #[test]
+  fn the_refracted_color_of_an_opaque_body() {
+    let w = World::book_default();
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = Intersections::new(vec![
+      Intersection::new(4.0, r, w.bodies[0].clone()),
+      Intersection::new(6.0, r, w.bodies[0].clone()),
+    ]);
+
+    let c = xs[0].get_computed_with(&xs);
+    let color = w.refracted_color_at(&xs[0].body.material(), &c, 5, 1.0, &mut w.secondary_rays());
+
+    assert_fuzzy_eq!(color, Color::black());
+  }
+
+  #[test]
+  fn the_refracted_color_at_the_maximum_recursive_depth() {
+    let mut w = World::book_default();
+    w.bodies[0] = w.bodies[0].clone().with_material(Material::from(glass()));
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = Intersections::new(vec![
+      Intersection::new(4.0, r, w.bodies[0].clone()),
+      Intersection::new(6.0, r, w.bodies[0].clone()),
+    ]);
+
+    let c = xs[0].get_computed_with(&xs);
+    let color = w.refracted_color_at(
+      &Material::from(glass()),
+      &c,
+      0,
+      1.0,
+      &mut w.secondary_rays(),
+    );
+
+    assert_fuzzy_eq!(color, Color::black());
+  }
+
+  #[test]
+  fn the_refracted_color_under_total_internal_reflection() {
+    let mut w = World::book_default();
+    w.bodies[0] = w.bodies[0].clone().with_material(Material::from(glass()));
+    let r = Ray::new(
+      Tuple::point(0.0, 0.0, SQRT_2 / 2.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    );
+    let xs = Intersections::new(vec![
+      Intersection::new(-SQRT_2 / 2.0, r, w.bodies[0].clone()),
+      Intersection::new(SQRT_2 / 2.0, r, w.bodies[0].clone()),
+    ]);
+
+    // Inside the sphere, so the second intersection is looked at
+    let c = xs[1].get_computed_with(&xs);
+    let color = w.refracted_color_at(
+      &Material::from(glass()),
+      &c,
+      5,
+      1.0,
+      &mut w.secondary_rays(),
+    );
+
+    assert_fuzzy_eq!(color, Color::black());
+  }
+
+  fn world_with_glass_floor(floor_material: Phong) -> World {
+    let mut w = World::book_default();
+    let floor = Plane::default()
+      .with_transform(Matrix::translation(0.0, -1.0, 0.0))
+      .with_material(Material::from(floor_material));
+    let ball = Sphere::default()
+      .with_transform(Matrix::translation(0.0, -3.5, -0.5))
+      .with_material(Material::from(
+        Phong::default()
+          .with_color(Color::new(1.0, 0.0, 0.0))
+          .with_ambient(0.5),
+      ));
+    w.bodies.push(Body::from(floor));
+    w.bodies.push(Body::from(ball));
+    w
+  }
+
+  #[test]
+  fn the_color_of_a_transparent_body() {
+    let w = world_with_glass_floor(
+      Phong::default()
+        .with_transparency(0.5)
+        .with_refractive_index(1.5),
+    );
+    let r = Ray::new(
+      Tuple::point(0.0, 0.0, -3.0),
+      Tuple::vector(0.0, -SQRT_2 / 2.0, SQRT_2 / 2.0),
+    );
+
+    // Unlike in the book, half of the light reaches the ball through the floor
+    assert_fuzzy_eq!(w.color_at(r), Color::new(1.12547, 0.68643, 0.68643));
+  }
+
+  #[test]
+  fn the_color_of_a_reflective_transparent_body_uses_schlick() {
+    let w = world_with_glass_floor(
+      Phong::default()
+        .with_reflectiveness(0.5)
+        .with_transparency(0.5)
+        .with_refractive_index(1.5),
+    );
+    let r = Ray::new(
+      Tuple::point(0.0, 0.0, -3.0),
+      Tuple::vector(0.0, -SQRT_2 / 2.0, SQRT_2 / 2.0),
+    );
+
+    // Unlike in the book, half of the light reaches the ball through the floor
+    assert_fuzzy_eq!(w.color_at(r), Color::new(1.11500, 0.69643, 0.69243));
+  }
+}
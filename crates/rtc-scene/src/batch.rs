@@ -0,0 +1,429 @@
+use crate::world_loader::yaml;
+use crate::world_loader::WorldLoader;
+use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
+use rtc_core::canvas::to_png::ToPNG;
+use rtc_core::failure::catch_panic;
+use rtc_core::fingerprint::Fingerprint;
+use rtc_core::post_processing::PostProcess;
+use rtc_core::renderer;
+use rtc_core::renderer::progressive;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use yaml_rust::{Yaml, YamlLoader};
+
+/// A scene file together with the cameras to render from it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchJob {
+  pub scene: PathBuf,
+  /// Names of the cameras to render, all cameras of the scene if empty
+  pub cameras: Vec<String>,
+  /// Directory the images are written to
+  pub output: PathBuf,
+  pub strict: bool,
+  /// Render progressively for the given time per camera
+  pub time_budget: Option<Duration>,
+}
+
+/// List of jobs, which are rendered by one invocation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchManifest {
+  pub jobs: Vec<BatchJob>,
+  /// Number of jobs rendered at the same time
+  pub workers: usize,
+}
+
+/// Result of rendering one camera of a job
+#[derive(Clone, Debug)]
+pub struct BatchResult {
+  pub scene: PathBuf,
+  pub camera: Option<String>,
+  pub output: Option<PathBuf>,
+  /// What the image was rendered from, see `World::fingerprint`
+  pub fingerprint: Option<Fingerprint>,
+  pub duration: Duration,
+  pub error: Option<String>,
+}
+
+impl BatchResult {
+  pub fn is_success(&self) -> bool {
+    self.error.is_none()
+  }
+}
+
+fn yaml_string(value: &Yaml, path: &str) -> Result<String> {
+  match value {
+    Yaml::String(string) => Ok(string.clone()),
+    _ => Err(anyhow!(
+      "Expected string at {}, but found {:?}",
+      path,
+      value
+    )),
+  }
+}
+
+impl BatchManifest {
+  /// Parses a manifest.
+  ///
+  /// Relative scene and output paths are resolved against `base_directory`,
+  /// which usually is the directory of the manifest file itself.
+  ///
+  /// ```yaml
+  /// workers: 2
+  /// jobs:
+  ///   - scene: scenes/spheres.yaml
+  ///     cameras: [main]
+  ///     output: renders
+  ///     time_budget: 30s
+  ///     strict: true
+  /// ```
+  pub fn from_yaml(source: &str, base_directory: &Path) -> Result<Self> {
+    let documents = YamlLoader::load_from_str(source)?;
+    let document = documents
+      .first()
+      .ok_or_else(|| anyhow!("Batch manifest is empty"))?;
+
+    let workers = match &document["workers"] {
+      Yaml::BadValue => 1,
+      Yaml::Integer(workers) if *workers > 0 => *workers as usize,
+      value => {
+        return Err(anyhow!(
+          "Expected positive integer at .workers, but found {:?}",
+          value
+        ))
+      }
+    };
+
+    let jobs_array = match &document["jobs"] {
+      Yaml::Array(jobs) => jobs,
+      value => {
+        return Err(anyhow!(
+          "Expected array of jobs at .jobs, but found {:?}",
+          value
+        ))
+      }
+    };
+
+    let jobs = jobs_array
+      .iter()
+      .enumerate()
+      .map(|(index, job)| Self::parse_job(job, &format!(".jobs[{}]", index), base_directory))
+      .collect::<Result<Vec<_>>>()?;
+
+    Ok(Self { jobs, workers })
+  }
+
+  fn parse_job(job: &Yaml, path: &str, base_directory: &Path) -> Result<BatchJob> {
+    if job.as_hash().is_none() {
+      return Err(anyhow!("Expected hash at {}, but found {:?}", path, job));
+    }
+
+    let scene = base_directory.join(yaml_string(&job["scene"], &format!("{}.scene", path))?);
+
+    let cameras = match &job["cameras"] {
+      Yaml::BadValue => vec![],
+      Yaml::Array(cameras) => cameras
+        .iter()
+        .enumerate()
+        .map(|(index, camera)| yaml_string(camera, &format!("{}.cameras[{}]", path, index)))
+        .collect::<Result<Vec<_>>>()?,
+      value => {
+        return Err(anyhow!(
+          "Expected array at {}.cameras, but found {:?}",
+          path,
+          value
+        ))
+      }
+    };
+
+    let output = match &job["output"] {
+      Yaml::BadValue => base_directory.to_path_buf(),
+      value => base_directory.join(yaml_string(value, &format!("{}.output", path))?),
+    };
+
+    let strict = match &job["strict"] {
+      Yaml::BadValue => false,
+      Yaml::Boolean(strict) => *strict,
+      value => {
+        return Err(anyhow!(
+          "Expected boolean at {}.strict, but found {:?}",
+          path,
+          value
+        ))
+      }
+    };
+
+    let time_budget = match &job["time_budget"] {
+      Yaml::BadValue => None,
+      Yaml::Integer(seconds) if *seconds >= 0 => Some(Duration::from_secs(*seconds as u64)),
+      value => {
+        let text = yaml_string(value, &format!("{}.time_budget", path))?;
+        Some(
+          progressive::parse_duration(&text)
+            .ok_or_else(|| anyhow!("Invalid duration '{}' found at {}.time_budget", text, path))?,
+        )
+      }
+    };
+
+    Ok(BatchJob {
+      scene,
+      cameras,
+      output,
+      strict,
+      time_budget,
+    })
+  }
+
+  /// Renders all jobs, calling `on_result` as soon as a camera is done.
+  pub fn run<R>(&self, on_result: R) -> Result<Vec<BatchResult>>
+  where
+    R: Fn(&BatchResult) + Sync,
+  {
+    let pool = rayon::ThreadPoolBuilder::new()
+      .num_threads(self.workers)
+      .build()?;
+
+    let results = pool.install(|| {
+      self
+        .jobs
+        .par_iter()
+        .flat_map_iter(|job| job.run(&on_result))
+        .collect()
+    });
+
+    Ok(results)
+  }
+}
+
+impl BatchJob {
+  /// Renders every selected camera of the job.
+  ///
+  /// Failures are reported as results instead of aborting the whole batch.
+  pub fn run<R>(&self, on_result: &R) -> Vec<BatchResult>
+  where
+    R: Fn(&BatchResult) + Sync,
+  {
+    let start = Instant::now();
+    let failure = |error: anyhow::Error, camera: Option<String>| {
+      let result = BatchResult {
+        scene: self.scene.clone(),
+        camera,
+        output: None,
+        fingerprint: None,
+        duration: start.elapsed(),
+        error: Some(format!("{:#}", error)),
+      };
+      on_result(&result);
+      vec![result]
+    };
+
+    let loaded = read_to_string(&self.scene)
+      .with_context(|| format!("Could not read scene {}", self.scene.display()))
+      .and_then(|source| {
+        catch_panic(|| {
+          let base_directory = self.scene.parent().unwrap_or_else(|| Path::new("."));
+          yaml::Loader::default()
+            .with_strict_mode(self.strict)
+            .with_base_directory(base_directory.to_path_buf())
+            .load_world(source)
+        })
+        .map_err(|message| anyhow!(message))?
+      });
+    let (world, cameras) = match loaded {
+      Ok(loaded) => loaded,
+      Err(error) => return failure(error, None),
+    };
+
+    if cameras.is_empty() {
+      return failure(anyhow!("Scene defines no camera to render"), None);
+    }
+
+    let mut names: Vec<String> = if self.cameras.is_empty() {
+      cameras.keys().cloned().collect()
+    } else {
+      self.cameras.clone()
+    };
+    names.sort();
+
+    if let Err(error) = create_dir_all(&self.output) {
+      return failure(
+        anyhow!(error).context(format!("Could not create {}", self.output.display())),
+        None,
+      );
+    }
+
+    names
+      .into_iter()
+      .map(|name| {
+        let start = Instant::now();
+        let output = self.output.join(format!("{}.png", name));
+        let rendered = cameras
+          .get(&name)
+          .ok_or_else(|| anyhow!("Scene does not contain a camera named '{}'", name))
+          .and_then(|camera| {
+            let fingerprint = world.fingerprint(camera);
+            let canvas = catch_panic(|| {
+              let world = world.for_camera(camera);
+              let canvas = match self.time_budget {
+                Some(time_budget) => progressive::render(&world, camera, time_budget, |_, _| {}).0,
+                None => renderer::render(&world, camera, |_| {}),
+              };
+              camera.post_processing.apply(&canvas)
+            })
+            .map_err(|message| anyhow!(message))?;
+            let scene = self.scene.display().to_string();
+            let png = canvas.to_png_with_text(&[
+              ("Fingerprint", &fingerprint.to_string()),
+              ("Scene", &scene),
+              ("Camera", &name),
+            ]);
+            write(&output, png).with_context(|| format!("Could not write {}", output.display()))?;
+            let report = self.output.join(format!("{}_fingerprint.txt", name));
+            write(&report, fingerprint.report(&scene, &name, None))
+              .with_context(|| format!("Could not write {}", report.display()))?;
+            Ok(fingerprint)
+          });
+
+        let result = BatchResult {
+          scene: self.scene.clone(),
+          camera: Some(name),
+          output: rendered.as_ref().ok().map(|_| output.clone()),
+          fingerprint: rendered.as_ref().ok().copied(),
+          duration: start.elapsed(),
+          error: rendered.err().map(|error| format!("{:#}", error)),
+        };
+        on_result(&result);
+        result
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rtc_core::canvas::from_png::png_text;
+  use std::env::temp_dir;
+  use std::fs::remove_dir_all;
+
+  #[test]
+  fn manifest_is_parsed() {
+    let source = r##"
+workers: 3
+jobs:
+  - scene: scenes/a.yaml
+  - scene: b.yaml
+    cameras: [main, side]
+    output: renders
+    strict: true
+    time_budget: 2m
+"##;
+
+    let manifest = BatchManifest::from_yaml(source, Path::new("/base")).unwrap();
+
+    assert_eq!(manifest.workers, 3);
+    assert_eq!(
+      manifest.jobs,
+      vec![
+        BatchJob {
+          scene: PathBuf::from("/base/scenes/a.yaml"),
+          cameras: vec![],
+          output: PathBuf::from("/base"),
+          strict: false,
+          time_budget: None,
+        },
+        BatchJob {
+          scene: PathBuf::from("/base/b.yaml"),
+          cameras: vec!["main".into(), "side".into()],
+          output: PathBuf::from("/base/renders"),
+          strict: true,
+          time_budget: Some(Duration::from_secs(120)),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn manifest_errors_name_the_location() {
+    let source = r##"
+jobs:
+  - scene: a.yaml
+  - cameras: [main]
+"##;
+
+    let result = BatchManifest::from_yaml(source, Path::new("."));
+
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "Expected string at .jobs[1].scene, but found BadValue"
+    );
+  }
+
+  #[test]
+  fn jobs_are_rendered_and_failures_reported() {
+    let directory = temp_dir().join(format!("rtc-batch-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    write(
+      directory.join("scene.yaml"),
+      r##"
+---
+- light:
+    type: point_light
+    at: [-10, 10, -10]
+    intensity: [1, 1, 1]
+- body:
+    type: sphere
+- camera:
+    name: tiny
+    width: 4
+    height: 3
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+"##,
+    )
+    .unwrap();
+
+    let manifest = BatchManifest {
+      jobs: vec![
+        BatchJob {
+          scene: directory.join("scene.yaml"),
+          cameras: vec!["tiny".into(), "missing".into()],
+          output: directory.join("out"),
+          strict: false,
+          time_budget: None,
+        },
+        BatchJob {
+          scene: directory.join("does_not_exist.yaml"),
+          cameras: vec![],
+          output: directory.join("out"),
+          strict: false,
+          time_budget: None,
+        },
+      ],
+      workers: 2,
+    };
+
+    let mut results = manifest.run(|_| {}).unwrap();
+    results.sort_by_key(|result| (result.scene.clone(), result.camera.clone()));
+    let png = std::fs::read(directory.join("out").join("tiny.png")).unwrap();
+    let report = read_to_string(directory.join("out").join("tiny_fingerprint.txt")).unwrap();
+    remove_dir_all(&directory).ok();
+
+    assert_eq!(results.len(), 3);
+    assert!(!results[0].is_success());
+    assert!(results[0].camera.is_none());
+    assert!(!results[1].is_success());
+    assert_eq!(results[1].camera.as_deref(), Some("missing"));
+    assert!(results[2].is_success());
+    assert_eq!(
+      results[2].output,
+      Some(directory.join("out").join("tiny.png"))
+    );
+    let fingerprint = results[2].fingerprint.unwrap().to_string();
+    assert!(png_text(&png).contains(&("Fingerprint".to_string(), fingerprint.clone())));
+    assert!(report.ends_with(&format!("camera: tiny\nfingerprint: {}\n", fingerprint)));
+  }
+}
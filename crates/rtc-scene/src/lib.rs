@@ -0,0 +1,5 @@
+pub mod batch;
+pub mod lint;
+pub mod thumbnail;
+pub mod to_rust;
+pub mod world_loader;
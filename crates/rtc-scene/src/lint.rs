@@ -0,0 +1,230 @@
+use rtc_core::body::{Body, Intersectable};
+use rtc_core::camera::Camera;
+use rtc_core::fuzzy_eq::FuzzyEq;
+use rtc_core::material::{Reflective, Refractive};
+use rtc_core::world::World;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Unbounded bodies a world may have, before every ray testing all of them
+/// adds up
+const MAX_UNBOUNDED_BODIES: usize = 16;
+
+/// Camera rays of a single render, beyond which samples should be spent
+/// more carefully
+const MAX_CAMERA_RAYS: usize = 100_000_000;
+
+/// Bodies both reflective and transparent, which may be hit by the rays
+/// they spawn themselves
+const MAX_REFLECTIVE_AND_TRANSPARENT_BODIES: usize = 1;
+
+/// Setup of a world, which makes rendering it needlessly slow
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lint {
+  pub problem: String,
+  pub suggestion: String,
+}
+
+impl Lint {
+  fn new(problem: impl Into<String>, suggestion: impl Into<String>) -> Self {
+    Self {
+      problem: problem.into(),
+      suggestion: suggestion.into(),
+    }
+  }
+}
+
+impl fmt::Display for Lint {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} {}", self.problem, self.suggestion)
+  }
+}
+
+/// Performance problems of a loaded world and its cameras, each with a
+/// suggestion how to avoid it
+pub fn lint(world: &World, cameras: &HashMap<String, Camera>) -> Vec<Lint> {
+  let mut lints = vec![];
+  lints.extend(lint_planes(world));
+  let mut names: Vec<&String> = cameras.keys().collect();
+  names.sort();
+  for name in names {
+    lints.extend(lint_camera(name, &cameras[name]));
+  }
+  lints.extend(lint_materials(world));
+  lints
+}
+
+fn lint_planes(world: &World) -> Vec<Lint> {
+  let mut lints = vec![];
+  let planes: Vec<&Body> = world
+    .bodies()
+    .iter()
+    .filter(|body| matches!(body, Body::Plane(_)))
+    .collect();
+  let duplicates = planes
+    .iter()
+    .enumerate()
+    .filter(|(index, plane)| {
+      planes.iter().enumerate().any(|(other_index, other)| {
+        other_index != *index && other.transform().fuzzy_eq(plane.transform())
+      })
+    })
+    .count();
+  if duplicates > 0 {
+    lints.push(Lint::new(
+      format!(
+        "{} planes lie exactly on top of each other, e.g. several untransformed floors.",
+        duplicates
+      ),
+      "Only one of each can be seen, remove the others.",
+    ));
+  }
+
+  let unbounded = world
+    .bodies()
+    .iter()
+    .filter(|body| body.bounds().is_infinite())
+    .count();
+  if unbounded > MAX_UNBOUNDED_BODIES {
+    lints.push(Lint::new(
+      format!(
+        "{} unbounded bodies, like planes, are tested by every ray.",
+        unbounded
+      ),
+      "Use quads for floors and walls of limited size, whose bounds rays can miss.",
+    ));
+  }
+  lints
+}
+
+fn lint_camera(name: &str, camera: &Camera) -> Vec<Lint> {
+  if camera.samples <= 1 || camera.adaptive_sampling.is_some() {
+    return vec![];
+  }
+  let rays = camera.hsize * camera.vsize * camera.samples * camera.samples;
+  if rays <= MAX_CAMERA_RAYS {
+    return vec![];
+  }
+  vec![Lint::new(
+    format!(
+      "Camera '{}' traces {} million camera rays, {}x{} pixels with {}x{} samples each.",
+      name,
+      rays / 1_000_000,
+      camera.hsize,
+      camera.vsize,
+      camera.samples,
+      camera.samples
+    ),
+    "Lower the samples, or use adaptive_sampling or --time-budget to spend them only on noisy parts.",
+  )]
+}
+
+fn lint_materials(world: &World) -> Vec<Lint> {
+  if world.secondary_ray_budget.is_some() {
+    return vec![];
+  }
+  let count = world
+    .bodies()
+    .iter()
+    .chain(
+      world
+        .moving_bodies()
+        .iter()
+        .map(|moving_body| moving_body.body()),
+    )
+    .map(|body| body.material())
+    .filter(|material| material.reflectiveness() > 0.0 && material.transparency() > 0.0)
+    .count();
+  if count <= MAX_REFLECTIVE_AND_TRANSPARENT_BODIES {
+    return vec![];
+  }
+  vec![Lint::new(
+    format!(
+      "{} bodies are both reflective and transparent, so every hit of them spawns two rays, up to {} per camera ray at the reflection limit of {}.",
+      count,
+      1usize << world.reflection_limit(),
+      world.reflection_limit()
+    ),
+    "Set a secondary_ray_budget in the settings, raise the contribution_cutoff, or keep the materials either reflective or transparent.",
+  )]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rtc_core::angle::Angle;
+  use rtc_core::material::{Material, Phong};
+  use rtc_core::matrix::Matrix;
+  use rtc_core::plane::Plane;
+  use rtc_core::sphere::Sphere;
+  use rtc_core::F;
+
+  #[test]
+  fn planes_on_top_of_each_other_are_flagged() {
+    let world = World::new(
+      vec![
+        Body::from(Plane::default()),
+        Body::from(Plane::default()),
+        Body::from(Plane::default().with_transform(Matrix::translation(0.0, 1.0, 0.0))),
+      ],
+      vec![],
+    );
+
+    let lints = lint(&world, &HashMap::new());
+
+    assert_eq!(lints.len(), 1);
+    assert!(lints[0].problem.starts_with("2 planes lie exactly on top"));
+  }
+
+  #[test]
+  fn cameras_with_too_many_samples_are_flagged() {
+    let mut cameras = HashMap::new();
+    cameras.insert(
+      "poster".to_string(),
+      Camera::new(7680, 4320, Angle::radians(std::f64::consts::PI / 3.0)).with_samples(4),
+    );
+    cameras.insert(
+      "preview".to_string(),
+      Camera::new(640, 480, Angle::radians(std::f64::consts::PI / 3.0)).with_samples(4),
+    );
+
+    let lints = lint(&World::default(), &cameras);
+
+    assert_eq!(lints.len(), 1);
+    assert!(lints[0]
+      .problem
+      .starts_with("Camera 'poster' traces 530 million"));
+  }
+
+  #[test]
+  fn glass_without_a_ray_budget_is_flagged() {
+    let glass = Material::from(
+      Phong::default()
+        .with_reflectiveness(0.9)
+        .with_transparency(0.9),
+    );
+    let spheres = |count: usize| -> Vec<Body> {
+      (0..count)
+        .map(|index| {
+          Body::from(
+            Sphere::default()
+              .with_transform(Matrix::translation(3.0 * index as F, 0.0, 0.0))
+              .with_material(glass.clone()),
+          )
+        })
+        .collect()
+    };
+
+    let single = lint(&World::new(spheres(1), vec![]), &HashMap::new());
+    let several = lint(&World::new(spheres(3), vec![]), &HashMap::new());
+    let budgeted = lint(
+      &World::new(spheres(3), vec![]).with_secondary_ray_budget(16),
+      &HashMap::new(),
+    );
+
+    assert!(single.is_empty());
+    assert_eq!(several.len(), 1);
+    assert!(several[0].problem.contains("up to 32 per camera ray"));
+    assert!(budgeted.is_empty());
+  }
+}
@@ -0,0 +1,117 @@
+use crate::world_loader::yaml;
+use crate::world_loader::WorldLoader;
+use anyhow::{anyhow, Context, Result};
+use rtc_core::angle::Angle;
+use rtc_core::camera::Camera;
+use rtc_core::canvas::to_png::ToPNG;
+use rtc_core::renderer;
+use rtc_core::F;
+use std::fs::{read_to_string, write};
+use std::path::{Path, PathBuf};
+
+/// Camera rendering the view of the given one with at most `max_px` pixels
+/// along its longer side.
+///
+/// Everything costly is left out: a single sample per pixel, no depth of
+/// field and no post processing.
+pub fn preview_camera(camera: &Camera, max_px: usize) -> Camera {
+  let scale = (max_px as F / camera.hsize.max(camera.vsize) as F).min(1.0);
+  let hsize = ((camera.hsize as F * scale).round() as usize).max(1);
+  let vsize = ((camera.vsize as F * scale).round() as usize).max(1);
+  Camera::new(hsize, vsize, Angle::radians(camera.field_of_view))
+    .with_transform(camera.transform)
+    .with_exposure(camera.exposure)
+    .with_frustum_culling(true)
+}
+
+/// Renders a small preview of the scene from its first camera, in
+/// alphabetical order of the names, for scene browsers and galleries.
+///
+/// The image is written next to the scene as `<scene>_thumbnail.png`, whose
+/// path is returned.
+pub fn render_thumbnail(scene_path: impl AsRef<Path>, max_px: usize) -> Result<PathBuf> {
+  let scene_path = scene_path.as_ref();
+  let base_directory = scene_path.parent().unwrap_or_else(|| Path::new("."));
+  let source = read_to_string(scene_path)
+    .with_context(|| format!("Could not read scene {}", scene_path.display()))?;
+  let (world, cameras) = yaml::Loader::default()
+    .with_base_directory(base_directory.to_path_buf())
+    .load_world(source)
+    .with_context(|| format!("Could not load scene {}", scene_path.display()))?;
+
+  let camera = cameras
+    .keys()
+    .min()
+    .map(|name| preview_camera(&cameras[name], max_px))
+    .ok_or_else(|| anyhow!("Scene {} does not contain a camera", scene_path.display()))?;
+  let canvas = renderer::render(&world.with_frustum_culling(&camera), &camera, |_| {});
+
+  let stem = scene_path
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default();
+  let output = base_directory.join(format!("{}_thumbnail.png", stem));
+  write(&output, canvas.to_png())
+    .with_context(|| format!("Could not write {}", output.display()))?;
+  Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rtc_core::canvas::Canvas;
+  use std::env::temp_dir;
+  use std::fs::{create_dir_all, read, remove_dir_all};
+
+  #[test]
+  fn the_preview_camera_keeps_the_view_at_a_smaller_size() {
+    let camera = Camera::new(400, 200, Angle::radians(1.0))
+      .with_samples(16)
+      .with_depth_of_field(0.5, 4.0);
+
+    let preview = preview_camera(&camera, 100);
+
+    assert_eq!((preview.hsize, preview.vsize), (100, 50));
+    assert_eq!(preview.field_of_view, camera.field_of_view);
+    assert_eq!(preview.samples, 1);
+    assert_eq!(preview.aperture, 0.0);
+    assert_eq!(preview_camera(&camera, 1000).hsize, 400);
+  }
+
+  #[test]
+  fn thumbnails_are_written_next_to_the_scene() {
+    let directory = temp_dir().join(format!("rtc-thumbnail-{}", std::process::id()));
+    create_dir_all(&directory).unwrap();
+    let scene = directory.join("scene.yaml");
+    write(
+      &scene,
+      r##"
+---
+- light:
+    type: point_light
+    at: [-10, 10, -10]
+    intensity: [1, 1, 1]
+- body:
+    type: sphere
+- camera:
+    name: wide
+    width: 40
+    height: 20
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+"##,
+    )
+    .unwrap();
+
+    let output = render_thumbnail(&scene, 8).unwrap();
+    let thumbnail = Canvas::from_png(&read(&output).unwrap()).unwrap();
+    let missing = render_thumbnail(directory.join("missing.yaml"), 8);
+    remove_dir_all(&directory).ok();
+
+    assert_eq!(output, directory.join("scene_thumbnail.png"));
+    assert_eq!((thumbnail.width, thumbnail.height), (8, 4));
+    assert!(missing.is_err());
+  }
+}
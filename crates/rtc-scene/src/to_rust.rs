@@ -0,0 +1,797 @@
+use rtc_core::angle::Angle;
+use rtc_core::body::{Body, Intersectable, Shadows};
+use rtc_core::camera::Camera;
+use rtc_core::canvas::Color;
+use rtc_core::fuzzy_eq::FuzzyEq;
+use rtc_core::light::{Attenuation, Light};
+use rtc_core::material::lambert::Lambert;
+use rtc_core::material::{Material, Phong};
+use rtc_core::matrix::Matrix;
+use rtc_core::pattern::{CheckerBoard, Easing, Fill, Noise, Pattern, Stencil, Striped};
+use rtc_core::tuple::Tuple;
+use rtc_core::world::World;
+use rtc_core::F;
+use std::collections::{BTreeSet, HashMap};
+
+/// Rust code building the given world with the builders of `rtc_core`, the
+/// reverse of what the chapter binaries do by hand.
+///
+/// The code is a complete binary rendering every camera into `<name>.png`.
+/// Transformations are written as translation, rotation around a single axis
+/// and scaling where possible, as plain matrices otherwise. Parts of the
+/// world which can't be built this way, like meshes, are listed in a comment
+/// at the top instead.
+pub fn to_rust(world: &World, cameras: &HashMap<String, Camera>) -> String {
+  let mut code = RustCode::default();
+  let world_function = code.world(world);
+
+  let mut names: Vec<&String> = cameras.keys().collect();
+  names.sort();
+  let camera_functions: Vec<String> = names
+    .iter()
+    .map(|name| code.camera(name, &cameras[*name]))
+    .collect();
+
+  let mut rust = String::new();
+  if !code.notes.is_empty() {
+    rust += "// Not exported:\n";
+    for note in code.notes.iter() {
+      rust += &format!("// - {}\n", note);
+    }
+    rust += "\n";
+  }
+  code.uses.insert("rtc_core::camera::Camera");
+  code.uses.insert("rtc_core::canvas::to_png::ToPNG");
+  code.uses.insert("rtc_core::renderer");
+  code.uses.insert("rtc_core::world::World");
+  code.uses.insert("std::fs::write");
+  for path in code.uses.iter() {
+    rust += &format!("use {};\n", path);
+  }
+  rust += "\n";
+  rust += &world_function;
+  for camera_function in camera_functions.iter() {
+    rust += "\n";
+    rust += camera_function;
+  }
+
+  rust += "\nfn main() {\n  let world = world();\n  let cameras: Vec<(&str, Camera)> = vec![";
+  let cameras: Vec<String> = names
+    .iter()
+    .map(|name| format!("({:?}, camera_{}())", name, identifier(name)))
+    .collect();
+  rust += &cameras.join(", ");
+  rust += "];\n";
+  rust += r#"
+  for (name, camera) in cameras {
+    let canvas = renderer::render(&world, &camera, |_| {});
+    let filename = format!("./{}.png", name);
+    write(&filename, canvas.to_png()).expect("Could not write the image to disk.");
+    println!("Wrote {}.", filename);
+  }
+}
+"#;
+  rust
+}
+
+#[derive(Default)]
+struct RustCode {
+  uses: BTreeSet<&'static str>,
+  notes: BTreeSet<String>,
+  /// Let bindings of the function currently written
+  statements: Vec<String>,
+}
+
+impl RustCode {
+  fn note(&mut self, note: &str) {
+    self.notes.insert(note.to_string());
+  }
+
+  /// Binds the builder chain to the name and returns the name
+  fn binding(&mut self, name: String, start: String, calls: Vec<String>) -> String {
+    let mut statement = format!("  let {} = {}", name, start);
+    for call in calls.iter() {
+      statement += &format!("\n    .{}", call);
+    }
+    statement += ";\n";
+    self.statements.push(statement);
+    name
+  }
+
+  fn world(&mut self, world: &World) -> String {
+    let bodies: Vec<String> = world
+      .bodies()
+      .iter()
+      .enumerate()
+      .filter_map(|(index, body)| self.body(index, body))
+      .map(|name| format!("Body::from({})", name))
+      .collect();
+    let lights: Vec<String> = world
+      .lights()
+      .iter()
+      .enumerate()
+      .map(|(index, light)| format!("Light::from({})", self.light(index, light)))
+      .collect();
+    if !bodies.is_empty() {
+      self.uses.insert("rtc_core::body::Body");
+    }
+    if !lights.is_empty() {
+      self.uses.insert("rtc_core::light::Light");
+    }
+    if !world.instances().is_empty() {
+      self.note("Meshes");
+    }
+    if !world.moving_bodies().is_empty() {
+      self.note("Moving bodies");
+    }
+    if !world.sweeps.is_empty() {
+      self.note("Animations");
+    }
+
+    let defaults = World::default();
+    let mut calls = vec![];
+    if !world.background.fuzzy_eq(defaults.background) {
+      calls.push(format!("with_background({})", self.color(world.background)));
+    }
+    if !world.unlit_color.fuzzy_eq(defaults.unlit_color) {
+      calls.push(format!(
+        "with_unlit_color({})",
+        self.color(world.unlit_color)
+      ));
+    }
+    if let Some(contact_shadows) = world.contact_shadows {
+      self
+        .uses
+        .insert("rtc_core::contact_shadows::ContactShadows");
+      calls.push(format!(
+        "with_contact_shadows(ContactShadows::new({}, {}).with_strength({}))",
+        float(contact_shadows.distance),
+        contact_shadows.samples,
+        float(contact_shadows.strength)
+      ));
+    }
+    if let Some(caustics) = world.caustics {
+      self.uses.insert("rtc_core::photon_map::Caustics");
+      calls.push(format!(
+        "with_caustics(Caustics::new({}, {}).with_seed({}))",
+        caustics.photons,
+        float(caustics.radius),
+        caustics.seed
+      ));
+    }
+    if let Some(budget) = world.secondary_ray_budget {
+      calls.push(format!("with_secondary_ray_budget({})", budget));
+    }
+    if !world
+      .contribution_cutoff
+      .fuzzy_eq(defaults.contribution_cutoff)
+    {
+      calls.push(format!(
+        "with_contribution_cutoff({})",
+        float(world.contribution_cutoff)
+      ));
+    }
+
+    let mut function = String::from("fn world() -> World {\n");
+    function += &self.statements.drain(..).collect::<Vec<_>>().join("\n");
+    if !function.ends_with('{') && !function.ends_with("{\n") {
+      function += "\n";
+    }
+    function += &format!(
+      "  World::new(\n    vec![{}],\n    vec![{}],\n  )",
+      bodies.join(", "),
+      lights.join(", ")
+    );
+    for call in calls.iter() {
+      function += &format!("\n  .{}", call);
+    }
+    function += "\n}\n";
+    function
+  }
+
+  fn body(&mut self, index: usize, body: &Body) -> Option<String> {
+    let (path, start, mut calls) = match *body {
+      Body::Sphere(_) => (
+        "rtc_core::sphere::Sphere",
+        "Sphere::default()".into(),
+        vec![],
+      ),
+      Body::Plane(_) => ("rtc_core::plane::Plane", "Plane::default()".into(), vec![]),
+      Body::Quad(ref quad) => {
+        let mut calls = vec![];
+        if !quad.width.fuzzy_eq(2.0) {
+          calls.push(format!("with_width({})", float(quad.width)));
+        }
+        if !quad.height.fuzzy_eq(2.0) {
+          calls.push(format!("with_height({})", float(quad.height)));
+        }
+        ("rtc_core::quad::Quad", "Quad::default()".into(), calls)
+      }
+      Body::Cylinder(ref cylinder) => (
+        "rtc_core::cylinder::Cylinder",
+        "Cylinder::default()".into(),
+        extent(cylinder.minimum, cylinder.maximum, cylinder.closed),
+      ),
+      Body::Cone(ref cone) => (
+        "rtc_core::cone::Cone",
+        "Cone::default()".into(),
+        extent(cone.minimum, cone.maximum, cone.closed),
+      ),
+      Body::Triangle(ref triangle) => (
+        "rtc_core::triangle::Triangle",
+        format!(
+          "Triangle::new({}, {}, {})",
+          self.point(triangle.p1),
+          self.point(triangle.p2),
+          self.point(triangle.p3)
+        ),
+        vec![],
+      ),
+      Body::SmoothTriangle(ref smooth) => (
+        "rtc_core::triangle::SmoothTriangle",
+        format!(
+          "SmoothTriangle::new({}, {}, {}, {}, {}, {})",
+          self.point(smooth.triangle.p1),
+          self.point(smooth.triangle.p2),
+          self.point(smooth.triangle.p3),
+          self.vector(smooth.n1),
+          self.vector(smooth.n2),
+          self.vector(smooth.n3)
+        ),
+        vec![],
+      ),
+      Body::Sdf(_) => {
+        self.note("SDF bodies");
+        return None;
+      }
+      Body::Repeated(_) => {
+        self.note("Repeated bodies");
+        return None;
+      }
+      Body::Mirrored(_) => {
+        self.note("Mirrored bodies");
+        return None;
+      }
+      Body::Mesh(_) => {
+        self.note("Meshes");
+        return None;
+      }
+    };
+    self.uses.insert(path);
+
+    if let Some(transform) = self.transform(body.transform()) {
+      calls.push(format!("with_transform({})", transform));
+    }
+    if let Some(material) = self.material(index, body.material()) {
+      self.uses.insert("rtc_core::material::Material");
+      calls.push(format!("with_material(Material::from({}))", material));
+    }
+    let shadows = body.shadows();
+    if shadows.cast != Shadows::default().cast || shadows.receive != Shadows::default().receive {
+      self.uses.insert("rtc_core::body::Shadows");
+      calls.push(format!(
+        "with_shadows(Shadows {{ cast: {}, receive: {} }})",
+        shadows.cast, shadows.receive
+      ));
+    }
+
+    Some(self.binding(format!("body_{}", index), start, calls))
+  }
+
+  /// Name of the binding of the material, if it differs from the default
+  fn material(&mut self, index: usize, material: Material) -> Option<String> {
+    if material.fuzzy_eq(Material::default()) {
+      return None;
+    }
+    let name = format!("material_{}", index);
+    match material {
+      Material::Phong(ref phong) => {
+        let defaults = Phong::default();
+        let mut calls = self.surface(
+          index,
+          phong.color,
+          phong.pattern.clone(),
+          phong.ambient,
+          phong.diffuse,
+          (defaults.color, defaults.ambient, defaults.diffuse),
+        );
+        let scalars = [
+          ("specular", phong.specular, defaults.specular),
+          ("shininess", phong.shininess, defaults.shininess),
+          (
+            "reflectiveness",
+            phong.reflectiveness,
+            defaults.reflectiveness,
+          ),
+          ("transparency", phong.transparency, defaults.transparency),
+          (
+            "refractive_index",
+            phong.refractive_index,
+            defaults.refractive_index,
+          ),
+          ("shadow_bias", phong.shadow_bias, defaults.shadow_bias),
+        ];
+        for (builder, value, default) in scalars.iter() {
+          if !value.fuzzy_eq(*default) {
+            calls.push(format!("with_{}({})", builder, float(*value)));
+          }
+        }
+        if let Some(coat) = phong.coat {
+          self.uses.insert("rtc_core::material::coat::Coat");
+          calls.push(format!(
+            "with_coat(Coat::new({}, {}).with_tint({}))",
+            float(coat.reflectiveness),
+            float(coat.roughness),
+            self.color(coat.tint)
+          ));
+        }
+        if phong.bump.is_some() {
+          self.note("Bump maps");
+        }
+        if phong.cutout.is_some() {
+          self.note("Cutouts");
+        }
+        self.uses.insert("rtc_core::material::Phong");
+        Some(self.binding(name, "Phong::default()".into(), calls))
+      }
+      Material::Lambert(ref lambert) => {
+        let defaults = Lambert::default();
+        let mut calls = self.surface(
+          index,
+          lambert.color,
+          lambert.pattern.clone(),
+          lambert.ambient,
+          lambert.diffuse,
+          (defaults.color, defaults.ambient, defaults.diffuse),
+        );
+        if !lambert.shadow_bias.fuzzy_eq(defaults.shadow_bias) {
+          calls.push(format!("with_shadow_bias({})", float(lambert.shadow_bias)));
+        }
+        self.uses.insert("rtc_core::material::lambert::Lambert");
+        Some(self.binding(name, "Lambert::default()".into(), calls))
+      }
+      Material::Layered(_) => {
+        self.note("Layered materials");
+        None
+      }
+    }
+  }
+
+  /// Builder calls shared by Phong and Lambert materials
+  fn surface(
+    &mut self,
+    index: usize,
+    color: Color,
+    pattern: Option<Pattern>,
+    ambient: F,
+    diffuse: F,
+    (default_color, default_ambient, default_diffuse): (Color, F, F),
+  ) -> Vec<String> {
+    let mut calls = vec![];
+    if !color.fuzzy_eq(default_color) {
+      calls.push(format!("with_color({})", self.color(color)));
+    }
+    if let Some(pattern) =
+      pattern.and_then(|pattern| self.pattern(format!("pattern_{}", index), pattern))
+    {
+      self.uses.insert("rtc_core::pattern::Pattern");
+      calls.push(format!("with_pattern(Pattern::from({}))", pattern));
+    }
+    if !ambient.fuzzy_eq(default_ambient) {
+      calls.push(format!("with_ambient({})", float(ambient)));
+    }
+    if !diffuse.fuzzy_eq(default_diffuse) {
+      calls.push(format!("with_diffuse({})", float(diffuse)));
+    }
+    calls
+  }
+
+  fn pattern(&mut self, name: String, pattern: Pattern) -> Option<String> {
+    let (kind, transform, color_a, color_b, mut extra) = match pattern {
+      Pattern::Striped(p) => {
+        let mut calls = vec![];
+        if !p.direction.fuzzy_eq(Striped::default().direction) {
+          calls.push(format!("with_direction({})", self.vector(p.direction)));
+        }
+        ("Striped", p.transform(), p.color_a, p.color_b, calls)
+      }
+      Pattern::Gradient(p) => {
+        let mut calls = vec![];
+        if p.easing != Easing::default() {
+          self.uses.insert("rtc_core::pattern::Easing");
+          calls.push(format!("with_easing(Easing::{:?})", p.easing));
+        }
+        ("Gradient", p.transform(), p.color_a, p.color_b, calls)
+      }
+      Pattern::Ring(p) => ("Ring", p.transform(), p.color_a, p.color_b, vec![]),
+      Pattern::CheckerBoard(p) => {
+        let mut calls = vec![];
+        if p.third_dimension != CheckerBoard::default().third_dimension {
+          calls.push(format!("with_third_dimension({})", p.third_dimension));
+        }
+        ("CheckerBoard", p.transform(), p.color_a, p.color_b, calls)
+      }
+      Pattern::Noise(p) => {
+        let defaults = Noise::default();
+        let mut calls = vec![];
+        if p.octaves != defaults.octaves {
+          calls.push(format!("with_octaves({})", p.octaves));
+        }
+        if !p.persistence.fuzzy_eq(defaults.persistence) {
+          calls.push(format!("with_persistence({})", float(p.persistence)));
+        }
+        if p.seed != defaults.seed {
+          calls.push(format!("with_seed({})", p.seed));
+        }
+        ("Noise", p.transform(), p.color_a, p.color_b, calls)
+      }
+      Pattern::Texture(_) => {
+        self.note("Textures");
+        return None;
+      }
+      Pattern::TextureMap(_) => {
+        self.note("Texture maps");
+        return None;
+      }
+      Pattern::CubeMap(_) => {
+        self.note("Cube maps");
+        return None;
+      }
+      Pattern::Test(p) => {
+        self.uses.insert("rtc_core::pattern::TestPattern");
+        let calls = self
+          .transform(p.transform())
+          .map(|transform| vec![format!("with_transform({})", transform)])
+          .unwrap_or_default();
+        return Some(self.binding(name, "TestPattern::default()".to_string(), calls));
+      }
+    };
+    self.uses.insert(match kind {
+      "Striped" => "rtc_core::pattern::Striped",
+      "Gradient" => "rtc_core::pattern::Gradient",
+      "Ring" => "rtc_core::pattern::Ring",
+      "CheckerBoard" => "rtc_core::pattern::CheckerBoard",
+      _ => "rtc_core::pattern::Noise",
+    });
+
+    let mut calls = vec![format!(
+      "with_colors({}, {})",
+      self.fill(format!("{}_a", name), color_a),
+      self.fill(format!("{}_b", name), color_b)
+    )];
+    if let Some(transform) = self.transform(transform) {
+      calls.push(format!("with_transform({})", transform));
+    }
+    calls.append(&mut extra);
+    Some(self.binding(name, format!("{}::default()", kind), calls))
+  }
+
+  /// Color of a slot of a pattern, or the nested pattern bound to the name
+  fn fill(&mut self, name: String, fill: Fill) -> String {
+    match fill {
+      Fill::Color(color) => self.color(color),
+      Fill::Pattern(pattern) => match self.pattern(name, Pattern::clone(&pattern)) {
+        Some(pattern) => {
+          self.uses.insert("rtc_core::pattern::Pattern");
+          format!("Pattern::from({})", pattern)
+        }
+        None => self.color(Color::black()),
+      },
+    }
+  }
+
+  fn light(&mut self, index: usize, light: &Light) -> String {
+    match *light {
+      Light::Point(ref point_light) => {
+        self.uses.insert("rtc_core::light::PointLight");
+        let start = format!(
+          "PointLight::new({}, {})",
+          self.point(point_light.position),
+          self.color(point_light.intensity)
+        );
+        let mut calls = vec![];
+        let attenuation = point_light.attenuation;
+        if !attenuation.fuzzy_eq(Attenuation::default()) {
+          self.uses.insert("rtc_core::light::Attenuation");
+          calls.push(format!(
+            "with_attenuation(Attenuation::new({}, {}, {}))",
+            float(attenuation.constant),
+            float(attenuation.linear),
+            float(attenuation.quadratic)
+          ));
+        }
+        self.binding(format!("light_{}", index), start, calls)
+      }
+    }
+  }
+
+  fn camera(&mut self, name: &str, camera: &Camera) -> String {
+    self.uses.insert("rtc_core::angle::Angle");
+    let mut calls = vec![];
+    if let Some((from, to, up)) = view(camera.transform) {
+      calls.push(format!(
+        "look_at_from_position({}, {}, {})",
+        self.point(from),
+        self.point(to),
+        self.vector(up)
+      ));
+    } else if let Some(transform) = self.transform(camera.transform) {
+      calls.push(format!("with_transform({})", transform));
+    }
+    if camera.samples > 1 {
+      calls.push(format!("with_samples({})", camera.samples));
+    }
+    if !camera.exposure.fuzzy_eq(0.0) {
+      calls.push(format!("with_exposure({})", float(camera.exposure)));
+    }
+    if camera.aperture > 0.0 {
+      calls.push(format!(
+        "with_depth_of_field({}, {})",
+        float(camera.aperture),
+        float(camera.focal_distance)
+      ));
+    }
+    if camera.adaptive_sampling.is_some() {
+      self.note("Adaptive sampling");
+    }
+    if !camera.post_processing.effects.is_empty() {
+      self.note("Post processing");
+    }
+
+    let mut function = format!(
+      "fn camera_{}() -> Camera {{\n  Camera::new({}, {}, Angle::degrees({}))",
+      identifier(name),
+      camera.hsize,
+      camera.vsize,
+      float(camera.field_of_view.to_degrees())
+    );
+    for call in calls.iter() {
+      function += &format!("\n    .{}", call);
+    }
+    function += "\n}\n";
+    function
+  }
+
+  /// Expression of the transformation, unless it is the identity
+  fn transform(&mut self, transform: Matrix<4>) -> Option<String> {
+    if transform.fuzzy_eq(Matrix::identity()) {
+      return None;
+    }
+    self.uses.insert("rtc_core::matrix::Matrix");
+    if let Some(parts) = decompose(transform) {
+      if parts.iter().any(|part| part.contains("Angle")) {
+        self.uses.insert("rtc_core::angle::Angle");
+      }
+      return Some(parts.join(" * "));
+    }
+    let rows: Vec<String> = (0..4)
+      .map(|row| {
+        let values: Vec<String> = (0..4).map(|column| float(transform[row][column])).collect();
+        format!("[{}]", values.join(", "))
+      })
+      .collect();
+    Some(format!("Matrix::from([{}])", rows.join(", ")))
+  }
+
+  fn color(&mut self, color: Color) -> String {
+    self.uses.insert("rtc_core::canvas::Color");
+    format!(
+      "Color::new({}, {}, {})",
+      float(color.red),
+      float(color.green),
+      float(color.blue)
+    )
+  }
+
+  fn point(&mut self, point: Tuple) -> String {
+    self.uses.insert("rtc_core::tuple::Tuple");
+    format!(
+      "Tuple::point({}, {}, {})",
+      float(point.x),
+      float(point.y),
+      float(point.z)
+    )
+  }
+
+  fn vector(&mut self, vector: Tuple) -> String {
+    self.uses.insert("rtc_core::tuple::Tuple");
+    format!(
+      "Tuple::vector({}, {}, {})",
+      float(vector.x),
+      float(vector.y),
+      float(vector.z)
+    )
+  }
+}
+
+/// Builder calls of cylinders and cones, which share their defaults
+fn extent(minimum: F, maximum: F, closed: bool) -> Vec<String> {
+  let mut calls = vec![];
+  if minimum != F::NEG_INFINITY {
+    calls.push(format!("with_minimum({})", float(minimum)));
+  }
+  if maximum != F::INFINITY {
+    calls.push(format!("with_maximum({})", float(maximum)));
+  }
+  if closed {
+    calls.push("with_closed(true)".to_string());
+  }
+  calls
+}
+
+/// Splits the transformation into translation, rotation around a single axis
+/// and scaling, leaving out the parts doing nothing
+fn decompose(transform: Matrix<4>) -> Option<Vec<String>> {
+  let scale: Vec<F> = (0..3)
+    .map(|column| {
+      Tuple::vector(
+        transform[0][column],
+        transform[1][column],
+        transform[2][column],
+      )
+      .magnitude()
+    })
+    .collect();
+  if scale.iter().any(|factor| factor.fuzzy_eq(0.0)) {
+    return None;
+  }
+  let rotation = |row: usize, column: usize| transform[row][column] / scale[column];
+  let candidates = [
+    ("x", rotation(2, 1).atan2(rotation(1, 1))),
+    ("y", rotation(0, 2).atan2(rotation(0, 0))),
+    ("z", rotation(1, 0).atan2(rotation(0, 0))),
+  ];
+  let (x, y, z) = (transform[0][3], transform[1][3], transform[2][3]);
+
+  candidates.iter().find_map(|(axis, angle)| {
+    let rotation = match *axis {
+      "x" => Matrix::rotation_x(Angle::radians(*angle)),
+      "y" => Matrix::rotation_y(Angle::radians(*angle)),
+      _ => Matrix::rotation_z(Angle::radians(*angle)),
+    };
+    let candidate =
+      Matrix::translation(x, y, z) * rotation * Matrix::scaling(scale[0], scale[1], scale[2]);
+    if !candidate.fuzzy_eq(transform) {
+      return None;
+    }
+
+    let mut parts = vec![];
+    if !(x.fuzzy_eq(0.0) && y.fuzzy_eq(0.0) && z.fuzzy_eq(0.0)) {
+      parts.push(format!(
+        "Matrix::translation({}, {}, {})",
+        float(x),
+        float(y),
+        float(z)
+      ));
+    }
+    if !angle.fuzzy_eq(0.0) {
+      parts.push(format!(
+        "Matrix::rotation_{}(Angle::degrees({}))",
+        axis,
+        float(angle.to_degrees())
+      ));
+    }
+    if scale.iter().any(|factor| !factor.fuzzy_eq(1.0)) {
+      parts.push(format!(
+        "Matrix::scaling({}, {}, {})",
+        float(scale[0]),
+        float(scale[1]),
+        float(scale[2])
+      ));
+    }
+    Some(parts)
+  })
+}
+
+/// Position, target and up vector of a view transformation
+fn view(transform: Matrix<4>) -> Option<(Tuple, Tuple, Tuple)> {
+  if transform.fuzzy_eq(Matrix::identity()) {
+    return None;
+  }
+  let inverse = transform.inverse();
+  let from = inverse * Tuple::point(0.0, 0.0, 0.0);
+  let to = from + inverse * Tuple::vector(0.0, 0.0, -1.0);
+  let up = inverse * Tuple::vector(0.0, 1.0, 0.0);
+  if Matrix::view_transform(from, to, up).fuzzy_eq(transform) {
+    Some((from, to, up))
+  } else {
+    None
+  }
+}
+
+/// Float literal, rounded to hide errors of the float arithmetic
+fn float(value: F) -> String {
+  if value.is_infinite() {
+    return if value > 0.0 {
+      "f64::INFINITY"
+    } else {
+      "-f64::INFINITY"
+    }
+    .to_string();
+  }
+  let rounded = (value * 1e6).round() / 1e6;
+  // Avoids writing -0.0
+  format!("{:?}", rounded + 0.0)
+}
+
+/// Name usable in a function name
+fn identifier(name: &str) -> String {
+  name
+    .chars()
+    .map(|character| {
+      if character.is_ascii_alphanumeric() {
+        character.to_ascii_lowercase()
+      } else {
+        '_'
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rtc_core::light::PointLight;
+  use rtc_core::pattern::Striped;
+  use rtc_core::sphere::Sphere;
+  use std::f64::consts::PI;
+
+  #[test]
+  fn bodies_are_written_with_their_builders() {
+    let material = Phong::default()
+      .with_color(Color::new(0.5, 0.25, 1.0))
+      .with_pattern(Pattern::from(
+        Striped::default().with_colors(Color::black(), Color::white()),
+      ));
+    let sphere = Sphere::default()
+      .with_transform(Matrix::translation(1.0, 2.0, 3.0) * Matrix::scaling(0.5, 0.5, 0.5))
+      .with_material(Material::from(material));
+    let light = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::white());
+    let world = World::new(vec![sphere.into()], vec![light.into()]);
+
+    let rust = to_rust(&world, &HashMap::new());
+
+    assert!(rust.contains("use rtc_core::sphere::Sphere;\n"));
+    assert!(rust.contains(
+      "  let body_0 = Sphere::default()\n    .with_transform(Matrix::translation(1.0, 2.0, 3.0) * Matrix::scaling(0.5, 0.5, 0.5))\n    .with_material(Material::from(material_0));"
+    ));
+    assert!(rust.contains("    .with_pattern(Pattern::from(pattern_0))"));
+    assert!(rust.contains("  let light_0 = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));"));
+    assert!(rust.contains("    vec![Body::from(body_0)],\n    vec![Light::from(light_0)],"));
+    assert!(!rust.contains("Not exported"));
+  }
+
+  #[test]
+  fn transformations_are_split_into_their_parts_where_possible() {
+    let rotated = Matrix::translation(0.0, 1.0, 0.0) * Matrix::rotation_y(Angle::radians(PI / 4.0));
+    let sheared =
+      Matrix::rotation_x(Angle::radians(PI / 4.0)) * Matrix::rotation_y(Angle::radians(PI / 4.0));
+
+    assert_eq!(
+      decompose(rotated),
+      Some(vec![
+        "Matrix::translation(0.0, 1.0, 0.0)".to_string(),
+        "Matrix::rotation_y(Angle::degrees(45.0))".to_string()
+      ])
+    );
+    assert_eq!(decompose(sheared), None);
+  }
+
+  #[test]
+  fn cameras_look_from_their_position() {
+    let camera = Camera::new(100, 50, Angle::radians(PI / 3.0)).look_at_from_position(
+      Tuple::point(0.0, 1.5, -5.0),
+      Tuple::point(0.0, 1.5, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+    );
+    let mut cameras = HashMap::new();
+    cameras.insert("Main View".to_string(), camera);
+
+    let rust = to_rust(&World::default(), &cameras);
+
+    assert!(rust.contains(
+      "fn camera_main_view() -> Camera {\n  Camera::new(100, 50, Angle::degrees(60.0))\n    .look_at_from_position(Tuple::point(0.0, 1.5, -5.0), Tuple::point(0.0, 1.5, -4.0), Tuple::vector(0.0, 1.0, 0.0))\n}"
+    ));
+    assert!(rust.contains("vec![(\"Main View\", camera_main_view())]"));
+  }
+}
@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+
+use rtc_core::camera::Camera;
+use rtc_core::world::World;
+
+pub type LoaderResult = anyhow::Result<(World, HashMap<String, Camera>)>;
+pub type LoaderWithWarningsResult = anyhow::Result<(World, HashMap<String, Camera>, Vec<String>)>;
+pub trait WorldLoader {
+  fn load_world_with_warnings<T: AsRef<str>>(&self, source: T) -> LoaderWithWarningsResult;
+
+  fn load_world<T: AsRef<str>>(&self, source: T) -> LoaderResult {
+    let (world, cameras, _warnings) = self.load_world_with_warnings(source)?;
+    Ok((world, cameras))
+  }
+}
+
+pub mod cube;
+pub mod obj;
+pub mod orientation;
+pub mod schema;
+pub mod svg;
+pub mod yaml;
@@ -0,0 +1,148 @@
+//! Parser for 3D lookup tables stored as Adobe/Resolve `.cube` files.
+//!
+//! `LUT_3D_SIZE`, `DOMAIN_MIN` and `DOMAIN_MAX` are supported, followed by
+//! the entries of the table with red changing fastest. Titles and comments
+//! are ignored, 1D tables are rejected.
+
+use anyhow::{anyhow, Result};
+use rtc_core::canvas::Color;
+use rtc_core::post_processing::lut::Lut;
+use rtc_core::F;
+
+/// Largest size the format allows, which keeps the table of `size³` entries
+/// within memory
+const MAX_SIZE: usize = 256;
+
+/// Parses the given `.cube` source into a lookup table.
+pub fn parse_cube(source: &str) -> Result<Lut> {
+  let mut size: Option<usize> = None;
+  let mut domain_min = Color::black();
+  let mut domain_max = Color::white();
+  let mut table: Vec<Color> = vec![];
+
+  for (index, line) in source.lines().enumerate() {
+    let line_number = index + 1;
+    let mut parts = line.split_whitespace();
+    match parts.next() {
+      None => {}
+      Some(keyword) if keyword.starts_with('#') || keyword == "TITLE" => {}
+      Some("LUT_3D_SIZE") => {
+        size = parts.next().and_then(|size| size.parse::<usize>().ok());
+        if !matches!(size, Some(size) if (2..=MAX_SIZE).contains(&size)) {
+          return Err(anyhow!(
+            "Expected a size between 2 and {}, but found '{}' at line {}",
+            MAX_SIZE,
+            line,
+            line_number
+          ));
+        }
+      }
+      Some("LUT_1D_SIZE") => {
+        return Err(anyhow!(
+          "Only 3D lookup tables are supported, but found '{}' at line {}",
+          line,
+          line_number
+        ))
+      }
+      Some("DOMAIN_MIN") => domain_min = parse_color(parts, line, line_number)?,
+      Some("DOMAIN_MAX") => domain_max = parse_color(parts, line, line_number)?,
+      Some(_) => table.push(parse_color(line.split_whitespace(), line, line_number)?),
+    }
+  }
+
+  let size = size.ok_or_else(|| anyhow!("Expected a LUT_3D_SIZE, but found none"))?;
+  if table.len() != size * size * size {
+    return Err(anyhow!(
+      "Expected {} entries for a LUT of size {}, but found {}",
+      size * size * size,
+      size,
+      table.len()
+    ));
+  }
+
+  Ok(Lut::new(size, table).with_domain(domain_min, domain_max))
+}
+
+fn parse_color<'a, I>(parts: I, line: &str, line_number: usize) -> Result<Color>
+where
+  I: Iterator<Item = &'a str>,
+{
+  let values = parts
+    .map(|part| part.parse::<F>())
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap_or_default();
+
+  match values.as_slice() {
+    [red, green, blue] => Ok(Color::new(*red, *green, *blue)),
+    _ => Err(anyhow!(
+      "Expected three numbers, but found '{}' at line {}",
+      line,
+      line_number
+    )),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rtc_core::assert_fuzzy_eq;
+  use rtc_core::fuzzy_eq::*;
+
+  #[test]
+  fn a_cube_file_is_parsed() {
+    let source = r##"
+# Swaps red and blue
+TITLE "Swap"
+LUT_3D_SIZE 2
+DOMAIN_MIN 0 0 0
+DOMAIN_MAX 1 1 1
+
+0 0 0
+0 0 1
+0 1 0
+0 1 1
+1 0 0
+1 0 1
+1 1 0
+1 1 1
+"##;
+
+    let lut = parse_cube(source).unwrap();
+
+    assert_eq!(lut.size, 2);
+    assert_fuzzy_eq!(
+      lut.lookup(Color::new(0.2, 0.5, 0.9)),
+      Color::new(0.9, 0.5, 0.2)
+    );
+  }
+
+  #[test]
+  fn missing_entries_are_an_error() {
+    let error = parse_cube("LUT_3D_SIZE 2\n0 0 0\n").unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Expected 8 entries for a LUT of size 2, but found 1"
+    );
+  }
+
+  #[test]
+  fn sizes_beyond_the_format_are_rejected() {
+    let error = parse_cube("LUT_3D_SIZE 4294967296\n").unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Expected a size between 2 and 256, but found 'LUT_3D_SIZE 4294967296' at line 1"
+    );
+  }
+
+  #[test]
+  fn one_dimensional_tables_are_rejected() {
+    let error = parse_cube("LUT_1D_SIZE 16\n").unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Only 3D lookup tables are supported, but found 'LUT_1D_SIZE 16' at line 1"
+    );
+  }
+}
@@ -0,0 +1,273 @@
+//! Parser for triangle meshes stored as Wavefront OBJ files.
+//!
+//! Vertices (`v`), vertex normals (`vn`) and faces (`f`) are supported. Faces
+//! with more than three vertices are triangulated as a fan. Every other
+//! statement (groups, texture coordinates, materials, ...) is ignored.
+
+use anyhow::{anyhow, Result};
+use rtc_core::mesh::{Face, Mesh};
+use rtc_core::triangle::{SmoothTriangle, Triangle};
+use rtc_core::tuple::Tuple;
+use rtc_core::F;
+
+/// Parses the given OBJ source into a mesh.
+pub fn parse_obj(source: &str) -> Result<Mesh> {
+  let mut vertices: Vec<Tuple> = vec![];
+  let mut normals: Vec<Tuple> = vec![];
+  let mut faces: Vec<Face> = vec![];
+
+  for (index, line) in source.lines().enumerate() {
+    let line_number = index + 1;
+    let mut parts = line.split_whitespace();
+    match parts.next() {
+      Some("v") => {
+        let [x, y, z] = parse_coordinates(parts, line, line_number)?;
+        vertices.push(Tuple::point(x, y, z));
+      }
+      Some("vn") => {
+        let [x, y, z] = parse_coordinates(parts, line, line_number)?;
+        normals.push(Tuple::vector(x, y, z));
+      }
+      Some("f") => {
+        let corners = parts
+          .map(|corner| parse_corner(corner, &vertices, &normals, line_number))
+          .collect::<Result<Vec<_>>>()?;
+        if corners.len() < 3 {
+          return Err(anyhow!(
+            "Expected a face with at least three vertices, but found '{}' at line {}",
+            line,
+            line_number
+          ));
+        }
+        faces.extend(triangulate(&corners));
+      }
+      _ => {}
+    }
+  }
+
+  Ok(Mesh::new(faces))
+}
+
+fn parse_coordinates<'a, I>(parts: I, line: &str, line_number: usize) -> Result<[F; 3]>
+where
+  I: Iterator<Item = &'a str>,
+{
+  let coordinates = parts
+    .take(3)
+    .map(|part| part.parse::<F>())
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap_or_default();
+
+  match coordinates.as_slice() {
+    [x, y, z] => Ok([*x, *y, *z]),
+    _ => Err(anyhow!(
+      "Expected three coordinates, but found '{}' at line {}",
+      line,
+      line_number
+    )),
+  }
+}
+
+/// Resolves a 1-based (or negative, relative to the end) index
+fn resolve<T: Copy>(items: &[T], index: &str, kind: &str, line_number: usize) -> Result<T> {
+  let position = index
+    .parse::<isize>()
+    .ok()
+    .and_then(|index| match index {
+      index if index > 0 => Some(index as usize - 1),
+      index if index < 0 => items.len().checked_sub(index.unsigned_abs()),
+      _ => None,
+    })
+    .filter(|position| *position < items.len());
+
+  match position {
+    Some(position) => Ok(items[position]),
+    None => Err(anyhow!(
+      "Invalid {} index '{}' found at line {}",
+      kind,
+      index,
+      line_number
+    )),
+  }
+}
+
+/// A face corner of the form `v`, `v/vt`, `v//vn` or `v/vt/vn`
+fn parse_corner(
+  corner: &str,
+  vertices: &[Tuple],
+  normals: &[Tuple],
+  line_number: usize,
+) -> Result<(Tuple, Option<Tuple>)> {
+  let mut indices = corner.split('/');
+  let vertex = resolve(
+    vertices,
+    indices.next().unwrap_or(""),
+    "vertex",
+    line_number,
+  )?;
+  let normal = match indices.nth(1) {
+    Some(index) if !index.is_empty() => Some(resolve(normals, index, "normal", line_number)?),
+    _ => None,
+  };
+  Ok((vertex, normal))
+}
+
+/// Fan triangulation of a convex polygon
+fn triangulate(corners: &[(Tuple, Option<Tuple>)]) -> Vec<Face> {
+  let (p1, n1) = corners[0];
+  corners[1..]
+    .windows(2)
+    .map(|pair| {
+      let ((p2, n2), (p3, n3)) = (pair[0], pair[1]);
+      match (n1, n2, n3) {
+        (Some(n1), Some(n2), Some(n3)) => Face::from(SmoothTriangle::new(p1, p2, p3, n1, n2, n3)),
+        _ => Face::from(Triangle::new(p1, p2, p3)),
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rtc_core::assert_fuzzy_eq;
+  use rtc_core::body::Intersectable;
+  use rtc_core::fuzzy_eq::*;
+
+  #[test]
+  fn unrecognized_lines_are_ignored() {
+    let source = r##"
+There was a young lady named Bright
+who traveled much faster than light.
+# A comment
+g FirstGroup
+vt 0.5 0.5
+"##;
+
+    let mesh = parse_obj(source).unwrap();
+
+    assert_eq!(mesh.faces.len(), 0);
+  }
+
+  #[test]
+  fn triangle_faces_are_parsed() {
+    let source = r##"
+v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+f 1 3 4
+"##;
+
+    let mesh = parse_obj(source).unwrap();
+
+    assert_eq!(
+      mesh.faces,
+      vec![
+        Face::from(Triangle::new(
+          Tuple::point(-1.0, 1.0, 0.0),
+          Tuple::point(-1.0, 0.0, 0.0),
+          Tuple::point(1.0, 0.0, 0.0),
+        )),
+        Face::from(Triangle::new(
+          Tuple::point(-1.0, 1.0, 0.0),
+          Tuple::point(1.0, 0.0, 0.0),
+          Tuple::point(1.0, 1.0, 0.0),
+        )),
+      ]
+    );
+  }
+
+  #[test]
+  fn polygons_are_triangulated_as_a_fan() {
+    let source = r##"
+v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+v 0 2 0
+
+f 1 2 3 4 5
+"##;
+
+    let mesh = parse_obj(source).unwrap();
+
+    assert_eq!(mesh.faces.len(), 3);
+    assert_eq!(
+      mesh.faces[2],
+      Face::from(Triangle::new(
+        Tuple::point(-1.0, 1.0, 0.0),
+        Tuple::point(1.0, 1.0, 0.0),
+        Tuple::point(0.0, 2.0, 0.0),
+      ))
+    );
+  }
+
+  #[test]
+  fn faces_with_normals_are_smooth() {
+    let source = r##"
+v 0 1 0
+v -1 0 0
+v 1 0 0
+
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+
+f 1//3 2//1 3//2
+f 1/0/3 2/102/1 -1/5/-2
+"##;
+
+    let mesh = parse_obj(source).unwrap();
+
+    let expected = Face::from(SmoothTriangle::new(
+      Tuple::point(0.0, 1.0, 0.0),
+      Tuple::point(-1.0, 0.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0),
+      Tuple::vector(-1.0, 0.0, 0.0),
+      Tuple::vector(1.0, 0.0, 0.0),
+    ));
+    assert_eq!(mesh.faces, vec![expected.clone(), expected]);
+    assert_fuzzy_eq!(
+      mesh.body().normal_at(Tuple::point(0.0, 0.5, 0.0)),
+      Tuple::vector(0.0, 1.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn malformed_input_is_rejected() {
+    let examples = vec![
+      (
+        "v 1 2\n",
+        "Expected three coordinates, but found 'v 1 2' at line 1",
+      ),
+      (
+        "v 1 2 3\nvn 1 a 3\n",
+        "Expected three coordinates, but found 'vn 1 a 3' at line 2",
+      ),
+      (
+        "v 1 2 3\nv 1 2 4\nf 1 2\n",
+        "Expected a face with at least three vertices, but found 'f 1 2' at line 3",
+      ),
+      (
+        "v 1 2 3\nv 1 2 4\nv 1 3 4\nf 1 2 4\n",
+        "Invalid vertex index '4' found at line 4",
+      ),
+      (
+        "v 1 2 3\nv 1 2 4\nv 1 3 4\nf 0 1 2\n",
+        "Invalid vertex index '0' found at line 4",
+      ),
+      (
+        "v 1 2 3\nv 1 2 4\nv 1 3 4\nf 1//1 2//1 3//1\n",
+        "Invalid normal index '1' found at line 4",
+      ),
+    ];
+
+    for (source, message) in examples {
+      assert_eq!(parse_obj(source).unwrap_err().to_string(), message);
+    }
+  }
+}
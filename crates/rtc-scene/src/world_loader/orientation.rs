@@ -0,0 +1,106 @@
+//! Axis conventions of imported models.
+//!
+//! The renderer uses a left-handed coordinate system with y pointing up.
+//! Many modelling tools export right-handed models, often with z pointing
+//! up, which would otherwise end up mirrored or lying on their side.
+
+use rtc_core::angle::Angle;
+use rtc_core::matrix::Matrix;
+
+/// Axis pointing up in a model
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpAxis {
+  X,
+  #[default]
+  Y,
+  Z,
+}
+
+/// Handedness of the coordinate system of a model
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Handedness {
+  /// The convention of the renderer itself
+  #[default]
+  Left,
+  Right,
+}
+
+/// Coordinate convention of a model, converted to the one of the renderer
+/// when loading it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Orientation {
+  pub up: UpAxis,
+  pub handedness: Handedness,
+}
+
+impl Orientation {
+  pub fn new(up: UpAxis, handedness: Handedness) -> Self {
+    Self { up, handedness }
+  }
+
+  /// Transformation from the coordinates of the model to the ones of the
+  /// renderer, applied before any transformation of the body itself.
+  pub fn to_world(&self) -> Matrix<4> {
+    let rotation = match self.up {
+      UpAxis::X => Matrix::rotation_z(Angle::degrees(90.0)),
+      UpAxis::Y => Matrix::identity(),
+      UpAxis::Z => Matrix::rotation_x(Angle::degrees(-90.0)),
+    };
+    match self.handedness {
+      Handedness::Left => rotation,
+      Handedness::Right => Matrix::scaling(1.0, 1.0, -1.0) * rotation,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rtc_core::assert_fuzzy_eq;
+  use rtc_core::fuzzy_eq::*;
+  use rtc_core::tuple::Tuple;
+
+  #[test]
+  fn the_default_orientation_keeps_models_as_they_are() {
+    assert_fuzzy_eq!(Orientation::default().to_world(), Matrix::identity());
+  }
+
+  #[test]
+  fn right_handed_z_up_models_are_turned_upright_without_mirroring() {
+    let to_world = Orientation::new(UpAxis::Z, Handedness::Right).to_world();
+
+    // Up stays up, and forward (away from the viewer) stays forward
+    assert_fuzzy_eq!(
+      to_world * Tuple::vector(0.0, 0.0, 1.0),
+      Tuple::vector(0.0, 1.0, 0.0)
+    );
+    assert_fuzzy_eq!(
+      to_world * Tuple::vector(0.0, 1.0, 0.0),
+      Tuple::vector(0.0, 0.0, 1.0)
+    );
+    assert_fuzzy_eq!(
+      to_world * Tuple::vector(1.0, 0.0, 0.0),
+      Tuple::vector(1.0, 0.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn right_handed_y_up_models_are_mirrored_along_z() {
+    let to_world = Orientation::new(UpAxis::Y, Handedness::Right).to_world();
+
+    assert_fuzzy_eq!(
+      to_world * Tuple::point(1.0, 2.0, 3.0),
+      Tuple::point(1.0, 2.0, -3.0)
+    );
+  }
+
+  #[test]
+  fn x_up_models_are_turned_upright() {
+    let to_world = Orientation::new(UpAxis::X, Handedness::Left).to_world();
+
+    assert_fuzzy_eq!(
+      to_world * Tuple::vector(1.0, 0.0, 0.0),
+      Tuple::vector(0.0, 1.0, 0.0)
+    );
+  }
+}
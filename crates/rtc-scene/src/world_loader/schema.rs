@@ -0,0 +1,1768 @@
+use itertools::Itertools;
+
+/// Type of a value stored under a key of a world definition block
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValueType {
+  String,
+  Integer,
+  Float,
+  Boolean,
+  /// String, which needs to be one of the given values
+  OneOf(&'static [&'static str]),
+  /// Array of exactly three numbers (points, vectors and colors)
+  Triple,
+  /// Array of arbitrary many numbers
+  FloatArray,
+  /// Single string or array of strings
+  StringOrStringArray,
+  /// Array of exactly three triples (e.g. the corners of a triangle)
+  TripleArray,
+  /// Array of arbitrary many triples
+  TripleList,
+  /// Array of exactly three numbers, or nested block with the given name
+  TripleOrBlock(&'static str),
+  /// Number in radians, or nested 'angle' block with degrees or radians
+  Angle,
+  /// Number or string of the form "16:9"
+  AspectRatio,
+  /// Nested block with the given name
+  Block(&'static str),
+  /// Nested block with the given name, or the name of a registered one
+  BlockOrName(&'static str),
+  /// Array of nested blocks with the given name
+  BlockArray(&'static str),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Field {
+  pub name: &'static str,
+  pub value_type: ValueType,
+  pub required: bool,
+  pub default: Option<&'static str>,
+  /// Defaults replacing `default` for some kinds of the block, as pairs of
+  /// the value of its 'type' and the default
+  pub kind_defaults: &'static [(&'static str, &'static str)],
+  pub description: &'static str,
+}
+
+impl Field {
+  pub const fn required(
+    name: &'static str,
+    value_type: ValueType,
+    description: &'static str,
+  ) -> Self {
+    Self {
+      name,
+      value_type,
+      required: true,
+      default: None,
+      kind_defaults: &[],
+      description,
+    }
+  }
+
+  pub const fn optional(
+    name: &'static str,
+    value_type: ValueType,
+    default: Option<&'static str>,
+    description: &'static str,
+  ) -> Self {
+    Self {
+      name,
+      value_type,
+      required: false,
+      default,
+      kind_defaults: &[],
+      description,
+    }
+  }
+
+  pub const fn with_kind_defaults(
+    self,
+    kind_defaults: &'static [(&'static str, &'static str)],
+  ) -> Self {
+    Self {
+      kind_defaults,
+      ..self
+    }
+  }
+
+  /// Default of the field in a block of the given kind, if it has any
+  pub fn default_of(&self, kind: Option<&str>) -> Option<&'static str> {
+    self
+      .kind_defaults
+      .iter()
+      .find(|(of_kind, _)| Some(*of_kind) == kind)
+      .map(|(_, default)| *default)
+      .or(self.default)
+  }
+}
+
+/// Description of all keys allowed in one kind of block (hash) of a world
+/// definition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Block {
+  pub name: &'static str,
+  pub description: &'static str,
+  pub fields: &'static [Field],
+}
+
+impl Block {
+  pub fn field(&self, name: &str) -> Option<&Field> {
+    self.fields.iter().find(|field| field.name == name)
+  }
+
+  pub fn contains(&self, name: &str) -> bool {
+    self.field(name).is_some()
+  }
+
+  pub fn field_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+    self.fields.iter().map(|field| field.name)
+  }
+
+  /// Known field name closest to the given (unknown) one, if any is similar
+  /// enough to be a probable typo.
+  pub fn suggest(&self, name: &str) -> Option<&'static str> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    self
+      .field_names()
+      .map(|field_name| (edit_distance(name, field_name), field_name))
+      .filter(|(distance, _)| *distance <= max_distance)
+      .min_by_key(|(distance, _)| *distance)
+      .map(|(_, field_name)| field_name)
+  }
+}
+
+/// Levenshtein distance between two strings
+pub fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+  for (i, a_char) in a.chars().enumerate() {
+    let mut current_row = vec![i + 1; b.len() + 1];
+    for (j, b_char) in b.iter().enumerate() {
+      let substitution_cost = if a_char == *b_char { 0 } else { 1 };
+      current_row[j + 1] = (previous_row[j] + substitution_cost)
+        .min(previous_row[j + 1] + 1)
+        .min(current_row[j] + 1);
+    }
+    previous_row = current_row;
+  }
+
+  previous_row[b.len()]
+}
+
+pub const ITEM: Block = Block {
+  name: "item",
+  description: "Entry of a world document. Exactly one of the keys needs to be given.",
+  fields: &[
+    Field::optional("light", ValueType::Block("light"), None, "A light source"),
+    Field::optional(
+      "body",
+      ValueType::Block("body"),
+      None,
+      "A body placed in the world",
+    ),
+    Field::optional(
+      "camera",
+      ValueType::Block("camera"),
+      None,
+      "A named camera perspective",
+    ),
+    Field::optional(
+      "material",
+      ValueType::Block("material"),
+      None,
+      "A named material, which bodies reference by its name",
+    ),
+    Field::optional(
+      "settings",
+      ValueType::Block("settings"),
+      None,
+      "Settings affecting the rendering of the whole world",
+    ),
+    Field::optional(
+      "animate",
+      ValueType::Block("animate"),
+      None,
+      "A property of named bodies swept over the frames of an animation",
+    ),
+  ],
+};
+
+pub const SETTINGS: Block = Block {
+  name: "settings",
+  description: "Settings affecting the rendering of the whole world",
+  fields: &[
+    Field::optional(
+      "contact_shadows",
+      ValueType::Block("contact_shadows"),
+      None,
+      "Cheap ambient occlusion using a few short rays",
+    ),
+    Field::optional(
+      "caustics",
+      ValueType::Block("caustics"),
+      None,
+      "Light focused by glass and mirrors onto other surfaces, none if not given",
+    ),
+    Field::optional(
+      "background",
+      ValueType::Triple,
+      Some("[0, 0, 0]"),
+      "Color of rays leaving the world without hitting anything",
+    ),
+    Field::optional(
+      "unlit_color",
+      ValueType::Triple,
+      Some("[0, 0, 0]"),
+      "Color of every body hit while the world has no lights",
+    ),
+    Field::optional(
+      "orientation",
+      ValueType::Block("orientation"),
+      None,
+      "Axis convention of all meshes not declaring their own",
+    ),
+    Field::optional(
+      "secondary_ray_budget",
+      ValueType::Integer,
+      None,
+      "Reflected and refracted rays all samples of a pixel may spawn together, further ones see the background",
+    ),
+    Field::optional(
+      "contribution_cutoff",
+      ValueType::Float,
+      Some("0.01"),
+      "Reflections and refractions contributing less to a camera ray are not traced",
+    ),
+  ],
+};
+
+pub const CONTACT_SHADOWS: Block = Block {
+  name: "contact_shadows",
+  description: "Darkens the ambient light close to other bodies",
+  fields: &[
+    Field::optional(
+      "distance",
+      ValueType::Float,
+      Some("0.5"),
+      "Maximum distance of occluding bodies",
+    ),
+    Field::optional(
+      "samples",
+      ValueType::Integer,
+      Some("8"),
+      "Number of rays per shaded point",
+    ),
+    Field::optional(
+      "strength",
+      ValueType::Float,
+      Some("1"),
+      "Fraction of the ambient light removed when fully occluded",
+    ),
+  ],
+};
+
+pub const CAUSTICS: Block = Block {
+  name: "caustics",
+  description: "Photons traced through glass and mirrors before rendering",
+  fields: &[
+    Field::optional(
+      "photons",
+      ValueType::Integer,
+      Some("50000"),
+      "Photons shot from each light, more give smoother caustics",
+    ),
+    Field::optional(
+      "radius",
+      ValueType::Float,
+      Some("0.1"),
+      "Distance photons are gathered from around a shaded point",
+    ),
+    Field::optional(
+      "seed",
+      ValueType::Integer,
+      Some("0"),
+      "Seed of the random directions photons are shot in",
+    ),
+  ],
+};
+
+pub const ANIMATE: Block = Block {
+  name: "animate",
+  description: "Linear change of a property of named bodies from the first to the last frame, rendered with 'render --frames'",
+  fields: &[
+    Field::required("body", ValueType::String, "Name of the bodies to animate"),
+    Field::required(
+      "property",
+      ValueType::OneOf(&[
+        "transform.translate.x",
+        "transform.translate.y",
+        "transform.translate.z",
+        "transform.rotate.x",
+        "transform.rotate.y",
+        "transform.rotate.z",
+        "transform.scale",
+        "transform.scale.x",
+        "transform.scale.y",
+        "transform.scale.z",
+        "kinematics",
+      ]),
+      "Movement along an axis of the world, or rotation (in degrees) and scaling around the origin of the bodies, on top of their transforms. Kinematics throw the bodies, bouncing off the ground at y = 0",
+    ),
+    Field::required(
+      "from",
+      ValueType::Float,
+      "Value at the first frame, the time in seconds for kinematics",
+    ),
+    Field::required(
+      "to",
+      ValueType::Float,
+      "Value at the last frame, the time in seconds for kinematics",
+    ),
+    Field::optional(
+      "velocity",
+      ValueType::Triple,
+      Some("[0, 0, 0]"),
+      "Kinematics only: Velocity at a time of zero seconds",
+    ),
+    Field::optional(
+      "gravity",
+      ValueType::Triple,
+      Some("[0, -9.81, 0]"),
+      "Kinematics only: Acceleration pulling the bodies",
+    ),
+    Field::optional(
+      "restitution",
+      ValueType::Float,
+      Some("0.7"),
+      "Kinematics only: Part of the speed kept by each bounce",
+    ),
+  ],
+};
+
+pub const SCATTER: Block = Block {
+  name: "scatter",
+  description: "Copies of a body standing on random points of a region, scaled and turned at random, but never overlapping",
+  fields: &[
+    Field::required("count", ValueType::Integer, "Number of copies"),
+    Field::required("min", ValueType::Triple, "Lowest corner of the region"),
+    Field::required("max", ValueType::Triple, "Highest corner of the region"),
+    Field::optional(
+      "min_scale",
+      ValueType::Float,
+      Some("1.0"),
+      "Smallest scaling of a copy",
+    ),
+    Field::optional(
+      "max_scale",
+      ValueType::Float,
+      Some("1.0"),
+      "Largest scaling of a copy",
+    ),
+    Field::optional(
+      "rotate",
+      ValueType::Boolean,
+      Some("true"),
+      "Turn the copies around the y axis",
+    ),
+    Field::optional(
+      "seed",
+      ValueType::Integer,
+      Some("0"),
+      "Seed of the pseudo random placement",
+    ),
+    Field::optional(
+      "attempts",
+      ValueType::Integer,
+      Some("100"),
+      "Tries to place each copy before giving up on the rest",
+    ),
+  ],
+};
+
+pub const MIRROR: Block = Block {
+  name: "mirror",
+  description: "Plane a body is mirrored across, so symmetric models only need one of their halves",
+  fields: &[
+    Field::optional(
+      "point",
+      ValueType::Triple,
+      Some("[0, 0, 0]"),
+      "Point the plane runs through",
+    ),
+    Field::optional(
+      "normal",
+      ValueType::Triple,
+      Some("[1, 0, 0]"),
+      "Direction perpendicular to the plane, pointing towards the side the body is on",
+    ),
+  ],
+};
+
+pub const ORIENTATION: Block = Block {
+  name: "orientation",
+  description: "Axis convention of a mesh, converted to the left-handed, y up one of the renderer",
+  fields: &[
+    Field::optional(
+      "up",
+      ValueType::OneOf(&["x", "y", "z"]),
+      Some("\"y\""),
+      "Axis pointing up",
+    ),
+    Field::optional(
+      "handedness",
+      ValueType::OneOf(&["left", "right"]),
+      Some("\"left\""),
+      "Handedness of the coordinate system",
+    ),
+  ],
+};
+
+pub const ATTENUATION: Block = Block {
+  name: "attenuation",
+  description: "Divides the intensity by constant + linear * d + quadratic * d² at distance d",
+  fields: &[
+    Field::optional("constant", ValueType::Float, Some("1"), "Constant part"),
+    Field::optional(
+      "linear",
+      ValueType::Float,
+      Some("0"),
+      "Part growing with the distance",
+    ),
+    Field::optional(
+      "quadratic",
+      ValueType::Float,
+      Some("0"),
+      "Part growing with the squared distance, physically correct on its own",
+    ),
+  ],
+};
+
+pub const LIGHT: Block = Block {
+  name: "light",
+  description: "A light source illuminating the world",
+  fields: &[
+    Field::required("type", ValueType::OneOf(&["point_light"]), "Kind of light"),
+    Field::required("at", ValueType::Triple, "Position of the light"),
+    Field::required(
+      "intensity",
+      ValueType::Triple,
+      "Color and intensity of the light",
+    ),
+    Field::optional(
+      "ev",
+      ValueType::Float,
+      Some("0"),
+      "Stops the intensity is scaled by, each stop doubles the brightness",
+    ),
+    Field::optional(
+      "attenuation",
+      ValueType::Block("attenuation"),
+      None,
+      "Falloff of the intensity with the distance, none if not given",
+    ),
+    Field::optional(
+      "layers",
+      ValueType::StringOrStringArray,
+      None,
+      "Layers the light belongs to, without any it lights every layer",
+    ),
+  ],
+};
+
+pub const BODY: Block = Block {
+  name: "body",
+  description: "A body placed in the world",
+  fields: &[
+    Field::required(
+      "type",
+      ValueType::OneOf(&[
+        "sphere",
+        "plane",
+        "quad",
+        "cylinder",
+        "cone",
+        "triangle",
+        "smooth_triangle",
+        "mesh",
+        "sdf",
+        "repeated",
+        "backdrop",
+        "tube",
+        "text",
+        "svg",
+      ]),
+      "Kind of body",
+    ),
+    Field::optional(
+      "name",
+      ValueType::String,
+      None,
+      "Name to refer to the body by, e.g. to focus a camera on it",
+    ),
+    Field::optional(
+      "layers",
+      ValueType::StringOrStringArray,
+      None,
+      "Layers the body belongs to, 'default' if none are given",
+    ),
+    Field::optional(
+      "width",
+      ValueType::Float,
+      Some("2.0"),
+      "Quads and backdrops only: Extent along the x axis",
+    ),
+    Field::optional(
+      "height",
+      ValueType::Float,
+      Some("2.0"),
+      "Quads and backdrops only: Extent along the z axis of quads, height of the backdrop wall",
+    ),
+    Field::optional(
+      "depth",
+      ValueType::Float,
+      Some("2.0"),
+      "Backdrops, text and SVG only: Extent of the floor in front of the curve, or thickness of the letters and shapes (0.2 by default)",
+    )
+    .with_kind_defaults(&[("text", "0.2"), ("svg", "0.2")]),
+    Field::optional(
+      "bevel",
+      ValueType::Float,
+      Some("0.0"),
+      "SVG only: Width of the chamfer around the front and back of the shapes",
+    ),
+    Field::optional(
+      "radius",
+      ValueType::Float,
+      Some("0.5"),
+      "Backdrops and tubes only: Radius of the curve between floor and wall, or of the tube (0.1 by default)",
+    )
+    .with_kind_defaults(&[("tube", "0.1")]),
+    Field::optional(
+      "segments",
+      ValueType::Integer,
+      Some("16"),
+      "Backdrops only: Number of strips approximating the curve",
+    ),
+    Field::optional(
+      "floor_color",
+      ValueType::Triple,
+      Some("[1, 1, 1]"),
+      "Backdrops only: Color of the gradient at the floor, unless a material is given",
+    ),
+    Field::optional(
+      "wall_color",
+      ValueType::Triple,
+      Some("[0.6, 0.6, 0.6]"),
+      "Backdrops only: Color of the gradient at the top of the wall, unless a material is given",
+    ),
+    Field::optional(
+      "minimum",
+      ValueType::Float,
+      None,
+      "Cylinder and cone only: Lower end along the y axis (unbounded if omitted)",
+    ),
+    Field::optional(
+      "maximum",
+      ValueType::Float,
+      None,
+      "Cylinder and cone only: Upper end along the y axis (unbounded if omitted)",
+    ),
+    Field::optional(
+      "closed",
+      ValueType::Boolean,
+      Some("false"),
+      "Cylinder and cone only: Whether the ends are capped",
+    ),
+    Field::optional(
+      "points",
+      ValueType::TripleList,
+      None,
+      "Triangles and tubes only: The three corners, or the points the tube passes",
+    ),
+    Field::optional(
+      "curve",
+      ValueType::OneOf(&["polyline", "bezier"]),
+      Some("\"polyline\""),
+      "Tubes only: Straight segments between the points, or cubic Bezier curves with two control points between each pair of points",
+    ),
+    Field::optional(
+      "tolerance",
+      ValueType::Float,
+      Some("0.01"),
+      "Tubes only: Largest deviation of the straight segments from Bezier curves",
+    ),
+    Field::optional(
+      "text",
+      ValueType::String,
+      None,
+      "Text only: The letters to extrude, one unit high and starting at the origin, with newlines starting further lines below",
+    ),
+    Field::optional(
+      "normals",
+      ValueType::TripleArray,
+      None,
+      "Smooth triangles only: Normals at the three corners",
+    ),
+    Field::optional(
+      "shape",
+      ValueType::Block("sdf"),
+      None,
+      "SDF only: Signed distance function describing the body",
+    ),
+    Field::optional(
+      "blend",
+      ValueType::Block("sdf_blend"),
+      None,
+      "SDF only: Second shape smoothly merged into the first one",
+    ),
+    Field::optional(
+      "body",
+      ValueType::Block("body"),
+      None,
+      "Repeated only: Body copied into every cell of the lattice, which needs to fit into the cell around the origin",
+    ),
+    Field::optional(
+      "spacing",
+      ValueType::Triple,
+      None,
+      "Repeated only: Distance between the copies along each axis, with 0 not repeating along the axis",
+    ),
+    Field::optional(
+      "count",
+      ValueType::Triple,
+      None,
+      "Repeated only: Number of copies along each axis starting at the origin (endless if omitted)",
+    ),
+    Field::optional(
+      "file",
+      ValueType::String,
+      None,
+      "Meshes and SVG only: Wavefront OBJ file, or SVG file whose paths are extruded one unit high, relative to the world file",
+    ),
+    Field::optional(
+      "orientation",
+      ValueType::Block("orientation"),
+      None,
+      "Meshes only: Axis convention of the file, overriding the one of the settings",
+    ),
+    Field::optional(
+      "material",
+      ValueType::BlockOrName("material"),
+      None,
+      "Surface material, or the name of a registered one",
+    ),
+    Field::optional(
+      "transforms",
+      ValueType::BlockArray("transform"),
+      Some("[]"),
+      "Transformations applied to the body in order",
+    ),
+    Field::optional(
+      "motion",
+      ValueType::BlockArray("transform"),
+      None,
+      "Transformations at the end of the shutter interval, which blur the moving body",
+    ),
+    Field::optional(
+      "mirror",
+      ValueType::Block("mirror"),
+      None,
+      "Adds the mirror image of the body across a plane, e.g. the other half of a symmetric model",
+    ),
+    Field::optional(
+      "scatter",
+      ValueType::Block("scatter"),
+      None,
+      "Replaces the body by copies randomly placed without overlaps",
+    ),
+    Field::optional(
+      "cast_shadow",
+      ValueType::Boolean,
+      Some("true"),
+      "Whether the body throws shadows onto other bodies",
+    ),
+    Field::optional(
+      "receive_shadow",
+      ValueType::Boolean,
+      Some("true"),
+      "Whether shadows of other bodies fall onto the body",
+    ),
+  ],
+};
+
+pub const SDF: Block = Block {
+  name: "sdf",
+  description: "Built-in signed distance function, centered at the origin",
+  fields: &[
+    Field::required(
+      "type",
+      ValueType::OneOf(&["sphere", "box", "torus", "mandelbulb"]),
+      "Kind of shape",
+    ),
+    Field::optional("radius", ValueType::Float, Some("1"), "Sphere only: Radius"),
+    Field::optional(
+      "size",
+      ValueType::Triple,
+      Some("[1, 1, 1]"),
+      "Box only: Half extents along each axis",
+    ),
+    Field::optional(
+      "major_radius",
+      ValueType::Float,
+      Some("1"),
+      "Torus only: Radius of the ring",
+    ),
+    Field::optional(
+      "minor_radius",
+      ValueType::Float,
+      Some("0.25"),
+      "Torus only: Radius of the tube",
+    ),
+    Field::optional(
+      "power",
+      ValueType::Float,
+      Some("8"),
+      "Mandelbulb only: Power of the iteration",
+    ),
+    Field::optional(
+      "iterations",
+      ValueType::Integer,
+      Some("8"),
+      "Mandelbulb only: Number of iterations",
+    ),
+  ],
+};
+
+pub const SDF_BLEND: Block = Block {
+  name: "sdf_blend",
+  description: "Second signed distance function smoothly merged into a body",
+  fields: &[
+    Field::required("shape", ValueType::Block("sdf"), "Shape to merge"),
+    Field::required(
+      "offset",
+      ValueType::Triple,
+      "Position of the shape relative to the body",
+    ),
+    Field::optional(
+      "smoothness",
+      ValueType::Float,
+      Some("0.5"),
+      "Size of the smooth transition, 0 for a hard union",
+    ),
+  ],
+};
+
+pub const MATERIAL: Block = Block {
+  name: "material",
+  description: "Surface material of a body",
+  fields: &[
+    Field::required(
+      "type",
+      ValueType::OneOf(&["phong", "layered", "lambert"]),
+      "Kind of material, lambert only uses color, pattern, texture, ambient and diffuse",
+    ),
+    Field::optional(
+      "name",
+      ValueType::String,
+      None,
+      "Registered materials only: Name bodies reference the material by",
+    ),
+    Field::optional(
+      "color",
+      ValueType::Triple,
+      Some("[1, 1, 1]"),
+      "Surface color",
+    ),
+    Field::optional(
+      "pattern",
+      ValueType::Block("pattern"),
+      None,
+      "Pattern replacing the color",
+    ),
+    Field::optional(
+      "texture",
+      ValueType::String,
+      None,
+      "PNG image wrapped around the body, relative to the world file",
+    ),
+    Field::optional(
+      "bump",
+      ValueType::Block("bump"),
+      None,
+      "Bumps perturbing the shading normal",
+    ),
+    Field::optional(
+      "cutout",
+      ValueType::Block("cutout"),
+      None,
+      "Holes in the surface, which rays and shadows pass through",
+    ),
+    Field::optional(
+      "shadow_bias",
+      ValueType::Float,
+      Some("0.00001"),
+      "Offset of shadow and secondary rays from the surface, larger for huge bodies and smaller for tiny ones",
+    ),
+    Field::optional(
+      "ambient",
+      ValueType::Float,
+      Some("0.1"),
+      "Ambient reflection",
+    ),
+    Field::optional(
+      "diffuse",
+      ValueType::Float,
+      Some("0.9"),
+      "Diffuse reflection",
+    ),
+    Field::optional(
+      "specular",
+      ValueType::Float,
+      Some("0.9"),
+      "Specular reflection",
+    ),
+    Field::optional(
+      "shininess",
+      ValueType::Float,
+      Some("200"),
+      "Size of specular highlights",
+    ),
+    Field::optional(
+      "reflectiveness",
+      ValueType::Float,
+      Some("0"),
+      "Mirror reflection",
+    ),
+    Field::optional(
+      "transparency",
+      ValueType::Float,
+      Some("0"),
+      "Fraction of the light passing through the body",
+    ),
+    Field::optional(
+      "refractive_index",
+      ValueType::Float,
+      Some("1"),
+      "How strongly light passing the surface is bent, e.g. 1.33 for water or 1.5 for glass",
+    ),
+    Field::optional(
+      "coat",
+      ValueType::Block("coat"),
+      None,
+      "Clear coat on top of a phong material",
+    ),
+    Field::optional(
+      "base",
+      ValueType::Block("material"),
+      None,
+      "Layered only: Material below the layer",
+    ),
+    Field::optional(
+      "layer",
+      ValueType::Block("material"),
+      None,
+      "Layered only: Material on top of the base",
+    ),
+    Field::optional(
+      "factor",
+      ValueType::Float,
+      Some("0.5"),
+      "Layered only: Coverage of the layer between 0 and 1",
+    ),
+    Field::optional(
+      "mask",
+      ValueType::Block("pattern"),
+      None,
+      "Layered only: Pattern, whose luminance is the coverage of the layer",
+    ),
+  ],
+};
+
+pub const COAT: Block = Block {
+  name: "coat",
+  description: "Clear coat reflecting more light at grazing angles",
+  fields: &[
+    Field::optional(
+      "reflectiveness",
+      ValueType::Float,
+      Some("0.04"),
+      "Reflectance when looking straight at the surface",
+    ),
+    Field::optional(
+      "roughness",
+      ValueType::Float,
+      Some("0.05"),
+      "Spread of the highlight between 0 (sharp) and 1 (blurry)",
+    ),
+    Field::optional(
+      "tint",
+      ValueType::Triple,
+      Some("[1, 1, 1]"),
+      "Color of the coat filtering the material below",
+    ),
+  ],
+};
+
+pub const BUMP: Block = Block {
+  name: "bump",
+  description: "Surface detail from perturbed shading normals",
+  fields: &[
+    Field::required(
+      "type",
+      ValueType::OneOf(&["noise", "normal_map"]),
+      "Procedural bumps or a tangent space normal map",
+    ),
+    Field::optional(
+      "scale",
+      ValueType::Float,
+      Some("1"),
+      "Noise only: Size of the bumps",
+    ),
+    Field::optional(
+      "strength",
+      ValueType::Float,
+      None,
+      "Tilt of the normal, 0.3 for noise and 1 for normal maps by default",
+    )
+    .with_kind_defaults(&[("noise", "0.3"), ("normal_map", "1")]),
+    Field::optional(
+      "file",
+      ValueType::String,
+      None,
+      "Normal map only: PNG image, relative to the world file",
+    ),
+  ],
+};
+
+pub const CUTOUT: Block = Block {
+  name: "cutout",
+  description: "Opacity mask cutting holes into a surface, like a leaf painted onto a flat card",
+  fields: &[
+    Field::required(
+      "mask",
+      ValueType::Block("pattern"),
+      "Pattern, whose brightness is the opacity of the surface",
+    ),
+    Field::optional(
+      "threshold",
+      ValueType::Float,
+      Some("0.5"),
+      "Opacity below which the surface is cut away",
+    ),
+  ],
+};
+
+pub const PATTERN: Block = Block {
+  name: "pattern",
+  description: "Pattern of two alternating colors",
+  fields: &[
+    Field::required(
+      "type",
+      ValueType::OneOf(&[
+        "striped",
+        "gradient",
+        "ring",
+        "checkerboard",
+        "noise",
+        "texture_map",
+        "cube_map",
+        "test",
+      ]),
+      "Kind of pattern, 'test' colors each point with its coordinates in pattern space",
+    ),
+    Field::optional(
+      "colorA",
+      ValueType::TripleOrBlock("pattern"),
+      None,
+      "First color, or a nested pattern. Required unless a texture map has a file, unused by the test pattern",
+    ),
+    Field::optional(
+      "colorB",
+      ValueType::TripleOrBlock("pattern"),
+      None,
+      "Second color, or a nested pattern. Required unless a texture map has a file, unused by the test pattern",
+    ),
+    Field::optional(
+      "3d",
+      ValueType::Boolean,
+      Some("true"),
+      "Checkerboard only: Alternate along the z axis as well",
+    ),
+    Field::optional(
+      "octaves",
+      ValueType::Integer,
+      Some("4"),
+      "Noise only: Layers of noise, each twice as fine as the previous one",
+    ),
+    Field::optional(
+      "persistence",
+      ValueType::Float,
+      Some("0.5"),
+      "Noise only: Strength of each layer relative to the previous one",
+    ),
+    Field::optional(
+      "seed",
+      ValueType::Integer,
+      Some("0"),
+      "Noise only: Seed of the pseudo random variation",
+    ),
+    Field::optional(
+      "direction",
+      ValueType::Triple,
+      Some("[1, 0, 0]"),
+      "Striped only: Direction the colors alternate along",
+    ),
+    Field::optional(
+      "easing",
+      ValueType::OneOf(&["linear", "smoothstep", "mirrored", "clamped"]),
+      Some("\"linear\""),
+      "Gradient only: Ramp between the colors, repeated every unit except when clamped",
+    ),
+    Field::optional(
+      "projection",
+      ValueType::OneOf(&["spherical", "planar", "cylindrical", "cubic"]),
+      None,
+      "Texture map only: Projection onto the texture, defaults to the one of each body",
+    ),
+    Field::optional(
+      "file",
+      ValueType::String,
+      None,
+      "Texture map only: PNG image to map instead of checkers of colorA and colorB",
+    ),
+    Field::optional(
+      "columns",
+      ValueType::Integer,
+      Some("2"),
+      "Texture map only: Squares of the checkers around the texture",
+    ),
+    Field::optional(
+      "rows",
+      ValueType::Integer,
+      Some("2"),
+      "Texture map only: Squares of the checkers up the texture",
+    ),
+    Field::optional(
+      "left",
+      ValueType::Block("uv_pattern"),
+      None,
+      "Cube map only: Face towards the negative x axis",
+    ),
+    Field::optional(
+      "front",
+      ValueType::Block("uv_pattern"),
+      None,
+      "Cube map only: Face towards the negative z axis",
+    ),
+    Field::optional(
+      "right",
+      ValueType::Block("uv_pattern"),
+      None,
+      "Cube map only: Face towards the positive x axis",
+    ),
+    Field::optional(
+      "back",
+      ValueType::Block("uv_pattern"),
+      None,
+      "Cube map only: Face towards the positive z axis",
+    ),
+    Field::optional(
+      "up",
+      ValueType::Block("uv_pattern"),
+      None,
+      "Cube map only: Face towards the positive y axis",
+    ),
+    Field::optional(
+      "down",
+      ValueType::Block("uv_pattern"),
+      None,
+      "Cube map only: Face towards the negative y axis",
+    ),
+    Field::optional(
+      "transforms",
+      ValueType::BlockArray("transform"),
+      Some("[]"),
+      "Transformations applied to the pattern in order",
+    ),
+  ],
+};
+
+pub const UV_PATTERN: Block = Block {
+  name: "uv_pattern",
+  description: "Flat pattern in UV coordinates, e.g. a face of a cube map",
+  fields: &[
+    Field::required(
+      "type",
+      ValueType::OneOf(&["image", "checkers", "align_check"]),
+      "Kind of UV pattern",
+    ),
+    Field::optional(
+      "file",
+      ValueType::String,
+      None,
+      "Image only: PNG image, relative to the world file",
+    ),
+    Field::optional(
+      "colorA",
+      ValueType::Triple,
+      Some("[0, 0, 0]"),
+      "Checkers only: First color",
+    ),
+    Field::optional(
+      "colorB",
+      ValueType::Triple,
+      Some("[1, 1, 1]"),
+      "Checkers only: Second color",
+    ),
+    Field::optional(
+      "columns",
+      ValueType::Integer,
+      Some("2"),
+      "Checkers only: Squares along u",
+    ),
+    Field::optional(
+      "rows",
+      ValueType::Integer,
+      Some("2"),
+      "Checkers only: Squares along v",
+    ),
+    Field::optional(
+      "main",
+      ValueType::Triple,
+      Some("[1, 1, 1]"),
+      "Align check only: Color besides the corners",
+    ),
+    Field::optional(
+      "upper_left",
+      ValueType::Triple,
+      Some("[1, 0, 0]"),
+      "Align check only: Color of the upper left corner",
+    ),
+    Field::optional(
+      "upper_right",
+      ValueType::Triple,
+      Some("[1, 1, 0]"),
+      "Align check only: Color of the upper right corner",
+    ),
+    Field::optional(
+      "bottom_left",
+      ValueType::Triple,
+      Some("[0, 1, 0]"),
+      "Align check only: Color of the bottom left corner",
+    ),
+    Field::optional(
+      "bottom_right",
+      ValueType::Triple,
+      Some("[0, 1, 1]"),
+      "Align check only: Color of the bottom right corner",
+    ),
+  ],
+};
+
+pub const TRANSFORM: Block = Block {
+  name: "transform",
+  description: "A single transformation",
+  fields: &[
+    Field::required(
+      "type",
+      ValueType::OneOf(&["translate", "scale", "rotate_x", "rotate_y", "rotate_z"]),
+      "Kind of transformation",
+    ),
+    Field::optional(
+      "to",
+      ValueType::Triple,
+      None,
+      "Translate and scale only: Target vector",
+    ),
+    Field::optional(
+      "radians",
+      ValueType::Float,
+      None,
+      "Rotations only: Angle in radians",
+    ),
+    Field::optional(
+      "degrees",
+      ValueType::Float,
+      None,
+      "Rotations only: Angle in degrees",
+    ),
+  ],
+};
+
+pub const CAMERA: Block = Block {
+  name: "camera",
+  description: "A named camera perspective, which is rendered to an image",
+  fields: &[
+    Field::required(
+      "name",
+      ValueType::String,
+      "Name of the camera and its output file",
+    ),
+    Field::optional(
+      "width",
+      ValueType::Integer,
+      None,
+      "Horizontal size in pixels",
+    ),
+    Field::optional(
+      "height",
+      ValueType::Integer,
+      None,
+      "Vertical size in pixels",
+    ),
+    Field::optional(
+      "aspect_ratio",
+      ValueType::AspectRatio,
+      None,
+      "Ratio of width to height, used to derive a missing dimension",
+    ),
+    Field::optional(
+      "quality",
+      ValueType::OneOf(&[
+        "480p", "720p", "1080p", "1440p", "2160p", "4k", "4320p", "8k",
+      ]),
+      None,
+      "Resolution preset defining the height (16:9 unless an aspect ratio is given)",
+    ),
+    Field::required(
+      "field_of_view",
+      ValueType::Angle,
+      "Field of view in radians, or given as angle",
+    ),
+    Field::required("from", ValueType::Triple, "Position of the camera"),
+    Field::required("to", ValueType::Triple, "Point the camera looks at"),
+    Field::required("up", ValueType::Triple, "Up vector of the camera"),
+    Field::optional(
+      "samples",
+      ValueType::Integer,
+      Some("1"),
+      "Samples per pixel axis",
+    ),
+    Field::optional(
+      "jitter",
+      ValueType::Block("jitter"),
+      None,
+      "Placement of samples",
+    ),
+    Field::optional(
+      "adaptive_sampling",
+      ValueType::Block("adaptive_sampling"),
+      None,
+      "Choose the samples per tile based on the measured noise",
+    ),
+    Field::optional(
+      "exposure",
+      ValueType::Float,
+      Some("0"),
+      "Exposure compensation in stops",
+    ),
+    Field::optional(
+      "frustum_culling",
+      ValueType::Boolean,
+      Some("false"),
+      "Camera rays skip bodies outside of the view, which still cast shadows and show up in reflections",
+    ),
+    Field::optional(
+      "memory_ceiling",
+      ValueType::Integer,
+      None,
+      "Megabytes the pixels and samples of the tiles rendered at the same time may use, larger tiles are split. The world and the finished image are not counted",
+    ),
+    Field::optional(
+      "crop",
+      ValueType::Block("crop"),
+      None,
+      "Normalized part of the film to render, scaled evenly to fit the image",
+    ),
+    Field::optional(
+      "post_processing",
+      ValueType::BlockArray("effect"),
+      None,
+      "Effects applied to the rendered image in the given order",
+    ),
+    Field::optional(
+      "aperture",
+      ValueType::Float,
+      Some("0"),
+      "Radius of the lens for depth of field, 0 keeps everything sharp",
+    ),
+    Field::optional(
+      "focal_distance",
+      ValueType::Float,
+      Some("1"),
+      "Distance from the camera at which the image is sharp",
+    ),
+    Field::optional(
+      "focus_on",
+      ValueType::String,
+      None,
+      "Name of the body to focus on, replacing the focal distance",
+    ),
+  ],
+};
+
+pub const EFFECT: Block = Block {
+  name: "effect",
+  description: "A single post processing effect",
+  fields: &[
+    Field::required(
+      "type",
+      ValueType::OneOf(&[
+        "auto_exposure",
+        "bloom",
+        "glare",
+        "lens_distortion",
+        "lut",
+        "vignette",
+      ]),
+      "Kind of effect",
+    ),
+    Field::optional(
+      "key",
+      ValueType::Float,
+      Some("0.18"),
+      "Auto exposure only: Log-average luminance the image is scaled to",
+    ),
+    Field::optional(
+      "compensation",
+      ValueType::Float,
+      Some("0"),
+      "Auto exposure only: Stops added on top of the automatic exposure",
+    ),
+    Field::optional(
+      "threshold",
+      ValueType::Float,
+      Some("1"),
+      "Bloom and glare only: Luminance above which pixels glow",
+    ),
+    Field::optional(
+      "sigma",
+      ValueType::Float,
+      Some("4"),
+      "Bloom only: Standard deviation of the blur in pixels",
+    ),
+    Field::optional(
+      "intensity",
+      ValueType::Float,
+      None,
+      "Bloom and glare only: Strength of the added light, 0.5 for bloom and 0.25 for glare by default",
+    )
+    .with_kind_defaults(&[("bloom", "0.5"), ("glare", "0.25")]),
+    Field::optional(
+      "streaks",
+      ValueType::Integer,
+      Some("4"),
+      "Glare only: Number of streaks",
+    ),
+    Field::optional(
+      "length",
+      ValueType::Integer,
+      Some("16"),
+      "Glare only: Length of the streaks in pixels",
+    ),
+    Field::optional(
+      "k1",
+      ValueType::Float,
+      Some("0"),
+      "Lens distortion only: Quadratic coefficient",
+    ),
+    Field::optional(
+      "k2",
+      ValueType::Float,
+      Some("0"),
+      "Lens distortion only: Quartic coefficient",
+    ),
+    Field::optional(
+      "inverse",
+      ValueType::Boolean,
+      Some("false"),
+      "Lens distortion only: Remove the distortion instead of adding it",
+    ),
+    Field::optional(
+      "strength",
+      ValueType::Float,
+      Some("0.5"),
+      "Vignette only: Darkening at the corners",
+    ),
+    Field::optional(
+      "radius",
+      ValueType::Float,
+      Some("0.5"),
+      "Vignette only: Normalized distance from the center where darkening starts",
+    ),
+    Field::optional(
+      "file",
+      ValueType::String,
+      None,
+      "LUT only: 3D lookup table in the .cube format, relative to the world file",
+    ),
+  ],
+};
+
+pub const ADAPTIVE_SAMPLING: Block = Block {
+  name: "adaptive_sampling",
+  description: "Renders a pilot pass to choose the samples per tile",
+  fields: &[
+    Field::optional(
+      "tile_size",
+      ValueType::Integer,
+      Some("16"),
+      "Size of the tiles in pixels",
+    ),
+    Field::optional(
+      "pilot_samples",
+      ValueType::Integer,
+      Some("2"),
+      "Samples per pixel axis of the pilot pass",
+    ),
+    Field::optional(
+      "max_samples",
+      ValueType::Integer,
+      Some("8"),
+      "Maximum samples per pixel axis",
+    ),
+    Field::optional(
+      "target_variance",
+      ValueType::Float,
+      Some("0.0005"),
+      "Acceptable variance of the pixel luminance",
+    ),
+    Field::optional(
+      "time_budget",
+      ValueType::Float,
+      None,
+      "Maximum rendering time in seconds",
+    ),
+  ],
+};
+
+pub const CROP: Block = Block {
+  name: "crop",
+  description: "Normalized sub rectangle of the film with (0, 0) being the top left corner",
+  fields: &[
+    Field::required("left", ValueType::Float, "Left edge in the range [0, 1]"),
+    Field::required("top", ValueType::Float, "Top edge in the range [0, 1]"),
+    Field::required("right", ValueType::Float, "Right edge in the range [0, 1]"),
+    Field::required(
+      "bottom",
+      ValueType::Float,
+      "Bottom edge in the range [0, 1]",
+    ),
+  ],
+};
+
+pub const JITTER: Block = Block {
+  name: "jitter",
+  description: "Reproducible placement of samples. Either 'seed' or 'sequence' needs to be given.",
+  fields: &[
+    Field::optional(
+      "seed",
+      ValueType::Integer,
+      None,
+      "Seed for pseudo random offsets",
+    ),
+    Field::optional(
+      "sequence",
+      ValueType::FloatArray,
+      None,
+      "Explicit offsets in the range [0, 1), which are cycled through",
+    ),
+  ],
+};
+
+pub const ANGLE: Block = Block {
+  name: "angle",
+  description: "An angle. Either 'degrees' or 'radians' needs to be given.",
+  fields: &[
+    Field::optional("degrees", ValueType::Float, None, "Angle in degrees"),
+    Field::optional("radians", ValueType::Float, None, "Angle in radians"),
+  ],
+};
+
+/// All blocks known to the world definition format
+pub const BLOCKS: &[Block] = &[
+  ITEM,
+  SETTINGS,
+  CONTACT_SHADOWS,
+  CAUSTICS,
+  ANIMATE,
+  SCATTER,
+  MIRROR,
+  ORIENTATION,
+  ATTENUATION,
+  LIGHT,
+  BODY,
+  SDF,
+  SDF_BLEND,
+  MATERIAL,
+  COAT,
+  BUMP,
+  CUTOUT,
+  PATTERN,
+  UV_PATTERN,
+  TRANSFORM,
+  CAMERA,
+  ADAPTIVE_SAMPLING,
+  CROP,
+  EFFECT,
+  JITTER,
+  ANGLE,
+];
+
+pub fn block(name: &str) -> Option<&'static Block> {
+  BLOCKS.iter().find(|block| block.name == name)
+}
+
+fn json_string(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len() + 2);
+  escaped.push('"');
+  for character in value.chars() {
+    match character {
+      '"' => escaped.push_str("\\\""),
+      '\\' => escaped.push_str("\\\\"),
+      '\n' => escaped.push_str("\\n"),
+      _ => escaped.push(character),
+    }
+  }
+  escaped.push('"');
+  escaped
+}
+
+fn value_type_to_json_schema(value_type: ValueType) -> String {
+  match value_type {
+    ValueType::String => r#"{"type": "string"}"#.into(),
+    ValueType::Integer => r#"{"type": "integer"}"#.into(),
+    ValueType::Float => r#"{"type": "number"}"#.into(),
+    ValueType::Boolean => r#"{"type": "boolean"}"#.into(),
+    ValueType::OneOf(values) => format!(
+      r#"{{"type": "string", "enum": [{}]}}"#,
+      values.iter().map(|value| json_string(value)).join(", ")
+    ),
+    ValueType::Triple => {
+      r#"{"type": "array", "items": {"type": "number"}, "minItems": 3, "maxItems": 3}"#.into()
+    }
+    ValueType::FloatArray => r#"{"type": "array", "items": {"type": "number"}}"#.into(),
+    ValueType::StringOrStringArray => {
+      r#"{"oneOf": [{"type": "string"}, {"type": "array", "items": {"type": "string"}}]}"#.into()
+    }
+    ValueType::TripleArray => format!(
+      r#"{{"type": "array", "items": {}, "minItems": 3, "maxItems": 3}}"#,
+      value_type_to_json_schema(ValueType::Triple)
+    ),
+    ValueType::TripleList => format!(
+      r#"{{"type": "array", "items": {}}}"#,
+      value_type_to_json_schema(ValueType::Triple)
+    ),
+    ValueType::TripleOrBlock(name) => format!(
+      r##"{{"oneOf": [{}, {{"$ref": "#/definitions/{}"}}]}}"##,
+      value_type_to_json_schema(ValueType::Triple),
+      name
+    ),
+    ValueType::AspectRatio => {
+      r#"{"oneOf": [{"type": "number"}, {"type": "string", "pattern": "^[0-9.]+:[0-9.]+$"}]}"#
+        .into()
+    }
+    ValueType::Angle => {
+      r##"{"oneOf": [{"type": "number"}, {"$ref": "#/definitions/angle"}]}"##.into()
+    }
+    ValueType::Block(name) => format!(r##"{{"$ref": "#/definitions/{}"}}"##, name),
+    ValueType::BlockOrName(name) => format!(
+      r##"{{"oneOf": [{{"type": "string"}}, {{"$ref": "#/definitions/{}"}}]}}"##,
+      name
+    ),
+    ValueType::BlockArray(name) => format!(
+      r##"{{"type": "array", "items": {{"$ref": "#/definitions/{}"}}}}"##,
+      name
+    ),
+  }
+}
+
+fn block_to_json_schema(block: &Block) -> String {
+  let properties = block
+    .fields
+    .iter()
+    .map(|field| {
+      let mut property = value_type_to_json_schema(field.value_type);
+      // Inject description (and default) into the already rendered object
+      property.pop();
+      property.push_str(&format!(
+        ", \"description\": {}",
+        json_string(field.description)
+      ));
+      if let Some(default) = field.default {
+        property.push_str(&format!(", \"default\": {}", default));
+      }
+      property.push('}');
+      format!("{}: {}", json_string(field.name), property)
+    })
+    .join(", ");
+
+  let required = block
+    .fields
+    .iter()
+    .filter(|field| field.required)
+    .map(|field| json_string(field.name))
+    .join(", ");
+
+  format!(
+    r#"{{"type": "object", "description": {}, "properties": {{{}}}, "required": [{}]}}"#,
+    json_string(block.description),
+    properties,
+    required
+  )
+}
+
+/// Machine readable JSON schema of the YAML world definition format.
+///
+/// Editors supporting JSON schema for YAML files can use it to provide
+/// completion and validation of world files.
+pub fn to_json_schema() -> String {
+  let definitions = BLOCKS
+    .iter()
+    .map(|block| {
+      format!(
+        "{}: {}",
+        json_string(block.name),
+        block_to_json_schema(block)
+      )
+    })
+    .join(",\n    ");
+
+  format!(
+    r##"{{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "title": "The Ray Tracer Challenge world definition",
+  "type": "array",
+  "items": {{"$ref": "#/definitions/item", "minProperties": 1, "maxProperties": 1}},
+  "definitions": {{
+    {}
+  }}
+}}
+"##,
+    definitions
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn blocks_can_be_found_by_name() {
+    assert_eq!(block("camera"), Some(&CAMERA));
+    assert_eq!(block("unknown"), None);
+  }
+
+  #[test]
+  fn fields_can_be_found_in_block() {
+    let field = MATERIAL.field("shininess").unwrap();
+
+    assert_eq!(field.value_type, ValueType::Float);
+    assert_eq!(field.default, Some("200"));
+    assert!(!MATERIAL.contains("colour"));
+  }
+
+  #[test]
+  fn edit_distance_of_strings() {
+    assert_eq!(edit_distance("color", "color"), 0);
+    assert_eq!(edit_distance("colour", "color"), 1);
+    assert_eq!(edit_distance("ambiant", "ambient"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+  }
+
+  #[test]
+  fn typos_get_a_suggestion() {
+    assert_eq!(MATERIAL.suggest("colour"), Some("color"));
+    assert_eq!(MATERIAL.suggest("ambiant"), Some("ambient"));
+    assert_eq!(CAMERA.suggest("feild_of_view"), Some("field_of_view"));
+  }
+
+  #[test]
+  fn unrelated_keys_get_no_suggestion() {
+    assert_eq!(MATERIAL.suggest("wavelength"), None);
+  }
+
+  #[test]
+  fn every_referenced_block_exists() {
+    for block in BLOCKS {
+      for field in block.fields {
+        if let ValueType::Block(name)
+        | ValueType::BlockOrName(name)
+        | ValueType::BlockArray(name)
+        | ValueType::TripleOrBlock(name) = field.value_type
+        {
+          assert!(
+            super::block(name).is_some(),
+            "Block {} is not defined",
+            name
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn json_schema_contains_all_blocks() {
+    let schema = to_json_schema();
+
+    for block in BLOCKS {
+      assert!(schema.contains(&format!("\"{}\": {{\"type\": \"object\"", block.name)));
+    }
+    assert!(schema.contains(r#""required": ["name", "field_of_view", "from""#));
+  }
+
+  #[test]
+  fn defaults_are_json_literals() {
+    for block in BLOCKS {
+      for field in block.fields {
+        let kind_defaults = field.kind_defaults.iter().map(|(_, default)| *default);
+        for default in field.default.into_iter().chain(kind_defaults) {
+          assert!(
+            default.parse::<f64>().is_ok_and(|value| value.is_finite())
+              || default == "true"
+              || default == "false"
+              || default.starts_with('[')
+              || default.starts_with('"'),
+            "Default of {}.{} is no JSON literal: {}",
+            block.name,
+            field.name,
+            default
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn kind_defaults_are_given_for_kinds_of_the_block() {
+    for block in BLOCKS {
+      for field in block.fields {
+        for (kind, _) in field.kind_defaults {
+          let kinds = match block.field("type").map(|field| field.value_type) {
+            Some(ValueType::OneOf(kinds)) => kinds,
+            _ => &[],
+          };
+          assert!(
+            kinds.contains(kind),
+            "Default of {}.{} for unknown kind '{}'",
+            block.name,
+            field.name,
+            kind
+          );
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn json_strings_are_escaped() {
+    assert_eq!(
+      json_string(r#"a "quoted" \ text"#),
+      r#""a \"quoted\" \\ text""#
+    );
+  }
+}
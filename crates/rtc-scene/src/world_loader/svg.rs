@@ -0,0 +1,438 @@
+//! Parser for the outlines of SVG paths, to extrude them into 3D.
+//!
+//! Only the `d` attribute of `<path>` elements is read, with all of its
+//! commands. Curves and arcs are flattened into straight segments. Every
+//! other element and attribute (transforms, styles, fill rules, ...) is
+//! ignored.
+
+use anyhow::{anyhow, Result};
+use rtc_core::extrusion::Extrusion;
+use rtc_core::tuple::Tuple;
+use rtc_core::F;
+use std::f64::consts::PI;
+
+/// Straight segments each curve is flattened into
+const CURVE_SEGMENTS: usize = 16;
+
+/// Parses the paths of the given SVG source into an extrusion. Outlines are
+/// flipped to have y pointing up and scaled to be one unit high, with the
+/// lower left corner of their bounds at the origin.
+pub fn parse_svg(source: &str) -> Result<Extrusion> {
+  let mut contours = vec![];
+  for (index, data) in path_data(source).iter().enumerate() {
+    let path = parse_path(data).map_err(|error| anyhow!("{} in path {}", error, index + 1))?;
+    contours.extend(path);
+  }
+
+  let points = contours.iter().flatten();
+  let min_x = points
+    .clone()
+    .map(|point| point.x)
+    .fold(F::INFINITY, F::min);
+  let min_y = points
+    .clone()
+    .map(|point| point.y)
+    .fold(F::INFINITY, F::min);
+  let max_y = points.map(|point| point.y).fold(F::NEG_INFINITY, F::max);
+  let height = max_y - min_y;
+  if height <= 0.0 {
+    return Err(anyhow!(
+      "Expected paths with outlines, but found none of any height"
+    ));
+  }
+
+  let fitted = contours
+    .iter()
+    .map(|contour| {
+      contour
+        .iter()
+        .map(|point| Tuple::point((point.x - min_x) / height, (max_y - point.y) / height, 0.0))
+        .collect()
+    })
+    .collect();
+  Ok(Extrusion::new(fitted))
+}
+
+/// Values of the `d` attributes of all `<path>` elements
+fn path_data(source: &str) -> Vec<String> {
+  let mut data = vec![];
+  let mut rest = source;
+  while let Some(start) = rest.find("<path") {
+    rest = &rest[start + "<path".len()..];
+    if !rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+      continue;
+    }
+    let tag = &rest[..rest.find('>').unwrap_or(rest.len())];
+    if let Some(value) = attribute(tag, "d") {
+      data.push(value.to_string());
+    }
+  }
+  data
+}
+
+/// Value of the attribute in the tag, quoted with single or double quotes
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+  let mut rest = tag;
+  loop {
+    rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+    let equals = rest.find('=')?;
+    let key = rest[..equals].trim();
+    rest = rest[equals + 1..].trim_start();
+    let quote = rest.chars().next()?;
+    if quote != '"' && quote != '\'' {
+      return None;
+    }
+    let end = rest[1..].find(quote)? + 1;
+    if key == name {
+      return Some(&rest[1..end]);
+    }
+    rest = &rest[end + 1..];
+  }
+}
+
+/// Reads the numbers and flags of path data, which may be separated by
+/// whitespace, commas, signs or nothing at all
+struct Lexer<'a> {
+  data: &'a [u8],
+  position: usize,
+}
+
+impl<'a> Lexer<'a> {
+  fn skip_separators(&mut self) {
+    while self.position < self.data.len()
+      && (self.data[self.position].is_ascii_whitespace() || self.data[self.position] == b',')
+    {
+      self.position += 1;
+    }
+  }
+
+  /// The next command letter, if the next token is one
+  fn command(&mut self) -> Option<char> {
+    self.skip_separators();
+    match self.data.get(self.position) {
+      Some(c) if c.is_ascii_alphabetic() => {
+        self.position += 1;
+        Some(*c as char)
+      }
+      _ => None,
+    }
+  }
+
+  fn at_end(&mut self) -> bool {
+    self.skip_separators();
+    self.position >= self.data.len()
+  }
+
+  fn number(&mut self) -> Result<F> {
+    self.skip_separators();
+    let start = self.position;
+    let digits = |lexer: &mut Self| {
+      while lexer.position < lexer.data.len() && lexer.data[lexer.position].is_ascii_digit() {
+        lexer.position += 1;
+      }
+    };
+    if matches!(self.data.get(self.position), Some(b'+') | Some(b'-')) {
+      self.position += 1;
+    }
+    digits(self);
+    if self.data.get(self.position) == Some(&b'.') {
+      self.position += 1;
+      digits(self);
+    }
+    if matches!(self.data.get(self.position), Some(b'e') | Some(b'E')) {
+      self.position += 1;
+      if matches!(self.data.get(self.position), Some(b'+') | Some(b'-')) {
+        self.position += 1;
+      }
+      digits(self);
+    }
+
+    let token = String::from_utf8_lossy(&self.data[start..self.position]);
+    token.parse::<F>().map_err(|_| {
+      let found = String::from_utf8_lossy(&self.data[start..]);
+      anyhow!(
+        "Expected a number, but found '{}'",
+        found.chars().take(10).collect::<String>()
+      )
+    })
+  }
+
+  /// Arc flags are single digits, which need no separator
+  fn flag(&mut self) -> Result<bool> {
+    self.skip_separators();
+    match self.data.get(self.position) {
+      Some(b'0') | Some(b'1') => {
+        self.position += 1;
+        Ok(self.data[self.position - 1] == b'1')
+      }
+      _ => Err(anyhow!("Expected an arc flag of 0 or 1")),
+    }
+  }
+
+  fn point(&mut self, relative_to: Option<Tuple>) -> Result<Tuple> {
+    let x = self.number()?;
+    let y = self.number()?;
+    Ok(match relative_to {
+      Some(origin) => Tuple::point(origin.x + x, origin.y + y, 0.0),
+      None => Tuple::point(x, y, 0.0),
+    })
+  }
+}
+
+/// Outlines of the subpaths of the path data
+fn parse_path(data: &str) -> Result<Vec<Vec<Tuple>>> {
+  let mut lexer = Lexer {
+    data: data.as_bytes(),
+    position: 0,
+  };
+  let mut contours: Vec<Vec<Tuple>> = vec![];
+  let mut contour: Vec<Tuple> = vec![];
+  let mut current = Tuple::point(0.0, 0.0, 0.0);
+  let mut start = current;
+  // Control point of the previous curve, reflected by smooth curves
+  let mut previous_control: Option<(char, Tuple)> = None;
+  let mut command = None;
+
+  while !lexer.at_end() {
+    if let Some(next) = lexer.command() {
+      command = Some(next);
+    }
+    let letter = command.ok_or_else(|| anyhow!("Expected a command before the coordinates"))?;
+    let relative = letter.is_ascii_lowercase();
+    let origin = if relative { Some(current) } else { None };
+    if contour.is_empty() {
+      contour.push(current);
+    }
+    let mut control = None;
+
+    match letter.to_ascii_uppercase() {
+      'M' => {
+        let point = lexer.point(origin)?;
+        contours.push(std::mem::take(&mut contour));
+        contour.push(point);
+        current = point;
+        start = point;
+        // Further pairs of coordinates are lines
+        command = Some(if relative { 'l' } else { 'L' });
+      }
+      'L' => {
+        current = lexer.point(origin)?;
+        contour.push(current);
+      }
+      'H' => {
+        let x = lexer.number()?;
+        current.x = if relative { current.x + x } else { x };
+        contour.push(current);
+      }
+      'V' => {
+        let y = lexer.number()?;
+        current.y = if relative { current.y + y } else { y };
+        contour.push(current);
+      }
+      'C' | 'S' => {
+        let first = if letter.eq_ignore_ascii_case(&'C') {
+          lexer.point(origin)?
+        } else {
+          reflect(previous_control, 'C', current)
+        };
+        let second = lexer.point(origin)?;
+        let end = lexer.point(origin)?;
+        contour.extend(curve_points(|t| {
+          let u = 1.0 - t;
+          current * (u * u * u)
+            + first * (3.0 * u * u * t)
+            + second * (3.0 * u * t * t)
+            + end * (t * t * t)
+        }));
+        control = Some(('C', second));
+        current = end;
+      }
+      'Q' | 'T' => {
+        let middle = if letter.eq_ignore_ascii_case(&'Q') {
+          lexer.point(origin)?
+        } else {
+          reflect(previous_control, 'Q', current)
+        };
+        let end = lexer.point(origin)?;
+        contour.extend(curve_points(|t| {
+          let u = 1.0 - t;
+          current * (u * u) + middle * (2.0 * u * t) + end * (t * t)
+        }));
+        control = Some(('Q', middle));
+        current = end;
+      }
+      'A' => {
+        let (rx, ry, rotation) = (lexer.number()?, lexer.number()?, lexer.number()?);
+        let (large_arc, sweep) = (lexer.flag()?, lexer.flag()?);
+        let end = lexer.point(origin)?;
+        contour.extend(arc_points(current, end, rx, ry, rotation, large_arc, sweep));
+        current = end;
+      }
+      'Z' => {
+        contours.push(std::mem::take(&mut contour));
+        current = start;
+        command = None;
+      }
+      _ => return Err(anyhow!("Unknown path command '{}'", letter)),
+    }
+    previous_control = control;
+  }
+  contours.push(contour);
+
+  Ok(
+    contours
+      .into_iter()
+      .filter(|contour| contour.len() >= 3)
+      .collect(),
+  )
+}
+
+/// The control point of the previous curve of the same kind mirrored at the
+/// current point, or the current point after any other command
+fn reflect(previous: Option<(char, Tuple)>, kind: char, current: Tuple) -> Tuple {
+  match previous {
+    Some((previous_kind, control)) if previous_kind == kind => current + (current - control),
+    _ => current,
+  }
+}
+
+/// Points along the curve given by a function of `t` between 0 and 1,
+/// without its start
+fn curve_points(curve: impl Fn(F) -> Tuple) -> Vec<Tuple> {
+  (1..=CURVE_SEGMENTS)
+    .map(|step| curve(step as F / CURVE_SEGMENTS as F))
+    .collect()
+}
+
+/// Points along an elliptical arc in SVG's endpoint notation, without its
+/// start. See the implementation notes of the SVG specification.
+fn arc_points(
+  start: Tuple,
+  end: Tuple,
+  rx: F,
+  ry: F,
+  rotation: F,
+  large_arc: bool,
+  sweep: bool,
+) -> Vec<Tuple> {
+  // Arcs ending where they start are omitted entirely
+  if start == end {
+    return vec![];
+  }
+  let (mut rx, mut ry) = (rx.abs(), ry.abs());
+  if rx == 0.0 || ry == 0.0 {
+    return vec![end];
+  }
+  let (sin, cos) = (rotation * PI / 180.0).sin_cos();
+  let (dx, dy) = ((start.x - end.x) / 2.0, (start.y - end.y) / 2.0);
+  let (x1, y1) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+
+  // Radii too small to reach the end are scaled up
+  let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+  if lambda > 1.0 {
+    rx *= lambda.sqrt();
+    ry *= lambda.sqrt();
+  }
+  let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+  let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+  let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+  let factor = sign * (numerator / denominator).max(0.0).sqrt();
+  let (cx1, cy1) = (factor * rx * y1 / ry, -factor * ry * x1 / rx);
+  let cx = cos * cx1 - sin * cy1 + (start.x + end.x) / 2.0;
+  let cy = sin * cx1 + cos * cy1 + (start.y + end.y) / 2.0;
+
+  let theta = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+  let mut delta = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx) - theta;
+  if sweep && delta < 0.0 {
+    delta += 2.0 * PI;
+  } else if !sweep && delta > 0.0 {
+    delta -= 2.0 * PI;
+  }
+
+  let mut points = curve_points(|t| {
+    let (sin_angle, cos_angle) = (theta + delta * t).sin_cos();
+    Tuple::point(
+      cx + rx * cos * cos_angle - ry * sin * sin_angle,
+      cy + rx * sin * cos_angle + ry * cos * sin_angle,
+      0.0,
+    )
+  });
+  *points.last_mut().unwrap() = end;
+  points
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rtc_core::assert_fuzzy_eq;
+  use rtc_core::fuzzy_eq::*;
+
+  #[test]
+  fn outlines_are_flipped_and_fitted_to_one_unit() {
+    let source = r##"<svg xmlns="http://www.w3.org/2000/svg">
+  <rect width="10" height="10"/>
+  <path id="square" fill='red' d="M10 10 h20 v-20 H10 Z"/>
+</svg>"##;
+
+    let extrusion = parse_svg(source).unwrap();
+
+    assert_eq!(extrusion.contours.len(), 1);
+    assert_fuzzy_eq!(extrusion.contours[0][0], Tuple::point(0.0, 0.0, 0.0));
+    assert_fuzzy_eq!(extrusion.contours[0][1], Tuple::point(1.0, 0.0, 0.0));
+    // The lowest point in SVG coordinates ends up on top
+    assert_fuzzy_eq!(extrusion.contours[0][2], Tuple::point(1.0, 1.0, 0.0));
+  }
+
+  #[test]
+  fn subpaths_become_separate_outlines() {
+    let source = r##"<path d="M0,0L30,0 30,30 0,30z m10,10 l10,0 0,10 -10,0z"/>"##;
+
+    let extrusion = parse_svg(source).unwrap();
+
+    assert_eq!(extrusion.contours.len(), 2);
+    // The inner square is a hole
+    assert!(extrusion.contains(0.2, 0.2));
+    assert!(!extrusion.contains(0.5, 0.5));
+  }
+
+  #[test]
+  fn curves_and_arcs_are_flattened() {
+    let source = r##"<path d="M0 0 C0 10 10 10 10 0 S20-10 20 0 A10 10 0 0 1 0 0"/>"##;
+
+    let extrusion = parse_svg(source).unwrap();
+
+    let contour = &extrusion.contours[0];
+    assert_eq!(contour.len(), 1 + 3 * CURVE_SEGMENTS - 1);
+    // The arc is the lower half of a circle around (10, 0) on screen. The
+    // curves bulge by 7.5 at most, so its bottom at (10, 10) ends up at the
+    // bottom of a path 17.5 high.
+    assert!(contour
+      .iter()
+      .any(|point| point.fuzzy_eq(Tuple::point(10.0 / 17.5, 0.0, 0.0))));
+  }
+
+  #[test]
+  fn arcs_ending_at_their_start_are_omitted() {
+    let source = r##"<path d="M0 0 L10 0 L10 10 A 5 5 0 0 1 10 10 Z"/>"##;
+
+    let extrusion = parse_svg(source).unwrap();
+
+    let contour = &extrusion.contours[0];
+    assert_eq!(contour.len(), 3);
+    assert!(contour
+      .iter()
+      .all(|point| point.x.is_finite() && point.y.is_finite()));
+  }
+
+  #[test]
+  fn invalid_path_data_is_reported() {
+    let source = r##"<path d="M0 0 L10 x"/>"##;
+
+    let error = parse_svg(source).unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Expected a number, but found 'x' in path 1"
+    );
+  }
+}
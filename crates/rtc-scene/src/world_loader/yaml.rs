@@ -0,0 +1,5624 @@
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::fs::{read, read_to_string};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::cube::parse_cube;
+use super::obj::parse_obj;
+use super::orientation::{Handedness, Orientation, UpAxis};
+use super::schema;
+use super::svg::parse_svg;
+use super::{LoaderResult, LoaderWithWarningsResult, WorldLoader};
+use anyhow::*;
+use itertools::Itertools;
+use yaml_rust::{yaml, YamlLoader};
+
+use rtc_core::angle::Angle;
+use rtc_core::backdrop::Backdrop;
+use rtc_core::body::{Body, Shadows};
+use rtc_core::bounds::Bounds;
+use rtc_core::camera::{Camera, CropWindow};
+use rtc_core::canvas::{Canvas, Color};
+use rtc_core::cone::Cone;
+use rtc_core::contact_shadows::ContactShadows;
+use rtc_core::cylinder::Cylinder;
+use rtc_core::extrusion::Extrusion;
+use rtc_core::instance::{Geometry, Instance};
+use rtc_core::layer::Layer;
+use rtc_core::light::{Attenuation, Light, PointLight};
+use rtc_core::material::{
+  Bump, Coat, Cutout, Lambert, LayerMask, Layered, Material, MaterialRegistry, Phong,
+};
+use rtc_core::matrix::Matrix;
+use rtc_core::mirrored::Mirrored;
+use rtc_core::motion::MovingBody;
+use rtc_core::pattern::{
+  CheckerBoard, Easing, Fill, Gradient, Noise, Pattern, Ring, Striped, TestPattern,
+};
+use rtc_core::photon_map::Caustics;
+use rtc_core::plane::Plane;
+use rtc_core::post_processing::auto_exposure::AutoExposure;
+use rtc_core::post_processing::bloom::Bloom;
+use rtc_core::post_processing::glare::Glare;
+use rtc_core::post_processing::lens_distortion::LensDistortion;
+use rtc_core::post_processing::vignette::Vignette;
+use rtc_core::post_processing::{Effect, Pipeline};
+use rtc_core::quad::Quad;
+use rtc_core::renderer::adaptive::AdaptiveSampling;
+use rtc_core::repeated::Repeated;
+use rtc_core::sampling::Jitter;
+use rtc_core::scatter::Scatter;
+use rtc_core::sdf::{Sdf, SdfBlend, SdfBody};
+use rtc_core::sphere::Sphere;
+use rtc_core::sweep::{Axis, Kinematics, Sweep, SweepProperty};
+use rtc_core::text::Text;
+use rtc_core::texture::Texture;
+use rtc_core::triangle::{SmoothTriangle, Triangle};
+use rtc_core::tube::Tube;
+use rtc_core::tuple::Tuple;
+use rtc_core::uv::{CubeMap, TextureMap, UvMap, UvPattern};
+use rtc_core::world::World;
+use rtc_core::F;
+
+#[derive(Clone)]
+enum Segment {
+  Key(String),
+  Index(usize),
+}
+
+#[derive(Clone, Default)]
+struct Path(Vec<Segment>);
+
+impl Path {
+  pub fn push(&mut self, segment: Segment) {
+    self.0.push(segment);
+  }
+
+  pub fn pop(&mut self) {
+    self.0.pop();
+  }
+}
+
+impl std::fmt::Display for Path {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let path = self
+      .0
+      .iter()
+      .map(|segment| match segment {
+        Segment::Key(key) => format!(".{}", key),
+        Segment::Index(index) => format!("[{}]", index),
+      })
+      .join("");
+    write!(f, "{}", path)
+  }
+}
+
+macro_rules! key {
+  ($yaml:expr) => {
+    &yaml::Yaml::String($yaml.into())
+  };
+}
+
+type ParserResult<T = ()> = anyhow::Result<T>;
+
+struct FocusTarget {
+  camera: String,
+  body: String,
+  path: String,
+}
+
+#[derive(Default)]
+pub struct YamlParser<'a> {
+  data: &'a str,
+  path: Path,
+  lights: Vec<Light>,
+  bodies: Vec<Body>,
+  instances: Vec<Instance>,
+  moving_bodies: Vec<MovingBody>,
+  geometries: HashMap<PathBuf, Arc<Geometry>>,
+  textures: HashMap<PathBuf, Texture>,
+  named_bounds: HashMap<String, Bounds>,
+  body_names: Vec<(String, Layer)>,
+  materials: MaterialRegistry,
+  /// Bodies referencing a registered material by its name
+  material_references: Vec<(String, Range<usize>)>,
+  layers: Vec<(String, Layer)>,
+  /// Cameras focusing on a body, which may only be defined later on
+  focus_targets: Vec<FocusTarget>,
+  /// Sweeps of named bodies with the path they are found at, as the bodies
+  /// may only be defined later on
+  sweeps: Vec<(Sweep, String)>,
+  cameras: HashMap<String, Camera>,
+  contact_shadows: Option<ContactShadows>,
+  caustics: Option<Caustics>,
+  background: Option<Color>,
+  unlit_color: Option<Color>,
+  secondary_ray_budget: Option<usize>,
+  contribution_cutoff: Option<F>,
+  warnings: Vec<String>,
+  base_directory: PathBuf,
+  /// Orientation of meshes not declaring their own, set by the settings
+  orientation: Option<Orientation>,
+  /// Orientation of meshes when neither they nor the settings declare one
+  default_orientation: Orientation,
+  /// Meshes by their instance index, with their own orientation if declared
+  mesh_orientations: Vec<(usize, Option<Orientation>)>,
+}
+impl<'a> YamlParser<'a> {
+  pub fn new(data: &'a str) -> Self {
+    Self {
+      data,
+      path: Path::default(),
+      lights: Vec::new(),
+      bodies: Vec::new(),
+      instances: Vec::new(),
+      moving_bodies: Vec::new(),
+      geometries: HashMap::new(),
+      textures: HashMap::new(),
+      named_bounds: HashMap::new(),
+      body_names: Vec::new(),
+      materials: MaterialRegistry::new(),
+      material_references: Vec::new(),
+      layers: Vec::new(),
+      focus_targets: Vec::new(),
+      sweeps: Vec::new(),
+      cameras: HashMap::new(),
+      contact_shadows: None,
+      caustics: None,
+      background: None,
+      unlit_color: None,
+      secondary_ray_budget: None,
+      contribution_cutoff: None,
+      warnings: Vec::new(),
+      base_directory: PathBuf::from("."),
+      orientation: None,
+      default_orientation: Orientation::default(),
+      mesh_orientations: Vec::new(),
+    }
+  }
+
+  /// Directory relative file references (e.g. meshes) are resolved against
+  pub fn with_base_directory(mut self, base_directory: PathBuf) -> Self {
+    self.base_directory = base_directory;
+    self
+  }
+
+  /// Orientation of meshes neither they nor the settings declare one for
+  pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+    self.default_orientation = orientation;
+    self
+  }
+
+  pub fn warnings(&self) -> &[String] {
+    &self.warnings
+  }
+
+  /// Warns about every key of the hash, which is not part of the given schema
+  /// block.
+  fn check_keys(&mut self, hash: &yaml::Hash, block: &schema::Block) {
+    for key in hash.keys() {
+      match key.as_str() {
+        Some(name) if block.contains(name) => {}
+        Some(name) => {
+          let suggestion = match block.suggest(name) {
+            Some(suggestion) => format!(" Did you mean '{}'?", suggestion),
+            None => String::new(),
+          };
+          self.warnings.push(format!(
+            "Unknown key '{}' in {} found at {}.{}",
+            name, block.name, self.path, suggestion
+          ))
+        }
+        None => self.warnings.push(format!(
+          "Expected string key in {}, but found {:?} at {}",
+          block.name, key, self.path
+        )),
+      }
+    }
+  }
+
+  /// Checks the keys of the hash against the given schema block and returns
+  /// the hash with the defaults of the schema filled in for missing keys, so
+  /// visitors never repeat them. Missing required keys are an error.
+  fn apply_schema(&mut self, hash: &yaml::Hash, block: &schema::Block) -> ParserResult<yaml::Hash> {
+    self.check_keys(hash, block);
+    let kind = hash.get(key!("type")).and_then(|kind| kind.as_str());
+    let mut filled = hash.clone();
+    for field in block.fields.iter() {
+      if hash.contains_key(key!(field.name)) {
+        continue;
+      }
+      if field.required {
+        return Err(anyhow!(
+          "Tried to get value with key '{}' from hash at {}: Key not found.",
+          field.name,
+          self.path.to_string()
+        ));
+      }
+      if let Some(default) = field.default_of(kind) {
+        let default = YamlLoader::load_from_str(default)
+          .ok()
+          .and_then(|documents| documents.into_iter().next())
+          .expect("schema defaults are YAML literals");
+        filled.insert(yaml::Yaml::String(field.name.into()), default);
+      }
+    }
+    Ok(filled)
+  }
+
+  #[inline(always)]
+  fn get_value_from_hash<'b>(
+    &self,
+    hash: &'b yaml::Hash,
+    key: impl AsRef<str>,
+  ) -> ParserResult<&'b yaml::Yaml> {
+    let yaml_key = yaml::Yaml::String(key.as_ref().into());
+    if !hash.contains_key(&yaml_key) {
+      Err(anyhow!(
+        "Tried to get value with key '{}' from hash at {}: Key not found.",
+        key.as_ref(),
+        self.path.to_string()
+      ))
+    } else {
+      Ok(&hash[&yaml_key])
+    }
+  }
+
+  #[inline(always)]
+  #[allow(clippy::ptr_arg)]
+  fn get_index_from_array<'b>(
+    &self,
+    array: &'b yaml::Array,
+    index: usize,
+  ) -> ParserResult<&'b yaml::Yaml> {
+    if index > array.len() {
+      Err(anyhow!(
+        "Tried to get value with index {} from array at {}: Index not found (Array length = {}).",
+        index,
+        self.path.to_string(),
+        array.len()
+      ))
+    } else {
+      Ok(&array[index])
+    }
+  }
+
+  #[inline(always)]
+  fn value_to_string<'b>(&self, yaml: &'b yaml::Yaml) -> ParserResult<&'b impl AsRef<str>> {
+    match yaml {
+      yaml::Yaml::String(content) => Ok(content),
+      _ => Err(anyhow!(
+        "Expected string value at {}, but found {:?}",
+        self.path.to_string(),
+        yaml
+      )),
+    }
+  }
+
+  #[inline(always)]
+  fn hash_value_to_string<'b>(
+    &mut self,
+    hash: &'b yaml::Hash,
+    key: impl AsRef<str>,
+  ) -> ParserResult<&'b impl AsRef<str>> {
+    self.path.push(Segment::Key(key.as_ref().into()));
+    let value = self.get_value_from_hash(hash, key)?;
+    let result = self.value_to_string(value);
+    self.path.pop();
+    result
+  }
+
+  #[inline(always)]
+  fn value_to_int(&self, yaml: &yaml::Yaml) -> ParserResult<i64> {
+    match yaml {
+      yaml::Yaml::Integer(content) => Ok(*content),
+      _ => Err(anyhow!(
+        "Expected integer value at {}, but found {:?}",
+        self.path.to_string(),
+        yaml
+      )),
+    }
+  }
+
+  #[inline(always)]
+  fn hash_value_to_int(&mut self, hash: &yaml::Hash, key: impl AsRef<str>) -> ParserResult<i64> {
+    self.path.push(Segment::Key(key.as_ref().into()));
+    let value = self.get_value_from_hash(hash, key)?;
+    let result = self.value_to_int(value);
+    self.path.pop();
+    result
+  }
+
+  /// Number of samples, segments or the like, or a seed, which can not be
+  /// negative
+  fn hash_value_to_count(
+    &mut self,
+    hash: &yaml::Hash,
+    key: impl AsRef<str>,
+  ) -> ParserResult<usize> {
+    let count = self.hash_value_to_int(hash, key.as_ref())?;
+    if count < 0 {
+      return Err(anyhow!(
+        "Expected a count of zero or more, but found {} at {}.{}",
+        count,
+        self.path.to_string(),
+        key.as_ref()
+      ));
+    }
+    Ok(count as usize)
+  }
+
+  #[inline(always)]
+  fn value_to_float(&self, yaml: &yaml::Yaml) -> ParserResult<F> {
+    match yaml {
+      yaml::Yaml::Integer(content) => Ok(*content as f64),
+      yaml::Yaml::Real(_) => match yaml.as_f64() {
+        Some(content) => Ok(content),
+        _ => Err(anyhow!(
+          "Expected float value at {}, but found {:?}",
+          self.path.to_string(),
+          yaml
+        )),
+      },
+      _ => Err(anyhow!(
+        "Expected float value at {}, but found {:?}",
+        self.path.to_string(),
+        yaml
+      )),
+    }
+  }
+
+  #[inline(always)]
+  fn hash_value_to_float(&mut self, hash: &yaml::Hash, key: impl AsRef<str>) -> ParserResult<f64> {
+    self.path.push(Segment::Key(key.as_ref().into()));
+    let value = self.get_value_from_hash(hash, key)?;
+    let result = self.value_to_float(value);
+    self.path.pop();
+    result
+  }
+
+  /// Size of a body, which ends up in a scaling. It needs to be positive and
+  /// large enough for the scaling to be inverted.
+  fn hash_value_to_size(&mut self, hash: &yaml::Hash, key: impl AsRef<str>) -> ParserResult<F> {
+    let size = self.hash_value_to_float(hash, key.as_ref())?;
+    if size <= 0.0 || !Matrix::scaling(size, size, size).is_invertible() {
+      return Err(anyhow!(
+        "Expected a positive size, which is not too small to be rendered, but found {} at {}.{}",
+        size,
+        self.path.to_string(),
+        key.as_ref()
+      ));
+    }
+    Ok(size)
+  }
+
+  #[inline(always)]
+  fn value_to_array<'b>(&self, yaml: &'b yaml::Yaml) -> ParserResult<&'b yaml::Array> {
+    match yaml {
+      yaml::Yaml::Array(ref content) => Ok(content),
+      _ => Err(anyhow!(
+        "Expected array at {}, but found {:?}",
+        self.path.to_string(),
+        yaml
+      )),
+    }
+  }
+
+  #[inline(always)]
+  fn value_to_hash<'b>(&self, yaml: &'b yaml::Yaml) -> ParserResult<&'b yaml::Hash> {
+    match yaml {
+      yaml::Yaml::Hash(ref content) => Ok(content),
+      _ => Err(anyhow!(
+        "Expected hash at {}, but found {:?}",
+        self.path.to_string(),
+        yaml
+      )),
+    }
+  }
+
+  #[inline(always)]
+  fn value_to_bool(&self, yaml: &yaml::Yaml) -> ParserResult<bool> {
+    match yaml {
+      yaml::Yaml::Boolean(content) => Ok(*content),
+      _ => Err(anyhow!(
+        "Expected boolean value at {}, but found {:?}",
+        self.path.to_string(),
+        yaml
+      )),
+    }
+  }
+
+  #[inline(always)]
+  fn hash_value_to_bool(&mut self, hash: &yaml::Hash, key: impl AsRef<str>) -> ParserResult<bool> {
+    self.path.push(Segment::Key(key.as_ref().into()));
+    let value = self.get_value_from_hash(hash, key)?;
+    let result = self.value_to_bool(value);
+    self.path.pop();
+    result
+  }
+
+  pub fn parse_yaml(&mut self) -> LoaderResult {
+    let yaml = YamlLoader::load_from_str(self.data)?;
+    self.visit_documents(&yaml)
+  }
+
+  fn visit_documents(
+    &mut self,
+    documents_array: &[yaml_rust::Yaml],
+  ) -> ParserResult<(World, HashMap<String, Camera>)> {
+    self.path.push(Segment::Key("document".into()));
+    for (index, document) in documents_array.iter().enumerate() {
+      self.path.push(Segment::Index(index));
+      self.visit_document(document)?;
+      self.path.pop();
+    }
+    self.path.pop();
+
+    self.resolve_focus_targets()?;
+    self.check_sweeps()?;
+    self.orient_meshes();
+
+    let cameras_clone = self.cameras.clone();
+    let bodies_clone = self.bodies.clone();
+    let lights_clone = self.lights.clone();
+    let mut world = World::new(bodies_clone, lights_clone)
+      .with_instances(self.instances.clone())
+      .with_moving_bodies(self.moving_bodies.clone());
+    for (name, members) in self.body_names.iter() {
+      world = world
+        .with_name(name.clone(), members.bodies.clone())
+        .with_instance_name(name.clone(), members.instances.clone())
+        .with_moving_body_name(name.clone(), members.moving_bodies.clone());
+    }
+    world = world.with_materials(self.materials.clone());
+    for (name, indices) in self.material_references.iter() {
+      world = world.with_material_reference(name.clone(), indices.clone());
+    }
+    for (name, members) in self.layers.iter() {
+      world = world.with_layer(name.clone(), members.clone());
+    }
+    if let Some(contact_shadows) = self.contact_shadows {
+      world = world.with_contact_shadows(contact_shadows);
+    }
+    if let Some(background) = self.background {
+      world = world.with_background(background);
+    }
+    if let Some(unlit_color) = self.unlit_color {
+      world = world.with_unlit_color(unlit_color);
+    }
+    if let Some(secondary_ray_budget) = self.secondary_ray_budget {
+      world = world.with_secondary_ray_budget(secondary_ray_budget);
+    }
+    if let Some(contribution_cutoff) = self.contribution_cutoff {
+      world = world.with_contribution_cutoff(contribution_cutoff);
+    }
+    world = world.with_sweeps(self.sweeps.iter().map(|(sweep, _)| sweep.clone()).collect());
+    // Photons are traced through the finished world
+    if let Some(caustics) = self.caustics {
+      world = world.with_caustics(caustics);
+    }
+    Ok((world, cameras_clone))
+  }
+
+  /// Converts meshes to the axis convention of the renderer. The settings
+  /// may come after the meshes, so this happens once everything is parsed.
+  fn orient_meshes(&mut self) {
+    for (index, orientation) in self.mesh_orientations.iter() {
+      let orientation = orientation
+        .or(self.orientation)
+        .unwrap_or(self.default_orientation);
+      let instance = &mut self.instances[*index];
+      *instance = instance
+        .clone()
+        .with_transform(instance.transform() * orientation.to_world());
+    }
+  }
+
+  /// Focuses cameras on the center of the bounds of their named bodies
+  fn resolve_focus_targets(&mut self) -> ParserResult {
+    for target in self.focus_targets.iter() {
+      let bounds = self.named_bounds.get(&target.body).ok_or_else(|| {
+        anyhow!(
+          "Unknown body '{}' to focus on found at {}",
+          target.body,
+          target.path
+        )
+      })?;
+      if let Some(camera) = self.cameras.get_mut(&target.camera) {
+        // The focal plane is perpendicular to the view axis, so the depth of
+        // the body along it is used rather than its distance
+        let focal_distance = -(camera.transform * bounds.center()).z;
+        if focal_distance <= 0.0 {
+          return Err(anyhow!(
+            "Body '{}' to focus on is behind the camera found at {}",
+            target.body,
+            target.path
+          ));
+        }
+        *camera = camera.clone().with_focal_distance(focal_distance);
+      }
+    }
+    Ok(())
+  }
+
+  /// Ensures every sweep moves at least one body, meshes can not be swept
+  fn check_sweeps(&self) -> ParserResult {
+    for (sweep, path) in self.sweeps.iter() {
+      let swept = self
+        .body_names
+        .iter()
+        .any(|(name, members)| *name == sweep.body && !members.bodies.is_empty());
+      if !swept {
+        return Err(anyhow!(
+          "Unknown body '{}' to animate found at {}",
+          sweep.body,
+          path
+        ));
+      }
+    }
+    Ok(())
+  }
+
+  fn visit_document(&mut self, document: &yaml_rust::Yaml) -> ParserResult {
+    self.path.push(Segment::Key("item".into()));
+    let document_array = self.value_to_array(document)?;
+    for (index, item) in document_array.iter().enumerate() {
+      self.path.push(Segment::Index(index));
+      self.visit_item(item)?;
+      self.path.pop();
+    }
+    self.path.pop();
+    Ok(())
+  }
+
+  fn visit_item(&mut self, item: &yaml::Yaml) -> ParserResult {
+    let item_hash = &self.apply_schema(self.value_to_hash(item)?, &schema::ITEM)?;
+    if item_hash.contains_key(key!("light")) {
+      let light_value = self.get_value_from_hash(item_hash, "light")?;
+      self.path.push(Segment::Key("light".into()));
+      let light = self.visit_light(light_value)?;
+      for name in self.visit_layer_names(self.value_to_hash(light_value)?)? {
+        let members = Layer::new().with_lights(vec![self.lights.len()]);
+        self.layers.push((name, members));
+      }
+      self.path.pop();
+
+      self.lights.push(light);
+    } else if item_hash.contains_key(key!("body")) {
+      let body_value = self.get_value_from_hash(item_hash, "body")?;
+      self.path.push(Segment::Key("body".into()));
+      self.visit_body_item(body_value)?;
+      self.path.pop();
+    } else if item_hash.contains_key(key!("camera")) {
+      let camera_value = self.get_value_from_hash(item_hash, "camera")?;
+      self.path.push(Segment::Key("camera".into()));
+      let (name, camera) = self.visit_camera(camera_value)?;
+      self.path.pop();
+      self.cameras.insert(name, camera);
+    } else if item_hash.contains_key(key!("material")) {
+      let material_value = self.get_value_from_hash(item_hash, "material")?;
+      self.path.push(Segment::Key("material".into()));
+      self.visit_named_material(material_value)?;
+      self.path.pop();
+    } else if item_hash.contains_key(key!("settings")) {
+      let settings_value = self.get_value_from_hash(item_hash, "settings")?;
+      self.path.push(Segment::Key("settings".into()));
+      self.visit_settings(settings_value)?;
+      self.path.pop();
+    } else if item_hash.contains_key(key!("animate")) {
+      let animate_value = self.get_value_from_hash(item_hash, "animate")?;
+      self.path.push(Segment::Key("animate".into()));
+      let sweep = self.visit_sweep(animate_value)?;
+      self.sweeps.push((sweep, self.path.to_string()));
+      self.path.pop();
+    } else {
+      return Err(anyhow!(format!(
+        "Unknown item type found at {}",
+        self.path.to_string()
+      )));
+    }
+    Ok(())
+  }
+
+  fn visit_settings(&mut self, settings: &yaml::Yaml) -> ParserResult {
+    let settings_hash = &self.apply_schema(self.value_to_hash(settings)?, &schema::SETTINGS)?;
+
+    if settings_hash.contains_key(key!("contact_shadows")) {
+      let contact_shadows_value = self.get_value_from_hash(settings_hash, "contact_shadows")?;
+      self.path.push(Segment::Key("contact_shadows".into()));
+      let contact_shadows_hash = &self.apply_schema(
+        self.value_to_hash(contact_shadows_value)?,
+        &schema::CONTACT_SHADOWS,
+      )?;
+      let contact_shadows = ContactShadows {
+        distance: self.hash_value_to_float(contact_shadows_hash, "distance")?,
+        samples: self.hash_value_to_count(contact_shadows_hash, "samples")?,
+        strength: self.hash_value_to_float(contact_shadows_hash, "strength")?,
+      };
+      self.path.pop();
+      self.contact_shadows = Some(contact_shadows);
+    }
+
+    if settings_hash.contains_key(key!("caustics")) {
+      let caustics_value = self.get_value_from_hash(settings_hash, "caustics")?;
+      self.path.push(Segment::Key("caustics".into()));
+      let caustics_hash =
+        &self.apply_schema(self.value_to_hash(caustics_value)?, &schema::CAUSTICS)?;
+      let caustics = Caustics {
+        photons: self.hash_value_to_count(caustics_hash, "photons")?,
+        radius: self.hash_value_to_float(caustics_hash, "radius")?,
+        seed: self.hash_value_to_count(caustics_hash, "seed")? as u64,
+      };
+      self.path.pop();
+      self.caustics = Some(caustics);
+    }
+
+    self.background = Some(self.hash_value_to_color(settings_hash, "background")?);
+    self.unlit_color = Some(self.hash_value_to_color(settings_hash, "unlit_color")?);
+
+    if settings_hash.contains_key(key!("orientation")) {
+      let orientation_value = self.get_value_from_hash(settings_hash, "orientation")?;
+      self.path.push(Segment::Key("orientation".into()));
+      self.orientation = Some(self.visit_orientation(orientation_value)?);
+      self.path.pop();
+    }
+
+    if settings_hash.contains_key(key!("secondary_ray_budget")) {
+      self.secondary_ray_budget =
+        Some(self.hash_value_to_count(settings_hash, "secondary_ray_budget")?);
+    }
+
+    self.contribution_cutoff =
+      Some(self.hash_value_to_float(settings_hash, "contribution_cutoff")?);
+
+    Ok(())
+  }
+
+  fn visit_light(&mut self, light: &yaml::Yaml) -> ParserResult<Light> {
+    let light_hash = &self.apply_schema(self.value_to_hash(light)?, &schema::LIGHT)?;
+    let light_type = self.hash_value_to_string(light_hash, "type")?;
+
+    if light_type.as_ref() == "point_light" {
+      let light_at_value = self.get_value_from_hash(light_hash, "at")?;
+      self.path.push(Segment::Key("at".into()));
+      let light_at = self.visit_point(light_at_value)?;
+      self.path.pop();
+
+      let light_intensity_value = self.get_value_from_hash(light_hash, "intensity")?;
+      self.path.push(Segment::Key("intensity".into()));
+      let light_intensity = self.visit_color(light_intensity_value)?;
+      self.path.pop();
+
+      let mut light = PointLight::new(light_at, light_intensity)
+        .with_ev(self.hash_value_to_float(light_hash, "ev")?);
+      if light_hash.contains_key(key!("attenuation")) {
+        let attenuation_value = self.get_value_from_hash(light_hash, "attenuation")?;
+        self.path.push(Segment::Key("attenuation".into()));
+        let attenuation = self.visit_attenuation(attenuation_value)?;
+        self.path.pop();
+        light = light.with_attenuation(attenuation);
+      }
+      Ok(light.into())
+    } else {
+      Err(anyhow!(
+        "Unknown light type '{}' found at {}",
+        light_type.as_ref(),
+        self.path.to_string()
+      ))
+    }
+  }
+
+  fn visit_attenuation(&mut self, attenuation: &yaml::Yaml) -> ParserResult<Attenuation> {
+    let attenuation_hash =
+      &self.apply_schema(self.value_to_hash(attenuation)?, &schema::ATTENUATION)?;
+    let mut coefficients = [0.0; 3];
+    let keys = ["constant", "linear", "quadratic"];
+    for (key, coefficient) in keys.iter().zip(coefficients.iter_mut()) {
+      let value = self.hash_value_to_float(attenuation_hash, key)?;
+      if value < 0.0 {
+        return Err(anyhow!(
+          "Attenuation coefficients need to be non negative, but found {} at {}.{}",
+          value,
+          self.path.to_string(),
+          key
+        ));
+      }
+      *coefficient = value;
+    }
+    let [constant, linear, quadratic] = coefficients;
+    Ok(Attenuation::new(constant, linear, quadratic))
+  }
+
+  fn visit_orientation(&mut self, orientation: &yaml::Yaml) -> ParserResult<Orientation> {
+    let orientation_hash =
+      &self.apply_schema(self.value_to_hash(orientation)?, &schema::ORIENTATION)?;
+    let up = match self.hash_value_to_string(orientation_hash, "up")?.as_ref() {
+      "x" => UpAxis::X,
+      "y" => UpAxis::Y,
+      "z" => UpAxis::Z,
+      up => {
+        return Err(anyhow!(
+          "Unknown up axis '{}' found at {}",
+          up,
+          self.path.to_string()
+        ))
+      }
+    };
+    let handedness = match self
+      .hash_value_to_string(orientation_hash, "handedness")?
+      .as_ref()
+    {
+      "left" => Handedness::Left,
+      "right" => Handedness::Right,
+      handedness => {
+        return Err(anyhow!(
+          "Unknown handedness '{}' found at {}",
+          handedness,
+          self.path.to_string()
+        ))
+      }
+    };
+    Ok(Orientation { up, handedness })
+  }
+
+  fn visit_point(&mut self, point: &yaml::Yaml) -> ParserResult<Tuple> {
+    let point_array = self.value_to_array(point)?;
+    let x_value = self.get_index_from_array(point_array, 0)?;
+    self.path.push(Segment::Index(0));
+    let x = self.value_to_float(x_value)?;
+    self.path.pop();
+    let y_value = self.get_index_from_array(point_array, 1)?;
+    self.path.push(Segment::Index(1));
+    let y = self.value_to_float(y_value)?;
+    self.path.pop();
+    let z_value = self.get_index_from_array(point_array, 2)?;
+    self.path.push(Segment::Index(2));
+    let z = self.value_to_float(z_value)?;
+    self.path.pop();
+    Ok(Tuple::point(x, y, z))
+  }
+
+  fn visit_vector(&mut self, vector: &yaml::Yaml) -> ParserResult<Tuple> {
+    let vector_array = self.value_to_array(vector)?;
+    let x_value = self.get_index_from_array(vector_array, 0)?;
+    self.path.push(Segment::Index(0));
+    let x = self.value_to_float(x_value)?;
+    self.path.pop();
+    let y_value = self.get_index_from_array(vector_array, 1)?;
+    self.path.push(Segment::Index(1));
+    let y = self.value_to_float(y_value)?;
+    self.path.pop();
+    let z_value = self.get_index_from_array(vector_array, 2)?;
+    self.path.push(Segment::Index(2));
+    let z = self.value_to_float(z_value)?;
+    self.path.pop();
+    Ok(Tuple::vector(x, y, z))
+  }
+
+  fn visit_color(&mut self, color: &yaml::Yaml) -> ParserResult<Color> {
+    let color_array = self.value_to_array(color)?;
+    let r_value = self.get_index_from_array(color_array, 0)?;
+    self.path.push(Segment::Index(0));
+    let r = self.value_to_float(r_value)?;
+    self.path.pop();
+    let g_value = self.get_index_from_array(color_array, 1)?;
+    self.path.push(Segment::Index(1));
+    let g = self.value_to_float(g_value)?;
+    self.path.pop();
+    let b_value = self.get_index_from_array(color_array, 2)?;
+    self.path.push(Segment::Index(2));
+    let b = self.value_to_float(b_value)?;
+    self.path.pop();
+    Ok(Color::new(r, g, b))
+  }
+
+  fn hash_value_to_point(&mut self, hash: &yaml::Hash, key: &str) -> ParserResult<Tuple> {
+    let point_value = self.get_value_from_hash(hash, key)?;
+    self.path.push(Segment::Key(key.into()));
+    let point = self.visit_point(point_value)?;
+    self.path.pop();
+    Ok(point)
+  }
+
+  fn hash_value_to_vector(&mut self, hash: &yaml::Hash, key: &str) -> ParserResult<Tuple> {
+    let vector_value = self.get_value_from_hash(hash, key)?;
+    self.path.push(Segment::Key(key.into()));
+    let vector = self.visit_vector(vector_value)?;
+    self.path.pop();
+    Ok(vector)
+  }
+
+  fn hash_value_to_color(&mut self, hash: &yaml::Hash, key: &str) -> ParserResult<Color> {
+    let color_value = self.get_value_from_hash(hash, key)?;
+    self.path.push(Segment::Key(key.into()));
+    let color = self.visit_color(color_value)?;
+    self.path.pop();
+    Ok(color)
+  }
+
+  fn visit_pattern(&mut self, pattern: &yaml::Yaml) -> ParserResult<Pattern> {
+    let pattern_hash = &self.apply_schema(self.value_to_hash(pattern)?, &schema::PATTERN)?;
+    let pattern_type = self.hash_value_to_string(pattern_hash, "type")?;
+
+    match pattern_type.as_ref() {
+      "striped" => self.visit_striped_pattern(pattern_hash),
+      "gradient" => self.visit_gradient_pattern(pattern_hash),
+      "ring" => self.visit_ring_pattern(pattern_hash),
+      "checkerboard" => self.visit_checkerboard_pattern(pattern_hash),
+      "noise" => self.visit_noise_pattern(pattern_hash),
+      "texture_map" => self.visit_texture_map_pattern(pattern_hash),
+      "cube_map" => self.visit_cube_map_pattern(pattern_hash),
+      "test" => self.visit_test_pattern(pattern_hash),
+      _ => Err(anyhow!(
+        "Unknown Pattern type '{}' found at {}",
+        pattern_type.as_ref(),
+        self.path.to_string()
+      )),
+    }
+  }
+
+  /// Flat color, or a nested pattern filling a color slot of a pattern
+  fn visit_fill(&mut self, fill: &yaml::Yaml) -> ParserResult<Fill> {
+    match fill {
+      yaml::Yaml::Hash(_) => Ok(Fill::from(self.visit_pattern(fill)?)),
+      _ => Ok(Fill::from(self.visit_color(fill)?)),
+    }
+  }
+
+  fn visit_striped_pattern(&mut self, pattern_hash: &yaml::Hash) -> ParserResult<Pattern> {
+    let color_a_value = self.get_value_from_hash(pattern_hash, "colorA")?;
+    self.path.push(Segment::Key("colorA".into()));
+    let color_a = self.visit_fill(color_a_value)?;
+    self.path.pop();
+    let color_b_value = self.get_value_from_hash(pattern_hash, "colorB")?;
+    self.path.push(Segment::Key("colorB".into()));
+    let color_b = self.visit_fill(color_b_value)?;
+    self.path.pop();
+
+    let direction = self.hash_value_to_vector(pattern_hash, "direction")?;
+    if direction.magnitude() == 0.0 {
+      return Err(anyhow!(
+        "Stripe direction needs to be non zero, but found {:?} at {}.direction",
+        direction,
+        self.path.to_string()
+      ));
+    }
+
+    let transform = self.hash_value_to_transform(pattern_hash)?;
+
+    Ok(Pattern::from(
+      Striped::default()
+        .with_direction(direction)
+        .with_colors(color_a, color_b)
+        .with_transform(transform),
+    ))
+  }
+
+  fn visit_gradient_pattern(&mut self, pattern_hash: &yaml::Hash) -> ParserResult<Pattern> {
+    let color_a_value = self.get_value_from_hash(pattern_hash, "colorA")?;
+    self.path.push(Segment::Key("colorA".into()));
+    let color_a = self.visit_fill(color_a_value)?;
+    self.path.pop();
+    let color_b_value = self.get_value_from_hash(pattern_hash, "colorB")?;
+    self.path.push(Segment::Key("colorB".into()));
+    let color_b = self.visit_fill(color_b_value)?;
+    self.path.pop();
+
+    let transform = self.hash_value_to_transform(pattern_hash)?;
+
+    let name = self.hash_value_to_string(pattern_hash, "easing")?;
+    let easing = match name.as_ref() {
+      "linear" => Easing::Linear,
+      "smoothstep" => Easing::Smoothstep,
+      "mirrored" => Easing::Mirrored,
+      "clamped" => Easing::Clamped,
+      _ => {
+        return Err(anyhow!(
+          "Unknown easing '{}' found at {}",
+          name.as_ref(),
+          self.path.to_string()
+        ))
+      }
+    };
+
+    Ok(Pattern::from(
+      Gradient::default()
+        .with_colors(color_a, color_b)
+        .with_easing(easing)
+        .with_transform(transform),
+    ))
+  }
+
+  fn visit_ring_pattern(&mut self, pattern_hash: &yaml::Hash) -> ParserResult<Pattern> {
+    let color_a_value = self.get_value_from_hash(pattern_hash, "colorA")?;
+    self.path.push(Segment::Key("colorA".into()));
+    let color_a = self.visit_fill(color_a_value)?;
+    self.path.pop();
+    let color_b_value = self.get_value_from_hash(pattern_hash, "colorB")?;
+    self.path.push(Segment::Key("colorB".into()));
+    let color_b = self.visit_fill(color_b_value)?;
+    self.path.pop();
+
+    let transform = self.hash_value_to_transform(pattern_hash)?;
+
+    Ok(Pattern::from(
+      Ring::default()
+        .with_colors(color_a, color_b)
+        .with_transform(transform),
+    ))
+  }
+
+  fn visit_checkerboard_pattern(&mut self, pattern_hash: &yaml::Hash) -> ParserResult<Pattern> {
+    let color_a_value = self.get_value_from_hash(pattern_hash, "colorA")?;
+    self.path.push(Segment::Key("colorA".into()));
+    let color_a = self.visit_fill(color_a_value)?;
+    self.path.pop();
+    let color_b_value = self.get_value_from_hash(pattern_hash, "colorB")?;
+    self.path.push(Segment::Key("colorB".into()));
+    let color_b = self.visit_fill(color_b_value)?;
+    self.path.pop();
+
+    let third_dimension = self.hash_value_to_bool(pattern_hash, "3d")?;
+
+    let transform = self.hash_value_to_transform(pattern_hash)?;
+
+    Ok(Pattern::from(
+      CheckerBoard::default()
+        .with_colors(color_a, color_b)
+        .with_transform(transform)
+        .with_third_dimension(third_dimension),
+    ))
+  }
+
+  fn visit_noise_pattern(&mut self, pattern_hash: &yaml::Hash) -> ParserResult<Pattern> {
+    let color_a_value = self.get_value_from_hash(pattern_hash, "colorA")?;
+    self.path.push(Segment::Key("colorA".into()));
+    let color_a = self.visit_fill(color_a_value)?;
+    self.path.pop();
+    let color_b_value = self.get_value_from_hash(pattern_hash, "colorB")?;
+    self.path.push(Segment::Key("colorB".into()));
+    let color_b = self.visit_fill(color_b_value)?;
+    self.path.pop();
+
+    let octaves = self.hash_value_to_count(pattern_hash, "octaves")?;
+    let mut noise = Noise::default()
+      .with_colors(color_a, color_b)
+      .with_octaves(octaves)
+      .with_persistence(self.hash_value_to_float(pattern_hash, "persistence")?)
+      .with_seed(self.hash_value_to_count(pattern_hash, "seed")? as u64);
+
+    noise = noise.with_transform(self.hash_value_to_transform(pattern_hash)?);
+
+    Ok(Pattern::from(noise))
+  }
+
+  /// Position in pattern space as color, to debug transformations
+  fn visit_test_pattern(&mut self, pattern_hash: &yaml::Hash) -> ParserResult<Pattern> {
+    let mut test = TestPattern::default();
+    test = test.with_transform(self.hash_value_to_transform(pattern_hash)?);
+
+    Ok(Pattern::from(test))
+  }
+
+  /// Image or checkers in UV space, wrapped around bodies by a projection
+  fn visit_texture_map_pattern(&mut self, pattern_hash: &yaml::Hash) -> ParserResult<Pattern> {
+    let uv_pattern = if pattern_hash.contains_key(key!("file")) {
+      UvPattern::Image(self.visit_texture_file(pattern_hash, "file")?)
+    } else {
+      let color_a_value = self.get_value_from_hash(pattern_hash, "colorA")?;
+      self.path.push(Segment::Key("colorA".into()));
+      let color_a = self.visit_color(color_a_value)?;
+      self.path.pop();
+      let color_b_value = self.get_value_from_hash(pattern_hash, "colorB")?;
+      self.path.push(Segment::Key("colorB".into()));
+      let color_b = self.visit_color(color_b_value)?;
+      self.path.pop();
+      let columns = self.hash_value_to_count(pattern_hash, "columns")?;
+      let rows = self.hash_value_to_count(pattern_hash, "rows")?;
+      UvPattern::checkers(columns, rows, color_a, color_b)
+    };
+
+    let mut texture_map = TextureMap::new(uv_pattern);
+    if pattern_hash.contains_key(key!("projection")) {
+      let projection = self.hash_value_to_string(pattern_hash, "projection")?;
+      let map = match projection.as_ref() {
+        "spherical" => UvMap::Spherical,
+        "planar" => UvMap::Planar,
+        "cylindrical" => UvMap::Cylindrical,
+        "cubic" => UvMap::Cubic,
+        _ => {
+          return Err(anyhow!(
+            "Unknown projection '{}' found at {}",
+            projection.as_ref(),
+            self.path.to_string()
+          ))
+        }
+      };
+      texture_map = texture_map.with_map(map);
+    }
+
+    texture_map = texture_map.with_transform(self.hash_value_to_transform(pattern_hash)?);
+
+    Ok(Pattern::from(texture_map))
+  }
+
+  fn visit_cube_map_pattern(&mut self, pattern_hash: &yaml::Hash) -> ParserResult<Pattern> {
+    let mut faces = vec![];
+    for face in ["left", "front", "right", "back", "up", "down"] {
+      let face_value = self.get_value_from_hash(pattern_hash, face)?;
+      self.path.push(Segment::Key(face.into()));
+      faces.push(self.visit_uv_pattern(face_value)?);
+      self.path.pop();
+    }
+
+    let faces: [UvPattern; 6] = faces.try_into().expect("a pattern for each face");
+    let mut cube_map = CubeMap::new(faces);
+    cube_map = cube_map.with_transform(self.hash_value_to_transform(pattern_hash)?);
+
+    Ok(Pattern::from(cube_map))
+  }
+
+  /// Image, checkers or align check in UV space, e.g. a face of a cube map
+  fn visit_uv_pattern(&mut self, uv_pattern: &yaml::Yaml) -> ParserResult<UvPattern> {
+    let uv_pattern_hash =
+      &self.apply_schema(self.value_to_hash(uv_pattern)?, &schema::UV_PATTERN)?;
+    let uv_pattern_type = self.hash_value_to_string(uv_pattern_hash, "type")?;
+
+    match uv_pattern_type.as_ref() {
+      "image" => Ok(UvPattern::Image(
+        self.visit_texture_file(uv_pattern_hash, "file")?,
+      )),
+      "checkers" => {
+        let color_a = self.hash_value_to_color(uv_pattern_hash, "colorA")?;
+        let color_b = self.hash_value_to_color(uv_pattern_hash, "colorB")?;
+        let columns = self.hash_value_to_count(uv_pattern_hash, "columns")?;
+        let rows = self.hash_value_to_count(uv_pattern_hash, "rows")?;
+        Ok(UvPattern::checkers(columns, rows, color_a, color_b))
+      }
+      "align_check" => Ok(UvPattern::AlignCheck {
+        main: self.hash_value_to_color(uv_pattern_hash, "main")?,
+        upper_left: self.hash_value_to_color(uv_pattern_hash, "upper_left")?,
+        upper_right: self.hash_value_to_color(uv_pattern_hash, "upper_right")?,
+        bottom_left: self.hash_value_to_color(uv_pattern_hash, "bottom_left")?,
+        bottom_right: self.hash_value_to_color(uv_pattern_hash, "bottom_right")?,
+      }),
+      _ => Err(anyhow!(
+        "Unknown UV pattern type '{}' found at {}",
+        uv_pattern_type.as_ref(),
+        self.path.to_string()
+      )),
+    }
+  }
+
+  /// A single body, or all triangles of a mesh
+  /// Body together with its name and motion, which may turn it into moving
+  /// bodies
+  fn visit_body_item(&mut self, body_value: &yaml::Yaml) -> ParserResult {
+    let instance_count = self.instances.len();
+    let body_count = self.bodies.len();
+    let moving_body_count = self.moving_bodies.len();
+    let body_hash = &self.apply_schema(self.value_to_hash(body_value)?, &schema::BODY)?;
+    let mut bodies = self.visit_body(body_hash)?;
+    if let Some((point, normal)) = self.visit_body_mirror(body_hash)? {
+      bodies = bodies
+        .into_iter()
+        .map(|body| Body::from(Mirrored::new(body).with_plane(point, normal)))
+        .collect();
+      // Meshes are mirrored by a reflected instance of the same geometry
+      let reflection = Matrix::reflection(point, normal);
+      let meshes = self.instances.len() - instance_count;
+      for index in instance_count..instance_count + meshes {
+        let instance = self.instances[index].clone();
+        let transform = reflection * instance.transform();
+        self.instances.push(instance.with_transform(transform));
+      }
+      let orientations: Vec<(usize, Option<Orientation>)> = self
+        .mesh_orientations
+        .iter()
+        .filter(|(index, _)| *index >= instance_count)
+        .map(|&(index, orientation)| (index + meshes, orientation))
+        .collect();
+      self.mesh_orientations.extend(orientations);
+    }
+    if let Some(scatter) = self.visit_body_scatter(body_hash)? {
+      if self.instances.len() > instance_count {
+        self.warnings.push(format!(
+          "Scattering of meshes is not supported, found at {}",
+          self.path
+        ));
+      } else {
+        let placed = scatter.bodies(&bodies);
+        if placed.len() < scatter.count * bodies.len() {
+          self.warnings.push(format!(
+            "Only {} of {} scattered bodies fit without overlaps, found at {}",
+            placed.len() / bodies.len(),
+            scatter.count,
+            self.path
+          ));
+        }
+        bodies = placed;
+      }
+    }
+    let shadows = Shadows {
+      cast: self.hash_value_to_bool(body_hash, "cast_shadow")?,
+      receive: self.hash_value_to_bool(body_hash, "receive_shadow")?,
+    };
+    bodies = bodies
+      .into_iter()
+      .map(|body| body.with_shadows(shadows))
+      .collect();
+    for instance in self.instances[instance_count..].iter_mut() {
+      instance.shadows = Some(shadows);
+    }
+    let mut bounds: Vec<Bounds> = self.instances[instance_count..]
+      .iter()
+      .map(|instance| instance.bounds())
+      .collect();
+
+    match self.visit_body_motion(body_hash)? {
+      None => {
+        bounds.extend(bodies.iter().map(|body| body.bounds()));
+        self.bodies.extend(bodies);
+      }
+      Some(end_transform) => {
+        if self.instances.len() > instance_count {
+          self.warnings.push(format!(
+            "Motion of meshes is not supported, found at {}",
+            self.path
+          ));
+        }
+        let mut moving_bodies = vec![];
+        for body in bodies {
+          let moving_body = MovingBody::new(body, end_transform).ok_or_else(|| {
+            anyhow!(
+              "Motion can only translate, rotate and scale a body without shearing or flipping it, found at {}.motion",
+              self.path.to_string()
+            )
+          })?;
+          moving_bodies.push(moving_body);
+        }
+        bounds.extend(moving_bodies.iter().map(|moving_body| moving_body.bounds()));
+        self.moving_bodies.extend(moving_bodies);
+      }
+    }
+
+    if let Some(name) = self.visit_body_material_name(body_hash)? {
+      self
+        .material_references
+        .push((name, body_count..self.bodies.len()));
+    }
+
+    let members = Layer::new()
+      .with_bodies(body_count..self.bodies.len())
+      .with_instances(instance_count..self.instances.len())
+      .with_moving_bodies(moving_body_count..self.moving_bodies.len());
+    for name in self.visit_layer_names(body_hash)? {
+      self.layers.push((name, members.clone()));
+    }
+
+    if let Some(name) = self.visit_body_name(body_hash)? {
+      self.body_names.push((name.clone(), members));
+      if let Some(bounds) = bounds
+        .into_iter()
+        .reduce(|bounds, other| bounds.union(other))
+      {
+        self.named_bounds.insert(name, bounds);
+      }
+    }
+    Ok(())
+  }
+
+  /// Layers of a body or light, given as a single name or a list of names
+  fn visit_layer_names(&mut self, hash: &yaml::Hash) -> ParserResult<Vec<String>> {
+    if !hash.contains_key(key!("layers")) {
+      return Ok(vec![]);
+    }
+    let layers_value = self.get_value_from_hash(hash, "layers")?;
+    self.path.push(Segment::Key("layers".into()));
+    let names = match layers_value {
+      yaml::Yaml::Array(array) => {
+        let mut names = vec![];
+        for (index, name_value) in array.iter().enumerate() {
+          self.path.push(Segment::Index(index));
+          names.push(self.value_to_string(name_value)?.as_ref().to_string());
+          self.path.pop();
+        }
+        names
+      }
+      _ => vec![self.value_to_string(layers_value)?.as_ref().to_string()],
+    };
+    self.path.pop();
+    Ok(names)
+  }
+
+  /// Transformation at the end of the shutter interval of a moving body
+  fn visit_body_motion(&mut self, body_hash: &yaml::Hash) -> ParserResult<Option<Matrix<4>>> {
+    if !body_hash.contains_key(key!("motion")) {
+      return Ok(None);
+    }
+    let motion_value = self.get_value_from_hash(body_hash, "motion")?;
+    self.path.push(Segment::Key("motion".into()));
+    let end_transform = self.visit_transforms(motion_value)?;
+    self.path.pop();
+    Ok(Some(end_transform))
+  }
+
+  fn visit_body_scatter(&mut self, body_hash: &yaml::Hash) -> ParserResult<Option<Scatter>> {
+    if !body_hash.contains_key(key!("scatter")) {
+      return Ok(None);
+    }
+    let scatter_value = self.get_value_from_hash(body_hash, "scatter")?;
+    self.path.push(Segment::Key("scatter".into()));
+    let scatter_hash = &self.apply_schema(self.value_to_hash(scatter_value)?, &schema::SCATTER)?;
+    let count = self.hash_value_to_count(scatter_hash, "count")?;
+    let min = self.hash_value_to_point(scatter_hash, "min")?;
+    let max = self.hash_value_to_point(scatter_hash, "max")?;
+    let attempts = self.hash_value_to_count(scatter_hash, "attempts")?;
+
+    let scatter = Scatter::new(count, Bounds::new(min, max))
+      .with_scale(
+        self.hash_value_to_size(scatter_hash, "min_scale")?,
+        self.hash_value_to_size(scatter_hash, "max_scale")?,
+      )
+      .with_rotation(self.hash_value_to_bool(scatter_hash, "rotate")?)
+      .with_seed(self.hash_value_to_count(scatter_hash, "seed")? as u64)
+      .with_attempts(attempts);
+    self.path.pop();
+    Ok(Some(scatter))
+  }
+
+  /// Point and normal of the plane a body is mirrored across
+  fn visit_body_mirror(&mut self, body_hash: &yaml::Hash) -> ParserResult<Option<(Tuple, Tuple)>> {
+    if !body_hash.contains_key(key!("mirror")) {
+      return Ok(None);
+    }
+    let mirror_value = self.get_value_from_hash(body_hash, "mirror")?;
+    self.path.push(Segment::Key("mirror".into()));
+    let mirror_hash = &self.apply_schema(self.value_to_hash(mirror_value)?, &schema::MIRROR)?;
+    let point = self.hash_value_to_point(mirror_hash, "point")?;
+    let normal = self.hash_value_to_vector(mirror_hash, "normal")?;
+    if normal.magnitude() == 0.0 {
+      return Err(anyhow!(
+        "Mirror normal needs to be non zero, but found {:?} at {}.normal",
+        normal,
+        self.path.to_string()
+      ));
+    }
+    self.path.pop();
+    Ok(Some((point, normal)))
+  }
+
+  fn visit_body_name(&mut self, body_hash: &yaml::Hash) -> ParserResult<Option<String>> {
+    if !body_hash.contains_key(key!("name")) {
+      return Ok(None);
+    }
+    let name: String = self
+      .hash_value_to_string(body_hash, "name")?
+      .as_ref()
+      .into();
+    if self.named_bounds.contains_key(&name) {
+      self.warnings.push(format!(
+        "Duplicate body name '{}' found at {}",
+        name, self.path
+      ));
+    }
+    Ok(Some(name))
+  }
+
+  /// Name of the registered material the body references, if any
+  fn visit_body_material_name(&mut self, body_hash: &yaml::Hash) -> ParserResult<Option<String>> {
+    if !body_hash.contains_key(key!("material")) {
+      return Ok(None);
+    }
+    let material_value = self.get_value_from_hash(body_hash, "material")?;
+    Ok(material_value.as_str().map(String::from))
+  }
+
+  /// Material item registered under its name
+  fn visit_named_material(&mut self, material: &yaml::Yaml) -> ParserResult {
+    let material_hash = self.value_to_hash(material)?;
+    let name: String = self
+      .hash_value_to_string(material_hash, "name")?
+      .as_ref()
+      .into();
+    if self.materials.contains(&name) {
+      self.warnings.push(format!(
+        "Duplicate material name '{}' found at {}",
+        name, self.path
+      ));
+    }
+    let material = self.visit_material(material)?;
+    self.materials.insert(name, material);
+    Ok(())
+  }
+
+  /// Registered material of the given name
+  fn visit_material_reference(&self, name: &str) -> ParserResult<Material> {
+    self.materials.get(name).ok_or_else(|| {
+      anyhow!(
+        "Unknown material '{}' found at {}",
+        name,
+        self.path.to_string()
+      )
+    })
+  }
+
+  /// Bodies of a body hash, which the schema has been applied to
+  fn visit_body(&mut self, body_hash: &yaml::Hash) -> ParserResult<Vec<Body>> {
+    let mut material = Material::default();
+    let body_type = self.hash_value_to_string(body_hash, "type")?;
+
+    if body_hash.contains_key(key!("material")) {
+      let material_value = self.get_value_from_hash(body_hash, "material")?;
+      self.path.push(Segment::Key("material".into()));
+      material = match material_value.as_str() {
+        Some(name) => self.visit_material_reference(name)?,
+        None => self.visit_material(material_value)?,
+      };
+      self.path.pop();
+    }
+
+    let transform = self.hash_value_to_transform(body_hash)?;
+
+    match body_type.as_ref() {
+      "sphere" => Ok(Body::from(Sphere::new(material, transform))),
+      "plane" => Ok(Body::from(Plane::new(material, transform))),
+      "quad" => {
+        let quad = Quad::new(material, transform)
+          .with_width(self.hash_value_to_float(body_hash, "width")?)
+          .with_height(self.hash_value_to_float(body_hash, "height")?);
+        Ok(Body::from(quad))
+      }
+      "cylinder" => {
+        let mut cylinder = Cylinder::new(material, transform);
+        if body_hash.contains_key(key!("minimum")) {
+          cylinder = cylinder.with_minimum(self.hash_value_to_float(body_hash, "minimum")?);
+        }
+        if body_hash.contains_key(key!("maximum")) {
+          cylinder = cylinder.with_maximum(self.hash_value_to_float(body_hash, "maximum")?);
+        }
+        cylinder = cylinder.with_closed(self.hash_value_to_bool(body_hash, "closed")?);
+        Ok(Body::from(cylinder))
+      }
+      "cone" => {
+        let mut cone = Cone::new(material, transform);
+        if body_hash.contains_key(key!("minimum")) {
+          cone = cone.with_minimum(self.hash_value_to_float(body_hash, "minimum")?);
+        }
+        if body_hash.contains_key(key!("maximum")) {
+          cone = cone.with_maximum(self.hash_value_to_float(body_hash, "maximum")?);
+        }
+        cone = cone.with_closed(self.hash_value_to_bool(body_hash, "closed")?);
+        Ok(Body::from(cone))
+      }
+      "triangle" => {
+        let [p1, p2, p3] = self.visit_triple_array(body_hash, "points", Self::visit_point)?;
+        Ok(Body::from(
+          Triangle::new(p1, p2, p3)
+            .with_material(material)
+            .with_transform(transform),
+        ))
+      }
+      "smooth_triangle" => {
+        let [p1, p2, p3] = self.visit_triple_array(body_hash, "points", Self::visit_point)?;
+        let [n1, n2, n3] = self.visit_triple_array(body_hash, "normals", Self::visit_vector)?;
+        Ok(Body::from(
+          SmoothTriangle::new(p1, p2, p3, n1, n2, n3)
+            .with_material(material)
+            .with_transform(transform),
+        ))
+      }
+      "sdf" => {
+        let shape_value = self.get_value_from_hash(body_hash, "shape")?;
+        self.path.push(Segment::Key("shape".into()));
+        let shape = self.visit_sdf(shape_value)?;
+        self.path.pop();
+        let mut sdf_body = SdfBody::new(shape)
+          .with_material(material)
+          .with_transform(transform);
+        if body_hash.contains_key(key!("blend")) {
+          let blend_value = self.get_value_from_hash(body_hash, "blend")?;
+          self.path.push(Segment::Key("blend".into()));
+          let blend = self.visit_sdf_blend(blend_value)?;
+          self.path.pop();
+          sdf_body = sdf_body.with_blend(blend.shape, blend.offset, blend.smoothness);
+        }
+        Ok(Body::from(sdf_body))
+      }
+      "repeated" => {
+        let mut repeated = self.visit_repeated(body_hash)?.with_transform(transform);
+        if body_hash.contains_key(key!("material")) {
+          repeated = repeated.with_material(material);
+        }
+        Ok(Body::from(repeated))
+      }
+      "tube" => {
+        return Ok(
+          self
+            .visit_tube(body_hash)?
+            .with_material(material)
+            .with_transform(transform)
+            .bodies(),
+        );
+      }
+      "backdrop" => {
+        let backdrop = self.visit_backdrop(body_hash)?;
+        if !body_hash.contains_key(key!("material")) {
+          material = backdrop.material();
+        }
+        Ok(
+          backdrop
+            .mesh()
+            .with_material(material)
+            .with_transform(transform)
+            .body(),
+        )
+      }
+      "text" => {
+        let text = self.hash_value_to_string(body_hash, "text")?;
+        Ok(
+          Text::new(text.as_ref())
+            .with_depth(self.hash_value_to_float(body_hash, "depth")?)
+            .mesh()
+            .with_material(material)
+            .with_transform(transform)
+            .body(),
+        )
+      }
+      "svg" => Ok(
+        self
+          .visit_svg_file(body_hash)?
+          .with_depth(self.hash_value_to_float(body_hash, "depth")?)
+          .with_bevel(self.hash_value_to_float(body_hash, "bevel")?)
+          .mesh()
+          .with_material(material)
+          .with_transform(transform)
+          .body(),
+      ),
+      "mesh" => {
+        // Meshes are instanced, so every file is only kept in memory once
+        let geometry = self.visit_mesh_file(body_hash)?;
+        let orientation = if body_hash.contains_key(key!("orientation")) {
+          let orientation_value = self.get_value_from_hash(body_hash, "orientation")?;
+          self.path.push(Segment::Key("orientation".into()));
+          let orientation = self.visit_orientation(orientation_value)?;
+          self.path.pop();
+          Some(orientation)
+        } else {
+          None
+        };
+        self
+          .mesh_orientations
+          .push((self.instances.len(), orientation));
+        self.instances.push(
+          Instance::new(geometry)
+            .with_material(material)
+            .with_transform(transform),
+        );
+        return Ok(vec![]);
+      }
+      _ => Err(anyhow!(
+        "Unknown body type '{}' found at {}",
+        body_type.as_ref(),
+        self.path.to_string()
+      )),
+    }
+    .map(|body| vec![body])
+  }
+
+  fn visit_sweep(&mut self, animate: &yaml::Yaml) -> ParserResult<Sweep> {
+    let animate_hash = &self.apply_schema(self.value_to_hash(animate)?, &schema::ANIMATE)?;
+    let body = self.hash_value_to_string(animate_hash, "body")?;
+    let property_name = self.hash_value_to_string(animate_hash, "property")?;
+    let property = match property_name.as_ref() {
+      "transform.translate.x" => SweepProperty::Translate(Axis::X),
+      "transform.translate.y" => SweepProperty::Translate(Axis::Y),
+      "transform.translate.z" => SweepProperty::Translate(Axis::Z),
+      "transform.rotate.x" => SweepProperty::Rotate(Axis::X),
+      "transform.rotate.y" => SweepProperty::Rotate(Axis::Y),
+      "transform.rotate.z" => SweepProperty::Rotate(Axis::Z),
+      "transform.scale" => SweepProperty::Scale(None),
+      "transform.scale.x" => SweepProperty::Scale(Some(Axis::X)),
+      "transform.scale.y" => SweepProperty::Scale(Some(Axis::Y)),
+      "transform.scale.z" => SweepProperty::Scale(Some(Axis::Z)),
+      "kinematics" => SweepProperty::Kinematics(self.visit_kinematics(animate_hash)?),
+      _ => {
+        return Err(anyhow!(
+          "Unknown property '{}' to animate found at {}",
+          property_name.as_ref(),
+          self.path.to_string()
+        ))
+      }
+    };
+    Ok(Sweep::new(
+      body.as_ref(),
+      property,
+      self.hash_value_to_float(animate_hash, "from")?,
+      self.hash_value_to_float(animate_hash, "to")?,
+    ))
+  }
+
+  fn visit_kinematics(&mut self, animate_hash: &yaml::Hash) -> ParserResult<Kinematics> {
+    let velocity = self.hash_value_to_vector(animate_hash, "velocity")?;
+    Ok(
+      Kinematics::new(velocity)
+        .with_gravity(self.hash_value_to_vector(animate_hash, "gravity")?)
+        .with_restitution(self.hash_value_to_float(animate_hash, "restitution")?),
+    )
+  }
+
+  fn visit_backdrop(&mut self, body_hash: &yaml::Hash) -> ParserResult<Backdrop> {
+    let segments = self.hash_value_to_count(body_hash, "segments")?;
+    Ok(
+      Backdrop::default()
+        .with_size(
+          self.hash_value_to_float(body_hash, "width")?,
+          self.hash_value_to_float(body_hash, "depth")?,
+          self.hash_value_to_float(body_hash, "height")?,
+        )
+        .with_radius(self.hash_value_to_float(body_hash, "radius")?)
+        .with_segments(segments)
+        .with_colors(
+          self.hash_value_to_color(body_hash, "floor_color")?,
+          self.hash_value_to_color(body_hash, "wall_color")?,
+        ),
+    )
+  }
+
+  fn visit_tube(&mut self, body_hash: &yaml::Hash) -> ParserResult<Tube> {
+    let points_value = self.get_value_from_hash(body_hash, "points")?;
+    self.path.push(Segment::Key("points".into()));
+    let points_array = self.value_to_array(points_value)?;
+    let mut points = vec![];
+    for (index, point_value) in points_array.iter().enumerate() {
+      self.path.push(Segment::Index(index));
+      points.push(self.visit_point(point_value)?);
+      self.path.pop();
+    }
+    self.path.pop();
+
+    let curve = self.hash_value_to_string(body_hash, "curve")?;
+    let tube = match curve.as_ref() {
+      "polyline" => Tube::new(points),
+      "bezier" => {
+        if points.len() % 3 != 1 {
+          return Err(anyhow!(
+            "Expected a start point and three more points for each Bezier curve, but found {} points at {}",
+            points.len(),
+            self.path.to_string()
+          ));
+        }
+        let tolerance = self.hash_value_to_float(body_hash, "tolerance")?;
+        Tube::bezier(&points, tolerance)
+      }
+      _ => {
+        return Err(anyhow!(
+          "Unknown curve '{}' found at {}",
+          curve.as_ref(),
+          self.path.to_string()
+        ))
+      }
+    };
+
+    Ok(tube.with_radius(self.hash_value_to_size(body_hash, "radius")?))
+  }
+
+  fn visit_sdf(&mut self, sdf: &yaml::Yaml) -> ParserResult<Sdf> {
+    let sdf_hash = &self.apply_schema(self.value_to_hash(sdf)?, &schema::SDF)?;
+    let sdf_type = self.hash_value_to_string(sdf_hash, "type")?;
+
+    match sdf_type.as_ref() {
+      "sphere" => Ok(Sdf::Sphere {
+        radius: self.hash_value_to_float(sdf_hash, "radius")?,
+      }),
+      "box" => Ok(Sdf::Box {
+        size: self.hash_value_to_vector(sdf_hash, "size")?,
+      }),
+      "torus" => Ok(Sdf::Torus {
+        major_radius: self.hash_value_to_float(sdf_hash, "major_radius")?,
+        minor_radius: self.hash_value_to_float(sdf_hash, "minor_radius")?,
+      }),
+      "mandelbulb" => {
+        let iterations = self.hash_value_to_count(sdf_hash, "iterations")?;
+        Ok(Sdf::Mandelbulb {
+          power: self.hash_value_to_float(sdf_hash, "power")?,
+          iterations,
+        })
+      }
+      _ => Err(anyhow!(
+        "Unknown signed distance function '{}' found at {}",
+        sdf_type.as_ref(),
+        self.path.to_string()
+      )),
+    }
+  }
+
+  /// Repeated body with the copied body, spacing and count of the hash,
+  /// leaving material and transform to the caller
+  fn visit_repeated(&mut self, hash: &yaml::Hash) -> ParserResult<Repeated> {
+    let body_value = self.get_value_from_hash(hash, "body")?;
+    self.path.push(Segment::Key("body".into()));
+    let body_hash = &self.apply_schema(self.value_to_hash(body_value)?, &schema::BODY)?;
+    let mut bodies = self.visit_body(body_hash)?;
+    if bodies.len() != 1 {
+      return Err(anyhow!(
+        "Repeated body needs to be a single body, but it consists of {} found at {}",
+        bodies.len(),
+        self.path.to_string()
+      ));
+    }
+    let body = bodies.remove(0);
+    self.path.pop();
+
+    let spacing_value = self.get_value_from_hash(hash, "spacing")?;
+    self.path.push(Segment::Key("spacing".into()));
+    let spacing = self.visit_vector(spacing_value)?;
+    self.path.pop();
+
+    let mut repeated = Repeated::new(body, spacing);
+    if hash.contains_key(key!("count")) {
+      let count_value = self.get_value_from_hash(hash, "count")?;
+      self.path.push(Segment::Key("count".into()));
+      let count = self.visit_vector(count_value)?;
+      self.path.pop();
+      repeated = repeated.with_count(
+        count.x.round().max(1.0) as usize,
+        count.y.round().max(1.0) as usize,
+        count.z.round().max(1.0) as usize,
+      );
+    }
+    Ok(repeated)
+  }
+
+  fn visit_sdf_blend(&mut self, blend: &yaml::Yaml) -> ParserResult<SdfBlend> {
+    let blend_hash = &self.apply_schema(self.value_to_hash(blend)?, &schema::SDF_BLEND)?;
+
+    let shape_value = self.get_value_from_hash(blend_hash, "shape")?;
+    self.path.push(Segment::Key("shape".into()));
+    let shape = self.visit_sdf(shape_value)?;
+    self.path.pop();
+
+    Ok(SdfBlend {
+      shape,
+      offset: self.hash_value_to_vector(blend_hash, "offset")?,
+      smoothness: self.hash_value_to_float(blend_hash, "smoothness")?,
+    })
+  }
+
+  /// Geometry of the OBJ file referenced by the `file` key, loaded only once
+  fn visit_mesh_file(&mut self, hash: &yaml::Hash) -> ParserResult<Arc<Geometry>> {
+    let file = self.hash_value_to_string(hash, "file")?;
+    let file_path = self.base_directory.join(file.as_ref());
+    if let Some(geometry) = self.geometries.get(&file_path) {
+      return Ok(geometry.clone());
+    }
+
+    let source = read_to_string(&file_path).with_context(|| {
+      format!(
+        "Could not read mesh file {} found at {}",
+        file_path.display(),
+        self.path
+      )
+    })?;
+    let mesh = parse_obj(&source).with_context(|| {
+      format!(
+        "Could not parse mesh file {} found at {}",
+        file_path.display(),
+        self.path
+      )
+    })?;
+
+    let geometry = Arc::new(Geometry::new(vec![mesh.body()]));
+    self.geometries.insert(file_path, geometry.clone());
+    Ok(geometry)
+  }
+
+  fn visit_svg_file(&mut self, hash: &yaml::Hash) -> ParserResult<Extrusion> {
+    let file = self.hash_value_to_string(hash, "file")?;
+    let file_path = self.base_directory.join(file.as_ref());
+    let source = read_to_string(&file_path).with_context(|| {
+      format!(
+        "Could not read SVG file {} found at {}",
+        file_path.display(),
+        self.path
+      )
+    })?;
+    parse_svg(&source).with_context(|| {
+      format!(
+        "Could not parse SVG file {} found at {}",
+        file_path.display(),
+        self.path
+      )
+    })
+  }
+
+  /// Texture of the PNG file referenced by the given key, loaded only once
+  fn visit_texture_file(&mut self, hash: &yaml::Hash, key: &str) -> ParserResult<Texture> {
+    let file = self.hash_value_to_string(hash, key)?;
+    let file_path = self.base_directory.join(file.as_ref());
+    if let Some(texture) = self.textures.get(&file_path) {
+      return Ok(texture.clone());
+    }
+
+    let data = read(&file_path).with_context(|| {
+      format!(
+        "Could not read texture file {} found at {}",
+        file_path.display(),
+        self.path
+      )
+    })?;
+    let image = Canvas::from_png(&data).with_context(|| {
+      format!(
+        "Could not decode texture file {} found at {}",
+        file_path.display(),
+        self.path
+      )
+    })?;
+
+    let texture = Texture::new(image);
+    self.textures.insert(file_path, texture.clone());
+    Ok(texture)
+  }
+
+  /// Array of exactly three points or vectors stored under the given key
+  fn visit_triple_array(
+    &mut self,
+    hash: &yaml::Hash,
+    key: &str,
+    visit: fn(&mut Self, &yaml::Yaml) -> ParserResult<Tuple>,
+  ) -> ParserResult<[Tuple; 3]> {
+    let array_value = self.get_value_from_hash(hash, key)?;
+    self.path.push(Segment::Key(key.into()));
+    let array = self.value_to_array(array_value)?;
+    if array.len() != 3 {
+      return Err(anyhow!(
+        "Expected exactly three entries at {}, but found {}",
+        self.path.to_string(),
+        array.len()
+      ));
+    }
+    let mut tuples = [Tuple::point(0.0, 0.0, 0.0); 3];
+    for (index, value) in array.iter().enumerate() {
+      self.path.push(Segment::Index(index));
+      tuples[index] = visit(self, value)?;
+      self.path.pop();
+    }
+    self.path.pop();
+    Ok(tuples)
+  }
+
+  fn visit_material(&mut self, material: &yaml::Yaml) -> ParserResult<Material> {
+    let material_hash = &self.apply_schema(self.value_to_hash(material)?, &schema::MATERIAL)?;
+    let material_type = self.hash_value_to_string(material_hash, "type")?;
+
+    match material_type.as_ref() {
+      "phong" => Ok(Material::from(self.visit_phong(material_hash)?)),
+      "lambert" => Ok(Material::from(self.visit_lambert(material_hash)?)),
+      "layered" => {
+        let base = self.visit_layer_material(material_hash, "base")?;
+        let layer = self.visit_layer_material(material_hash, "layer")?;
+        let mask = if material_hash.contains_key(key!("mask")) {
+          let mask_value = self.get_value_from_hash(material_hash, "mask")?;
+          self.path.push(Segment::Key("mask".into()));
+          let pattern = self.visit_pattern(mask_value)?;
+          self.path.pop();
+          LayerMask::Pattern(pattern)
+        } else {
+          LayerMask::Factor(self.hash_value_to_float(material_hash, "factor")?)
+        };
+        Ok(Material::from(Layered::new(base, layer, mask)))
+      }
+      _ => Err(anyhow!(
+        "Unknown material type '{}' found at {}",
+        material_type.as_ref(),
+        self.path.to_string()
+      )),
+    }
+  }
+
+  fn visit_lambert(&mut self, material_hash: &yaml::Hash) -> ParserResult<Lambert> {
+    // Shares color, pattern and texture with phong materials
+    let phong = self.visit_phong(material_hash)?;
+    let mut lambert = Lambert::default()
+      .with_color(phong.color)
+      .with_ambient(phong.ambient)
+      .with_diffuse(phong.diffuse)
+      .with_shadow_bias(phong.shadow_bias);
+    if let Some(pattern) = phong.pattern {
+      lambert = lambert.with_pattern(pattern);
+    }
+    Ok(lambert)
+  }
+
+  fn visit_phong(&mut self, material_hash: &yaml::Hash) -> ParserResult<Phong> {
+    let mut phong_material = Phong::default()
+      .with_color(self.hash_value_to_color(material_hash, "color")?)
+      .with_diffuse(self.hash_value_to_float(material_hash, "diffuse")?)
+      .with_ambient(self.hash_value_to_float(material_hash, "ambient")?)
+      .with_specular(self.hash_value_to_float(material_hash, "specular")?)
+      .with_shininess(self.hash_value_to_float(material_hash, "shininess")?)
+      .with_reflectiveness(self.hash_value_to_float(material_hash, "reflectiveness")?)
+      .with_transparency(self.hash_value_to_float(material_hash, "transparency")?)
+      .with_refractive_index(self.hash_value_to_float(material_hash, "refractive_index")?)
+      .with_shadow_bias(self.hash_value_to_float(material_hash, "shadow_bias")?);
+
+    if material_hash.contains_key(key!("pattern")) {
+      let pattern_value = self.get_value_from_hash(material_hash, "pattern")?;
+      self.path.push(Segment::Key("pattern".into()));
+      let pattern = self.visit_pattern(pattern_value)?;
+      self.path.pop();
+      phong_material = phong_material.with_pattern(pattern);
+    }
+    if material_hash.contains_key(key!("texture")) {
+      let texture = self.visit_texture_file(material_hash, "texture")?;
+      phong_material = phong_material.with_pattern(Pattern::from(texture));
+    }
+    if material_hash.contains_key(key!("coat")) {
+      let coat_value = self.get_value_from_hash(material_hash, "coat")?;
+      self.path.push(Segment::Key("coat".into()));
+      let coat = self.visit_coat(coat_value)?;
+      self.path.pop();
+      phong_material = phong_material.with_coat(coat);
+    }
+    if material_hash.contains_key(key!("bump")) {
+      let bump_value = self.get_value_from_hash(material_hash, "bump")?;
+      self.path.push(Segment::Key("bump".into()));
+      let bump = self.visit_bump(bump_value)?;
+      self.path.pop();
+      phong_material = phong_material.with_bump(bump);
+    }
+    if material_hash.contains_key(key!("cutout")) {
+      let cutout_value = self.get_value_from_hash(material_hash, "cutout")?;
+      self.path.push(Segment::Key("cutout".into()));
+      let cutout = self.visit_cutout(cutout_value)?;
+      self.path.pop();
+      phong_material = phong_material.with_cutout(cutout);
+    }
+
+    for problem in phong_material.validate() {
+      self.warnings.push(format!(
+        "Invalid material, {} found at {}",
+        problem, self.path
+      ));
+    }
+
+    Ok(phong_material)
+  }
+
+  fn visit_coat(&mut self, coat: &yaml::Yaml) -> ParserResult<Coat> {
+    let coat_hash = &self.apply_schema(self.value_to_hash(coat)?, &schema::COAT)?;
+    let reflectiveness = self.hash_value_to_float(coat_hash, "reflectiveness")?;
+    let roughness = self.hash_value_to_float(coat_hash, "roughness")?;
+    Ok(Coat::new(reflectiveness, roughness).with_tint(self.hash_value_to_color(coat_hash, "tint")?))
+  }
+
+  fn visit_bump(&mut self, bump: &yaml::Yaml) -> ParserResult<Bump> {
+    let bump_hash = &self.apply_schema(self.value_to_hash(bump)?, &schema::BUMP)?;
+    let bump_type = self.hash_value_to_string(bump_hash, "type")?;
+
+    match bump_type.as_ref() {
+      "noise" => {
+        let scale = self.hash_value_to_float(bump_hash, "scale")?;
+        let strength = self.hash_value_to_float(bump_hash, "strength")?;
+        Ok(Bump::noise(scale, strength))
+      }
+      "normal_map" => {
+        let texture = self.visit_texture_file(bump_hash, "file")?;
+        let strength = self.hash_value_to_float(bump_hash, "strength")?;
+        Ok(Bump::NormalMap { texture, strength })
+      }
+      _ => Err(anyhow!(
+        "Unknown Bump type '{}' found at {}",
+        bump_type.as_ref(),
+        self.path.to_string()
+      )),
+    }
+  }
+
+  fn visit_cutout(&mut self, cutout: &yaml::Yaml) -> ParserResult<Cutout> {
+    let cutout_hash = &self.apply_schema(self.value_to_hash(cutout)?, &schema::CUTOUT)?;
+    let mask_value = self.get_value_from_hash(cutout_hash, "mask")?;
+    self.path.push(Segment::Key("mask".into()));
+    let mask = self.visit_pattern(mask_value)?;
+    self.path.pop();
+    let threshold = self.hash_value_to_float(cutout_hash, "threshold")?;
+    Ok(Cutout::new(mask).with_threshold(threshold))
+  }
+
+  /// Base or layer of a layered material, which needs to be a phong material
+  fn visit_layer_material(&mut self, hash: &yaml::Hash, key: &str) -> ParserResult<Material> {
+    let material_value = self.get_value_from_hash(hash, key)?;
+    self.path.push(Segment::Key(key.into()));
+    let material = self.visit_material(material_value)?;
+    self.path.pop();
+    Ok(material)
+  }
+
+  /// Combined transformations stored under the 'transforms' key
+  fn hash_value_to_transform(&mut self, hash: &yaml::Hash) -> ParserResult<Matrix<4>> {
+    let transforms_value = self.get_value_from_hash(hash, "transforms")?;
+    self.path.push(Segment::Key("transform".into()));
+    let transform = self.visit_transforms(transforms_value)?;
+    self.path.pop();
+    Ok(transform)
+  }
+
+  fn visit_transforms(&mut self, transforms: &yaml::Yaml) -> ParserResult<Matrix<4>> {
+    let transforms_array = self.value_to_array(transforms)?;
+    let mut combined_transform = Matrix::identity();
+    for (index, transform) in transforms_array.iter().enumerate().rev() {
+      self.path.push(Segment::Index(index));
+      let next_transform = self.visit_transform(transform)?;
+      combined_transform = combined_transform * next_transform;
+      self.path.pop();
+    }
+    if !combined_transform.is_invertible() {
+      return Err(anyhow!(
+        "Transformations need to be invertible, but they flatten everything onto a plane, line or point at {}",
+        self.path.to_string()
+      ));
+    }
+
+    Ok(combined_transform)
+  }
+
+  fn visit_transform(&mut self, transform: &yaml::Yaml) -> ParserResult<Matrix<4>> {
+    let transform_hash = &self.apply_schema(self.value_to_hash(transform)?, &schema::TRANSFORM)?;
+    let transform_type = self.hash_value_to_string(transform_hash, "type")?;
+
+    if transform_type.as_ref() == "translate" {
+      let to_value = self.get_value_from_hash(transform_hash, "to")?;
+      self.path.push(Segment::Key("to".into()));
+      let v = self.visit_vector(to_value)?;
+      self.path.pop();
+      Ok(Matrix::translation(v.x, v.y, v.z))
+    } else if transform_type.as_ref() == "scale" {
+      let to_value = self.get_value_from_hash(transform_hash, "to")?;
+      self.path.push(Segment::Key("to".into()));
+      let v = self.visit_vector(to_value)?;
+      self.path.pop();
+      Ok(Matrix::scaling(v.x, v.y, v.z))
+    } else if transform_type.as_ref() == "rotate_x" {
+      let angle = self.visit_radians_or_degrees(transform_hash)?;
+      Ok(Matrix::rotation_x(angle))
+    } else if transform_type.as_ref() == "rotate_y" {
+      let angle = self.visit_radians_or_degrees(transform_hash)?;
+      Ok(Matrix::rotation_y(angle))
+    } else if transform_type.as_ref() == "rotate_z" {
+      let angle = self.visit_radians_or_degrees(transform_hash)?;
+      Ok(Matrix::rotation_z(angle))
+    } else {
+      Err(anyhow!(
+        "Unknown transform type '{}' found at {}",
+        transform_type.as_ref(),
+        self.path.to_string()
+      ))
+    }
+  }
+
+  fn visit_radians_or_degrees(&mut self, transform_hash: &yaml::Hash) -> ParserResult<Angle> {
+    if transform_hash.contains_key(key!("radians")) {
+      let radians = self.hash_value_to_float(transform_hash, "radians")?;
+      Ok(Angle::radians(radians))
+    } else if transform_hash.contains_key(key!("degrees")) {
+      let degrees = self.hash_value_to_float(transform_hash, "degrees")?;
+      Ok(Angle::degrees(degrees))
+    } else {
+      Err(anyhow!(
+        "Expected either 'degrees' or 'radians' key, but found nothing at {}",
+        self.path.to_string()
+      ))
+    }
+  }
+
+  /// Angle given either as plain number in radians or as hash with a
+  /// 'degrees' or 'radians' key.
+  fn visit_angle(&mut self, angle: &yaml::Yaml) -> ParserResult<Angle> {
+    match angle {
+      yaml::Yaml::Hash(ref angle_hash) => {
+        let angle_hash = &self.apply_schema(angle_hash, &schema::ANGLE)?;
+        self.visit_radians_or_degrees(angle_hash)
+      }
+      _ => Ok(Angle::radians(self.value_to_float(angle)?)),
+    }
+  }
+
+  fn visit_camera(&mut self, camera: &yaml::Yaml) -> ParserResult<(String, Camera)> {
+    let camera_hash = &self.apply_schema(self.value_to_hash(camera)?, &schema::CAMERA)?;
+    let camera_name = self.hash_value_to_string(camera_hash, "name")?;
+    let (width, height) = self.visit_camera_size(camera_hash)?;
+    let fov_value = self.get_value_from_hash(camera_hash, "field_of_view")?;
+    self.path.push(Segment::Key("field_of_view".into()));
+    let fov = self.visit_angle(fov_value)?;
+    self.path.pop();
+    let to_value = self.get_value_from_hash(camera_hash, "to")?;
+    self.path.push(Segment::Key("to".into()));
+    let to = self.visit_point(to_value)?;
+    self.path.pop();
+    let from_value = self.get_value_from_hash(camera_hash, "from")?;
+    self.path.push(Segment::Key("from".into()));
+    let from = self.visit_point(from_value)?;
+    self.path.pop();
+    let up_value = self.get_value_from_hash(camera_hash, "up")?;
+    self.path.push(Segment::Key("up".into()));
+    let up = self.visit_vector(up_value)?;
+    self.path.pop();
+
+    let samples = self.hash_value_to_count(camera_hash, "samples")?;
+    let mut camera = Camera::new(width, height, fov)
+      .look_at_from_position(from, to, up)
+      .with_samples(samples)
+      .with_exposure(self.hash_value_to_float(camera_hash, "exposure")?)
+      .with_frustum_culling(self.hash_value_to_bool(camera_hash, "frustum_culling")?)
+      .with_depth_of_field(
+        self.hash_value_to_float(camera_hash, "aperture")?,
+        self.hash_value_to_float(camera_hash, "focal_distance")?,
+      );
+
+    if camera_hash.contains_key(key!("jitter")) {
+      let jitter_value = self.get_value_from_hash(camera_hash, "jitter")?;
+      self.path.push(Segment::Key("jitter".into()));
+      let jitter = self.visit_jitter(jitter_value)?;
+      self.path.pop();
+      camera = camera.with_jitter(jitter);
+    }
+
+    if camera_hash.contains_key(key!("adaptive_sampling")) {
+      let adaptive_value = self.get_value_from_hash(camera_hash, "adaptive_sampling")?;
+      self.path.push(Segment::Key("adaptive_sampling".into()));
+      let adaptive_sampling = self.visit_adaptive_sampling(adaptive_value)?;
+      self.path.pop();
+      camera = camera.with_adaptive_sampling(adaptive_sampling);
+    }
+
+    if camera_hash.contains_key(key!("memory_ceiling")) {
+      let megabytes = self.hash_value_to_int(camera_hash, "memory_ceiling")?;
+      let bytes = usize::try_from(megabytes)
+        .ok()
+        .and_then(|megabytes| megabytes.checked_mul(1024 * 1024))
+        .ok_or_else(|| {
+          anyhow!(
+            "Memory ceiling out of range, expected a positive number of megabytes but found {} at {}.memory_ceiling",
+            megabytes,
+            self.path.to_string()
+          )
+        })?;
+      camera = camera.with_memory_ceiling(bytes);
+    }
+
+    if camera_hash.contains_key(key!("crop")) {
+      let crop_value = self.get_value_from_hash(camera_hash, "crop")?;
+      self.path.push(Segment::Key("crop".into()));
+      let crop_window = self.visit_crop_window(crop_value)?;
+      self.path.pop();
+      camera = camera.with_crop_window(crop_window);
+    }
+
+    if camera_hash.contains_key(key!("post_processing")) {
+      let effects_value = self.get_value_from_hash(camera_hash, "post_processing")?;
+      self.path.push(Segment::Key("post_processing".into()));
+      let pipeline = self.visit_effects(effects_value)?;
+      self.path.pop();
+      camera = camera.with_post_processing(pipeline);
+    }
+
+    if camera_hash.contains_key(key!("focus_on")) {
+      let body = self.hash_value_to_string(camera_hash, "focus_on")?;
+      self.focus_targets.push(FocusTarget {
+        camera: camera_name.as_ref().into(),
+        body: body.as_ref().into(),
+        path: format!("{}.focus_on", self.path),
+      });
+    }
+
+    Ok((camera_name.as_ref().into(), camera))
+  }
+
+  /// Size of the camera in pixels.
+  ///
+  /// It may either be given explicitly using 'width' and 'height', by one of
+  /// both in combination with an 'aspect_ratio' or by a 'quality' preset.
+  fn visit_camera_size(&mut self, camera_hash: &yaml::Hash) -> ParserResult<(usize, usize)> {
+    let width = if camera_hash.contains_key(key!("width")) {
+      Some(self.hash_value_to_count(camera_hash, "width")?)
+    } else {
+      None
+    };
+    let mut height = if camera_hash.contains_key(key!("height")) {
+      Some(self.hash_value_to_count(camera_hash, "height")?)
+    } else {
+      None
+    };
+    let mut aspect_ratio = if camera_hash.contains_key(key!("aspect_ratio")) {
+      let aspect_ratio_value = self.get_value_from_hash(camera_hash, "aspect_ratio")?;
+      self.path.push(Segment::Key("aspect_ratio".into()));
+      let aspect_ratio = self.visit_aspect_ratio(aspect_ratio_value)?;
+      self.path.pop();
+      Some(aspect_ratio)
+    } else {
+      None
+    };
+
+    if camera_hash.contains_key(key!("quality")) {
+      if width.is_some() || height.is_some() {
+        return Err(anyhow!(
+          "A camera 'quality' can not be combined with 'width' or 'height' at {}",
+          self.path.to_string()
+        ));
+      }
+      let quality = self.hash_value_to_string(camera_hash, "quality")?;
+      height = match quality.as_ref() {
+        "480p" => Some(480),
+        "720p" => Some(720),
+        "1080p" => Some(1080),
+        "1440p" => Some(1440),
+        "4k" | "2160p" => Some(2160),
+        "8k" | "4320p" => Some(4320),
+        _ => {
+          return Err(anyhow!(
+            "Unknown camera quality '{}' found at {}",
+            quality.as_ref(),
+            self.path.to_string()
+          ))
+        }
+      };
+      aspect_ratio = aspect_ratio.or(Some(16.0 / 9.0));
+    }
+
+    match (width, height, aspect_ratio) {
+      (Some(width), Some(height), None) => Ok((width, height)),
+      (Some(width), None, Some(aspect_ratio)) => {
+        Ok((width, (width as F / aspect_ratio).round() as usize))
+      }
+      (None, Some(height), Some(aspect_ratio)) => {
+        Ok(((height as F * aspect_ratio).round() as usize, height))
+      }
+      (Some(_), Some(_), Some(_)) => Err(anyhow!(
+        "Only two of 'width', 'height' and 'aspect_ratio' may be given at {}",
+        self.path.to_string()
+      )),
+      _ => Err(anyhow!(
+        "Expected 'width' and 'height', one of them with an 'aspect_ratio' or a 'quality' at {}",
+        self.path.to_string()
+      )),
+    }
+  }
+
+  /// Aspect ratio given as number (1.7778) or as string ("16:9")
+  fn visit_aspect_ratio(&mut self, aspect_ratio: &yaml::Yaml) -> ParserResult<F> {
+    let ratio = match aspect_ratio {
+      yaml::Yaml::String(ratio) => {
+        let parts: Vec<&str> = ratio.split(':').collect();
+        match parts.as_slice() {
+          [horizontal, vertical] => {
+            let horizontal = horizontal.trim().parse::<F>().unwrap_or(F::NAN);
+            let vertical = vertical.trim().parse::<F>().unwrap_or(F::NAN);
+            horizontal / vertical
+          }
+          _ => F::NAN,
+        }
+      }
+      _ => self.value_to_float(aspect_ratio)?,
+    };
+
+    if !ratio.is_finite() || ratio <= 0.0 {
+      return Err(anyhow!(
+        "Expected positive aspect ratio (e.g. 1.5 or \"16:9\") at {}, but found {:?}",
+        self.path.to_string(),
+        aspect_ratio
+      ));
+    }
+
+    Ok(ratio)
+  }
+
+  fn visit_adaptive_sampling(&mut self, adaptive: &yaml::Yaml) -> ParserResult<AdaptiveSampling> {
+    let adaptive_hash =
+      &self.apply_schema(self.value_to_hash(adaptive)?, &schema::ADAPTIVE_SAMPLING)?;
+    let tile_size = self.hash_value_to_count(adaptive_hash, "tile_size")?;
+    let pilot_samples = self.hash_value_to_count(adaptive_hash, "pilot_samples")?;
+    let max_samples = self.hash_value_to_count(adaptive_hash, "max_samples")?;
+    let mut adaptive_sampling = AdaptiveSampling::default()
+      .with_tile_size(tile_size)
+      .with_pilot_samples(pilot_samples)
+      .with_max_samples(max_samples)
+      .with_target_variance(self.hash_value_to_float(adaptive_hash, "target_variance")?);
+
+    if adaptive_hash.contains_key(key!("time_budget")) {
+      let seconds = self.hash_value_to_float(adaptive_hash, "time_budget")?;
+      let time_budget = Duration::try_from_secs_f64(seconds).map_err(|_| {
+        anyhow!(
+          "Time budget out of range, expected a positive number of seconds but found {} at {}.time_budget",
+          seconds,
+          self.path.to_string()
+        )
+      })?;
+      adaptive_sampling = adaptive_sampling.with_time_budget(time_budget);
+    }
+
+    Ok(adaptive_sampling)
+  }
+
+  fn visit_crop_window(&mut self, crop: &yaml::Yaml) -> ParserResult<CropWindow> {
+    let crop_hash = &self.apply_schema(self.value_to_hash(crop)?, &schema::CROP)?;
+
+    let crop_window = CropWindow::new(
+      self.hash_value_to_float(crop_hash, "left")?,
+      self.hash_value_to_float(crop_hash, "top")?,
+      self.hash_value_to_float(crop_hash, "right")?,
+      self.hash_value_to_float(crop_hash, "bottom")?,
+    );
+
+    let in_range = |value: F| (0.0..=1.0).contains(&value);
+    if !(in_range(crop_window.left)
+      && in_range(crop_window.top)
+      && in_range(crop_window.right)
+      && in_range(crop_window.bottom))
+      || crop_window.width() <= 0.0
+      || crop_window.height() <= 0.0
+    {
+      return Err(anyhow!(
+        "Crop window needs to be a non empty rectangle inside [0, 1], but found {:?} at {}",
+        crop_window,
+        self.path.to_string()
+      ));
+    }
+
+    Ok(crop_window)
+  }
+
+  fn visit_effects(&mut self, effects: &yaml::Yaml) -> ParserResult<Pipeline> {
+    let effects_array = self.value_to_array(effects)?;
+    let mut pipeline = Pipeline::new();
+    for (index, effect) in effects_array.iter().enumerate() {
+      self.path.push(Segment::Index(index));
+      pipeline = pipeline.with_effect(self.visit_effect(effect)?);
+      self.path.pop();
+    }
+
+    Ok(pipeline)
+  }
+
+  fn visit_effect(&mut self, effect: &yaml::Yaml) -> ParserResult<Effect> {
+    let effect_hash = &self.apply_schema(self.value_to_hash(effect)?, &schema::EFFECT)?;
+    let effect_type = self.hash_value_to_string(effect_hash, "type")?;
+
+    if effect_type.as_ref() == "auto_exposure" {
+      Ok(
+        AutoExposure {
+          key: self.hash_value_to_float(effect_hash, "key")?,
+          compensation: self.hash_value_to_float(effect_hash, "compensation")?,
+        }
+        .into(),
+      )
+    } else if effect_type.as_ref() == "bloom" {
+      Ok(
+        Bloom::new(
+          self.hash_value_to_float(effect_hash, "threshold")?,
+          self.hash_value_to_float(effect_hash, "sigma")?,
+          self.hash_value_to_float(effect_hash, "intensity")?,
+        )
+        .into(),
+      )
+    } else if effect_type.as_ref() == "glare" {
+      let streaks = self.hash_value_to_count(effect_hash, "streaks")?;
+      let length = self.hash_value_to_count(effect_hash, "length")?;
+      Ok(
+        Glare::new(
+          self.hash_value_to_float(effect_hash, "threshold")?,
+          streaks,
+          length,
+          self.hash_value_to_float(effect_hash, "intensity")?,
+        )
+        .into(),
+      )
+    } else if effect_type.as_ref() == "lens_distortion" {
+      let mut lens_distortion = LensDistortion::new(
+        self.hash_value_to_float(effect_hash, "k1")?,
+        self.hash_value_to_float(effect_hash, "k2")?,
+      );
+      if self.hash_value_to_bool(effect_hash, "inverse")? {
+        lens_distortion = lens_distortion.inverse();
+      }
+      Ok(lens_distortion.into())
+    } else if effect_type.as_ref() == "vignette" {
+      Ok(
+        Vignette {
+          strength: self.hash_value_to_float(effect_hash, "strength")?,
+          radius: self.hash_value_to_float(effect_hash, "radius")?,
+        }
+        .into(),
+      )
+    } else if effect_type.as_ref() == "lut" {
+      let file = self.hash_value_to_string(effect_hash, "file")?;
+      let file_path = self.base_directory.join(file.as_ref());
+      let source = read_to_string(&file_path).with_context(|| {
+        format!(
+          "Could not read LUT file {} found at {}",
+          file_path.display(),
+          self.path
+        )
+      })?;
+      let lut = parse_cube(&source).with_context(|| {
+        format!(
+          "Could not parse LUT file {} found at {}",
+          file_path.display(),
+          self.path
+        )
+      })?;
+      Ok(lut.into())
+    } else {
+      Err(anyhow!(
+        "Unknown effect type '{}' found at {}",
+        effect_type.as_ref(),
+        self.path.to_string()
+      ))
+    }
+  }
+
+  fn visit_jitter(&mut self, jitter: &yaml::Yaml) -> ParserResult<Jitter> {
+    let jitter_hash = &self.apply_schema(self.value_to_hash(jitter)?, &schema::JITTER)?;
+
+    if jitter_hash.contains_key(key!("seed")) {
+      let seed = self.hash_value_to_int(jitter_hash, "seed")?;
+      Ok(Jitter::Seeded(seed as u64))
+    } else if jitter_hash.contains_key(key!("sequence")) {
+      let sequence_value = self.get_value_from_hash(jitter_hash, "sequence")?;
+      self.path.push(Segment::Key("sequence".into()));
+      let sequence_array = self.value_to_array(sequence_value)?;
+      let mut sequence = Vec::with_capacity(sequence_array.len());
+      for (index, offset_value) in sequence_array.iter().enumerate() {
+        self.path.push(Segment::Index(index));
+        let offset = self.value_to_float(offset_value)?;
+        if !(0.0..1.0).contains(&offset) {
+          return Err(anyhow!(
+            "Jitter offsets need to be in the range [0, 1), but found {} at {}",
+            offset,
+            self.path.to_string()
+          ));
+        }
+        sequence.push(offset);
+        self.path.pop();
+      }
+      self.path.pop();
+      Ok(Jitter::Sequence(sequence))
+    } else {
+      Err(anyhow!(
+        "Expected either 'seed' or 'sequence' key, but found nothing at {}",
+        self.path.to_string()
+      ))
+    }
+  }
+}
+
+#[derive(Default)]
+pub struct Loader {
+  strict: bool,
+  base_directory: Option<PathBuf>,
+  orientation: Orientation,
+}
+
+impl Loader {
+  /// Resolve relative file references against the given directory instead of
+  /// the current working directory.
+  pub fn with_base_directory(mut self, base_directory: PathBuf) -> Self {
+    self.base_directory = Some(base_directory);
+    self
+  }
+
+  /// Axis convention of meshes, unless the world file declares their own
+  pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+    self.orientation = orientation;
+    self
+  }
+
+  /// In strict mode every warning (e.g. an unknown key) is treated as an error.
+  pub fn with_strict_mode(mut self, strict: bool) -> Self {
+    self.strict = strict;
+    self
+  }
+}
+
+impl WorldLoader for Loader {
+  fn load_world_with_warnings<T: AsRef<str>>(&self, source: T) -> LoaderWithWarningsResult {
+    let mut parser = YamlParser::new(source.as_ref()).with_orientation(self.orientation);
+    if let Some(ref base_directory) = self.base_directory {
+      parser = parser.with_base_directory(base_directory.clone());
+    }
+    let (world, cameras) = parser.parse_yaml()?;
+    if self.strict && !parser.warnings.is_empty() {
+      return Err(anyhow!(
+        "Strict mode does not allow warnings:\n{}",
+        parser.warnings.join("\n")
+      ));
+    }
+    Ok((world, cameras, parser.warnings))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rtc_core::body::Intersectable;
+  use rtc_core::body::{Body, Shadows};
+  use rtc_core::camera::Camera;
+  use rtc_core::canvas::Color;
+  use rtc_core::light::{Attenuation, Light, PointLight};
+  use rtc_core::material::Material;
+  use rtc_core::material::Phong;
+  use rtc_core::matrix::Matrix;
+  use rtc_core::ray::Ray;
+  use rtc_core::sphere::Sphere;
+  use rtc_core::tuple::Tuple;
+  use std::f64::consts::PI;
+
+  use super::*;
+  use rtc_core::assert_fuzzy_eq;
+  use rtc_core::fuzzy_eq::*;
+
+  #[test]
+  #[allow(clippy::approx_constant)]
+  fn load_simple_yaml_world() {
+    let source = r##"
+---
+- camera:
+    name: output1
+    width: 800
+    height: 600
+    field_of_view: 0.785 # 45degrees
+    from: [1, 2, 3.4]
+    to: [5.6, 7, 8]
+    up: [9.10, 11, -1.2]
+
+- light:
+    type: point_light
+    at: [1.1, 2.2, 3.3]
+    intensity: [0.4, 0.5, 0.6]
+
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [1,1,1]
+      diffuse: 0.7
+      ambient: 0.1
+      specular: 0.0
+      shininess: 200
+    transforms:
+      - type: translate
+        to: [1, 2, 3]
+      - type: rotate_x
+        radians: 3.14
+"##;
+
+    let expected_world = World::new(
+      vec![Body::from(Sphere::new(
+        Material::from(
+          Phong::default()
+            .with_color(Color::new(1.0, 1.0, 1.0))
+            .with_ambient(0.1)
+            .with_diffuse(0.7)
+            .with_specular(0.0)
+            .with_shininess(200.0),
+        ),
+        Matrix::rotation_x(Angle::radians(3.14)) * Matrix::translation(1.0, 2.0, 3.0),
+      ))],
+      vec![PointLight::new(Tuple::point(1.1, 2.2, 3.3), Color::new(0.4, 0.5, 0.6)).into()],
+    );
+
+    let mut expected_cameras = HashMap::new();
+    expected_cameras.insert(
+      String::from("output1"),
+      Camera::new(800, 600, Angle::radians(0.785)).look_at_from_position(
+        Tuple::point(1.0, 2.0, 3.4),
+        Tuple::point(5.6, 7.0, 8.0),
+        Tuple::vector(9.10, 11.0, -1.2),
+      ),
+    );
+
+    let yaml_loader = Loader::default();
+
+    let (loaded_world, loaded_cameras) = yaml_loader.load_world(source).unwrap();
+    assert_fuzzy_eq!(loaded_world, expected_world);
+    assert_fuzzy_eq!(loaded_cameras, expected_cameras);
+  }
+
+  #[test]
+  #[allow(clippy::approx_constant)]
+  fn load_multiple_cameras() {
+    let source = r##"
+---
+- camera:
+    name: output1
+    width: 800
+    height: 600
+    field_of_view: 0.785 # 45degrees
+    from: [1, 2, 3.4]
+    to: [5.6, 7, 8]
+    up: [9.10, 11, -1.2]
+
+- camera:
+    name: output2
+    width: 1920
+    height: 1080
+    field_of_view: 1.047 # PI/3
+    from: [1, 0.5, -5]
+    to: [0, 1, 0]
+    up: [0, 1, 0]
+
+- light:
+    type: point_light
+    at: [1.1, 2.2, 3.3]
+    intensity: [0.4, 0.5, 0.6]
+
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [1,1,1]
+      diffuse: 0.7
+      ambient: 0.1
+      specular: 0.0
+      shininess: 200
+    transforms:
+      - type: translate
+        to: [1, 2, 3]
+      - type: rotate_x
+        radians: 3.14
+"##;
+
+    let expected_world = World::new(
+      vec![Body::from(Sphere::new(
+        Material::from(
+          Phong::default()
+            .with_color(Color::new(1.0, 1.0, 1.0))
+            .with_ambient(0.1)
+            .with_diffuse(0.7)
+            .with_specular(0.0)
+            .with_shininess(200.0),
+        ),
+        Matrix::rotation_x(Angle::radians(3.14)) * Matrix::translation(1.0, 2.0, 3.0),
+      ))],
+      vec![PointLight::new(Tuple::point(1.1, 2.2, 3.3), Color::new(0.4, 0.5, 0.6)).into()],
+    );
+
+    let mut expected_cameras = HashMap::new();
+    expected_cameras.insert(
+      String::from("output1"),
+      Camera::new(800, 600, Angle::radians(0.785)).look_at_from_position(
+        Tuple::point(1.0, 2.0, 3.4),
+        Tuple::point(5.6, 7.0, 8.0),
+        Tuple::vector(9.10, 11.0, -1.2),
+      ),
+    );
+    expected_cameras.insert(
+      String::from("output2"),
+      Camera::new(1920, 1080, Angle::radians(1.047)).look_at_from_position(
+        Tuple::point(1.0, 0.5, -5.0),
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::vector(0.0, 1.0, 0.0),
+      ),
+    );
+
+    let yaml_loader = Loader::default();
+
+    let (loaded_world, loaded_cameras) = yaml_loader.load_world(source).unwrap();
+    assert_fuzzy_eq!(loaded_world, expected_world);
+    assert_fuzzy_eq!(loaded_cameras, expected_cameras);
+  }
+
+  #[test]
+  fn complex_scene_multiple_cameras_multiple_bodies() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    at: [-10, 10, -10]
+    intensity: [1, 1, 1]
+
+# Floor
+- body:
+    type: plane
+    material:
+      type: phong
+      color: [0.5, 0.45, 0.45]
+      specular: 0.0
+
+# Left Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0.635, 0, 1]
+    transforms:
+      - type: scale
+        to: [0.33, 0.33, 0.33]
+      - type: translate
+        to: [-1.5, 0.33, -0.75]
+
+# Middle Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [1, 0, 0.635]
+      diffuse: 0.9
+      specular: 1.8
+    transforms:
+      - type: translate
+        to: [-0.5, 1.0, 0.5]
+
+# Right Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0, 0.635, 1]
+    transforms:
+      - type: scale
+        to: [0.5, 0.5, 0.5]
+      - type: translate
+        to: [1.5, 0.5, -0.5]
+
+# Camera
+- camera:
+    name: main_camera
+    width: 3840
+    height: 2160
+    field_of_view: 1.047 # PI/3
+    from: [0, 1.5, -5]
+    to: [0, 1, 0]
+    up: [0, 1, 0]
+
+# Camera further moved down
+- camera:
+    name: second_camera
+    width: 1920
+    height: 1080
+    field_of_view: 1.047 # PI/3
+    from: [1, 0.5, -5]
+    to: [0, 1, 0]
+    up: [0, 1, 0]
+
+"##;
+
+    let expected_world = World::new(
+      vec![
+        // # Floor
+        // - body:
+        //     type: plane
+        //     material:
+        //       type: phong
+        //       color: [0.5, 0.45, 0.45]
+        //       specular: 0.0
+        Body::from(
+          Plane::default().with_material(Material::from(
+            Phong::default()
+              .with_color(Color::new(0.5, 0.45, 0.45))
+              .with_specular(0.0),
+          )),
+        ),
+        // # Left Sphere
+        // - body:
+        //     type: sphere
+        //     material:
+        //       type: phong
+        //       color: [0.635, 0, 1]
+        //     transforms:
+        //       - type: scale
+        //         to: [0.33, 0.33, 0.33]
+        //       - type: translate
+        //         to: [-1.5, 0.33, -0.75]
+        Body::from(
+          Sphere::default()
+            .with_material(Material::from(
+              Phong::default().with_color(Color::new(0.635, 0.0, 1.0)),
+            ))
+            .with_transform(
+              Matrix::translation(-1.5, 0.33, -0.75) * Matrix::scaling(0.33, 0.33, 0.33),
+            ),
+        ),
+        // # Middle Sphere
+        // - body:
+        //     type: sphere
+        //     material:
+        //       type: phong
+        //       color: [1, 0, 0.635]
+        //       diffuse: 0.9
+        //       specular: 1.8
+        //     transforms:
+        //       - type: translate
+        //         to: [-0.5, 1.0, 0.5]
+        Body::from(
+          Sphere::default()
+            .with_material(Material::from(
+              Phong::default()
+                .with_color(Color::new(1.0, 0.0, 0.635))
+                .with_diffuse(0.9)
+                .with_specular(1.8),
+            ))
+            .with_transform(Matrix::translation(-0.5, 1.0, 0.5)),
+        ),
+        // # Right Sphere
+        // - body:
+        //     type: sphere
+        //     material:
+        //       type: phong
+        //       color: [0, 0.635, 1]
+        //     transforms:
+        //       - type: scale
+        //         to: [0.5, 0.5, 0.5]
+        //       - type: translate
+        //         to: [1.5, 0.5, -0.5]
+        Body::from(
+          Sphere::default()
+            .with_material(Material::from(
+              Phong::default().with_color(Color::new(0.0, 0.635, 1.0)),
+            ))
+            .with_transform(Matrix::translation(1.5, 0.5, -0.5) * Matrix::scaling(0.5, 0.5, 0.5)),
+        ),
+      ],
+      vec![
+        // - light:
+        //     type: point_light
+        //     at: [-10, 10, -10]
+        //     intensity: [1, 1, 1]
+        PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0)).into(),
+      ],
+    );
+
+    let mut expected_cameras = HashMap::new();
+    // # Camera
+    // - camera:
+    //     name: main_camera
+    //     width: 3840
+    //     height: 2160
+    //     field_of_view: 1.047 # PI/3
+    //     from: [0, 1.5, -5]
+    //     to: [0, 1, 0]
+    //     up: [0, 1, 0]
+    expected_cameras.insert(
+      String::from("main_camera"),
+      Camera::new(3840, 2160, Angle::radians(1.047)).look_at_from_position(
+        Tuple::point(0.0, 1.5, -5.0),
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::vector(0.0, 1.0, 0.0),
+      ),
+    );
+
+    // # Camera further moved down
+    // - camera:
+    //     name: second_camera
+    //     width: 1920
+    //     height: 1080
+    //     field_of_view: 1.047 # PI/3
+    //     from: [1, 0.5, -5]
+    //     to: [0, 1, 0]
+    //     up: [0, 1, 0]
+    expected_cameras.insert(
+      String::from("second_camera"),
+      Camera::new(1920, 1080, Angle::radians(1.047)).look_at_from_position(
+        Tuple::point(1.0, 0.5, -5.0),
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::vector(0.0, 1.0, 0.0),
+      ),
+    );
+
+    let yaml_loader = Loader::default();
+
+    let (loaded_world, loaded_cameras) = yaml_loader.load_world(source).unwrap();
+    assert_fuzzy_eq!(loaded_world, expected_world);
+    assert_fuzzy_eq!(loaded_cameras, expected_cameras);
+  }
+
+  #[test]
+  fn unknown_base_item() {
+    let source = r##"
+---
+- something_unknown:
+    great: isnt it?
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!("Unknown item type found at .document[0].item[0]");
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn unknown_light_type() {
+    let source = r##"
+---
+- light:
+    type: bright_but_unknown_light
+    at: [-10, 10, -10]
+    intensity: [1, 1, 1]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected =
+      anyhow!("Unknown light type 'bright_but_unknown_light' found at .document[0].item[0].light");
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn missing_light_at_property() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    intensity: [1, 1, 1]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Tried to get value with key 'at' from hash at .document[0].item[0].light: Key not found."
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn light_type_is_not_a_string() {
+    let source = r##"
+---
+- light:
+    type: 423.0
+    at: [-10, 10, -10]
+    intensity: [1, 1, 1]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Expected string value at .document[0].item[0].light.type, but found Real(\"423.0\")"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn light_at_is_not_point_array() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    at: [-10, some_strange_string, -10]
+    intensity: [1, 1, 1]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!("Expected float value at .document[0].item[0].light.at[1], but found String(\"some_strange_string\")");
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn light_intensity_is_not_color_array() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    at: [-10, 10, -10]
+    intensity: [1, 1, blub]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Expected float value at .document[0].item[0].light.intensity[2], but found String(\"blub\")"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn light_intensity_is_scaled_by_ev() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    at: [0, 10, 0]
+    intensity: [1, 0.5, 0.25]
+    ev: -1
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+
+    assert_fuzzy_eq!(
+      world.lights()[0].intensity_at(Tuple::point(0.0, 0.0, 0.0)),
+      Color::new(0.5, 0.25, 0.125)
+    );
+  }
+
+  #[test]
+  fn light_attenuation_is_parsed() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    at: [0, 10, 0]
+    intensity: [1, 1, 1]
+    attenuation:
+      quadratic: 0.01
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+
+    assert_fuzzy_eq!(
+      world.lights()[0],
+      Light::from(
+        PointLight::new(Tuple::point(0.0, 10.0, 0.0), Color::white())
+          .with_attenuation(Attenuation::new(1.0, 0.0, 0.01))
+      )
+    );
+  }
+
+  #[test]
+  fn light_attenuation_must_not_be_negative() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    at: [0, 10, 0]
+    intensity: [1, 1, 1]
+    attenuation:
+      linear: -0.5
+"##;
+
+    let actual = Loader::default().load_world(source).unwrap_err();
+    assert_eq!(
+      actual.to_string(),
+      "Attenuation coefficients need to be non negative, but found -0.5 at .document[0].item[0].light.attenuation.linear"
+    );
+  }
+
+  #[test]
+  fn unknown_body_type() {
+    let source = r##"
+---
+# Right Sphere
+- body:
+    type: unknown_something
+    material:
+      type: phong
+      color: [0, 0.635, 1]
+    transforms:
+      - type: scale
+        to: [0.5, 0.5, 0.5]
+      - type: translate
+        to: [1.5, 0.5, -0.5]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected =
+      anyhow!("Unknown body type 'unknown_something' found at .document[0].item[0].body");
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn unknown_material_type() {
+    let source = r##"
+---
+# Right Sphere
+- body:
+    type: sphere
+    material:
+      type: hyper_realistic_everything
+      color: [0, 0.635, 1]
+    transforms:
+      - type: scale
+        to: [0.5, 0.5, 0.5]
+      - type: translate
+        to: [1.5, 0.5, -0.5]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Unknown material type 'hyper_realistic_everything' found at .document[0].item[0].body.material"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn faulty_material_color() {
+    let source = r##"
+---
+# Right Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0, sparkly_red, 1]
+    transforms:
+      - type: scale
+        to: [0.5, 0.5, 0.5]
+      - type: translate
+        to: [1.5, 0.5, -0.5]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Expected float value at .document[0].item[0].body.material.color[1], but found String(\"sparkly_red\")"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn faulty_material_shininess() {
+    let source = r##"
+---
+# Right Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0, 0.5, 1]
+      shininess: really_shiny
+    transforms:
+      - type: scale
+        to: [0.5, 0.5, 0.5]
+      - type: translate
+        to: [1.5, 0.5, -0.5]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Expected float value at .document[0].item[0].body.material.shininess, but found String(\"really_shiny\")"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn unknown_transform_type() {
+    let source = r##"
+---
+# Right Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0, 0.5, 1]
+    transforms:
+      - type: woop_di_duuu
+        to: [0.5, 0.5, 0.5]
+      - type: translate
+        to: [1.5, 0.5, -0.5]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Unknown transform type 'woop_di_duuu' found at .document[0].item[0].body.transform[0]"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn transforms_flattening_a_body_are_an_error() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    transforms:
+      - type: scale
+        to: [0, 1, 1]
+"##;
+
+    let actual = Loader::default().load_world(source).unwrap_err();
+    assert_eq!(
+      actual.to_string(),
+      "Transformations need to be invertible, but they flatten everything onto a plane, line or point at .document[0].item[0].body.transform"
+    );
+  }
+
+  #[test]
+  fn wrong_to_in_scale_transform() {
+    let source = r##"
+---
+# Right Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0, 0.5, 1]
+    transforms:
+      - type: scale
+        to: foobar
+      - type: translate
+        to: [1.5, 0.5, -0.5]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Expected array at .document[0].item[0].body.transform[0].to, but found String(\"foobar\")"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn wrong_to_in_translate_transform() {
+    let source = r##"
+---
+# Right Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0, 0.5, 1]
+    transforms:
+      - type: scale
+        to: [1.5, 0.5, -0.5]
+      - type: translate
+        to: [1.5, 0.5, nope]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Expected float value at .document[0].item[0].body.transform[1].to[2], but found String(\"nope\")"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn wrong_radians_in_rotate_y_transform() {
+    let source = r##"
+---
+# Right Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0, 0.5, 1]
+    transforms:
+      - type: scale
+        to: [1.5, 0.5, -0.5]
+      - type: translate
+        to: [1.5, 0.5, 12]
+      - type: rotate_y
+        radians: 90deg
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Expected float value at .document[0].item[0].body.transform[2].radians, but found String(\"90deg\")"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn specify_body_rotation_transformation_in_degrees() {
+    let source = r##"
+---
+# Right Sphere
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0, 0.5, 1]
+    transforms:
+      - type: rotate_x
+        degrees: 180
+      - type: rotate_y
+        degrees: 90
+      - type: rotate_z
+        degrees: 423
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_ok());
+    let (world, _camera_hash) = result.unwrap();
+
+    assert_eq!(1, world.bodies().len());
+    let body = &world.bodies()[0];
+
+    let expected_transform = Matrix::rotation_z(Angle::radians(423.0 / 180.0 * PI))
+      * Matrix::rotation_y(Angle::radians(90.0 / 180.0 * PI))
+      * Matrix::rotation_x(Angle::radians(PI));
+
+    assert_eq!(expected_transform, body.transform());
+  }
+
+  #[test]
+  fn striped_pattern_in_body_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: striped
+        colorA: [0,0,0]
+        colorB: [1,1,1]
+        transforms:
+          - type: scale
+            to: [.2,.2,.2]
+          - type: rotate_z
+            degrees: 45
+"##;
+
+    let color_a = Color::new(0.0, 0.0, 0.0);
+    let color_b = Color::new(1.0, 1.0, 1.0);
+    let pattern_transform =
+      Matrix::rotation_z(Angle::radians((45.0 / 180.0) * PI)) * Matrix::scaling(0.2, 0.2, 0.2);
+    let pattern = Pattern::from(
+      Striped::default()
+        .with_colors(color_a, color_b)
+        .with_transform(pattern_transform),
+    );
+
+    let material = Material::from(Phong::default().with_pattern(pattern));
+    let body_transform = Matrix::identity();
+    let sphere = Sphere::default()
+      .with_material(material)
+      .with_transform(body_transform);
+    let body = Body::from(sphere);
+
+    let yaml_loader = Loader::default();
+
+    let (loaded_world, _) = yaml_loader.load_world(source).unwrap();
+    assert_eq!(1, loaded_world.bodies().len());
+    assert_fuzzy_eq!(body, loaded_world.bodies()[0].clone());
+  }
+
+  #[test]
+  fn noise_pattern_in_body_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: noise
+        colorA: [0.1,0.2,0.3]
+        colorB: [0.4,0.5,0.6]
+        octaves: 6
+        persistence: 0.7
+        seed: 3
+        transforms:
+          - type: scale
+            to: [.2,.2,.2]
+"##;
+
+    let pattern = Pattern::from(
+      Noise::default()
+        .with_colors(Color::new(0.1, 0.2, 0.3), Color::new(0.4, 0.5, 0.6))
+        .with_octaves(6)
+        .with_persistence(0.7)
+        .with_seed(3)
+        .with_transform(Matrix::scaling(0.2, 0.2, 0.2)),
+    );
+    let material = Material::from(Phong::default().with_pattern(pattern));
+    let body = Body::from(Sphere::default().with_material(material));
+
+    let yaml_loader = Loader::default();
+
+    let (loaded_world, _) = yaml_loader.load_world(source).unwrap();
+    assert_fuzzy_eq!(body, loaded_world.bodies()[0].clone());
+  }
+
+  #[test]
+  fn stripe_direction_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: striped
+        colorA: [0,0,0]
+        colorB: [1,1,1]
+        direction: [0, 1, 1]
+"##;
+
+    let pattern = Pattern::from(
+      Striped::default()
+        .with_colors(Color::black(), Color::white())
+        .with_direction(Tuple::vector(0.0, 1.0, 1.0)),
+    );
+    let material = Material::from(Phong::default().with_pattern(pattern));
+    let body = Body::from(Sphere::default().with_material(material));
+
+    let yaml_loader = Loader::default();
+
+    let (loaded_world, _) = yaml_loader.load_world(source).unwrap();
+    assert_fuzzy_eq!(body, loaded_world.bodies()[0].clone());
+    assert!(yaml_loader
+      .load_world(source.replace("[0, 1, 1]", "[0, 0, 0]"))
+      .is_err());
+  }
+
+  #[test]
+  fn gradient_easing_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: gradient
+        colorA: [0,0,0]
+        colorB: [1,1,1]
+        easing: mirrored
+"##;
+
+    let pattern = Pattern::from(
+      Gradient::default()
+        .with_colors(Color::black(), Color::white())
+        .with_easing(Easing::Mirrored),
+    );
+    let material = Material::from(Phong::default().with_pattern(pattern));
+    let body = Body::from(Sphere::default().with_material(material));
+
+    let yaml_loader = Loader::default();
+
+    let (loaded_world, _) = yaml_loader.load_world(source).unwrap();
+    assert_fuzzy_eq!(body, loaded_world.bodies()[0].clone());
+    assert!(yaml_loader
+      .load_world(source.replace("mirrored", "bouncy"))
+      .is_err());
+  }
+
+  #[test]
+  fn test_pattern_in_body_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: test
+        transforms:
+          - type: translate
+            to: [1,0,0]
+"##;
+
+    let pattern =
+      Pattern::from(TestPattern::default().with_transform(Matrix::translation(1.0, 0.0, 0.0)));
+    let material = Material::from(Phong::default().with_pattern(pattern));
+    let body = Body::from(Sphere::default().with_material(material));
+
+    let yaml_loader = Loader::default();
+
+    let (loaded_world, _) = yaml_loader.load_world(source).unwrap();
+    assert_fuzzy_eq!(body, loaded_world.bodies()[0].clone());
+  }
+
+  #[test]
+  fn nested_patterns_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: checkerboard
+        colorA:
+          type: striped
+          colorA: [1, 0, 0]
+          colorB: [0, 0, 1]
+        colorB: [1, 1, 1]
+"##;
+
+    let stripes =
+      Striped::default().with_colors(Color::new(1.0, 0.0, 0.0), Color::new(0.0, 0.0, 1.0));
+    let pattern =
+      Pattern::from(CheckerBoard::default().with_colors(Pattern::from(stripes), Color::white()));
+    let material = Material::from(Phong::default().with_pattern(pattern));
+
+    let (loaded_world, _) = Loader::default().load_world(source).unwrap();
+    assert_fuzzy_eq!(loaded_world.bodies()[0].material(), material);
+  }
+
+  #[test]
+  fn colorful_striped_pattern_in_body_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: striped
+        colorA: [0.1,0.2,0.3]
+        colorB: [0.4,0.5,0.6]
+        transforms:
+          - type: scale
+            to: [.2,.2,.2]
+          - type: rotate_z
+            degrees: 90
+"##;
+
+    let color_a = Color::new(0.1, 0.2, 0.3);
+    let color_b = Color::new(0.4, 0.5, 0.6);
+    let pattern_transform =
+      Matrix::rotation_z(Angle::radians((90.0 / 180.0) * PI)) * Matrix::scaling(0.2, 0.2, 0.2);
+    let pattern = Pattern::from(
+      Striped::default()
+        .with_colors(color_a, color_b)
+        .with_transform(pattern_transform),
+    );
+
+    let material = Material::from(Phong::default().with_pattern(pattern));
+    let body_transform = Matrix::identity();
+    let sphere = Sphere::default()
+      .with_material(material)
+      .with_transform(body_transform);
+    let body = Body::from(sphere);
+
+    let yaml_loader = Loader::default();
+
+    let (loaded_world, _) = yaml_loader.load_world(source).unwrap();
+    assert_eq!(1, loaded_world.bodies().len());
+    assert_fuzzy_eq!(body, loaded_world.bodies()[0].clone());
+  }
+
+  #[test]
+  fn camera_supersampling_with_seeded_jitter_is_parsed() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 100
+    height: 50
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    samples: 4
+    jitter:
+      seed: 1234
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+    let camera = &cameras["main"];
+
+    assert_eq!(camera.samples, 4);
+    assert_eq!(camera.jitter, Jitter::Seeded(1234));
+  }
+
+  #[test]
+  fn camera_jitter_sequence_is_parsed() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 100
+    height: 50
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    samples: 2
+    jitter:
+      sequence: [0.7, 0.3, 0.9, 0.1, 0.5]
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+
+    assert_fuzzy_eq!(
+      cameras["main"].jitter,
+      Jitter::Sequence(vec![0.7, 0.3, 0.9, 0.1, 0.5])
+    );
+  }
+
+  #[test]
+  fn camera_jitter_sequence_out_of_range() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 100
+    height: 50
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    jitter:
+      sequence: [0.5, 1.5]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Jitter offsets need to be in the range [0, 1), but found 1.5 at .document[0].item[0].camera.jitter.sequence[1]"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn unknown_keys_are_reported_as_warnings() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      colour: [1, 0, 0]
+      ambiant: 0.5
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _, warnings) = yaml_loader.load_world_with_warnings(source).unwrap();
+
+    assert_eq!(1, world.bodies().len());
+    assert_eq!(
+      warnings,
+      vec![
+        "Unknown key 'colour' in material found at .document[0].item[0].body.material. Did you mean 'color'?",
+        "Unknown key 'ambiant' in material found at .document[0].item[0].body.material. Did you mean 'ambient'?",
+      ]
+    );
+  }
+
+  #[test]
+  fn known_keys_do_not_produce_warnings() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    at: [-10, 10, -10]
+    intensity: [1, 1, 1]
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: checkerboard
+        3d: false
+        colorA: [0, 0, 0]
+        colorB: [1, 1, 1]
+    transforms:
+      - type: rotate_x
+        degrees: 90
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, _, warnings) = yaml_loader.load_world_with_warnings(source).unwrap();
+
+    assert!(warnings.is_empty());
+  }
+
+  #[test]
+  fn unknown_keys_without_similar_key_have_no_suggestion() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    at: [-10, 10, -10]
+    intensity: [1, 1, 1]
+    flicker: true
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, _, warnings) = yaml_loader.load_world_with_warnings(source).unwrap();
+
+    assert_eq!(
+      warnings,
+      vec!["Unknown key 'flicker' in light found at .document[0].item[0].light."]
+    );
+  }
+
+  #[test]
+  fn unknown_keys_are_errors_in_strict_mode() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      colour: [1, 0, 0]
+"##;
+
+    let yaml_loader = Loader::default().with_strict_mode(true);
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Strict mode does not allow warnings:\nUnknown key 'colour' in material found at .document[0].item[0].body.material. Did you mean 'color'?"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn layered_materials_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: layered
+      base:
+        type: phong
+        reflectiveness: 0.8
+      layer:
+        type: phong
+        color: [0.5, 0.2, 0.1]
+      mask:
+        type: striped
+        colorA: [0, 0, 0]
+        colorB: [1, 1, 1]
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let base = Phong::default().with_reflectiveness(0.8);
+    let layer = Phong::default().with_color(Color::new(0.5, 0.2, 0.1));
+    let mask = Pattern::from(Striped::default().with_colors(Color::black(), Color::white()));
+
+    assert_fuzzy_eq!(
+      world.bodies()[0].material(),
+      Material::from(Layered::new(
+        Material::from(base),
+        Material::from(layer),
+        LayerMask::Pattern(mask)
+      ))
+    );
+  }
+
+  #[test]
+  fn backdrops_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: backdrop
+    width: 10
+    segments: 4
+    floor_color: [1, 1, 1]
+    wall_color: [0, 0, 0.5]
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let backdrop = Backdrop::default()
+      .with_size(10.0, 2.0, 2.0)
+      .with_segments(4)
+      .with_colors(Color::white(), Color::new(0.0, 0.0, 0.5));
+
+    assert_eq!(world.bodies().len(), 1);
+    assert_fuzzy_eq!(world.bodies()[0].material(), backdrop.material());
+  }
+
+  #[test]
+  fn tubes_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: tube
+    radius: 0.05
+    points: [[0, 0, 0], [1, 0, 0], [1, 1, 0]]
+- body:
+    type: tube
+    curve: bezier
+    tolerance: 0.5
+    points: [[0, 0, 0], [0, 1, 0], [1, 1, 0], [1, 0, 0]]
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let polyline = Tube::new(vec![
+      Tuple::point(0.0, 0.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::point(1.0, 1.0, 0.0),
+    ])
+    .with_radius(0.05);
+    let bezier = Tube::bezier(
+      &[
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::point(1.0, 1.0, 0.0),
+        Tuple::point(1.0, 0.0, 0.0),
+      ],
+      0.5,
+    );
+
+    let polyline_bodies = polyline.bodies();
+    assert_eq!(
+      world.bodies().len(),
+      polyline_bodies.len() + bezier.bodies().len()
+    );
+    assert_fuzzy_eq!(world.bodies()[4], polyline_bodies[4].clone());
+  }
+
+  #[test]
+  fn text_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: text
+    text: "Hi"
+    depth: 0.5
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let text = Text::new("Hi").with_depth(0.5).mesh().body();
+
+    assert_eq!(world.bodies().len(), 1);
+    assert_fuzzy_eq!(world.bodies()[0], text);
+  }
+
+  #[test]
+  fn bezier_tubes_need_three_points_per_curve() {
+    let source = r##"
+---
+- body:
+    type: tube
+    curve: bezier
+    points: [[0, 0, 0], [0, 1, 0], [1, 1, 0]]
+"##;
+
+    let error = Loader::default().load_world(source).unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Expected a start point and three more points for each Bezier curve, but found 3 points at .document[0].item[0].body"
+    );
+  }
+
+  #[test]
+  fn tubes_need_a_positive_radius() {
+    let source = r##"
+---
+- body:
+    type: tube
+    radius: 0
+    points: [[0, 0, 0], [0, 1, 0]]
+"##;
+
+    let error = Loader::default().load_world(source).unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "Expected a positive size, which is not too small to be rendered, but found 0 at .document[0].item[0].body.radius"
+    );
+  }
+
+  #[test]
+  fn clear_coats_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      color: [0.6, 0, 0]
+      coat:
+        reflectiveness: 0.08
+        tint: [1, 0.9, 0.8]
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let coat = Coat::new(0.08, 0.05).with_tint(Color::new(1.0, 0.9, 0.8));
+    let expected = Phong::default()
+      .with_color(Color::new(0.6, 0.0, 0.0))
+      .with_coat(coat);
+
+    assert_fuzzy_eq!(world.bodies()[0].material(), Material::from(expected));
+  }
+
+  #[test]
+  fn bodies_reference_registered_materials_by_name() {
+    let source = r##"
+---
+- material:
+    name: paint
+    type: phong
+    color: [1, 0, 0]
+- body:
+    type: sphere
+    material: paint
+- body:
+    type: plane
+"##;
+    let unknown = format!("{}- body:\n    type: sphere\n    material: rust\n", source);
+
+    let result = Loader::default().load_world(source);
+    let missing = Loader::default().load_world(unknown);
+
+    let (mut world, _) = result.unwrap();
+    let paint = Material::from(Phong::default().with_color(Color::new(1.0, 0.0, 0.0)));
+    assert_eq!(world.materials.get("paint"), Some(paint.clone()));
+    assert_eq!(world.bodies()[0].material(), paint);
+    assert_eq!(world.material_name_of(0), Some("paint"));
+
+    let blue = Material::from(Phong::default().with_color(Color::new(0.0, 0.0, 1.0)));
+    world.set_material("paint", blue.clone());
+    assert_eq!(world.bodies()[0].material(), blue);
+    assert_eq!(world.bodies()[1].material(), Material::default());
+
+    assert_eq!(
+      missing.unwrap_err().to_string(),
+      "Unknown material 'rust' found at .document[0].item[3].body.material"
+    );
+  }
+
+  #[test]
+  fn bodies_and_lights_are_assigned_to_layers() {
+    let source = r##"
+---
+- light:
+    type: point_light
+    at: [0, 5, 0]
+    intensity: [1, 1, 1]
+    layers: characters
+- body:
+    type: sphere
+    layers: [furniture, characters]
+- body:
+    type: plane
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+
+    assert_eq!(world.layer_names(), vec!["characters", "furniture"]);
+    assert_eq!(
+      world.only_layers(&["furniture"]).bodies(),
+      vec![world.bodies()[0].clone()]
+    );
+    assert_eq!(world.only_layers(&["furniture"]).lights().len(), 0);
+    assert_eq!(world.only_layers(&["characters"]).lights().len(), 1);
+    assert_eq!(
+      world.only_layers(&["default"]).bodies(),
+      vec![world.bodies()[1].clone()]
+    );
+  }
+
+  #[test]
+  fn lambert_materials_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: lambert
+      color: [0.2, 0.4, 0.6]
+      diffuse: 0.7
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let expected = Lambert::default()
+      .with_color(Color::new(0.2, 0.4, 0.6))
+      .with_diffuse(0.7);
+
+    assert_fuzzy_eq!(world.bodies()[0].material(), Material::from(expected));
+  }
+
+  #[test]
+  fn shadow_biases_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: lambert
+      shadow_bias: 0.001
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let expected = Lambert::default().with_shadow_bias(0.001);
+
+    assert_fuzzy_eq!(world.bodies()[0].material(), Material::from(expected));
+    assert_fuzzy_eq!(world.bodies()[0].material().shadow_bias(), 0.001);
+  }
+
+  #[test]
+  fn bumps_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      bump:
+        type: noise
+        scale: 0.2
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let expected = Phong::default().with_bump(Bump::noise(0.2, 0.3));
+
+    assert_fuzzy_eq!(world.bodies()[0].material(), Material::from(expected));
+  }
+
+  #[test]
+  fn cutouts_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: quad
+    material:
+      type: phong
+      cutout:
+        mask:
+          type: striped
+          colorA: [1, 1, 1]
+          colorB: [0, 0, 0]
+        threshold: 0.2
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let mask = Pattern::from(Striped::default().with_colors(Color::white(), Color::black()));
+    let expected = Phong::default().with_cutout(Cutout::new(mask).with_threshold(0.2));
+
+    assert_fuzzy_eq!(world.bodies()[0].material(), Material::from(expected));
+  }
+
+  #[test]
+  fn transparent_materials_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      transparency: 0.9
+      refractive_index: 1.5
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let expected = Phong::default()
+      .with_transparency(0.9)
+      .with_refractive_index(1.5);
+
+    assert_fuzzy_eq!(world.bodies()[0].material(), Material::from(expected));
+  }
+
+  #[test]
+  fn layered_materials_layer_any_kind_of_material() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: layered
+      factor: 0.3
+      base:
+        type: lambert
+        color: [1, 0, 0]
+      layer:
+        type: layered
+        base:
+          type: phong
+        layer:
+          type: phong
+          reflectiveness: 0.5
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let base = Lambert::default().with_color(Color::new(1.0, 0.0, 0.0));
+    let layer = Layered::new(
+      Material::default(),
+      Material::from(Phong::default().with_reflectiveness(0.5)),
+      LayerMask::Factor(0.5),
+    );
+
+    assert_fuzzy_eq!(
+      world.bodies()[0].material(),
+      Material::from(Layered::new(
+        Material::from(base),
+        Material::from(layer),
+        LayerMask::Factor(0.3)
+      ))
+    );
+  }
+
+  #[test]
+  fn invalid_material_parameters_are_reported_as_warnings() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      specular: 1.8
+"##;
+
+    let (_, _, warnings) = Loader::default().load_world_with_warnings(source).unwrap();
+    assert_eq!(
+      warnings,
+      vec![
+        "Invalid material, specular needs to be within [0, 1], but is 1.8 found at .document[0].item[0].body.material"
+      ]
+    );
+    assert!(Loader::default()
+      .with_strict_mode(true)
+      .load_world(source)
+      .is_err());
+  }
+
+  #[test]
+  fn camera_field_of_view_in_degrees() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 100
+    height: 50
+    field_of_view:
+      degrees: 60
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+
+    assert_fuzzy_eq!(cameras["main"].field_of_view, PI / 3.0);
+  }
+
+  #[test]
+  fn camera_field_of_view_in_explicit_radians() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 100
+    height: 50
+    field_of_view:
+      radians: 0.5
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+
+    assert_fuzzy_eq!(cameras["main"].field_of_view, 0.5);
+  }
+
+  #[test]
+  fn camera_size_from_width_and_aspect_ratio() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 1920
+    aspect_ratio: "16:9"
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+
+    assert_eq!(cameras["main"].hsize, 1920);
+    assert_eq!(cameras["main"].vsize, 1080);
+  }
+
+  #[test]
+  fn camera_size_from_height_and_numeric_aspect_ratio() {
+    let source = r##"
+---
+- camera:
+    name: main
+    height: 600
+    aspect_ratio: 1.5
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+
+    assert_eq!(cameras["main"].hsize, 900);
+    assert_eq!(cameras["main"].vsize, 600);
+  }
+
+  #[test]
+  fn camera_size_from_quality_preset() {
+    let source = r##"
+---
+- camera:
+    name: main
+    quality: 4k
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+- camera:
+    name: square
+    quality: 720p
+    aspect_ratio: "1:1"
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+
+    assert_eq!(cameras["main"].hsize, 3840);
+    assert_eq!(cameras["main"].vsize, 2160);
+    assert_eq!(cameras["square"].hsize, 720);
+    assert_eq!(cameras["square"].vsize, 720);
+  }
+
+  #[test]
+  fn camera_size_is_over_determined() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 800
+    height: 600
+    aspect_ratio: "16:9"
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Only two of 'width', 'height' and 'aspect_ratio' may be given at .document[0].item[0].camera"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn camera_with_invalid_aspect_ratio() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 800
+    aspect_ratio: "wide"
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Expected positive aspect ratio (e.g. 1.5 or \"16:9\") at .document[0].item[0].camera.aspect_ratio, but found String(\"wide\")"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn camera_exposure_and_crop_window_are_parsed() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 200
+    height: 100
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    exposure: -1.5
+    crop:
+      left: 0.25
+      top: 0
+      right: 0.75
+      bottom: 0.5
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+    let camera = &cameras["main"];
+
+    assert_fuzzy_eq!(camera.exposure, -1.5);
+    assert_fuzzy_eq!(camera.crop_window, CropWindow::new(0.25, 0.0, 0.75, 0.5));
+  }
+
+  #[test]
+  fn camera_memory_ceilings_and_frustum_culling_are_parsed() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 200
+    height: 100
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    memory_ceiling: 256
+    frustum_culling: true
+"##;
+
+    let (_, cameras) = Loader::default().load_world(source).unwrap();
+
+    assert_eq!(cameras["main"].memory_ceiling, Some(256 * 1024 * 1024));
+    assert!(cameras["main"].frustum_culling);
+  }
+
+  #[test]
+  fn camera_memory_ceilings_must_fit_into_memory() {
+    for megabytes in ["-1", "9223372036854775807"].iter() {
+      let source = format!(
+        r##"
+---
+- camera:
+    name: main
+    width: 200
+    height: 100
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    memory_ceiling: {}
+"##,
+        megabytes
+      );
+
+      let actual = Loader::default().load_world(&source).unwrap_err();
+      assert_eq!(
+        actual.to_string(),
+        format!(
+          "Memory ceiling out of range, expected a positive number of megabytes but found {} \
+           at .document[0].item[0].camera.memory_ceiling",
+          megabytes
+        )
+      );
+    }
+  }
+
+  #[test]
+  fn camera_crop_window_must_not_be_empty() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 200
+    height: 100
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    crop:
+      left: 0.5
+      top: 0
+      right: 0.5
+      bottom: 1
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Crop window needs to be a non empty rectangle inside [0, 1], but found CropWindow {{ left: 0.5, top: 0.0, right: 0.5, bottom: 1.0 }} at .document[0].item[0].camera.crop"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn camera_post_processing_is_parsed() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 200
+    height: 100
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    post_processing:
+      - type: auto_exposure
+        compensation: 0.5
+      - type: bloom
+        threshold: 0.8
+        sigma: 2
+        intensity: 0.3
+      - type: glare
+        streaks: 6
+      - type: lens_distortion
+        k1: 0.1
+        inverse: true
+      - type: vignette
+        strength: 0.4
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+
+    let expected = Pipeline::new()
+      .with_effect(AutoExposure::new(0.18, 0.5))
+      .with_effect(Bloom::new(0.8, 2.0, 0.3))
+      .with_effect(Glare::new(1.0, 6, 16, 0.25))
+      .with_effect(LensDistortion::new(0.1, 0.0).inverse())
+      .with_effect(Vignette::new(0.4, 0.5));
+    assert_eq!(cameras["main"].post_processing, expected);
+  }
+
+  #[test]
+  fn lut_effects_are_loaded_relative_to_the_base_directory() {
+    let directory = std::env::temp_dir().join(format!("rtc-lut-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let cube = "LUT_3D_SIZE 2\n0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1\n";
+    std::fs::write(directory.join("identity.cube"), cube).unwrap();
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 20
+    height: 10
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    post_processing:
+      - type: lut
+        file: identity.cube
+"##;
+
+    let result = Loader::default()
+      .with_base_directory(directory.clone())
+      .load_world(source);
+    let missing = Loader::default().load_world(source);
+    std::fs::remove_dir_all(&directory).ok();
+
+    let (_, cameras) = result.unwrap();
+    let expected = Pipeline::new().with_effect(parse_cube(cube).unwrap());
+    assert_eq!(cameras["main"].post_processing, expected);
+    assert!(missing
+      .unwrap_err()
+      .to_string()
+      .starts_with("Could not read LUT file ./identity.cube found at"));
+  }
+
+  #[test]
+  fn unknown_post_processing_effect() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 200
+    height: 100
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    post_processing:
+      - type: sepia
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected = anyhow!(
+      "Unknown effect type 'sepia' found at .document[0].item[0].camera.post_processing[0]"
+    );
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn quad_body_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: quad
+    width: 3
+    height: 0.5
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    let expected = Body::from(Quad::default().with_width(3.0).with_height(0.5));
+    assert_fuzzy_eq!(world.bodies()[0], expected);
+  }
+
+  #[test]
+  fn mesh_body_is_loaded_relative_to_the_base_directory() {
+    let directory = std::env::temp_dir().join(format!("rtc-mesh-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+      directory.join("quad.obj"),
+      "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3 4\n",
+    )
+    .unwrap();
+    let source = r##"
+---
+- body:
+    type: mesh
+    file: quad.obj
+    transforms:
+      - type: translate
+        to: [0, 0, 5]
+"##;
+
+    let result = Loader::default()
+      .with_base_directory(directory.clone())
+      .load_world(source);
+    let missing = Loader::default().load_world(source);
+    std::fs::remove_dir_all(&directory).ok();
+
+    let (world, _) = result.unwrap();
+    assert!(world.bodies().is_empty());
+    assert_eq!(world.instances().len(), 1);
+    let instance = &world.instances()[0];
+    assert_eq!(instance.geometry.bodies().len(), 1);
+    let r = Ray::new(Tuple::point(0.5, 0.8, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = instance.intersect(r);
+    assert_eq!(xs.len(), 1);
+    assert_fuzzy_eq!(
+      xs[0].body.clone(),
+      Body::from(
+        Triangle::new(
+          Tuple::point(-1.0, 1.0, 0.0),
+          Tuple::point(1.0, 0.0, 0.0),
+          Tuple::point(1.0, 1.0, 0.0),
+        )
+        .with_transform(Matrix::translation(0.0, 0.0, 5.0))
+      )
+    );
+    assert!(missing
+      .unwrap_err()
+      .to_string()
+      .starts_with("Could not read mesh file ./quad.obj found at"));
+  }
+
+  #[test]
+  fn svg_paths_are_extruded() {
+    let directory = std::env::temp_dir().join(format!("rtc-svg-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+      directory.join("logo.svg"),
+      r#"<svg><path d="M0 0 H10 V10 H0 Z"/></svg>"#,
+    )
+    .unwrap();
+    let source = r##"
+---
+- body:
+    type: svg
+    file: logo.svg
+    depth: 0.5
+    bevel: 0.1
+"##;
+
+    let result = Loader::default()
+      .with_base_directory(directory.clone())
+      .load_world(source);
+    std::fs::remove_dir_all(&directory).ok();
+
+    let (world, _) = result.unwrap();
+    let extrusion = Extrusion::new(vec![vec![
+      Tuple::point(0.0, 1.0, 0.0),
+      Tuple::point(1.0, 1.0, 0.0),
+      Tuple::point(1.0, 0.0, 0.0),
+      Tuple::point(0.0, 0.0, 0.0),
+    ]])
+    .with_depth(0.5)
+    .with_bevel(0.1);
+    assert_eq!(world.bodies(), vec![extrusion.mesh().body()]);
+  }
+
+  #[test]
+  fn meshes_are_converted_to_the_axis_convention_of_the_renderer() {
+    let directory = std::env::temp_dir().join(format!("rtc-orientation-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+      directory.join("quad.obj"),
+      "v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3\n",
+    )
+    .unwrap();
+    let source = r##"
+---
+- body:
+    type: mesh
+    file: quad.obj
+    transforms:
+      - type: translate
+        to: [0, 0, 5]
+- body:
+    type: mesh
+    file: quad.obj
+    orientation:
+      handedness: left
+- settings:
+    orientation:
+      up: z
+      handedness: right
+"##;
+    let right_handed = Orientation::new(UpAxis::Y, Handedness::Right);
+
+    let result = Loader::default()
+      .with_base_directory(directory.clone())
+      .load_world(source);
+    let defaulted = Loader::default()
+      .with_base_directory(directory.clone())
+      .with_orientation(right_handed)
+      .load_world("---\n- body:\n    type: mesh\n    file: quad.obj\n");
+    let unknown = Loader::default()
+      .with_base_directory(directory.clone())
+      .load_world("---\n- settings:\n    orientation:\n      up: w\n");
+    std::fs::remove_dir_all(&directory).ok();
+
+    let (world, _) = result.unwrap();
+    assert_fuzzy_eq!(
+      world.instances()[0].transform(),
+      Matrix::translation(0.0, 0.0, 5.0)
+        * Orientation::new(UpAxis::Z, Handedness::Right).to_world()
+    );
+    assert_fuzzy_eq!(world.instances()[1].transform(), Matrix::identity());
+    let (world, _) = defaulted.unwrap();
+    assert_fuzzy_eq!(world.instances()[0].transform(), right_handed.to_world());
+    assert_eq!(
+      unknown.unwrap_err().to_string(),
+      "Unknown up axis 'w' found at .document[0].item[0].settings.orientation"
+    );
+  }
+
+  #[test]
+  fn textures_are_loaded_once_relative_to_the_base_directory() {
+    use rtc_core::canvas::to_png::ToPNG;
+    use rtc_core::pattern::Stencil;
+
+    let directory = std::env::temp_dir().join(format!("rtc-texture-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let mut image = Canvas::new(1, 1);
+    image.write_pixel(0, 0, Color::new(1.0, 0.0, 0.0));
+    std::fs::write(directory.join("red.png"), image.to_png()).unwrap();
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      texture: red.png
+- body:
+    type: plane
+    material:
+      type: phong
+      texture: red.png
+"##;
+
+    let result = Loader::default()
+      .with_base_directory(directory.clone())
+      .load_world(source);
+    let missing = Loader::default().load_world(source);
+    std::fs::remove_dir_all(&directory).ok();
+
+    let (world, _) = result.unwrap();
+    let pattern = |body: &Body| match body.material() {
+      Material::Phong(phong) => phong.pattern.unwrap(),
+      _ => panic!("Expected a phong material"),
+    };
+    assert_eq!(pattern(&world.bodies()[0]), pattern(&world.bodies()[1]));
+    assert_fuzzy_eq!(
+      pattern(&world.bodies()[0]).color_at(Tuple::point(0.0, 1.0, 0.0), &world.bodies()[0]),
+      Color::new(1.0, 0.0, 0.0)
+    );
+    assert!(missing
+      .unwrap_err()
+      .to_string()
+      .starts_with("Could not read texture file ./red.png found at"));
+  }
+
+  #[test]
+  fn texture_maps_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: texture_map
+        projection: cubic
+        colorA: [0, 0, 0]
+        colorB: [1, 1, 1]
+        rows: 4
+"##;
+    let unknown = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: texture_map
+        projection: conical
+        colorA: [0, 0, 0]
+        colorB: [1, 1, 1]
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let result = Loader::default().load_world(unknown);
+
+    match world.bodies()[0].material() {
+      Material::Phong(phong) => assert_fuzzy_eq!(
+        phong.pattern.unwrap(),
+        Pattern::from(
+          TextureMap::new(UvPattern::checkers(2, 4, Color::black(), Color::white()))
+            .with_map(UvMap::Cubic)
+        )
+      ),
+      _ => panic!("Expected a phong material"),
+    }
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "Unknown projection 'conical' found at .document[0].item[0].body.material.pattern"
+    );
+  }
+
+  #[test]
+  fn cube_maps_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    material:
+      type: phong
+      pattern:
+        type: cube_map
+        left: { type: align_check }
+        front: { type: align_check, main: [0, 0, 1] }
+        right: { type: checkers, columns: 4 }
+        back: { type: align_check }
+        up: { type: align_check }
+        down: { type: align_check }
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+
+    let align_check = |main: Color| UvPattern::AlignCheck {
+      main,
+      upper_left: Color::new(1.0, 0.0, 0.0),
+      upper_right: Color::new(1.0, 1.0, 0.0),
+      bottom_left: Color::new(0.0, 1.0, 0.0),
+      bottom_right: Color::new(0.0, 1.0, 1.0),
+    };
+    let white = align_check(Color::white());
+    match world.bodies()[0].material() {
+      Material::Phong(phong) => assert_fuzzy_eq!(
+        phong.pattern.unwrap(),
+        Pattern::from(CubeMap::new([
+          white.clone(),
+          align_check(Color::new(0.0, 0.0, 1.0)),
+          UvPattern::checkers(4, 2, Color::black(), Color::white()),
+          white.clone(),
+          white.clone(),
+          white,
+        ]))
+      ),
+      _ => panic!("Expected a phong material"),
+    }
+  }
+
+  #[test]
+  fn mirrored_bodies_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    transforms:
+      - type: translate
+        to: [2, 0, 0]
+    mirror:
+      point: [0, 0, 1]
+      normal: [0, 0, -1]
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+    let zero = Loader::default().load_world(
+      "---
+- body:
+    type: sphere
+    mirror:
+      normal: [0, 0, 0]
+",
+    );
+
+    let expected = Body::from(
+      Mirrored::new(Body::from(
+        Sphere::default().with_transform(Matrix::translation(2.0, 0.0, 0.0)),
+      ))
+      .with_plane(Tuple::point(0.0, 0.0, 1.0), Tuple::vector(0.0, 0.0, -1.0)),
+    );
+    assert_fuzzy_eq!(world.bodies()[0], expected);
+    assert_eq!(
+      zero.unwrap_err().to_string(),
+      "Mirror normal needs to be non zero, but found Tuple { x: 0.0, y: 0.0, z: 0.0, w: 0.0 } at .document[0].item[0].body.mirror.normal"
+    );
+  }
+
+  #[test]
+  fn mirrored_meshes_add_a_reflected_instance() {
+    let directory = std::env::temp_dir().join(format!("rtc-mirror-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+      directory.join("triangle.obj"),
+      "v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3\n",
+    )
+    .unwrap();
+    let source = r##"
+---
+- body:
+    type: mesh
+    file: triangle.obj
+    transforms:
+      - type: translate
+        to: [1, 0, 0]
+    mirror:
+      normal: [1, 0, 0]
+"##;
+
+    let result = Loader::default()
+      .with_base_directory(directory.clone())
+      .load_world(source);
+    std::fs::remove_dir_all(&directory).ok();
+
+    let (world, _) = result.unwrap();
+    let orientation = Orientation::default().to_world();
+    assert_eq!(world.instances().len(), 2);
+    assert!(Arc::ptr_eq(
+      &world.instances()[0].geometry,
+      &world.instances()[1].geometry
+    ));
+    assert_fuzzy_eq!(
+      world.instances()[1].transform(),
+      Matrix::scaling(-1.0, 1.0, 1.0) * Matrix::translation(1.0, 0.0, 0.0) * orientation
+    );
+  }
+
+  #[test]
+  fn scattered_bodies_are_placed_reproducibly() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    name: balls
+    scatter:
+      count: 5
+      min: [-10, 0, -10]
+      max: [10, 0, 10]
+      seed: 7
+- body:
+    type: sphere
+    scatter:
+      count: 10
+      min: [0, 0, 0]
+      max: [1, 0, 1]
+"##;
+
+    let (world, _, warnings) = Loader::default().load_world_with_warnings(source).unwrap();
+    let (again, _) = Loader::default().load_world(source).unwrap();
+
+    assert_eq!(world.bodies_named("balls").len(), 5);
+    assert_eq!(world.bodies(), again.bodies());
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("Only 1 of 10 scattered bodies fit without overlaps"));
+  }
+
+  #[test]
+  fn meshes_from_the_same_file_share_their_geometry() {
+    let directory = std::env::temp_dir().join(format!("rtc-instance-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    std::fs::write(
+      directory.join("tri.obj"),
+      "v 0 1 0\nv -1 0 0\nv 1 0 0\nf 1 2 3\n",
+    )
+    .unwrap();
+    let source = r##"
+---
+- body:
+    type: mesh
+    file: tri.obj
+- body:
+    type: mesh
+    file: tri.obj
+    transforms:
+      - type: translate
+        to: [3, 0, 0]
+"##;
+
+    let result = Loader::default()
+      .with_base_directory(directory.clone())
+      .load_world(source);
+    std::fs::remove_dir_all(&directory).ok();
+
+    let (world, _) = result.unwrap();
+    assert_eq!(world.instances().len(), 2);
+    assert!(Arc::ptr_eq(
+      &world.instances()[0].geometry,
+      &world.instances()[1].geometry
+    ));
+  }
+
+  #[test]
+  fn bodies_with_motion_are_moving_bodies() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    name: ball
+    motion:
+      - type: translate
+        to: [2, 0, 0]
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+
+    assert!(world.bodies().is_empty());
+    assert_eq!(world.moving_bodies().len(), 1);
+    assert_eq!(world.moving_bodies_named("ball").len(), 1);
+    assert_fuzzy_eq!(
+      world.moving_bodies()[0],
+      MovingBody::new(
+        Body::from(Sphere::default()),
+        Matrix::translation(2.0, 0.0, 0.0)
+      )
+      .unwrap()
+    );
+  }
+
+  #[test]
+  fn motion_flipping_a_body_is_an_error() {
+    let source = r##"
+---
+- body:
+    type: sphere
+    motion:
+      - type: scale
+        to: [-1, 1, 1]
+"##;
+
+    let actual = Loader::default().load_world(source).unwrap_err();
+    assert_eq!(
+      actual.to_string(),
+      "Motion can only translate, rotate and scale a body without shearing or flipping it, found at .document[0].item[0].body.motion"
+    );
+  }
+
+  #[test]
+  fn cameras_focus_on_named_bodies() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 10
+    height: 10
+    field_of_view: 1.0
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    aperture: 0.1
+    focus_on: ball
+- body:
+    type: sphere
+    name: ball
+    transforms:
+      - type: translate
+        to: [0, 0, 3]
+"##;
+
+    let (world, cameras) = Loader::default().load_world(source).unwrap();
+    let camera = &cameras["main"];
+
+    assert_eq!(world.body_by_name("ball"), Some(&world.bodies()[0]));
+    assert_fuzzy_eq!(camera.aperture, 0.1);
+    assert_fuzzy_eq!(camera.focal_distance, 8.0);
+  }
+
+  #[test]
+  fn cameras_focus_on_the_depth_of_off_axis_bodies() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 10
+    height: 10
+    field_of_view: 1.0
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    aperture: 0.1
+    focus_on: ball
+- body:
+    type: sphere
+    name: ball
+    transforms:
+      - type: translate
+        to: [4, 3, 0]
+"##;
+
+    let (_, cameras) = Loader::default().load_world(source).unwrap();
+
+    assert_fuzzy_eq!(cameras["main"].focal_distance, 5.0);
+  }
+
+  #[test]
+  fn focusing_on_unknown_bodies_is_an_error() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 10
+    height: 10
+    field_of_view: 1.0
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    focus_on: ball
+"##;
+
+    let result = Loader::default().load_world(source);
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "Unknown body 'ball' to focus on found at .document[0].item[0].camera.focus_on"
+    );
+  }
+
+  #[test]
+  fn focusing_on_bodies_behind_the_camera_is_an_error() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 10
+    height: 10
+    field_of_view: 1.0
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    aperture: 0.1
+    focus_on: ball
+- body:
+    type: sphere
+    name: ball
+    transforms:
+      - type: translate
+        to: [0, 0, -8]
+"##;
+
+    let result = Loader::default().load_world(source);
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "Body 'ball' to focus on is behind the camera found at .document[0].item[0].camera.focus_on"
+    );
+  }
+
+  #[test]
+  fn sweeps_of_named_bodies_are_parsed() {
+    let source = r##"
+---
+- animate:
+    body: ball
+    property: transform.translate.y
+    from: 0
+    to: 2
+- body:
+    type: sphere
+    name: ball
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+
+    assert_eq!(
+      world.sweeps,
+      vec![Sweep::new(
+        "ball",
+        SweepProperty::Translate(Axis::Y),
+        0.0,
+        2.0
+      )]
+    );
+    assert_fuzzy_eq!(
+      world.swept(0.5).bodies()[0].transform(),
+      Matrix::translation(0.0, 1.0, 0.0)
+    );
+  }
+
+  #[test]
+  fn kinematics_are_parsed() {
+    let source = r##"
+---
+- animate:
+    body: ball
+    property: kinematics
+    from: 0
+    to: 2
+    velocity: [1, 2, 0]
+    restitution: 0.5
+- body:
+    type: sphere
+    name: ball
+"##;
+
+    let (world, _) = Loader::default().load_world(source).unwrap();
+
+    assert_eq!(
+      world.sweeps,
+      vec![Sweep::new(
+        "ball",
+        SweepProperty::Kinematics(
+          Kinematics::new(Tuple::vector(1.0, 2.0, 0.0)).with_restitution(0.5)
+        ),
+        0.0,
+        2.0
+      )]
+    );
+  }
+
+  #[test]
+  fn animating_unknown_bodies_is_an_error() {
+    let source = r##"
+---
+- animate:
+    body: ball
+    property: transform.scale
+    from: 1
+    to: 2
+"##;
+
+    let result = Loader::default().load_world(source);
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "Unknown body 'ball' to animate found at .document[0].item[0].animate"
+    );
+  }
+
+  #[test]
+  fn sdf_body_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: sdf
+    shape:
+      type: torus
+      major_radius: 2
+    blend:
+      shape:
+        type: box
+        size: [0.5, 0.5, 0.5]
+      offset: [0, 1, 0]
+      smoothness: 0.25
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    let expected = Body::from(
+      SdfBody::new(Sdf::Torus {
+        major_radius: 2.0,
+        minor_radius: 0.25,
+      })
+      .with_blend(
+        Sdf::Box {
+          size: Tuple::vector(0.5, 0.5, 0.5),
+        },
+        Tuple::vector(0.0, 1.0, 0.0),
+        0.25,
+      ),
+    );
+    assert_fuzzy_eq!(world.bodies()[0], expected);
+  }
+
+  #[test]
+  fn repeated_body_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: repeated
+    spacing: [3, 0, 3]
+    count: [4, 1, 2]
+    transforms:
+      - type: translate
+        to: [0, 1, 0]
+    body:
+      type: sphere
+      material:
+        type: phong
+        color: [1, 0, 0]
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    let expected = Body::from(
+      Repeated::new(
+        Body::from(Sphere::default().with_material(Material::from(
+          Phong::default().with_color(Color::new(1.0, 0.0, 0.0)),
+        ))),
+        Tuple::vector(3.0, 0.0, 3.0),
+      )
+      .with_count(4, 1, 2)
+      .with_transform(Matrix::translation(0.0, 1.0, 0.0)),
+    );
+    assert_fuzzy_eq!(world.bodies()[0], expected);
+  }
+
+  #[test]
+  fn repeated_bodies_need_to_be_single_bodies() {
+    let source = r##"
+---
+- body:
+    type: repeated
+    spacing: [3, 0, 3]
+    body:
+      type: tube
+      points:
+        - [0, 0, 0]
+        - [0, 1, 0]
+        - [1, 1, 0]
+"##;
+
+    let result = Loader::default().load_world(source);
+    assert_eq!(
+      result.unwrap_err().to_string(),
+      "Repeated body needs to be a single body, but it consists of 5 found at .document[0].item[0].body.body"
+    );
+  }
+
+  #[test]
+  fn cylinder_body_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: cylinder
+    minimum: -1
+    maximum: 2.5
+    closed: true
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    let expected = Body::from(
+      Cylinder::default()
+        .with_minimum(-1.0)
+        .with_maximum(2.5)
+        .with_closed(true),
+    );
+    assert_fuzzy_eq!(world.bodies()[0], expected);
+  }
+
+  #[test]
+  fn cone_body_is_parsed() {
+    let source = r##"
+---
+- body:
+    type: cone
+    minimum: -1
+    maximum: 0
+    closed: true
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    let expected = Body::from(
+      Cone::default()
+        .with_minimum(-1.0)
+        .with_maximum(0.0)
+        .with_closed(true),
+    );
+    assert_fuzzy_eq!(world.bodies()[0], expected);
+  }
+
+  #[test]
+  fn shadow_flags_of_bodies_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: plane
+    cast_shadow: false
+- body:
+    type: sphere
+    receive_shadow: false
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    assert_eq!(
+      world.bodies()[0].shadows(),
+      Shadows {
+        cast: false,
+        receive: true
+      }
+    );
+    assert_eq!(
+      world.bodies()[1].shadows(),
+      Shadows {
+        cast: true,
+        receive: false
+      }
+    );
+  }
+
+  #[test]
+  fn contact_shadow_settings_are_parsed() {
+    let source = r##"
+---
+- settings:
+    contact_shadows:
+      distance: 0.25
+      samples: 6
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    assert_fuzzy_eq!(world.contact_shadows, Some(ContactShadows::new(0.25, 6)));
+  }
+
+  #[test]
+  fn caustic_settings_are_parsed() {
+    let source = r##"
+---
+- settings:
+    caustics:
+      photons: 1000
+      radius: 0.3
+      seed: 7
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    assert_fuzzy_eq!(world.caustics, Some(Caustics::new(1000, 0.3).with_seed(7)));
+  }
+
+  #[test]
+  fn background_and_ray_limit_settings_are_parsed() {
+    let source = r##"
+---
+- settings:
+    background: [0.1, 0.2, 0.3]
+    unlit_color: [1, 0, 1]
+    secondary_ray_budget: 16
+    contribution_cutoff: 0.001
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    assert_fuzzy_eq!(world.background, Color::new(0.1, 0.2, 0.3));
+    assert_fuzzy_eq!(world.unlit_color, Color::new(1.0, 0.0, 1.0));
+    assert_eq!(world.secondary_ray_budget, Some(16));
+    assert_fuzzy_eq!(world.contribution_cutoff, 0.001);
+  }
+
+  #[test]
+  fn camera_adaptive_sampling_is_parsed() {
+    let source = r##"
+---
+- camera:
+    name: main
+    width: 200
+    height: 100
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    adaptive_sampling:
+      tile_size: 8
+      max_samples: 6
+      target_variance: 0.001
+      time_budget: 90
+"##;
+
+    let yaml_loader = Loader::default();
+    let (_, cameras) = yaml_loader.load_world(source).unwrap();
+
+    let expected = AdaptiveSampling::default()
+      .with_tile_size(8)
+      .with_max_samples(6)
+      .with_target_variance(0.001)
+      .with_time_budget(Duration::from_secs(90));
+    assert_eq!(cameras["main"].adaptive_sampling, Some(expected));
+  }
+
+  #[test]
+  fn time_budgets_out_of_range_are_rejected() {
+    for (time_budget, found) in [
+      ("-1", "-1"),
+      ("1e30", "1000000000000000000000000000000"),
+      (".inf", "inf"),
+    ] {
+      let source = format!(
+        r##"
+---
+- camera:
+    name: main
+    width: 200
+    height: 100
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    adaptive_sampling:
+      time_budget: {}
+"##,
+        time_budget
+      );
+
+      let yaml_loader = Loader::default();
+      let actual = yaml_loader.load_world(&source).unwrap_err();
+      assert_eq!(
+        actual.to_string(),
+        format!(
+          "Time budget out of range, expected a positive number of seconds but found {} \
+           at .document[0].item[0].camera.adaptive_sampling.time_budget",
+          found
+        )
+      );
+    }
+  }
+
+  #[test]
+  fn negative_counts_are_rejected() {
+    let camera = r##"
+---
+- camera:
+    name: main
+    width: 200
+    height: 100
+    field_of_view: 1.047
+    from: [0, 0, -5]
+    to: [0, 0, 0]
+    up: [0, 1, 0]
+    samples: -4
+"##;
+    let settings = r##"
+---
+- settings:
+    caustics:
+      photons: -1000
+"##;
+
+    assert_eq!(
+      Loader::default()
+        .load_world(camera)
+        .unwrap_err()
+        .to_string(),
+      "Expected a count of zero or more, but found -4 at .document[0].item[0].camera.samples"
+    );
+    assert_eq!(
+      Loader::default()
+        .load_world(settings)
+        .unwrap_err()
+        .to_string(),
+      "Expected a count of zero or more, but found -1000 at .document[0].item[0].settings.caustics.photons"
+    );
+  }
+
+  #[test]
+  fn triangle_bodies_are_parsed() {
+    let source = r##"
+---
+- body:
+    type: triangle
+    points: [[0, 1, 0], [-1, 0, 0], [1, 0, 0]]
+- body:
+    type: smooth_triangle
+    points: [[0, 1, 0], [-1, 0, 0], [1, 0, 0]]
+    normals: [[0, 1, 0], [-1, 0, 0], [1, 0, 0]]
+"##;
+
+    let yaml_loader = Loader::default();
+    let (world, _) = yaml_loader.load_world(source).unwrap();
+
+    let p1 = Tuple::point(0.0, 1.0, 0.0);
+    let p2 = Tuple::point(-1.0, 0.0, 0.0);
+    let p3 = Tuple::point(1.0, 0.0, 0.0);
+    let n1 = Tuple::vector(0.0, 1.0, 0.0);
+    let n2 = Tuple::vector(-1.0, 0.0, 0.0);
+    let n3 = Tuple::vector(1.0, 0.0, 0.0);
+    assert_fuzzy_eq!(world.bodies()[0], Body::from(Triangle::new(p1, p2, p3)));
+    assert_fuzzy_eq!(
+      world.bodies()[1],
+      Body::from(SmoothTriangle::new(p1, p2, p3, n1, n2, n3))
+    );
+  }
+
+  #[test]
+  fn triangle_needs_three_points() {
+    let source = r##"
+---
+- body:
+    type: triangle
+    points: [[0, 1, 0], [-1, 0, 0]]
+"##;
+
+    let yaml_loader = Loader::default();
+    let result = yaml_loader.load_world(source);
+    assert!(result.is_err());
+    let actual = result.unwrap_err();
+    let expected =
+      anyhow!("Expected exactly three entries at .document[0].item[0].body.points, but found 2");
+    assert_eq!(actual.to_string(), expected.to_string());
+  }
+
+  /// Documents around every block with defaults in the schema. `FIELD` is
+  /// replaced by one of its fields, `TYPE` by each of its kinds.
+  const DEFAULT_HOSTS: &[(&str, &str)] = &[
+    ("settings", "- settings: {FIELD}"),
+    ("contact_shadows", "- settings: {contact_shadows: {FIELD}}"),
+    (
+      "caustics",
+      "- settings: {caustics: {FIELD photons: 100}}
+- light: {type: point_light, at: [0, 5, 0], intensity: [1, 1, 1]}
+- body: {type: sphere, material: {type: phong, transparency: 1}}",
+    ),
+    (
+      "animate",
+      "- body: {type: sphere, name: ball}
+- animate: {FIELD body: ball, property: kinematics, from: 0, to: 1}",
+    ),
+    (
+      "scatter",
+      "- body: {type: sphere, scatter: {FIELD count: 3, min: [-10, 0, -10], max: [10, 0, 10]}}",
+    ),
+    ("mirror", "- body: {type: sphere, mirror: {FIELD}}"),
+    ("orientation", "- settings: {orientation: {FIELD}}"),
+    (
+      "attenuation",
+      "- light: {type: point_light, at: [0, 5, 0], intensity: [1, 1, 1], attenuation: {FIELD}}",
+    ),
+    (
+      "light",
+      "- light: {FIELD type: TYPE, at: [0, 5, 0], intensity: [1, 1, 1]}",
+    ),
+    (
+      "body",
+      "- body: {FIELD type: TYPE, points: [[0, 0, 0], [1, 0, 0], [0, 1, 0]], text: A, normals: [[0, 0, -1], [0, 0, -1], [0, 0, -1]], spacing: [3, 3, 3], shape: {type: sphere}, body: {type: sphere}}",
+    ),
+    ("sdf", "- body: {type: sdf, shape: {FIELD type: TYPE}}"),
+    (
+      "sdf_blend",
+      "- body: {type: sdf, shape: {type: sphere}, blend: {FIELD shape: {type: box}, offset: [1, 0, 0]}}",
+    ),
+    (
+      "material",
+      "- body: {type: sphere, material: {FIELD type: TYPE, base: {type: phong}, layer: {type: phong}}}",
+    ),
+    ("coat", "- body: {type: sphere, material: {type: phong, coat: {FIELD}}}"),
+    (
+      "bump",
+      "- body: {type: sphere, material: {type: phong, bump: {FIELD type: TYPE}}}",
+    ),
+    (
+      "cutout",
+      "- body: {type: sphere, material: {type: phong, cutout: {FIELD mask: {type: striped, colorA: [0, 0, 0], colorB: [1, 1, 1]}}}}",
+    ),
+    (
+      "pattern",
+      "- body: {type: sphere, material: {type: phong, pattern: {FIELD type: TYPE, colorA: [1, 0, 0], colorB: [0, 0, 1]}}}",
+    ),
+    (
+      "uv_pattern",
+      "- body: {type: sphere, material: {type: phong, pattern: {type: cube_map, left: {FIELD type: TYPE}, front: {FIELD type: TYPE}, right: {FIELD type: TYPE}, back: {FIELD type: TYPE}, up: {FIELD type: TYPE}, down: {FIELD type: TYPE}}}}",
+    ),
+    ("camera", "- camera: {FIELD CAMERA}"),
+    (
+      "effect",
+      "- camera: {CAMERA, post_processing: [{FIELD type: TYPE}]}",
+    ),
+    (
+      "adaptive_sampling",
+      "- camera: {CAMERA, adaptive_sampling: {FIELD}}",
+    ),
+  ];
+
+  #[test]
+  fn the_schema_defaults_are_the_defaults_of_the_loader() {
+    let camera = "name: main, width: 10, height: 10, field_of_view: 1, from: [0, 0, -5], to: [0, 0, 0], up: [0, 1, 0]";
+    for block in schema::BLOCKS.iter() {
+      if block
+        .fields
+        .iter()
+        .all(|field| field.default.is_none() && field.kind_defaults.is_empty())
+      {
+        continue;
+      }
+      let host = DEFAULT_HOSTS
+        .iter()
+        .find(|(name, _)| *name == block.name)
+        .unwrap_or_else(|| panic!("No document for the defaults of {}", block.name))
+        .1
+        .replace("CAMERA", camera);
+      let kinds = match block.field("type").map(|field| field.value_type) {
+        Some(schema::ValueType::OneOf(kinds)) => kinds.to_vec(),
+        _ => vec![""],
+      };
+      for kind in kinds {
+        let host = host.replace("TYPE", kind);
+        let load = |field: &str| {
+          let source = format!("---\n{}\n", host.replace("FIELD", field));
+          Loader::default()
+            .load_world(source)
+            .map_err(|error| error.to_string())
+        };
+        let omitted = load("");
+        for field in block.fields.iter() {
+          let default = match field.default_of(Some(kind)) {
+            Some(default) => default,
+            None => continue,
+          };
+          let given = load(&format!("{}: {}, ", field.name, default));
+          match (given.as_ref().ok(), omitted.as_ref().ok()) {
+            (Some((world, cameras)), Some((other_world, other_cameras))) => assert!(
+              world == other_world
+                && cameras.len() == other_cameras.len()
+                && cameras
+                  .iter()
+                  .all(|(name, camera)| camera.fuzzy_eq(other_cameras[name].clone())),
+              "Default of {}.{} for '{}' differs from {}",
+              block.name,
+              field.name,
+              kind,
+              default
+            ),
+            // Kinds needing more than the document gives, e.g. files, fail
+            // either way
+            _ => assert_eq!(
+              given.as_ref().err(),
+              omitted.as_ref().err(),
+              "Default of {}.{} for '{}' fails",
+              block.name,
+              field.name,
+              kind
+            ),
+          }
+        }
+      }
+    }
+  }
+}